@@ -0,0 +1,108 @@
+#![feature(test)]
+
+///! Test evaluating the same AST repeatedly with fresh inputs
+extern crate test;
+
+use rhai::{Engine, OptimizationLevel, ReusableState, Scope, AST, INT};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use test::Bencher;
+
+/// Allocator that counts the number of allocations made.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const SCRIPT: &str = r#"
+    let rate = requests_succeeded * 100 / requests_made;
+    rate >= threshold
+"#;
+
+fn setup() -> (Engine, AST) {
+    let mut engine = Engine::new();
+    engine.set_optimization_level(OptimizationLevel::None);
+
+    let ast = engine.compile(SCRIPT).unwrap();
+
+    (engine, ast)
+}
+
+fn eval_fresh(engine: &Engine, ast: &AST, made: INT) -> bool {
+    let mut scope = Scope::new();
+    scope.push("requests_made", made);
+    scope.push("requests_succeeded", 90 as INT);
+    scope.push("threshold", 80 as INT);
+
+    engine.eval_ast_with_scope(&mut scope, ast).unwrap()
+}
+
+fn eval_reuse(engine: &Engine, ast: &AST, state: &mut ReusableState, made: INT) -> bool {
+    let inputs = [
+        ("requests_made", made.into()),
+        ("requests_succeeded", (90 as INT).into()),
+        ("threshold", (80 as INT).into()),
+    ];
+
+    engine.eval_reuse(ast, state, inputs).unwrap()
+}
+
+/// Average number of allocations made by each call to `run`.
+fn allocations_per_run(mut run: impl FnMut(INT)) -> f64 {
+    const RUNS: INT = 1000;
+
+    // Warm up any buffers that are kept between runs
+    run(RUNS);
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    (1..=RUNS).for_each(|made| run(made));
+    let after = ALLOCATIONS.load(Ordering::Relaxed);
+
+    (after - before) as f64 / RUNS as f64
+}
+
+#[bench]
+fn bench_eval_reuse_fresh_scope(bench: &mut Bencher) {
+    let (engine, ast) = setup();
+
+    let allocations = allocations_per_run(|made| {
+        eval_fresh(&engine, &ast, made);
+    });
+    eprintln!(
+        "eval_ast_with_scope: {:.1} allocations per evaluation",
+        allocations
+    );
+
+    bench.iter(|| eval_fresh(&engine, &ast, 100));
+}
+
+#[bench]
+fn bench_eval_reuse_state(bench: &mut Bencher) {
+    let (engine, ast) = setup();
+    let mut state = ReusableState::new();
+
+    let fresh = allocations_per_run(|made| {
+        eval_fresh(&engine, &ast, made);
+    });
+    let allocations = allocations_per_run(|made| {
+        eval_reuse(&engine, &ast, &mut state, made);
+    });
+    eprintln!("eval_reuse: {:.1} allocations per evaluation", allocations);
+
+    assert!(allocations < fresh);
+
+    bench.iter(|| eval_reuse(&engine, &ast, &mut state, 100));
+}
@@ -0,0 +1,74 @@
+#![feature(test)]
+
+///! Test the memory held by an AST with many repeated string literals
+extern crate test;
+
+use rhai::{Engine, OptimizationLevel, AST};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use test::Bencher;
+
+/// Allocator that counts the number of bytes currently allocated.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const ITEMS: usize = 5000;
+
+/// Script with thousands of object maps, using the same property names and string values
+/// in every map when `repeated` is true, and different ones (of the same length) otherwise.
+fn make_script(repeated: bool) -> String {
+    let items: Vec<_> = (0..ITEMS)
+        .map(|i| {
+            let n = if repeated { 0 } else { i };
+            format!(r#"#{{ key{:04}: "value{:04}", id: {} }}"#, n, n, i)
+        })
+        .collect();
+
+    format!("let x = [{}];", items.join(","))
+}
+
+/// Number of bytes held by the `AST` compiled from a script.
+fn ast_size(engine: &Engine, script: &str) -> (AST, usize) {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let ast = engine.compile(script).unwrap();
+    let after = ALLOCATED.load(Ordering::Relaxed);
+
+    (ast, after - before)
+}
+
+#[bench]
+fn bench_parse_repeated_strings(bench: &mut Bencher) {
+    let mut engine = Engine::new();
+    engine.set_optimization_level(OptimizationLevel::None);
+
+    let repeated = make_script(true);
+    let distinct = make_script(false);
+
+    // String literals are interned, so the repeated ones are only allocated once
+    let (_ast1, repeated_size) = ast_size(&engine, &repeated);
+    let (_ast2, distinct_size) = ast_size(&engine, &distinct);
+    eprintln!(
+        "AST size: {} bytes with repeated strings, {} bytes with distinct strings",
+        repeated_size, distinct_size
+    );
+
+    assert!(repeated_size < distinct_size);
+
+    bench.iter(|| engine.compile(&repeated).unwrap());
+}
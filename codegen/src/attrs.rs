@@ -22,6 +22,8 @@ pub trait ExportedParams: Sized {
 pub struct AttrItem {
     pub key: proc_macro2::Ident,
     pub value: Option<syn::LitStr>,
+    /// Arguments of an item written as a call, e.g. `instantiate(i64, f64)`.
+    pub args: Option<Vec<syn::Expr>>,
     pub span: proc_macro2::Span,
 }
 
@@ -51,7 +53,7 @@ pub fn parse_punctuated_items(
     let mut attrs: Vec<AttrItem> = Vec::new();
     for arg in arg_list {
         let arg_span = arg.span();
-        let (key, value) = match arg {
+        let (key, value, args) = match arg {
             syn::Expr::Assign(syn::ExprAssign {
                 ref left,
                 ref right,
@@ -72,20 +74,34 @@ pub fn parse_punctuated_items(
                     }) => string.clone(),
                     x => return Err(syn::Error::new(x.span(), "expecting string literal")),
                 };
-                (attr_name, Some(attr_value))
+                (attr_name, Some(attr_value), None)
             }
             syn::Expr::Path(syn::ExprPath {
                 path: attr_path, ..
             }) => attr_path
                 .get_ident()
                 .cloned()
-                .map(|a| (a, None))
+                .map(|a| (a, None, None))
                 .ok_or_else(|| syn::Error::new(attr_path.span(), "expecting attribute name"))?,
+            syn::Expr::Call(syn::ExprCall {
+                ref func, ref args, ..
+            }) => {
+                let attr_name: syn::Ident = match func.as_ref() {
+                    syn::Expr::Path(syn::ExprPath {
+                        path: attr_path, ..
+                    }) => attr_path.get_ident().cloned().ok_or_else(|| {
+                        syn::Error::new(attr_path.span(), "expecting attribute name")
+                    })?,
+                    x => return Err(syn::Error::new(x.span(), "expecting attribute name")),
+                };
+                (attr_name, None, Some(args.iter().cloned().collect()))
+            }
             x => return Err(syn::Error::new(x.span(), "expecting identifier")),
         };
         attrs.push(AttrItem {
             key,
             value,
+            args,
             span: arg_span,
         });
     }
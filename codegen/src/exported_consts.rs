@@ -0,0 +1,186 @@
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{parse::Parse, parse::ParseStream, spanned::Spanned};
+
+#[derive(Debug)]
+pub(crate) struct ExportedConst {
+    name: syn::Ident,
+    conversion: TokenStream,
+}
+
+#[allow(dead_code)]
+impl ExportedConst {
+    pub fn name(&self) -> &syn::Ident {
+        &self.name
+    }
+
+    pub fn conversion(&self) -> &TokenStream {
+        &self.conversion
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ExportedConsts {
+    mod_all: syn::ItemMod,
+    consts: Vec<ExportedConst>,
+}
+
+impl Parse for ExportedConsts {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mod_all: syn::ItemMod = input.parse()?;
+
+        let items = match mod_all.content {
+            Some((_, ref items)) => items,
+            None => {
+                return Err(syn::Error::new(
+                    mod_all.span(),
+                    "cannot export a module without its contents",
+                ))
+            }
+        };
+
+        let mut consts = Vec::new();
+
+        for item in items.iter() {
+            match item {
+                syn::Item::Const(c) if matches!(c.vis, syn::Visibility::Public(_)) => {
+                    crate::attrs::deny_cfg_attr(&c.attrs)?;
+
+                    let value = c.ident.clone();
+                    consts.push(ExportedConst {
+                        name: c.ident.clone(),
+                        conversion: to_dynamic(&c.ty, quote! { #value })?,
+                    });
+                }
+                syn::Item::Const(_) | syn::Item::Use(_) => (),
+                _ => {
+                    return Err(syn::Error::new(
+                        item.span(),
+                        "only constants can be exported with #[export_consts]",
+                    ))
+                }
+            }
+        }
+
+        Ok(ExportedConsts { mod_all, consts })
+    }
+}
+
+#[allow(dead_code)]
+impl ExportedConsts {
+    pub fn module_name(&self) -> &syn::Ident {
+        &self.mod_all.ident
+    }
+
+    pub fn consts(&self) -> &[ExportedConst] {
+        &self.consts
+    }
+
+    pub fn generate(self) -> TokenStream {
+        let ExportedConsts { mod_all, consts } = self;
+        let syn::ItemMod {
+            attrs,
+            vis,
+            ident,
+            content,
+            ..
+        } = mod_all;
+        let items = content.map(|(_, items)| items).unwrap_or_default();
+
+        let names: Vec<syn::LitStr> = consts
+            .iter()
+            .map(|c| syn::LitStr::new(&c.name.to_string(), c.name.span()))
+            .collect();
+        let conversions: Vec<&TokenStream> = consts.iter().map(|c| &c.conversion).collect();
+
+        quote! {
+            #(#attrs)*
+            #vis mod #ident {
+                #(#items)*
+
+                #[allow(unused_imports)]
+                use super::*;
+
+                pub fn rhai_module_generate() -> Module {
+                    let mut m = Module::new();
+                    rhai_generate_into_module(&mut m, false);
+                    m
+                }
+                pub fn rhai_generate_into_module(m: &mut Module, _flatten: bool) {
+                    #(m.set_var(#names, #conversions);)*
+                }
+            }
+        }
+    }
+}
+
+/// Generate an expression that converts `value`, of type `ty`, into a `Dynamic`.
+///
+/// Fixed-size arrays and slices become Rhai arrays, while arrays and slices of
+/// `(&str, T)` pairs become object maps.  Their elements are converted in turn.
+///
+/// Any other named type is passed to `Dynamic::from` as it is, so the compiler checks that it
+/// can be held in a `Dynamic`, with the error pointing at the type of the constant.
+fn to_dynamic(ty: &syn::Type, value: TokenStream) -> syn::Result<TokenStream> {
+    match ty {
+        syn::Type::Group(g) => to_dynamic(&g.elem, value),
+        syn::Type::Paren(p) => to_dynamic(&p.elem, value),
+        syn::Type::Path(_) => Ok(quote_spanned! { ty.span()=> Dynamic::from::<#ty>(#value) }),
+        syn::Type::Reference(r) if is_str(&r.elem) => {
+            Ok(quote! { Dynamic::from(ImmutableString::from(#value)) })
+        }
+        syn::Type::Reference(r) => match *r.elem {
+            syn::Type::Slice(ref s) => list_to_dynamic(&s.elem, value),
+            _ => Err(unsupported_type(ty)),
+        },
+        syn::Type::Array(a) => list_to_dynamic(&a.elem, value),
+        _ => Err(unsupported_type(ty)),
+    }
+}
+
+/// Generate an expression that converts a list of `elem` into an array, or into an
+/// object map if the elements are `(&str, T)` pairs.
+fn list_to_dynamic(elem: &syn::Type, value: TokenStream) -> syn::Result<TokenStream> {
+    match elem {
+        syn::Type::Tuple(t) if t.elems.len() == 2 && is_str_ref(&t.elems[0]) => {
+            let conversion = to_dynamic(&t.elems[1], quote! { v })?;
+            Ok(quote! {
+                Dynamic::from(
+                    #value
+                        .iter()
+                        .cloned()
+                        .map(|(k, v)| (ImmutableString::from(k), #conversion))
+                        .collect::<Map>()
+                )
+            })
+        }
+        _ => {
+            let conversion = to_dynamic(elem, quote! { v })?;
+            Ok(quote! {
+                Dynamic::from(#value.iter().cloned().map(|v| #conversion).collect::<Array>())
+            })
+        }
+    }
+}
+
+fn is_str(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(p) => p.qself.is_none() && p.path.is_ident("str"),
+        _ => false,
+    }
+}
+
+fn is_str_ref(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Reference(r) => is_str(&r.elem),
+        _ => false,
+    }
+}
+
+fn unsupported_type(ty: &syn::Type) -> syn::Error {
+    syn::Error::new(
+        ty.span(),
+        "type cannot be converted into a Rhai constant \
+         (expected a named type, &str, or an array or slice of these)",
+    )
+}
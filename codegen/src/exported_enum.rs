@@ -0,0 +1,245 @@
+use quote::quote;
+use syn::{parse::Parse, parse::ParseStream, spanned::Spanned};
+
+use crate::attrs::{AttrItem, ExportInfo, ExportedParams};
+
+#[derive(Debug, Default)]
+pub(crate) struct ExportedEnumParams {
+    pub name: Option<String>,
+    pub case_insensitive: bool,
+}
+
+impl Parse for ExportedEnumParams {
+    fn parse(args: ParseStream) -> syn::Result<Self> {
+        if args.is_empty() {
+            return Ok(ExportedEnumParams::default());
+        }
+
+        let info = crate::attrs::parse_attr_items(args)?;
+
+        Self::from_info(info)
+    }
+}
+
+impl ExportedParams for ExportedEnumParams {
+    fn parse_stream(args: ParseStream) -> syn::Result<Self> {
+        Self::parse(args)
+    }
+
+    fn no_attrs() -> Self {
+        Default::default()
+    }
+
+    fn from_info(info: ExportInfo) -> syn::Result<Self> {
+        let ExportInfo { items: attrs, .. } = info;
+        let mut name = None;
+        let mut case_insensitive = false;
+        for attr in attrs {
+            let AttrItem {
+                key, value, args, ..
+            } = attr;
+            if args.is_some() {
+                return Err(syn::Error::new(key.span(), "extraneous arguments"));
+            }
+            match (key.to_string().as_ref(), value) {
+                ("name", Some(s)) => name = Some(s.value()),
+                ("name", None) => return Err(syn::Error::new(key.span(), "requires value")),
+                ("case_insensitive", None) => case_insensitive = true,
+                ("case_insensitive", Some(s)) => {
+                    return Err(syn::Error::new(s.span(), "extraneous value"))
+                }
+                (attr, _) => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!("unknown attribute '{}'", attr),
+                    ))
+                }
+            }
+        }
+
+        Ok(ExportedEnumParams {
+            name,
+            case_insensitive,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ExportedEnum {
+    type_name: syn::Ident,
+    variants: Vec<syn::Ident>,
+    params: ExportedEnumParams,
+}
+
+impl Parse for ExportedEnum {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut item: syn::DeriveInput = input.parse()?;
+
+        if !item.generics.params.is_empty() {
+            return Err(syn::Error::new(
+                item.generics.span(),
+                "cannot export generic enums to Rhai",
+            ));
+        }
+        let data = match item.data {
+            syn::Data::Enum(ref data) => data,
+            _ => {
+                return Err(syn::Error::new(
+                    item.ident.span(),
+                    "only enums can be exported to Rhai with this derive",
+                ))
+            }
+        };
+        if data.variants.is_empty() {
+            return Err(syn::Error::new(
+                item.ident.span(),
+                "cannot export an enum without variants to Rhai",
+            ));
+        }
+
+        let mut variants = Vec::new();
+        for variant in data.variants.iter() {
+            match variant.fields {
+                syn::Fields::Unit => variants.push(variant.ident.clone()),
+                _ => {
+                    return Err(syn::Error::new(
+                        variant.fields.span(),
+                        "only fieldless enum variants can be exported to Rhai",
+                    ))
+                }
+            }
+        }
+
+        let params = crate::attrs::inner_item_attributes(&mut item.attrs, "rhai_enum")?;
+
+        Ok(ExportedEnum {
+            type_name: item.ident,
+            variants,
+            params,
+        })
+    }
+}
+
+#[allow(dead_code)]
+impl ExportedEnum {
+    pub fn type_name(&self) -> &syn::Ident {
+        &self.type_name
+    }
+
+    pub fn exported_name(&self) -> String {
+        self.params
+            .name
+            .clone()
+            .unwrap_or_else(|| self.type_name.to_string())
+    }
+
+    pub fn parse_fn_name(&self) -> String {
+        format!("parse_{}", to_snake_case(&self.exported_name()))
+    }
+
+    pub fn variants(&self) -> &[syn::Ident] {
+        &self.variants
+    }
+
+    pub fn generate(self) -> proc_macro2::TokenStream {
+        let exported_name = self.exported_name();
+        let parse_fn_name = syn::LitStr::new(&self.parse_fn_name(), self.type_name.span());
+        let ExportedEnum {
+            type_name,
+            variants,
+            params,
+        } = self;
+
+        let mod_name = crate::exported_type::generated_type_module_name(&type_name);
+        let names: Vec<syn::LitStr> = variants
+            .iter()
+            .map(|v| syn::LitStr::new(&v.to_string(), v.span()))
+            .collect();
+        let indices: Vec<usize> = (0..variants.len()).collect();
+        let count = variants.len();
+        let matches_name = if params.case_insensitive {
+            quote! { n.eq_ignore_ascii_case(name) }
+        } else {
+            quote! { *n == name }
+        };
+
+        quote! {
+            #[allow(non_snake_case)]
+            pub mod #mod_name {
+                #[allow(unused_imports)]
+                use super::*;
+
+                const NAMES: [&str; #count] = [#(#names),*];
+
+                fn index_of(value: &#type_name) -> usize {
+                    match value {
+                        #(#type_name::#variants => #indices,)*
+                    }
+                }
+
+                fn variant(index: usize) -> #type_name {
+                    match index {
+                        #(#indices => #type_name::#variants,)*
+                        _ => unreachable!(),
+                    }
+                }
+
+                pub fn parse(name: &str) -> Result<#type_name, Box<EvalAltResult>> {
+                    NAMES
+                        .iter()
+                        .position(|n| #matches_name)
+                        .map(variant)
+                        .ok_or_else(|| {
+                            format!(
+                                "Invalid {} '{}' (valid names: {})",
+                                #exported_name,
+                                name,
+                                NAMES.join(", ")
+                            )
+                            .into()
+                        })
+                }
+
+                pub fn rhai_module_generate() -> Module {
+                    let mut m = Module::new();
+                    rhai_generate_into_module(&mut m, false);
+                    m
+                }
+                pub fn rhai_generate_into_module(m: &mut Module, _flatten: bool) {
+                    #(m.set_var(#names, #type_name::#variants);)*
+
+                    m.set_fn_1(#parse_fn_name, |name: ImmutableString| parse(&name));
+                    m.set_fn_1_mut("to_string", |value: &mut #type_name| {
+                        Ok(ImmutableString::from(NAMES[index_of(value)]))
+                    });
+                    m.set_fn_2_mut("==", |x: &mut #type_name, y: #type_name| {
+                        Ok(index_of(x) == index_of(&y))
+                    });
+                    m.set_fn_2_mut("!=", |x: &mut #type_name, y: #type_name| {
+                        Ok(index_of(x) != index_of(&y))
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Convert a type name such as `TrafficLight` into `traffic_light`.
+fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut result = String::new();
+
+    for (i, &ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = matches!(chars.get(i + 1), Some(c) if c.is_lowercase());
+
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_is_lower) {
+                result.push('_');
+            }
+        }
+        result.extend(ch.to_lowercase());
+    }
+
+    result
+}
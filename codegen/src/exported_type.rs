@@ -0,0 +1,228 @@
+use quote::quote;
+use syn::{parse::Parse, parse::ParseStream, spanned::Spanned};
+
+use crate::function::{ExportedFn, ExportedFnParams};
+use crate::module::ExportedModParams;
+
+#[derive(Debug)]
+pub(crate) struct ExportedType {
+    item_impl: syn::ItemImpl,
+    type_name: syn::Ident,
+    methods: Vec<ExportedFn>,
+    constructors: Vec<ExportedFn>,
+    params: ExportedModParams,
+}
+
+impl ExportedType {
+    pub fn set_params(&mut self, params: ExportedModParams) -> syn::Result<()> {
+        self.params = params;
+        Ok(())
+    }
+}
+
+impl Parse for ExportedType {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut item_impl: syn::ItemImpl = input.parse()?;
+
+        if let Some((_, ref path, _)) = item_impl.trait_ {
+            return Err(syn::Error::new(
+                path.span(),
+                "cannot export trait implementations to Rhai",
+            ));
+        }
+        if !item_impl.generics.params.is_empty() {
+            return Err(syn::Error::new(
+                item_impl.generics.span(),
+                "cannot export generic implementations to Rhai",
+            ));
+        }
+        let type_name = match item_impl.self_ty.as_ref() {
+            syn::Type::Path(syn::TypePath { qself: None, path }) => {
+                path.segments.last().unwrap().ident.clone()
+            }
+            t => {
+                return Err(syn::Error::new(
+                    t.span(),
+                    "exported type must be a named type",
+                ))
+            }
+        };
+        let self_type = item_impl.self_ty.as_ref().clone();
+
+        // Gather and parse methods and associated functions.
+        let mut methods = Vec::new();
+        let mut constructors = Vec::new();
+        for item in item_impl.items.iter_mut() {
+            let method = match item {
+                syn::ImplItem::Method(m) => m,
+                _ => continue,
+            };
+            let params: ExportedFnParams =
+                crate::attrs::inner_item_attributes(&mut method.attrs, "rhai_fn")?;
+
+            // 'self' by value would only ever consume a copy of the object
+            if let Some(syn::FnArg::Receiver(syn::Receiver {
+                reference: None,
+                ref self_token,
+                ..
+            })) = method.sig.inputs.first()
+            {
+                return Err(syn::Error::new(
+                    self_token.span(),
+                    "'self' cannot be passed by value from Rhai; use '&self' or '&mut self' instead",
+                ));
+            }
+
+            // The generated code lives outside the impl block, so 'Self' must be spelled out.
+            let mut sig = method.sig.clone();
+            for arg in sig.inputs.iter_mut() {
+                if let syn::FnArg::Typed(syn::PatType { ref mut ty, .. }) = arg {
+                    replace_self_type(ty.as_mut(), &self_type);
+                }
+            }
+            if let syn::ReturnType::Type(_, ref mut ty) = sig.output {
+                replace_self_type(ty.as_mut(), &self_type);
+            }
+            let syn::ImplItemMethod {
+                ref attrs,
+                ref vis,
+                ref block,
+                ..
+            } = method;
+
+            let item_fn = syn::parse2::<syn::ItemFn>(quote! {
+                #(#attrs)* #vis #sig #block
+            })?;
+            let mut function = ExportedFn::with_params(item_fn, params)?;
+            function.set_self_type(self_type.clone());
+
+            if function.has_receiver() {
+                methods.push(function);
+            } else {
+                constructors.push(function);
+            }
+        }
+
+        Ok(ExportedType {
+            item_impl,
+            type_name,
+            methods,
+            constructors,
+            params: ExportedModParams::default(),
+        })
+    }
+}
+
+/// Replace a bare `Self` (or a reference to it) with the implementing type.
+fn replace_self_type(ty: &mut syn::Type, self_type: &syn::Type) {
+    match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) if path.is_ident("Self") => {
+            *ty = self_type.clone()
+        }
+        syn::Type::Reference(syn::TypeReference { ref mut elem, .. })
+        | syn::Type::Group(syn::TypeGroup { ref mut elem, .. })
+        | syn::Type::Paren(syn::TypeParen { ref mut elem, .. }) => {
+            replace_self_type(elem.as_mut(), self_type)
+        }
+        _ => (),
+    }
+}
+
+#[allow(dead_code)]
+impl ExportedType {
+    pub fn type_name(&self) -> &syn::Ident {
+        &self.type_name
+    }
+
+    pub fn exported_name(&self) -> String {
+        self.params
+            .name
+            .clone()
+            .unwrap_or_else(|| self.type_name.to_string())
+    }
+
+    pub fn methods(&self) -> &[ExportedFn] {
+        &self.methods
+    }
+
+    pub fn constructors(&self) -> &[ExportedFn] {
+        &self.constructors
+    }
+
+    pub fn generate(self) -> proc_macro2::TokenStream {
+        match self.generate_inner() {
+            Ok(tokens) => tokens,
+            Err(e) => e.to_compile_error(),
+        }
+    }
+
+    fn generate_inner(self) -> Result<proc_macro2::TokenStream, syn::Error> {
+        // Check for collisions if the "name" attribute was used on methods.
+        crate::rhai_module::check_rename_collisions(&self.methods)?;
+        crate::rhai_module::check_rename_collisions(&self.constructors)?;
+
+        let exported_name = syn::LitStr::new(&self.exported_name(), self.type_name.span());
+        let ExportedType {
+            item_impl,
+            type_name,
+            mut methods,
+            mut constructors,
+            params,
+        } = self;
+
+        let mod_name = generated_type_module_name(&type_name);
+        let methods_gen =
+            crate::rhai_module::generate_body(&mut methods, &[], &mut [], &params.scope);
+        let constructors_gen =
+            crate::rhai_module::generate_body(&mut constructors, &[], &mut [], &params.scope);
+
+        Ok(quote! {
+            #item_impl
+
+            #[allow(non_snake_case)]
+            pub mod #mod_name {
+                #[allow(unused_imports)]
+                use super::*;
+
+                pub mod methods {
+                    #[allow(unused_imports)]
+                    use super::*;
+                    #methods_gen
+                }
+
+                pub mod constructors {
+                    #[allow(unused_imports)]
+                    use super::*;
+                    #constructors_gen
+                }
+
+                pub fn rhai_module_generate() -> Module {
+                    let mut m = Module::new();
+                    rhai_generate_into_module(&mut m, false);
+                    m
+                }
+                pub fn rhai_generate_into_module(m: &mut Module, flatten: bool) {
+                    methods::rhai_generate_into_module(m, flatten);
+
+                    if flatten {
+                        constructors::rhai_generate_into_module(m, flatten);
+                    } else {
+                        m.set_sub_module(#exported_name, constructors::rhai_module_generate());
+                    }
+                }
+            }
+        })
+    }
+}
+
+pub(crate) fn generated_type_module_name(type_name: &syn::Ident) -> syn::Ident {
+    syn::Ident::new(&format!("rhai_type_{}", type_name), type_name.span())
+}
+
+pub(crate) fn generated_type_module_path(type_path: &syn::Path) -> syn::Path {
+    let mut path = type_path.clone();
+    let last = path.segments.last_mut().unwrap();
+    last.ident = generated_type_module_name(&last.ident);
+    last.arguments = syn::PathArguments::None;
+    path
+}
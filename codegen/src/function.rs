@@ -26,8 +26,8 @@ pub enum Index {
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Property {
-    Get(syn::Ident),
-    Set(syn::Ident),
+    Get(Vec<syn::Ident>),
+    Set(Vec<syn::Ident>),
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -43,10 +43,42 @@ impl Default for FnSpecialAccess {
     }
 }
 
-#[derive(Debug, Default)]
+impl FnSpecialAccess {
+    /// The `#[rhai_fn]` attribute declaring this kind of access.
+    fn attr_name(&self) -> &'static str {
+        match self {
+            FnSpecialAccess::None => "",
+            FnSpecialAccess::Index(Index::Get) => "index_get",
+            FnSpecialAccess::Index(Index::Set) => "index_set",
+            FnSpecialAccess::Property(Property::Get(_)) => "get",
+            FnSpecialAccess::Property(Property::Set(_)) => "set",
+        }
+    }
+
+    /// The error for declaring the access `attr` at `span` when this one is already declared.
+    fn conflict(&self, attr: &str, span: proc_macro2::Span) -> syn::Error {
+        if self.attr_name() == attr {
+            syn::Error::new(span, format!("duplicate {}", attr))
+        } else {
+            syn::Error::new(
+                span,
+                format!("cannot combine '{}' with '{}'", attr, self.attr_name()),
+            )
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
 pub(crate) struct ExportedFnParams {
     pub name: Option<Vec<String>>,
+    pub instantiate: Option<Vec<syn::Type>>,
     pub return_raw: bool,
+    pub by_ref: bool,
+    pub pure: bool,
+    pub checked: bool,
+    pub global: bool,
+    pub option_as_unit: bool,
+    pub deprecated: Option<String>,
     pub skip: bool,
     pub span: Option<proc_macro2::Span>,
     pub special: FnSpecialAccess,
@@ -55,6 +87,12 @@ pub(crate) struct ExportedFnParams {
 pub const FN_IDX_GET: &str = "index$get$";
 pub const FN_IDX_SET: &str = "index$set$";
 
+/// Operators that can be overloaded, which are valid Rhai function names.
+pub const OPERATOR_NAMES: [&str; 29] = [
+    "+", "-", "*", "/", "%", "~", "<<", ">>", "&", "|", "^", "!", "==", "!=", "<", "<=", ">", ">=",
+    "+=", "-=", "*=", "/=", "%=", "~=", "<<=", ">>=", "&=", "|=", "^=",
+];
+
 impl Parse for ExportedFnParams {
     fn parse(args: ParseStream) -> syn::Result<Self> {
         if args.is_empty() {
@@ -82,16 +120,55 @@ impl ExportedParams for ExportedFnParams {
         } = info;
         let mut name = Vec::new();
         let mut return_raw = false;
+        let mut by_ref = false;
+        let mut pure = false;
+        let mut checked = false;
+        let mut global = false;
+        let mut option_as_unit = false;
+        let mut deprecated = None;
         let mut skip = false;
         let mut special = FnSpecialAccess::None;
+        let mut instantiate: Option<Vec<syn::Type>> = None;
         for attr in attrs {
             let crate::attrs::AttrItem {
                 key,
                 value,
+                args,
                 span: item_span,
             } = attr;
+            match (key.to_string().as_ref(), args) {
+                ("instantiate", Some(_)) if instantiate.is_some() => {
+                    return Err(syn::Error::new(item_span, "duplicate instantiate"))
+                }
+                ("instantiate", Some(args)) if !args.is_empty() => {
+                    let mut types: Vec<syn::Type> = Vec::new();
+                    for arg in args {
+                        let ty = match arg {
+                            syn::Expr::Path(syn::ExprPath { qself, path, .. }) => {
+                                syn::Type::Path(syn::TypePath { qself, path })
+                            }
+                            x => return Err(syn::Error::new(x.span(), "expecting a type")),
+                        };
+                        let suffix = instance_suffix(&ty);
+                        if types.iter().any(|t| instance_suffix(t) == suffix) {
+                            return Err(syn::Error::new(
+                                ty.span(),
+                                format!("type '{}' is already instantiated", type_to_string(&ty)),
+                            ));
+                        }
+                        types.push(ty);
+                    }
+                    instantiate = Some(types);
+                    continue;
+                }
+                ("instantiate", _) => {
+                    return Err(syn::Error::new(key.span(), "requires a list of types"))
+                }
+                (_, Some(_)) => return Err(syn::Error::new(key.span(), "extraneous arguments")),
+                _ => {}
+            }
             match (key.to_string().as_ref(), value) {
-                ("get", None) | ("set", None) | ("name", None) => {
+                ("get", None) | ("set", None) | ("name", None) | ("deprecated", None) => {
                     return Err(syn::Error::new(key.span(), "requires value"))
                 }
                 ("name", Some(s)) if &s.value() == FN_IDX_GET => {
@@ -136,42 +213,83 @@ impl ExportedParams for ExportedFnParams {
                         "Rhai function names may not contain dot",
                     ))
                 }
+                ("name", Some(s)) if !is_valid_fn_name(&s.value()) => {
+                    return Err(syn::Error::new(
+                        s.span(),
+                        invalid_fn_name_message(&s.value()),
+                    ))
+                }
+                ("name", Some(s)) if name.contains(&s.value()) => {
+                    return Err(syn::Error::new(
+                        s.span(),
+                        format!("alias '{}' is already declared", s.value()),
+                    ))
+                }
                 ("name", Some(s)) => name.push(s.value()),
+                ("deprecated", Some(s)) => deprecated = Some(s.value()),
                 ("set", Some(s)) => {
-                    special = match special {
-                        FnSpecialAccess::None => FnSpecialAccess::Property(Property::Set(
-                            syn::Ident::new(&s.value(), s.span()),
-                        )),
-                        _ => return Err(syn::Error::new(item_span.span(), "conflicting setter")),
+                    let prop = syn::Ident::new(&s.value(), s.span());
+                    match special {
+                        FnSpecialAccess::None => {
+                            special = FnSpecialAccess::Property(Property::Set(vec![prop]))
+                        }
+                        FnSpecialAccess::Property(Property::Set(ref props))
+                            if props.contains(&prop) =>
+                        {
+                            return Err(syn::Error::new(
+                                item_span.span(),
+                                format!("duplicate setter '{}'", prop),
+                            ))
+                        }
+                        FnSpecialAccess::Property(Property::Set(ref mut props)) => props.push(prop),
+                        _ => return Err(special.conflict("set", item_span.span())),
                     }
                 }
                 ("get", Some(s)) => {
-                    special = match special {
-                        FnSpecialAccess::None => FnSpecialAccess::Property(Property::Get(
-                            syn::Ident::new(&s.value(), s.span()),
-                        )),
-                        _ => return Err(syn::Error::new(item_span.span(), "conflicting getter")),
+                    let prop = syn::Ident::new(&s.value(), s.span());
+                    match special {
+                        FnSpecialAccess::None => {
+                            special = FnSpecialAccess::Property(Property::Get(vec![prop]))
+                        }
+                        FnSpecialAccess::Property(Property::Get(ref props))
+                            if props.contains(&prop) =>
+                        {
+                            return Err(syn::Error::new(
+                                item_span.span(),
+                                format!("duplicate getter '{}'", prop),
+                            ))
+                        }
+                        FnSpecialAccess::Property(Property::Get(ref mut props)) => props.push(prop),
+                        _ => return Err(special.conflict("get", item_span.span())),
                     }
                 }
                 ("index_get", None) => {
                     special = match special {
                         FnSpecialAccess::None => FnSpecialAccess::Index(Index::Get),
-                        _ => {
-                            return Err(syn::Error::new(item_span.span(), "conflicting index_get"))
-                        }
+                        _ => return Err(special.conflict("index_get", item_span.span())),
                     }
                 }
 
                 ("index_set", None) => {
                     special = match special {
                         FnSpecialAccess::None => FnSpecialAccess::Index(Index::Set),
-                        _ => {
-                            return Err(syn::Error::new(item_span.span(), "conflicting index_set"))
-                        }
+                        _ => return Err(special.conflict("index_set", item_span.span())),
                     }
                 }
                 ("return_raw", None) => return_raw = true,
-                ("index_get", Some(s)) | ("index_set", Some(s)) | ("return_raw", Some(s)) => {
+                ("by_ref", None) => by_ref = true,
+                ("pure", None) => pure = true,
+                ("checked", None) => checked = true,
+                ("global", None) => global = true,
+                ("option_as_unit", None) => option_as_unit = true,
+                ("index_get", Some(s))
+                | ("index_set", Some(s))
+                | ("return_raw", Some(s))
+                | ("by_ref", Some(s))
+                | ("pure", Some(s))
+                | ("checked", Some(s))
+                | ("global", Some(s))
+                | ("option_as_unit", Some(s)) => {
                     return Err(syn::Error::new(s.span(), "extraneous value"))
                 }
                 ("skip", None) => skip = true,
@@ -187,7 +305,14 @@ impl ExportedParams for ExportedFnParams {
 
         Ok(ExportedFnParams {
             name: if name.is_empty() { None } else { Some(name) },
+            instantiate,
             return_raw,
+            by_ref,
+            pure,
+            checked,
+            global,
+            option_as_unit,
+            deprecated,
             skip,
             special,
             span: Some(span),
@@ -200,47 +325,413 @@ impl ExportedParams for ExportedFnParams {
 pub(crate) struct ExportedFn {
     entire_span: proc_macro2::Span,
     signature: syn::Signature,
+    doc_attrs: Vec<syn::Attribute>,
+    cfg_attrs: Vec<syn::Attribute>,
     is_public: bool,
     mut_receiver: bool,
+    self_type: Option<syn::Type>,
+    instance: Option<syn::Type>,
     params: ExportedFnParams,
 }
 
 impl Parse for ExportedFn {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let fn_all: syn::ItemFn = input.parse()?;
+        Self::from_item_fn(fn_all, false)
+    }
+}
+
+/// The largest tuple returned to Rhai as an array; larger tuples are returned as custom values.
+const MAX_TUPLE_ARITY: usize = 12;
+
+/// Generate an expression that converts the `value` returned by a function, of type `ty`,
+/// into a `Dynamic`.
+///
+/// A `Vec<T>` becomes an array, with each element converted via `Into<Dynamic>`, instead of an
+/// opaque custom value.  `Vec<Dynamic>` is already an array, and `Vec<u8>` becomes an array
+/// of integers.
+///
+/// A tuple of up to `MAX_TUPLE_ARITY` elements becomes an array of its elements, in order,
+/// each converted in the same way.
+///
+/// An `Option<T>` becomes its value, converted in the same way, or `()` for `None`.
+///
+/// A `String` is turned into an `ImmutableString` directly, which saves `Dynamic::from` from
+/// boxing it first.  A `Dynamic` is returned as it is.
+fn return_to_dynamic(
+    ty: Option<&syn::Type>,
+    value: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    if let Some(syn::Type::Tuple(t)) = ty {
+        if !t.elems.is_empty() && t.elems.len() <= MAX_TUPLE_ARITY {
+            let vars: Vec<_> = (0..t.elems.len())
+                .map(|i| syn::Ident::new(&format!("v{}", i), t.span()))
+                .collect();
+            let elems = t
+                .elems
+                .iter()
+                .zip(vars.iter())
+                .map(|(ty, var)| return_to_dynamic(Some(ty), quote! { #var }));
+
+            return quote! {
+                {
+                    let (#(#vars,)*) = #value;
+                    Dynamic::from(new_vec![#(#elems),*])
+                }
+            };
+        }
+    }
+
+    if let Some(item) = ty.and_then(|ty| generic_arg(ty, "Option")) {
+        let item_value = return_to_dynamic(Some(item), quote! { v });
+        return quote! {
+            match #value {
+                Some(v) => #item_value,
+                None => Dynamic::from(()),
+            }
+        };
+    }
+
+    match ty.and_then(|ty| generic_arg(ty, "Vec")) {
+        Some(elem) if is_type_named(elem, "Dynamic") => quote! { Dynamic::from(#value) },
+        Some(elem) if is_type_named(elem, "u8") => quote! {
+            Dynamic::from(
+                #value.into_iter().map(|v| Dynamic::from(INT::from(v))).collect::<Vec<Dynamic>>()
+            )
+        },
+        // A compile error at the element type if it cannot be converted into a Dynamic
+        Some(elem) => quote_spanned! { elem.span()=>
+            Dynamic::from(
+                #value.into_iter().map(Into::<Dynamic>::into).collect::<Vec<Dynamic>>()
+            )
+        },
+        None => match ty.and_then(map_args) {
+            // Keys are checked to be `String` when the function is parsed
+            Some((_, item)) => quote_spanned! { item.span()=>
+                Dynamic::from(
+                    #value.into_iter()
+                        .map(|(k, v)| (ImmutableString::from(k), Into::<Dynamic>::into(v)))
+                        .collect::<Map>()
+                )
+            },
+            None if matches!(ty, Some(ty) if is_type_named(ty, "String")) => {
+                quote! { Dynamic::from(ImmutableString::from(#value)) }
+            }
+            // Already a `Dynamic`
+            None if matches!(ty, Some(ty) if is_type_named(ty, "Dynamic")) => quote! { #value },
+            None => quote! { Dynamic::from(#value) },
+        },
+    }
+}
+
+/// If `ty` is the generic type `name` with a single type argument (e.g. `Vec<T>`),
+/// return that type argument.
+fn generic_arg<'a>(ty: &'a syn::Type, name: &str) -> Option<&'a syn::Type> {
+    match generic_args(ty, name)?.as_slice() {
+        &[arg] => Some(arg),
+        _ => None,
+    }
+}
+
+/// If `ty` is `Result<T, Box<EvalAltResult>>`, return `T`.
+///
+/// Functions returning such a type can fail with a Rhai error, so they are treated as if they
+/// had the `return_raw` attribute.
+fn fallible_result_type(ty: &syn::Type) -> Option<&syn::Type> {
+    match generic_args(ty, "Result")?.as_slice() {
+        &[item, err] => match generic_arg(err, "Box") {
+            Some(e) if is_type_named(e, "EvalAltResult") => Some(item),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// If `ty` is `HashMap<K, T>` or `BTreeMap<K, T>`, return the key and item types.
+///
+/// Always `None` under `no_object`, as there are no object maps to convert into.
+fn map_args(ty: &syn::Type) -> Option<(&syn::Type, &syn::Type)> {
+    if cfg!(feature = "no_object") {
+        return None;
+    }
+
+    let args = generic_args(ty, "HashMap").or_else(|| generic_args(ty, "BTreeMap"))?;
+
+    // `HashMap` may also have a hasher type argument
+    match args.as_slice() {
+        &[key, item] | &[key, item, _] => Some((key, item)),
+        _ => None,
+    }
+}
+
+/// If `ty` is the generic type `name`, return its type arguments.
+///
+/// Lifetime arguments (e.g. in `Cow<'static, str>`) are skipped.
+fn generic_args<'a>(ty: &'a syn::Type, name: &str) -> Option<Vec<&'a syn::Type>> {
+    let segment = match flatten_type_groups(ty) {
+        syn::Type::Path(p) if p.qself.is_none() => p.path.segments.last()?,
+        _ => return None,
+    };
+
+    if segment.ident != name {
+        return None;
+    }
+
+    match segment.arguments {
+        syn::PathArguments::AngleBracketed(ref args) => args
+            .args
+            .iter()
+            .filter(|arg| !matches!(arg, syn::GenericArgument::Lifetime(_)))
+            .map(|arg| match arg {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => None,
+    }
+}
+
+/// If `ty` is a smart pointer (`Box<T>`, `Rc<T>` or `Arc<T>`), return the type it points to.
+///
+/// Values are stored in a `Dynamic` as `T` itself, so a `&mut` reference to the pointer cannot
+/// be made for them.
+fn smart_pointer_target(ty: &syn::Type) -> Option<&syn::Type> {
+    generic_arg(ty, "Box")
+        .or_else(|| generic_arg(ty, "Rc"))
+        .or_else(|| generic_arg(ty, "Arc"))
+}
+
+/// Is `ty` `Cow<str>`?
+pub(crate) fn is_cow_str_type(ty: &syn::Type) -> bool {
+    matches!(generic_arg(ty, "Cow"), Some(t) if is_type_named(t, "str"))
+}
+
+/// Is `ty` a plain type path whose last segment is `name`?
+fn is_type_named(ty: &syn::Type, name: &str) -> bool {
+    match flatten_type_groups(ty) {
+        syn::Type::Path(p) => {
+            p.qself.is_none()
+                && matches!(p.path.segments.last(), Some(s) if s.ident == name && s.arguments.is_empty())
+        }
+        _ => false,
+    }
+}
+
+/// Is `ty` the unit type `()`?
+fn is_unit_type(ty: &syn::Type) -> bool {
+    match flatten_type_groups(ty) {
+        syn::Type::Tuple(t) => t.elems.is_empty(),
+        _ => false,
+    }
+}
+
+/// A function name is either an identifier or one of the operators that can be overloaded.
+fn is_valid_fn_name(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    match chars.next() {
+        Some(c) if c.is_alphabetic() || c == '_' => chars.all(|c| c.is_alphanumeric() || c == '_'),
+        _ => OPERATOR_NAMES.contains(&name),
+    }
+}
+
+/// Error message for an invalid function name, suggesting an operator with the same symbols.
+fn invalid_fn_name_message(name: &str) -> String {
+    let mut symbols: Vec<_> = name.chars().collect();
+    symbols.sort();
+
+    let suggestion = OPERATOR_NAMES.iter().find(|op| {
+        let mut op_symbols: Vec<_> = op.chars().collect();
+        op_symbols.sort();
+        op_symbols == symbols
+    });
+
+    match suggestion {
+        Some(op) => format!(
+            "'{}' is not a valid Rhai function name or operator; did you mean '{}'?",
+            name, op
+        ),
+        None => format!("'{}' is not a valid Rhai function name or operator", name),
+    }
+}
+
+/// Is `ty` the `&[Dynamic]` type of a trailing parameter taking any number of arguments?
+pub(crate) fn is_varadic_type(ty: &syn::Type) -> bool {
+    match flatten_type_groups(ty) {
+        syn::Type::Reference(syn::TypeReference {
+            mutability: None,
+            ref elem,
+            ..
+        }) => match flatten_type_groups(elem.as_ref()) {
+            syn::Type::Slice(syn::TypeSlice { ref elem, .. }) => is_type_named(elem, "Dynamic"),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Note on the arguments that can be passed by shared reference.
+const SHARED_REF_HELP: &str = "help: '&str' is the only shared reference allowed, \
+    unless the function has the 'by_ref' attribute";
+
+/// Render a type as it would be written in source, for diagnostics.
+fn type_to_string(ty: &syn::Type) -> String {
+    quote!(#ty)
+        .to_string()
+        .replace(" :: ", "::")
+        .replace(" <", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace("& ", "&")
+        .replace("[ ", "[")
+        .replace(" ]", "]")
+}
+
+/// The suffix naming the instance of a generic function for the type `ty`, e.g. `vec_u8` for
+/// `Vec::<u8>`.
+pub(crate) fn instance_suffix(ty: &syn::Type) -> String {
+    let mut suffix = String::new();
+    for c in quote!(#ty).to_string().chars() {
+        if c.is_alphanumeric() {
+            suffix.extend(c.to_lowercase());
+        } else if !suffix.is_empty() && !suffix.ends_with('_') {
+            suffix.push('_');
+        }
+    }
+    suffix.trim_end_matches('_').to_string()
+}
+
+/// Replace every occurrence of the identifier `param` in `tokens` with the type `ty`.
+fn substitute_type_param(
+    tokens: proc_macro2::TokenStream,
+    param: &syn::Ident,
+    ty: &syn::Type,
+) -> proc_macro2::TokenStream {
+    tokens
+        .into_iter()
+        .map(|tt| match tt {
+            proc_macro2::TokenTree::Ident(ref ident) if ident == param => quote! { #ty },
+            proc_macro2::TokenTree::Group(ref group) => {
+                let mut new_group = proc_macro2::Group::new(
+                    group.delimiter(),
+                    substitute_type_param(group.stream(), param, ty),
+                );
+                new_group.set_span(group.span());
+                proc_macro2::TokenTree::Group(new_group).into()
+            }
+            tt => tt.into(),
+        })
+        .collect()
+}
+
+/// The referenced type of an argument passed by shared reference, other than `&str`.
+pub(crate) fn shared_ref_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let str_type_path = syn::parse2::<syn::Path>(quote! { str }).unwrap();
+
+    match flatten_type_groups(ty) {
+        &syn::Type::Reference(syn::TypeReference {
+            mutability: None,
+            ref elem,
+            ..
+        }) => match flatten_type_groups(elem.as_ref()) {
+            &syn::Type::Path(ref p) if p.path == str_type_path => None,
+            _ => Some(elem.as_ref()),
+        },
+        _ => None,
+    }
+}
+
+impl ExportedFn {
+    /// Create an exported function from its definition.
+    ///
+    /// Arguments other than `&str` may only be passed by shared reference if `allow_shared_refs`
+    /// is set, i.e. the function has the `by_ref` attribute.
+    pub(crate) fn from_item_fn(fn_all: syn::ItemFn, allow_shared_refs: bool) -> syn::Result<Self> {
         let entire_span = fn_all.span();
         let str_type_path = syn::parse2::<syn::Path>(quote! { str }).unwrap();
 
-        // #[cfg] attributes are not allowed on functions due to what is generated for them
-        crate::attrs::deny_cfg_attr(&fn_all.attrs)?;
-
         // Determine if the function is public.
         let is_public = matches!(fn_all.vis, syn::Visibility::Public(_));
+
+        // Keep the doc comments, in order, to pass them on to the generated module.
+        let doc_attrs = fn_all
+            .attrs
+            .iter()
+            .filter(|a| a.path.is_ident("doc"))
+            .cloned()
+            .collect();
+
+        // Keep the #[cfg] attributes to gate everything generated for the function with them.
+        let cfg_attrs = fn_all
+            .attrs
+            .iter()
+            .filter(|a| a.path.is_ident("cfg"))
+            .cloned()
+            .collect();
+
+        // Only the last argument may take the rest of the arguments as `&[Dynamic]`.
+        let arg_count = fn_all.sig.inputs.len();
+        for arg in fn_all.sig.inputs.iter().take(arg_count.saturating_sub(1)) {
+            match arg {
+                syn::FnArg::Typed(syn::PatType { ref ty, .. }) if is_varadic_type(ty) => {
+                    return Err(syn::Error::new(
+                        ty.span(),
+                        "only the last parameter can take the rest of the arguments",
+                    ))
+                }
+                _ => {}
+            }
+        }
+
         // Determine whether function generates a special calling convention for a mutable
         // reciever.
         let mut_receiver = {
             if let Some(first_arg) = fn_all.sig.inputs.first() {
                 match first_arg {
                     syn::FnArg::Receiver(syn::Receiver {
-                        reference: Some(_), ..
+                        reference: Some(_),
+                        mutability: Some(_),
+                        ..
                     }) => true,
                     syn::FnArg::Typed(syn::PatType { ref ty, .. }) => {
                         match flatten_type_groups(ty.as_ref()) {
                             &syn::Type::Reference(syn::TypeReference {
                                 mutability: Some(_),
+                                ref elem,
                                 ..
-                            }) => true,
+                            }) => match smart_pointer_target(elem.as_ref()) {
+                                Some(target) => {
+                                    return Err(syn::Error::new(
+                                        ty.span(),
+                                        format!(
+                                            "smart-pointer receivers are not supported; \
+                                                use '&mut {}' instead of '{}'",
+                                            type_to_string(target),
+                                            type_to_string(ty),
+                                        ),
+                                    ))
+                                }
+                                None => true,
+                            },
                             &syn::Type::Reference(syn::TypeReference {
                                 mutability: None,
                                 ref elem,
                                 ..
                             }) => match flatten_type_groups(elem.as_ref()) {
                                 &syn::Type::Path(ref p) if p.path == str_type_path => false,
+                                _ if allow_shared_refs || is_varadic_type(ty) => false,
                                 _ => {
                                     return Err(syn::Error::new(
                                         ty.span(),
-                                        "references from Rhai in this position \
-                                            must be mutable",
+                                        format!(
+                                            "cannot pass '{}' from Rhai; use '&mut {}' instead, \
+                                                or take '{}' by value\n{}",
+                                            type_to_string(ty),
+                                            type_to_string(elem),
+                                            type_to_string(elem),
+                                            SHARED_REF_HELP
+                                        ),
                                     ))
                                 }
                             },
@@ -254,36 +745,50 @@ impl Parse for ExportedFn {
             }
         };
 
-        // All arguments after the first must be moved except for &str.
+        // All arguments after the first must be moved except for &str,
+        // or shared references for `by_ref` functions.
         for arg in fn_all.sig.inputs.iter().skip(1) {
             let ty = match arg {
                 syn::FnArg::Typed(syn::PatType { ref ty, .. }) => ty,
                 _ => panic!("internal error: receiver argument outside of first position!?"),
             };
-            let is_ok = match flatten_type_groups(ty.as_ref()) {
+            let message = match flatten_type_groups(ty.as_ref()) {
                 &syn::Type::Reference(syn::TypeReference {
                     mutability: Some(_),
+                    ref elem,
                     ..
-                }) => false,
+                }) => format!(
+                    "only the first argument can be passed from Rhai by mutable reference; \
+                        take '{}' by value instead",
+                    type_to_string(elem)
+                ),
                 &syn::Type::Reference(syn::TypeReference {
                     mutability: None,
                     ref elem,
                     ..
-                }) => {
-                    matches!(flatten_type_groups(elem.as_ref()), &syn::Type::Path(ref p) if p.path == str_type_path)
+                }) if !(allow_shared_refs
+                    || is_varadic_type(ty)
+                    || matches!(flatten_type_groups(elem.as_ref()), &syn::Type::Path(ref p) if p.path == str_type_path)) =>
+                {
+                    format!(
+                        "cannot pass '{}' from Rhai in this position; take '{}' by value instead\n{}",
+                        type_to_string(ty),
+                        type_to_string(elem),
+                        SHARED_REF_HELP
+                    )
                 }
-                &syn::Type::Verbatim(_) => false,
-                _ => true,
+                &syn::Type::Verbatim(_) => "this type cannot be passed from Rhai".to_string(),
+                _ => continue,
             };
-            if !is_ok {
-                return Err(syn::Error::new(
-                    ty.span(),
-                    "this type in this position passes from \
-                                                        Rhai by value",
-                ));
-            }
+            return Err(syn::Error::new(ty.span(), message));
         }
 
+        // Functions returning `Result<T, Box<EvalAltResult>>` are always "raw".
+        let is_fallible = match fn_all.sig.output {
+            syn::ReturnType::Type(_, ref rtype) => fallible_result_type(rtype).is_some(),
+            syn::ReturnType::Default => false,
+        };
+
         // No returning references or pointers.
         if let syn::ReturnType::Type(_, ref rtype) = fn_all.sig.output {
             match rtype.as_ref() {
@@ -299,20 +804,41 @@ impl Parse for ExportedFn {
                         "cannot return a reference to Rhai",
                     ))
                 }
-                _ => {}
+                // Object maps returned to Rhai are keyed by strings.
+                ty => match map_args(ty) {
+                    Some((key, _)) if !is_type_named(key, "String") => {
+                        return Err(syn::Error::new(
+                            key.span(),
+                            "object map keys returned to Rhai must be String",
+                        ))
+                    }
+                    _ => {}
+                },
             }
         }
         Ok(ExportedFn {
             entire_span,
             signature: fn_all.sig,
+            doc_attrs,
+            cfg_attrs,
             is_public,
             mut_receiver,
-            params: ExportedFnParams::default(),
+            self_type: None,
+            instance: None,
+            params: ExportedFnParams {
+                return_raw: is_fallible,
+                ..Default::default()
+            },
         })
     }
-}
 
-impl ExportedFn {
+    /// Create an exported function from its definition and the parameters from its attributes.
+    pub(crate) fn with_params(fn_all: syn::ItemFn, params: ExportedFnParams) -> syn::Result<Self> {
+        let mut function = Self::from_item_fn(fn_all, params.by_ref)?;
+        function.set_params(params)?;
+        Ok(function)
+    }
+
     pub(crate) fn params(&self) -> &ExportedFnParams {
         &self.params
     }
@@ -331,6 +857,10 @@ impl ExportedFn {
         self.params.skip
     }
 
+    pub(crate) fn is_global(&self) -> bool {
+        self.params.global
+    }
+
     pub(crate) fn signature(&self) -> &syn::Signature {
         &self.signature
     }
@@ -339,6 +869,131 @@ impl ExportedFn {
         self.mut_receiver
     }
 
+    pub(crate) fn self_type(&self) -> Option<&syn::Type> {
+        self.self_type.as_ref()
+    }
+
+    pub(crate) fn set_self_type(&mut self, self_type: syn::Type) {
+        self.self_type = Some(self_type);
+    }
+
+    /// The name of the function, followed by the type it is instantiated with, if any.
+    pub(crate) fn instance_name(&self) -> String {
+        match self.instance {
+            Some(ref ty) => format!("{}_{}", self.name(), instance_suffix(ty)),
+            None => self.name().to_string(),
+        }
+    }
+
+    /// A copy of a generic function for each type listed in its `instantiate` attribute, with
+    /// its type parameter replaced by that type.
+    pub(crate) fn instantiations(&self) -> Vec<ExportedFn> {
+        let types = match self.params.instantiate {
+            Some(ref types) => types,
+            None => return Vec::new(),
+        };
+        let param = &self
+            .signature
+            .generics
+            .type_params()
+            .next()
+            .expect("internal error: instantiating a function without type parameter!?")
+            .ident;
+
+        types
+            .iter()
+            .map(|ty| {
+                let substitute = |t: &syn::Type| {
+                    syn::parse2::<syn::Type>(substitute_type_param(quote! { #t }, param, ty))
+                        .unwrap()
+                };
+
+                let mut signature = self.signature.clone();
+                signature.generics.params = signature
+                    .generics
+                    .params
+                    .into_iter()
+                    .filter(|p| !matches!(p, syn::GenericParam::Type(_)))
+                    .collect();
+                signature.generics.where_clause = None;
+                for arg in signature.inputs.iter_mut() {
+                    if let syn::FnArg::Typed(syn::PatType { ref mut ty, .. }) = arg {
+                        **ty = substitute(ty);
+                    }
+                }
+                if let syn::ReturnType::Type(_, ref mut rtype) = signature.output {
+                    **rtype = substitute(rtype);
+                }
+
+                ExportedFn {
+                    entire_span: self.entire_span,
+                    signature,
+                    doc_attrs: self.doc_attrs.clone(),
+                    cfg_attrs: self.cfg_attrs.clone(),
+                    is_public: self.is_public,
+                    mut_receiver: self.mut_receiver,
+                    self_type: self.self_type.clone(),
+                    instance: Some(ty.clone()),
+                    params: ExportedFnParams {
+                        instantiate: None,
+                        ..self.params.clone()
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// Does the last parameter, `&[Dynamic]`, take any number of trailing arguments?
+    pub(crate) fn is_varadic(&self) -> bool {
+        match self.arg_list().last() {
+            Some(syn::FnArg::Typed(syn::PatType { ref ty, .. })) => is_varadic_type(ty),
+            _ => false,
+        }
+    }
+
+    /// The number of leading arguments with a fixed type.
+    pub(crate) fn fixed_arg_count(&self) -> usize {
+        self.arg_count() - self.is_varadic() as usize
+    }
+
+    pub(crate) fn has_receiver(&self) -> bool {
+        matches!(self.arg_list().next(), Some(syn::FnArg::Receiver(_)))
+    }
+
+    /// Reject a `self` receiver, which needs the `Self` type of an `impl` block.
+    ///
+    /// Only methods exported via `#[export_type]` know their `Self` type.
+    pub(crate) fn deny_receiver(&self) -> syn::Result<()> {
+        match self.arg_list().next() {
+            Some(syn::FnArg::Receiver(syn::Receiver {
+                reference: None,
+                ref self_token,
+                ..
+            })) => Err(syn::Error::new(
+                self_token.span(),
+                "'self' cannot be passed by value from Rhai",
+            )),
+            Some(syn::FnArg::Receiver(syn::Receiver { ref self_token, .. })) => {
+                Err(syn::Error::new(
+                    self_token.span(),
+                    "methods with 'self' can only be exported from an #[export_type] impl block",
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The type of a function argument, which is the `Self` type for a receiver.
+    pub(crate) fn arg_type<'a>(&'a self, arg: &'a syn::FnArg) -> &'a syn::Type {
+        match arg {
+            syn::FnArg::Typed(syn::PatType { ref ty, .. }) => ty.as_ref(),
+            syn::FnArg::Receiver(_) => self
+                .self_type
+                .as_ref()
+                .expect("internal error: receiver fn outside impl!?"),
+        }
+    }
+
     pub(crate) fn is_public(&self) -> bool {
         self.is_public
     }
@@ -347,6 +1002,14 @@ impl ExportedFn {
         &self.entire_span
     }
 
+    pub(crate) fn doc_attrs(&self) -> &[syn::Attribute] {
+        &self.doc_attrs
+    }
+
+    pub(crate) fn cfg_attrs(&self) -> &[syn::Attribute] {
+        &self.cfg_attrs
+    }
+
     pub(crate) fn name(&self) -> &syn::Ident {
         &self.signature.ident
     }
@@ -365,14 +1028,16 @@ impl ExportedFn {
 
         match self.params.special {
             FnSpecialAccess::None => {}
-            FnSpecialAccess::Property(Property::Get(ref g)) => literals.push(syn::LitStr::new(
-                &format!("get${}", g.to_string()),
-                g.span(),
-            )),
-            FnSpecialAccess::Property(Property::Set(ref s)) => literals.push(syn::LitStr::new(
-                &format!("set${}", s.to_string()),
-                s.span(),
-            )),
+            FnSpecialAccess::Property(Property::Get(ref props)) => literals.extend(
+                props
+                    .iter()
+                    .map(|g| syn::LitStr::new(&format!("get${}", g.to_string()), g.span())),
+            ),
+            FnSpecialAccess::Property(Property::Set(ref props)) => literals.extend(
+                props
+                    .iter()
+                    .map(|s| syn::LitStr::new(&format!("set${}", s.to_string()), s.span())),
+            ),
             FnSpecialAccess::Index(Index::Get) => {
                 literals.push(syn::LitStr::new(FN_IDX_GET, proc_macro2::Span::call_site()))
             }
@@ -415,12 +1080,24 @@ impl ExportedFn {
         }
     }
 
+    /// Is this a fallible index getter or setter?
+    ///
+    /// These return `Result<T, Box<EvalAltResult>>` for any `T` (`()` for setters), which is
+    /// mapped into a `Dynamic`.
+    fn is_raw_indexer(&self) -> bool {
+        self.params.return_raw && matches!(self.params.special, FnSpecialAccess::Index(_))
+    }
+
     pub fn set_params(&mut self, mut params: ExportedFnParams) -> syn::Result<()> {
         // Several issues are checked here to avoid issues with diagnostics caused by raising them
         // later.
         //
         // 1. Do not allow non-returning raw functions.
         //
+        //    Functions returning `Result<T, Box<EvalAltResult>>` are raw even without the
+        //    attribute.
+        //
+        params.return_raw = params.return_raw || self.params.return_raw;
         if params.return_raw
             && mem::discriminant(&self.signature.output)
                 == mem::discriminant(&syn::ReturnType::Default)
@@ -431,21 +1108,56 @@ impl ExportedFn {
             ));
         }
 
-        match params.special {
-            // 2a. Property getters must take only the subject as an argument.
-            FnSpecialAccess::Property(Property::Get(_)) if self.arg_count() != 1 => {
-                return Err(syn::Error::new(
-                    self.signature.span(),
-                    "property getter requires exactly 1 argument",
-                ))
+        // 2. Property getters/setters and indexers take a fixed number of arguments.
+        //
+        if self.is_varadic() && params.special != FnSpecialAccess::None {
+            return Err(syn::Error::new(
+                self.signature.span(),
+                "property and index functions cannot take any number of arguments",
+            ));
+        }
+
+        // 2a. Property getters must take only the subject as an argument, and must return a
+        //     value, which may be fallible.  Aliases call the same function, so they cannot be
+        //     binary operators.  All of these are reported in a single error.
+        //
+        if let FnSpecialAccess::Property(Property::Get(_)) = params.special {
+            let mut problems = Vec::new();
+            let mut span = self.signature.span();
+
+            if self.arg_count() != 1 {
+                problems.push("requires exactly 1 argument".to_string());
             }
-            // 2b. Property getters must return a value.
-            FnSpecialAccess::Property(Property::Get(_)) if self.return_type().is_none() => {
-                return Err(syn::Error::new(
-                    self.signature.span(),
-                    "property getter must return a value",
-                ))
+            let returns_value = matches!(
+                self.return_type(),
+                Some(t) if !is_unit_type(fallible_result_type(t).unwrap_or(t))
+            );
+            if !returns_value {
+                problems.push("must return a value".to_string());
+                if problems.len() == 1 {
+                    if let Some(t) = self.return_type() {
+                        span = t.span();
+                    }
+                }
+            }
+            for name in params.name.iter().flatten() {
+                if OPERATOR_NAMES.contains(&name.as_str()) && name != "!" && name != "-" {
+                    problems.push(format!("cannot be aliased to binary operator '{}'", name));
+                    span = self.signature.span();
+                }
+            }
+
+            if let Some((last, rest)) = problems.split_last() {
+                let message = if rest.is_empty() {
+                    format!("property getter {}", last)
+                } else {
+                    format!("property getter {} and {}", rest.join(", "), last)
+                };
+                return Err(syn::Error::new(span, message));
             }
+        }
+
+        match params.special {
             // 3a. Property setters must take the subject and a new value as arguments.
             FnSpecialAccess::Property(Property::Set(_)) if self.arg_count() != 2 => {
                 return Err(syn::Error::new(
@@ -481,28 +1193,155 @@ impl ExportedFn {
                     "index setter requires exactly 3 arguments",
                 ))
             }
-            // 5b. Index setters must return nothing.
-            FnSpecialAccess::Index(Index::Set) if self.return_type().is_some() => {
+            // 5b. Index setters must return nothing, unless they are fallible.
+            FnSpecialAccess::Index(Index::Set)
+                if self.return_type().is_some() && !params.return_raw =>
+            {
                 return Err(syn::Error::new(
                     self.signature.span(),
                     "index setter must return no value",
                 ))
             }
+            // 5c. Fallible index setters must not return a value on success either.
+            FnSpecialAccess::Index(Index::Set)
+                if matches!(
+                    self.return_type().and_then(fallible_result_type),
+                    Some(t) if !is_unit_type(t)
+                ) =>
+            {
+                return Err(syn::Error::new(
+                    self.signature.span(),
+                    "index setter must return Result<(), Box<EvalAltResult>>",
+                ))
+            }
+            _ => {}
+        }
+
+        // 6. Pure functions promise not to change their first argument, so it must be one that
+        //    could have been changed, and they cannot be setters.
+        //
+        if params.pure && !self.mutable_receiver() {
+            return Err(syn::Error::new(
+                self.signature.span(),
+                "pure functions must take their first argument by mutable reference",
+            ));
+        }
+        match params.special {
+            FnSpecialAccess::Property(Property::Set(_)) | FnSpecialAccess::Index(Index::Set)
+                if params.pure =>
+            {
+                return Err(syn::Error::new(
+                    self.signature.span(),
+                    "property and index setters cannot be pure",
+                ))
+            }
             _ => {}
         }
 
+        // 7. Generic functions are instantiated by replacing their only type parameter.
+        //
+        let generics = &self.signature.generics;
+        if params.instantiate.is_some()
+            && (generics.type_params().count() != 1 || generics.const_params().next().is_some())
+        {
+            return Err(syn::Error::new(
+                if generics.params.is_empty() {
+                    self.signature.span()
+                } else {
+                    generics.span()
+                },
+                "instantiate requires a function with exactly one type parameter",
+            ));
+        }
+
+        // 8. Operators take as many arguments as their operands.
+        //
+        for name in params.name.iter().flatten() {
+            let (valid, expected) = match name.as_str() {
+                "!" => (self.arg_count() == 1, "exactly 1 argument"),
+                "-" => (
+                    self.arg_count() == 1 || self.arg_count() == 2,
+                    "1 or 2 arguments",
+                ),
+                op if OPERATOR_NAMES.contains(&op) => {
+                    (self.arg_count() == 2, "exactly 2 arguments")
+                }
+                _ => continue,
+            };
+            if !valid {
+                return Err(syn::Error::new(
+                    self.signature.span(),
+                    format!(
+                        "operator '{}' requires {}\n\
+                            help: the recognized operators are {}; \
+                            '!' and '-' may be unary, the others are binary",
+                        name,
+                        expected,
+                        OPERATOR_NAMES.join(" ")
+                    ),
+                ));
+            }
+        }
+
+        // 9. Only a returned `Option` can be kept opaque.
+        //
+        if params.option_as_unit
+            && self
+                .return_type()
+                .map(|t| fallible_result_type(t).unwrap_or(t))
+                .and_then(|t| generic_arg(t, "Option"))
+                .is_none()
+        {
+            return Err(syn::Error::new(
+                self.return_type()
+                    .map_or_else(|| self.signature.span(), |t| t.span()),
+                "option_as_unit requires a function returning Option<T>",
+            ));
+        }
+
         self.params = params;
         Ok(())
     }
 
     pub fn generate(self) -> proc_macro2::TokenStream {
-        let name: syn::Ident =
-            syn::Ident::new(&format!("rhai_fn_{}", self.name()), self.name().span());
+        // Each instance of a generic function is exported on its own
+        if self.params.instantiate.is_some() {
+            let warnings = self.generate_warnings();
+            let cfg_attrs = self.cfg_attrs();
+            let mut output: proc_macro2::TokenStream = self
+                .instantiations()
+                .into_iter()
+                .map(ExportedFn::generate)
+                .collect();
+            if !warnings.is_empty() {
+                output.extend(quote! {
+                    #(#cfg_attrs)*
+                    #warnings
+                });
+            }
+            return output;
+        }
+
+        let name: syn::Ident = syn::Ident::new(
+            &format!("rhai_fn_{}", self.instance_name()),
+            self.name().span(),
+        );
         let impl_block = self.generate_impl("Token");
         let callable_block = self.generate_callable("Token");
         let input_types_block = self.generate_input_types("Token");
+        let hash_block = self.generate_hash("Token");
+        let metadata_block = self.generate_metadata("Token");
+        let deprecated = match self.params.deprecated {
+            Some(ref message) => quote! { Some(#message) },
+            None => quote! { None },
+        };
         let dyn_result_fn_block = self.generate_dynamic_fn();
+        let warnings = self.generate_warnings();
+        let doc_attrs = self.doc_attrs();
+        let cfg_attrs = self.cfg_attrs();
         quote! {
+            #(#doc_attrs)*
+            #(#cfg_attrs)*
             #[allow(unused)]
             pub mod #name {
                 use super::*;
@@ -510,13 +1349,105 @@ impl ExportedFn {
                 #impl_block
                 #callable_block
                 #input_types_block
+                #hash_block
+                #metadata_block
+                pub const DEPRECATED: Option<&str> = #deprecated;
                 #dyn_result_fn_block
+                #warnings
+            }
+        }
+    }
+
+    /// Generate warnings about the function.
+    ///
+    /// Procedural macros cannot raise warnings directly, so each one is the use of a deprecated
+    /// item, with the warning as its note.
+    pub fn generate_warnings(&self) -> proc_macro2::TokenStream {
+        // Warnings are raised once for a generic function, not for each of its instances.
+        if self.instance.is_some() {
+            return proc_macro2::TokenStream::new();
+        }
+
+        // A sole alias identical to the function's own name changes nothing.
+        let note = match self.params.name {
+            Some(ref names)
+                if self.params.special == FnSpecialAccess::None
+                    && names.len() == 1
+                    && self.name() == &names[0] =>
+            {
+                format!(
+                    "redundant alias '{}': the function is already registered under its own name",
+                    names[0]
+                )
+            }
+            _ => return proc_macro2::TokenStream::new(),
+        };
+
+        let span = self
+            .params
+            .span
+            .unwrap_or_else(|| proc_macro2::Span::call_site());
+        quote_spanned! { span=>
+            const _: () = {
+                #[allow(non_camel_case_types)]
+                #[deprecated(note = #note)]
+                struct rhai_fn_warning;
+                let _ = rhai_fn_warning;
+            };
+        }
+    }
+
+    /// The type of the value returned by the function, `ty`, as used to convert it into a
+    /// `Dynamic`.
+    ///
+    /// With `option_as_unit`, an `Option` is treated as a type without special handling, so it
+    /// is kept whole as a custom value.
+    fn dynamic_return_type<'a>(&self, ty: Option<&'a syn::Type>) -> Option<&'a syn::Type> {
+        ty.filter(|ty| !(self.params.option_as_unit && generic_arg(ty, "Option").is_some()))
+    }
+
+    /// Generate an expression that turns the result of `call` into a
+    /// `Result<Dynamic, Box<EvalAltResult>>`.
+    fn generate_return(&self, call: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        let return_span = self
+            .return_type()
+            .map(|r| r.span())
+            .unwrap_or_else(|| proc_macro2::Span::call_site());
+
+        // Handle "raw returns", aka cases where the result is a dynamic or an error.
+        //
+        // This allows skipping the Dynamic::from wrap, except for the successful value of
+        // a known `Result<T, Box<EvalAltResult>>` that is not already a `Dynamic`.
+        if !self.params.return_raw {
+            let return_value =
+                return_to_dynamic(self.dynamic_return_type(self.return_type()), call);
+            quote_spanned! { return_span=>
+                Ok(#return_value)
+            }
+        } else if self.is_raw_indexer() {
+            quote_spanned! { return_span=>
+                #call.map(Dynamic::from)
+            }
+        } else {
+            match self.return_type().and_then(fallible_result_type) {
+                Some(item) if !is_type_named(item, "Dynamic") => {
+                    let value =
+                        return_to_dynamic(self.dynamic_return_type(Some(item)), quote! { v });
+                    quote! { #call.map(|v| #value) }
+                }
+                _ => quote_spanned! { return_span=>
+                    #call
+                },
             }
         }
     }
 
     pub fn generate_dynamic_fn(&self) -> proc_macro2::TokenStream {
         let name = self.name().clone();
+        let name = match self.instance {
+            Some(ref ty) => quote! { #name::<#ty> },
+            None => quote! { #name },
+        };
 
         let mut dynamic_signature = self.signature.clone();
         dynamic_signature.ident =
@@ -545,19 +1476,11 @@ impl ExportedFn {
             .return_type()
             .map(|r| r.span())
             .unwrap_or_else(|| proc_macro2::Span::call_site());
-        if !self.params.return_raw {
-            quote_spanned! { return_span=>
-                type EvalBox = Box<EvalAltResult>;
-                pub #dynamic_signature {
-                    Ok(Dynamic::from(super::#name(#(#arguments),*)))
-                }
-            }
-        } else {
-            quote_spanned! { return_span=>
-                type EvalBox = Box<EvalAltResult>;
-                pub #dynamic_signature {
-                    super::#name(#(#arguments),*)
-                }
+        let return_expr = self.generate_return(quote! { super::#name(#(#arguments),*) });
+        quote_spanned! { return_span=>
+            type EvalBox = Box<EvalAltResult>;
+            pub #dynamic_signature {
+                #return_expr
             }
         }
     }
@@ -588,22 +1511,97 @@ impl ExportedFn {
         }
     }
 
+    pub fn generate_hash(&self, on_type_name: &str) -> proc_macro2::TokenStream {
+        let input_types_fn_name: syn::Ident = syn::Ident::new(
+            format!("{}_input_types", on_type_name.to_lowercase()).as_str(),
+            self.name().span(),
+        );
+        let hash_fn_name: syn::Ident = syn::Ident::new(
+            format!("{}_hash", on_type_name.to_lowercase()).as_str(),
+            self.name().span(),
+        );
+        // The first name the function is registered under
+        let name = self.exported_names().remove(0);
+        quote! {
+            pub fn #hash_fn_name() -> u64 {
+                let input_types = #input_types_fn_name();
+                let num_args = if input_types.is_empty() {
+                    usize::MAX
+                } else {
+                    input_types.len()
+                };
+                calc_fn_hash(core::iter::empty(), #name, num_args, input_types.iter().cloned())
+            }
+        }
+    }
+
+    pub fn generate_metadata(&self, on_type_name: &str) -> proc_macro2::TokenStream {
+        let metadata_fn_name: syn::Ident = syn::Ident::new(
+            format!("{}_metadata", on_type_name.to_lowercase()).as_str(),
+            self.name().span(),
+        );
+        let names = self.exported_names();
+        let arg_count = self.arg_count();
+        let param_types: Vec<String> = self
+            .arg_list()
+            .map(|arg| match arg {
+                syn::FnArg::Receiver(syn::Receiver {
+                    reference: Some(_),
+                    mutability,
+                    ..
+                }) => format!(
+                    "&{}{}",
+                    if mutability.is_some() { "mut " } else { "" },
+                    type_to_string(self.arg_type(arg))
+                ),
+                _ => type_to_string(self.arg_type(arg)),
+            })
+            .collect();
+        let return_type = self
+            .return_type()
+            .map_or_else(|| "()".to_string(), type_to_string);
+        quote! {
+            pub fn #metadata_fn_name() -> FnMetadata {
+                FnMetadata {
+                    names: &[#(#names),*],
+                    arg_count: #arg_count,
+                    param_types: &[#(#param_types),*],
+                    return_type: #return_type,
+                }
+            }
+        }
+    }
+
     pub fn generate_impl(&self, on_type_name: &str) -> proc_macro2::TokenStream {
         let sig_name = self.name().clone();
+        let sig_name = match self.self_type {
+            Some(ref self_type) => quote! { <#self_type>::#sig_name },
+            None => quote! { #sig_name },
+        };
+        let call_name = match self.instance {
+            Some(ref ty) => quote! { #sig_name::<#ty> },
+            None => sig_name.clone(),
+        };
         let name = self.params.name.as_ref().map_or_else(
             || self.name().to_string(),
             |names| names.last().unwrap().clone(),
         );
 
         let arg_count = self.arg_count();
+        let fixed_arg_count = self.fixed_arg_count();
         let is_method_call = self.mutable_receiver();
+        let is_varadic = self.is_varadic();
+        let is_pure = self.params.pure;
 
         let mut unpack_stmts: Vec<syn::Stmt> = Vec::new();
+        let mut borrow_stmts: Vec<syn::Stmt> = Vec::new();
         let mut unpack_exprs: Vec<syn::Expr> = Vec::new();
         let mut input_type_exprs: Vec<syn::Expr> = Vec::new();
         let skip_first_arg;
 
-        // Handle the first argument separately if the function has a "method like" receiver
+        // Handle the first argument separately if the function has a "method like" receiver.
+        //
+        // Pure functions get a copy of it instead, so that it is only ever read-locked.
         if is_method_call {
             skip_first_arg = true;
             let first_arg = self.arg_list().next().unwrap();
@@ -614,8 +1612,13 @@ impl ExportedFn {
                         &syn::Type::Reference(syn::TypeReference { ref elem, .. }) => elem.as_ref(),
                         p => p,
                     };
-                    let downcast_span = quote_spanned!(
-                        arg_type.span()=> &mut args[0usize].write_lock::<#arg_type>().unwrap());
+                    let downcast_span = if is_pure {
+                        quote_spanned!(proc_macro2::Span::call_site().located_at(arg_type.span())=>
+                            &mut args[0usize].read_lock::<#arg_type>().unwrap().clone())
+                    } else {
+                        quote_spanned!(proc_macro2::Span::call_site().located_at(arg_type.span())=>
+                            &mut args[0usize].write_lock::<#arg_type>().unwrap())
+                    };
                     unpack_stmts.push(
                         syn::parse2::<syn::Stmt>(quote! {
                             let #var: &mut _ = #downcast_span;
@@ -629,7 +1632,23 @@ impl ExportedFn {
                         .unwrap(),
                     );
                 }
-                syn::FnArg::Receiver(_) => todo!("true self parameters not implemented yet"),
+                syn::FnArg::Receiver(_) => {
+                    let self_type = self.arg_type(first_arg);
+                    let downcast = if is_pure {
+                        quote! { &mut args[0usize].read_lock::<#self_type>().unwrap().clone() }
+                    } else {
+                        quote! { &mut args[0usize].write_lock::<#self_type>().unwrap() }
+                    };
+                    unpack_stmts.push(
+                        syn::parse2::<syn::Stmt>(quote! {
+                            let #var: &mut _ = #downcast;
+                        })
+                        .unwrap(),
+                    );
+                    input_type_exprs.push(
+                        syn::parse2::<syn::Expr>(quote! { TypeId::of::<#self_type>() }).unwrap(),
+                    );
+                }
             }
             unpack_exprs.push(syn::parse2::<syn::Expr>(quote! { #var }).unwrap());
         } else {
@@ -639,7 +1658,10 @@ impl ExportedFn {
         // Handle the rest of the arguments, which all are passed by value.
         //
         // The only exception is strings, which need to be downcast to ImmutableString to enable a
-        // zero-copy conversion to &str by reference, or a cloned String.
+        // zero-copy conversion to &str by reference, or a cloned String (also for Cow<str>).
+        //
+        // Shared references in `by_ref` functions are read-locked in place instead. In method
+        // calls, the first argument is split off first so that it can still be borrowed mutably.
         let str_type_path = syn::parse2::<syn::Path>(quote! { str }).unwrap();
         let string_type_path = syn::parse2::<syn::Path>(quote! { String }).unwrap();
         for (i, arg) in self.arg_list().enumerate().skip(skip_first_arg as usize) {
@@ -647,6 +1669,43 @@ impl ExportedFn {
             let is_string;
             let is_ref;
             match arg {
+                // The rest of the arguments are moved into a new list
+                syn::FnArg::Typed(_) if i == fixed_arg_count => {
+                    is_ref = true;
+                    unpack_stmts.push(
+                        syn::parse2::<syn::Stmt>(quote! {
+                            let #var: Vec<Dynamic> =
+                                args[#i..].iter_mut().map(|v| mem::take(*v)).collect();
+                        })
+                        .unwrap(),
+                    );
+                }
+                syn::FnArg::Typed(pattern) if shared_ref_type(pattern.ty.as_ref()).is_some() => {
+                    let arg_type = shared_ref_type(pattern.ty.as_ref()).unwrap();
+                    is_ref = true;
+                    if is_method_call {
+                        let index = i - 1;
+                        borrow_stmts.push(
+                            syn::parse2::<syn::Stmt>(quote! {
+                                let #var = args_rest[#index].read_lock::<#arg_type>().unwrap();
+                            })
+                            .unwrap(),
+                        );
+                    } else {
+                        borrow_stmts.push(
+                            syn::parse2::<syn::Stmt>(quote! {
+                                let #var = args[#i].read_lock::<#arg_type>().unwrap();
+                            })
+                            .unwrap(),
+                        );
+                    }
+                    input_type_exprs.push(
+                        syn::parse2::<syn::Expr>(quote_spanned!(
+                            arg_type.span()=> TypeId::of::<#arg_type>()
+                        ))
+                        .unwrap(),
+                    );
+                }
                 syn::FnArg::Typed(pattern) => {
                     let arg_type: &syn::Type = pattern.ty.as_ref();
                     let downcast_span = match flatten_type_groups(pattern.ty.as_ref()) {
@@ -658,7 +1717,7 @@ impl ExportedFn {
                             &syn::Type::Path(ref p) if p.path == str_type_path => {
                                 is_string = true;
                                 is_ref = true;
-                                quote_spanned!(arg_type.span()=>
+                                quote_spanned!(proc_macro2::Span::call_site().located_at(arg_type.span())=>
                                                mem::take(args[#i]).take_immutable_string().unwrap())
                             }
                             _ => panic!("internal error: why wasn't this found earlier!?"),
@@ -666,13 +1725,19 @@ impl ExportedFn {
                         &syn::Type::Path(ref p) if p.path == string_type_path => {
                             is_string = true;
                             is_ref = false;
-                            quote_spanned!(arg_type.span()=>
+                            quote_spanned!(proc_macro2::Span::call_site().located_at(arg_type.span())=>
                                            mem::take(args[#i]).take_string().unwrap())
                         }
+                        _ if is_cow_str_type(arg_type) => {
+                            is_string = true;
+                            is_ref = false;
+                            quote_spanned!(proc_macro2::Span::call_site().located_at(arg_type.span())=>
+                                           <#arg_type>::Owned(mem::take(args[#i]).take_string().unwrap()))
+                        }
                         _ => {
                             is_string = false;
                             is_ref = false;
-                            quote_spanned!(arg_type.span()=>
+                            quote_spanned!(proc_macro2::Span::call_site().located_at(arg_type.span())=>
                                            mem::take(args[#i]).cast::<#arg_type>())
                         }
                     };
@@ -699,7 +1764,20 @@ impl ExportedFn {
                         );
                     }
                 }
-                syn::FnArg::Receiver(_) => panic!("internal error: how did this happen!?"),
+                syn::FnArg::Receiver(syn::Receiver { ref reference, .. }) => {
+                    // Non-mutating receivers ('&self') take a copy of the value
+                    let self_type = self.arg_type(arg);
+                    is_ref = reference.is_some();
+                    unpack_stmts.push(
+                        syn::parse2::<syn::Stmt>(quote! {
+                            let #var = mem::take(args[#i]).cast::<#self_type>();
+                        })
+                        .unwrap(),
+                    );
+                    input_type_exprs.push(
+                        syn::parse2::<syn::Expr>(quote! { TypeId::of::<#self_type>() }).unwrap(),
+                    );
+                }
             }
             if !is_ref {
                 unpack_exprs.push(syn::parse2::<syn::Expr>(quote! { #var }).unwrap());
@@ -710,48 +1788,83 @@ impl ExportedFn {
 
         // In method calls, the first argument will need to be mutably borrowed. Because Rust marks
         // that as needing to borrow the entire array, all of the previous argument unpacking via
-        // clone needs to happen first.
+        // clone needs to happen first, followed by the read locks on the rest of the array.
         if is_method_call {
             let arg0 = unpack_stmts.remove(0);
+            if !borrow_stmts.is_empty() {
+                unpack_stmts.push(
+                    syn::parse2::<syn::Stmt>(quote! {
+                        let (args, args_rest) = args.split_at_mut(1usize);
+                    })
+                    .unwrap(),
+                );
+            }
+            unpack_stmts.extend(borrow_stmts);
             unpack_stmts.push(arg0);
+        } else {
+            unpack_stmts.extend(borrow_stmts);
         }
 
-        // Handle "raw returns", aka cases where the result is a dynamic or an error.
-        //
-        // This allows skipping the Dynamic::from wrap.
-        let return_span = self
-            .return_type()
-            .map(|r| r.span())
-            .unwrap_or_else(|| proc_macro2::Span::call_site());
-        let return_expr = if !self.params.return_raw {
-            quote_spanned! { return_span=>
-                Ok(Dynamic::from(#sig_name(#(#unpack_exprs),*)))
+        let return_expr = self.generate_return(quote! { #call_name(#(#unpack_exprs),*) });
+
+        let arg_count_check = if self.params.checked {
+            // Arity mismatches are possible when modules are built dynamically, so report them
+            // as a proper error instead of panicking on the argument indexing below.
+            let fn_name = sig_name.to_string();
+            let (mismatch, expected) = if is_varadic {
+                (quote! { args.len() < #fixed_arg_count }, fixed_arg_count)
+            } else {
+                (quote! { args.len() != #arg_count }, arg_count)
+            };
+            quote! {
+                if #mismatch {
+                    return EvalAltResult::ErrorFunctionNotFound(
+                        format!("{} with {} arguments (expecting {})", #fn_name, args.len(), #expected),
+                        Position::none(),
+                    )
+                    .into();
+                }
+            }
+        } else if is_varadic {
+            quote! {
+                debug_assert!(args.len() >= #fixed_arg_count,
+                              "wrong arg count: {} < {}",
+                              args.len(), #fixed_arg_count);
             }
         } else {
-            quote_spanned! { return_span=>
-                #sig_name(#(#unpack_exprs),*)
+            quote! {
+                debug_assert_eq!(args.len(), #arg_count,
+                                 "wrong arg count: {} != {}",
+                                 args.len(), #arg_count);
             }
         };
 
+        // Only deprecated functions override the default
+        let deprecated_fn = match self.params.deprecated {
+            Some(ref message) => quote! {
+                fn deprecated(&self) -> Option<&'static str> { Some(#message) }
+            },
+            None => proc_macro2::TokenStream::new(),
+        };
+
         let type_name = syn::Ident::new(on_type_name, proc_macro2::Span::call_site());
         quote! {
             impl PluginFunction for #type_name {
                 fn call(&self,
                         args: &mut [&mut Dynamic]
                 ) -> Result<Dynamic, Box<EvalAltResult>> {
-                    debug_assert_eq!(args.len(), #arg_count,
-                                     "wrong arg count: {} != {}",
-                                     args.len(), #arg_count);
+                    #arg_count_check
                     #(#unpack_stmts)*
                     #return_expr
                 }
 
                 fn is_method_call(&self) -> bool { #is_method_call }
-                fn is_varadic(&self) -> bool { false }
+                fn is_varadic(&self) -> bool { #is_varadic }
                 fn clone_boxed(&self) -> Box<dyn PluginFunction> { Box::new(#type_name()) }
                 fn input_types(&self) -> Box<[TypeId]> {
                     new_vec![#(#input_type_exprs),*].into_boxed_slice()
                 }
+                #deprecated_fn
             }
         }
     }
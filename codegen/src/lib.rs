@@ -97,6 +97,9 @@ use quote::quote;
 use syn::parse_macro_input;
 
 mod attrs;
+mod exported_consts;
+mod exported_enum;
+mod exported_type;
 mod function;
 mod module;
 mod register;
@@ -116,10 +119,13 @@ pub fn export_fn(
         Ok(args) => args,
         Err(err) => return proc_macro::TokenStream::from(err.to_compile_error()),
     };
-    let mut function_def = parse_macro_input!(input as function::ExportedFn);
-    if let Err(e) = function_def.set_params(parsed_params) {
-        return e.to_compile_error().into();
-    }
+    let item_fn = parse_macro_input!(input as syn::ItemFn);
+    let function_def = match function::ExportedFn::with_params(item_fn, parsed_params)
+        .and_then(|f| f.deny_receiver().map(|_| f))
+    {
+        Ok(f) => f,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
     output.extend(function_def.generate());
     proc_macro::TokenStream::from(output)
@@ -143,6 +149,55 @@ pub fn export_module(
     proc_macro::TokenStream::from(tokens)
 }
 
+#[proc_macro_attribute]
+pub fn export_type(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let parsed_params = match crate::attrs::outer_item_attributes(args.into(), "export_type") {
+        Ok(args) => args,
+        Err(err) => return proc_macro::TokenStream::from(err.to_compile_error()),
+    };
+    let mut type_def = parse_macro_input!(input as exported_type::ExportedType);
+    if let Err(e) = type_def.set_params(parsed_params) {
+        return e.to_compile_error().into();
+    }
+
+    let tokens = type_def.generate();
+    proc_macro::TokenStream::from(tokens)
+}
+
+#[proc_macro_attribute]
+pub fn export_consts(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    if let Some(arg) = proc_macro2::TokenStream::from(args).into_iter().next() {
+        return syn::Error::new(arg.span(), "#[export_consts] does not take any arguments")
+            .to_compile_error()
+            .into();
+    }
+
+    let consts_def = parse_macro_input!(input as exported_consts::ExportedConsts);
+    proc_macro::TokenStream::from(consts_def.generate())
+}
+
+#[proc_macro_derive(ExportEnum, attributes(rhai_enum))]
+pub fn export_enum(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let enum_def = parse_macro_input!(input as exported_enum::ExportedEnum);
+    proc_macro::TokenStream::from(enum_def.generate())
+}
+
+#[proc_macro]
+pub fn exported_type(type_path: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let type_path = parse_macro_input!(type_path as syn::Path);
+    let gen_mod_path = crate::exported_type::generated_type_module_path(&type_path);
+    let tokens = quote::quote! {
+        #gen_mod_path::rhai_module_generate()
+    };
+    proc_macro::TokenStream::from(tokens)
+}
+
 #[proc_macro]
 pub fn exported_module(module_path: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let module_path = parse_macro_input!(module_path as syn::Path);
@@ -174,7 +229,9 @@ pub fn register_exported_fn(args: proc_macro::TokenStream) -> proc_macro::TokenS
     };
     let gen_mod_path = crate::register::generated_module_path(&rust_modpath);
     let tokens = quote! {
-        #engine_expr.register_result_fn(&(#export_name), #gen_mod_path::dynamic_result_fn);
+        #engine_expr.register_plugin_fn(&(#export_name),
+                                        #gen_mod_path::token_input_types().as_ref(),
+                                        #gen_mod_path::token_callable());
     };
     proc_macro::TokenStream::from(tokens)
 }
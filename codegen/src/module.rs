@@ -53,7 +53,12 @@ impl ExportedParams for ExportedModParams {
         let mut skip = false;
         let mut scope = ExportScope::default();
         for attr in attrs {
-            let AttrItem { key, value, .. } = attr;
+            let AttrItem {
+                key, value, args, ..
+            } = attr;
+            if args.is_some() {
+                return Err(syn::Error::new(key.span(), "extraneous arguments"));
+            }
             match (key.to_string().as_ref(), value) {
                 ("name", Some(s)) => name = Some(s.value()),
                 ("name", None) => return Err(syn::Error::new(key.span(), "requires value")),
@@ -116,19 +121,13 @@ impl Parse for Module {
                     _ => None,
                 })
                 .try_fold(Vec::new(), |mut vec, itemfn| {
-                    // #[cfg] attributes are not allowed on functions
-                    crate::attrs::deny_cfg_attr(&itemfn.attrs)?;
-
                     let params: ExportedFnParams =
                         match crate::attrs::inner_item_attributes(&mut itemfn.attrs, "rhai_fn") {
                             Ok(p) => p,
                             Err(e) => return Err(e),
                         };
-                    syn::parse2::<ExportedFn>(itemfn.to_token_stream())
-                        .and_then(|mut f| {
-                            f.set_params(params)?;
-                            Ok(f)
-                        })
+                    ExportedFn::with_params(itemfn.clone(), params)
+                        .and_then(|f| f.deny_receiver().map(|_| f))
                         .map(|f| vec.push(f))
                         .map(|_| vec)
                 })?;
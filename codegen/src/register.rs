@@ -6,10 +6,18 @@ pub(crate) fn generated_module_path(
 ) -> syn::punctuated::Punctuated<syn::PathSegment, syn::Token![::]> {
     let mut g = fn_path.clone().segments;
     g.pop();
-    let ident = syn::Ident::new(
-        &format!("rhai_fn_{}", fn_path.segments.last().unwrap().ident),
-        fn_path.span(),
-    );
+    let last = fn_path.segments.last().unwrap();
+    // An instance of a generic function is picked by its type, e.g. `add::<i64>`
+    let name = match last.arguments {
+        syn::PathArguments::AngleBracketed(ref generics) => match generics.args.first() {
+            Some(syn::GenericArgument::Type(ty)) if generics.args.len() == 1 => {
+                format!("{}_{}", last.ident, crate::function::instance_suffix(ty))
+            }
+            _ => last.ident.to_string(),
+        },
+        _ => last.ident.to_string(),
+    };
+    let ident = syn::Ident::new(&format!("rhai_fn_{}", name), fn_path.span());
     g.push_value(syn::PathSegment {
         ident,
         arguments: syn::PathArguments::None,
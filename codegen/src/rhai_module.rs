@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use quote::{quote, ToTokens};
 
 use crate::attrs::ExportScope;
-use crate::function::ExportedFn;
+use crate::function::{is_cow_str_type, ExportedFn};
 use crate::module::Module;
 
 pub(crate) type ExportedConst = (String, Box<syn::Type>, syn::Expr);
@@ -15,6 +15,7 @@ pub(crate) fn generate_body(
     parent_scope: &ExportScope,
 ) -> proc_macro2::TokenStream {
     let mut set_fn_stmts: Vec<syn::Stmt> = Vec::new();
+    let mut metadata_stmts: Vec<syn::Stmt> = Vec::new();
     let mut set_const_stmts: Vec<syn::Stmt> = Vec::new();
     let mut add_mod_blocks: Vec<syn::ExprBlock> = Vec::new();
     let mut set_flattened_mod_blocks: Vec<syn::ExprBlock> = Vec::new();
@@ -67,6 +68,13 @@ pub(crate) fn generate_body(
         );
     }
 
+    // An alias identical to the function's own name is not redundant when it is needed to
+    // overload another function renamed to the same name
+    let mut name_counts = HashMap::<String, usize>::new();
+    for name in fns.iter().flat_map(|f| f.exported_names()) {
+        *name_counts.entry(name.value()).or_default() += 1;
+    }
+
     // NB: these are token streams, because reparsing messes up "> >" vs ">>"
     let mut gen_fn_tokens: Vec<proc_macro2::TokenStream> = Vec::new();
     for function in fns {
@@ -74,18 +82,29 @@ pub(crate) fn generate_body(
         if function.skipped() {
             continue;
         }
-        let fn_token_name = syn::Ident::new(
-            &format!("{}_token", function.name().to_string()),
-            function.name().span(),
-        );
         let reg_names = function.exported_names();
+        let cfg_attrs = function.cfg_attrs();
+
+        // A generic function is registered once for each type it is instantiated with
+        let instantiations = function.instantiations();
+        let instances: Vec<&ExportedFn> = if instantiations.is_empty() {
+            vec![&*function]
+        } else {
+            instantiations.iter().collect()
+        };
+
+        for function in instances {
+            let fn_token_name = syn::Ident::new(
+                &format!("{}_token", function.instance_name()),
+                function.name().span(),
+            );
 
-        let fn_input_types: Vec<syn::Expr> = function
-            .arg_list()
-            .map(|fnarg| match fnarg {
-                syn::FnArg::Receiver(_) => panic!("internal error: receiver fn outside impl!?"),
-                syn::FnArg::Typed(syn::PatType { ref ty, .. }) => {
-                    let arg_type = match flatten_type_groups(ty.as_ref()) {
+            let fn_input_types: Vec<syn::Expr> = function
+                .arg_list()
+                .take(function.fixed_arg_count())
+                .map(|fnarg| {
+                    let ty = function.arg_type(fnarg);
+                    let arg_type = match flatten_type_groups(ty) {
                         syn::Type::Reference(syn::TypeReference {
                             mutability: None,
                             ref elem,
@@ -96,13 +115,16 @@ pub(crate) fn generate_body(
                                 ImmutableString })
                                 .unwrap()
                             }
-                            _ => panic!("internal error: non-string shared reference!?"),
+                            _ => elem.as_ref().clone(),
                         },
                         syn::Type::Path(ref p) if p.path == string_type_path => {
                             syn::parse2::<syn::Type>(quote! {
                             ImmutableString })
                             .unwrap()
                         }
+                        t if is_cow_str_type(t) => syn::parse2::<syn::Type>(quote! {
+                        ImmutableString })
+                        .unwrap(),
                         syn::Type::Reference(syn::TypeReference {
                             mutability: Some(_),
                             ref elem,
@@ -118,27 +140,73 @@ pub(crate) fn generate_body(
                     syn::parse2::<syn::Expr>(quote! {
                     core::any::TypeId::of::<#arg_type>()})
                     .unwrap()
-                }
-            })
-            .collect();
+                })
+                .collect();
 
-        for fn_literal in reg_names {
-            set_fn_stmts.push(
+            for fn_literal in reg_names.iter() {
+                let set_fn_stmt = if function.is_global() {
+                    // Also callable without qualification when the module is imported
+                    quote! {
+                        #(#cfg_attrs)*
+                        {
+                            let hash = m.set_fn(#fn_literal, FnAccess::Public, &[#(#fn_input_types),*],
+                                                CallableFunction::from_plugin(#fn_token_name()));
+                            m.set_fn_global(hash);
+                        }
+                    }
+                } else {
+                    quote! {
+                        #(#cfg_attrs)*
+                        m.set_fn(#fn_literal, FnAccess::Public, &[#(#fn_input_types),*],
+                                 CallableFunction::from_plugin(#fn_token_name()));
+                    }
+                };
+                set_fn_stmts.push(syn::parse2::<syn::Stmt>(set_fn_stmt).unwrap());
+            }
+
+            let fn_metadata_name = syn::Ident::new(
+                &format!("{}_metadata", fn_token_name),
+                function.name().span(),
+            );
+            metadata_stmts.push(
                 syn::parse2::<syn::Stmt>(quote! {
-                    m.set_fn(#fn_literal, FnAccess::Public, &[#(#fn_input_types),*],
-                             CallableFunction::from_plugin(#fn_token_name()));
+                    #(#cfg_attrs)*
+                    metadata.push(#fn_metadata_name());
                 })
                 .unwrap(),
             );
+
+            // Each generated item is gated by the same #[cfg] attributes as the function
+            let gen_items = vec![
+                quote! {
+                    #[allow(non_camel_case_types)]
+                    struct #fn_token_name();
+                },
+                function.generate_impl(&fn_token_name.to_string()),
+                function.generate_callable(&fn_token_name.to_string()),
+                function.generate_input_types(&fn_token_name.to_string()),
+                function.generate_hash(&fn_token_name.to_string()),
+                function.generate_metadata(&fn_token_name.to_string()),
+            ];
+            gen_fn_tokens.extend(gen_items.into_iter().filter(|item| !item.is_empty()).map(
+                |item| {
+                    quote! {
+                        #(#cfg_attrs)*
+                        #item
+                    }
+                },
+            ));
         }
 
-        gen_fn_tokens.push(quote! {
-            #[allow(non_camel_case_types)]
-            struct #fn_token_name();
-        });
-        gen_fn_tokens.push(function.generate_impl(&fn_token_name.to_string()));
-        gen_fn_tokens.push(function.generate_callable(&fn_token_name.to_string()));
-        gen_fn_tokens.push(function.generate_input_types(&fn_token_name.to_string()));
+        if reg_names.iter().all(|n| name_counts[&n.value()] == 1) {
+            let warnings = function.generate_warnings();
+            if !warnings.is_empty() {
+                gen_fn_tokens.push(quote! {
+                    #(#cfg_attrs)*
+                    #warnings
+                });
+            }
+        }
     }
 
     let mut generate_fncall = syn::parse2::<syn::ItemMod>(quote! {
@@ -162,6 +230,12 @@ pub(crate) fn generate_body(
                     #(#add_mod_blocks)*
                 }
             }
+            #[allow(unused_mut)]
+            pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                let mut metadata = Vec::new();
+                #(#metadata_stmts)*
+                metadata
+            }
         }
     })
     .unwrap();
@@ -182,27 +256,23 @@ pub(crate) fn flatten_type_groups(ty: &syn::Type) -> &syn::Type {
     }
 }
 
-pub(crate) fn check_rename_collisions(fns: &Vec<ExportedFn>) -> Result<(), syn::Error> {
-    let mut renames = HashMap::<String, proc_macro2::Span>::new();
-    let mut names = HashMap::<String, proc_macro2::Span>::new();
+pub(crate) fn check_rename_collisions(fns: &[ExportedFn]) -> Result<(), syn::Error> {
+    // Functions under different #[cfg] attributes, e.g. one per platform, do not collide
+    let mut renames = HashMap::<(String, String), proc_macro2::Span>::new();
+    let mut names = HashMap::<(String, String), proc_macro2::Span>::new();
     for itemfn in fns.iter() {
+        let cfg_attrs = itemfn.cfg_attrs();
+        let cfg = quote! { #(#cfg_attrs)* }.to_string();
         if let Some(ref names) = itemfn.params().name {
             for name in names {
                 let current_span = itemfn.params().span.as_ref().unwrap();
                 let key = itemfn.arg_list().fold(name.clone(), |mut argstr, fnarg| {
-                    let type_string: String = match fnarg {
-                        syn::FnArg::Receiver(_) => {
-                            unimplemented!("receiver rhai_fns not implemented")
-                        }
-                        syn::FnArg::Typed(syn::PatType { ref ty, .. }) => {
-                            ty.as_ref().to_token_stream().to_string()
-                        }
-                    };
+                    let type_string: String = itemfn.arg_type(fnarg).to_token_stream().to_string();
                     argstr.push('.');
                     argstr.push_str(&type_string);
                     argstr
                 });
-                if let Some(other_span) = renames.insert(key, *current_span) {
+                if let Some(other_span) = renames.insert((cfg.clone(), key), *current_span) {
                     let mut err = syn::Error::new(
                         *current_span,
                         format!("duplicate Rhai signature for '{}'", &name),
@@ -216,7 +286,7 @@ pub(crate) fn check_rename_collisions(fns: &Vec<ExportedFn>) -> Result<(), syn::
             }
         } else {
             let ident = itemfn.name();
-            if let Some(other_span) = names.insert(ident.to_string(), ident.span()) {
+            if let Some(other_span) = names.insert((cfg, ident.to_string()), ident.span()) {
                 let mut err = syn::Error::new(
                     ident.span(),
                     format!("duplicate function '{}'", ident.to_string()),
@@ -229,9 +299,9 @@ pub(crate) fn check_rename_collisions(fns: &Vec<ExportedFn>) -> Result<(), syn::
             }
         }
     }
-    for (new_name, attr_span) in renames.drain() {
+    for ((cfg, new_name), attr_span) in renames.drain() {
         let new_name = new_name.split('.').next().unwrap();
-        if let Some(fn_span) = names.get(new_name) {
+        if let Some(fn_span) = names.get(&(cfg, new_name.to_string())) {
             let mut err = syn::Error::new(
                 attr_span,
                 format!("duplicate Rhai signature for '{}'", &new_name),
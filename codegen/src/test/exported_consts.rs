@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod exported_consts_tests {
+    use crate::exported_consts::ExportedConsts;
+
+    use proc_macro2::TokenStream;
+    use quote::quote;
+
+    #[test]
+    fn scalar_consts() {
+        let input_tokens: TokenStream = quote! {
+            pub mod limits {
+                use rhai::INT;
+
+                pub const MAX: INT = 100;
+                pub const NAME: &str = "limits";
+                const HIDDEN: bool = true;
+            }
+        };
+
+        let item_mod = syn::parse2::<ExportedConsts>(input_tokens).unwrap();
+        assert_eq!(item_mod.module_name().to_string(), "limits");
+        assert_eq!(
+            item_mod
+                .consts()
+                .iter()
+                .map(|c| c.name().to_string())
+                .collect::<Vec<_>>(),
+            vec!["MAX", "NAME"]
+        );
+        assert_eq!(
+            item_mod.consts()[0].conversion().to_string(),
+            quote! { Dynamic::from::<INT>(MAX) }.to_string()
+        );
+        assert_eq!(
+            item_mod.consts()[1].conversion().to_string(),
+            quote! { Dynamic::from(ImmutableString::from(NAME)) }.to_string()
+        );
+    }
+
+    #[test]
+    fn array_and_map_consts() {
+        let input_tokens: TokenStream = quote! {
+            pub mod limits {
+                pub const PORTS: [i64; 2] = [80, 443];
+                pub const LEVELS: &[(&str, f64)] = &[("low", 0.1), ("high", 0.9)];
+            }
+        };
+
+        let item_mod = syn::parse2::<ExportedConsts>(input_tokens).unwrap();
+        assert_eq!(
+            item_mod.consts()[0].conversion().to_string(),
+            quote! {
+                Dynamic::from(
+                    PORTS
+                        .iter()
+                        .cloned()
+                        .map(|v| Dynamic::from::<i64>(v))
+                        .collect::<Array>()
+                )
+            }
+            .to_string()
+        );
+        assert_eq!(
+            item_mod.consts()[1].conversion().to_string(),
+            quote! {
+                Dynamic::from(
+                    LEVELS
+                        .iter()
+                        .cloned()
+                        .map(|(k, v)| (ImmutableString::from(k), Dynamic::from::<f64>(v)))
+                        .collect::<Map>()
+                )
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn custom_type_const() {
+        let input_tokens: TokenStream = quote! {
+            pub mod limits {
+                pub const ORIGIN: Point = Point { x: 0, y: 0 };
+            }
+        };
+
+        let item_mod = syn::parse2::<ExportedConsts>(input_tokens).unwrap();
+        assert_eq!(
+            item_mod.consts()[0].conversion().to_string(),
+            quote! { Dynamic::from::<Point>(ORIGIN) }.to_string()
+        );
+    }
+
+    #[test]
+    fn unsupported_const_type() {
+        let input_tokens: TokenStream = quote! {
+            pub mod limits {
+                pub const ORIGIN: (i64, i64) = (0, 0);
+            }
+        };
+
+        let err = syn::parse2::<ExportedConsts>(input_tokens).unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "type cannot be converted into a Rhai constant \
+             (expected a named type, &str, or an array or slice of these)"
+        );
+    }
+
+    #[test]
+    fn non_const_item() {
+        let input_tokens: TokenStream = quote! {
+            pub mod limits {
+                pub const MAX: i64 = 100;
+                pub fn max() -> i64 { MAX }
+            }
+        };
+
+        let err = syn::parse2::<ExportedConsts>(input_tokens).unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "only constants can be exported with #[export_consts]"
+        );
+    }
+}
@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod exported_enum_tests {
+    use crate::exported_enum::ExportedEnum;
+
+    use proc_macro2::TokenStream;
+    use quote::quote;
+
+    #[test]
+    fn fieldless_enum() {
+        let input_tokens: TokenStream = quote! {
+            #[derive(Clone)]
+            pub enum TrafficLight {
+                Red,
+                Amber,
+                Green,
+            }
+        };
+
+        let item_enum = syn::parse2::<ExportedEnum>(input_tokens).unwrap();
+        assert_eq!(item_enum.type_name().to_string(), "TrafficLight");
+        assert_eq!(item_enum.exported_name(), "TrafficLight");
+        assert_eq!(item_enum.parse_fn_name(), "parse_traffic_light");
+        assert_eq!(
+            item_enum
+                .variants()
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>(),
+            vec!["Red", "Amber", "Green"]
+        );
+    }
+
+    #[test]
+    fn renamed_enum() {
+        let input_tokens: TokenStream = quote! {
+            #[rhai_enum(name = "HTTPMethod", case_insensitive)]
+            pub enum Method {
+                Get,
+                Post,
+            }
+        };
+
+        let item_enum = syn::parse2::<ExportedEnum>(input_tokens).unwrap();
+        assert_eq!(item_enum.exported_name(), "HTTPMethod");
+        assert_eq!(item_enum.parse_fn_name(), "parse_http_method");
+    }
+
+    #[test]
+    fn enum_with_fields() {
+        let input_tokens: TokenStream = quote! {
+            pub enum Shape {
+                Dot,
+                Circle(f64),
+            }
+        };
+
+        let err = syn::parse2::<ExportedEnum>(input_tokens).unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "only fieldless enum variants can be exported to Rhai"
+        );
+    }
+
+    #[test]
+    fn struct_not_enum() {
+        let input_tokens: TokenStream = quote! {
+            pub struct Point {
+                x: i64,
+            }
+        };
+
+        let err = syn::parse2::<ExportedEnum>(input_tokens).unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "only enums can be exported to Rhai with this derive"
+        );
+    }
+}
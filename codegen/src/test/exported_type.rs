@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod exported_type_tests {
+    use crate::exported_type::ExportedType;
+
+    use proc_macro2::TokenStream;
+    use quote::quote;
+
+    #[test]
+    fn methods_and_constructors() {
+        let input_tokens: TokenStream = quote! {
+            impl Point {
+                pub fn new(x: INT, y: INT) -> Self {
+                    Point { x, y }
+                }
+                pub fn move_by(&mut self, dx: INT) {
+                    self.x += dx;
+                }
+                pub fn get_x(&self) -> INT {
+                    self.x
+                }
+            }
+        };
+
+        let item_type = syn::parse2::<ExportedType>(input_tokens).unwrap();
+        assert_eq!(item_type.type_name().to_string(), "Point");
+        assert_eq!(item_type.exported_name(), "Point");
+
+        assert_eq!(item_type.constructors().len(), 1);
+        assert_eq!(item_type.constructors()[0].name().to_string(), "new");
+        assert_eq!(
+            item_type.constructors()[0].return_type().unwrap(),
+            &syn::parse2::<syn::Type>(quote! { Point }).unwrap()
+        );
+
+        assert_eq!(item_type.methods().len(), 2);
+        assert_eq!(item_type.methods()[0].name().to_string(), "move_by");
+        assert!(item_type.methods()[0].mutable_receiver());
+        assert_eq!(item_type.methods()[1].name().to_string(), "get_x");
+        assert!(!item_type.methods()[1].mutable_receiver());
+    }
+
+    #[test]
+    fn self_type_in_args() {
+        let input_tokens: TokenStream = quote! {
+            impl Point {
+                pub fn dist(&self, other: Self) -> INT {
+                    (self.x - other.x).abs()
+                }
+            }
+        };
+
+        let item_type = syn::parse2::<ExportedType>(input_tokens).unwrap();
+        let method = &item_type.methods()[0];
+        let mut args = method.arg_list();
+        assert_eq!(
+            method.arg_type(args.next().unwrap()),
+            &syn::parse2::<syn::Type>(quote! { Point }).unwrap()
+        );
+        assert_eq!(
+            method.arg_type(args.next().unwrap()),
+            &syn::parse2::<syn::Type>(quote! { Point }).unwrap()
+        );
+    }
+
+    #[test]
+    fn self_by_value_rejected() {
+        let input_tokens: TokenStream = quote! {
+            impl Point {
+                pub fn into_x(self) -> INT {
+                    self.x
+                }
+            }
+        };
+
+        let err = syn::parse2::<ExportedType>(input_tokens).unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "'self' cannot be passed by value from Rhai; use '&self' or '&mut self' instead"
+        );
+    }
+
+    #[test]
+    fn rhai_fn_attributes_stripped() {
+        let input_tokens: TokenStream = quote! {
+            impl Point {
+                #[rhai_fn(name = "create")]
+                pub fn new() -> Self {
+                    Point { x: 0, y: 0 }
+                }
+                #[rhai_fn(skip)]
+                pub fn reset(&mut self) {
+                    self.x = 0;
+                }
+            }
+        };
+
+        let item_type = syn::parse2::<ExportedType>(input_tokens).unwrap();
+        assert_eq!(item_type.constructors()[0].exported_name(), "create");
+        assert!(item_type.methods()[0].skipped());
+        assert!(!item_type.generate().to_string().contains("rhai_fn ("));
+    }
+
+    #[test]
+    fn trait_impl_rejected() {
+        let input_tokens: TokenStream = quote! {
+            impl Clone for Point {
+                fn clone(&self) -> Self {
+                    Point { x: self.x, y: self.y }
+                }
+            }
+        };
+
+        let err = syn::parse2::<ExportedType>(input_tokens).unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "cannot export trait implementations to Rhai"
+        );
+    }
+}
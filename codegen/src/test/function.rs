@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod function_tests {
-    use crate::function::ExportedFn;
+    use crate::function::{ExportedFn, ExportedFnParams};
 
     use proc_macro2::TokenStream;
     use quote::quote;
@@ -19,6 +19,31 @@ mod function_tests {
         assert_eq!(item_fn.arg_list().count(), 0);
     }
 
+    #[test]
+    fn documented_fn() {
+        let input_tokens: TokenStream = quote! {
+            /// Does nothing.
+            ///
+            /// Really.
+            #[inline]
+            pub fn do_nothing() { }
+        };
+
+        let item_fn = syn::parse2::<ExportedFn>(input_tokens).unwrap();
+        let docs: Vec<_> = item_fn
+            .doc_attrs()
+            .iter()
+            .map(|a| match a.parse_meta().unwrap() {
+                syn::Meta::NameValue(syn::MetaNameValue {
+                    lit: syn::Lit::Str(s),
+                    ..
+                }) => s.value(),
+                _ => panic!("doc comment is not a string"),
+            })
+            .collect();
+        assert_eq!(docs, [" Does nothing.", "", " Really."]);
+    }
+
     #[test]
     fn one_arg_fn() {
         let input_tokens: TokenStream = quote! {
@@ -91,6 +116,32 @@ mod function_tests {
         assert_eq!(format!("{}", err), "cannot return a reference to Rhai");
     }
 
+    #[test]
+    #[cfg(not(feature = "no_object"))]
+    fn non_string_keyed_map_returning_fn() {
+        let input_tokens: TokenStream = quote! {
+            pub fn get_scores() -> HashMap<INT, INT> { HashMap::new() }
+        };
+
+        let err = syn::parse2::<ExportedFn>(input_tokens).unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "object map keys returned to Rhai must be String"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "no_object")]
+    fn map_returning_fn_no_object() {
+        let input_tokens: TokenStream = quote! {
+            pub fn get_scores() -> HashMap<INT, INT> { HashMap::new() }
+        };
+
+        let item_fn: ExportedFn = syn::parse2(input_tokens).unwrap();
+        let output = item_fn.generate().to_string();
+        assert!(!output.contains("< Map >"));
+    }
+
     #[test]
     fn ptr_returning_fn() {
         let input_tokens: TokenStream = quote! {
@@ -110,7 +161,55 @@ mod function_tests {
         let err = syn::parse2::<ExportedFn>(input_tokens).unwrap_err();
         assert_eq!(
             format!("{}", err),
-            "references from Rhai in this position must be mutable"
+            "cannot pass '&Person' from Rhai; use '&mut Person' instead, or take 'Person' by value\n\
+                help: '&str' is the only shared reference allowed, \
+                unless the function has the 'by_ref' attribute"
+        );
+    }
+
+    #[test]
+    fn duplicate_alias_fn() {
+        let err =
+            syn::parse2::<ExportedFnParams>(quote! { name = "foo", name = "bar", name = "foo" })
+                .unwrap_err();
+        assert_eq!(format!("{}", err), "alias 'foo' is already declared");
+    }
+
+    #[test]
+    fn redundant_alias_fn() {
+        let input_tokens: TokenStream = quote! {
+            pub fn foo(x: usize) -> usize { x }
+        };
+
+        let params = syn::parse2::<ExportedFnParams>(quote! { name = "foo" }).unwrap();
+        let item_fn = ExportedFn::with_params(
+            syn::parse2::<syn::ItemFn>(input_tokens.clone()).unwrap(),
+            params,
+        )
+        .unwrap();
+        assert!(item_fn
+            .generate_warnings()
+            .to_string()
+            .contains("redundant alias 'foo'"));
+
+        // Not redundant if the function is also registered as a getter
+        let params = syn::parse2::<ExportedFnParams>(quote! { name = "foo", get = "foo" }).unwrap();
+        let item_fn =
+            ExportedFn::with_params(syn::parse2::<syn::ItemFn>(input_tokens).unwrap(), params)
+                .unwrap();
+        assert!(item_fn.generate_warnings().is_empty());
+    }
+
+    #[test]
+    fn mut_box_arg_fn() {
+        let input_tokens: TokenStream = quote! {
+            pub fn greet(who: &mut Box<Person>) { }
+        };
+
+        let err = syn::parse2::<ExportedFn>(input_tokens).unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "smart-pointer receivers are not supported; use '&mut Person' instead of '&mut Box<Person>'"
         );
     }
 
@@ -123,10 +222,25 @@ mod function_tests {
         let err = syn::parse2::<ExportedFn>(input_tokens).unwrap_err();
         assert_eq!(
             format!("{}", err),
-            "this type in this position passes from Rhai by value"
+            "cannot pass '&Person' from Rhai in this position; take 'Person' by value instead\n\
+                help: '&str' is the only shared reference allowed, \
+                unless the function has the 'by_ref' attribute"
         );
     }
 
+    #[test]
+    fn ref_generic_second_arg_fn() {
+        let input_tokens: TokenStream = quote! {
+            pub fn greet(count: usize, who: &Vec<std::rc::Rc<Person> >) { }
+        };
+
+        let err = syn::parse2::<ExportedFn>(input_tokens).unwrap_err();
+        assert!(format!("{}", err).starts_with(
+            "cannot pass '&Vec<std::rc::Rc<Person>>' from Rhai in this position; \
+                take 'Vec<std::rc::Rc<Person>>' by value instead"
+        ));
+    }
+
     #[test]
     fn mut_ref_second_arg_fn() {
         let input_tokens: TokenStream = quote! {
@@ -136,7 +250,47 @@ mod function_tests {
         let err = syn::parse2::<ExportedFn>(input_tokens).unwrap_err();
         assert_eq!(
             format!("{}", err),
-            "this type in this position passes from Rhai by value"
+            "only the first argument can be passed from Rhai by mutable reference; \
+                take 'Person' by value instead"
+        );
+    }
+
+    #[test]
+    fn ref_arg_by_ref_fn() {
+        let input_tokens: TokenStream = quote! {
+            pub fn greet(count: usize, who: &Person) { }
+        };
+        let params = ExportedFnParams {
+            by_ref: true,
+            ..Default::default()
+        };
+
+        let item_fn =
+            ExportedFn::with_params(syn::parse2::<syn::ItemFn>(input_tokens).unwrap(), params)
+                .unwrap();
+        assert_eq!(&item_fn.name().to_string(), "greet");
+        assert_eq!(item_fn.arg_list().count(), 2);
+        assert!(!item_fn.mutable_receiver());
+        assert!(item_fn.params().by_ref);
+    }
+
+    #[test]
+    fn mut_ref_second_arg_by_ref_fn() {
+        let input_tokens: TokenStream = quote! {
+            pub fn give(who: &Person, into: &mut Person) { }
+        };
+        let params = ExportedFnParams {
+            by_ref: true,
+            ..Default::default()
+        };
+
+        let err =
+            ExportedFn::with_params(syn::parse2::<syn::ItemFn>(input_tokens).unwrap(), params)
+                .unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "only the first argument can be passed from Rhai by mutable reference; \
+                take 'Person' by value instead"
         );
     }
 
@@ -218,16 +372,76 @@ mod function_tests {
 
         let item_fn = syn::parse2::<ExportedFn>(input_tokens).unwrap();
         assert_eq!(&item_fn.name().to_string(), "act_upon");
-        assert!(item_fn.mutable_receiver());
+        assert!(!item_fn.mutable_receiver());
         assert!(item_fn.is_public());
         assert!(item_fn.return_type().is_none());
         assert_eq!(item_fn.arg_list().count(), 1);
     }
+
+    #[test]
+    fn varadic_not_last_fn() {
+        let input_tokens: TokenStream = quote! {
+            pub fn sum(rest: &[Dynamic], last: usize) -> usize { }
+        };
+
+        let err = syn::parse2::<ExportedFn>(input_tokens).unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "only the last parameter can take the rest of the arguments"
+        );
+    }
+
+    #[test]
+    fn receiver_fn_outside_impl() {
+        let input_tokens: TokenStream = quote! {
+            pub fn act_upon(&mut self) { }
+        };
+
+        let item_fn = syn::parse2::<ExportedFn>(input_tokens).unwrap();
+        let err = item_fn.deny_receiver().unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "methods with 'self' can only be exported from an #[export_type] impl block"
+        );
+    }
+
+    #[test]
+    fn self_by_value_fn() {
+        let input_tokens: TokenStream = quote! {
+            pub fn act_upon(self) { }
+        };
+
+        let item_fn = syn::parse2::<ExportedFn>(input_tokens).unwrap();
+        let err = item_fn.deny_receiver().unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "'self' cannot be passed by value from Rhai"
+        );
+    }
+
+    #[test]
+    fn pure_fn_without_mut_arg() {
+        let input_tokens: TokenStream = quote! {
+            pub fn get_age(person: Person) -> usize { person.age }
+        };
+        let params = ExportedFnParams {
+            pure: true,
+            ..Default::default()
+        };
+
+        let err =
+            ExportedFn::with_params(syn::parse2::<syn::ItemFn>(input_tokens).unwrap(), params)
+                .unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "pure functions must take their first argument by mutable reference"
+        );
+    }
 }
 
 #[cfg(test)]
 mod generate_tests {
-    use crate::function::ExportedFn;
+    use crate::function::{ExportedFn, ExportedFnParams};
 
     use proc_macro2::TokenStream;
     use quote::quote;
@@ -295,6 +509,92 @@ mod generate_tests {
                 pub fn token_input_types() -> Box<[TypeId]> {
                     Token().input_types()
                 }
+                pub fn token_hash() -> u64 {
+                    let input_types = token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "do_nothing", num_args, input_types.iter().cloned())
+                }
+                pub fn token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["do_nothing"],
+                        arg_count: 0usize,
+                        param_types: &[],
+                        return_type: "()",
+                    }
+                }
+                pub const DEPRECATED: Option<&str> = None;
+                type EvalBox = Box<EvalAltResult>;
+                pub fn dynamic_result_fn() -> Result<Dynamic, EvalBox> {
+                    Ok(Dynamic::from(super::do_nothing()))
+                }
+            }
+        };
+
+        let item_fn = syn::parse2::<ExportedFn>(input_tokens).unwrap();
+        assert_streams_eq(item_fn.generate(), expected_tokens);
+    }
+
+    #[test]
+    fn documented_fn() {
+        let input_tokens: TokenStream = quote! {
+            /// Does nothing.
+            ///
+            /// Really.
+            pub fn do_nothing() { }
+        };
+
+        let expected_tokens = quote! {
+            /// Does nothing.
+            ///
+            /// Really.
+            #[allow(unused)]
+            pub mod rhai_fn_do_nothing {
+                use super::*;
+                struct Token();
+                impl PluginFunction for Token {
+                    fn call(&self,
+                            args: &mut [&mut Dynamic]
+                    ) -> Result<Dynamic, Box<EvalAltResult>> {
+                        debug_assert_eq!(args.len(), 0usize,
+                                         "wrong arg count: {} != {}", args.len(), 0usize);
+                        Ok(Dynamic::from(do_nothing()))
+                    }
+
+                    fn is_method_call(&self) -> bool { false }
+                    fn is_varadic(&self) -> bool { false }
+                    fn clone_boxed(&self) -> Box<dyn PluginFunction> { Box::new(Token()) }
+                    fn input_types(&self) -> Box<[TypeId]> {
+                        new_vec![].into_boxed_slice()
+                    }
+                }
+                pub fn token_callable() -> CallableFunction {
+                    CallableFunction::from_plugin(Token())
+                }
+                pub fn token_input_types() -> Box<[TypeId]> {
+                    Token().input_types()
+                }
+                pub fn token_hash() -> u64 {
+                    let input_types = token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "do_nothing", num_args, input_types.iter().cloned())
+                }
+                pub fn token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["do_nothing"],
+                        arg_count: 0usize,
+                        param_types: &[],
+                        return_type: "()",
+                    }
+                }
+                pub const DEPRECATED: Option<&str> = None;
                 type EvalBox = Box<EvalAltResult>;
                 pub fn dynamic_result_fn() -> Result<Dynamic, EvalBox> {
                     Ok(Dynamic::from(super::do_nothing()))
@@ -340,6 +640,24 @@ mod generate_tests {
                 pub fn token_input_types() -> Box<[TypeId]> {
                     Token().input_types()
                 }
+                pub fn token_hash() -> u64 {
+                    let input_types = token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "do_something", num_args, input_types.iter().cloned())
+                }
+                pub fn token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["do_something"],
+                        arg_count: 1usize,
+                        param_types: &["usize"],
+                        return_type: "()",
+                    }
+                }
+                pub const DEPRECATED: Option<&str> = None;
                 type EvalBox = Box<EvalAltResult>;
                 pub fn dynamic_result_fn(x: usize) -> Result<Dynamic, EvalBox> {
                     Ok(Dynamic::from(super::do_something(x)))
@@ -351,6 +669,103 @@ mod generate_tests {
         assert_streams_eq(item_fn.generate(), expected_tokens);
     }
 
+    #[test]
+    fn varadic_fn_impl() {
+        let input_tokens: TokenStream = quote! {
+            pub fn sum(first: usize, rest: &[Dynamic]) -> usize { }
+        };
+
+        let expected_tokens = quote! {
+            impl PluginFunction for MyType {
+                fn call(&self,
+                        args: &mut [&mut Dynamic]
+                ) -> Result<Dynamic, Box<EvalAltResult>> {
+                    debug_assert!(args.len() >= 1usize,
+                                "wrong arg count: {} < {}", args.len(), 1usize);
+                    let arg0 = mem::take(args[0usize]).cast::<usize>();
+                    let arg1: Vec<Dynamic> =
+                        args[1usize..].iter_mut().map(|v| mem::take(*v)).collect();
+                    Ok(Dynamic::from(sum(arg0, &arg1)))
+                }
+
+                fn is_method_call(&self) -> bool { false }
+                fn is_varadic(&self) -> bool { true }
+                fn clone_boxed(&self) -> Box<dyn PluginFunction> { Box::new(MyType()) }
+                fn input_types(&self) -> Box<[TypeId]> {
+                    new_vec![TypeId::of::<usize>()].into_boxed_slice()
+                }
+            }
+        };
+
+        let item_fn = syn::parse2::<ExportedFn>(input_tokens).unwrap();
+        assert!(item_fn.is_varadic());
+        assert_eq!(item_fn.fixed_arg_count(), 1);
+        assert_streams_eq(item_fn.generate_impl("MyType"), expected_tokens);
+    }
+
+    #[test]
+    fn string_returning_fn() {
+        let input_tokens: TokenStream = quote! {
+            pub fn shout(s: &str) -> String { s.to_uppercase() }
+        };
+
+        let expected_tokens = quote! {
+            #[allow(unused)]
+            pub mod rhai_fn_shout {
+                use super::*;
+                struct Token();
+                impl PluginFunction for Token {
+                    fn call(&self,
+                            args: &mut [&mut Dynamic]
+                    ) -> Result<Dynamic, Box<EvalAltResult>> {
+                        debug_assert_eq!(args.len(), 1usize,
+                                    "wrong arg count: {} != {}", args.len(), 1usize);
+                        let arg0 = mem::take(args[0usize]).take_immutable_string().unwrap();
+                        Ok(Dynamic::from(ImmutableString::from(shout(&arg0))))
+                    }
+
+                    fn is_method_call(&self) -> bool { false }
+                    fn is_varadic(&self) -> bool { false }
+                    fn clone_boxed(&self) -> Box<dyn PluginFunction> { Box::new(Token()) }
+                    fn input_types(&self) -> Box<[TypeId]> {
+                        new_vec![TypeId::of::<ImmutableString>()].into_boxed_slice()
+                    }
+                }
+                pub fn token_callable() -> CallableFunction {
+                    CallableFunction::from_plugin(Token())
+                }
+                pub fn token_input_types() -> Box<[TypeId]> {
+                    Token().input_types()
+                }
+                pub fn token_hash() -> u64 {
+                    let input_types = token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "shout", num_args, input_types.iter().cloned())
+                }
+                pub fn token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["shout"],
+                        arg_count: 1usize,
+                        param_types: &["&str"],
+                        return_type: "String",
+                    }
+                }
+                pub const DEPRECATED: Option<&str> = None;
+                type EvalBox = Box<EvalAltResult>;
+                pub fn dynamic_result_fn(s: &str) -> Result<Dynamic, EvalBox> {
+                    Ok(Dynamic::from(ImmutableString::from(super::shout(s))))
+                }
+            }
+        };
+
+        let item_fn = syn::parse2::<ExportedFn>(input_tokens).unwrap();
+        assert_streams_eq(item_fn.generate(), expected_tokens);
+    }
+
     #[test]
     fn one_arg_usize_fn_impl() {
         let input_tokens: TokenStream = quote! {
@@ -417,6 +832,24 @@ mod generate_tests {
                 pub fn token_input_types() -> Box<[TypeId]> {
                     Token().input_types()
                 }
+                pub fn token_hash() -> u64 {
+                    let input_types = token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "add_together", num_args, input_types.iter().cloned())
+                }
+                pub fn token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["add_together"],
+                        arg_count: 2usize,
+                        param_types: &["usize", "usize"],
+                        return_type: "usize",
+                    }
+                }
+                pub const DEPRECATED: Option<&str> = None;
                 type EvalBox = Box<EvalAltResult>;
                 pub fn dynamic_result_fn(x: usize, y: usize) -> Result<Dynamic, EvalBox> {
                     Ok(Dynamic::from(super::add_together(x, y)))
@@ -464,6 +897,24 @@ mod generate_tests {
                 pub fn token_input_types() -> Box<[TypeId]> {
                     Token().input_types()
                 }
+                pub fn token_hash() -> u64 {
+                    let input_types = token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "increment", num_args, input_types.iter().cloned())
+                }
+                pub fn token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["increment"],
+                        arg_count: 2usize,
+                        param_types: &["&mut usize", "usize"],
+                        return_type: "()",
+                    }
+                }
+                pub const DEPRECATED: Option<&str> = None;
                 type EvalBox = Box<EvalAltResult>;
                 pub fn dynamic_result_fn(x: &mut usize, y: usize) -> Result<Dynamic, EvalBox> {
                     Ok(Dynamic::from(super::increment(x, y)))
@@ -476,6 +927,268 @@ mod generate_tests {
         assert_streams_eq(item_fn.generate(), expected_tokens);
     }
 
+    #[test]
+    fn ref_arg_by_ref_fn() {
+        let input_tokens: TokenStream = quote! {
+            pub fn add_to(x: &mut usize, y: &usize) { *x += *y; }
+        };
+        let params = ExportedFnParams {
+            by_ref: true,
+            ..Default::default()
+        };
+
+        let expected_tokens = quote! {
+            #[allow(unused)]
+            pub mod rhai_fn_add_to {
+                use super::*;
+                struct Token();
+                impl PluginFunction for Token {
+                    fn call(&self,
+                            args: &mut [&mut Dynamic]
+                    ) -> Result<Dynamic, Box<EvalAltResult>> {
+                        debug_assert_eq!(args.len(), 2usize,
+                                    "wrong arg count: {} != {}", args.len(), 2usize);
+                        let (args, args_rest) = args.split_at_mut(1usize);
+                        let arg1 = args_rest[0usize].read_lock::<usize>().unwrap();
+                        let arg0: &mut _ = &mut args[0usize].write_lock::<usize>().unwrap();
+                        Ok(Dynamic::from(add_to(arg0, &arg1)))
+                    }
+
+                    fn is_method_call(&self) -> bool { true }
+                    fn is_varadic(&self) -> bool { false }
+                    fn clone_boxed(&self) -> Box<dyn PluginFunction> { Box::new(Token()) }
+                    fn input_types(&self) -> Box<[TypeId]> {
+                        new_vec![TypeId::of::<usize>(),
+                             TypeId::of::<usize>()].into_boxed_slice()
+                    }
+                }
+                pub fn token_callable() -> CallableFunction {
+                    CallableFunction::from_plugin(Token())
+                }
+                pub fn token_input_types() -> Box<[TypeId]> {
+                    Token().input_types()
+                }
+                pub fn token_hash() -> u64 {
+                    let input_types = token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "add_to", num_args, input_types.iter().cloned())
+                }
+                pub fn token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["add_to"],
+                        arg_count: 2usize,
+                        param_types: &["&mut usize", "&usize"],
+                        return_type: "()",
+                    }
+                }
+                pub const DEPRECATED: Option<&str> = None;
+                type EvalBox = Box<EvalAltResult>;
+                pub fn dynamic_result_fn(x: &mut usize, y: &usize) -> Result<Dynamic, EvalBox> {
+                    Ok(Dynamic::from(super::add_to(x, y)))
+                }
+            }
+        };
+
+        let item_fn =
+            ExportedFn::with_params(syn::parse2::<syn::ItemFn>(input_tokens).unwrap(), params)
+                .unwrap();
+        assert!(item_fn.mutable_receiver());
+        assert_streams_eq(item_fn.generate(), expected_tokens);
+    }
+
+    #[test]
+    fn fallible_fn() {
+        let input_tokens: TokenStream = quote! {
+            pub fn half(x: usize) -> Result<usize, Box<EvalAltResult>> { Ok(x / 2) }
+        };
+
+        let expected_tokens = quote! {
+            #[allow(unused)]
+            pub mod rhai_fn_half {
+                use super::*;
+                struct Token();
+                impl PluginFunction for Token {
+                    fn call(&self,
+                            args: &mut [&mut Dynamic]
+                    ) -> Result<Dynamic, Box<EvalAltResult>> {
+                        debug_assert_eq!(args.len(), 1usize,
+                                    "wrong arg count: {} != {}", args.len(), 1usize);
+                        let arg0 = mem::take(args[0usize]).cast::<usize>();
+                        half(arg0).map(|v| Dynamic::from(v))
+                    }
+
+                    fn is_method_call(&self) -> bool { false }
+                    fn is_varadic(&self) -> bool { false }
+                    fn clone_boxed(&self) -> Box<dyn PluginFunction> { Box::new(Token()) }
+                    fn input_types(&self) -> Box<[TypeId]> {
+                        new_vec![TypeId::of::<usize>()].into_boxed_slice()
+                    }
+                }
+                pub fn token_callable() -> CallableFunction {
+                    CallableFunction::from_plugin(Token())
+                }
+                pub fn token_input_types() -> Box<[TypeId]> {
+                    Token().input_types()
+                }
+                pub fn token_hash() -> u64 {
+                    let input_types = token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "half", num_args, input_types.iter().cloned())
+                }
+                pub fn token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["half"],
+                        arg_count: 1usize,
+                        param_types: &["usize"],
+                        return_type: "Result<usize, Box<EvalAltResult>>",
+                    }
+                }
+                pub const DEPRECATED: Option<&str> = None;
+                type EvalBox = Box<EvalAltResult>;
+                pub fn dynamic_result_fn(x: usize) -> Result<Dynamic, EvalBox> {
+                    super::half(x).map(|v| Dynamic::from(v))
+                }
+            }
+        };
+
+        let item_fn = syn::parse2::<ExportedFn>(input_tokens).unwrap();
+        assert!(item_fn.params().return_raw);
+        assert_streams_eq(item_fn.generate(), expected_tokens);
+    }
+
+    #[test]
+    fn other_result_fn() {
+        let input_tokens: TokenStream = quote! {
+            pub fn parse(s: String) -> Result<usize, ParseIntError> { s.parse() }
+        };
+
+        let item_fn = syn::parse2::<ExportedFn>(input_tokens).unwrap();
+        assert!(!item_fn.params().return_raw);
+    }
+
+    #[test]
+    fn pure_mut_arg_fn() {
+        let input_tokens: TokenStream = quote! {
+            pub fn peek(x: &mut usize, y: usize) -> usize { *x + y }
+        };
+        let params = ExportedFnParams {
+            pure: true,
+            ..Default::default()
+        };
+
+        let expected_tokens = quote! {
+            #[allow(unused)]
+            pub mod rhai_fn_peek {
+                use super::*;
+                struct Token();
+                impl PluginFunction for Token {
+                    fn call(&self,
+                            args: &mut [&mut Dynamic]
+                    ) -> Result<Dynamic, Box<EvalAltResult>> {
+                        debug_assert_eq!(args.len(), 2usize,
+                                    "wrong arg count: {} != {}", args.len(), 2usize);
+                        let arg1 = mem::take(args[1usize]).cast::<usize>();
+                        let arg0: &mut _ = &mut args[0usize].read_lock::<usize>().unwrap().clone();
+                        Ok(Dynamic::from(peek(arg0, arg1)))
+                    }
+
+                    fn is_method_call(&self) -> bool { true }
+                    fn is_varadic(&self) -> bool { false }
+                    fn clone_boxed(&self) -> Box<dyn PluginFunction> { Box::new(Token()) }
+                    fn input_types(&self) -> Box<[TypeId]> {
+                        new_vec![TypeId::of::<usize>(),
+                             TypeId::of::<usize>()].into_boxed_slice()
+                    }
+                }
+                pub fn token_callable() -> CallableFunction {
+                    CallableFunction::from_plugin(Token())
+                }
+                pub fn token_input_types() -> Box<[TypeId]> {
+                    Token().input_types()
+                }
+                pub fn token_hash() -> u64 {
+                    let input_types = token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "peek", num_args, input_types.iter().cloned())
+                }
+                pub fn token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["peek"],
+                        arg_count: 2usize,
+                        param_types: &["&mut usize", "usize"],
+                        return_type: "usize",
+                    }
+                }
+                pub const DEPRECATED: Option<&str> = None;
+                type EvalBox = Box<EvalAltResult>;
+                pub fn dynamic_result_fn(x: &mut usize, y: usize) -> Result<Dynamic, EvalBox> {
+                    Ok(Dynamic::from(super::peek(x, y)))
+                }
+            }
+        };
+
+        let item_fn =
+            ExportedFn::with_params(syn::parse2::<syn::ItemFn>(input_tokens).unwrap(), params)
+                .unwrap();
+        assert!(item_fn.mutable_receiver());
+        assert_streams_eq(item_fn.generate(), expected_tokens);
+    }
+
+    #[test]
+    fn checked_fn() {
+        let input_tokens: TokenStream = quote! {
+            pub fn add(x: usize, y: usize) -> usize { x + y }
+        };
+        let params = ExportedFnParams {
+            checked: true,
+            ..Default::default()
+        };
+
+        let expected_tokens = quote! {
+            impl PluginFunction for Token {
+                fn call(&self,
+                        args: &mut [&mut Dynamic]
+                ) -> Result<Dynamic, Box<EvalAltResult>> {
+                    if args.len() != 2usize {
+                        return EvalAltResult::ErrorFunctionNotFound(
+                            format!("{} with {} arguments (expecting {})", "add", args.len(), 2usize),
+                            Position::none(),
+                        )
+                        .into();
+                    }
+                    let arg0 = mem::take(args[0usize]).cast::<usize>();
+                    let arg1 = mem::take(args[1usize]).cast::<usize>();
+                    Ok(Dynamic::from(add(arg0, arg1)))
+                }
+
+                fn is_method_call(&self) -> bool { false }
+                fn is_varadic(&self) -> bool { false }
+                fn clone_boxed(&self) -> Box<dyn PluginFunction> { Box::new(Token()) }
+                fn input_types(&self) -> Box<[TypeId]> {
+                    new_vec![TypeId::of::<usize>(),
+                         TypeId::of::<usize>()].into_boxed_slice()
+                }
+            }
+        };
+
+        let item_fn =
+            ExportedFn::with_params(syn::parse2::<syn::ItemFn>(input_tokens).unwrap(), params)
+                .unwrap();
+        assert_streams_eq(item_fn.generate_impl("Token"), expected_tokens);
+    }
+
     #[test]
     fn str_arg_fn() {
         let input_tokens: TokenStream = quote! {
@@ -510,6 +1223,24 @@ mod generate_tests {
                 pub fn token_input_types() -> Box<[TypeId]> {
                     Token().input_types()
                 }
+                pub fn token_hash() -> u64 {
+                    let input_types = token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "special_print", num_args, input_types.iter().cloned())
+                }
+                pub fn token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["special_print"],
+                        arg_count: 1usize,
+                        param_types: &["&str"],
+                        return_type: "()",
+                    }
+                }
+                pub const DEPRECATED: Option<&str> = None;
                 type EvalBox = Box<EvalAltResult>;
                 pub fn dynamic_result_fn(message: &str) -> Result<Dynamic, EvalBox> {
                     Ok(Dynamic::from(super::special_print(message)))
@@ -521,4 +1252,34 @@ mod generate_tests {
         assert!(!item_fn.mutable_receiver());
         assert_streams_eq(item_fn.generate(), expected_tokens);
     }
+
+    #[test]
+    fn cow_str_arg_fn() {
+        let input_tokens: TokenStream = quote! {
+            pub fn greet(name: Cow<'static, str>) -> usize { name.len() }
+        };
+
+        let expected_tokens = quote! {
+            impl PluginFunction for Token {
+                fn call(&self,
+                        args: &mut [&mut Dynamic]
+                ) -> Result<Dynamic, Box<EvalAltResult>> {
+                    debug_assert_eq!(args.len(), 1usize,
+                                "wrong arg count: {} != {}", args.len(), 1usize);
+                    let arg0 = <Cow<'static, str> >::Owned(mem::take(args[0usize]).take_string().unwrap());
+                    Ok(Dynamic::from(greet(arg0)))
+                }
+
+                fn is_method_call(&self) -> bool { false }
+                fn is_varadic(&self) -> bool { false }
+                fn clone_boxed(&self) -> Box<dyn PluginFunction> { Box::new(Token()) }
+                fn input_types(&self) -> Box<[TypeId]> {
+                    new_vec![TypeId::of::<ImmutableString>()].into_boxed_slice()
+                }
+            }
+        };
+
+        let item_fn = syn::parse2::<ExportedFn>(input_tokens).unwrap();
+        assert_streams_eq(item_fn.generate_impl("Token"), expected_tokens);
+    }
 }
@@ -1,2 +1,5 @@
+mod exported_consts;
+mod exported_enum;
+mod exported_type;
 mod function;
 mod module;
@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod module_tests {
     use crate::module::Module;
+    use crate::rhai_module::check_rename_collisions;
 
     use proc_macro2::TokenStream;
     use quote::quote;
@@ -204,6 +205,89 @@ mod module_tests {
         assert!(item_mod.fns().is_empty());
         assert!(item_mod.consts().is_empty());
     }
+
+    #[test]
+    fn operator_renamed_fn_module() {
+        let input_tokens: TokenStream = quote! {
+            pub mod ops {
+                #[rhai_fn(name = "+", name = "+=", name = "<<=")]
+                pub fn add_together(x: INT, y: INT) -> INT {
+                    x + y
+                }
+            }
+        };
+
+        let item_mod = syn::parse2::<Module>(input_tokens).unwrap();
+        assert_eq!(item_mod.fns().len(), 1);
+        assert_eq!(
+            item_mod.fns()[0].params().name,
+            Some(vec!["+".to_string(), "+=".to_string(), "<<=".to_string()])
+        );
+    }
+
+    #[test]
+    fn bad_operator_renamed_fn_module() {
+        let input_tokens: TokenStream = quote! {
+            pub mod ops {
+                #[rhai_fn(name = "=+")]
+                pub fn add_together(x: INT, y: INT) -> INT {
+                    x + y
+                }
+            }
+        };
+
+        let err = syn::parse2::<Module>(input_tokens).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "'=+' is not a valid Rhai function name or operator; did you mean '+='?"
+        );
+
+        let input_tokens: TokenStream = quote! {
+            pub mod ops {
+                #[rhai_fn(name = "+-*")]
+                pub fn add_together(x: INT, y: INT) -> INT {
+                    x + y
+                }
+            }
+        };
+
+        let err = syn::parse2::<Module>(input_tokens).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "'+-*' is not a valid Rhai function name or operator"
+        );
+    }
+
+    #[test]
+    fn cfg_fns_module() {
+        let input_tokens: TokenStream = quote! {
+            pub mod platform {
+                #[cfg(windows)]
+                pub fn path_separator() -> char { '\\' }
+                #[cfg(not(windows))]
+                pub fn path_separator() -> char { '/' }
+            }
+        };
+
+        let item_mod = syn::parse2::<Module>(input_tokens).unwrap();
+        assert_eq!(item_mod.fns().len(), 2);
+        assert_eq!(item_mod.fns()[0].cfg_attrs().len(), 1);
+        assert_eq!(item_mod.fns()[1].cfg_attrs().len(), 1);
+        assert!(check_rename_collisions(item_mod.fns()).is_ok());
+
+        let input_tokens: TokenStream = quote! {
+            pub mod platform {
+                #[cfg(windows)]
+                pub fn path_separator() -> char { '\\' }
+                #[cfg(windows)]
+                pub fn path_separator() -> char { '/' }
+            }
+        };
+
+        let item_mod = syn::parse2::<Module>(input_tokens).unwrap();
+        let err = check_rename_collisions(item_mod.fns()).unwrap_err();
+        assert_eq!(err.to_string(), "duplicate function 'path_separator'");
+    }
 }
 
 #[cfg(test)]
@@ -263,6 +347,11 @@ mod generate_tests {
                 pub fn rhai_generate_into_module(m: &mut Module, flatten: bool) {
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata
+                }
             }
         };
 
@@ -299,6 +388,12 @@ mod generate_tests {
                              CallableFunction::from_plugin(get_mystic_number_token()));
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata.push(get_mystic_number_token_metadata());
+                    metadata
+                }
                 #[allow(non_camel_case_types)]
                 struct get_mystic_number_token();
                 impl PluginFunction for get_mystic_number_token {
@@ -325,6 +420,118 @@ mod generate_tests {
                 pub fn get_mystic_number_token_input_types() -> Box<[TypeId]> {
                     get_mystic_number_token().input_types()
                 }
+                pub fn get_mystic_number_token_hash() -> u64 {
+                    let input_types = get_mystic_number_token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "get_mystic_number", num_args, input_types.iter().cloned())
+                }
+                pub fn get_mystic_number_token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["get_mystic_number"],
+                        arg_count: 0usize,
+                        param_types: &[],
+                        return_type: "INT",
+                    }
+                }
+            }
+        };
+
+        let item_mod = syn::parse2::<Module>(input_tokens).unwrap();
+        assert_streams_eq(item_mod.generate(), expected_tokens);
+    }
+
+    #[test]
+    fn one_cfg_fn_module() {
+        let input_tokens: TokenStream = quote! {
+            pub mod one_fn {
+                #[cfg(feature = "extra")]
+                pub fn get_mystic_number() -> INT {
+                    42
+                }
+            }
+        };
+
+        let expected_tokens = quote! {
+            pub mod one_fn {
+                #[cfg(feature = "extra")]
+                pub fn get_mystic_number() -> INT {
+                    42
+                }
+                #[allow(unused_imports)]
+                use super::*;
+
+                pub fn rhai_module_generate() -> Module {
+                    let mut m = Module::new();
+                    rhai_generate_into_module(&mut m, false);
+                    m
+                }
+                #[allow(unused_mut)]
+                pub fn rhai_generate_into_module(m: &mut Module, flatten: bool) {
+                    #[cfg(feature = "extra")]
+                    m.set_fn("get_mystic_number", FnAccess::Public, &[],
+                             CallableFunction::from_plugin(get_mystic_number_token()));
+                    if flatten {} else {}
+                }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    #[cfg(feature = "extra")]
+                    metadata.push(get_mystic_number_token_metadata());
+                    metadata
+                }
+                #[cfg(feature = "extra")]
+                #[allow(non_camel_case_types)]
+                struct get_mystic_number_token();
+                #[cfg(feature = "extra")]
+                impl PluginFunction for get_mystic_number_token {
+                    fn call(&self,
+                            args: &mut [&mut Dynamic]
+                    ) -> Result<Dynamic, Box<EvalAltResult>> {
+                        debug_assert_eq!(args.len(), 0usize,
+                                            "wrong arg count: {} != {}", args.len(), 0usize);
+                        Ok(Dynamic::from(get_mystic_number()))
+                    }
+
+                    fn is_method_call(&self) -> bool { false }
+                    fn is_varadic(&self) -> bool { false }
+                    fn clone_boxed(&self) -> Box<dyn PluginFunction> {
+                        Box::new(get_mystic_number_token())
+                    }
+                    fn input_types(&self) -> Box<[TypeId]> {
+                        new_vec![].into_boxed_slice()
+                    }
+                }
+                #[cfg(feature = "extra")]
+                pub fn get_mystic_number_token_callable() -> CallableFunction {
+                    CallableFunction::from_plugin(get_mystic_number_token())
+                }
+                #[cfg(feature = "extra")]
+                pub fn get_mystic_number_token_input_types() -> Box<[TypeId]> {
+                    get_mystic_number_token().input_types()
+                }
+                #[cfg(feature = "extra")]
+                pub fn get_mystic_number_token_hash() -> u64 {
+                    let input_types = get_mystic_number_token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "get_mystic_number", num_args, input_types.iter().cloned())
+                }
+                #[cfg(feature = "extra")]
+                pub fn get_mystic_number_token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["get_mystic_number"],
+                        arg_count: 0usize,
+                        param_types: &[],
+                        return_type: "INT",
+                    }
+                }
             }
         };
 
@@ -361,6 +568,12 @@ mod generate_tests {
                              CallableFunction::from_plugin(add_one_to_token()));
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata.push(add_one_to_token_metadata());
+                    metadata
+                }
                 #[allow(non_camel_case_types)]
                 struct add_one_to_token();
                 impl PluginFunction for add_one_to_token {
@@ -388,6 +601,23 @@ mod generate_tests {
                 pub fn add_one_to_token_input_types() -> Box<[TypeId]> {
                     add_one_to_token().input_types()
                 }
+                pub fn add_one_to_token_hash() -> u64 {
+                    let input_types = add_one_to_token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "add_one_to", num_args, input_types.iter().cloned())
+                }
+                pub fn add_one_to_token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["add_one_to"],
+                        arg_count: 1usize,
+                        param_types: &["INT"],
+                        return_type: "INT",
+                    }
+                }
             }
         };
 
@@ -438,6 +668,13 @@ mod generate_tests {
                              CallableFunction::from_plugin(add_n_to_token()));
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata.push(add_one_to_token_metadata());
+                    metadata.push(add_n_to_token_metadata());
+                    metadata
+                }
                 #[allow(non_camel_case_types)]
                 struct add_one_to_token();
                 impl PluginFunction for add_one_to_token {
@@ -465,6 +702,23 @@ mod generate_tests {
                 pub fn add_one_to_token_input_types() -> Box<[TypeId]> {
                     add_one_to_token().input_types()
                 }
+                pub fn add_one_to_token_hash() -> u64 {
+                    let input_types = add_one_to_token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "add_n", num_args, input_types.iter().cloned())
+                }
+                pub fn add_one_to_token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["add_n"],
+                        arg_count: 1usize,
+                        param_types: &["INT"],
+                        return_type: "INT",
+                    }
+                }
 
                 #[allow(non_camel_case_types)]
                 struct add_n_to_token();
@@ -495,6 +749,23 @@ mod generate_tests {
                 pub fn add_n_to_token_input_types() -> Box<[TypeId]> {
                     add_n_to_token().input_types()
                 }
+                pub fn add_n_to_token_hash() -> u64 {
+                    let input_types = add_n_to_token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "add_n", num_args, input_types.iter().cloned())
+                }
+                pub fn add_n_to_token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["add_n"],
+                        arg_count: 2usize,
+                        param_types: &["INT", "INT"],
+                        return_type: "INT",
+                    }
+                }
             }
         };
 
@@ -532,6 +803,12 @@ mod generate_tests {
                              CallableFunction::from_plugin(add_together_token()));
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata.push(add_together_token_metadata());
+                    metadata
+                }
                 #[allow(non_camel_case_types)]
                 struct add_together_token();
                 impl PluginFunction for add_together_token {
@@ -561,6 +838,23 @@ mod generate_tests {
                 pub fn add_together_token_input_types() -> Box<[TypeId]> {
                     add_together_token().input_types()
                 }
+                pub fn add_together_token_hash() -> u64 {
+                    let input_types = add_together_token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "add_together", num_args, input_types.iter().cloned())
+                }
+                pub fn add_together_token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["add_together"],
+                        arg_count: 2usize,
+                        param_types: &["INT", "INT"],
+                        return_type: "INT",
+                    }
+                }
             }
         };
 
@@ -605,6 +899,12 @@ mod generate_tests {
                              CallableFunction::from_plugin(add_together_token()));
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata.push(add_together_token_metadata());
+                    metadata
+                }
                 #[allow(non_camel_case_types)]
                 struct add_together_token();
                 impl PluginFunction for add_together_token {
@@ -634,6 +934,23 @@ mod generate_tests {
                 pub fn add_together_token_input_types() -> Box<[TypeId]> {
                     add_together_token().input_types()
                 }
+                pub fn add_together_token_hash() -> u64 {
+                    let input_types = add_together_token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "add", num_args, input_types.iter().cloned())
+                }
+                pub fn add_together_token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["add", "+", "add_together"],
+                        arg_count: 2usize,
+                        param_types: &["INT", "INT"],
+                        return_type: "INT",
+                    }
+                }
             }
         };
 
@@ -671,6 +988,11 @@ mod generate_tests {
                     m.set_var("MYSTIC_NUMBER", MYSTIC_NUMBER);
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata
+                }
             }
         };
 
@@ -702,6 +1024,11 @@ mod generate_tests {
                     m.set_var("MYSTIC_NUMBER", MYSTIC_NUMBER);
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata
+                }
             }
         };
 
@@ -735,6 +1062,11 @@ mod generate_tests {
                     m.set_var("MYSTIC_NUMBER", MYSTIC_NUMBER);
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata
+                }
             }
         };
 
@@ -769,6 +1101,11 @@ mod generate_tests {
                 pub fn rhai_generate_into_module(m: &mut Module, flatten: bool) {
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata
+                }
             }
         };
 
@@ -804,6 +1141,11 @@ mod generate_tests {
                 pub fn rhai_generate_into_module(m: &mut Module, flatten: bool) {
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata
+                }
             }
         };
 
@@ -847,6 +1189,12 @@ mod generate_tests {
                              CallableFunction::from_plugin(get_mystic_number_token()));
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata.push(get_mystic_number_token_metadata());
+                    metadata
+                }
                 #[allow(non_camel_case_types)]
                 struct get_mystic_number_token();
                 impl PluginFunction for get_mystic_number_token {
@@ -873,6 +1221,23 @@ mod generate_tests {
                 pub fn get_mystic_number_token_input_types() -> Box<[TypeId]> {
                     get_mystic_number_token().input_types()
                 }
+                pub fn get_mystic_number_token_hash() -> u64 {
+                    let input_types = get_mystic_number_token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "get_mystic_number", num_args, input_types.iter().cloned())
+                }
+                pub fn get_mystic_number_token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["get_mystic_number"],
+                        arg_count: 0usize,
+                        param_types: &[],
+                        return_type: "INT",
+                    }
+                }
             }
         };
 
@@ -903,6 +1268,11 @@ mod generate_tests {
                 pub fn rhai_generate_into_module(m: &mut Module, flatten: bool) {
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata
+                }
             }
         };
 
@@ -940,6 +1310,12 @@ mod generate_tests {
                              CallableFunction::from_plugin(print_out_to_token()));
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata.push(print_out_to_token_metadata());
+                    metadata
+                }
                 #[allow(non_camel_case_types)]
                 struct print_out_to_token();
                 impl PluginFunction for print_out_to_token {
@@ -967,6 +1343,23 @@ mod generate_tests {
                 pub fn print_out_to_token_input_types() -> Box<[TypeId]> {
                     print_out_to_token().input_types()
                 }
+                pub fn print_out_to_token_hash() -> u64 {
+                    let input_types = print_out_to_token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "print_out_to", num_args, input_types.iter().cloned())
+                }
+                pub fn print_out_to_token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["print_out_to"],
+                        arg_count: 1usize,
+                        param_types: &["&str"],
+                        return_type: "()",
+                    }
+                }
             }
         };
 
@@ -1004,6 +1397,12 @@ mod generate_tests {
                              CallableFunction::from_plugin(print_out_to_token()));
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata.push(print_out_to_token_metadata());
+                    metadata
+                }
                 #[allow(non_camel_case_types)]
                 struct print_out_to_token();
                 impl PluginFunction for print_out_to_token {
@@ -1031,6 +1430,23 @@ mod generate_tests {
                 pub fn print_out_to_token_input_types() -> Box<[TypeId]> {
                     print_out_to_token().input_types()
                 }
+                pub fn print_out_to_token_hash() -> u64 {
+                    let input_types = print_out_to_token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "print_out_to", num_args, input_types.iter().cloned())
+                }
+                pub fn print_out_to_token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["print_out_to"],
+                        arg_count: 1usize,
+                        param_types: &["String"],
+                        return_type: "()",
+                    }
+                }
             }
         };
 
@@ -1068,6 +1484,12 @@ mod generate_tests {
                              CallableFunction::from_plugin(increment_token()));
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata.push(increment_token_metadata());
+                    metadata
+                }
                 #[allow(non_camel_case_types)]
                 struct increment_token();
                 impl PluginFunction for increment_token {
@@ -1095,6 +1517,23 @@ mod generate_tests {
                 pub fn increment_token_input_types() -> Box<[TypeId]> {
                     increment_token().input_types()
                 }
+                pub fn increment_token_hash() -> u64 {
+                    let input_types = increment_token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "increment", num_args, input_types.iter().cloned())
+                }
+                pub fn increment_token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["increment"],
+                        arg_count: 1usize,
+                        param_types: &["&mut FLOAT"],
+                        return_type: "()",
+                    }
+                }
             }
         };
 
@@ -1135,6 +1574,12 @@ mod generate_tests {
                                  CallableFunction::from_plugin(increment_token()));
                         if flatten {} else {}
                     }
+                    #[allow(unused_mut)]
+                    pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                        let mut metadata = Vec::new();
+                        metadata.push(increment_token_metadata());
+                        metadata
+                    }
                     #[allow(non_camel_case_types)]
                     struct increment_token();
                     impl PluginFunction for increment_token {
@@ -1162,6 +1607,23 @@ mod generate_tests {
                     pub fn increment_token_input_types() -> Box<[TypeId]> {
                         increment_token().input_types()
                     }
+                    pub fn increment_token_hash() -> u64 {
+                        let input_types = increment_token_input_types();
+                        let num_args = if input_types.is_empty() {
+                            usize::MAX
+                        } else {
+                            input_types.len()
+                        };
+                        calc_fn_hash(core::iter::empty(), "increment", num_args, input_types.iter().cloned())
+                    }
+                    pub fn increment_token_metadata() -> FnMetadata {
+                        FnMetadata {
+                            names: &["increment"],
+                            arg_count: 1usize,
+                            param_types: &["&mut FLOAT"],
+                            return_type: "()",
+                        }
+                    }
                 }
                 #[allow(unused_imports)]
                 use super::*;
@@ -1179,6 +1641,11 @@ mod generate_tests {
                         { m.set_sub_module("it_is", self::it_is::rhai_module_generate()); }
                     }
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata
+                }
             }
         };
 
@@ -1222,6 +1689,12 @@ mod generate_tests {
                                  CallableFunction::from_plugin(increment_token()));
                         if flatten {} else {}
                     }
+                    #[allow(unused_mut)]
+                    pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                        let mut metadata = Vec::new();
+                        metadata.push(increment_token_metadata());
+                        metadata
+                    }
                     #[allow(non_camel_case_types)]
                     struct increment_token();
                     impl PluginFunction for increment_token {
@@ -1249,6 +1722,23 @@ mod generate_tests {
                     pub fn increment_token_input_types() -> Box<[TypeId]> {
                         increment_token().input_types()
                     }
+                    pub fn increment_token_hash() -> u64 {
+                        let input_types = increment_token_input_types();
+                        let num_args = if input_types.is_empty() {
+                            usize::MAX
+                        } else {
+                            input_types.len()
+                        };
+                        calc_fn_hash(core::iter::empty(), "increment", num_args, input_types.iter().cloned())
+                    }
+                    pub fn increment_token_metadata() -> FnMetadata {
+                        FnMetadata {
+                            names: &["increment"],
+                            arg_count: 1usize,
+                            param_types: &["&mut FLOAT"],
+                            return_type: "()",
+                        }
+                    }
                 }
                 #[allow(unused_imports)]
                 use super::*;
@@ -1270,6 +1760,11 @@ mod generate_tests {
                         }
                     }
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata
+                }
             }
         };
 
@@ -1307,6 +1802,12 @@ mod generate_tests {
                              CallableFunction::from_plugin(int_foo_token()));
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata.push(int_foo_token_metadata());
+                    metadata
+                }
                 #[allow(non_camel_case_types)]
                 struct int_foo_token();
                 impl PluginFunction for int_foo_token {
@@ -1334,6 +1835,23 @@ mod generate_tests {
                 pub fn int_foo_token_input_types() -> Box<[TypeId]> {
                     int_foo_token().input_types()
                 }
+                pub fn int_foo_token_hash() -> u64 {
+                    let input_types = int_foo_token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "get$square", num_args, input_types.iter().cloned())
+                }
+                pub fn int_foo_token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["get$square"],
+                        arg_count: 1usize,
+                        param_types: &["&mut u64"],
+                        return_type: "u64",
+                    }
+                }
             }
         };
 
@@ -1373,6 +1891,12 @@ mod generate_tests {
                              CallableFunction::from_plugin(int_foo_token()));
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata.push(int_foo_token_metadata());
+                    metadata
+                }
                 #[allow(non_camel_case_types)]
                 struct int_foo_token();
                 impl PluginFunction for int_foo_token {
@@ -1400,6 +1924,112 @@ mod generate_tests {
                 pub fn int_foo_token_input_types() -> Box<[TypeId]> {
                     int_foo_token().input_types()
                 }
+                pub fn int_foo_token_hash() -> u64 {
+                    let input_types = int_foo_token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "square", num_args, input_types.iter().cloned())
+                }
+                pub fn int_foo_token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["square", "get$square"],
+                        arg_count: 1usize,
+                        param_types: &["&mut u64"],
+                        return_type: "u64",
+                    }
+                }
+            }
+        };
+
+        let item_mod = syn::parse2::<Module>(input_tokens).unwrap();
+        assert_streams_eq(item_mod.generate(), expected_tokens);
+    }
+
+    #[test]
+    fn one_getter_two_names_fn_module() {
+        let input_tokens: TokenStream = quote! {
+            pub mod one_fn {
+                #[rhai_fn(get = "square", get = "sq")]
+                pub fn int_foo(x: &mut u64) -> u64 {
+                    (*x) * (*x)
+                }
+            }
+        };
+
+        let expected_tokens = quote! {
+            pub mod one_fn {
+                pub fn int_foo(x: &mut u64) -> u64 {
+                    (*x) * (*x)
+                }
+                #[allow(unused_imports)]
+                use super::*;
+
+                pub fn rhai_module_generate() -> Module {
+                    let mut m = Module::new();
+                    rhai_generate_into_module(&mut m, false);
+                    m
+                }
+                #[allow(unused_mut)]
+                pub fn rhai_generate_into_module(m: &mut Module, flatten: bool) {
+                    m.set_fn("get$square", FnAccess::Public, &[core::any::TypeId::of::<u64>()],
+                             CallableFunction::from_plugin(int_foo_token()));
+                    m.set_fn("get$sq", FnAccess::Public, &[core::any::TypeId::of::<u64>()],
+                             CallableFunction::from_plugin(int_foo_token()));
+                    if flatten {} else {}
+                }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata.push(int_foo_token_metadata());
+                    metadata
+                }
+                #[allow(non_camel_case_types)]
+                struct int_foo_token();
+                impl PluginFunction for int_foo_token {
+                    fn call(&self,
+                            args: &mut [&mut Dynamic]
+                    ) -> Result<Dynamic, Box<EvalAltResult>> {
+                        debug_assert_eq!(args.len(), 1usize,
+                                            "wrong arg count: {} != {}", args.len(), 1usize);
+                        let arg0: &mut _ = &mut args[0usize].write_lock::<u64>().unwrap();
+                        Ok(Dynamic::from(int_foo(arg0)))
+                    }
+
+                    fn is_method_call(&self) -> bool { true }
+                    fn is_varadic(&self) -> bool { false }
+                    fn clone_boxed(&self) -> Box<dyn PluginFunction> {
+                        Box::new(int_foo_token())
+                    }
+                    fn input_types(&self) -> Box<[TypeId]> {
+                        new_vec![TypeId::of::<u64>()].into_boxed_slice()
+                    }
+                }
+                pub fn int_foo_token_callable() -> CallableFunction {
+                    CallableFunction::from_plugin(int_foo_token())
+                }
+                pub fn int_foo_token_input_types() -> Box<[TypeId]> {
+                    int_foo_token().input_types()
+                }
+                pub fn int_foo_token_hash() -> u64 {
+                    let input_types = int_foo_token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "get$square", num_args, input_types.iter().cloned())
+                }
+                pub fn int_foo_token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["get$square", "get$sq"],
+                        arg_count: 1usize,
+                        param_types: &["&mut u64"],
+                        return_type: "u64",
+                    }
+                }
             }
         };
 
@@ -1439,6 +2069,12 @@ mod generate_tests {
                              CallableFunction::from_plugin(int_foo_token()));
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata.push(int_foo_token_metadata());
+                    metadata
+                }
                 #[allow(non_camel_case_types)]
                 struct int_foo_token();
                 impl PluginFunction for int_foo_token {
@@ -1467,6 +2103,23 @@ mod generate_tests {
                 pub fn int_foo_token_input_types() -> Box<[TypeId]> {
                     int_foo_token().input_types()
                 }
+                pub fn int_foo_token_hash() -> u64 {
+                    let input_types = int_foo_token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "set$squared", num_args, input_types.iter().cloned())
+                }
+                pub fn int_foo_token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["set$squared"],
+                        arg_count: 2usize,
+                        param_types: &["&mut u64", "u64"],
+                        return_type: "()",
+                    }
+                }
             }
         };
 
@@ -1510,6 +2163,12 @@ mod generate_tests {
                              CallableFunction::from_plugin(int_foo_token()));
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata.push(int_foo_token_metadata());
+                    metadata
+                }
                 #[allow(non_camel_case_types)]
                 struct int_foo_token();
                 impl PluginFunction for int_foo_token {
@@ -1538,6 +2197,23 @@ mod generate_tests {
                 pub fn int_foo_token_input_types() -> Box<[TypeId]> {
                     int_foo_token().input_types()
                 }
+                pub fn int_foo_token_hash() -> u64 {
+                    let input_types = int_foo_token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "set_sq", num_args, input_types.iter().cloned())
+                }
+                pub fn int_foo_token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["set_sq", "set$squared"],
+                        arg_count: 2usize,
+                        param_types: &["&mut u64", "u64"],
+                        return_type: "()",
+                    }
+                }
             }
         };
 
@@ -1577,6 +2253,12 @@ mod generate_tests {
                              CallableFunction::from_plugin(get_by_index_token()));
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata.push(get_by_index_token_metadata());
+                    metadata
+                }
                 #[allow(non_camel_case_types)]
                 struct get_by_index_token();
                 impl PluginFunction for get_by_index_token {
@@ -1606,6 +2288,23 @@ mod generate_tests {
                 pub fn get_by_index_token_input_types() -> Box<[TypeId]> {
                     get_by_index_token().input_types()
                 }
+                pub fn get_by_index_token_hash() -> u64 {
+                    let input_types = get_by_index_token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "index$get$", num_args, input_types.iter().cloned())
+                }
+                pub fn get_by_index_token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["index$get$"],
+                        arg_count: 2usize,
+                        param_types: &["&mut MyCollection", "u64"],
+                        return_type: "FLOAT",
+                    }
+                }
             }
         };
 
@@ -1649,6 +2348,12 @@ mod generate_tests {
                              CallableFunction::from_plugin(get_by_index_token()));
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata.push(get_by_index_token_metadata());
+                    metadata
+                }
                 #[allow(non_camel_case_types)]
                 struct get_by_index_token();
                 impl PluginFunction for get_by_index_token {
@@ -1678,6 +2383,23 @@ mod generate_tests {
                 pub fn get_by_index_token_input_types() -> Box<[TypeId]> {
                     get_by_index_token().input_types()
                 }
+                pub fn get_by_index_token_hash() -> u64 {
+                    let input_types = get_by_index_token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "get", num_args, input_types.iter().cloned())
+                }
+                pub fn get_by_index_token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["get", "index$get$"],
+                        arg_count: 2usize,
+                        param_types: &["&mut MyCollection", "u64"],
+                        return_type: "FLOAT",
+                    }
+                }
             }
         };
 
@@ -1718,6 +2440,12 @@ mod generate_tests {
                              CallableFunction::from_plugin(set_by_index_token()));
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata.push(set_by_index_token_metadata());
+                    metadata
+                }
                 #[allow(non_camel_case_types)]
                 struct set_by_index_token();
                 impl PluginFunction for set_by_index_token {
@@ -1749,6 +2477,215 @@ mod generate_tests {
                 pub fn set_by_index_token_input_types() -> Box<[TypeId]> {
                     set_by_index_token().input_types()
                 }
+                pub fn set_by_index_token_hash() -> u64 {
+                    let input_types = set_by_index_token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "index$set$", num_args, input_types.iter().cloned())
+                }
+                pub fn set_by_index_token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["index$set$"],
+                        arg_count: 3usize,
+                        param_types: &["&mut MyCollection", "u64", "FLOAT"],
+                        return_type: "()",
+                    }
+                }
+            }
+        };
+
+        let item_mod = syn::parse2::<Module>(input_tokens).unwrap();
+        assert_streams_eq(item_mod.generate(), expected_tokens);
+    }
+
+    #[test]
+    fn one_global_index_setter_fn_module() {
+        let input_tokens: TokenStream = quote! {
+            pub mod one_index_fn {
+                #[rhai_fn(index_set, global)]
+                pub fn set_by_index(x: &mut MyCollection, i: u64, item: FLOAT) {
+                    x.entry(i).set(item)
+                }
+            }
+        };
+
+        let expected_tokens = quote! {
+            pub mod one_index_fn {
+                pub fn set_by_index(x: &mut MyCollection, i: u64, item: FLOAT) {
+                    x.entry(i).set(item)
+                }
+                #[allow(unused_imports)]
+                use super::*;
+
+                pub fn rhai_module_generate() -> Module {
+                    let mut m = Module::new();
+                    rhai_generate_into_module(&mut m, false);
+                    m
+                }
+                #[allow(unused_mut)]
+                pub fn rhai_generate_into_module(m: &mut Module, flatten: bool) {
+                    {
+                        let hash = m.set_fn("index$set$", FnAccess::Public,
+                                            &[core::any::TypeId::of::<MyCollection>(),
+                                              core::any::TypeId::of::<u64>(),
+                                              core::any::TypeId::of::<FLOAT>()],
+                                            CallableFunction::from_plugin(set_by_index_token()));
+                        m.set_fn_global(hash);
+                    }
+                    if flatten {} else {}
+                }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata.push(set_by_index_token_metadata());
+                    metadata
+                }
+                #[allow(non_camel_case_types)]
+                struct set_by_index_token();
+                impl PluginFunction for set_by_index_token {
+                    fn call(&self,
+                            args: &mut [&mut Dynamic]
+                    ) -> Result<Dynamic, Box<EvalAltResult>> {
+                        debug_assert_eq!(args.len(), 3usize,
+                                            "wrong arg count: {} != {}", args.len(), 3usize);
+                        let arg1 = mem::take(args[1usize]).cast::<u64>();
+                        let arg2 = mem::take(args[2usize]).cast::<FLOAT>();
+                        let arg0: &mut _ = &mut args[0usize].write_lock::<MyCollection>().unwrap();
+                        Ok(Dynamic::from(set_by_index(arg0, arg1, arg2)))
+                    }
+
+                    fn is_method_call(&self) -> bool { true }
+                    fn is_varadic(&self) -> bool { false }
+                    fn clone_boxed(&self) -> Box<dyn PluginFunction> {
+                        Box::new(set_by_index_token())
+                    }
+                    fn input_types(&self) -> Box<[TypeId]> {
+                        new_vec![TypeId::of::<MyCollection>(),
+                                 TypeId::of::<u64>(),
+                                 TypeId::of::<FLOAT>()].into_boxed_slice()
+                    }
+                }
+                pub fn set_by_index_token_callable() -> CallableFunction {
+                    CallableFunction::from_plugin(set_by_index_token())
+                }
+                pub fn set_by_index_token_input_types() -> Box<[TypeId]> {
+                    set_by_index_token().input_types()
+                }
+                pub fn set_by_index_token_hash() -> u64 {
+                    let input_types = set_by_index_token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "index$set$", num_args, input_types.iter().cloned())
+                }
+                pub fn set_by_index_token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["index$set$"],
+                        arg_count: 3usize,
+                        param_types: &["&mut MyCollection", "u64", "FLOAT"],
+                        return_type: "()",
+                    }
+                }
+            }
+        };
+
+        let item_mod = syn::parse2::<Module>(input_tokens).unwrap();
+        assert_streams_eq(item_mod.generate(), expected_tokens);
+    }
+
+    #[test]
+    fn one_fallible_index_setter_fn_module() {
+        // The space in '> >' matches how the parsed return type is printed back out
+        let input_tokens: TokenStream = quote! {
+            pub mod one_index_fn {
+                #[rhai_fn(index_set, return_raw)]
+                pub fn set_by_index(x: &mut MyCollection, i: u64, item: FLOAT) -> Result<(), Box<EvalAltResult> > {
+                    x.entry(i).set(item)
+                }
+            }
+        };
+
+        let expected_tokens = quote! {
+            pub mod one_index_fn {
+                pub fn set_by_index(x: &mut MyCollection, i: u64, item: FLOAT) -> Result<(), Box<EvalAltResult> > {
+                    x.entry(i).set(item)
+                }
+                #[allow(unused_imports)]
+                use super::*;
+
+                pub fn rhai_module_generate() -> Module {
+                    let mut m = Module::new();
+                    rhai_generate_into_module(&mut m, false);
+                    m
+                }
+                #[allow(unused_mut)]
+                pub fn rhai_generate_into_module(m: &mut Module, flatten: bool) {
+                    m.set_fn("index$set$", FnAccess::Public,
+                             &[core::any::TypeId::of::<MyCollection>(),
+                               core::any::TypeId::of::<u64>(),
+                               core::any::TypeId::of::<FLOAT>()],
+                             CallableFunction::from_plugin(set_by_index_token()));
+                    if flatten {} else {}
+                }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata.push(set_by_index_token_metadata());
+                    metadata
+                }
+                #[allow(non_camel_case_types)]
+                struct set_by_index_token();
+                impl PluginFunction for set_by_index_token {
+                    fn call(&self,
+                            args: &mut [&mut Dynamic]
+                    ) -> Result<Dynamic, Box<EvalAltResult>> {
+                        debug_assert_eq!(args.len(), 3usize,
+                                            "wrong arg count: {} != {}", args.len(), 3usize);
+                        let arg1 = mem::take(args[1usize]).cast::<u64>();
+                        let arg2 = mem::take(args[2usize]).cast::<FLOAT>();
+                        let arg0: &mut _ = &mut args[0usize].write_lock::<MyCollection>().unwrap();
+                        set_by_index(arg0, arg1, arg2).map(Dynamic::from)
+                    }
+
+                    fn is_method_call(&self) -> bool { true }
+                    fn is_varadic(&self) -> bool { false }
+                    fn clone_boxed(&self) -> Box<dyn PluginFunction> {
+                        Box::new(set_by_index_token())
+                    }
+                    fn input_types(&self) -> Box<[TypeId]> {
+                        new_vec![TypeId::of::<MyCollection>(),
+                                 TypeId::of::<u64>(),
+                                 TypeId::of::<FLOAT>()].into_boxed_slice()
+                    }
+                }
+                pub fn set_by_index_token_callable() -> CallableFunction {
+                    CallableFunction::from_plugin(set_by_index_token())
+                }
+                pub fn set_by_index_token_input_types() -> Box<[TypeId]> {
+                    set_by_index_token().input_types()
+                }
+                pub fn set_by_index_token_hash() -> u64 {
+                    let input_types = set_by_index_token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "index$set$", num_args, input_types.iter().cloned())
+                }
+                pub fn set_by_index_token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["index$set$"],
+                        arg_count: 3usize,
+                        param_types: &["&mut MyCollection", "u64", "FLOAT"],
+                        return_type: "Result<(), Box<EvalAltResult>>",
+                    }
+                }
             }
         };
 
@@ -1794,6 +2731,12 @@ mod generate_tests {
                              CallableFunction::from_plugin(set_by_index_token()));
                     if flatten {} else {}
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata.push(set_by_index_token_metadata());
+                    metadata
+                }
                 #[allow(non_camel_case_types)]
                 struct set_by_index_token();
                 impl PluginFunction for set_by_index_token {
@@ -1825,6 +2768,23 @@ mod generate_tests {
                 pub fn set_by_index_token_input_types() -> Box<[TypeId]> {
                     set_by_index_token().input_types()
                 }
+                pub fn set_by_index_token_hash() -> u64 {
+                    let input_types = set_by_index_token_input_types();
+                    let num_args = if input_types.is_empty() {
+                        usize::MAX
+                    } else {
+                        input_types.len()
+                    };
+                    calc_fn_hash(core::iter::empty(), "set", num_args, input_types.iter().cloned())
+                }
+                pub fn set_by_index_token_metadata() -> FnMetadata {
+                    FnMetadata {
+                        names: &["set", "index$set$"],
+                        arg_count: 3usize,
+                        param_types: &["&mut MyCollection", "u64", "FLOAT"],
+                        return_type: "()",
+                    }
+                }
             }
         };
 
@@ -1859,6 +2819,11 @@ mod generate_tests {
                         m.set_var("MYSTIC_NUMBER", MYSTIC_NUMBER);
                         if flatten {} else {}
                     }
+                    #[allow(unused_mut)]
+                    pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                        let mut metadata = Vec::new();
+                        metadata
+                    }
                 }
                 #[allow(unused_imports)]
                 use super::*;
@@ -1876,6 +2841,11 @@ mod generate_tests {
                         { m.set_sub_module("it_is", self::it_is::rhai_module_generate()); }
                     }
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata
+                }
             }
         };
 
@@ -1913,6 +2883,11 @@ mod generate_tests {
                         m.set_var("MYSTIC_NUMBER", MYSTIC_NUMBER);
                         if flatten {} else {}
                     }
+                    #[allow(unused_mut)]
+                    pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                        let mut metadata = Vec::new();
+                        metadata
+                    }
                 }
                 pub mod second_is {
                     pub const SPECIAL_CPU_NUMBER: INT = 68000;
@@ -1929,6 +2904,11 @@ mod generate_tests {
                         m.set_var("SPECIAL_CPU_NUMBER", SPECIAL_CPU_NUMBER);
                         if flatten {} else {}
                     }
+                    #[allow(unused_mut)]
+                    pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                        let mut metadata = Vec::new();
+                        metadata
+                    }
                 }
                 #[allow(unused_imports)]
                 use super::*;
@@ -1948,6 +2928,11 @@ mod generate_tests {
                         { m.set_sub_module("second_is", self::second_is::rhai_module_generate()); }
                     }
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata
+                }
             }
         };
 
@@ -2009,6 +2994,11 @@ mod generate_tests {
                                 m.set_var("VALUE", VALUE);
                                 if flatten {} else {}
                             }
+                            #[allow(unused_mut)]
+                            pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                                let mut metadata = Vec::new();
+                                metadata
+                            }
                         }
                         pub mod right {
                             pub const VALUE: INT = 7;
@@ -2025,6 +3015,11 @@ mod generate_tests {
                                 m.set_var("VALUE", VALUE);
                                 if flatten {} else {}
                             }
+                            #[allow(unused_mut)]
+                            pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                                let mut metadata = Vec::new();
+                                metadata
+                            }
                         }
                         #[allow(unused_imports)]
                         use super::*;
@@ -2046,6 +3041,11 @@ mod generate_tests {
                                 { m.set_sub_module("right", self::right::rhai_module_generate()); }
                             }
                         }
+                        #[allow(unused_mut)]
+                        pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                            let mut metadata = Vec::new();
+                            metadata
+                        }
                     }
                     pub mod right {
                         pub const VALUE: INT = 3;
@@ -2062,6 +3062,11 @@ mod generate_tests {
                             m.set_var("VALUE", VALUE);
                             if flatten {} else {}
                         }
+                        #[allow(unused_mut)]
+                        pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                            let mut metadata = Vec::new();
+                            metadata
+                        }
                     }
                     #[allow(unused_imports)]
                     use super::*;
@@ -2083,6 +3088,11 @@ mod generate_tests {
                             { m.set_sub_module("right", self::right::rhai_module_generate()); }
                         }
                     }
+                    #[allow(unused_mut)]
+                    pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                        let mut metadata = Vec::new();
+                        metadata
+                    }
                 }
                 pub mod right {
                     pub const VALUE: INT = 36;
@@ -2101,6 +3111,11 @@ mod generate_tests {
                             m.set_var("VALUE", VALUE);
                             if flatten {} else {}
                         }
+                        #[allow(unused_mut)]
+                        pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                            let mut metadata = Vec::new();
+                            metadata
+                        }
                     }
                     pub mod right {
                         pub const VALUE: INT = 1;
@@ -2117,6 +3132,11 @@ mod generate_tests {
                             m.set_var("VALUE", VALUE);
                             if flatten {} else {}
                         }
+                        #[allow(unused_mut)]
+                        pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                            let mut metadata = Vec::new();
+                            metadata
+                        }
                     }
                     #[allow(unused_imports)]
                     use super::*;
@@ -2138,6 +3158,11 @@ mod generate_tests {
                             { m.set_sub_module("right", self::right::rhai_module_generate()); }
                         }
                     }
+                    #[allow(unused_mut)]
+                    pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                        let mut metadata = Vec::new();
+                        metadata
+                    }
                 }
                 #[allow(unused_imports)]
                 use super::*;
@@ -2159,6 +3184,11 @@ mod generate_tests {
                         { m.set_sub_module("right", self::right::rhai_module_generate()); }
                     }
                 }
+                #[allow(unused_mut)]
+                pub fn rhai_module_metadata() -> Vec<FnMetadata> {
+                    let mut metadata = Vec::new();
+                    metadata
+                }
             }
         };
 
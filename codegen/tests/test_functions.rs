@@ -1,6 +1,6 @@
 use rhai::module_resolvers::*;
 use rhai::plugin::*;
-use rhai::{Engine, EvalAltResult, Module, RegisterFn, FLOAT};
+use rhai::{Engine, EvalAltResult, Module, RegisterFn, FLOAT, INT};
 
 pub mod raw_fn {
     use rhai::plugin::*;
@@ -200,3 +200,348 @@ fn raw_returning_fn_test() -> Result<(), Box<EvalAltResult>> {
     );
     Ok(())
 }
+
+pub mod fallible_fn {
+    use rhai::plugin::*;
+    use rhai::INT;
+
+    /// Divide `x` by `y`.
+    ///
+    /// Dividing by zero is an error.
+    #[export_fn]
+    pub fn checked_div(x: INT, y: INT) -> Result<INT, Box<EvalAltResult>> {
+        x.checked_div(y).ok_or_else(|| {
+            EvalAltResult::ErrorArithmetic("Division by zero".into(), rhai::Position::none()).into()
+        })
+    }
+}
+
+#[test]
+fn fallible_fn_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    rhai::register_exported_fn!(engine, "checked_div", fallible_fn::checked_div);
+
+    assert_eq!(engine.eval::<INT>("checked_div(84, 2)")?, 42);
+    assert!(matches!(
+        *engine.eval::<INT>("checked_div(42, 0)").expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(msg, _) if msg == "Division by zero"
+    ));
+    Ok(())
+}
+
+pub mod dynamic_fn {
+    use rhai::plugin::*;
+    use rhai::INT;
+
+    #[export_fn]
+    pub fn pick() -> Dynamic {
+        Dynamic::from(42 as INT)
+    }
+}
+
+#[test]
+fn dynamic_fn_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    rhai::register_exported_fn!(engine, "pick", dynamic_fn::pick);
+
+    assert_eq!(engine.eval::<INT>("pick()")?, 42);
+    assert!(engine.eval::<bool>("type_of(pick()) == type_of(0)")?);
+    Ok(())
+}
+
+pub mod cfg_fn {
+    use rhai::plugin::*;
+    use rhai::INT;
+
+    #[export_fn]
+    #[cfg(feature = "extra")]
+    pub fn answer() -> INT {
+        0
+    }
+
+    #[export_fn]
+    #[cfg(not(feature = "extra"))]
+    pub fn answer() -> INT {
+        42
+    }
+}
+
+#[test]
+fn cfg_fn_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    rhai::register_exported_fn!(engine, "answer", cfg_fn::answer);
+
+    #[cfg(feature = "extra")]
+    assert_eq!(engine.eval::<INT>("answer()")?, 0);
+    #[cfg(not(feature = "extra"))]
+    assert_eq!(engine.eval::<INT>("answer()")?, 42);
+    Ok(())
+}
+
+pub mod varadic_fn {
+    use rhai::plugin::*;
+    use rhai::FLOAT;
+
+    #[export_fn]
+    pub fn max_of(first: FLOAT, args: &[Dynamic]) -> FLOAT {
+        args.iter()
+            .filter_map(|v| v.as_float().ok())
+            .fold(first, FLOAT::max)
+    }
+}
+
+#[test]
+fn varadic_fn_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let mut m = Module::new();
+    rhai::set_exported_fn!(m, "max_of", varadic_fn::max_of);
+    engine.load_package(m);
+
+    assert_eq!(engine.eval::<FLOAT>("max_of(1.0)")?, 1.0);
+    assert_eq!(engine.eval::<FLOAT>("max_of(1.0, 3.5, 2.0)")?, 3.5);
+    Ok(())
+}
+
+#[test]
+fn varadic_fn_register_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    rhai::register_exported_fn!(engine, "max_of", varadic_fn::max_of);
+
+    assert_eq!(engine.eval::<FLOAT>("max_of(1.0)")?, 1.0);
+    assert_eq!(engine.eval::<FLOAT>("max_of(1.0, 3.5, 2.0)")?, 3.5);
+    assert!(matches!(
+        *engine.eval::<FLOAT>("max_of()").expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+    Ok(())
+}
+
+pub mod checked_fn {
+    use rhai::plugin::*;
+    use rhai::INT;
+
+    #[export_fn(checked)]
+    pub fn add(x: INT, y: INT) -> INT {
+        x + y
+    }
+}
+
+#[test]
+fn checked_fn_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    rhai::register_exported_fn!(engine, "add", checked_fn::add);
+
+    assert_eq!(engine.eval::<INT>("add(40, 2)")?, 42);
+
+    // Calling with the wrong number of arguments is an error, not a panic
+    let f = checked_fn::rhai_fn_add::token_callable().get_plugin_fn();
+    let mut x = Dynamic::from(42 as INT);
+    assert!(matches!(
+        *f.call(&mut [&mut x]).expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(msg, _)
+            if msg == "add with 1 arguments (expecting 2)"
+    ));
+    Ok(())
+}
+
+pub mod cow_str_fn {
+    use rhai::plugin::*;
+    use rhai::INT;
+    use std::borrow::Cow;
+
+    #[export_fn]
+    pub fn count_words(text: Cow<'static, str>) -> INT {
+        text.split_whitespace().count() as INT
+    }
+}
+
+#[test]
+fn cow_str_fn_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let mut m = Module::new();
+    rhai::set_exported_fn!(m, "count_words", cow_str_fn::count_words);
+    engine.load_package(m);
+
+    assert_eq!(
+        engine.eval::<INT>(r#"count_words("the quick brown fox")"#)?,
+        4
+    );
+    Ok(())
+}
+
+pub mod hashed_fn {
+    use rhai::plugin::*;
+    use rhai::INT;
+
+    #[export_fn]
+    pub fn repeat(s: &str, n: INT) -> String {
+        s.repeat(n as usize)
+    }
+}
+
+#[test]
+fn hashed_fn_test() -> Result<(), Box<EvalAltResult>> {
+    let mut m = Module::new();
+    rhai::set_exported_fn!(m, "repeat", hashed_fn::repeat);
+
+    assert!(m.contains_fn(hashed_fn::rhai_fn_repeat::token_hash(), true));
+    Ok(())
+}
+
+pub mod generic_fn {
+    use rhai::plugin::*;
+    use rhai::{FLOAT, INT};
+    use std::ops::Add;
+
+    #[export_fn(instantiate(INT, FLOAT))]
+    pub fn double<T: Add<Output = T> + Copy>(x: T) -> T {
+        x + x
+    }
+}
+
+#[test]
+fn generic_fn_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    rhai::register_exported_fn!(engine, "double", generic_fn::double::<INT>);
+    rhai::register_exported_fn!(engine, "double", generic_fn::double::<FLOAT>);
+
+    assert_eq!(engine.eval::<INT>("double(21)")?, 42);
+    assert_eq!(engine.eval::<FLOAT>("double(1.25)")?, 2.5);
+    Ok(())
+}
+
+pub mod deprecated_fn {
+    use rhai::plugin::*;
+    use rhai::INT;
+
+    #[export_fn(deprecated = "use 'add' instead")]
+    pub fn plus(x: INT, y: INT) -> INT {
+        x + y
+    }
+
+    #[export_fn]
+    pub fn add(x: INT, y: INT) -> INT {
+        x + y
+    }
+}
+
+#[test]
+fn deprecated_fn_test() -> Result<(), Box<EvalAltResult>> {
+    assert_eq!(
+        deprecated_fn::rhai_fn_plus::DEPRECATED,
+        Some("use 'add' instead")
+    );
+    assert_eq!(deprecated_fn::rhai_fn_add::DEPRECATED, None);
+
+    let calls = std::sync::Arc::new(std::sync::RwLock::new(Vec::<String>::new()));
+    let log = calls.clone();
+
+    let mut engine = Engine::new();
+    let mut m = Module::new();
+    rhai::set_exported_fn!(m, "plus", deprecated_fn::plus);
+    rhai::set_exported_fn!(m, "add", deprecated_fn::add);
+    engine.load_package(m);
+    engine.on_deprecated(move |name, message| {
+        log.write().unwrap().push(format!("{}: {}", name, message));
+    });
+
+    assert_eq!(engine.eval::<INT>("add(plus(1, 2), plus(3, 4))")?, 10);
+    assert_eq!(engine.eval::<INT>("plus(40, 2)")?, 42);
+
+    // Reported once in each evaluation
+    assert_eq!(
+        *calls.read().unwrap(),
+        ["plus: use 'add' instead", "plus: use 'add' instead"]
+    );
+    Ok(())
+}
+
+pub mod raw_aliases_fn {
+    use rhai::plugin::*;
+    use rhai::INT;
+
+    #[export_fn(return_raw)]
+    pub fn parse_number(s: &str) -> Result<Dynamic, Box<EvalAltResult>> {
+        s.parse::<INT>()
+            .map(Dynamic::from)
+            .map_err(|_| format!("'{}' is not a number", s).into())
+    }
+}
+
+#[test]
+fn raw_aliases_fn_test() -> Result<(), Box<EvalAltResult>> {
+    const NAMES: [&str; 3] = ["parse", "to_number", "as_int"];
+
+    // Registered directly via `dynamic_result_fn`, and into a module via the plugin token
+    let mut direct = Engine::new();
+    let mut module = Module::new();
+    for name in &NAMES {
+        rhai::register_exported_fn!(direct, *name, raw_aliases_fn::parse_number);
+        rhai::set_exported_fn!(module, *name, raw_aliases_fn::parse_number);
+    }
+    let mut via_module = Engine::new();
+    via_module.load_package(module);
+
+    for engine in &[direct, via_module] {
+        for name in &NAMES {
+            assert_eq!(engine.eval::<INT>(&format!(r#"{}("42")"#, name))?, 42);
+            assert!(matches!(
+                *engine
+                    .eval::<INT>(&format!(r#"{}("x")"#, name))
+                    .expect_err("should error"),
+                EvalAltResult::ErrorRuntime(s, _) if s == "'x' is not a number"
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub mod metadata_fn {
+    use rhai::plugin::*;
+    use rhai::FLOAT;
+
+    #[export_fn]
+    pub fn scale(values: &mut Vec<FLOAT>, factor: FLOAT) {
+        values.iter_mut().for_each(|v| *v *= factor);
+    }
+}
+
+#[test]
+fn metadata_fn_test() {
+    let metadata = metadata_fn::rhai_fn_scale::token_metadata();
+
+    assert_eq!(metadata.names, ["scale"]);
+    assert_eq!(metadata.arg_count, 2);
+    assert_eq!(metadata.param_types, ["&mut Vec<FLOAT>", "FLOAT"]);
+    assert_eq!(metadata.return_type, "()");
+}
+
+macro_rules! gen_macro_fn {
+    ($root:ident => $int_type:ty, $string_type:ty, $cow_type:ty) => {
+        pub mod $root {
+            use rhai::plugin::*;
+            use rhai::INT;
+
+            #[export_fn]
+            pub fn describe(n: $int_type, name: $string_type, suffix: $cow_type) -> String {
+                format!("{}:{}{}", name, n, suffix)
+            }
+        }
+    };
+}
+
+gen_macro_fn!(macro_fn => INT, String, std::borrow::Cow<'static, str>);
+
+#[test]
+fn macro_fn_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let mut m = Module::new();
+    rhai::set_exported_fn!(m, "describe", macro_fn::describe);
+    engine.load_package(m);
+
+    assert_eq!(
+        engine.eval::<String>(r#"describe(42, "answer", "!")"#)?,
+        "answer:42!"
+    );
+    Ok(())
+}
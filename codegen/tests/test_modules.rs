@@ -1,5 +1,5 @@
 use rhai::module_resolvers::*;
-use rhai::{Array, Engine, EvalAltResult, RegisterFn, FLOAT, INT};
+use rhai::{Array, Engine, EvalAltResult, Map, RegisterFn, FLOAT, INT};
 
 pub mod empty_module {
     use rhai::plugin::*;
@@ -181,6 +181,219 @@ fn mut_opaque_ref_test() -> Result<(), Box<EvalAltResult>> {
     Ok(())
 }
 
+mod by_ref_module {
+    use rhai::plugin::*;
+    #[export_module]
+    pub mod array_sums {
+        use rhai::{Array, INT};
+
+        #[rhai_fn(by_ref)]
+        pub fn sum(list: &Array) -> INT {
+            list.iter().map(|v| v.as_int().unwrap()).sum()
+        }
+
+        #[rhai_fn(by_ref)]
+        pub fn add_sum(total: &mut INT, list: &Array) {
+            *total += sum(list);
+        }
+
+        #[rhai_fn(by_ref)]
+        pub fn append_copy(list: &mut Array, other: &Array) {
+            list.extend(other.iter().cloned());
+        }
+    }
+}
+
+#[test]
+fn by_ref_module_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let m = rhai::exported_module!(crate::by_ref_module::array_sums);
+    engine.load_package(m);
+
+    assert_eq!(engine.eval::<INT>("let a = [1, 2, 39]; sum(a)")?, 42);
+    assert_eq!(
+        engine.eval::<INT>("let a = [1, 2, 3]; let t = 36; t.add_sum(a); t + a.len()")?,
+        45
+    );
+    assert_eq!(
+        engine.eval::<INT>("let a = [1, 2]; a.append_copy(a); sum(a)")?,
+        6
+    );
+
+    // A shared value cannot be both the receiver and a borrowed argument
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let a = [1, 2]; let f = || a; a.append_copy(a); sum(a)")
+            .expect_err("should error"),
+        EvalAltResult::ErrorDataRace(_, _)
+    ));
+    assert_eq!(
+        engine.eval::<INT>("let a = [1, 2]; let f = || a; let b = a; b.append_copy(a); sum(a)")?,
+        3
+    );
+    Ok(())
+}
+
+mod pure_module {
+    use rhai::plugin::*;
+    #[export_module]
+    pub mod array_peeks {
+        use rhai::{Array, INT};
+
+        #[rhai_fn(pure, by_ref)]
+        pub fn count_common(list: &mut Array, other: &Array) -> INT {
+            list.iter()
+                .filter(|&x| other.iter().any(|y| x.as_int() == y.as_int()))
+                .count() as INT
+        }
+
+        // Wrongly marked as pure, so the change is not seen by the script
+        #[rhai_fn(pure)]
+        pub fn pop_front(list: &mut Array) -> Dynamic {
+            list.remove(0)
+        }
+    }
+}
+
+#[test]
+fn pure_module_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let m = rhai::exported_module!(crate::pure_module::array_peeks);
+    engine.load_package(m);
+
+    assert_eq!(
+        engine.eval::<INT>("let a = [1, 2, 3]; a.count_common([2, 3, 4])")?,
+        2
+    );
+    assert_eq!(
+        engine.eval::<INT>("let a = [1, 2, 3]; let x = a.pop_front(); x * 10 + a.len()")?,
+        13
+    );
+    assert_eq!(
+        engine.eval::<INT>("let a = [1, 2]; let f = || a; a.count_common([2]) + f.call().len()")?,
+        3
+    );
+    Ok(())
+}
+
+macro_rules! gen_pure_module {
+    ($root:ident => $list_type:ty) => {
+        pub mod $root {
+            use rhai::plugin::*;
+            #[export_module]
+            pub mod list_fns {
+                use rhai::INT;
+
+                #[rhai_fn(pure)]
+                pub fn peek_len(list: &mut $list_type) -> INT {
+                    list.len() as INT
+                }
+
+                pub fn push_len(list: &mut $list_type) {
+                    let len = list.len() as INT;
+                    list.push(len.into());
+                }
+            }
+        }
+    };
+}
+
+gen_pure_module!(pure_macro_module => rhai::Array);
+
+#[test]
+fn pure_macro_module_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let m = rhai::exported_module!(crate::pure_macro_module::list_fns);
+    engine.load_package(m);
+
+    assert_eq!(
+        engine.eval::<INT>("let a = [1, 2, 3]; a.push_len(); a.peek_len() * 10 + a[3]")?,
+        43
+    );
+    Ok(())
+}
+
+mod property_aliases {
+    use rhai::plugin::*;
+    #[export_module]
+    pub mod array_props {
+        use rhai::{Array, INT};
+
+        #[rhai_fn(get = "length", get = "size")]
+        pub fn length(list: &mut Array) -> INT {
+            list.len() as INT
+        }
+
+        #[rhai_fn(set = "first", set = "head")]
+        pub fn set_first(list: &mut Array, value: INT) {
+            list[0] = value.into();
+        }
+    }
+}
+
+#[test]
+fn property_aliases_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let m = rhai::exported_module!(crate::property_aliases::array_props);
+    engine.load_package(m);
+
+    assert_eq!(
+        engine.eval::<INT>("let a = [1, 2, 3]; a.length + a.size")?,
+        6
+    );
+    assert_eq!(
+        engine.eval::<INT>("let a = [1, 2, 3]; a.first = 0; a.head = 42; a[0]")?,
+        42
+    );
+    Ok(())
+}
+
+mod cfg_module {
+    use rhai::plugin::*;
+    #[export_module]
+    pub mod platform {
+        use rhai::INT;
+
+        #[cfg(feature = "extra")]
+        pub fn answer() -> INT {
+            0
+        }
+
+        #[cfg(not(feature = "extra"))]
+        pub fn answer() -> INT {
+            42
+        }
+
+        #[cfg(feature = "extra")]
+        #[rhai_fn(name = "extra")]
+        pub fn extra_only() -> INT {
+            1
+        }
+    }
+}
+
+#[test]
+fn cfg_module_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let m = rhai::exported_module!(crate::cfg_module::platform);
+    engine.load_package(m);
+
+    #[cfg(feature = "extra")]
+    {
+        assert_eq!(engine.eval::<INT>("answer()")?, 0);
+        assert_eq!(engine.eval::<INT>("extra()")?, 1);
+    }
+    #[cfg(not(feature = "extra"))]
+    {
+        assert_eq!(engine.eval::<INT>("answer()")?, 42);
+        assert!(matches!(
+            *engine.eval::<INT>("extra()").expect_err("should error"),
+            EvalAltResult::ErrorFunctionNotFound(_, _)
+        ));
+    }
+    Ok(())
+}
+
 mod duplicate_fn_rename {
     use rhai::plugin::*;
     #[export_module]
@@ -443,3 +656,764 @@ fn export_all_test() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+pub mod fallible_indexers {
+    use rhai::plugin::*;
+    use rhai::INT;
+
+    #[derive(Clone)]
+    pub struct Sparse {
+        pub size: INT,
+        pub items: Vec<(INT, INT)>,
+    }
+
+    #[export_module]
+    pub mod sparse {
+        use super::Sparse;
+
+        pub fn new_sparse(size: INT) -> Sparse {
+            Sparse {
+                size,
+                items: Vec::new(),
+            }
+        }
+
+        #[rhai_fn(index_get, return_raw)]
+        pub fn get(s: &mut Sparse, i: INT) -> Result<INT, Box<EvalAltResult>> {
+            if i < 0 || i >= s.size {
+                return Err(format!("index {} out of bounds for Sparse", i).into());
+            }
+
+            Ok(s.items
+                .iter()
+                .find(|&&(k, _)| k == i)
+                .map(|&(_, v)| v)
+                .unwrap_or(0))
+        }
+
+        #[rhai_fn(index_set, return_raw)]
+        pub fn set(s: &mut Sparse, i: INT, value: INT) -> Result<(), Box<EvalAltResult>> {
+            if i < 0 || i >= s.size {
+                return Err(format!("index {} out of bounds for Sparse", i).into());
+            }
+
+            s.items.retain(|&(k, _)| k != i);
+            s.items.push((i, value));
+            Ok(())
+        }
+    }
+}
+
+#[test]
+fn fallible_indexers_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let m = rhai::exported_module!(crate::fallible_indexers::sparse);
+    engine.load_package(m);
+
+    assert_eq!(
+        engine
+            .eval::<INT>("let s = new_sparse(100); s[42] = 1; s[99] = 2; s[42] + s[99] + s[7]")?,
+        3
+    );
+
+    assert!(matches!(
+        *engine.eval::<INT>("let s = new_sparse(10); s[10]").unwrap_err(),
+        EvalAltResult::ErrorRuntime(s, _) if s == "index 10 out of bounds for Sparse"
+    ));
+    assert!(matches!(
+        *engine.eval::<()>("let s = new_sparse(10); let i = -1; s[i] = 42;").unwrap_err(),
+        EvalAltResult::ErrorRuntime(s, _) if s == "index -1 out of bounds for Sparse"
+    ));
+
+    Ok(())
+}
+
+pub mod fallible_getter_module {
+    use rhai::plugin::*;
+    use rhai::INT;
+
+    #[derive(Clone)]
+    pub struct Setting {
+        pub raw: String,
+    }
+
+    #[export_module]
+    pub mod setting {
+        use super::Setting;
+
+        pub fn new_setting(raw: &str) -> Setting {
+            Setting {
+                raw: raw.to_string(),
+            }
+        }
+
+        #[rhai_fn(get = "value", return_raw)]
+        pub fn value(s: &mut Setting) -> Result<INT, Box<EvalAltResult>> {
+            s.raw
+                .parse::<INT>()
+                .map_err(|_| format!("setting '{}' is not a number", s.raw).into())
+        }
+    }
+}
+
+#[test]
+fn fallible_getter_module_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let m = rhai::exported_module!(crate::fallible_getter_module::setting);
+    engine.load_package(m);
+
+    assert_eq!(
+        engine.eval::<INT>(r#"let s = new_setting("42"); s.value"#)?,
+        42
+    );
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"let s = new_setting("unset"); s.value"#)
+            .unwrap_err(),
+        EvalAltResult::ErrorRuntime(s, _) if s == "setting 'unset' is not a number"
+    ));
+
+    Ok(())
+}
+
+pub mod exported_consts {
+    use rhai::plugin::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Point {
+        pub x: INT,
+        pub y: INT,
+    }
+
+    #[export_consts]
+    pub mod limits {
+        use super::Point;
+        use rhai::INT;
+
+        pub const MAX_USERS: INT = 100;
+        pub const RATIO: f64 = 0.5;
+        pub const NAME: &str = "limits";
+        pub const ENABLED: bool = true;
+        pub const SEPARATOR: char = ':';
+        pub const PORTS: [INT; 3] = [80, 443, 8080];
+        pub const LEVELS: &[(&str, INT)] = &[("low", 1), ("high", 9)];
+        pub const GRID: [[u8; 2]; 2] = [[1, 2], [3, 4]];
+        pub const ORIGIN: Point = Point { x: 0, y: 0 };
+        pub const CORNERS: [Point; 2] = [Point { x: 0, y: 0 }, Point { x: 1, y: 1 }];
+
+        const HIDDEN: INT = 42;
+
+        pub const DOUBLE_HIDDEN: INT = HIDDEN * 2;
+    }
+}
+
+#[test]
+fn exported_consts_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let m = rhai::exported_module!(crate::exported_consts::limits);
+    let mut r = StaticModuleResolver::new();
+    r.insert("limits".to_string(), m);
+    engine.set_module_resolver(Some(r));
+
+    assert_eq!(
+        engine.eval::<INT>(r#"import "limits" as lim; lim::MAX_USERS + lim::DOUBLE_HIDDEN"#)?,
+        184
+    );
+    assert_eq!(
+        engine.eval::<FLOAT>(r#"import "limits" as lim; lim::RATIO"#)?,
+        0.5
+    );
+    assert_eq!(
+        engine.eval::<String>(
+            r#"import "limits" as lim; if lim::ENABLED { lim::NAME + lim::SEPARATOR } else { "" }"#
+        )?,
+        "limits:"
+    );
+    assert_eq!(
+        engine.eval::<INT>(r#"import "limits" as lim; let p = lim::PORTS; p[0] + p[2]"#)?,
+        8160
+    );
+    assert_eq!(
+        engine
+            .eval::<INT>(r#"import "limits" as lim; let l = lim::LEVELS; l.low * 10 + l.high"#)?,
+        19
+    );
+    assert_eq!(
+        engine.eval::<String>(
+            r#"import "limits" as lim; let g = lim::GRID; type_of(g[1]) + g[1][0]"#
+        )?,
+        "array3"
+    );
+    assert_eq!(
+        engine.eval::<crate::exported_consts::Point>(r#"import "limits" as lim; lim::ORIGIN"#)?,
+        crate::exported_consts::Point { x: 0, y: 0 }
+    );
+    assert_eq!(
+        engine.eval::<crate::exported_consts::Point>(
+            r#"import "limits" as lim; let c = lim::CORNERS; c[1]"#
+        )?,
+        crate::exported_consts::Point { x: 1, y: 1 }
+    );
+    assert!(engine
+        .eval::<INT>(r#"import "limits" as lim; lim::HIDDEN"#)
+        .is_err());
+
+    Ok(())
+}
+
+pub mod vec_returning_module {
+    use rhai::plugin::*;
+
+    #[export_module]
+    pub mod lists {
+        pub fn numbers(n: INT) -> Vec<INT> {
+            (1..=n).collect()
+        }
+        pub fn words() -> Vec<String> {
+            vec!["hello".to_string(), "world".to_string()]
+        }
+        pub fn bytes() -> Vec<u8> {
+            vec![1, 2, 255]
+        }
+        pub fn mixed() -> Vec<Dynamic> {
+            vec![Dynamic::from(42 as INT), Dynamic::from("x")]
+        }
+        pub fn grid() -> Vec<Vec<INT>> {
+            vec![vec![1, 2], vec![3, 4]]
+        }
+    }
+}
+
+#[test]
+fn vec_returning_module_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let m = rhai::exported_module!(crate::vec_returning_module::lists);
+    engine.load_package(m);
+
+    assert_eq!(
+        engine.eval::<INT>("let a = numbers(4); a[0] + a[3] + len(a)")?,
+        9
+    );
+    assert_eq!(
+        engine.eval::<String>("let w = words(); w[0] + \" \" + w[1]")?,
+        "hello world"
+    );
+    assert_eq!(engine.eval::<INT>("let b = bytes(); b[2] + 1")?, 256);
+    assert_eq!(
+        engine.eval::<String>("let m = mixed(); type_of(m[0]) + type_of(m[1])")?,
+        "i64string"
+    );
+    assert_eq!(engine.eval::<INT>("let g = grid(); g[1][0]")?, 3);
+    assert_eq!(engine.eval::<Array>("numbers(3)")?.len(), 3);
+
+    Ok(())
+}
+
+pub mod map_returning_module {
+    use rhai::plugin::*;
+
+    #[export_module]
+    pub mod maps {
+        use std::collections::{BTreeMap, HashMap};
+
+        pub fn scores() -> HashMap<String, INT> {
+            let mut map = HashMap::new();
+            map.insert("alice".to_string(), 42);
+            map.insert("bob".to_string(), 1);
+            map
+        }
+        pub fn ordered() -> BTreeMap<String, String> {
+            let mut map = BTreeMap::new();
+            map.insert("b".to_string(), "world".to_string());
+            map.insert("a".to_string(), "hello".to_string());
+            map
+        }
+        pub fn nested() -> HashMap<String, Vec<INT>> {
+            let mut map = HashMap::new();
+            map.insert("list".to_string(), vec![1, 2, 3]);
+            map
+        }
+    }
+}
+
+#[test]
+fn map_returning_module_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let m = rhai::exported_module!(crate::map_returning_module::maps);
+    engine.load_package(m);
+
+    assert_eq!(
+        engine.eval::<INT>("let m = scores(); m.alice + m[\"bob\"] + len(m)")?,
+        45
+    );
+    assert_eq!(
+        engine.eval::<String>("let m = ordered(); m.a + \" \" + m.b")?,
+        "hello world"
+    );
+    assert_eq!(engine.eval::<INT>("nested().list[2]")?, 3);
+    assert_eq!(engine.eval::<Map>("scores()")?.len(), 2);
+
+    Ok(())
+}
+
+pub mod varadic_module {
+    use rhai::plugin::*;
+
+    #[export_module]
+    pub mod varadic {
+        pub fn sum(args: &[Dynamic]) -> INT {
+            args.iter().map(|v| v.as_int().unwrap_or(0)).sum()
+        }
+        pub fn join(sep: &str, args: &[Dynamic]) -> String {
+            args.iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(sep)
+        }
+        pub fn push_all(list: &mut Array, args: &[Dynamic]) -> INT {
+            list.extend(args.iter().cloned());
+            list.len() as INT
+        }
+    }
+}
+
+#[test]
+fn varadic_module_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let m = rhai::exported_module!(crate::varadic_module::varadic);
+    engine.load_package(m);
+
+    assert_eq!(engine.eval::<INT>("sum()")?, 0);
+    assert_eq!(engine.eval::<INT>("sum(42)")?, 42);
+    assert_eq!(engine.eval::<INT>("sum(1, 2, 3, 4)")?, 10);
+    assert_eq!(
+        engine.eval::<String>(r#"join("-", 1, "x", true)"#)?,
+        "1-x-true"
+    );
+    assert_eq!(engine.eval::<String>(r#"join(", ")"#)?, "");
+    assert_eq!(
+        engine.eval::<INT>("let a = [1]; a.push_all(2, 3); a.push_all(4) + a[2]")?,
+        7
+    );
+
+    // The fixed leading arguments must still match
+    assert!(matches!(
+        *engine
+            .eval::<String>("join(1, 2)")
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn varadic_qualified_module_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let m = rhai::exported_module!(crate::varadic_module::varadic);
+    let mut r = StaticModuleResolver::new();
+    r.insert("Varadic".to_string(), m);
+    engine.set_module_resolver(Some(r));
+
+    assert_eq!(engine.eval::<INT>(r#"import "Varadic" as v; v::sum()"#)?, 0);
+    assert_eq!(
+        engine.eval::<INT>(r#"import "Varadic" as v; v::sum(1, 2, 3, 4)"#)?,
+        10
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"import "Varadic" as v; v::join("-", 1, "x", true)"#)?,
+        "1-x-true"
+    );
+    assert_eq!(
+        engine.eval::<INT>(r#"import "Varadic" as v; let a = [1]; v::push_all(a, 2, 3)"#)?,
+        3
+    );
+    assert!(matches!(
+        *engine
+            .eval::<String>(r#"import "Varadic" as v; v::join(1, 2)"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+
+    Ok(())
+}
+
+pub mod global_fn_module {
+    use rhai::plugin::*;
+
+    #[export_module]
+    pub mod counter {
+        #[derive(Clone)]
+        pub struct Counter {
+            pub count: INT,
+        }
+
+        pub fn new_counter() -> Counter {
+            Counter { count: 0 }
+        }
+        #[rhai_fn(name = "+=", global)]
+        pub fn add(c: &mut Counter, n: INT) {
+            c.count += n;
+        }
+        #[rhai_fn(index_get, global)]
+        pub fn get(c: &mut Counter, n: INT) -> INT {
+            c.count * n
+        }
+        #[rhai_fn(global)]
+        pub fn count(c: &mut Counter) -> INT {
+            c.count
+        }
+    }
+}
+
+#[test]
+fn global_fn_module_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let m = rhai::exported_module!(crate::global_fn_module::counter);
+    let mut r = StaticModuleResolver::new();
+    r.insert("Counter".to_string(), m);
+    engine.set_module_resolver(Some(r));
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"import "Counter" as c;
+               let x = c::new_counter();
+               x += 20; x += 1;
+               x[2]"#
+        )?,
+        42
+    );
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"import "Counter" as c;
+               let x = c::new_counter();
+               x += 42;
+               count(x) + c::count(x) - x.count()"#
+        )?,
+        42
+    );
+
+    // Only global functions can be called without qualification
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"import "Counter" as c; new_counter().count()"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(s, _) if s == "new_counter ()"
+    ));
+
+    // Global functions go out of scope together with the import
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"let x = { import "Counter" as c; c::new_counter() }; count(x)"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(s, _) if s.starts_with("count (")
+    ));
+
+    Ok(())
+}
+
+pub mod hashed_module {
+    use rhai::plugin::*;
+
+    #[export_module]
+    pub mod hashed {
+        pub fn zero() -> INT {
+            0
+        }
+        pub fn repeat(s: &str, n: INT) -> String {
+            s.repeat(n as usize)
+        }
+        #[rhai_fn(get = "doubled")]
+        pub fn doubled(x: &mut INT) -> INT {
+            *x * 2
+        }
+    }
+}
+
+#[test]
+fn hashed_module_test() -> Result<(), Box<EvalAltResult>> {
+    use crate::hashed_module::hashed;
+
+    let m = rhai::exported_module!(crate::hashed_module::hashed);
+
+    // Each hash matches the one the function is registered under
+    assert!(m.contains_fn(hashed::zero_token_hash(), true));
+    assert!(m.contains_fn(hashed::repeat_token_hash(), true));
+    assert!(m.contains_fn(hashed::doubled_token_hash(), true));
+    assert_ne!(hashed::zero_token_hash(), hashed::repeat_token_hash());
+
+    Ok(())
+}
+
+pub mod generic_fn_module {
+    use rhai::plugin::*;
+
+    #[export_module]
+    pub mod pick {
+        #[rhai_fn(instantiate(INT, ImmutableString))]
+        pub fn either<T>(first: bool, a: T, b: T) -> T {
+            if first {
+                a
+            } else {
+                b
+            }
+        }
+    }
+}
+
+#[test]
+fn generic_fn_module_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let m = rhai::exported_module!(crate::generic_fn_module::pick);
+    engine.load_package(m);
+
+    assert_eq!(engine.eval::<INT>("either(true, 42, 0)")?, 42);
+    assert_eq!(
+        engine.eval::<String>(r#"either(false, "foo", "bar")"#)?,
+        "bar"
+    );
+    assert!(matches!(
+        *engine
+            .eval::<INT>("either(true, 1, 2.0)")
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+
+    Ok(())
+}
+
+pub mod deprecated_fn_module {
+    use rhai::plugin::*;
+
+    #[export_module]
+    pub mod math {
+        #[rhai_fn(deprecated = "use 'max' instead")]
+        pub fn maximum(x: INT, y: INT) -> INT {
+            x.max(y)
+        }
+        pub fn max(x: INT, y: INT) -> INT {
+            x.max(y)
+        }
+    }
+}
+
+#[test]
+fn deprecated_fn_module_test() -> Result<(), Box<EvalAltResult>> {
+    let calls = std::sync::Arc::new(std::sync::RwLock::new(Vec::<String>::new()));
+    let log = calls.clone();
+
+    let mut engine = Engine::new();
+    let m = rhai::exported_module!(crate::deprecated_fn_module::math);
+    let mut r = StaticModuleResolver::new();
+    r.insert("Math".to_string(), m);
+    engine.set_module_resolver(Some(r));
+    engine.on_deprecated(move |name, message| {
+        log.write().unwrap().push(format!("{}: {}", name, message));
+    });
+
+    assert_eq!(
+        engine.eval::<INT>(r#"import "Math" as math; math::max(1, 2) + math::maximum(3, 4)"#)?,
+        6
+    );
+    assert_eq!(*calls.read().unwrap(), ["maximum: use 'max' instead"]);
+
+    Ok(())
+}
+
+pub mod raw_aliases_module {
+    use rhai::plugin::*;
+
+    #[export_module]
+    pub mod parse {
+        #[rhai_fn(name = "parse", name = "to_number", name = "as_int", return_raw)]
+        pub fn parse_number(s: &str) -> Result<Dynamic, Box<EvalAltResult>> {
+            s.parse::<INT>()
+                .map(Dynamic::from)
+                .map_err(|_| format!("'{}' is not a number", s).into())
+        }
+    }
+}
+
+#[test]
+fn raw_aliases_module_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let m = rhai::exported_module!(crate::raw_aliases_module::parse);
+    engine.load_package(m);
+
+    for name in &["parse", "to_number", "as_int"] {
+        assert_eq!(engine.eval::<INT>(&format!(r#"{}("42")"#, name))?, 42);
+        assert!(matches!(
+            *engine
+                .eval::<INT>(&format!(r#"{}("x")"#, name))
+                .expect_err("should error"),
+            EvalAltResult::ErrorRuntime(s, _) if s == "'x' is not a number"
+        ));
+    }
+
+    Ok(())
+}
+
+pub mod tuple_module {
+    use rhai::plugin::*;
+
+    #[export_module]
+    pub mod geometry {
+        use rhai::INT;
+
+        pub fn div_rem(x: INT, y: INT) -> (INT, INT) {
+            (x / y, x % y)
+        }
+
+        pub fn labelled(x: INT) -> (String, (INT, bool)) {
+            (format!("#{}", x), (x * 2, x > 0))
+        }
+
+        pub fn checked_div_rem(x: INT, y: INT) -> Result<(INT, INT), Box<EvalAltResult>> {
+            if y == 0 {
+                Err("Division by zero".into())
+            } else {
+                Ok((x / y, x % y))
+            }
+        }
+    }
+}
+
+#[test]
+fn tuple_module_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let m = rhai::exported_module!(crate::tuple_module::geometry);
+    engine.load_package(m);
+
+    let r = engine.eval::<Array>("div_rem(17, 5)")?;
+    assert_eq!(r.len(), 2);
+    assert_eq!(r[0].clone().cast::<INT>(), 3);
+    assert_eq!(r[1].clone().cast::<INT>(), 2);
+
+    assert_eq!(
+        engine.eval::<String>(r#"let r = labelled(21); r[0] + " " + r[1][0] + " " + r[1][1]"#)?,
+        "#21 42 true"
+    );
+
+    assert_eq!(engine.eval::<INT>("checked_div_rem(17, 5)[1]")?, 2);
+    assert!(engine.eval::<Array>("checked_div_rem(17, 0)").is_err());
+
+    Ok(())
+}
+
+pub mod metadata_module {
+    use rhai::plugin::*;
+
+    #[export_module]
+    pub mod strings {
+        use rhai::INT;
+
+        #[rhai_fn(name = "repeat", name = "times")]
+        pub fn repeat_str(s: &str, n: INT) -> String {
+            s.repeat(n as usize)
+        }
+
+        #[rhai_fn(get = "len")]
+        pub fn len(s: &mut ImmutableString) -> INT {
+            s.len() as INT
+        }
+
+        pub fn clear(list: &mut Vec<String>) {
+            list.clear();
+        }
+    }
+}
+
+#[test]
+fn metadata_module_test() {
+    use rhai::plugin::FnMetadata;
+
+    let metadata = crate::metadata_module::strings::rhai_module_metadata();
+
+    assert_eq!(
+        metadata,
+        [
+            FnMetadata {
+                names: &["repeat", "times"],
+                arg_count: 2,
+                param_types: &["&str", "INT"],
+                return_type: "String",
+            },
+            FnMetadata {
+                names: &["get$len"],
+                arg_count: 1,
+                param_types: &["&mut ImmutableString"],
+                return_type: "INT",
+            },
+            FnMetadata {
+                names: &["clear"],
+                arg_count: 1,
+                param_types: &["&mut Vec<String>"],
+                return_type: "()",
+            },
+        ]
+    );
+    assert_eq!(
+        crate::metadata_module::strings::len_token_metadata(),
+        metadata[1]
+    );
+}
+
+pub mod option_module {
+    use rhai::plugin::*;
+
+    #[export_module]
+    pub mod lookup {
+        use rhai::INT;
+
+        pub fn find(list: &mut Array, value: INT) -> Option<INT> {
+            list.iter()
+                .position(|v| v.as_int() == Ok(value))
+                .map(|i| i as INT)
+        }
+
+        pub fn name_of(n: INT) -> Option<String> {
+            match n {
+                1 => Some("one".to_string()),
+                _ => None,
+            }
+        }
+
+        pub fn checked_find(
+            list: &mut Array,
+            value: INT,
+        ) -> Result<Option<INT>, Box<EvalAltResult>> {
+            if list.is_empty() {
+                Err("empty list".into())
+            } else {
+                Ok(find(list, value))
+            }
+        }
+
+        #[rhai_fn(option_as_unit)]
+        pub fn raw_find(list: &mut Array, value: INT) -> Option<INT> {
+            find(list, value)
+        }
+    }
+}
+
+#[test]
+fn option_module_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let m = rhai::exported_module!(crate::option_module::lookup);
+    engine.load_package(m);
+
+    assert_eq!(engine.eval::<INT>("[1, 2, 3].find(3)")?, 2);
+    assert_eq!(engine.eval::<()>("[1, 2, 3].find(4)")?, ());
+    assert_eq!(engine.eval::<String>("name_of(1)")?, "one");
+    assert_eq!(engine.eval::<()>("name_of(2)")?, ());
+    assert_eq!(engine.eval::<INT>("[1, 2, 3].checked_find(2)")?, 1);
+    assert_eq!(engine.eval::<()>("[1, 2, 3].checked_find(4)")?, ());
+    assert!(engine.eval::<()>("[].checked_find(4)").is_err());
+    assert_eq!(
+        engine.eval::<Option<INT>>("[1, 2, 3].raw_find(2)")?,
+        Some(1)
+    );
+    assert_eq!(engine.eval::<Option<INT>>("[1, 2, 3].raw_find(4)")?, None);
+
+    Ok(())
+}
@@ -0,0 +1,179 @@
+use rhai::module_resolvers::*;
+use rhai::{Array, Engine, EvalAltResult, INT};
+
+pub mod point {
+    use rhai::plugin::*;
+    use rhai::INT;
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Point {
+        pub x: INT,
+        pub y: INT,
+    }
+
+    #[export_type]
+    impl Point {
+        #[rhai_fn(name = "create")]
+        pub fn new(x: INT, y: INT) -> Self {
+            Point { x, y }
+        }
+
+        #[rhai_fn(name = "origin")]
+        pub fn zero() -> Point {
+            Point { x: 0, y: 0 }
+        }
+
+        pub fn move_by(&mut self, dx: INT, dy: INT) {
+            self.x += dx;
+            self.y += dy;
+        }
+
+        #[rhai_fn(get = "x")]
+        pub fn get_x(&mut self) -> INT {
+            self.x
+        }
+
+        #[rhai_fn(name = "dist")]
+        pub fn manhattan_distance(&self, other: Self) -> INT {
+            (self.x - other.x).abs() + (self.y - other.y).abs()
+        }
+
+        #[rhai_fn(skip)]
+        pub fn reset(&mut self) {
+            self.x = 0;
+            self.y = 0;
+        }
+
+        #[allow(dead_code)]
+        fn secret(&self) -> INT {
+            42
+        }
+    }
+}
+
+#[test]
+fn export_type_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    engine.register_type_with_name::<point::Point>("Point");
+    engine.load_package(rhai::exported_type!(point::Point));
+
+    let mut r = StaticModuleResolver::new();
+    r.insert("geometry".to_string(), rhai::exported_type!(point::Point));
+    engine.set_module_resolver(Some(r));
+
+    let output_array = engine.eval::<Array>(
+        r#"
+            import "geometry" as geo;
+            let p = geo::Point::create(1, 2);
+            p.move_by(2, 3);
+            let q = geo::Point::origin();
+            [p.x, p.dist(q), q.dist(p), p.x]
+        "#,
+    )?;
+    assert_eq!(&output_array[0].as_int().unwrap(), &3);
+    assert_eq!(&output_array[1].as_int().unwrap(), &8);
+    assert_eq!(&output_array[2].as_int().unwrap(), &8);
+    assert_eq!(&output_array[3].as_int().unwrap(), &3);
+
+    assert!(matches!(*engine.eval::<INT>(
+        r#"import "geometry" as geo; let p = geo::Point::create(1, 2); p.reset(); p.x"#
+    ).expect_err("should error"),
+    EvalAltResult::ErrorFunctionNotFound(s, _) if s.starts_with("reset")));
+
+    assert!(matches!(*engine.eval::<INT>(
+        r#"import "geometry" as geo; let p = geo::Point::create(1, 2); p.secret()"#
+    ).expect_err("should error"),
+    EvalAltResult::ErrorFunctionNotFound(s, _) if s.starts_with("secret")));
+
+    Ok(())
+}
+
+#[test]
+fn export_type_flatten_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let mut m = rhai::Module::new();
+    rhai::combine_with_exported_module!(&mut m, "Point", point::rhai_type_Point);
+    engine.load_package(m);
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                let p = create(1, 2);
+                p.move_by(3, 3);
+                p.dist(origin())
+            "#
+        )?,
+        9
+    );
+
+    Ok(())
+}
+
+pub mod colors {
+    use rhai::plugin::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, ExportEnum)]
+    pub enum Color {
+        Red,
+        Green,
+        Blue,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, ExportEnum)]
+    #[rhai_enum(name = "Light", case_insensitive)]
+    pub enum TrafficLight {
+        Red,
+        Amber,
+        Green,
+    }
+}
+
+#[test]
+fn export_enum_test() -> Result<(), Box<EvalAltResult>> {
+    use colors::{Color, TrafficLight};
+
+    let mut engine = Engine::new();
+    engine.register_type_with_name::<Color>("Color");
+    engine.load_package(rhai::exported_type!(colors::Color));
+    engine.load_package(rhai::exported_type!(colors::TrafficLight));
+
+    let mut r = StaticModuleResolver::new();
+    r.insert("Color".to_string(), rhai::exported_type!(colors::Color));
+    engine.set_module_resolver(Some(r));
+
+    assert_eq!(
+        engine.eval::<Color>(r#"import "Color" as Color; Color::Green"#)?,
+        Color::Green
+    );
+    assert_eq!(engine.eval::<Color>(r#"parse_color("Blue")"#)?, Color::Blue);
+    assert_eq!(
+        engine.eval::<String>(r#"let c = parse_color("Red"); c.to_string()"#)?,
+        "Red"
+    );
+    assert!(engine.eval::<bool>(
+        r#"import "Color" as Color; let c = parse_color("Red"); c == Color::Red && c != Color::Blue"#
+    )?);
+    assert_eq!(
+        engine.eval::<String>(r#"type_of(parse_color("Red"))"#)?,
+        "Color"
+    );
+
+    // Names are case-sensitive by default
+    assert!(matches!(
+        *engine.eval::<Color>(r#"parse_color("red")"#).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _)
+            if msg == "Invalid Color 'red' (valid names: Red, Green, Blue)"
+    ));
+
+    assert_eq!(
+        engine.eval::<TrafficLight>(r#"parse_light("AMBER")"#)?,
+        TrafficLight::Amber
+    );
+    assert!(matches!(
+        *engine.eval::<TrafficLight>(r#"parse_light("blue")"#).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _)
+            if msg == "Invalid Light 'blue' (valid names: Red, Amber, Green)"
+    ));
+
+    Ok(())
+}
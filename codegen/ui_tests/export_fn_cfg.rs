@@ -6,8 +6,8 @@ pub struct Point {
     y: f32,
 }
 
-#[cfg(not(feature = "foo"))]
 #[export_fn]
+#[cfg(feature = "foo")]
 pub fn test_fn(input: Point) -> bool {
     input.x > input.y
 }
@@ -0,0 +1,31 @@
+use rhai::plugin::*;
+
+#[derive(Clone)]
+pub struct Clonable {
+    a: f32,
+    b: u32,
+    c: char,
+    d: bool,
+}
+
+impl Clonable {
+    #[export_fn]
+    pub fn test_fn(&mut self, x: INT) -> bool {
+        self.b += x as u32;
+        self.d
+    }
+}
+
+fn main() {
+    let mut n = Clonable {
+        a: 0.0,
+        b: 10,
+        c: 'a',
+        d: true,
+    };
+    if n.test_fn(1) {
+        println!("yes");
+    } else {
+        println!("no");
+    }
+}
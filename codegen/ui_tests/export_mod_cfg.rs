@@ -8,8 +8,8 @@ pub struct Point {
 
 #[export_module]
 pub mod test_mod {
-#[cfg(not(feature = "foo"))]
-#[rhai_fn]
+#[cfg(feature = "foo")]
+#[rhai_fn(name = "test")]
 pub fn test_fn(input: Point) -> bool {
     input.x > input.y
 }
@@ -0,0 +1,24 @@
+use rhai::plugin::*;
+
+#[derive(Clone)]
+pub struct Clonable {
+    a: f32,
+    b: u32,
+    c: char,
+    d: bool,
+}
+
+#[export_module]
+pub mod test_module {
+    pub fn test_fn(self) -> bool {
+        true
+    }
+}
+
+fn main() {
+    if test_module::test_fn() {
+        println!("yes");
+    } else {
+        println!("no");
+    }
+}
@@ -0,0 +1,21 @@
+use rhai::plugin::*;
+
+#[derive(Clone)]
+struct Point {
+    x: f32,
+    y: f32,
+}
+
+#[export_fn]
+pub fn test_fn(input: &mut Box<Point>) -> bool {
+    input.x > input.y
+}
+
+fn main() {
+    let mut n = Box::new(Point { x: 0.0, y: 10.0 });
+    if test_fn(&mut n) {
+        println!("yes");
+    } else {
+        println!("no");
+    }
+}
@@ -8,7 +8,7 @@ pub struct Point {
 
 #[export_module]
 pub mod test_module {
-    #[cfg(not(feature = "foo"))]
+    #[cfg(feature = "foo")]
     pub fn test_fn(input: Point) -> bool {
         input.x > input.y
     }
@@ -0,0 +1,16 @@
+use rhai::plugin::*;
+
+#[export_fn]
+pub fn test_fn(input: INT) -> std::collections::HashMap<INT, INT> {
+    let mut map = std::collections::HashMap::new();
+    map.insert(input, input);
+    map
+}
+
+fn main() {
+    if test_fn(42).len() == 1 {
+        println!("yes");
+    } else {
+        println!("no");
+    }
+}
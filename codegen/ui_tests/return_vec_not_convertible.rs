@@ -0,0 +1,28 @@
+use rhai::plugin::*;
+
+#[derive(Clone)]
+struct Clonable {
+    a: f32,
+    b: u32,
+    c: char,
+    d: bool,
+}
+
+#[export_fn]
+pub fn test_fn(input: Clonable) -> Vec<Clonable> {
+    vec![input]
+}
+
+fn main() {
+    let n = Clonable {
+        a: 0.0,
+        b: 10,
+        c: 'a',
+        d: true,
+    };
+    if test_fn(n).len() == 1 {
+        println!("yes");
+    } else {
+        println!("no");
+    }
+}
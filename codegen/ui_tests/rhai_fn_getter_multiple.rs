@@ -9,7 +9,7 @@ pub struct Point {
 #[export_module]
 pub mod test_module {
     pub use super::Point;
-    #[rhai_fn(name = "foo", get = "foo", get = "bar")]
+    #[rhai_fn(name = "foo", get = "foo", get = "foo")]
     pub fn test_fn(input: Point) -> bool {
         input.x > input.y
     }
@@ -0,0 +1,30 @@
+use rhai::plugin::*;
+
+#[derive(Clone)]
+pub struct Point {
+    x: f32,
+    y: f32,
+}
+
+#[export_module]
+pub mod test_module {
+    pub use super::Point;
+    #[rhai_fn(get = "foo", return_raw)]
+    pub fn test_fn(input: &mut Point) -> Result<(), Box<EvalAltResult>> {
+        input.x *= 2.0;
+        Ok(())
+    }
+}
+
+fn main() {
+    let mut n = Point {
+        x: 0.0,
+        y: 10.0,
+    };
+    test_module::test_fn(&mut n).unwrap();
+    if n.x > 10.0 {
+        println!("yes");
+    } else {
+        println!("no");
+    }
+}
@@ -0,0 +1,30 @@
+use rhai::plugin::*;
+
+#[derive(Clone)]
+pub struct Point {
+    x: f32,
+    y: f32,
+}
+
+#[export_module]
+pub mod test_module {
+    pub use super::Point;
+    #[rhai_fn(index_set)]
+    pub fn test_fn(input: &mut Point, index: i64, value: f32) -> Result<bool, Box<EvalAltResult>> {
+        if index == 0 {
+            input.x = value;
+        } else {
+            input.y = value;
+        }
+        Ok(true)
+    }
+}
+
+fn main() {
+    let mut n = Point {
+        x: 0.0,
+        y: 10.0,
+    };
+    test_module::test_fn(&mut n, 1, 5.0).unwrap();
+    println!("{}", n.y);
+}
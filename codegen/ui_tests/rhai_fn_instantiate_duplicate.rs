@@ -0,0 +1,17 @@
+use rhai::plugin::*;
+
+#[export_module]
+pub mod test_module {
+    #[rhai_fn(instantiate(i64, f64, i64))]
+    pub fn test_fn<T: Clone>(x: T) -> T {
+        x.clone()
+    }
+}
+
+fn main() {
+    if test_module::test_fn(42) == 42 {
+        println!("yes");
+    } else {
+        println!("no");
+    }
+}
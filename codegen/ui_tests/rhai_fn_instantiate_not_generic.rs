@@ -0,0 +1,17 @@
+use rhai::plugin::*;
+
+#[export_module]
+pub mod test_module {
+    #[rhai_fn(instantiate(i64, f64))]
+    pub fn test_fn(x: i64) -> i64 {
+        x + 1
+    }
+}
+
+fn main() {
+    if test_module::test_fn(41) == 42 {
+        println!("yes");
+    } else {
+        println!("no");
+    }
+}
@@ -0,0 +1,29 @@
+use rhai::plugin::*;
+
+#[derive(Clone)]
+pub struct Point {
+    x: f32,
+    y: f32,
+}
+
+#[export_module]
+pub mod test_module {
+    pub use super::Point;
+    #[rhai_fn(option_as_unit)]
+    pub fn test_fn(input: Point) -> f32 {
+        input.x
+    }
+}
+
+fn main() {
+    let n = Point {
+        x: 0.0,
+        y: 10.0,
+    };
+    test_module::test_fn(n.clone());
+    if n.x > 10.0 {
+        println!("yes");
+    } else {
+        println!("no");
+    }
+}
@@ -0,0 +1,28 @@
+use rhai::plugin::*;
+
+#[derive(Clone)]
+pub struct Point {
+    x: f32,
+    y: f32,
+}
+
+#[export_module]
+pub mod test_module {
+    pub use super::Point;
+    #[rhai_fn(name = "+")]
+    pub fn test_fn(input: Point, dx: f32, dy: f32) -> Point {
+        Point {
+            x: input.x + dx,
+            y: input.y + dy,
+        }
+    }
+}
+
+fn main() {
+    let n = Point {
+        x: 0.0,
+        y: 10.0,
+    };
+    let n = test_module::test_fn(n, 1.0, 2.0);
+    println!("{}", n.x);
+}
@@ -0,0 +1,29 @@
+use rhai::plugin::*;
+
+#[derive(Clone)]
+pub struct Point {
+    x: f32,
+    y: f32,
+}
+
+#[export_module]
+pub mod test_module {
+    pub use super::Point;
+    #[rhai_fn(set = "x", pure)]
+    pub fn set_x(input: &mut Point, value: f32) {
+        input.x = value;
+    }
+}
+
+fn main() {
+    let mut n = Point {
+        x: 0.0,
+        y: 10.0,
+    };
+    test_module::set_x(&mut n, 1.0);
+    if n.x > n.y {
+        println!("yes");
+    } else {
+        println!("no");
+    }
+}
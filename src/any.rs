@@ -1,11 +1,10 @@
 //! Helper module which defines the `Any` trait to to allow dynamic value handling.
 
-use crate::fn_native::{FnPtr, SendSync};
+use crate::fn_native::{shared_try_take, ExternalIterator, FnPtr, SendSync, Shared};
+use crate::packages::iter_basic::StepRange;
 use crate::parser::{ImmutableString, INT};
 use crate::r#unsafe::{unsafe_cast_box, unsafe_try_cast};
-
-#[cfg(not(feature = "no_closure"))]
-use crate::fn_native::{shared_try_take, Shared};
+use crate::result::CaughtError;
 
 #[cfg(not(feature = "no_float"))]
 use crate::parser::FLOAT;
@@ -19,11 +18,14 @@ use crate::engine::Map;
 use crate::stdlib::{
     any::{type_name, Any, TypeId},
     boxed::Box,
-    fmt,
-    ops::{Deref, DerefMut},
+    fmt, mem,
+    ops::{Deref, DerefMut, Range},
     string::String,
 };
 
+#[cfg(not(feature = "no_index"))]
+use crate::stdlib::{iter::FromIterator, vec::IntoIter};
+
 #[cfg(not(feature = "no_closure"))]
 #[cfg(not(feature = "sync"))]
 use crate::stdlib::{
@@ -38,6 +40,9 @@ use crate::stdlib::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
 #[cfg(not(feature = "no_object"))]
 use crate::stdlib::collections::HashMap;
 
+#[cfg(not(feature = "no_object"))]
+use indexmap::IndexMap;
+
 #[cfg(not(feature = "no_index"))]
 use crate::stdlib::vec::Vec;
 
@@ -156,12 +161,19 @@ pub enum Union {
     Float(FLOAT),
     #[cfg(not(feature = "no_index"))]
     Array(Box<Array>),
+    /// An array whose elements must all be of the type with the wrapped name.
+    #[cfg(not(feature = "no_index"))]
+    TypedArray(Box<(Array, ImmutableString)>),
     #[cfg(not(feature = "no_object"))]
     Map(Box<Map>),
     FnPtr(Box<FnPtr>),
 
     Variant(Box<Box<dyn Variant>>),
 
+    /// A value shared by all its clones, which is copied before it is modified.
+    #[allow(dead_code)]
+    Frozen(Shared<Dynamic>),
+
     #[cfg(not(feature = "no_closure"))]
     #[cfg(not(feature = "sync"))]
     Shared(Shared<RefCell<Dynamic>>),
@@ -278,6 +290,17 @@ impl Dynamic {
         }
     }
 
+    /// Does this `Dynamic` hold a frozen value?
+    ///
+    /// See [`into_frozen`](#method.into_frozen).
+    #[inline(always)]
+    pub fn is_frozen(&self) -> bool {
+        match self.0 {
+            Union::Frozen(_) => true,
+            _ => false,
+        }
+    }
+
     /// Is the value held by this `Dynamic` a particular type?
     ///
     /// If the `Dynamic` is a Shared variant checking is performed on
@@ -293,6 +316,63 @@ impl Dynamic {
         self.type_id() == target_type_id
     }
 
+    /// Is the value held by this `Dynamic` `()`?
+    #[inline(always)]
+    pub fn is_unit(&self) -> bool {
+        self.is::<()>()
+    }
+
+    /// Is the value held by this `Dynamic` a `bool`?
+    #[inline(always)]
+    pub fn is_bool(&self) -> bool {
+        self.is::<bool>()
+    }
+
+    /// Is the value held by this `Dynamic` the system integer type `INT`?
+    #[inline(always)]
+    pub fn is_int(&self) -> bool {
+        self.is::<INT>()
+    }
+
+    /// Is the value held by this `Dynamic` the system floating-point type `FLOAT`?
+    ///
+    /// Not available under the `no_float` feature.
+    #[cfg(not(feature = "no_float"))]
+    #[inline(always)]
+    pub fn is_float(&self) -> bool {
+        self.is::<FLOAT>()
+    }
+
+    /// Is the value held by this `Dynamic` a `char`?
+    #[inline(always)]
+    pub fn is_char(&self) -> bool {
+        self.is::<char>()
+    }
+
+    /// Is the value held by this `Dynamic` a string?
+    #[inline(always)]
+    pub fn is_string(&self) -> bool {
+        self.is::<ImmutableString>()
+    }
+
+    /// Is the value held by this `Dynamic` an `Array`?
+    ///
+    /// Not available under the `no_index` feature.
+    #[cfg(not(feature = "no_index"))]
+    #[inline(always)]
+    pub fn is_array(&self) -> bool {
+        self.is::<Array>()
+    }
+
+    /// Is the value held by this `Dynamic` an object `Map`?
+    ///
+    /// Not available under the `no_object` feature.
+    #[cfg(not(feature = "no_object"))]
+    #[inline(always)]
+    pub fn is_map(&self) -> bool {
+        self.is::<Map>()
+    }
+
     /// Get the TypeId of the value held by this `Dynamic`.
     ///
     /// # Panics or Deadlocks When Value is Shared
@@ -309,12 +389,13 @@ impl Dynamic {
             #[cfg(not(feature = "no_float"))]
             Union::Float(_) => TypeId::of::<FLOAT>(),
             #[cfg(not(feature = "no_index"))]
-            Union::Array(_) => TypeId::of::<Array>(),
+            Union::Array(_) | Union::TypedArray(_) => TypeId::of::<Array>(),
             #[cfg(not(feature = "no_object"))]
             Union::Map(_) => TypeId::of::<Map>(),
             Union::FnPtr(_) => TypeId::of::<FnPtr>(),
 
             Union::Variant(value) => (***value).type_id(),
+            Union::Frozen(value) => (**value).type_id(),
 
             #[cfg(not(feature = "no_closure"))]
             #[cfg(not(feature = "sync"))]
@@ -327,6 +408,9 @@ impl Dynamic {
 
     /// Get the name of the type of the value held by this `Dynamic`.
     ///
+    /// Standard types have friendly names (e.g. `"string"`, `"array"`).  A custom type has its
+    /// Rust type name; use `Engine::type_name_of` to get the name registered with an `Engine`.
+    ///
     /// # Panics or Deadlocks When Value is Shared
     ///
     /// Under the `sync` feature, this call may deadlock, or [panic](https://doc.rust-lang.org/std/sync/struct.RwLock.html#panics-1).
@@ -341,7 +425,7 @@ impl Dynamic {
             #[cfg(not(feature = "no_float"))]
             Union::Float(_) => type_name::<FLOAT>(),
             #[cfg(not(feature = "no_index"))]
-            Union::Array(_) => "array",
+            Union::Array(_) | Union::TypedArray(_) => "array",
             #[cfg(not(feature = "no_object"))]
             Union::Map(_) => "map",
             Union::FnPtr(_) => "Fn",
@@ -349,6 +433,7 @@ impl Dynamic {
             #[cfg(not(feature = "no_std"))]
             Union::Variant(value) if value.is::<Instant>() => "timestamp",
             Union::Variant(value) => (***value).type_name(),
+            Union::Frozen(value) => (**value).type_name(),
 
             #[cfg(not(feature = "no_closure"))]
             #[cfg(not(feature = "sync"))]
@@ -374,6 +459,10 @@ pub(crate) fn map_std_type_name(name: &str) -> &str {
         "string"
     } else if name == type_name::<FnPtr>() {
         "Fn"
+    } else if name == type_name::<CaughtError>() {
+        "error"
+    } else if name == type_name::<ExternalIterator>() {
+        "iterator"
     } else {
         #[cfg(not(feature = "no_std"))]
         if name == type_name::<Instant>() {
@@ -387,11 +476,25 @@ pub(crate) fn map_std_type_name(name: &str) -> &str {
         if name == type_name::<Map>() {
             return "map";
         }
+        if is_range_type_name(name) {
+            return "range";
+        }
 
         name
     }
 }
 
+/// Is the type name that of an integer range, as returned by the `range` function?
+fn is_range_type_name(name: &str) -> bool {
+    macro_rules! range_types {
+        ($($t:ty),*) => {
+            $(name == type_name::<Range<$t>>() || name == type_name::<StepRange<$t>>())||*
+        };
+    }
+
+    range_types!(i8, u8, i16, u16, i32, u32, i64, u64, i128, u128)
+}
+
 impl fmt::Display for Dynamic {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self.0 {
@@ -404,6 +507,8 @@ impl fmt::Display for Dynamic {
             Union::Float(value) => fmt::Display::fmt(value, f),
             #[cfg(not(feature = "no_index"))]
             Union::Array(value) => fmt::Debug::fmt(value, f),
+            #[cfg(not(feature = "no_index"))]
+            Union::TypedArray(value) => fmt::Debug::fmt(&value.0, f),
             #[cfg(not(feature = "no_object"))]
             Union::Map(value) => {
                 f.write_str("#")?;
@@ -414,6 +519,7 @@ impl fmt::Display for Dynamic {
             #[cfg(not(feature = "no_std"))]
             Union::Variant(value) if value.is::<Instant>() => f.write_str("<timestamp>"),
             Union::Variant(value) => f.write_str((*value).type_name()),
+            Union::Frozen(value) => fmt::Display::fmt(&**value, f),
 
             #[cfg(not(feature = "no_closure"))]
             #[cfg(not(feature = "sync"))]
@@ -443,6 +549,8 @@ impl fmt::Debug for Dynamic {
             Union::Float(value) => fmt::Debug::fmt(value, f),
             #[cfg(not(feature = "no_index"))]
             Union::Array(value) => fmt::Debug::fmt(value, f),
+            #[cfg(not(feature = "no_index"))]
+            Union::TypedArray(value) => fmt::Debug::fmt(&value.0, f),
             #[cfg(not(feature = "no_object"))]
             Union::Map(value) => {
                 f.write_str("#")?;
@@ -453,6 +561,7 @@ impl fmt::Debug for Dynamic {
             #[cfg(not(feature = "no_std"))]
             Union::Variant(value) if value.is::<Instant>() => write!(f, "<timestamp>"),
             Union::Variant(value) => write!(f, "{}", (*value).type_name()),
+            Union::Frozen(value) => fmt::Debug::fmt(&**value, f),
 
             #[cfg(not(feature = "no_closure"))]
             #[cfg(not(feature = "sync"))]
@@ -482,11 +591,14 @@ impl Clone for Dynamic {
             Union::Float(value) => Self(Union::Float(value)),
             #[cfg(not(feature = "no_index"))]
             Union::Array(ref value) => Self(Union::Array(value.clone())),
+            #[cfg(not(feature = "no_index"))]
+            Union::TypedArray(ref value) => Self(Union::TypedArray(value.clone())),
             #[cfg(not(feature = "no_object"))]
             Union::Map(ref value) => Self(Union::Map(value.clone())),
             Union::FnPtr(ref value) => Self(Union::FnPtr(value.clone())),
 
             Union::Variant(ref value) => (***value).clone_into_dynamic(),
+            Union::Frozen(ref value) => Self(Union::Frozen(value.clone())),
 
             #[cfg(not(feature = "no_closure"))]
             Union::Shared(ref cell) => Self(Union::Shared(cell.clone())),
@@ -632,6 +744,78 @@ impl Dynamic {
         unimplemented!()
     }
 
+    /// Freeze an `Array` or object `Map` held by this `Dynamic` so that it is shared, instead of
+    /// copied, whenever the `Dynamic` is cloned - e.g. when it is assigned to a variable or passed
+    /// to a function.  Arrays and object maps nested inside it are frozen as well.
+    ///
+    /// A frozen value can be read just like the original value.  It is copied only before it is
+    /// modified (copy-on-write), and only one level at a time - nested values stay frozen until
+    /// they themselves are modified.
+    ///
+    /// Values of other types are returned as is.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(not(feature = "no_index"))]
+    /// # {
+    /// use rhai::{Array, Dynamic, INT};
+    ///
+    /// let array: Array = vec![(1 as INT).into(), (2 as INT).into()];
+    /// let table = Dynamic::from(array).into_frozen();
+    ///
+    /// assert!(table.is_frozen());
+    /// assert_eq!(table.type_name(), "array");
+    ///
+    /// // Cloning a frozen value does not copy the array
+    /// let copy = table.clone();
+    /// assert_eq!(copy.cast::<Array>().len(), 2);
+    /// # }
+    /// ```
+    pub fn into_frozen(self) -> Self {
+        match self.0 {
+            #[cfg(not(feature = "no_index"))]
+            Union::Array(arr) => Self(Union::Frozen(Shared::new(Self(Union::Array(Box::new(
+                arr.into_iter().map(Self::into_frozen).collect(),
+            )))))),
+            #[cfg(not(feature = "no_index"))]
+            Union::TypedArray(arr) => {
+                let (arr, elem_type) = *arr;
+                let arr = arr.into_iter().map(Self::into_frozen).collect();
+                Self(Union::Frozen(Shared::new(Self(Union::TypedArray(
+                    Box::new((arr, elem_type)),
+                )))))
+            }
+            #[cfg(not(feature = "no_object"))]
+            Union::Map(map) => Self(Union::Frozen(Shared::new(Self(Union::Map(Box::new(
+                map.into_iter().map(|(k, v)| (k, v.into_frozen())).collect(),
+            )))))),
+            #[cfg(not(feature = "no_closure"))]
+            Union::Shared(_) => self.flatten().into_frozen(),
+            _ => self,
+        }
+    }
+
+    /// Unfreeze the `Dynamic`.
+    ///
+    /// If the `Dynamic` is a frozen value, it returns the frozen value if there are
+    /// no outstanding references, or a copy.  Values nested inside it stay frozen.
+    #[inline(always)]
+    pub(crate) fn thaw(self) -> Self {
+        match self.0 {
+            Union::Frozen(value) => shared_try_take(value).unwrap_or_else(|v| (*v).clone()),
+            _ => self,
+        }
+    }
+
+    /// Unfreeze the `Dynamic` in place, so that it can be modified.
+    #[inline(always)]
+    pub(crate) fn thaw_mut(&mut self) {
+        if self.is_frozen() {
+            *self = mem::take(self).thaw();
+        }
+    }
+
     /// Convert the `Dynamic` value into specific type.
     ///
     /// Casting to a `Dynamic` just returns as is, but if it contains a shared value,
@@ -665,6 +849,10 @@ impl Dynamic {
             #[cfg(not(feature = "no_closure"))]
             #[cfg(feature = "sync")]
             Union::Shared(cell) => return cell.read().unwrap().clone().try_cast(),
+
+            Union::Frozen(_) if TypeId::of::<T>() != TypeId::of::<Dynamic>() => {
+                return self.thaw().try_cast()
+            }
             _ => (),
         }
 
@@ -719,6 +907,7 @@ impl Dynamic {
         if TypeId::of::<T>() == TypeId::of::<Array>() {
             return match self.0 {
                 Union::Array(value) => unsafe_cast_box::<_, T>(value).ok().map(|v| *v),
+                Union::TypedArray(value) => unsafe_try_cast(value.0),
                 _ => None,
             };
         }
@@ -923,6 +1112,13 @@ impl Dynamic {
     /// Returns `None` if the cast fails, or if the value is shared.
     #[inline(always)]
     pub(crate) fn downcast_ref<T: Variant + Clone>(&self) -> Option<&T> {
+        // Read through a frozen value
+        if let Union::Frozen(value) = &self.0 {
+            if TypeId::of::<T>() != TypeId::of::<Dynamic>() {
+                return value.downcast_ref();
+            }
+        }
+
         if TypeId::of::<T>() == TypeId::of::<INT>() {
             return match &self.0 {
                 Union::Int(value) => <dyn Any>::downcast_ref::<T>(value),
@@ -964,6 +1160,7 @@ impl Dynamic {
         if TypeId::of::<T>() == TypeId::of::<Array>() {
             return match &self.0 {
                 Union::Array(value) => <dyn Any>::downcast_ref::<T>(value.as_ref()),
+                Union::TypedArray(value) => <dyn Any>::downcast_ref::<T>(&value.0),
                 _ => None,
             };
         }
@@ -1004,6 +1201,11 @@ impl Dynamic {
     /// Returns `None` if the cast fails, or if the value is shared.
     #[inline(always)]
     pub(crate) fn downcast_mut<T: Variant + Clone>(&mut self) -> Option<&mut T> {
+        // Copy a frozen value before it is modified
+        if self.is_frozen() && TypeId::of::<T>() == Dynamic::type_id(self) {
+            self.thaw_mut();
+        }
+
         if TypeId::of::<T>() == TypeId::of::<INT>() {
             return match &mut self.0 {
                 Union::Int(value) => <dyn Any>::downcast_mut::<T>(value),
@@ -1039,6 +1241,7 @@ impl Dynamic {
         if TypeId::of::<T>() == TypeId::of::<Array>() {
             return match &mut self.0 {
                 Union::Array(value) => <dyn Any>::downcast_mut::<T>(value.as_mut()),
+                Union::TypedArray(value) => <dyn Any>::downcast_mut::<T>(&mut value.0),
                 _ => None,
             };
         }
@@ -1135,6 +1338,59 @@ impl Dynamic {
         }
     }
 
+    /// Convert the `Dynamic` into an iterator over the items of an `Array`.
+    /// If there are other references to the same array, the items are cloned.
+    /// Returns the name of the actual type if the cast fails.
+    ///
+    /// Not available under the `no_index` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::Dynamic;
+    ///
+    /// let array: Dynamic = (1..=3).map(|x| x as rhai::INT).collect();
+    ///
+    /// let total: rhai::INT = array.into_array_iter().unwrap().map(|v| v.cast::<rhai::INT>()).sum();
+    ///
+    /// assert_eq!(total, 6);
+    /// ```
+    #[cfg(not(feature = "no_index"))]
+    #[inline]
+    pub fn into_array_iter(self) -> Result<IntoIter<Dynamic>, &'static str> {
+        match self.flatten().thaw() {
+            Self(Union::Array(a)) => Ok((*a).into_iter()),
+            Self(Union::TypedArray(a)) => Ok(a.0.into_iter()),
+            value => Err(value.type_name()),
+        }
+    }
+
+    /// Create an empty array whose elements must all be of the type named `elem_type`,
+    /// as returned by `type_of`.
+    #[cfg(not(feature = "no_index"))]
+    #[inline(always)]
+    pub(crate) fn typed_array(elem_type: ImmutableString) -> Self {
+        Self(Union::TypedArray(Box::new((Default::default(), elem_type))))
+    }
+
+    /// Get the name of the type that the elements of an array must be of, if any.
+    #[cfg(not(feature = "no_index"))]
+    pub(crate) fn elem_type(&self) -> Option<ImmutableString> {
+        match &self.0 {
+            Union::TypedArray(value) => Some(value.1.clone()),
+            Union::Frozen(value) => value.elem_type(),
+
+            #[cfg(not(feature = "no_closure"))]
+            #[cfg(not(feature = "sync"))]
+            Union::Shared(cell) => cell.try_borrow().ok().and_then(|v| v.elem_type()),
+            #[cfg(not(feature = "no_closure"))]
+            #[cfg(feature = "sync")]
+            Union::Shared(cell) => cell.read().unwrap().elem_type(),
+
+            _ => None,
+        }
+    }
+
     /// Convert the `Dynamic` into `String` and return it.
     /// If there are other references to the same string, a cloned copy is returned.
     /// Returns the name of the actual type if the cast fails.
@@ -1232,6 +1488,18 @@ impl<T: Variant + Clone> From<&[T]> for Dynamic {
         )))
     }
 }
+#[cfg(not(feature = "no_index"))]
+impl<T: Into<Dynamic>> FromIterator<T> for Dynamic {
+    /// Collect an iterator into an `Array`, without building an intermediate `Vec`.
+    ///
+    /// An empty iterator yields an empty `Array`.
+    #[inline(always)]
+    fn from_iter<X: IntoIterator<Item = T>>(iter: X) -> Self {
+        Self(Union::Array(Box::new(
+            iter.into_iter().map(Into::into).collect(),
+        )))
+    }
+}
 #[cfg(not(feature = "no_object"))]
 impl<K: Into<ImmutableString>, T: Variant + Clone> From<HashMap<K, T>> for Dynamic {
     #[inline(always)]
@@ -1244,6 +1512,18 @@ impl<K: Into<ImmutableString>, T: Variant + Clone> From<HashMap<K, T>> for Dynam
         )))
     }
 }
+#[cfg(not(feature = "no_object"))]
+impl<K: Into<ImmutableString>, T: Variant + Clone, S> From<IndexMap<K, T, S>> for Dynamic {
+    #[inline(always)]
+    fn from(value: IndexMap<K, T, S>) -> Self {
+        Self(Union::Map(Box::new(
+            value
+                .into_iter()
+                .map(|(k, v)| (k.into(), Dynamic::from(v)))
+                .collect(),
+        )))
+    }
+}
 impl From<FnPtr> for Dynamic {
     #[inline(always)]
     fn from(value: FnPtr) -> Self {
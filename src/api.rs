@@ -1,15 +1,19 @@
 //! Module that defines the extern API of `Engine`.
 
 use crate::any::{Dynamic, Variant};
-use crate::engine::{Engine, Imports, State};
+use crate::debugger::DebuggerCommand;
+use crate::engine::{Engine, Imports, ReusableState, State};
 use crate::error::ParseError;
-use crate::fn_native::{IteratorFn, SendSync};
+use crate::fn_native::{CallableFunction, FnCallArgs, IteratorFn, NativeCallContext, SendSync};
 use crate::module::{FuncReturn, Module};
 use crate::optimize::OptimizationLevel;
-use crate::parser::AST;
+use crate::parser::{FnAccess, AST};
 use crate::result::EvalAltResult;
 use crate::scope::Scope;
-use crate::token::{lex, Position};
+use crate::token::{lex, lex_with_source_map, Position, SourceMap, TokenStream};
+
+#[cfg(not(feature = "no_object"))]
+use crate::type_builder::TypeBuilder;
 
 #[cfg(not(feature = "no_index"))]
 use crate::{
@@ -20,17 +24,31 @@ use crate::{
 #[cfg(not(feature = "no_object"))]
 use crate::{
     engine::{make_getter, make_setter, Map},
-    error::ParseErrorType,
     token::Token,
 };
 
+#[cfg(any(not(feature = "no_object"), not(feature = "no_module")))]
+use crate::error::ParseErrorType;
+
 #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
 use crate::fn_register::{RegisterFn, RegisterResultFn};
 
+#[cfg(not(feature = "no_module"))]
+use crate::{
+    fn_native::Shared,
+    module::LazyModule,
+    scope::{Entry as ScopeEntry, EntryType as ScopeEntryType},
+    stdlib::collections::HashMap,
+};
+
+#[cfg(not(feature = "no_module"))]
+#[cfg(not(feature = "no_function"))]
+use crate::stdlib::format;
+
 #[cfg(not(feature = "no_function"))]
 use crate::{
     engine::get_script_function_by_signature, fn_args::FuncArgs, fn_call::ensure_no_data_race,
-    utils::StaticVec,
+    fn_native::FnPtr, utils::StaticVec,
 };
 
 #[cfg(not(feature = "no_optimize"))]
@@ -66,20 +84,51 @@ impl Engine {
     /// Notice that this will _consume_ the argument, replacing it with `()`.
     ///
     /// To access the first mutable parameter, use `args.get_mut(0).unwrap()`
+    ///
+    /// If there are more parameters than allowed by `Engine::set_max_fn_params`, calling the function
+    /// fails with `EvalAltResult::ErrorDataTooLarge`.
     #[deprecated(note = "this function is volatile and may change")]
     pub fn register_raw_fn<T: Variant + Clone>(
         &mut self,
         name: &str,
         arg_types: &[TypeId],
-        func: impl Fn(&Engine, &Module, &mut [&mut Dynamic]) -> FuncReturn<T> + SendSync + 'static,
+        func: impl Fn(&mut NativeCallContext, &mut [&mut Dynamic]) -> FuncReturn<T> + SendSync + 'static,
     ) -> &mut Self {
-        self.global_module.set_raw_fn(name, arg_types, func);
+        let f = move |context: &mut NativeCallContext, args: &mut FnCallArgs| {
+            func(context, args).map(Dynamic::from)
+        };
+        let func = self.check_num_params(
+            name,
+            arg_types.len(),
+            CallableFunction::from_method(Box::new(f)),
+        );
+        self.global_module
+            .set_fn(name, FnAccess::Public, arg_types, func);
+        self
+    }
+
+    /// Register a plugin function with the `Engine`.
+    ///
+    /// This function should not be used directly.  Use the `register_exported_fn!` macro instead.
+    #[doc(hidden)]
+    pub fn register_plugin_fn(
+        &mut self,
+        name: &str,
+        arg_types: &[TypeId],
+        func: CallableFunction,
+    ) -> &mut Self {
+        let func = self.check_num_params(name, arg_types.len(), func);
+        self.global_module
+            .set_fn(name, FnAccess::Public, arg_types, func);
         self
     }
 
     /// Register a custom type for use with the `Engine`.
     /// The type must implement `Clone`.
     ///
+    /// Returns a [`TypeBuilder`] that can be used to register methods, property getters/setters
+    /// and indexers for the type.
+    ///
     /// # Example
     ///
     /// ```
@@ -114,7 +163,7 @@ impl Engine {
     /// # }
     /// ```
     #[cfg(not(feature = "no_object"))]
-    pub fn register_type<T: Variant + Clone>(&mut self) -> &mut Self {
+    pub fn register_type<T: Variant + Clone>(&mut self) -> TypeBuilder<'_, T> {
         self.register_type_with_name::<T>(type_name::<T>())
     }
 
@@ -162,7 +211,10 @@ impl Engine {
     /// # }
     /// ```
     #[cfg(not(feature = "no_object"))]
-    pub fn register_type_with_name<T: Variant + Clone>(&mut self, name: &str) -> &mut Self {
+    pub fn register_type_with_name<T: Variant + Clone>(
+        &mut self,
+        name: &str,
+    ) -> TypeBuilder<'_, T> {
         if self.type_names.is_none() {
             self.type_names = Some(Default::default());
         }
@@ -171,7 +223,39 @@ impl Engine {
             .as_mut()
             .unwrap()
             .insert(type_name::<T>().into(), name.into());
-        self
+        TypeBuilder::new(self)
+    }
+
+    /// Get the friendly name of the type of a `Dynamic` value, as returned by `type_of` in scripts.
+    ///
+    /// A custom type registered via `register_type_with_name` has its registered name.
+    /// Otherwise, the name of the Rust type is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(not(feature = "no_object"))]
+    /// # {
+    /// #[derive(Debug, Clone)]
+    /// struct TestStruct;
+    ///
+    /// use rhai::{Dynamic, Engine};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// let value = Dynamic::from(TestStruct);
+    ///
+    /// // Without a registered name, the Rust type name is used
+    /// assert_eq!(engine.type_name_of(&value), std::any::type_name::<TestStruct>());
+    ///
+    /// engine.register_type_with_name::<TestStruct>("Test");
+    /// assert_eq!(engine.type_name_of(&value), "Test");
+    ///
+    /// assert_eq!(engine.type_name_of(&Dynamic::from("hello")), "string");
+    /// # }
+    /// ```
+    pub fn type_name_of(&self, value: &Dynamic) -> &str {
+        self.map_type_name(value.type_name())
     }
 
     /// Register an iterator adapter for a type with the `Engine`.
@@ -769,6 +853,46 @@ impl Engine {
             .register_indexer_set(setter)
     }
 
+    /// Register a module under a path, to be built by a factory function the first time
+    /// it is imported.
+    ///
+    /// The factory function is called at most once; the module it builds is kept for later imports.
+    /// Under the `sync` feature, concurrent imports wait for the module to be built.
+    ///
+    /// A module registered this way takes precedence over the module resolver.
+    /// Registering another module under the same path replaces it.
+    ///
+    /// Not available under the `no_module` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, Module};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.register_static_module_lazy("math", || {
+    ///     let mut module = Module::new();
+    ///     module.set_fn_1("double", |x: i64| Ok(x * 2));
+    ///     module
+    /// });
+    ///
+    /// assert_eq!(engine.eval::<i64>(r#"import "math" as m; m::double(21)"#)?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "no_module"))]
+    pub fn register_static_module_lazy(
+        &mut self,
+        path: impl Into<String>,
+        factory: impl Fn() -> Module + SendSync + 'static,
+    ) -> &mut Self {
+        self.lazy_modules
+            .insert(path.into(), LazyModule::new(Box::new(factory)));
+        self
+    }
+
     /// Compile a string into an `AST`, which can be used later for evaluation.
     ///
     /// # Example
@@ -888,6 +1012,151 @@ impl Engine {
         self.compile_with_scope_and_optimization_level(scope, scripts, self.optimization_level)
     }
 
+    /// Compile a list of scripts into a single `Module` with the given name.
+    ///
+    /// Each script is a tuple of a name (e.g. the file name, used in error messages) and
+    /// the script text.
+    ///
+    /// The public functions defined in all the scripts are merged into the module.
+    /// The statements at global level of each script are run once, and all constants (plus any
+    /// variables exported via `export`) are added to the module as variables.
+    ///
+    /// Each script is compiled and run on its own, so a script cannot see the functions or
+    /// constants of another script.  `private` functions are never added to the module, so
+    /// private functions with the same name in different scripts do not clash.
+    ///
+    /// A public function (with the same name and number of parameters) or a constant that is
+    /// defined in more than one script is an error (`ParseErrorType::DuplicatedDefinition`) that
+    /// names both scripts.  Errors are wrapped in `EvalAltResult::ErrorInModule` with the names of
+    /// the module and of the script that contains them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, Shared, module_resolvers::StaticModuleResolver};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// let module = engine.compile_into_module(&[
+    ///     ("math.rhai", "const SCALE = 10; fn scale(x) { x * 10 }"),
+    ///     ("text.rhai", r#"fn greet(name) { "Hello, " + name + "!" }"#),
+    /// ], "lib")?;
+    ///
+    /// let mut resolver = StaticModuleResolver::new();
+    /// resolver.insert("lib", Shared::try_unwrap(module).unwrap());
+    /// engine.set_module_resolver(Some(resolver));
+    ///
+    /// assert_eq!(engine.eval::<i64>(r#"import "lib" as lib; lib::scale(4) + lib::SCALE"#)?, 50);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "no_module"))]
+    pub fn compile_into_module(
+        &self,
+        scripts: &[(&str, &str)],
+        module_name: &str,
+    ) -> Result<Shared<Module>, Box<EvalAltResult>> {
+        let mut module = Module::new();
+        #[cfg(not(feature = "no_function"))]
+        let mut functions: HashMap<(String, usize), &str> = HashMap::new();
+        let mut variables: HashMap<String, &str> = HashMap::new();
+
+        let duplicated = |source: &str, name: String, first: &str, pos: Position| {
+            EvalAltResult::ErrorInModule(
+                module_name.into(),
+                source.into(),
+                Box::new(EvalAltResult::ErrorParsing(
+                    ParseErrorType::DuplicatedDefinition(name, first.into()),
+                    pos,
+                )),
+                Position::none(),
+            )
+        };
+
+        for &(source, script) in scripts {
+            let in_module = |err| {
+                EvalAltResult::ErrorInModule(
+                    module_name.into(),
+                    source.into(),
+                    err,
+                    Position::none(),
+                )
+            };
+
+            let ast = self.compile(script).map_err(|err| in_module(err.into()))?;
+
+            // Check for public functions already defined in other scripts
+            #[cfg(not(feature = "no_function"))]
+            for f in ast
+                .lib()
+                .iter_script_fn()
+                .filter(|f| f.access == FnAccess::Public)
+            {
+                let key = (f.name.to_string(), f.params.len());
+
+                if let Some(first) = functions.get(&key) {
+                    let signature = format!("{}({})", f.name, f.params.join(", "));
+                    return Err(duplicated(source, signature, first, f.pos).into());
+                }
+
+                functions.insert(key, source);
+            }
+
+            // Run the script
+            let mut scope = Scope::new();
+            self.eval_ast_with_scope_raw(&mut scope, &mut Imports::new(), &ast)
+                .map_err(in_module)?;
+
+            for ScopeEntry {
+                name,
+                typ,
+                value,
+                alias,
+                ..
+            } in scope.into_iter()
+            {
+                // Constants and exported variables become module variables
+                let name = match (typ, alias) {
+                    (_, Some(alias)) => *alias,
+                    (ScopeEntryType::Constant, None) => name.into_owned(),
+                    (ScopeEntryType::Normal, None) => continue,
+                };
+
+                if let Some(first) = variables.get(&name) {
+                    if *first != source {
+                        return Err(duplicated(source, name, first, Position::none()).into());
+                    }
+                }
+
+                variables.insert(name.clone(), source);
+                module.set_var(name, value);
+            }
+
+            // Functions call other functions (including private ones) in the same script only,
+            // and private functions are never exported from the module
+            #[cfg(not(feature = "no_function"))]
+            {
+                let lib = Shared::new(ast.lib().clone());
+
+                lib.iter_script_fn().for_each(|f| {
+                    // Never replace a public function of another script with a private one
+                    if f.access == FnAccess::Private
+                        && module.get_fn(&f.name, f.params.len()).is_some()
+                    {
+                        return;
+                    }
+
+                    let mut fn_def = (*f).clone();
+                    fn_def.lib = Some(lib.clone());
+                    module.set_script_fn(fn_def);
+                });
+            }
+        }
+
+        Ok(Shared::new(module))
+    }
+
     /// Join a list of strings and compile into an `AST` using own scope at a specific optimization level.
     pub(crate) fn compile_with_scope_and_optimization_level(
         &self,
@@ -895,8 +1164,23 @@ impl Engine {
         scripts: &[&str],
         optimization_level: OptimizationLevel,
     ) -> Result<AST, ParseError> {
-        let stream = lex(scripts, None, self);
-        self.parse(&mut stream.peekable(), scope, optimization_level)
+        self.lex_source(scripts, |stream| {
+            self.parse(stream, scope, optimization_level)
+        })
+    }
+
+    /// Run a list of script segments through the source pre-processor (if any), tokenize the result
+    /// and pass the token stream to a parsing function.
+    fn lex_source<T>(&self, scripts: &[&str], parse: impl FnOnce(&mut TokenStream) -> T) -> T {
+        match self.source_preprocessor {
+            Some(ref preprocess) => {
+                let (text, source_map) = preprocess(&scripts.concat());
+                let input = [text.as_str()];
+                let mut stream = lex_with_source_map(&input, source_map, None, self).peekable();
+                parse(&mut stream)
+            }
+            None => parse(&mut lex(scripts, None, self).peekable()),
+        }
     }
 
     /// Read the contents of a file into a string.
@@ -1132,12 +1416,9 @@ impl Engine {
         scope: &Scope,
         script: &str,
     ) -> Result<AST, ParseError> {
-        let scripts = [script];
-        let stream = lex(&scripts, None, self);
-        {
-            let mut peekable = stream.peekable();
-            self.parse_global_expr(&mut peekable, scope, self.optimization_level)
-        }
+        self.lex_source(&[script], |stream| {
+            self.parse_global_expr(stream, scope, self.optimization_level)
+        })
     }
 
     /// Evaluate a script file.
@@ -1287,11 +1568,10 @@ impl Engine {
         scope: &mut Scope,
         script: &str,
     ) -> Result<T, Box<EvalAltResult>> {
-        let scripts = [script];
-        let stream = lex(&scripts, None, self);
-
         // No need to optimize a lone expression
-        let ast = self.parse_global_expr(&mut stream.peekable(), scope, OptimizationLevel::None)?;
+        let ast = self.lex_source(&[script], |stream| {
+            self.parse_global_expr(stream, scope, OptimizationLevel::None)
+        })?;
 
         self.eval_ast_with_scope(scope, &ast)
     }
@@ -1353,7 +1633,67 @@ impl Engine {
         ast: &AST,
     ) -> Result<T, Box<EvalAltResult>> {
         let mut mods = Imports::new();
-        let (result, _) = self.eval_ast_with_scope_raw(scope, &mut mods, ast)?;
+        let result = self.eval_ast_with_scope_raw(scope, &mut mods, ast);
+        let (result, _) = self.end_eval(scope, result)?;
+
+        let typ = self.map_type_name(result.type_name());
+
+        return result.try_cast::<T>().ok_or_else(|| {
+            EvalAltResult::ErrorMismatchOutputType(
+                self.map_type_name(type_name::<T>()).into(),
+                typ.into(),
+                Position::none(),
+            )
+            .into()
+        });
+    }
+
+    /// Evaluate an `AST` with a set of inputs, reusing the buffers in a `ReusableState`
+    /// from previous runs.
+    ///
+    /// The scope in the `ReusableState` is cleared (without freeing its memory) and filled
+    /// with the inputs, in order, before the `AST` is evaluated.  The result is exactly the same
+    /// as evaluating the `AST` with `eval_ast_with_scope` on a new `Scope` holding the same inputs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, ReusableState};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// // Compile a script to an AST and store it for later evaluation
+    /// let ast = engine.compile("let z = x + y; z")?;
+    ///
+    /// // Create the state once
+    /// let mut state = ReusableState::new();
+    ///
+    /// // Evaluate it with different inputs
+    /// assert_eq!(engine.eval_reuse::<i64>(&ast, &mut state, vec![("x", 40_i64.into()), ("y", 2_i64.into())])?, 42);
+    /// assert_eq!(engine.eval_reuse::<i64>(&ast, &mut state, vec![("x", 1_i64.into()), ("y", 2_i64.into())])?, 3);
+    ///
+    /// // The scope holds the variables of the last run
+    /// assert_eq!(state.scope().get_value::<i64>("z").expect("variable z should exist"), 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn eval_reuse<T: Variant + Clone>(
+        &self,
+        ast: &AST,
+        state: &mut ReusableState,
+        inputs: impl IntoIterator<Item = (&'static str, Dynamic)>,
+    ) -> Result<T, Box<EvalAltResult>> {
+        state.reset();
+
+        let ReusableState { scope, mods } = state;
+
+        inputs.into_iter().for_each(|(name, value)| {
+            scope.push_dynamic(name, value);
+        });
+
+        let result = self.eval_ast_with_scope_raw(scope, mods, ast);
+        let (result, _) = self.end_eval(scope, result)?;
 
         let typ = self.map_type_name(result.type_name());
 
@@ -1388,6 +1728,19 @@ impl Engine {
             .map(|v| (v, state.operations))
     }
 
+    /// Call the callback registered with `on_eval_end` (if any) at the end of a top-level
+    /// evaluation, passing through its result.
+    pub(crate) fn end_eval<T>(
+        &self,
+        scope: &mut Scope,
+        result: Result<T, Box<EvalAltResult>>,
+    ) -> Result<T, Box<EvalAltResult>> {
+        if let Some(ref callback) = self.eval_end {
+            callback(scope);
+        }
+        result
+    }
+
     /// Evaluate a file, but throw away the result and only return error (if any).
     /// Useful for when you don't need the result, but still need to keep track of possible errors.
     #[cfg(not(feature = "no_std"))]
@@ -1421,9 +1774,9 @@ impl Engine {
         scope: &mut Scope,
         script: &str,
     ) -> Result<(), Box<EvalAltResult>> {
-        let scripts = [script];
-        let stream = lex(&scripts, None, self);
-        let ast = self.parse(&mut stream.peekable(), scope, self.optimization_level)?;
+        let ast = self.lex_source(&[script], |stream| {
+            self.parse(stream, scope, self.optimization_level)
+        })?;
         self.consume_ast_with_scope(scope, &ast)
     }
 
@@ -1443,7 +1796,8 @@ impl Engine {
         let mut state = State::new();
         let mut mods = Default::default();
 
-        ast.statements()
+        let result = ast
+            .statements()
             .iter()
             .try_fold(().into(), |_, stmt| {
                 self.eval_stmt(scope, &mut mods, &mut state, ast.lib(), &mut None, stmt, 0)
@@ -1454,7 +1808,66 @@ impl Engine {
                     err => Err(Box::new(err)),
                 },
                 |_| Ok(()),
-            )
+            );
+
+        self.end_eval(scope, result)
+    }
+
+    /// Run an `AST` purely for its side effects, discarding the result.
+    ///
+    /// Unlike `eval_ast::<()>`, this never fails because of the value of the last statement.
+    /// This is the same as `consume_ast`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::Engine;
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let ast = engine.compile("let x = 40; x + 2")?;
+    ///
+    /// // The value of the last statement is ignored
+    /// engine.run(&ast)?;
+    ///
+    /// // ... while 'eval_ast' insists on getting the requested type
+    /// assert!(engine.eval_ast::<()>(&ast).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn run(&self, ast: &AST) -> Result<(), Box<EvalAltResult>> {
+        self.consume_ast(ast)
+    }
+
+    /// Run an `AST` with own scope purely for its side effects, discarding the result.
+    ///
+    /// Unlike `eval_ast_with_scope::<()>`, this never fails because of the value of the last statement.
+    /// This is the same as `consume_ast_with_scope`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, Scope};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let ast = engine.compile("x = 42; x")?;
+    ///
+    /// let mut scope = Scope::new();
+    /// scope.push("x", 0_i64);
+    ///
+    /// engine.run_with_scope(&mut scope, &ast)?;
+    ///
+    /// assert_eq!(scope.get_value::<i64>("x").expect("variable x should exist"), 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn run_with_scope(&self, scope: &mut Scope, ast: &AST) -> Result<(), Box<EvalAltResult>> {
+        self.consume_ast_with_scope(scope, ast)
     }
 
     /// Call a script function defined in an `AST` with multiple arguments.
@@ -1504,7 +1917,8 @@ impl Engine {
         let mut arg_values = args.into_vec();
         let mut args: StaticVec<_> = arg_values.as_mut().iter_mut().collect();
 
-        let result = self.call_fn_dynamic_raw(scope, ast, name, &mut None, args.as_mut())?;
+        let result = self.call_fn_dynamic_raw(scope, ast, name, &mut None, args.as_mut());
+        let result = self.end_eval(scope, result)?;
 
         let typ = self.map_type_name(result.type_name());
 
@@ -1578,7 +1992,8 @@ impl Engine {
     ) -> FuncReturn<Dynamic> {
         let mut args: StaticVec<_> = arg_values.as_mut().iter_mut().collect();
 
-        self.call_fn_dynamic_raw(scope, lib, name, &mut this_ptr, args.as_mut())
+        let result = self.call_fn_dynamic_raw(scope, lib, name, &mut this_ptr, args.as_mut());
+        self.end_eval(scope, result)
     }
 
     /// Call a script function defined in an `AST` with multiple `Dynamic` arguments.
@@ -1615,6 +2030,58 @@ impl Engine {
         )
     }
 
+    /// Call a function pointer with multiple arguments, with curried arguments (if any) passed first.
+    /// Arguments are passed as a tuple.
+    ///
+    /// Script-defined functions are looked up in `lib`, which is usually the `Module` that the
+    /// function pointer was obtained from via `Module::get_fn`.  Private functions cannot be called.
+    ///
+    /// Each call starts a new evaluation with fresh states.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// # #[cfg(not(feature = "no_function"))]
+    /// # {
+    /// use rhai::{Engine, FnPtr};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let ast = engine.compile("fn add(x, y) { x + y }")?;
+    /// let add = engine.eval_ast::<FnPtr>(&(&ast + &engine.compile("Fn(\"add\").curry(40)")?))?;
+    ///
+    /// for _ in 0..3 {
+    ///     assert_eq!(engine.call_fn_ptr::<_, i64>(&ast, &add, (2_i64,))?, 42);
+    /// }
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "no_function"))]
+    pub fn call_fn_ptr<A: FuncArgs, T: Variant + Clone>(
+        &self,
+        lib: impl AsRef<Module>,
+        fn_ptr: &FnPtr,
+        args: A,
+    ) -> Result<T, Box<EvalAltResult>> {
+        let mut arg_values = args.into_vec();
+
+        let result = fn_ptr.call_dynamic(self, lib, None, arg_values.as_mut());
+        let result = self.end_eval(&mut Scope::new(), result)?;
+
+        let typ = self.map_type_name(result.type_name());
+
+        result.try_cast().ok_or_else(|| {
+            EvalAltResult::ErrorMismatchOutputType(
+                self.map_type_name(type_name::<T>()).into(),
+                typ.into(),
+                Position::none(),
+            )
+            .into()
+        })
+    }
+
     /// Optimize the `AST` with constants defined in an external Scope.
     /// An optimized copy of the `AST` is returned while the original `AST` is consumed.
     ///
@@ -1689,6 +2156,344 @@ impl Engine {
         self
     }
 
+    /// Register a callback that is called with the `Position` of each statement evaluated.
+    ///
+    /// This is useful for calculating script coverage.  Statements are reported from the
+    /// _optimized_ `AST`, so statements removed by script optimization never appear.
+    /// Use `AST::statement_positions` to get all the statements that can be reported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// # use std::sync::RwLock;
+    /// # use std::sync::Arc;
+    /// use std::collections::HashSet;
+    /// use rhai::Engine;
+    ///
+    /// let covered = Arc::new(RwLock::new(HashSet::new()));
+    /// let logger = covered.clone();
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.on_statement_executed(move |pos| {
+    ///     logger.write().unwrap().insert(pos.line().unwrap());
+    /// });
+    ///
+    /// let ast = engine.compile("let x = 0;\nif x > 0 {\n    x = 1;\n}")?;
+    ///
+    /// engine.consume_ast(&ast)?;
+    ///
+    /// let uncovered: Vec<_> = ast.statement_positions()
+    ///                             .iter()
+    ///                             .map(|pos| pos.line().unwrap())
+    ///                             .filter(|line| !covered.read().unwrap().contains(line))
+    ///                             .collect();
+    ///
+    /// assert_eq!(uncovered, vec![3]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn on_statement_executed(
+        &mut self,
+        callback: impl Fn(&Position) + SendSync + 'static,
+    ) -> &mut Self {
+        self.statement_executed = Some(Box::new(callback));
+        self
+    }
+
+    /// Register a step-through debugger callback.
+    ///
+    /// The callback is invoked before a statement is evaluated with the current `Scope` and the
+    /// statement's `Position`, whenever the statement is on a line with a breakpoint or when
+    /// single-stepping.  The `DebuggerCommand` returned determines where evaluation breaks next.
+    ///
+    /// The debugger always breaks at the first statement of each evaluation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// # use std::sync::RwLock;
+    /// # use std::sync::Arc;
+    /// use rhai::{DebuggerCommand, Engine};
+    ///
+    /// let result = Arc::new(RwLock::new(Vec::new()));
+    /// let logger = result.clone();
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.register_debugger(move |scope, pos| {
+    ///     logger.write().unwrap().push((pos.line().unwrap(), scope.len()));
+    ///     DebuggerCommand::StepInto
+    /// });
+    ///
+    /// engine.consume(
+    ///     r"
+    ///         let x = 40;
+    ///         let y = 2;
+    ///     ",
+    /// )?;
+    ///
+    /// assert_eq!(*result.read().unwrap(), vec![(2, 0), (3, 1)]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn register_debugger(
+        &mut self,
+        callback: impl Fn(&Scope, Position) -> DebuggerCommand + SendSync + 'static,
+    ) -> &mut Self {
+        self.debugger = Some(Box::new(callback));
+        self
+    }
+
+    /// Set a breakpoint for the debugger at a line number (1-based).
+    pub fn set_breakpoint(&mut self, line: usize) -> &mut Self {
+        self.breakpoints.insert(line);
+        self
+    }
+
+    /// Remove a breakpoint for the debugger.
+    pub fn remove_breakpoint(&mut self, line: usize) -> &mut Self {
+        self.breakpoints.remove(&line);
+        self
+    }
+
+    /// Remove all breakpoints for the debugger.
+    pub fn clear_breakpoints(&mut self) -> &mut Self {
+        self.breakpoints.clear();
+        self
+    }
+
+    /// Register a callback that is called when a named function call cannot be resolved.
+    ///
+    /// The callback is invoked with the function name and the arguments (for method calls, the
+    /// object is the first argument) after all normal lookups fail.  It returns `Ok(Some(value))`
+    /// to supply the result of the call, `Ok(None)` to raise the standard function-not-found error,
+    /// or an error.
+    ///
+    /// The callback is only called for failed function calls and method calls.  Operators,
+    /// property getters/setters, indexers and module-qualified function calls never reach it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Dynamic, Engine, INT};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.on_missing_fn(|name, args| match name {
+    ///     "answer" => Ok(Some(Dynamic::from(42 as INT))),
+    ///     "count_args" => Ok(Some(Dynamic::from(args.len() as INT))),
+    ///     _ => Ok(None),
+    /// });
+    ///
+    /// assert_eq!(engine.eval::<INT>("answer()")?, 42);
+    /// assert_eq!(engine.eval::<INT>("count_args(1, true, 'x')")?, 3);
+    /// assert!(engine.eval::<INT>("unknown()").is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn on_missing_fn(
+        &mut self,
+        callback: impl Fn(&str, &mut [&mut Dynamic]) -> Result<Option<Dynamic>, Box<EvalAltResult>>
+            + SendSync
+            + 'static,
+    ) -> &mut Self {
+        self.missing_fn = Some(Box::new(callback));
+        self
+    }
+
+    /// Register a callback that is called at the end of each top-level evaluation, with the
+    /// scope used in the evaluation.
+    ///
+    /// This can be used to clean up resources (such as open files) held by values in the scope.
+    ///
+    /// The callback is called by all the `eval`, `consume` and `call_fn` methods after the
+    /// script finishes running, whether it succeeded, failed with an error or was terminated
+    /// (e.g. by exceeding a limit).  It is not called if the script fails to compile.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, Scope, INT};
+    /// use std::sync::{Arc, RwLock};
+    ///
+    /// let total = Arc::new(RwLock::new(0));
+    /// let logger = total.clone();
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.on_eval_end(move |scope| {
+    ///     *logger.write().unwrap() += scope.get_value::<INT>("x").unwrap_or(0);
+    /// });
+    ///
+    /// let mut scope = Scope::new();
+    /// scope.push("x", 40 as INT);
+    ///
+    /// engine.consume_with_scope(&mut scope, "x += 1;")?;
+    /// assert!(engine.eval_with_scope::<INT>(&mut scope, "x += 1; throw x;").is_err());
+    ///
+    /// // The callback ran at the end of both evaluations
+    /// assert_eq!(*total.read().unwrap(), 41 + 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn on_eval_end(&mut self, callback: impl Fn(&mut Scope) + SendSync + 'static) -> &mut Self {
+        self.eval_end = Some(Box::new(callback));
+        self
+    }
+
+    /// Register a callback that is called when a script calls a plugin function marked with
+    /// `deprecated = "..."`, with the name of the function and the deprecation message.
+    ///
+    /// The callback is called only the first time each deprecated function is called during
+    /// an evaluation.
+    ///
+    /// Only plugin functions registered into a [`Module`] (e.g. via `set_exported_fn!` or
+    /// `#[export_module]`) are reported.  Functions registered via `register_exported_fn!`
+    /// are called directly and are not.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::plugin::*;
+    /// use rhai::{Engine, Module, INT};
+    /// use std::sync::{Arc, RwLock};
+    ///
+    /// #[export_fn(deprecated = "use 'add' instead")]
+    /// pub fn plus(x: INT, y: INT) -> INT {
+    ///     x + y
+    /// }
+    ///
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// let log = Arc::new(RwLock::new(Vec::<String>::new()));
+    /// let logger = log.clone();
+    ///
+    /// let mut module = Module::new();
+    /// set_exported_fn!(module, "plus", plus);
+    ///
+    /// let mut engine = Engine::new();
+    /// engine.load_package(module);
+    ///
+    /// engine.on_deprecated(move |name, message| {
+    ///     logger.write().unwrap().push(format!("{}: {}", name, message));
+    /// });
+    ///
+    /// assert_eq!(engine.eval::<INT>("plus(plus(1, 2), 3)")?, 6);
+    /// assert_eq!(*log.read().unwrap(), ["plus: use 'add' instead"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn on_deprecated(
+        &mut self,
+        callback: impl Fn(&str, &str) + SendSync + 'static,
+    ) -> &mut Self {
+        self.deprecated = Some(Box::new(callback));
+        self
+    }
+
+    /// Register a callback that converts number literals in scripts into a custom number type,
+    /// such as a fixed-point or decimal type.
+    ///
+    /// The callback is called during parsing with the text of each integer and floating-point literal.
+    /// Return `Some(value)` to use `value` in place of the literal, or `None` to keep the standard
+    /// `INT` or `FLOAT` value.  Negative literals are parsed as the unary `-` operator applied to
+    /// the converted value.
+    ///
+    /// Operators on the custom number type are registered as normal functions, e.g. `+`, `-`, `<`.
+    ///
+    /// Integers used as array indices, string positions or loop ranges must remain `INT`,
+    /// so return `None` for integer literals if those are needed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Dynamic, Engine, RegisterFn};
+    ///
+    /// // A fixed-point number with 2 decimal places
+    /// #[derive(Debug, Clone, Copy, PartialEq)]
+    /// struct Cents(i64);
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine
+    ///     .register_type::<Cents>()
+    ///     .with_fn("+", |a: Cents, b: Cents| Cents(a.0 + b.0))
+    ///     .with_fn("*", |a: Cents, b: Cents| Cents(a.0 * b.0 / 100));
+    ///
+    /// // Every floating-point literal becomes Cents
+    /// engine.on_number_literal(|text| {
+    ///     if text.contains('.') {
+    ///         text.parse::<f64>().ok().map(|v| Dynamic::from(Cents((v * 100.0).round() as i64)))
+    ///     } else {
+    ///         None
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(engine.eval::<Cents>("1.25 + 0.75 * 2.0")?, Cents(275));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn on_number_literal(
+        &mut self,
+        callback: impl Fn(&str) -> Option<Dynamic> + SendSync + 'static,
+    ) -> &mut Self {
+        self.number_literal = Some(Box::new(callback));
+        self
+    }
+
+    /// Register a callback that pre-processes the source text of every script before it is parsed.
+    ///
+    /// The callback receives the original script text, and returns the text to parse together with
+    /// an optional `SourceMap` that maps positions in the returned text back into the original text.
+    /// Positions in all errors, whether raised during parsing or evaluation, then refer to the original text.
+    ///
+    /// If no `SourceMap` is returned, positions refer to the pre-processed text.
+    ///
+    /// Scripts evaluated via `eval` within a script are not pre-processed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, Position, SourceMap};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// // Strip lines starting with '%' before parsing
+    /// engine.set_source_preprocessor(|text| {
+    ///     let mut output = Vec::new();
+    ///     let mut map = SourceMap::new();
+    ///
+    ///     for (line, text) in text.lines().enumerate() {
+    ///         if !text.starts_with('%') {
+    ///             output.push(text);
+    ///             map.add(Position::new(output.len() as u16, 1), Position::new(line as u16 + 1, 1));
+    ///         }
+    ///     }
+    ///
+    ///     (output.join("\n"), Some(map))
+    /// });
+    ///
+    /// assert_eq!(engine.eval::<i64>("% directive\n40 + 2")?, 42);
+    ///
+    /// let err = engine.compile("% directive\nlet x = 40 +* 2;").expect_err("should error");
+    /// assert_eq!(err.1, Position::new(2, 13));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_source_preprocessor(
+        &mut self,
+        callback: impl Fn(&str) -> (String, Option<SourceMap>) + SendSync + 'static,
+    ) -> &mut Self {
+        self.source_preprocessor = Some(Box::new(callback));
+        self
+    }
+
     /// Override default action of `print` (print to stdout using `println!`)
     ///
     /// # Example
@@ -0,0 +1,73 @@
+//! Module implementing the step-through debugger interface for `Engine`.
+
+use crate::engine::{Engine, State};
+use crate::parser::Stmt;
+use crate::result::EvalAltResult;
+use crate::scope::Scope;
+use crate::token::Position;
+
+use crate::stdlib::boxed::Box;
+
+/// A debugger callback function.
+#[cfg(not(feature = "sync"))]
+pub type OnDebuggerCallback = Box<dyn Fn(&Scope, Position) -> DebuggerCommand + 'static>;
+/// A debugger callback function.
+#[cfg(feature = "sync")]
+pub type OnDebuggerCallback =
+    Box<dyn Fn(&Scope, Position) -> DebuggerCommand + Send + Sync + 'static>;
+
+/// Command returned by a debugger callback to control script evaluation.
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
+pub enum DebuggerCommand {
+    /// Continue evaluation until the next breakpoint is hit.
+    Continue,
+    /// Break at the next statement, stepping into function calls.
+    StepInto,
+    /// Break at the next statement in the current function, stepping over function calls.
+    Break,
+    /// Terminate evaluation with `EvalAltResult::ErrorTerminated`.
+    Terminate,
+}
+
+impl Engine {
+    /// Consult the debugger, if any, before running a statement.
+    ///
+    /// Blocks and no-ops are skipped because the statements within are checked individually.
+    pub(crate) fn run_debugger(
+        &self,
+        scope: &Scope,
+        state: &mut State,
+        stmt: &Stmt,
+        level: usize,
+    ) -> Result<(), Box<EvalAltResult>> {
+        let debugger = match &self.debugger {
+            Some(debugger) => debugger,
+            None => return Ok(()),
+        };
+
+        match stmt {
+            Stmt::Noop(_) | Stmt::Block(_) => return Ok(()),
+            _ => (),
+        }
+
+        let pos = stmt.position();
+
+        let stop = match state.debugger_step {
+            Some(max_level) if level <= max_level => true,
+            _ => matches!(pos.line(), Some(line) if self.breakpoints.contains(&line)),
+        };
+
+        if !stop {
+            return Ok(());
+        }
+
+        state.debugger_step = match debugger(scope, pos) {
+            DebuggerCommand::Continue => None,
+            DebuggerCommand::StepInto => Some(usize::MAX),
+            DebuggerCommand::Break => Some(level),
+            DebuggerCommand::Terminate => return EvalAltResult::ErrorTerminated(pos).into(),
+        };
+
+        Ok(())
+    }
+}
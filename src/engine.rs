@@ -2,12 +2,18 @@
 
 use crate::any::{map_std_type_name, Dynamic, Union};
 use crate::calc_fn_hash;
+use crate::debugger::OnDebuggerCallback;
 use crate::fn_call::run_builtin_op_assignment;
-use crate::fn_native::{Callback, FnPtr};
+use crate::fn_native::{
+    shared_make_mut, CallableFunction, Callback, FnGuard, FnPtr, NativeCallContext,
+    OnDeprecatedCallback, OnEvalEndCallback, OnMissingFnCallback, OnNumberLiteralCallback,
+    OnPreprocessCallback, Shared,
+};
 use crate::module::{Module, ModuleRef};
 use crate::optimize::OptimizationLevel;
+use crate::packages::arithmetic::{is_zero, DivByZero};
 use crate::packages::{Package, PackagesCollection, StandardPackage};
-use crate::parser::{Expr, ReturnType, Stmt};
+use crate::parser::{Expr, ReturnType, Stmt, SwitchPattern};
 use crate::r#unsafe::unsafe_cast_var_name_to_lifetime;
 use crate::result::EvalAltResult;
 use crate::scope::{EntryType as ScopeEntryType, Scope};
@@ -18,19 +24,30 @@ use crate::utils::StaticVec;
 #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
 use crate::any::Variant;
 
+#[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+use crate::{parser::Pattern, stdlib::any::type_name};
+
+#[cfg(not(feature = "no_index"))]
+use crate::parser::INT;
+
 #[cfg(not(feature = "no_function"))]
 use crate::parser::ScriptFnDef;
 
+#[cfg(not(feature = "no_float"))]
+use crate::packages::arithmetic::FloatDivByZero;
+
 #[cfg(not(feature = "no_module"))]
-use crate::module::ModuleResolver;
+use crate::module::{LazyModule, ModuleResolver};
 
 #[cfg(not(feature = "no_std"))]
 #[cfg(not(feature = "no_module"))]
 use crate::module::resolvers;
 
-#[cfg(any(not(feature = "no_object"), not(feature = "no_module")))]
 use crate::utils::ImmutableString;
 
+#[cfg(not(feature = "no_object"))]
+use indexmap::IndexMap;
+
 #[cfg(not(feature = "no_closure"))]
 #[cfg(not(feature = "no_object"))]
 use crate::any::DynamicWriteLock;
@@ -40,6 +57,7 @@ use crate::stdlib::{
     boxed::Box,
     collections::{HashMap, HashSet},
     fmt, format,
+    hash::{Hash, Hasher},
     iter::{empty, once},
     ops::DerefMut,
     string::{String, ToString},
@@ -52,6 +70,12 @@ use crate::stdlib::any::TypeId;
 #[cfg(not(feature = "no_closure"))]
 use crate::stdlib::mem;
 
+#[cfg(not(feature = "no_index"))]
+use crate::stdlib::ops::Range;
+
+#[cfg(not(feature = "unchecked"))]
+use crate::stdlib::mem::size_of;
+
 /// Variable-sized array of `Dynamic` values.
 ///
 /// Not available under the `no_index` feature.
@@ -60,9 +84,21 @@ pub type Array = Vec<Dynamic>;
 
 /// Hash map of `Dynamic` values with `ImmutableString` keys.
 ///
+/// Properties are kept in insertion order, so iterating through a map is deterministic.
+///
 /// Not available under the `no_object` feature.
 #[cfg(not(feature = "no_object"))]
-pub type Map = HashMap<ImmutableString, Dynamic>;
+#[cfg(not(feature = "no_std"))]
+pub type Map = IndexMap<ImmutableString, Dynamic>;
+
+/// Hash map of `Dynamic` values with `ImmutableString` keys.
+///
+/// Properties are kept in insertion order, so iterating through a map is deterministic.
+///
+/// Not available under the `no_object` feature.
+#[cfg(not(feature = "no_object"))]
+#[cfg(feature = "no_std")]
+pub type Map = IndexMap<ImmutableString, Dynamic, ahash::RandomState>;
 
 /// [INTERNALS] A stack of imported modules.
 /// Exported under the `internals` feature only.
@@ -70,7 +106,7 @@ pub type Map = HashMap<ImmutableString, Dynamic>;
 /// ## WARNING
 ///
 /// This type is volatile and may change.
-pub type Imports<'a> = Vec<(Cow<'a, str>, Module)>;
+pub type Imports<'a> = Vec<(Cow<'a, str>, Shared<Module>)>;
 
 #[cfg(not(feature = "unchecked"))]
 #[cfg(debug_assertions)]
@@ -81,6 +117,9 @@ pub const MAX_EXPR_DEPTH: usize = 32;
 #[cfg(not(feature = "unchecked"))]
 #[cfg(debug_assertions)]
 pub const MAX_FUNCTION_EXPR_DEPTH: usize = 16;
+#[cfg(not(feature = "unchecked"))]
+#[cfg(debug_assertions)]
+pub const MAX_DATA_DEPTH: usize = 64;
 
 #[cfg(not(feature = "unchecked"))]
 #[cfg(not(debug_assertions))]
@@ -91,6 +130,13 @@ pub const MAX_EXPR_DEPTH: usize = 128;
 #[cfg(not(feature = "unchecked"))]
 #[cfg(not(debug_assertions))]
 pub const MAX_FUNCTION_EXPR_DEPTH: usize = 32;
+#[cfg(not(feature = "unchecked"))]
+#[cfg(not(debug_assertions))]
+pub const MAX_DATA_DEPTH: usize = 256;
+
+/// Native Rust functions can be registered with at most 20 parameters.
+#[cfg(not(feature = "unchecked"))]
+pub const MAX_FN_PARAMS: usize = 20;
 
 pub const KEYWORD_PRINT: &str = "print";
 pub const KEYWORD_DEBUG: &str = "debug";
@@ -102,6 +148,7 @@ pub const KEYWORD_FN_PTR_CURRY: &str = "curry";
 pub const KEYWORD_IS_SHARED: &str = "is_shared";
 pub const KEYWORD_THIS: &str = "this";
 pub const FN_TO_STRING: &str = "to_string";
+pub const FN_CONTAINS: &str = "contains";
 #[cfg(not(feature = "no_object"))]
 pub const FN_GET: &str = "get$";
 #[cfg(not(feature = "no_object"))]
@@ -292,7 +339,7 @@ impl<T: Into<Dynamic>> From<T> for Target<'_> {
 /// ## WARNING
 ///
 /// This type is volatile and may change.
-#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+#[derive(Debug, Clone)]
 pub struct State {
     /// Normally, access to variables are parsed with a relative offset into the scope to avoid a lookup.
     /// In some situation, e.g. after running an `eval` statement, subsequent offsets become mis-aligned.
@@ -305,6 +352,94 @@ pub struct State {
     pub operations: u64,
     /// Number of modules loaded.
     pub modules: usize,
+    /// Estimated memory (in bytes) used by all variables, in all function call frames.
+    #[cfg(not(feature = "unchecked"))]
+    pub memory: usize,
+    /// Imported modules with functions that can be called without qualification.
+    #[cfg(not(feature = "no_module"))]
+    pub global_modules: Vec<Shared<Module>>,
+    /// The debugger breaks at the next statement with a function call level no higher than this.
+    /// `None` if the debugger only breaks at breakpoints.
+    pub debugger_step: Option<usize>,
+    /// State of the random number generator used by the `rand` package.
+    /// `None` until a random number is first requested or the generator is seeded.
+    #[cfg(feature = "rand")]
+    pub rng: Option<u64>,
+    /// Names of deprecated functions already reported to the `on_deprecated` callback.
+    pub deprecated_fns: HashSet<String>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            always_search: false,
+            scope_level: 0,
+            operations: 0,
+            modules: 0,
+            #[cfg(not(feature = "unchecked"))]
+            memory: 0,
+            #[cfg(not(feature = "no_module"))]
+            global_modules: Vec::new(),
+            // Always break at the first statement
+            debugger_step: Some(usize::MAX),
+            #[cfg(feature = "rand")]
+            rng: None,
+            deprecated_fns: Default::default(),
+        }
+    }
+}
+
+// Imported modules are compared by identity.
+impl PartialEq for State {
+    fn eq(&self, other: &Self) -> bool {
+        #[cfg(not(feature = "unchecked"))]
+        if self.memory != other.memory {
+            return false;
+        }
+        #[cfg(not(feature = "no_module"))]
+        if self.global_modules.len() != other.global_modules.len()
+            || self
+                .global_modules
+                .iter()
+                .zip(other.global_modules.iter())
+                .any(|(a, b)| !Shared::ptr_eq(a, b))
+        {
+            return false;
+        }
+        #[cfg(feature = "rand")]
+        if self.rng != other.rng {
+            return false;
+        }
+
+        self.always_search == other.always_search
+            && self.scope_level == other.scope_level
+            && self.operations == other.operations
+            && self.modules == other.modules
+            && self.debugger_step == other.debugger_step
+            && self.deprecated_fns == other.deprecated_fns
+    }
+}
+
+impl Eq for State {}
+
+impl Hash for State {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.always_search.hash(state);
+        self.scope_level.hash(state);
+        self.operations.hash(state);
+        self.modules.hash(state);
+        #[cfg(not(feature = "unchecked"))]
+        self.memory.hash(state);
+        #[cfg(not(feature = "no_module"))]
+        self.global_modules
+            .iter()
+            .for_each(|m| (m.as_ref() as *const Module).hash(state));
+        self.debugger_step.hash(state);
+        #[cfg(feature = "rand")]
+        self.rng.hash(state);
+        // A `HashSet` has no stable iteration order
+        self.deprecated_fns.len().hash(state);
+    }
 }
 
 impl State {
@@ -313,6 +448,69 @@ impl State {
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// Find a function callable without qualification in the imported modules, latest first.
+    #[cfg(not(feature = "no_module"))]
+    pub(crate) fn get_global_fn(&self, hash_fn: u64, pub_only: bool) -> Option<CallableFunction> {
+        self.global_modules
+            .iter()
+            .rev()
+            .find_map(|m| m.get_global_fn(hash_fn, pub_only))
+            .cloned()
+    }
+}
+
+/// Buffers kept between evaluations of an `AST` by `Engine::eval_reuse`.
+///
+/// Running the same `AST` many times with fresh inputs normally builds a new `Scope` each time.
+/// A `ReusableState` is created once and cleared before every run instead, so the memory held by
+/// the scope (and by any imported modules list) is allocated only once.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+/// use rhai::{Engine, ReusableState};
+///
+/// let engine = Engine::new();
+/// let ast = engine.compile("x * 2")?;
+///
+/// let mut state = ReusableState::new();
+///
+/// for x in 0..10_i64 {
+///     let result: i64 = engine.eval_reuse(&ast, &mut state, vec![("x", x.into())])?;
+///     assert_eq!(result, x * 2);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ReusableState {
+    /// Scope holding the inputs and the global variables of the last run.
+    pub(crate) scope: Scope<'static>,
+    /// Modules imported during the last run.
+    pub(crate) mods: Imports<'static>,
+}
+
+impl ReusableState {
+    /// Create a new `ReusableState`.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Get the scope as left by the last run, including its inputs and all global variables
+    /// defined by the script.
+    #[inline(always)]
+    pub fn scope(&self) -> &Scope<'static> {
+        &self.scope
+    }
+
+    /// Clear the buffers for the next run, keeping their allocated capacity.
+    pub(crate) fn reset(&mut self) {
+        self.scope.clear();
+        self.mods.clear();
+    }
 }
 
 /// Get a script-defined function definition from a module.
@@ -325,7 +523,7 @@ pub fn get_script_function_by_signature<'a>(
 ) -> Option<&'a ScriptFnDef> {
     // Qualifiers (none) + function name + number of arguments.
     let hash_script = calc_fn_hash(empty(), name, params, empty());
-    let func = module.get_fn(hash_script, pub_only)?;
+    let func = module.get_fn_by_hash(hash_script, pub_only)?;
     if func.is_script() {
         Some(func.get_fn_def())
     } else {
@@ -354,12 +552,22 @@ pub struct Limits {
     pub max_operations: u64,
     /// Maximum number of modules allowed to load.
     pub max_modules: usize,
+    /// Maximum number of parameters of a function.
+    ///
+    /// Defaults to 20, the most parameters that a native Rust function can be registered with.
+    pub max_fn_params: usize,
     /// Maximum length of a string.
     pub max_string_size: usize,
     /// Maximum length of an array.
     pub max_array_size: usize,
     /// Maximum number of properties in a map.
     pub max_map_size: usize,
+    /// Maximum estimated memory usage (in bytes) of all variables, in all function calls.
+    pub max_memory: usize,
+    /// Maximum nesting depth of arrays and object maps.
+    ///
+    /// Defaults to 64 for debug builds and 256 for non-debug builds.
+    pub max_data_depth: usize,
 }
 
 /// Rhai main scripting engine.
@@ -384,12 +592,18 @@ pub struct Engine {
 
     /// A module containing all functions directly loaded into the Engine.
     pub(crate) global_module: Module,
+    /// Implementations of functions registered with guards, keyed by function hash,
+    /// in order of registration.
+    pub(crate) guarded_fns: HashMap<u64, Vec<(Shared<FnGuard>, CallableFunction)>>,
     /// A collection of all library packages loaded into the Engine.
     pub(crate) packages: PackagesCollection,
 
     /// A module resolution service.
     #[cfg(not(feature = "no_module"))]
     pub(crate) module_resolver: Option<Box<dyn ModuleResolver>>,
+    /// A hashmap of modules, keyed by path, that are built on first import.
+    #[cfg(not(feature = "no_module"))]
+    pub(crate) lazy_modules: HashMap<String, LazyModule>,
 
     /// A hashmap mapping type names to pretty-print names.
     pub(crate) type_names: Option<HashMap<String, String>>,
@@ -407,10 +621,45 @@ pub struct Engine {
     pub(crate) debug: Callback<str, ()>,
     /// Callback closure for progress reporting.
     pub(crate) progress: Option<Callback<u64, bool>>,
+    /// Callback closure for reporting each statement evaluated.
+    pub(crate) statement_executed: Option<Callback<Position, ()>>,
+    /// Callback closure for the step-through debugger.
+    pub(crate) debugger: Option<OnDebuggerCallback>,
+    /// Callback closure for named function calls that cannot be resolved.
+    pub(crate) missing_fn: Option<OnMissingFnCallback>,
+    /// Callback closure called at the end of each top-level evaluation.
+    pub(crate) eval_end: Option<OnEvalEndCallback>,
+    /// Callback closure for calls to deprecated plugin functions.
+    pub(crate) deprecated: Option<OnDeprecatedCallback>,
+    /// Callback closure for pre-processing the source text of scripts.
+    pub(crate) source_preprocessor: Option<OnPreprocessCallback>,
+    /// Callback closure for converting number literals into a custom number type.
+    pub(crate) number_literal: Option<OnNumberLiteralCallback>,
+    /// Line numbers of breakpoints for the debugger.
+    pub(crate) breakpoints: HashSet<usize>,
 
     /// Optimize the AST after compilation.
     pub(crate) optimization_level: OptimizationLevel,
 
+    /// Raise an error when a destructuring `let` statement cannot find a value to bind?
+    pub(crate) strict_destructuring: bool,
+    /// Raise an error when reading a variable that is not defined?
+    pub(crate) fail_on_undefined_var: bool,
+    /// Raise an error when assigning to a variable that is not defined?
+    pub(crate) fail_on_undefined_var_assign: bool,
+    /// Allow scripts to call `eval`?
+    pub(crate) allow_eval: bool,
+    /// Allow words reserved for future use as identifiers?
+    pub(crate) allow_reserved_identifiers: bool,
+    /// Call the method named after an operator when no function is found for the operator?
+    pub(crate) operator_method_fallback: bool,
+
+    /// Behavior of integer division and modulo by zero.
+    pub(crate) div_by_zero: DivByZero,
+    /// Behavior of floating-point division and modulo by zero.
+    #[cfg(not(feature = "no_float"))]
+    pub(crate) float_div_by_zero: FloatDivByZero,
+
     /// Max limits.
     #[cfg(not(feature = "unchecked"))]
     pub(crate) limits: Limits,
@@ -434,6 +683,7 @@ impl Default for Engine {
 
             packages: Default::default(),
             global_module: Default::default(),
+            guarded_fns: Default::default(),
 
             #[cfg(not(feature = "no_module"))]
             #[cfg(not(feature = "no_std"))]
@@ -442,6 +692,8 @@ impl Default for Engine {
             #[cfg(not(feature = "no_module"))]
             #[cfg(any(feature = "no_std", target_arch = "wasm32",))]
             module_resolver: None,
+            #[cfg(not(feature = "no_module"))]
+            lazy_modules: Default::default(),
 
             type_names: None,
             disabled_symbols: None,
@@ -455,6 +707,20 @@ impl Default for Engine {
             // progress callback
             progress: None,
 
+            // statement callback
+            statement_executed: None,
+
+            // debugger
+            debugger: None,
+            breakpoints: Default::default(),
+
+            // missing function callback
+            missing_fn: None,
+            eval_end: None,
+            deprecated: None,
+            source_preprocessor: None,
+            number_literal: None,
+
             // optimization level
             optimization_level: if cfg!(feature = "no_optimize") {
                 OptimizationLevel::None
@@ -462,6 +728,17 @@ impl Default for Engine {
                 OptimizationLevel::Simple
             },
 
+            strict_destructuring: false,
+            fail_on_undefined_var: true,
+            fail_on_undefined_var_assign: true,
+            allow_eval: true,
+            allow_reserved_identifiers: false,
+            operator_method_fallback: false,
+
+            div_by_zero: Default::default(),
+            #[cfg(not(feature = "no_float"))]
+            float_div_by_zero: Default::default(),
+
             #[cfg(not(feature = "unchecked"))]
             limits: Limits {
                 max_call_stack_depth: MAX_CALL_STACK_DEPTH,
@@ -469,9 +746,12 @@ impl Default for Engine {
                 max_function_expr_depth: MAX_FUNCTION_EXPR_DEPTH,
                 max_operations: 0,
                 max_modules: usize::MAX,
+                max_fn_params: MAX_FN_PARAMS,
                 max_string_size: 0,
                 max_array_size: 0,
                 max_map_size: 0,
+                max_memory: 0,
+                max_data_depth: MAX_DATA_DEPTH,
             },
         };
 
@@ -502,6 +782,157 @@ fn default_print(_s: &str) {
     println!("{}", _s);
 }
 
+/// Estimate the memory used by a value itself, not counting the values it contains.
+#[cfg(not(feature = "unchecked"))]
+fn calc_own_memory(value: &Dynamic) -> usize {
+    let size = size_of::<Dynamic>();
+
+    match value {
+        #[cfg(not(feature = "no_index"))]
+        Dynamic(Union::Array(arr)) => size + arr.len() * size,
+        #[cfg(not(feature = "no_index"))]
+        Dynamic(Union::TypedArray(arr)) => size + arr.0.len() * size,
+        #[cfg(not(feature = "no_object"))]
+        Dynamic(Union::Map(map)) => size + map.len() * (size + size_of::<ImmutableString>()),
+        Dynamic(Union::Str(s)) => size + s.len(),
+        Dynamic(Union::Frozen(value)) => calc_own_memory(value),
+        _ if value.is_shared() && !value.is_locked() => {
+            calc_own_memory(&*value.read_lock::<Dynamic>().unwrap())
+        }
+        _ => size,
+    }
+}
+
+/// Recursively estimate the memory used by a value, including all the values it contains.
+#[cfg(not(feature = "unchecked"))]
+fn calc_memory(value: &Dynamic) -> usize {
+    let size = size_of::<Dynamic>();
+
+    match value {
+        #[cfg(not(feature = "no_index"))]
+        Dynamic(Union::Array(_)) | Dynamic(Union::TypedArray(_)) => {
+            calc_own_memory(value)
+                + value
+                    .downcast_ref::<Array>()
+                    .unwrap()
+                    .iter()
+                    .map(|value| calc_memory(value) - size)
+                    .sum::<usize>()
+        }
+        #[cfg(not(feature = "no_object"))]
+        Dynamic(Union::Map(map)) => {
+            calc_own_memory(value)
+                + map
+                    .values()
+                    .map(|value| calc_memory(value) - size)
+                    .sum::<usize>()
+        }
+        Dynamic(Union::Frozen(value)) => calc_memory(value),
+        _ if value.is_shared() && !value.is_locked() => {
+            calc_memory(&*value.read_lock::<Dynamic>().unwrap())
+        }
+        _ => calc_own_memory(value),
+    }
+}
+
+/// Recalculate the hash of a method call if its arguments contain spread (`...`) expressions,
+/// because the actual number of arguments is only known after evaluation.
+#[cfg(not(feature = "no_object"))]
+fn calc_method_hash(name: &str, hash: u64, args_expr: &[Expr], arg_values: &Dynamic) -> u64 {
+    if args_expr.iter().any(|expr| matches!(expr, Expr::Spread(_))) {
+        let num_args = arg_values.read_lock::<StaticVec<Dynamic>>().unwrap().len();
+        calc_fn_hash(empty(), name, num_args, empty())
+    } else {
+        hash
+    }
+}
+
+/// Get the positions `[start, end)` of a slice `[from..to]` into a sequence of `len` items.
+///
+/// An upper bound that is out of range is clamped to the length of the sequence, and a reversed
+/// range yields an empty slice.  A negative bound is returned as an error.
+#[cfg(not(feature = "no_index"))]
+fn slice_bounds(range: Range<INT>, len: usize) -> Result<(usize, usize), INT> {
+    if range.start < 0 {
+        return Err(range.start);
+    } else if range.end < 0 {
+        return Err(range.end);
+    }
+
+    let start = (range.start as usize).min(len);
+    let end = (range.end as usize).min(len).max(start);
+
+    Ok((start, end))
+}
+
+/// Is the index value a slice `[from..to]` into an array or a string?
+#[cfg(not(feature = "no_index"))]
+fn is_slice(value: &Dynamic, idx: &Dynamic) -> bool {
+    idx.is::<Range<INT>>() && (value.is::<Array>() || value.is::<ImmutableString>())
+}
+
+/// Get the number of items in an array, or characters in a string.
+#[cfg(not(feature = "no_index"))]
+fn item_count(value: &Dynamic) -> Option<usize> {
+    match value {
+        Dynamic(Union::Array(_)) | Dynamic(Union::TypedArray(_)) => {
+            value.downcast_ref::<Array>().map(|arr| arr.len())
+        }
+        Dynamic(Union::Str(s)) => Some(s.chars().count()),
+        _ => None,
+    }
+}
+
+/// Read an item from a frozen `Array` or object `Map` without copying it.
+///
+/// Returns `None` if the item cannot simply be read, in which case the frozen value must be copied.
+#[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+fn read_frozen_item(
+    value: &Dynamic,
+    idx: &Dynamic,
+    _idx_pos: Position,
+) -> Option<Result<Dynamic, Box<EvalAltResult>>> {
+    #[cfg(not(feature = "no_index"))]
+    if let Some(arr) = value.downcast_ref::<Array>() {
+        let index = idx.as_int().ok()?;
+
+        return Some(if index >= 0 {
+            arr.get(index as usize)
+                .cloned()
+                .ok_or_else(|| EvalAltResult::ErrorArrayBounds(arr.len(), index, _idx_pos).into())
+        } else {
+            EvalAltResult::ErrorArrayBounds(arr.len(), index, _idx_pos).into()
+        });
+    }
+
+    #[cfg(not(feature = "no_object"))]
+    if let Some(map) = value.downcast_ref::<Map>() {
+        let index = idx.read_lock::<ImmutableString>()?;
+        return Some(Ok(map.get(&*index).cloned().unwrap_or_default()));
+    }
+
+    None
+}
+
+/// Can evaluating a dot/index chain modify the object that it starts from,
+/// apart from an assignment?  This is the case when the chain contains a method call.
+#[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+fn chain_may_modify(rhs: &Expr, chain_type: ChainType) -> bool {
+    match rhs {
+        Expr::FnCall(_) => chain_type == ChainType::Dot,
+        Expr::Dot(x) | Expr::Index(x) => {
+            let next_chain = match rhs {
+                Expr::Index(_) => ChainType::Index,
+                _ => ChainType::Dot,
+            };
+
+            (chain_type == ChainType::Dot && matches!(x.0, Expr::FnCall(_)))
+                || chain_may_modify(&x.1, next_chain)
+        }
+        _ => false,
+    }
+}
+
 /// Search for a module within an imports stack.
 /// Position in `EvalAltResult` is `None` and must be set afterwards.
 pub fn search_imports<'s>(
@@ -525,7 +956,7 @@ pub fn search_imports<'s>(
         mods.iter()
             .rev()
             .find(|(n, _)| n == root)
-            .map(|(_, m)| m)
+            .map(|(_, m)| m.as_ref())
             .ok_or_else(|| EvalAltResult::ErrorModuleNotFound(root.to_string(), *root_pos))?
     })
 }
@@ -548,12 +979,12 @@ pub fn search_imports_mut<'s>(
 
     Ok(if let Some(index) = index {
         let offset = mods.len() - index.get();
-        &mut mods.get_mut(offset).unwrap().1
+        shared_make_mut(&mut mods.get_mut(offset).unwrap().1)
     } else {
         mods.iter_mut()
             .rev()
             .find(|(n, _)| n == root)
-            .map(|(_, m)| m)
+            .map(|(_, m)| shared_make_mut(m))
             .ok_or_else(|| EvalAltResult::ErrorModuleNotFound(root.to_string(), *root_pos))?
     })
 }
@@ -654,9 +1085,12 @@ impl Engine {
 
             packages: Default::default(),
             global_module: Default::default(),
+            guarded_fns: Default::default(),
 
             #[cfg(not(feature = "no_module"))]
             module_resolver: None,
+            #[cfg(not(feature = "no_module"))]
+            lazy_modules: Default::default(),
 
             type_names: None,
             disabled_symbols: None,
@@ -666,6 +1100,14 @@ impl Engine {
             print: Box::new(|_| {}),
             debug: Box::new(|_| {}),
             progress: None,
+            statement_executed: None,
+            debugger: None,
+            breakpoints: Default::default(),
+            missing_fn: None,
+            eval_end: None,
+            deprecated: None,
+            source_preprocessor: None,
+            number_literal: None,
 
             optimization_level: if cfg!(feature = "no_optimize") {
                 OptimizationLevel::None
@@ -673,6 +1115,17 @@ impl Engine {
                 OptimizationLevel::Simple
             },
 
+            strict_destructuring: false,
+            fail_on_undefined_var: true,
+            fail_on_undefined_var_assign: true,
+            allow_eval: true,
+            allow_reserved_identifiers: false,
+            operator_method_fallback: false,
+
+            div_by_zero: Default::default(),
+            #[cfg(not(feature = "no_float"))]
+            float_div_by_zero: Default::default(),
+
             #[cfg(not(feature = "unchecked"))]
             limits: Limits {
                 max_call_stack_depth: MAX_CALL_STACK_DEPTH,
@@ -680,9 +1133,12 @@ impl Engine {
                 max_function_expr_depth: MAX_FUNCTION_EXPR_DEPTH,
                 max_operations: 0,
                 max_modules: usize::MAX,
+                max_fn_params: MAX_FN_PARAMS,
                 max_string_size: 0,
                 max_array_size: 0,
                 max_map_size: 0,
+                max_memory: 0,
+                max_data_depth: MAX_DATA_DEPTH,
             },
         }
     }
@@ -727,19 +1183,48 @@ impl Engine {
                     Expr::Dot(x) | Expr::Index(x) => {
                         let (idx, expr, pos) = x.as_ref();
                         let idx_pos = idx.position();
+
+                        // A slice is a copy, so writing through it would be silently lost
+                        let is_slice = is_slice(target.as_mut(), &idx_val);
+
+                        if is_slice && new_val.is_some() {
+                            return EvalAltResult::ErrorAssignmentToUnknownLHS(*pos).into();
+                        }
+
                         let obj_ptr = &mut self.get_indexed_mut(
                             state, lib, target, idx_val, idx_pos, false, true, level,
                         )?;
+                        let slice_len = if is_slice {
+                            item_count(obj_ptr.as_mut())
+                        } else {
+                            None
+                        };
 
-                        self.eval_dot_index_chain_helper(
-                            state, lib, this_ptr, obj_ptr, expr, idx_values, next_chain, level,
-                            new_val,
-                        )
-                        .map_err(|err| err.new_position(*pos))
+                        let (result, updated) = self
+                            .eval_dot_index_chain_helper(
+                                state, lib, this_ptr, obj_ptr, expr, idx_values, next_chain, level,
+                                new_val,
+                            )
+                            .map_err(|err| err.new_position(*pos))?;
+
+                        // A method call modifying the slice - e.g. `x[0..2].push(42)` - is also an error
+                        if is_slice
+                            && updated
+                            && (result.is::<()>() || item_count(obj_ptr.as_mut()) != slice_len)
+                        {
+                            return EvalAltResult::ErrorAssignmentToUnknownLHS(*pos).into();
+                        }
+
+                        Ok((result, updated))
                     }
                     // xxx[rhs] = new_val
                     _ if new_val.is_some() => {
+                        #[cfg(not(feature = "no_index"))]
+                        self.check_elem_type(target.as_mut(), new_val.as_ref().unwrap())
+                            .map_err(|err| err.new_position(rhs.position()))?;
+
                         let mut idx_val2 = idx_val.clone();
+                        let memory = self.mark_memory(target.as_mut(), &[]);
 
                         // `call_setter` is introduced to bypass double mutable borrowing of target
                         let _call_setter = match self
@@ -747,8 +1232,12 @@ impl Engine {
                         {
                             // Indexed value is a reference - update directly
                             Ok(ref mut obj_ptr) => {
+                                let new_val = new_val.unwrap();
+                                self.free_memory(state, obj_ptr.as_mut());
+                                self.alloc_memory(state, &new_val)
+                                    .map_err(|err| err.new_position(rhs.position()))?;
                                 obj_ptr
-                                    .set_value(new_val.unwrap())
+                                    .set_value(new_val)
                                     .map_err(|err| err.new_position(rhs.position()))?;
 
                                 None
@@ -762,6 +1251,10 @@ impl Engine {
                             },
                         };
 
+                        // The container itself may have grown by a new item
+                        self.update_memory(state, memory, target.as_mut(), &Default::default())
+                            .map_err(|err| err.new_position(rhs.position()))?;
+
                         #[cfg(not(feature = "no_index"))]
                         if let Some(mut new_val) = _call_setter {
                             let val = target.as_mut();
@@ -797,9 +1290,10 @@ impl Engine {
                 match rhs {
                     // xxx.fn_name(arg_expr_list)
                     Expr::FnCall(x) if x.1.is_none() => {
-                        let ((name, native, _, pos), _, hash, _, def_val) = x.as_ref();
+                        let ((name, native, _, pos), _, hash, args_expr, def_val) = x.as_ref();
+                        let hash = calc_method_hash(name, *hash, args_expr, &idx_val);
                         self.make_method_call(
-                            state, lib, name, *hash, target, idx_val, *def_val, *native, false,
+                            state, lib, name, hash, target, idx_val, *def_val, *native, false,
                             level,
                         )
                         .map_err(|err| err.new_position(*pos))
@@ -810,10 +1304,20 @@ impl Engine {
                     Expr::Property(x) if target.is::<Map>() && new_val.is_some() => {
                         let ((prop, _, _), pos) = x.as_ref();
                         let index = prop.clone().into();
+                        let memory = self.mark_memory(target.as_mut(), &[]);
                         let mut val = self
                             .get_indexed_mut(state, lib, target, index, *pos, true, false, level)?;
 
-                        val.set_value(new_val.unwrap())
+                        let new_val = new_val.unwrap();
+                        self.free_memory(state, val.as_mut());
+                        self.alloc_memory(state, &new_val)
+                            .map_err(|err| err.new_position(rhs.position()))?;
+                        val.set_value(new_val)
+                            .map_err(|err| err.new_position(rhs.position()))?;
+                        drop(val);
+
+                        // The object map itself may have grown by a new property
+                        self.update_memory(state, memory, target.as_mut(), &Default::default())
                             .map_err(|err| err.new_position(rhs.position()))?;
                         Ok((Default::default(), true))
                     }
@@ -864,11 +1368,13 @@ impl Engine {
                             }
                             // {xxx:map}.fn_name(arg_expr_list)[expr] | {xxx:map}.fn_name(arg_expr_list).expr
                             Expr::FnCall(x) if x.1.is_none() => {
-                                let ((name, native, _, pos), _, hash, _, def_val) = x.as_ref();
+                                let ((name, native, _, pos), _, hash, args_expr, def_val) =
+                                    x.as_ref();
+                                let hash = calc_method_hash(name, *hash, args_expr, &idx_val);
                                 let (val, _) = self
                                     .make_method_call(
-                                        state, lib, name, *hash, target, idx_val, *def_val,
-                                        *native, false, level,
+                                        state, lib, name, hash, target, idx_val, *def_val, *native,
+                                        false, level,
                                     )
                                     .map_err(|err| err.new_position(*pos))?;
                                 val.into()
@@ -941,11 +1447,13 @@ impl Engine {
                             }
                             // xxx.fn_name(arg_expr_list)[expr] | xxx.fn_name(arg_expr_list).expr
                             Expr::FnCall(x) if x.1.is_none() => {
-                                let ((name, native, _, pos), _, hash, _, def_val) = x.as_ref();
+                                let ((name, native, _, pos), _, hash, args_expr, def_val) =
+                                    x.as_ref();
+                                let hash = calc_method_hash(name, *hash, args_expr, &idx_val);
                                 let (mut val, _) = self
                                     .make_method_call(
-                                        state, lib, name, *hash, target, idx_val, *def_val,
-                                        *native, false, level,
+                                        state, lib, name, hash, target, idx_val, *def_val, *native,
+                                        false, level,
                                     )
                                     .map_err(|err| err.new_position(*pos))?;
                                 let val = &mut val;
@@ -972,6 +1480,32 @@ impl Engine {
         }
     }
 
+    /// Is this error raised by reading an undefined, non-qualified variable that should be
+    /// read as `()` instead?  See `Engine::set_fail_on_undefined_var`.
+    pub(crate) fn is_undefined_var_read_as_unit(&self, err: &EvalAltResult, expr: &Expr) -> bool {
+        if self.fail_on_undefined_var {
+            return false;
+        }
+
+        match (err, expr) {
+            (EvalAltResult::ErrorVariableNotFound(_, _), Expr::Variable(x)) => x.1.is_none(),
+            _ => false,
+        }
+    }
+
+    /// Is this error raised by assigning to an undefined, non-qualified variable that should be
+    /// defined instead?  See `Engine::set_fail_on_undefined_var_assign`.
+    fn is_undefined_var_assign_allowed(&self, err: &EvalAltResult, expr: &Expr) -> bool {
+        if self.fail_on_undefined_var_assign {
+            return false;
+        }
+
+        match (err, expr) {
+            (EvalAltResult::ErrorVariableNotFound(_, _), Expr::Variable(x)) => x.1.is_none(),
+            _ => false,
+        }
+    }
+
     /// Evaluate a dot/index chain.
     #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
     fn eval_dot_index_chain(
@@ -1006,7 +1540,24 @@ impl Engine {
                     .map_err(|err| err.new_position(*var_pos))?;
 
                 let (target, _, typ, pos) =
-                    search_namespace(scope, mods, state, this_ptr, dot_lhs)?;
+                    match search_namespace(scope, mods, state, this_ptr, dot_lhs) {
+                        Ok(v) => v,
+                        // Read an undefined variable as ()
+                        Err(err)
+                            if new_val.is_none()
+                                && self.is_undefined_var_read_as_unit(&err, dot_lhs) =>
+                        {
+                            let obj_ptr = &mut Target::Value(().into());
+                            return self
+                                .eval_dot_index_chain_helper(
+                                    state, lib, &mut None, obj_ptr, dot_rhs, idx_values,
+                                    chain_type, level, None,
+                                )
+                                .map(|(v, _)| v)
+                                .map_err(|err| err.new_position(*op_pos));
+                        }
+                        Err(err) => return Err(err),
+                    };
 
                 // Constants cannot be modified
                 match typ {
@@ -1017,7 +1568,15 @@ impl Engine {
                     ScopeEntryType::Constant | ScopeEntryType::Normal => (),
                 }
 
-                let obj_ptr = &mut target.into();
+                // A frozen variable is only read, without being copied, if nothing can modify it
+                let obj_ptr = &mut if new_val.is_none()
+                    && target.is_frozen()
+                    && !chain_may_modify(dot_rhs, chain_type)
+                {
+                    Target::Value(target.clone())
+                } else {
+                    target.into()
+                };
                 self.eval_dot_index_chain_helper(
                     state, lib, &mut None, obj_ptr, dot_rhs, idx_values, chain_type, level, new_val,
                 )
@@ -1066,11 +1625,7 @@ impl Engine {
         match expr {
             Expr::FnCall(x) if x.1.is_none() => {
                 let arg_values =
-                    x.3.iter()
-                        .map(|arg_expr| {
-                            self.eval_expr(scope, mods, state, lib, this_ptr, arg_expr, level)
-                        })
-                        .collect::<Result<StaticVec<Dynamic>, _>>()?;
+                    self.eval_arg_values(scope, mods, state, lib, this_ptr, &x.3, level)?;
 
                 idx_values.push(Dynamic::from(arg_values));
             }
@@ -1083,17 +1638,14 @@ impl Engine {
                 let lhs_val = match lhs {
                     Expr::Property(_) => Default::default(), // Store a placeholder in case of a property
                     Expr::FnCall(x) if chain_type == ChainType::Dot && x.1.is_none() => {
-                        let arg_values = x
-                            .3
-                            .iter()
-                            .map(|arg_expr| {
-                                self.eval_expr(scope, mods, state, lib, this_ptr, arg_expr, level)
-                            })
-                            .collect::<Result<StaticVec<Dynamic>, _>>()?;
+                        let arg_values =
+                            self.eval_arg_values(scope, mods, state, lib, this_ptr, &x.3, level)?;
 
                         Dynamic::from(arg_values)
                     }
-                    Expr::FnCall(_) => unreachable!(),
+                    // xxx.module::fn_name(...) - syntax error
+                    Expr::FnCall(_) if chain_type == ChainType::Dot => unreachable!(),
+                    // xxx[fn_name(...)] or xxx[from..to] - an index value, e.g. a slice followed by more indexing
                     _ => self.eval_expr(scope, mods, state, lib, this_ptr, lhs, level)?,
                 };
 
@@ -1134,12 +1686,41 @@ impl Engine {
         #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
         let is_ref = target.is_ref();
 
+        if target.as_mut().is_frozen() {
+            // Reading from a temporary frozen value needs no copy
+            if !is_ref {
+                if let Some(result) = read_frozen_item(target.as_mut(), &idx, idx_pos) {
+                    return result.map(Target::Value);
+                }
+            }
+            // Copy a frozen value before a reference into it is handed out
+            target.as_mut().thaw_mut();
+        }
+
         let val = target.as_mut();
 
         match val {
             #[cfg(not(feature = "no_index"))]
-            Dynamic(Union::Array(arr)) => {
+            Dynamic(Union::Array(_)) | Dynamic(Union::TypedArray(_)) if idx.is::<Range<INT>>() => {
+                // val_array[from..to]
+                let elem_type = val.elem_type();
+                let arr = val.downcast_ref::<Array>().unwrap();
+                let arr_len = arr.len();
+                let (start, end) = slice_bounds(idx.cast::<Range<INT>>(), arr_len)
+                    .map_err(|index| EvalAltResult::ErrorArrayBounds(arr_len, index, idx_pos))?;
+
+                let slice = arr[start..end].to_vec();
+
+                Ok(Target::Value(match elem_type {
+                    Some(elem_type) => Dynamic(Union::TypedArray(Box::new((slice, elem_type)))),
+                    None => slice.into(),
+                }))
+            }
+
+            #[cfg(not(feature = "no_index"))]
+            Dynamic(Union::Array(_)) | Dynamic(Union::TypedArray(_)) => {
                 // val_array[idx]
+                let arr = val.downcast_mut::<Array>().unwrap();
                 let index = idx
                     .as_int()
                     .map_err(|_| EvalAltResult::ErrorNumericIndexExpr(idx_pos))?;
@@ -1177,6 +1758,17 @@ impl Engine {
                 })
             }
 
+            #[cfg(not(feature = "no_index"))]
+            Dynamic(Union::Str(s)) if idx.is::<Range<INT>>() => {
+                // val_string[from..to] - by character position, so a character is never split
+                let chars_len = s.chars().count();
+                let (start, end) = slice_bounds(idx.cast::<Range<INT>>(), chars_len)
+                    .map_err(|index| EvalAltResult::ErrorStringBounds(chars_len, index, idx_pos))?;
+
+                let sub: String = s.chars().skip(start).take(end - start).collect();
+                Ok(Target::Value(sub.into()))
+            }
+
             #[cfg(not(feature = "no_index"))]
             Dynamic(Union::Str(s)) => {
                 // val_string[idx]
@@ -1239,9 +1831,39 @@ impl Engine {
         let lhs_value = self.eval_expr(scope, mods, state, lib, this_ptr, lhs, level)?;
         let rhs_value = self.eval_expr(scope, mods, state, lib, this_ptr, rhs, level)?;
 
+        self.eval_contains(state, lib, lhs_value, rhs_value, rhs.position(), level)
+    }
+
+    /// Check whether a value is contained in another value, as with the `in` operator.
+    fn eval_contains(
+        &self,
+        state: &mut State,
+        lib: &Module,
+        lhs_value: Dynamic,
+        rhs_value: Dynamic,
+        pos: Position,
+        level: usize,
+    ) -> Result<Dynamic, Box<EvalAltResult>> {
         match rhs_value {
+            // Look up a key in a frozen object map without copying it
+            #[cfg(not(feature = "no_object"))]
+            Dynamic(Union::Frozen(ref rhs_value))
+                if rhs_value.is::<Map>()
+                    && (lhs_value.is::<ImmutableString>() || lhs_value.is::<char>()) =>
+            {
+                let map = rhs_value.downcast_ref::<Map>().unwrap();
+                Ok(match lhs_value.as_char() {
+                    Ok(c) => map.contains_key(&c.to_string()),
+                    Err(_) => map.contains_key(lhs_value.as_str().unwrap()),
+                }
+                .into())
+            }
+            rhs_value @ Dynamic(Union::Frozen(_)) => {
+                self.eval_contains(state, lib, lhs_value, rhs_value.thaw(), pos, level)
+            }
             #[cfg(not(feature = "no_index"))]
-            Dynamic(Union::Array(mut rhs_value)) => {
+            rhs_value @ Dynamic(Union::Array(_)) | rhs_value @ Dynamic(Union::TypedArray(_)) => {
+                let mut rhs_value = rhs_value.cast::<Array>();
                 let op = "==";
 
                 // Call the `==` operator to compare each value
@@ -1254,8 +1876,8 @@ impl Engine {
                         calc_fn_hash(empty(), op, args.len(), args.iter().map(|a| a.type_id()));
 
                     if self
-                        .call_native_fn(state, lib, op, hash, args, false, false, def_value)
-                        .map_err(|err| err.new_position(rhs.position()))?
+                        .call_native_fn(state, lib, op, hash, args, false, false, def_value, level)
+                        .map_err(|err| err.new_position(pos))?
                         .0
                         .as_bool()
                         .unwrap_or(false)
@@ -1267,22 +1889,177 @@ impl Engine {
                 Ok(false.into())
             }
             #[cfg(not(feature = "no_object"))]
-            Dynamic(Union::Map(rhs_value)) => match lhs_value {
-                // Only allows String or char
-                Dynamic(Union::Str(s)) => Ok(rhs_value.contains_key(&s).into()),
-                Dynamic(Union::Char(c)) => Ok(rhs_value.contains_key(&c.to_string()).into()),
-                _ => EvalAltResult::ErrorInExpr(lhs.position()).into(),
-            },
-            Dynamic(Union::Str(rhs_value)) => match lhs_value {
-                // Only allows String or char
-                Dynamic(Union::Str(s)) => Ok(rhs_value.contains(s.as_str()).into()),
-                Dynamic(Union::Char(c)) => Ok(rhs_value.contains(c).into()),
-                _ => EvalAltResult::ErrorInExpr(lhs.position()).into(),
-            },
-            _ => EvalAltResult::ErrorInExpr(rhs.position()).into(),
+            Dynamic(Union::Map(ref rhs_value)) if lhs_value.is::<ImmutableString>() => {
+                let key = lhs_value.as_str().unwrap();
+                Ok(rhs_value.contains_key(key).into())
+            }
+            #[cfg(not(feature = "no_object"))]
+            Dynamic(Union::Map(ref rhs_value)) if lhs_value.is::<char>() => {
+                let key = lhs_value.as_char().unwrap().to_string();
+                Ok(rhs_value.contains_key(&key).into())
+            }
+            Dynamic(Union::Str(ref rhs_value)) if lhs_value.is::<ImmutableString>() => {
+                let s = lhs_value.as_str().unwrap();
+                Ok(rhs_value.contains(s).into())
+            }
+            Dynamic(Union::Str(ref rhs_value)) if lhs_value.is::<char>() => {
+                let c = lhs_value.as_char().unwrap();
+                Ok(rhs_value.contains(c).into())
+            }
+            // Call the `contains` function for all other types
+            mut rhs_value => {
+                let fn_name = FN_CONTAINS;
+                let args = &mut [&mut rhs_value, &mut lhs_value.clone()];
+
+                // Qualifiers (none) + function name + number of arguments + argument `TypeId`'s.
+                let hash = calc_fn_hash(
+                    empty(),
+                    fn_name,
+                    args.len(),
+                    args.iter().map(|a| a.type_id()),
+                );
+
+                match self
+                    .call_native_fn(state, lib, fn_name, hash, args, false, false, None, level)
+                {
+                    Ok((result, _)) => Ok(result),
+                    Err(err) => match *err {
+                        EvalAltResult::ErrorFunctionNotFound(ref f, _)
+                            if f.starts_with(fn_name) =>
+                        {
+                            EvalAltResult::ErrorInExpr(
+                                format!(
+                                    "Cannot check whether {} is in {}",
+                                    self.map_type_name(lhs_value.type_name()),
+                                    self.map_type_name(args[0].type_name())
+                                ),
+                                pos,
+                            )
+                            .into()
+                        }
+                        _ => Err(err.new_position(pos)),
+                    },
+                }
+            }
         }
     }
 
+    /// Evaluate a `switch` statement, running the body of the first arm that matches.
+    ///
+    /// # Safety
+    ///
+    /// This method uses some unsafe code, mainly for avoiding cloning of local variable names via
+    /// direct lifetime casting.
+    fn eval_switch(
+        &self,
+        scope: &mut Scope,
+        mods: &mut Imports,
+        state: &mut State,
+        lib: &Module,
+        this_ptr: &mut Option<&mut Dynamic>,
+        x: &(
+            Expr,
+            StaticVec<(SwitchPattern, Option<Expr>, Stmt)>,
+            Position,
+        ),
+        level: usize,
+    ) -> Result<Dynamic, Box<EvalAltResult>> {
+        let (expr, arms, _) = x;
+        let value = self.eval_expr(scope, mods, state, lib, this_ptr, expr, level)?;
+
+        let prev_scope_len = scope.len();
+        state.scope_level += 1;
+
+        let mut result = Ok(Default::default());
+
+        for (pattern, guard, body) in arms.iter() {
+            // Arms are tried in order - the first arm that matches wins
+            let matched = match pattern {
+                SwitchPattern::Any(_) => Ok(true),
+                SwitchPattern::Bind(name, pos) => {
+                    let var_name = unsafe_cast_var_name_to_lifetime(name, &state);
+                    let value = value.clone();
+                    self.alloc_memory(state, &value)
+                        .map_err(|err| err.new_position(*pos))?;
+                    scope.push(var_name, value);
+                    Ok(true)
+                }
+                SwitchPattern::Value(label) => self
+                    .eval_expr(scope, mods, state, lib, this_ptr, label, level)
+                    .and_then(|mut label_value| {
+                        let op = "==";
+                        let same_type = value.type_id() == label_value.type_id();
+                        let args = &mut [&mut value.clone(), &mut label_value];
+
+                        // Qualifiers (none) + function name + number of arguments + argument `TypeId`'s.
+                        let hash =
+                            calc_fn_hash(empty(), op, args.len(), args.iter().map(|a| a.type_id()));
+
+                        self.call_native_fn(state, lib, op, hash, args, false, false, None, level)
+                            .map(|(v, _)| v.as_bool().unwrap_or(false))
+                            .or_else(|err| match *err {
+                                // Values of different types never match, but a custom type
+                                // without `==` cannot be matched against at all
+                                EvalAltResult::ErrorFunctionNotFound(ref f, _)
+                                    if f.starts_with(op) =>
+                                {
+                                    if same_type {
+                                        Err(self.make_not_comparable_error(&value, &value))
+                                    } else {
+                                        Ok(false)
+                                    }
+                                }
+                                _ => Err(err),
+                            })
+                            .map_err(|err| err.new_position(label.position()))
+                    }),
+                SwitchPattern::Range(label) => self
+                    .eval_expr(scope, mods, state, lib, this_ptr, label, level)
+                    .and_then(|range| {
+                        self.eval_contains(
+                            state,
+                            lib,
+                            value.clone(),
+                            range,
+                            label.position(),
+                            level,
+                        )
+                    })
+                    .map(|v| v.as_bool().unwrap_or(false)),
+            };
+
+            // An error in a guard aborts the switch instead of moving on to the next arm
+            let matched = match (matched, guard) {
+                (Ok(true), Some(guard)) => self
+                    .eval_expr(scope, mods, state, lib, this_ptr, guard, level)
+                    .and_then(|v| {
+                        v.as_bool()
+                            .map_err(|_| EvalAltResult::ErrorLogicGuard(guard.position()).into())
+                    }),
+                (matched, _) => matched,
+            };
+
+            match matched {
+                Ok(true) => {
+                    result = self.eval_stmt(scope, mods, state, lib, this_ptr, body, level);
+                    break;
+                }
+                Ok(false) => {
+                    self.rewind_scope(scope, state, prev_scope_len);
+                }
+                Err(err) => {
+                    result = Err(err);
+                    break;
+                }
+            }
+        }
+
+        self.rewind_scope(scope, state, prev_scope_len);
+        state.scope_level -= 1;
+
+        result
+    }
+
     /// Evaluate an expression
     pub(crate) fn eval_expr(
         &self,
@@ -1303,7 +2080,8 @@ impl Engine {
             Expr::IntegerConstant(x) => Ok(x.0.into()),
             #[cfg(not(feature = "no_float"))]
             Expr::FloatConstant(x) => Ok(x.0.into()),
-            Expr::StringConstant(x) => Ok(x.0.to_string().into()),
+            Expr::CustomNumberConstant(x) => Ok(x.0.clone()),
+            Expr::StringConstant(x) => Ok(Dynamic(Union::Str(x.0.clone()))),
             Expr::CharConstant(x) => Ok(x.0.into()),
             Expr::FnPointer(x) => Ok(FnPtr::new_unchecked(x.0.clone(), Default::default()).into()),
             Expr::Variable(x) if (x.0).0 == KEYWORD_THIS => {
@@ -1313,10 +2091,11 @@ impl Engine {
                     EvalAltResult::ErrorUnboundThis((x.0).1).into()
                 }
             }
-            Expr::Variable(_) => {
-                let (val, _, _, _) = search_namespace(scope, mods, state, this_ptr, expr)?;
-                Ok(val.clone())
-            }
+            Expr::Variable(_) => match search_namespace(scope, mods, state, this_ptr, expr) {
+                Ok((val, _, _, _)) => Ok(val.clone()),
+                Err(err) if self.is_undefined_var_read_as_unit(&err, expr) => Ok(().into()),
+                Err(err) => Err(err),
+            },
             Expr::Property(_) => unreachable!(),
 
             // Statement block
@@ -1327,8 +2106,38 @@ impl Engine {
                 let (lhs_expr, op, rhs_expr, op_pos) = x.as_ref();
                 let mut rhs_val =
                     self.eval_expr(scope, mods, state, lib, this_ptr, rhs_expr, level)?;
+                self.check_data_depth(&rhs_val)
+                    .map_err(|err| err.new_position(*op_pos))?;
                 let (lhs_ptr, name, typ, pos) =
-                    search_namespace(scope, mods, state, this_ptr, lhs_expr)?;
+                    match search_namespace(scope, mods, state, this_ptr, lhs_expr) {
+                        Ok(v) => v,
+                        // Assignment to an undefined variable defines it
+                        Err(err)
+                            if op.is_empty()
+                                && self.is_undefined_var_assign_allowed(&err, lhs_expr) =>
+                        {
+                            let ((var_name, _), _, _, _) = match lhs_expr {
+                                Expr::Variable(x) => x.as_ref(),
+                                _ => unreachable!(),
+                            };
+                            let value = rhs_val.flatten();
+                            self.alloc_memory(state, &value)
+                                .map_err(|err| err.new_position(*op_pos))?;
+                            let var_name = unsafe_cast_var_name_to_lifetime(var_name, &state);
+                            scope.push_dynamic_value(
+                                var_name,
+                                ScopeEntryType::Normal,
+                                value,
+                                false,
+                            );
+
+                            // IMPORTANT! All variable offsets from this point on are mis-aligned
+                            state.always_search = true;
+
+                            return Ok(Default::default());
+                        }
+                        Err(err) => return Err(err),
+                    };
                 self.inc_operations(state)
                     .map_err(|err| err.new_position(pos))?;
 
@@ -1340,6 +2149,9 @@ impl Engine {
                     // Normal assignment
                     ScopeEntryType::Normal if op.is_empty() => {
                         let value = rhs_val.flatten();
+                        self.free_memory(state, lhs_ptr);
+                        self.alloc_memory(state, &value)
+                            .map_err(|err| err.new_position(*op_pos))?;
                         if cfg!(not(feature = "no_closure")) && lhs_ptr.is_shared() {
                             *lhs_ptr.write_lock::<Dynamic>().unwrap() = value;
                         } else {
@@ -1357,11 +2169,23 @@ impl Engine {
                         let arg_types = once(lhs_ptr.type_id()).chain(once(rhs_val.type_id()));
                         let hash_fn = calc_fn_hash(empty(), op, 2, arg_types);
 
-                        match self
+                        let memory = self.mark_memory(lhs_ptr, &[&mut rhs_val]);
+
+                        let func = self
                             .global_module
-                            .get_fn(hash_fn, false)
-                            .or_else(|| self.packages.get_fn(hash_fn, false))
-                        {
+                            .get_fn_by_hash(hash_fn, false)
+                            .or_else(|| self.packages.get_fn(hash_fn, false));
+
+                        // Global functions in imported modules come last
+                        #[cfg(not(feature = "no_module"))]
+                        let global_fn = match func {
+                            None => state.get_global_fn(hash_fn, false),
+                            Some(_) => None,
+                        };
+                        #[cfg(not(feature = "no_module"))]
+                        let func = func.or(global_fn.as_ref());
+
+                        match func {
                             // op= function registered as method
                             Some(func) if func.is_method() => {
                                 let mut lock_guard;
@@ -1378,13 +2202,27 @@ impl Engine {
 
                                 // Overriding exact implementation
                                 if func.is_plugin_fn() {
-                                    func.get_plugin_fn().call(args)?;
+                                    self.report_deprecated(state, op, func);
+                                    func.get_plugin_fn()
+                                        .call(args)
+                                        .map_err(|err| err.new_position(*op_pos))?;
                                 } else {
-                                    func.get_native_fn()(self, lib, args)?;
+                                    let context = &mut NativeCallContext {
+                                        engine: self,
+                                        lib,
+                                        state,
+                                        level,
+                                    };
+                                    func.get_native_fn()(context, args)
+                                        .map_err(|err| err.new_position(*op_pos))?;
                                 }
                             }
-                            // Built-in op-assignment function
-                            _ if run_builtin_op_assignment(op, lhs_ptr, &rhs_val)?.is_some() => {}
+                            // Built-in op-assignment function, except for division by zero
+                            // which depends on the settings of the Engine
+                            _ if !((op == "/=" || op == "%=") && is_zero(&rhs_val))
+                                && run_builtin_op_assignment(op, lhs_ptr, &rhs_val)
+                                    .map_err(|err| err.new_position(*op_pos))?
+                                    .is_some() => {}
                             // Not built-in: expand to `var = var op rhs`
                             _ => {
                                 let op = &op[..op.len() - 1]; // extract operator without =
@@ -1394,10 +2232,7 @@ impl Engine {
 
                                 // Run function
                                 let (value, _) = self
-                                    .exec_fn_call(
-                                        state, lib, op, 0, args, false, false, false, None, None,
-                                        level,
-                                    )
+                                    .exec_operator_call(state, lib, op, args, false, None, level)
                                     .map_err(|err| err.new_position(*op_pos))?;
 
                                 let value = value.flatten();
@@ -1409,6 +2244,10 @@ impl Engine {
                                 }
                             }
                         }
+
+                        self.update_memory(state, memory, lhs_ptr, &Default::default())
+                            .map_err(|err| err.new_position(*op_pos))?;
+
                         Ok(Default::default())
                     }
                 }
@@ -1419,6 +2258,8 @@ impl Engine {
                 let (lhs_expr, op, rhs_expr, op_pos) = x.as_ref();
                 let mut rhs_val =
                     self.eval_expr(scope, mods, state, lib, this_ptr, rhs_expr, level)?;
+                self.check_data_depth(&rhs_val)
+                    .map_err(|err| err.new_position(*op_pos))?;
 
                 let _new_val = Some(if op.is_empty() {
                     // Normal assignment
@@ -1430,11 +2271,9 @@ impl Engine {
                         &mut self.eval_expr(scope, mods, state, lib, this_ptr, lhs_expr, level)?,
                         &mut rhs_val,
                     ];
-                    self.exec_fn_call(
-                        state, lib, op, 0, args, false, false, false, None, None, level,
-                    )
-                    .map(|(v, _)| v)
-                    .map_err(|err| err.new_position(*op_pos))?
+                    self.exec_operator_call(state, lib, op, args, false, None, level)
+                        .map(|(v, _)| v)
+                        .map_err(|err| err.new_position(*op_pos))?
                 });
 
                 match lhs_expr {
@@ -1481,9 +2320,8 @@ impl Engine {
 
             #[cfg(not(feature = "no_index"))]
             Expr::Array(x) => Ok(Dynamic(Union::Array(Box::new(
-                x.0.iter()
-                    .map(|item| self.eval_expr(scope, mods, state, lib, this_ptr, item, level))
-                    .collect::<Result<Vec<_>, _>>()?,
+                self.eval_arg_values(scope, mods, state, lib, this_ptr, &x.0, level)?
+                    .into_vec(),
             )))),
 
             #[cfg(not(feature = "no_object"))]
@@ -1493,7 +2331,7 @@ impl Engine {
                         self.eval_expr(scope, mods, state, lib, this_ptr, expr, level)
                             .map(|val| (key.clone(), val))
                     })
-                    .collect::<Result<HashMap<_, _>, _>>()?,
+                    .collect::<Result<Map, _>>()?,
             )))),
 
             // Normal function call
@@ -1598,6 +2436,16 @@ impl Engine {
         self.inc_operations(state)
             .map_err(|err| err.new_position(stmt.position()))?;
 
+        // Report the statement
+        if let Some(callback) = &self.statement_executed {
+            match stmt {
+                Stmt::Noop(_) | Stmt::Block(_) => (),
+                _ => callback(&stmt.position()),
+            }
+        }
+
+        self.run_debugger(scope, state, stmt, level)?;
+
         let result = match stmt {
             // No-op
             Stmt::Noop(_) => Ok(Default::default()),
@@ -1609,14 +2457,18 @@ impl Engine {
             Stmt::Block(x) => {
                 let prev_scope_len = scope.len();
                 let prev_mods_len = mods.len();
+                #[cfg(not(feature = "no_module"))]
+                let prev_global_modules_len = state.global_modules.len();
                 state.scope_level += 1;
 
                 let result = x.0.iter().try_fold(Default::default(), |_, stmt| {
                     self.eval_stmt(scope, mods, state, lib, this_ptr, stmt, level)
                 });
 
-                scope.rewind(prev_scope_len);
+                self.rewind_scope(scope, state, prev_scope_len);
                 mods.truncate(prev_mods_len);
+                #[cfg(not(feature = "no_module"))]
+                state.global_modules.truncate(prev_global_modules_len);
                 state.scope_level -= 1;
 
                 // The impact of an eval statement goes away at the end of a block
@@ -1644,6 +2496,9 @@ impl Engine {
                     })
             }
 
+            // Switch statement
+            Stmt::Switch(x) => self.eval_switch(scope, mods, state, lib, this_ptr, x, level),
+
             // While loop
             Stmt::While(x) => loop {
                 let (expr, body, _) = x.as_ref();
@@ -1669,6 +2524,43 @@ impl Engine {
                 }
             },
 
+            // While let loop
+            Stmt::WhileLet(x) => {
+                let (name, expr, body, _) = x.as_ref();
+
+                loop {
+                    // Re-evaluate the expression on every iteration
+                    let value = self.eval_expr(scope, mods, state, lib, this_ptr, expr, level)?;
+
+                    if value.is_unit() {
+                        return Ok(Default::default());
+                    }
+
+                    // The loop variable is only visible inside the body
+                    let var_name = unsafe_cast_var_name_to_lifetime(name, &state);
+                    let prev_scope_len = scope.len();
+                    let value = value.flatten();
+                    self.alloc_memory(state, &value)
+                        .map_err(|err| err.new_position(expr.position()))?;
+                    scope.push_dynamic(var_name, value);
+                    state.scope_level += 1;
+
+                    let result = self.eval_stmt(scope, mods, state, lib, this_ptr, body, level);
+
+                    self.rewind_scope(scope, state, prev_scope_len);
+                    state.scope_level -= 1;
+
+                    match result {
+                        Ok(_) => (),
+                        Err(err) => match *err {
+                            EvalAltResult::ErrorLoopBreak(false, _) => (),
+                            EvalAltResult::ErrorLoopBreak(true, _) => return Ok(Default::default()),
+                            _ => return Err(err),
+                        },
+                    }
+                }
+            }
+
             // Loop statement
             Stmt::Loop(x) => loop {
                 match self.eval_stmt(scope, mods, state, lib, this_ptr, &x.0, level) {
@@ -1694,6 +2586,8 @@ impl Engine {
                 {
                     // Add the loop variable
                     let var_name = unsafe_cast_var_name_to_lifetime(name, &state);
+                    self.alloc_memory(state, &Default::default())
+                        .map_err(|err| err.new_position(x.1.position()))?;
                     scope.push(var_name, ());
                     let index = scope.len() - 1;
                     state.scope_level += 1;
@@ -1702,6 +2596,9 @@ impl Engine {
                         let (loop_var, _) = scope.get_mut(index);
 
                         let value = iter_value.flatten();
+                        self.free_memory(state, loop_var);
+                        self.alloc_memory(state, &value)
+                            .map_err(|err| err.new_position(x.1.position()))?;
                         if cfg!(not(feature = "no_closure")) && loop_var.is_shared() {
                             *loop_var.write_lock().unwrap() = value;
                         } else {
@@ -1721,7 +2618,7 @@ impl Engine {
                         }
                     }
 
-                    scope.rewind(scope.len() - 1);
+                    self.rewind_scope(scope, state, index);
                     state.scope_level -= 1;
                     Ok(Default::default())
                 } else {
@@ -1729,6 +2626,41 @@ impl Engine {
                 }
             }
 
+            // Try/Catch statement
+            Stmt::TryCatch(x) => {
+                let (body, var_def, catch_body, _) = x.as_ref();
+                let prev_scope_len = scope.len();
+
+                match self.eval_stmt(scope, mods, state, lib, this_ptr, body, level) {
+                    Err(err) if err.is_catchable() => {
+                        self.rewind_scope(scope, state, prev_scope_len);
+                        state.scope_level += 1;
+
+                        if let Some((name, pos)) = var_def {
+                            let var_name = unsafe_cast_var_name_to_lifetime(name, &state);
+                            let value = (*err).into_caught_value();
+                            self.alloc_memory(state, &value)
+                                .map_err(|err| err.new_position(*pos))?;
+                            scope.push_dynamic_value(
+                                var_name,
+                                ScopeEntryType::Normal,
+                                value,
+                                false,
+                            );
+                        }
+
+                        let result =
+                            self.eval_stmt(scope, mods, state, lib, this_ptr, catch_body, level);
+
+                        self.rewind_scope(scope, state, prev_scope_len);
+                        state.scope_level -= 1;
+
+                        result
+                    }
+                    result => result,
+                }
+            }
+
             // Continue statement
             Stmt::Continue(pos) => EvalAltResult::ErrorLoopBreak(false, *pos).into(),
 
@@ -1753,12 +2685,19 @@ impl Engine {
             // Throw value
             Stmt::ReturnWithVal(x) if x.1.is_some() && (x.0).0 == ReturnType::Exception => {
                 let expr = x.1.as_ref().unwrap();
-                let val = self.eval_expr(scope, mods, state, lib, this_ptr, expr, level)?;
-                EvalAltResult::ErrorRuntime(
-                    val.take_string().unwrap_or_else(|_| "".into()),
-                    (x.0).1,
-                )
-                .into()
+                let val = self
+                    .eval_expr(scope, mods, state, lib, this_ptr, expr, level)?
+                    .flatten();
+
+                if val.is::<ImmutableString>() || val.is::<()>() {
+                    EvalAltResult::ErrorRuntime(
+                        val.take_string().unwrap_or_else(|_| "".into()),
+                        (x.0).1,
+                    )
+                    .into()
+                } else {
+                    EvalAltResult::ErrorThrown(val, (x.0).1).into()
+                }
             }
 
             // Empty throw
@@ -1770,29 +2709,136 @@ impl Engine {
 
             // Let statement
             Stmt::Let(x) if x.1.is_some() => {
-                let ((var_name, _), expr, _) = x.as_ref();
+                let ((var_name, pos), expr, _) = x.as_ref();
                 let expr = expr.as_ref().unwrap();
                 let val = self
                     .eval_expr(scope, mods, state, lib, this_ptr, expr, level)?
                     .flatten();
+                self.check_data_depth(&val)
+                    .map_err(|err| err.new_position(*pos))?;
+                self.alloc_memory(state, &val)
+                    .map_err(|err| err.new_position(*pos))?;
                 let var_name = unsafe_cast_var_name_to_lifetime(var_name, &state);
                 scope.push_dynamic_value(var_name, ScopeEntryType::Normal, val, false);
                 Ok(Default::default())
             }
 
             Stmt::Let(x) => {
-                let ((var_name, _), _, _) = x.as_ref();
+                let ((var_name, pos), _, _) = x.as_ref();
+                self.alloc_memory(state, &Default::default())
+                    .map_err(|err| err.new_position(*pos))?;
                 let var_name = unsafe_cast_var_name_to_lifetime(var_name, &state);
                 scope.push(var_name, ());
                 Ok(Default::default())
             }
 
+            // Destructuring let statement
+            #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+            Stmt::Destructure(x) => {
+                let (pattern, expr, _) = x.as_ref();
+                let val = self
+                    .eval_expr(scope, mods, state, lib, this_ptr, expr, level)?
+                    .flatten();
+                let typ = val.type_name();
+
+                let mismatch = |expected: &str| {
+                    EvalAltResult::ErrorMismatchOutputType(
+                        self.map_type_name(expected).into(),
+                        self.map_type_name(typ).into(),
+                        expr.position(),
+                    )
+                };
+
+                match pattern {
+                    #[cfg(not(feature = "no_index"))]
+                    Pattern::Array(names, rest) => {
+                        let arr = val
+                            .try_cast::<Array>()
+                            .ok_or_else(|| mismatch(type_name::<Array>()))?;
+                        let len = arr.len();
+                        let mut items = arr.into_iter();
+
+                        for (index, (var_name, pos)) in names.iter().enumerate() {
+                            let value = match items.next() {
+                                Some(value) => value,
+                                None if self.strict_destructuring => {
+                                    return EvalAltResult::ErrorArrayBounds(
+                                        len,
+                                        index as INT,
+                                        *pos,
+                                    )
+                                    .into();
+                                }
+                                None => Default::default(),
+                            };
+                            self.alloc_memory(state, &value)
+                                .map_err(|err| err.new_position(*pos))?;
+                            let var_name = unsafe_cast_var_name_to_lifetime(var_name, &state);
+                            scope.push_dynamic_value(
+                                var_name,
+                                ScopeEntryType::Normal,
+                                value,
+                                false,
+                            );
+                        }
+
+                        if let Some((var_name, pos)) = rest {
+                            let value: Dynamic = items.collect::<Array>().into();
+                            self.alloc_memory(state, &value)
+                                .map_err(|err| err.new_position(*pos))?;
+                            let var_name = unsafe_cast_var_name_to_lifetime(var_name, &state);
+                            scope.push_dynamic_value(
+                                var_name,
+                                ScopeEntryType::Normal,
+                                value,
+                                false,
+                            );
+                        }
+                    }
+                    #[cfg(not(feature = "no_object"))]
+                    Pattern::Map(names) => {
+                        let mut map = val
+                            .try_cast::<Map>()
+                            .ok_or_else(|| mismatch(type_name::<Map>()))?;
+
+                        for (var_name, pos) in names.iter() {
+                            let value = match map.shift_remove(var_name.as_str()) {
+                                Some(value) => value,
+                                None if self.strict_destructuring => {
+                                    return EvalAltResult::ErrorDotExpr(
+                                        format!("Unknown property '{}' in object map", var_name),
+                                        *pos,
+                                    )
+                                    .into();
+                                }
+                                None => Default::default(),
+                            };
+                            self.alloc_memory(state, &value)
+                                .map_err(|err| err.new_position(*pos))?;
+                            let var_name = unsafe_cast_var_name_to_lifetime(var_name, &state);
+                            scope.push_dynamic_value(
+                                var_name,
+                                ScopeEntryType::Normal,
+                                value,
+                                false,
+                            );
+                        }
+                    }
+                }
+
+                Ok(Default::default())
+            }
+
             // Const statement
             Stmt::Const(x) if x.1.is_constant() => {
-                let ((var_name, _), expr, _) = x.as_ref();
+                let ((var_name, pos), expr, _) = x.as_ref();
                 let val = self
                     .eval_expr(scope, mods, state, lib, this_ptr, &expr, level)?
                     .flatten();
+                self.check_data_depth(&val)
+                    .map_err(|err| err.new_position(*pos))?;
+                self.alloc_memory(state, &val)
+                    .map_err(|err| err.new_position(*pos))?;
                 let var_name = unsafe_cast_var_name_to_lifetime(var_name, &state);
                 scope.push_dynamic_value(var_name, ScopeEntryType::Constant, val, true);
                 Ok(Default::default())
@@ -1816,11 +2862,27 @@ impl Engine {
                     .eval_expr(scope, mods, state, lib, this_ptr, &expr, level)?
                     .try_cast::<ImmutableString>()
                 {
-                    if let Some(resolver) = &self.module_resolver {
+                    // Modules registered lazily take precedence over the module resolver
+                    // (they are already indexed and shared, so importing them does not clone)
+                    let module = if let Some(module) = self.lazy_modules.get(path.as_str()) {
+                        Some(module.get())
+                    } else if let Some(resolver) = &self.module_resolver {
                         let mut module = resolver.resolve(self, &path, expr.position())?;
+                        if alias.is_some() {
+                            module.index_all_sub_modules();
+                        }
+                        Some(Shared::new(module))
+                    } else {
+                        None
+                    };
+
+                    if let Some(module) = module {
+                        // Global functions can be called without qualification, even without an alias
+                        if module.has_global_fns() {
+                            state.global_modules.push(module.clone());
+                        }
 
                         if let Some((name, _)) = alias {
-                            module.index_all_sub_modules();
                             mods.push((name.clone().into(), module));
                         }
 
@@ -1877,6 +2939,30 @@ impl Engine {
             .map_err(|err| err.new_position(stmt.position()))
     }
 
+    /// Check that a value can be added to an array, if the array only accepts elements of one type.
+    /// Position in `EvalAltResult` is None and should be set afterwards.
+    #[cfg(not(feature = "no_index"))]
+    pub(crate) fn check_elem_type(
+        &self,
+        array: &Dynamic,
+        item: &Dynamic,
+    ) -> Result<(), Box<EvalAltResult>> {
+        match array.elem_type() {
+            Some(elem_type) if self.map_type_name(item.type_name()) != elem_type.as_str() => {
+                EvalAltResult::ErrorRuntime(
+                    format!(
+                        "Cannot add {} to a typed array of {}",
+                        self.map_type_name(item.type_name()),
+                        elem_type
+                    ),
+                    Position::none(),
+                )
+                .into()
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Check a result to ensure that the data size is within allowable limit.
     /// Position in `EvalAltResult` may be None and should be set afterwards.
     #[cfg(feature = "unchecked")]
@@ -1905,12 +2991,16 @@ impl Engine {
         fn calc_size(value: &Dynamic) -> (usize, usize, usize) {
             match value {
                 #[cfg(not(feature = "no_index"))]
-                Dynamic(Union::Array(arr)) => {
+                Dynamic(Union::Array(_)) | Dynamic(Union::TypedArray(_)) => {
                     let mut arrays = 0;
                     let mut maps = 0;
 
+                    let arr = value.downcast_ref::<Array>().unwrap();
+
                     arr.iter().for_each(|value| match value {
-                        Dynamic(Union::Array(_)) => {
+                        Dynamic(Union::Array(_))
+                        | Dynamic(Union::TypedArray(_))
+                        | Dynamic(Union::Frozen(_)) => {
                             let (a, m, _) = calc_size(value);
                             arrays += a;
                             maps += m;
@@ -1933,12 +3023,12 @@ impl Engine {
 
                     map.values().for_each(|value| match value {
                         #[cfg(not(feature = "no_index"))]
-                        Dynamic(Union::Array(_)) => {
+                        Dynamic(Union::Array(_)) | Dynamic(Union::TypedArray(_)) => {
                             let (a, m, _) = calc_size(value);
                             arrays += a;
                             maps += m;
                         }
-                        Dynamic(Union::Map(_)) => {
+                        Dynamic(Union::Map(_)) | Dynamic(Union::Frozen(_)) => {
                             let (a, m, _) = calc_size(value);
                             arrays += a;
                             maps += m;
@@ -1949,6 +3039,7 @@ impl Engine {
                     (arrays, maps, 0)
                 }
                 Dynamic(Union::Str(s)) => (0, 0, s.len()),
+                Dynamic(Union::Frozen(value)) => calc_size(value),
                 _ => (0, 0, 0),
             }
         }
@@ -1960,17 +3051,21 @@ impl Engine {
             Ok(Dynamic(Union::Str(_))) if self.limits.max_string_size > 0 => (),
             // Array with limit
             #[cfg(not(feature = "no_index"))]
-            Ok(Dynamic(Union::Array(_))) if self.limits.max_array_size > 0 => (),
+            Ok(Dynamic(Union::Array(_))) | Ok(Dynamic(Union::TypedArray(_)))
+                if self.limits.max_array_size > 0 => {}
             // Map with limit
             #[cfg(not(feature = "no_object"))]
-            Ok(Dynamic(Union::Map(_))) if self.limits.max_map_size > 0 => (),
+            Ok(Dynamic(Union::Map(_))) if self.limits.max_map_size > 0 => {}
+            // Frozen array or map with limit
+            Ok(Dynamic(Union::Frozen(_)))
+                if self.limits.max_array_size > 0 || self.limits.max_map_size > 0 => {}
             // Everything else is simply returned
             Ok(_) => return result,
         };
 
         let (arr, map, s) = calc_size(result.as_ref().unwrap());
 
-        if s > self.limits.max_string_size {
+        if self.limits.max_string_size > 0 && s > self.limits.max_string_size {
             EvalAltResult::ErrorDataTooLarge(
                 "Length of string".to_string(),
                 self.limits.max_string_size,
@@ -1978,7 +3073,7 @@ impl Engine {
                 Position::none(),
             )
             .into()
-        } else if arr > self.limits.max_array_size {
+        } else if self.limits.max_array_size > 0 && arr > self.limits.max_array_size {
             EvalAltResult::ErrorDataTooLarge(
                 "Size of array".to_string(),
                 self.limits.max_array_size,
@@ -1986,7 +3081,7 @@ impl Engine {
                 Position::none(),
             )
             .into()
-        } else if map > self.limits.max_map_size {
+        } else if self.limits.max_map_size > 0 && map > self.limits.max_map_size {
             EvalAltResult::ErrorDataTooLarge(
                 "Number of properties in object map".to_string(),
                 self.limits.max_map_size,
@@ -1999,6 +3094,240 @@ impl Engine {
         }
     }
 
+    /// Check that a value does not nest arrays and object maps too deeply.
+    /// Position in `EvalAltResult` is `None` and must be set afterwards.
+    #[cfg(feature = "unchecked")]
+    #[inline(always)]
+    pub(crate) fn check_data_depth(&self, _value: &Dynamic) -> Result<(), Box<EvalAltResult>> {
+        Ok(())
+    }
+
+    /// Check that a value does not nest arrays and object maps too deeply.
+    /// Position in `EvalAltResult` is `None` and must be set afterwards.
+    ///
+    /// This is only done at statement and function-call boundaries - i.e. when a value is stored
+    /// into a variable or returned from a script-defined function - and not after every expression.
+    #[cfg(not(feature = "unchecked"))]
+    pub(crate) fn check_data_depth(&self, value: &Dynamic) -> Result<(), Box<EvalAltResult>> {
+        // If no depth limit, just return
+        if self.limits.max_data_depth == 0 {
+            return Ok(());
+        }
+
+        // Calculate the nesting depth of a value, without going deeper than `max` levels
+        // so that the native stack cannot overflow on a deeply-nested value
+        fn calc_depth(value: &Dynamic, max: usize) -> usize {
+            if max == 0 {
+                return 0;
+            }
+
+            match value {
+                #[cfg(not(feature = "no_index"))]
+                Dynamic(Union::Array(_)) | Dynamic(Union::TypedArray(_)) => {
+                    1 + value
+                        .downcast_ref::<Array>()
+                        .unwrap()
+                        .iter()
+                        .map(|value| calc_depth(value, max - 1))
+                        .max()
+                        .unwrap_or(0)
+                }
+                #[cfg(not(feature = "no_object"))]
+                Dynamic(Union::Map(map)) => {
+                    1 + map
+                        .values()
+                        .map(|value| calc_depth(value, max - 1))
+                        .max()
+                        .unwrap_or(0)
+                }
+                Dynamic(Union::Frozen(value)) => calc_depth(value, max),
+                _ => 0,
+            }
+        }
+
+        let depth = calc_depth(value, self.limits.max_data_depth + 1);
+
+        if depth > self.limits.max_data_depth {
+            EvalAltResult::ErrorDataTooLarge(
+                "Nesting depth of arrays and object maps".to_string(),
+                self.limits.max_data_depth,
+                depth,
+                Position::none(),
+            )
+            .into()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Add the estimated memory used by a value newly held in a variable to the running total.
+    /// Position in `EvalAltResult` is `None` and must be set afterwards.
+    #[cfg(feature = "unchecked")]
+    #[inline(always)]
+    pub(crate) fn alloc_memory(
+        &self,
+        _state: &mut State,
+        _value: &Dynamic,
+    ) -> Result<(), Box<EvalAltResult>> {
+        Ok(())
+    }
+
+    /// Add the estimated memory used by a value newly held in a variable to the running total.
+    /// Position in `EvalAltResult` is `None` and must be set afterwards.
+    #[cfg(not(feature = "unchecked"))]
+    pub(crate) fn alloc_memory(
+        &self,
+        state: &mut State,
+        value: &Dynamic,
+    ) -> Result<(), Box<EvalAltResult>> {
+        if self.limits.max_memory == 0 {
+            return Ok(());
+        }
+
+        self.grow_memory(state, calc_memory(value))
+    }
+
+    /// Remove the estimated memory used by a value no longer held in a variable from the running total.
+    #[cfg(feature = "unchecked")]
+    #[inline(always)]
+    pub(crate) fn free_memory(&self, _state: &mut State, _value: &Dynamic) {}
+
+    /// Remove the estimated memory used by a value no longer held in a variable from the running total.
+    #[cfg(not(feature = "unchecked"))]
+    pub(crate) fn free_memory(&self, state: &mut State, value: &Dynamic) {
+        if self.limits.max_memory > 0 {
+            state.memory = state.memory.saturating_sub(calc_memory(value));
+        }
+    }
+
+    /// Take a mark of the estimated memory used by a value that may be modified in place,
+    /// together with the estimated memory of other values that may be moved into it.
+    /// Returns `None` if memory usage is not limited.
+    #[cfg(feature = "unchecked")]
+    #[inline(always)]
+    pub(crate) fn mark_memory(
+        &self,
+        _target: &Dynamic,
+        _inputs: &[&mut Dynamic],
+    ) -> Option<(usize, usize)> {
+        None
+    }
+
+    /// Take a mark of the estimated memory used by a value that may be modified in place,
+    /// together with the estimated memory of other values that may be moved into it.
+    /// Returns `None` if memory usage is not limited.
+    #[cfg(not(feature = "unchecked"))]
+    pub(crate) fn mark_memory(
+        &self,
+        target: &Dynamic,
+        inputs: &[&mut Dynamic],
+    ) -> Option<(usize, usize)> {
+        if self.limits.max_memory == 0 {
+            return None;
+        }
+
+        // A string only ever grows by its own characters
+        let incoming = if target.is::<ImmutableString>() {
+            0
+        } else {
+            inputs
+                .iter()
+                .map(|value| calc_memory(value) - size_of::<Dynamic>())
+                .sum()
+        };
+
+        Some((calc_own_memory(target), incoming))
+    }
+
+    /// Update the running total of estimated memory after a value marked by `mark_memory` may have
+    /// been modified in place.  `output` is the value returned by the modification (if any), which
+    /// usually holds anything that was removed.
+    /// Position in `EvalAltResult` is `None` and must be set afterwards.
+    #[cfg(feature = "unchecked")]
+    #[inline(always)]
+    pub(crate) fn update_memory(
+        &self,
+        _state: &mut State,
+        _mark: Option<(usize, usize)>,
+        _target: &Dynamic,
+        _output: &Dynamic,
+    ) -> Result<(), Box<EvalAltResult>> {
+        Ok(())
+    }
+
+    /// Update the running total of estimated memory after a value marked by `mark_memory` may have
+    /// been modified in place.  `output` is the value returned by the modification (if any), which
+    /// usually holds anything that was removed.
+    /// Position in `EvalAltResult` is `None` and must be set afterwards.
+    #[cfg(not(feature = "unchecked"))]
+    pub(crate) fn update_memory(
+        &self,
+        state: &mut State,
+        mark: Option<(usize, usize)>,
+        target: &Dynamic,
+        output: &Dynamic,
+    ) -> Result<(), Box<EvalAltResult>> {
+        let (before, incoming) = match mark {
+            Some(mark) => mark,
+            None => return Ok(()),
+        };
+
+        let after = calc_own_memory(target);
+
+        if after > before {
+            self.grow_memory(state, after - before + incoming)
+        } else {
+            if after < before {
+                let outgoing = if target.is::<ImmutableString>() {
+                    0
+                } else {
+                    calc_memory(output) - size_of::<Dynamic>()
+                };
+                state.memory = state.memory.saturating_sub(before - after + outgoing);
+            }
+            Ok(())
+        }
+    }
+
+    /// Add to the running total of estimated memory, checking it against the limit.
+    /// Position in `EvalAltResult` is `None` and must be set afterwards.
+    #[cfg(not(feature = "unchecked"))]
+    fn grow_memory(&self, state: &mut State, size: usize) -> Result<(), Box<EvalAltResult>> {
+        state.memory += size;
+
+        if state.memory > self.limits.max_memory {
+            return EvalAltResult::ErrorDataTooLarge(
+                "Memory usage".to_string(),
+                self.limits.max_memory,
+                state.memory,
+                Position::none(),
+            )
+            .into();
+        }
+
+        Ok(())
+    }
+
+    /// Remove all variables after the first `len` from a `Scope`.
+    #[cfg(feature = "unchecked")]
+    #[inline(always)]
+    pub(crate) fn rewind_scope(&self, scope: &mut Scope, _state: &mut State, len: usize) {
+        scope.rewind(len);
+    }
+
+    /// Remove all variables after the first `len` from a `Scope`, releasing their estimated memory.
+    #[cfg(not(feature = "unchecked"))]
+    pub(crate) fn rewind_scope(&self, scope: &mut Scope, state: &mut State, len: usize) {
+        if self.limits.max_memory > 0 {
+            scope
+                .iter()
+                .skip(len)
+                .for_each(|(_, _, value)| self.free_memory(state, value));
+        }
+
+        scope.rewind(len);
+    }
+
     /// Check if the number of operations stay within limit.
     /// Position in `EvalAltResult` is `None` and must be set afterwards.
     pub(crate) fn inc_operations(&self, state: &mut State) -> Result<(), Box<EvalAltResult>> {
@@ -10,12 +10,7 @@ use crate::stdlib::{
     string::{String, ToString},
 };
 
-/// [INTERNALS] Error encountered when tokenizing the script text.
-/// Exported under the `internals` feature only.
-///
-/// ## WARNING
-///
-/// This type is volatile and may change.
+/// Error encountered when tokenizing the script text.
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
 #[non_exhaustive]
 pub enum LexError {
@@ -23,6 +18,8 @@ pub enum LexError {
     UnexpectedInput(String),
     /// A string literal is not terminated before a new-line or EOF.
     UnterminatedString,
+    /// A block comment is not terminated before EOF.
+    UnterminatedComment,
     /// An identifier is in an invalid format.
     StringTooLong(usize),
     /// An string/character/numeric escape sequence is in an invalid format.
@@ -48,6 +45,7 @@ impl fmt::Display for LexError {
             Self::MalformedChar(s) => write!(f, "Invalid character: '{}'", s),
             Self::MalformedIdentifier(s) => write!(f, "Variable name is not proper: '{}'", s),
             Self::UnterminatedString => write!(f, "Open string is not terminated"),
+            Self::UnterminatedComment => write!(f, "Open block comment is not terminated"),
             Self::StringTooLong(max) => write!(
                 f,
                 "Length of string literal exceeds the maximum limit ({})",
@@ -75,6 +73,9 @@ impl LexError {
 pub enum ParseErrorType {
     /// Error in the script text. Wrapped value is the error message.
     BadInput(String),
+    /// Error when tokenizing the script text (e.g. an unterminated string literal).
+    /// Wrapped value is the error.
+    LexError(LexError),
     /// The script ends prematurely.
     UnexpectedEOF,
     /// An unknown operator is encountered. Wrapped value is the operator.
@@ -101,6 +102,9 @@ pub enum ParseErrorType {
     DuplicatedProperty(String),
     /// Invalid expression assigned to constant. Wrapped value is the name of the constant.
     ForbiddenConstantExpr(String),
+    /// The expression assigned to a constant cannot be evaluated at compile time.
+    /// Wrapped values are the name of the constant and the reason.
+    NonConstantExpr(String, String),
     /// Missing a property name for custom types and maps.
     ///
     /// Never appears under the `no_object` feature.
@@ -127,6 +131,11 @@ pub enum ParseErrorType {
     ///
     /// Never appears under the `no_function` feature.
     FnDuplicatedParam(String, String),
+    /// A function definition has more parameters than allowed.
+    /// Wrapped values are the function name and the maximum number of parameters.
+    ///
+    /// Never appears under the `no_function` or `unchecked` feature.
+    FnTooManyParams(String, usize),
     /// A function definition is missing the body. Wrapped value is the function name.
     ///
     /// Never appears under the `no_function` feature.
@@ -153,6 +162,14 @@ pub enum ParseErrorType {
     LiteralTooLarge(String, usize),
     /// Break statement not inside a loop.
     LoopBreak,
+    /// Call to a built-in function that is disabled (e.g. `eval`). Wrapped value is the function name.
+    DisabledFunction(String),
+    /// A function or constant is defined in more than one script combined into a module.
+    /// Wrapped values are the function signature (or constant name) and the name of the script
+    /// that first defines it.
+    ///
+    /// Never appears under the `no_module` feature.
+    DuplicatedDefinition(String, String),
 }
 
 impl ParseErrorType {
@@ -164,6 +181,7 @@ impl ParseErrorType {
     pub(crate) fn desc(&self) -> &str {
         match self {
             Self::BadInput(p) => p,
+            Self::LexError(_) => "Invalid script text",
             Self::UnexpectedEOF => "Script is incomplete",
             Self::UnknownOperator(_) => "Unknown operator",
             Self::MissingToken(_, _) => "Expecting a certain token that is missing",
@@ -173,6 +191,7 @@ impl ParseErrorType {
             Self::MalformedCapture(_) => "Invalid capturing",
             Self::DuplicatedProperty(_) => "Duplicated property in object map literal",
             Self::ForbiddenConstantExpr(_) => "Expecting a constant",
+            Self::NonConstantExpr(_, _) => "Not a constant expression",
             Self::PropertyExpected => "Expecting name of a property",
             Self::VariableExpected => "Expecting name of a variable",
             Self::Reserved(_) => "Invalid use of reserved keyword",
@@ -180,6 +199,7 @@ impl ParseErrorType {
             Self::FnMissingName => "Expecting function name in function declaration",
             Self::FnMissingParams(_) => "Expecting parameters in function declaration",
             Self::FnDuplicatedParam(_,_) => "Duplicated parameters in function declaration",
+            Self::FnTooManyParams(_,_) => "Too many parameters in function declaration",
             Self::FnMissingBody(_) => "Expecting body statement block for function declaration",
             Self::WrongFnDefinition => "Function definitions must be at global level and cannot be inside a block or another function",
             Self::DuplicatedExport(_) => "Duplicated variable/function in export statement",
@@ -188,7 +208,9 @@ impl ParseErrorType {
             Self::AssignmentToConstant(_) => "Cannot assign to a constant value",
             Self::ExprTooDeep => "Expression exceeds maximum complexity",
             Self::LiteralTooLarge(_, _) => "Literal exceeds maximum limit",
-            Self::LoopBreak => "Break statement should only be used inside a loop"
+            Self::LoopBreak => "Break statement should only be used inside a loop",
+            Self::DisabledFunction(_) => "Call to a disabled function",
+            Self::DuplicatedDefinition(_, _) => "Duplicated definition in module scripts",
         }
     }
 }
@@ -199,9 +221,13 @@ impl fmt::Display for ParseErrorType {
             Self::BadInput(s) | ParseErrorType::MalformedCallExpr(s) => {
                 f.write_str(if s.is_empty() { self.desc() } else { s })
             }
+            Self::LexError(err) => fmt::Display::fmt(err, f),
             Self::ForbiddenConstantExpr(s) => {
                 write!(f, "Expecting a constant to assign to '{}'", s)
             }
+            Self::NonConstantExpr(s, r) => {
+                write!(f, "{} assigned to '{}': {}", self.desc(), s, r)
+            }
             Self::UnknownOperator(s) => write!(f, "{}: '{}'", self.desc(), s),
 
             Self::MalformedIndexExpr(s) | Self::MalformedInExpr(s) | Self::MalformedCapture(s) => {
@@ -227,6 +253,17 @@ impl fmt::Display for ParseErrorType {
                 write!(f, "Duplicated parameter '{}' for function '{}'", arg, s)
             }
 
+            Self::FnTooManyParams(s, max) if s.is_empty() => write!(
+                f,
+                "Anonymous function has more than the maximum of {} parameters",
+                max
+            ),
+            Self::FnTooManyParams(s, max) => write!(
+                f,
+                "Function '{}' has more than the maximum of {} parameters",
+                s, max
+            ),
+
             Self::DuplicatedExport(s) => write!(
                 f,
                 "Duplicated variable/function '{}' in export statement",
@@ -241,6 +278,10 @@ impl fmt::Display for ParseErrorType {
                 write!(f, "{} exceeds the maximum limit ({})", typ, max)
             }
             Self::Reserved(s) => write!(f, "'{}' is a reserved keyword", s),
+            Self::DisabledFunction(s) => write!(f, "'{}' is disabled", s),
+            Self::DuplicatedDefinition(s, source) => {
+                write!(f, "'{}' is already defined in '{}'", s, source)
+            }
             _ => f.write_str(self.desc()),
         }
     }
@@ -252,7 +293,142 @@ impl From<&LexError> for ParseErrorType {
             LexError::StringTooLong(max) => {
                 Self::LiteralTooLarge("Length of string literal".to_string(), *max)
             }
-            _ => Self::BadInput(err.to_string()),
+            _ => Self::LexError(err.clone()),
+        }
+    }
+}
+
+/// Kind of error encountered when parsing a script, without any details.
+///
+/// Each kind corresponds to a variant of `ParseErrorType`, except that errors from tokenizing the
+/// script text are split into separate kinds.  This is useful for mapping errors into stable
+/// diagnostic codes (e.g. in an editor integration).
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// Error in the script text.
+    BadInput,
+    /// An unexpected token or symbol.
+    UnexpectedToken,
+    /// A string literal is not terminated before a new-line or EOF.
+    UnterminatedString,
+    /// A block comment is not terminated before EOF.
+    UnterminatedComment,
+    /// An invalid escape sequence.
+    MalformedEscapeSequence,
+    /// An invalid numeric literal.
+    MalformedNumber,
+    /// An invalid character literal.
+    MalformedChar,
+    /// An invalid identifier.
+    MalformedIdentifier,
+    /// An improper symbol (e.g. `===`).
+    ImproperSymbol,
+    /// The script ends prematurely.
+    UnexpectedEOF,
+    /// An unknown operator.
+    UnknownOperator,
+    /// Expecting a particular token but not finding one.
+    MissingToken,
+    /// Syntax error in function call arguments.
+    MalformedCallExpr,
+    /// Syntax error in indexing brackets.
+    MalformedIndexExpr,
+    /// Syntax error in an `in` expression.
+    MalformedInExpr,
+    /// Syntax error in a capture.
+    MalformedCapture,
+    /// Duplicated property names in an object map literal.
+    DuplicatedProperty,
+    /// Invalid expression assigned to a constant.
+    ForbiddenConstantExpr,
+    /// The expression assigned to a constant cannot be evaluated at compile time.
+    NonConstantExpr,
+    /// Missing a property name.
+    PropertyExpected,
+    /// Missing a variable name.
+    VariableExpected,
+    /// An identifier is a reserved keyword.
+    Reserved,
+    /// Missing an expression.
+    ExprExpected,
+    /// A function definition in an inappropriate place.
+    WrongFnDefinition,
+    /// Missing a function name.
+    FnMissingName,
+    /// Missing the parameters list of a function definition.
+    FnMissingParams,
+    /// Duplicated parameters in a function definition.
+    FnDuplicatedParam,
+    /// Too many parameters in a function definition.
+    FnTooManyParams,
+    /// Missing the body of a function definition.
+    FnMissingBody,
+    /// Duplicated names in an export statement.
+    DuplicatedExport,
+    /// Export statement not at global level.
+    WrongExport,
+    /// Assignment to a copy of a value.
+    AssignmentToCopy,
+    /// Assignment to a constant variable.
+    AssignmentToConstant,
+    /// Expression exceeding the maximum levels of complexity.
+    ExprTooDeep,
+    /// Literal exceeding the maximum size.
+    LiteralTooLarge,
+    /// Break statement not inside a loop.
+    LoopBreak,
+    /// Call to a built-in function that is disabled.
+    DisabledFunction,
+    /// A function or constant defined in more than one script combined into a module.
+    DuplicatedDefinition,
+}
+
+impl ParseErrorType {
+    /// Get the kind of this error.
+    pub fn kind(&self) -> ParseErrorKind {
+        match self {
+            Self::BadInput(_) => ParseErrorKind::BadInput,
+            Self::LexError(err) => match err {
+                LexError::UnexpectedInput(_) => ParseErrorKind::UnexpectedToken,
+                LexError::UnterminatedString => ParseErrorKind::UnterminatedString,
+                LexError::UnterminatedComment => ParseErrorKind::UnterminatedComment,
+                LexError::StringTooLong(_) => ParseErrorKind::LiteralTooLarge,
+                LexError::MalformedEscapeSequence(_) => ParseErrorKind::MalformedEscapeSequence,
+                LexError::MalformedNumber(_) => ParseErrorKind::MalformedNumber,
+                LexError::MalformedChar(_) => ParseErrorKind::MalformedChar,
+                LexError::MalformedIdentifier(_) => ParseErrorKind::MalformedIdentifier,
+                LexError::ImproperSymbol(_) => ParseErrorKind::ImproperSymbol,
+            },
+            Self::UnexpectedEOF => ParseErrorKind::UnexpectedEOF,
+            Self::UnknownOperator(_) => ParseErrorKind::UnknownOperator,
+            Self::MissingToken(_, _) => ParseErrorKind::MissingToken,
+            Self::MalformedCallExpr(_) => ParseErrorKind::MalformedCallExpr,
+            Self::MalformedIndexExpr(_) => ParseErrorKind::MalformedIndexExpr,
+            Self::MalformedInExpr(_) => ParseErrorKind::MalformedInExpr,
+            Self::MalformedCapture(_) => ParseErrorKind::MalformedCapture,
+            Self::DuplicatedProperty(_) => ParseErrorKind::DuplicatedProperty,
+            Self::ForbiddenConstantExpr(_) => ParseErrorKind::ForbiddenConstantExpr,
+            Self::NonConstantExpr(_, _) => ParseErrorKind::NonConstantExpr,
+            Self::PropertyExpected => ParseErrorKind::PropertyExpected,
+            Self::VariableExpected => ParseErrorKind::VariableExpected,
+            Self::Reserved(_) => ParseErrorKind::Reserved,
+            Self::ExprExpected(_) => ParseErrorKind::ExprExpected,
+            Self::WrongFnDefinition => ParseErrorKind::WrongFnDefinition,
+            Self::FnMissingName => ParseErrorKind::FnMissingName,
+            Self::FnMissingParams(_) => ParseErrorKind::FnMissingParams,
+            Self::FnDuplicatedParam(_, _) => ParseErrorKind::FnDuplicatedParam,
+            Self::FnTooManyParams(_, _) => ParseErrorKind::FnTooManyParams,
+            Self::FnMissingBody(_) => ParseErrorKind::FnMissingBody,
+            Self::DuplicatedExport(_) => ParseErrorKind::DuplicatedExport,
+            Self::WrongExport => ParseErrorKind::WrongExport,
+            Self::AssignmentToCopy => ParseErrorKind::AssignmentToCopy,
+            Self::AssignmentToConstant(_) => ParseErrorKind::AssignmentToConstant,
+            Self::ExprTooDeep => ParseErrorKind::ExprTooDeep,
+            Self::LiteralTooLarge(_, _) => ParseErrorKind::LiteralTooLarge,
+            Self::LoopBreak => ParseErrorKind::LoopBreak,
+            Self::DisabledFunction(_) => ParseErrorKind::DisabledFunction,
+            Self::DuplicatedDefinition(_, _) => ParseErrorKind::DuplicatedDefinition,
         }
     }
 }
@@ -263,6 +439,34 @@ pub struct ParseError(pub Box<ParseErrorType>, pub Position);
 
 impl Error for ParseError {}
 
+impl ParseError {
+    /// Get the type of this error, with its details.
+    #[inline(always)]
+    pub fn error_type(&self) -> &ParseErrorType {
+        &self.0
+    }
+
+    /// Get the kind of this error.
+    #[inline(always)]
+    pub fn error_kind(&self) -> ParseErrorKind {
+        self.0.kind()
+    }
+
+    /// Get the location in the script text where this error occurred.
+    #[inline(always)]
+    pub fn position(&self) -> Position {
+        self.1
+    }
+
+    /// Get the token that was expected but not found, if the error is a missing token.
+    pub fn expected_token(&self) -> Option<&str> {
+        match self.0.as_ref() {
+            ParseErrorType::MissingToken(token, _) => Some(token),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Display::fmt(&self.0, f)?;
@@ -8,14 +8,15 @@ use crate::engine::{
     KEYWORD_PRINT, KEYWORD_TYPE_OF,
 };
 use crate::error::ParseErrorType;
-use crate::fn_native::{FnCallArgs, FnPtr};
+use crate::fn_native::{CallableFunction, FnCallArgs, FnPtr, NativeCallContext};
 use crate::module::{Module, ModuleRef};
 use crate::optimize::OptimizationLevel;
-use crate::parser::{Expr, ImmutableString, AST, INT};
-use crate::result::EvalAltResult;
+use crate::packages::arithmetic::div_by_zero;
+use crate::parser::{Expr, FnAccess, ImmutableString, AST, INT};
+use crate::result::{CaughtError, EvalAltResult};
 use crate::scope::Scope;
 use crate::stdlib::ops::Deref;
-use crate::token::Position;
+use crate::token::{is_valid_identifier, lex, Position};
 use crate::utils::StaticVec;
 
 #[cfg(not(feature = "no_function"))]
@@ -27,8 +28,11 @@ use crate::{
 #[cfg(not(feature = "no_float"))]
 use crate::parser::FLOAT;
 
+#[cfg(not(feature = "no_float"))]
+use crate::packages::logic::compare_int_float_op;
+
 #[cfg(not(feature = "no_index"))]
-use crate::engine::{FN_IDX_GET, FN_IDX_SET};
+use crate::engine::{Array, FN_IDX_GET, FN_IDX_SET};
 
 #[cfg(not(feature = "no_object"))]
 use crate::engine::{Map, Target, FN_GET, FN_SET};
@@ -40,22 +44,26 @@ use crate::scope::Entry as ScopeEntry;
 use crate::stdlib::{
     any::{type_name, TypeId},
     boxed::Box,
+    cmp::Ordering,
     convert::TryFrom,
     format,
     iter::{empty, once},
     mem,
-    string::ToString,
+    string::{String, ToString},
     vec::Vec,
 };
 
 #[cfg(not(feature = "no_closure"))]
 #[cfg(not(feature = "no_function"))]
-use crate::stdlib::{collections::HashSet, string::String};
+use crate::stdlib::collections::HashSet;
 
 #[cfg(feature = "no_std")]
 #[cfg(not(feature = "no_float"))]
 use num_traits::float::Float;
 
+/// Maximum number of overloads listed in the error when no function signature matches.
+const MAX_OVERLOADS_LISTED: usize = 5;
+
 /// Extract the property name from a getter function name.
 #[inline(always)]
 fn extract_prop_from_getter(_fn_name: &str) -> Option<&str> {
@@ -78,10 +86,47 @@ fn extract_prop_from_setter(_fn_name: &str) -> Option<&str> {
     None
 }
 
+/// Name of the method that an operator falls back to when no function is found for it.
+/// See `Engine::set_operator_method_fallback`.
+fn operator_method_name(op: &str, num_args: usize) -> Option<&'static str> {
+    Some(match (op, num_args) {
+        ("-", 1) => "neg",
+        ("!", 1) => "not",
+        ("+", 2) => "add",
+        ("-", 2) => "sub",
+        ("*", 2) => "mul",
+        ("/", 2) => "div",
+        ("%", 2) => "rem",
+        ("**", 2) => "pow",
+        ("<<", 2) => "shl",
+        (">>", 2) => "shr",
+        ("&", 2) => "bitand",
+        ("|", 2) => "bitor",
+        ("^", 2) => "bitxor",
+        ("==", 2) => "eq",
+        ("!=", 2) => "ne",
+        ("<", 2) => "lt",
+        ("<=", 2) => "le",
+        (">", 2) => "gt",
+        (">=", 2) => "ge",
+        _ => return None,
+    })
+}
+
+/// Is the error reporting that the function `fn_name` itself is not found?
+fn is_fn_not_found(err: &EvalAltResult, fn_name: &str) -> bool {
+    match err {
+        EvalAltResult::ErrorFunctionNotFound(sig, _) => {
+            sig.starts_with(fn_name) && sig[fn_name.len()..].starts_with(" (")
+        }
+        _ => false,
+    }
+}
+
 /// A type that temporarily stores a mutable reference to a `Dynamic`,
 /// replacing it with a cloned copy.
 #[derive(Debug, Default)]
-struct ArgBackup<'a> {
+pub(crate) struct ArgBackup<'a> {
     orig_mut: Option<&'a mut Dynamic>,
     value_copy: Dynamic,
 }
@@ -97,7 +142,7 @@ impl<'a> ArgBackup<'a> {
     /// This method blindly casts a reference to another lifetime, which saves allocation and string cloning.
     ///
     /// If `restore_first_arg` is called before the end of the scope, the shorter lifetime will not leak.
-    fn change_first_arg_to_copy(&mut self, normalize: bool, args: &mut FnCallArgs<'a>) {
+    pub(crate) fn change_first_arg_to_copy(&mut self, normalize: bool, args: &mut FnCallArgs<'a>) {
         // Only do it for method calls with arguments.
         if !normalize || args.is_empty() {
             return;
@@ -127,7 +172,7 @@ impl<'a> ArgBackup<'a> {
     ///
     /// If `change_first_arg_to_copy` has been called, this function **MUST** be called _BEFORE_ exiting
     /// the current scope.  Otherwise it is undefined behavior as the shorter lifetime will leak.
-    fn restore_first_arg(&mut self, args: &mut FnCallArgs<'a>) {
+    pub(crate) fn restore_first_arg(&mut self, args: &mut FnCallArgs<'a>) {
         if let Some(this_pointer) = self.orig_mut.take() {
             args[0] = this_pointer;
         }
@@ -194,6 +239,19 @@ pub fn ensure_no_data_race(
 }
 
 impl Engine {
+    /// Report a call to a deprecated plugin function to the callback registered with
+    /// `on_deprecated` (if any), the first time the function is called during an evaluation.
+    pub(crate) fn report_deprecated(&self, state: &mut State, name: &str, func: &CallableFunction) {
+        if let Some(ref callback) = self.deprecated {
+            if let Some(message) = func.get_plugin_fn().deprecated() {
+                if !state.deprecated_fns.contains(name) {
+                    state.deprecated_fns.insert(name.to_string());
+                    callback(name, message);
+                }
+            }
+        }
+    }
+
     /// Call a native Rust function registered with the `Engine`.
     /// Position in `EvalAltResult` is `None` and must be set afterwards.
     ///
@@ -212,17 +270,87 @@ impl Engine {
         is_ref: bool,
         pub_only: bool,
         def_val: Option<bool>,
+        level: usize,
     ) -> Result<(Dynamic, bool), Box<EvalAltResult>> {
         self.inc_operations(state)?;
 
+        // Division by zero
+        if args.len() == 2 && (fn_name == "/" || fn_name == "%") {
+            if let Some(result) = div_by_zero(self, fn_name, args[0], args[1])? {
+                return Ok((result, false));
+            }
+        }
+
         // Search for the native function
         // First search registered functions (can override packages)
         // Then search packages
-        let func = self
+        let mut func = self
             .global_module
-            .get_fn(hash_fn, pub_only)
+            .get_fn_by_hash(hash_fn, pub_only)
             .or_else(|| self.packages.get_fn(hash_fn, pub_only));
 
+        // Then search global functions in imported modules
+        #[cfg(not(feature = "no_module"))]
+        let global_fn = match func {
+            None => state.get_global_fn(hash_fn, pub_only),
+            Some(_) => None,
+        };
+        #[cfg(not(feature = "no_module"))]
+        {
+            func = func.or(global_fn.as_ref());
+        }
+
+        if func.is_none() {
+            // See if it is built in.
+            if args.len() == 2 {
+                match run_builtin_binary_op(fn_name, args[0], args[1])? {
+                    Some(v) => return Ok((v, false)),
+                    None => (),
+                }
+            }
+
+            // Search for a function taking only `Dynamic` parameters, which accepts arguments of any type,
+            // but only among the few functions registered that way
+            if !args.is_empty()
+                && (self.global_module.has_dynamic_fns() || self.packages.has_dynamic_fns())
+            {
+                let hash_dynamic = calc_fn_hash(
+                    empty(),
+                    fn_name,
+                    args.len(),
+                    args.iter().map(|_| TypeId::of::<Dynamic>()),
+                );
+
+                func = self
+                    .global_module
+                    .get_dynamic_fn(hash_dynamic, pub_only)
+                    .or_else(|| self.packages.get_dynamic_fn(hash_dynamic, pub_only));
+            }
+
+            // Search for a variadic plugin function taking the leading arguments,
+            // preferring the one with the most fixed parameters
+            if func.is_none() {
+                func = (0..args.len()).rev().find_map(|num_fixed| {
+                    // Native functions with no parameters are hashed differently from script functions
+                    let hash_varadic = calc_fn_hash(
+                        empty(),
+                        fn_name,
+                        if num_fixed == 0 {
+                            usize::MAX
+                        } else {
+                            num_fixed
+                        },
+                        args[..num_fixed].iter().map(|a| a.type_id()),
+                    );
+
+                    self.global_module
+                        .get_fn_by_hash(hash_varadic, pub_only)
+                        .or_else(|| self.packages.get_fn(hash_varadic, pub_only))
+                        .filter(|f| f.is_varadic())
+                });
+            }
+        }
+
         if let Some(func) = func {
             assert!(func.is_native());
 
@@ -230,11 +358,25 @@ impl Engine {
             let mut backup: ArgBackup = Default::default();
             backup.change_first_arg_to_copy(is_ref && func.is_pure(), args);
 
+            // The first argument may be modified in place
+            let memory = if is_ref && !func.is_pure() {
+                self.mark_memory(args[0], &args[1..])
+            } else {
+                None
+            };
+
             // Run external function
             let result = if func.is_plugin_fn() {
+                self.report_deprecated(state, fn_name, func);
                 func.get_plugin_fn().call(args)
             } else {
-                func.get_native_fn()(self, lib, args)
+                let context = &mut NativeCallContext {
+                    engine: self,
+                    lib,
+                    state,
+                    level,
+                };
+                func.get_native_fn()(context, args)
             };
 
             // Restore the original reference
@@ -242,6 +384,10 @@ impl Engine {
 
             let result = result?;
 
+            if memory.is_some() {
+                self.update_memory(state, memory, args[0], &result)?;
+            }
+
             // See if the function match print/debug (which requires special processing)
             return Ok(match fn_name {
                 KEYWORD_PRINT => (
@@ -270,14 +416,6 @@ impl Engine {
             });
         }
 
-        // See if it is built in.
-        if args.len() == 2 {
-            match run_builtin_binary_op(fn_name, args[0], args[1])? {
-                Some(v) => return Ok((v, false)),
-                None => (),
-            }
-        }
-
         // Return default value (if any)
         if let Some(val) = def_val {
             return Ok((val.into(), false));
@@ -337,25 +475,237 @@ impl Engine {
             .into();
         }
 
+        let signature = self.make_fn_signature(fn_name, args);
+
+        // List other overloads of the same function, if any
+        let overloads = self.list_overloads(lib, fn_name, args);
+
         // Raise error
         EvalAltResult::ErrorFunctionNotFound(
-            format!(
-                "{} ({})",
-                fn_name,
-                args.iter()
-                    .map(|name| if name.is::<ImmutableString>() {
-                        "&str | ImmutableString | String"
-                    } else {
-                        self.map_type_name((*name).type_name())
-                    })
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            ),
+            if overloads.is_empty() {
+                signature
+            } else {
+                format!("{} - available: {}", signature, overloads.join("; "))
+            },
             Position::none(),
         )
         .into()
     }
 
+    /// Make the signature of a function call, as shown in `ErrorFunctionNotFound`.
+    pub(crate) fn make_fn_signature(&self, fn_name: &str, args: &FnCallArgs) -> String {
+        format!(
+            "{} ({})",
+            fn_name,
+            args.iter()
+                .map(|name| if name.is::<ImmutableString>() {
+                    "&str | ImmutableString | String"
+                } else {
+                    self.map_type_name((*name).type_name())
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+
+    /// List the signatures of the functions with a particular name, most relevant first.
+    ///
+    /// Overloads taking the type of the first argument come first, then those with the closest
+    /// number of parameters.  Only the first `MAX_OVERLOADS_LISTED` are listed, followed by
+    /// the number of overloads left out.
+    ///
+    /// Operators are excluded because they are usually heavily overloaded.  So are overloads in
+    /// packages taking internal types that cannot be named (e.g. `u8` when not among the arguments).
+    fn list_overloads(&self, lib: &Module, fn_name: &str, args: &FnCallArgs) -> Vec<String> {
+        if !is_valid_identifier(fn_name.chars()) {
+            return Vec::new();
+        }
+
+        let first_arg = args.first().map(|a| a.type_id());
+
+        let rank = |types: &Option<StaticVec<TypeId>>, num_params: usize| {
+            let first_matches = match (types.as_ref().and_then(|t| t.first()), first_arg) {
+                (Some(&t), Some(arg)) => t == arg || t == TypeId::of::<Dynamic>(),
+                _ => true,
+            };
+            let distance = if num_params > args.len() {
+                num_params - args.len()
+            } else {
+                args.len() - num_params
+            };
+            (!first_matches, distance)
+        };
+
+        let script_fns = lib.iter_fn().map(|f| (f, false));
+        let native_fns = self
+            .global_module
+            .iter_fn()
+            .map(|f| (f, false))
+            .chain(
+                self.packages
+                    .iter()
+                    .flat_map(|m| m.iter_fn())
+                    .map(|f| (f, true)),
+            )
+            .filter(|((_, access, _, _, _), _)| *access == FnAccess::Public);
+
+        let mut overloads: Vec<_> = script_fns
+            .chain(native_fns)
+            .filter(|((name, _, _, _, _), _)| name == fn_name)
+            .filter_map(|((_, _, num_params, types, _func), in_package)| {
+                let params: Vec<_> = match types {
+                    Some(types) => types
+                        .iter()
+                        .map(|&t| self.map_type_id_name(t, args).to_string())
+                        .collect(),
+                    #[cfg(not(feature = "no_function"))]
+                    None if _func.is_script() => _func.get_fn_def().params.to_vec(),
+                    _ => (0..*num_params).map(|_| "?".to_string()).collect(),
+                };
+
+                if in_package && params.iter().any(|p| p == "?") {
+                    return None;
+                }
+
+                let signature = format!("{} ({})", fn_name, params.join(", "));
+                Some((rank(types, *num_params), signature))
+            })
+            .collect();
+
+        overloads.sort();
+        overloads.dedup_by(|(_, a), (_, b)| a == b);
+
+        let num_overloads = overloads.len();
+
+        let mut overloads: Vec<_> = overloads
+            .into_iter()
+            .take(MAX_OVERLOADS_LISTED)
+            .map(|(_, signature)| signature)
+            .collect();
+
+        if num_overloads > MAX_OVERLOADS_LISTED {
+            overloads.push(format!("and {} more", num_overloads - MAX_OVERLOADS_LISTED));
+        }
+
+        overloads
+    }
+
+    /// Make sure that a curried function pointer does not carry more arguments than the
+    /// parameters of the function it refers to.
+    ///
+    /// Nothing is checked if there is no function of that name (yet).
+    fn check_curry_arity(&self, lib: &Module, fn_ptr: &FnPtr) -> Result<(), Box<EvalAltResult>> {
+        let fn_name = fn_ptr.fn_name();
+        let num_curried = fn_ptr.curry().len();
+
+        // None + function name + no arguments.
+        let hash_name = calc_fn_hash(empty(), fn_name, 0, empty());
+
+        let max_params = once(lib)
+            .chain(once(&self.global_module))
+            .chain(self.packages.iter())
+            .filter_map(|m| m.get_fn_max_params(hash_name))
+            .max();
+
+        match max_params {
+            Some(num_params) if num_curried > num_params => EvalAltResult::ErrorRuntime(
+                format!(
+                    "Cannot curry {} argument(s) into function '{}', which takes at most {} parameter(s)",
+                    num_curried, fn_name, num_params
+                ),
+                Position::none(),
+            )
+            .into(),
+            _ => Ok(()),
+        }
+    }
+
+    /// Compare two values with the `<` operator, for sorting and for `min`/`max`.
+    pub(crate) fn compare_values(
+        &self,
+        state: &mut State,
+        lib: &Module,
+        x: &Dynamic,
+        y: &Dynamic,
+        level: usize,
+    ) -> Result<Ordering, Box<EvalAltResult>> {
+        let op = "<";
+
+        let mut less_than = |a: &Dynamic, b: &Dynamic| {
+            let args = &mut [&mut a.clone(), &mut b.clone()];
+
+            // Qualifiers (none) + function name + number of arguments + argument `TypeId`'s.
+            let hash = calc_fn_hash(empty(), op, args.len(), args.iter().map(|a| a.type_id()));
+
+            self.call_native_fn(state, lib, op, hash, args, false, false, None, level)
+                .map(|(v, _)| v.as_bool().unwrap_or(false))
+                .map_err(|err| match *err {
+                    EvalAltResult::ErrorFunctionNotFound(ref f, _) if f.starts_with(op) => {
+                        self.make_not_comparable_error(a, b)
+                    }
+                    _ => err,
+                })
+        };
+
+        Ok(if less_than(x, y)? {
+            Ordering::Less
+        } else if less_than(y, x)? {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        })
+    }
+
+    /// Make the error raised when no comparison operator is defined between two values.
+    pub(crate) fn make_not_comparable_error(&self, x: &Dynamic, y: &Dynamic) -> Box<EvalAltResult> {
+        let message = if x.type_id() == y.type_id() {
+            format!(
+                "Type {} is not comparable",
+                self.map_type_name(x.type_name())
+            )
+        } else {
+            format!(
+                "Type {} is not comparable with {}",
+                self.map_type_name(x.type_name()),
+                self.map_type_name(y.type_name())
+            )
+        };
+
+        EvalAltResult::ErrorRuntime(message, Position::none()).into()
+    }
+
+    /// Map a `TypeId` into a pretty-print name, using the function call arguments
+    /// to find the names of custom types.
+    ///
+    /// Returns `?` if the type is unknown.
+    fn map_type_id_name(&self, type_id: TypeId, args: &FnCallArgs) -> &str {
+        if let Some(arg) = args.iter().find(|a| a.type_id() == type_id) {
+            return self.map_type_name(arg.type_name());
+        }
+
+        macro_rules! map_type {
+            ($($type:ty),*) => { $(
+                if type_id == TypeId::of::<$type>() {
+                    return self.map_type_name(type_name::<$type>());
+                }
+            )* };
+        }
+
+        if type_id == TypeId::of::<Dynamic>() {
+            return "Dynamic";
+        }
+
+        map_type!(INT, bool, char, ImmutableString, (), FnPtr);
+        #[cfg(not(feature = "no_float"))]
+        map_type!(FLOAT);
+        #[cfg(not(feature = "no_index"))]
+        map_type!(Array);
+        #[cfg(not(feature = "no_object"))]
+        map_type!(Map);
+
+        "?"
+    }
+
     /// Call a script-defined function.
     /// Position in `EvalAltResult` is `None` and must be set afterwards.
     ///
@@ -379,6 +729,9 @@ impl Engine {
     ) -> Result<Dynamic, Box<EvalAltResult>> {
         self.inc_operations(state)?;
 
+        // Functions carrying their own library call functions in it instead
+        let lib = fn_def.lib.as_ref().map_or(lib, |m| m.as_ref());
+
         // Check for stack overflow
         #[cfg(not(feature = "no_function"))]
         #[cfg(not(feature = "unchecked"))]
@@ -388,11 +741,18 @@ impl Engine {
             ));
         }
 
+        // Arguments become variables in the function's scope
+        for value in args.iter() {
+            self.alloc_memory(state, value)?;
+        }
+
         let orig_scope_level = state.scope_level;
         state.scope_level += 1;
 
         let prev_scope_len = scope.len();
         let prev_mods_len = mods.len();
+        #[cfg(not(feature = "no_module"))]
+        let prev_global_modules_len = state.global_modules.len();
 
         // Put arguments into scope as variables
         // Actually consume the arguments instead of cloning them
@@ -423,18 +783,28 @@ impl Engine {
                 }
                 _ => EvalAltResult::ErrorInFunctionCall(fn_name.to_string(), err, Position::none())
                     .into(),
-            });
+            })
+            // Data returned from a function must not nest too deeply
+            .and_then(|value| self.check_data_depth(&value).map(|_| value));
 
         // Remove all local variables
-        scope.rewind(prev_scope_len);
+        self.rewind_scope(scope, state, prev_scope_len);
         mods.truncate(prev_mods_len);
+        #[cfg(not(feature = "no_module"))]
+        state.global_modules.truncate(prev_global_modules_len);
         state.scope_level = orig_scope_level;
 
         result
     }
 
     // Has a system function an override?
-    fn has_override(&self, lib: &Module, hash_fn: u64, hash_script: u64, pub_only: bool) -> bool {
+    pub(crate) fn has_override(
+        &self,
+        lib: &Module,
+        hash_fn: u64,
+        hash_script: u64,
+        pub_only: bool,
+    ) -> bool {
         // NOTE: We skip script functions for global_module and packages, and native functions for lib
 
         // First check script-defined functions
@@ -494,10 +864,13 @@ impl Engine {
             KEYWORD_TYPE_OF
                 if args.len() == 1 && !self.has_override(lib, hash_fn, hash_script, pub_only) =>
             {
-                Ok((
-                    self.map_type_name(args[0].type_name()).to_string().into(),
-                    false,
-                ))
+                // The type of a caught error is its category
+                let type_name = match args[0].read_lock::<CaughtError>() {
+                    Some(err) => err.category(),
+                    None => self.map_type_name(args[0].type_name()),
+                };
+
+                Ok((type_name.to_string().into(), false))
             }
 
             // Fn
@@ -529,7 +902,7 @@ impl Engine {
             {
                 // Get scripted function
                 let func = lib
-                    .get_fn(hash_script, pub_only)
+                    .get_fn_by_hash(hash_script, pub_only)
                     .or_else(|| self.packages.get_fn(hash_script, pub_only))
                     .unwrap()
                     .get_fn_def();
@@ -578,7 +951,7 @@ impl Engine {
 
             // Normal native function call
             _ => self.call_native_fn(
-                state, lib, fn_name, hash_fn, args, is_ref, pub_only, def_val,
+                state, lib, fn_name, hash_fn, args, is_ref, pub_only, def_val, _level,
             ),
         }
     }
@@ -615,22 +988,40 @@ impl Engine {
 
         // Compile the script text
         // No optimizations because we only run it once
-        let mut ast = self.compile_with_scope_and_optimization_level(
-            &Scope::new(),
-            &[script],
-            OptimizationLevel::None,
-        )?;
+        let mut ast = self
+            .parse(
+                &mut lex(&[script], None, self).peekable(),
+                &Scope::new(),
+                OptimizationLevel::None,
+            )
+            .map_err(|err| EvalAltResult::ErrorInEval(err.into(), Position::none()))?;
 
         // If new functions are defined within the eval string, it is an error
         if ast.lib().num_fn() != 0 {
-            return Err(ParseErrorType::WrongFnDefinition.into());
+            return EvalAltResult::ErrorInEval(
+                ParseErrorType::WrongFnDefinition.into(),
+                Position::none(),
+            )
+            .into();
         }
 
         let statements = mem::take(ast.statements_mut());
         let ast = AST::new(statements, lib.clone());
 
         // Evaluate the AST
-        let (result, operations) = self.eval_ast_with_scope_raw(scope, mods, &ast)?;
+        let (result, operations) =
+            self.eval_ast_with_scope_raw(scope, mods, &ast)
+                .map_err(|err| match *err {
+                    // Errors enforcing the limits of the Engine, and breaking out of loops,
+                    // are passed through unchanged
+                    EvalAltResult::ErrorTooManyOperations(_)
+                    | EvalAltResult::ErrorTooManyModules(_)
+                    | EvalAltResult::ErrorStackOverflow(_)
+                    | EvalAltResult::ErrorDataTooLarge(_, _, _, _)
+                    | EvalAltResult::ErrorTerminated(_)
+                    | EvalAltResult::ErrorLoopBreak(_, _) => err,
+                    _ => Box::new(EvalAltResult::ErrorInEval(err, Position::none())),
+                })?;
 
         state.operations += operations;
         self.inc_operations(state)?;
@@ -638,6 +1029,47 @@ impl Engine {
         return Ok(result);
     }
 
+    /// Evaluate a list of argument expressions, expanding spread (`...`) arrays into
+    /// individual values.
+    pub(crate) fn eval_arg_values(
+        &self,
+        scope: &mut Scope,
+        mods: &mut Imports,
+        state: &mut State,
+        lib: &Module,
+        this_ptr: &mut Option<&mut Dynamic>,
+        args_expr: &[Expr],
+        level: usize,
+    ) -> Result<StaticVec<Dynamic>, Box<EvalAltResult>> {
+        let mut values = StaticVec::new();
+
+        for expr in args_expr {
+            match expr {
+                #[cfg(not(feature = "no_index"))]
+                Expr::Spread(x) => {
+                    let (expr, pos) = x.as_ref();
+                    let value = self.eval_expr(scope, mods, state, lib, this_ptr, expr, level)?;
+                    let typ = value.type_name();
+
+                    match value.try_cast::<Array>() {
+                        Some(arr) => values.extend(arr),
+                        None => {
+                            return EvalAltResult::ErrorMismatchOutputType(
+                                self.map_type_name(type_name::<Array>()).into(),
+                                self.map_type_name(typ).into(),
+                                *pos,
+                            )
+                            .into()
+                        }
+                    }
+                }
+                _ => values.push(self.eval_expr(scope, mods, state, lib, this_ptr, expr, level)?),
+            }
+        }
+
+        Ok(values)
+    }
+
     /// Call a dot method.
     /// Position in `EvalAltResult` is `None` and must be set afterwards.
     #[cfg(not(feature = "no_object"))]
@@ -711,19 +1143,17 @@ impl Engine {
         } else if _fn_name == KEYWORD_FN_PTR_CURRY && obj.is::<FnPtr>() {
             // Curry call
             let fn_ptr = obj.read_lock::<FnPtr>().unwrap();
-            Ok((
-                FnPtr::new_unchecked(
-                    fn_ptr.get_fn_name().clone(),
-                    fn_ptr
-                        .curry()
-                        .iter()
-                        .cloned()
-                        .chain(idx.into_iter())
-                        .collect(),
-                )
-                .into(),
-                false,
-            ))
+            let fn_ptr = FnPtr::new_unchecked(
+                fn_ptr.get_fn_name().clone(),
+                fn_ptr
+                    .curry()
+                    .iter()
+                    .cloned()
+                    .chain(idx.into_iter())
+                    .collect(),
+            );
+            self.check_curry_arity(lib, &fn_ptr)?;
+            Ok((fn_ptr.into(), false))
         } else if cfg!(not(feature = "no_closure"))
             && _fn_name == KEYWORD_IS_SHARED
             && idx.is_empty()
@@ -770,9 +1200,14 @@ impl Engine {
             let mut arg_values = once(obj).chain(idx.iter_mut()).collect::<StaticVec<_>>();
             let args = arg_values.as_mut();
 
-            self.exec_fn_call(
+            match self.exec_fn_call(
                 state, lib, _fn_name, hash, args, is_ref, true, pub_only, None, def_val, level,
-            )
+            ) {
+                Err(err) if !native => self
+                    .call_missing_fn(_fn_name, args, err)
+                    .map(|v| (v, false)),
+                result => result,
+            }
         }?;
 
         // Feed the changed temp value back
@@ -843,17 +1278,16 @@ impl Engine {
 
             let (fn_name, fn_curry) = fn_ptr.cast::<FnPtr>().take_data();
 
-            let curry: StaticVec<_> = args_expr
-                .iter()
-                .skip(1)
-                .map(|expr| self.eval_expr(scope, mods, state, lib, this_ptr, expr, level))
-                .collect::<Result<_, _>>()?;
+            let curry =
+                self.eval_arg_values(scope, mods, state, lib, this_ptr, &args_expr[1..], level)?;
 
-            return Ok(FnPtr::new_unchecked(
+            let fn_ptr = FnPtr::new_unchecked(
                 fn_name,
                 fn_curry.into_iter().chain(curry.into_iter()).collect(),
-            )
-            .into());
+            );
+            self.check_curry_arity(lib, &fn_ptr)?;
+
+            return Ok(fn_ptr.into());
         }
 
         // Handle is_shared()
@@ -902,13 +1336,25 @@ impl Engine {
             let hash_fn = calc_fn_hash(empty(), name, 1, once(TypeId::of::<ImmutableString>()));
 
             if !self.has_override(lib, hash_fn, hash_script, pub_only) {
+                if !self.allow_eval {
+                    return EvalAltResult::ErrorRuntime(
+                        "'eval' is disabled".into(),
+                        Position::none(),
+                    )
+                    .into();
+                }
+
                 // eval - only in function call style
                 let prev_len = scope.len();
                 let expr = args_expr.get(0).unwrap();
                 let script = self.eval_expr(scope, mods, state, lib, this_ptr, expr, level)?;
                 let result = self
                     .eval_script_expr(scope, mods, state, lib, &script, level + 1)
-                    .map_err(|err| err.new_position(expr.position()));
+                    .map_err(|err| match *err {
+                        // Leave it to the caller to set the position of the call to 'eval'
+                        EvalAltResult::ErrorInEval(_, _) => err,
+                        _ => err.new_position(expr.position()),
+                    });
 
                 // IMPORTANT! If the eval defines new variables in the current scope,
                 //            all variable offsets from this point on will be mis-aligned.
@@ -933,6 +1379,15 @@ impl Engine {
         if args_expr.is_empty() && curry.is_empty() {
             // No arguments
             args = Default::default();
+        } else if args_expr.is_empty() {
+            // Only curried arguments
+            args = curry.iter_mut().collect();
+        } else if args_expr.iter().any(|expr| matches!(expr, Expr::Spread(_))) {
+            // Spread arguments - the actual number of arguments is only known now
+            arg_values =
+                self.eval_arg_values(scope, mods, state, lib, this_ptr, args_expr, level)?;
+            hash_script = calc_fn_hash(empty(), name, curry.len() + arg_values.len(), empty());
+            args = curry.iter_mut().chain(arg_values.iter_mut()).collect();
         } else {
             // If the first argument is a variable, and there is no curried arguments, convert to method-call style
             // in order to leverage potential &mut first argument and avoid cloning the value
@@ -945,19 +1400,27 @@ impl Engine {
                         .map(|expr| self.eval_expr(scope, mods, state, lib, this_ptr, expr, level))
                         .collect::<Result<_, _>>()?;
 
-                    let (target, _, _, pos) = search_namespace(scope, mods, state, this_ptr, lhs)?;
-
-                    self.inc_operations(state)
-                        .map_err(|err| err.new_position(pos))?;
+                    match search_namespace(scope, mods, state, this_ptr, lhs) {
+                        Ok((target, _, _, pos)) => {
+                            self.inc_operations(state)
+                                .map_err(|err| err.new_position(pos))?;
 
-                    args = if target.is_shared() {
-                        arg_values.insert(0, target.flatten_clone());
-                        arg_values.iter_mut().collect()
-                    } else {
-                        // Turn it into a method call only if the object is not shared
-                        is_ref = true;
-                        once(target).chain(arg_values.iter_mut()).collect()
-                    };
+                            args = if target.is_shared() {
+                                arg_values.insert(0, target.flatten_clone());
+                                arg_values.iter_mut().collect()
+                            } else {
+                                // Turn it into a method call only if the object is not shared
+                                is_ref = true;
+                                once(target).chain(arg_values.iter_mut()).collect()
+                            };
+                        }
+                        // Pass an undefined variable as ()
+                        Err(err) if self.is_undefined_var_read_as_unit(&err, lhs) => {
+                            arg_values.insert(0, ().into());
+                            args = arg_values.iter_mut().collect();
+                        }
+                        Err(err) => return Err(err),
+                    }
                 }
                 // func(..., ...)
                 _ => {
@@ -971,13 +1434,112 @@ impl Engine {
             }
         }
 
-        let hash = if native { 0 } else { hash_script };
         let args = args.as_mut();
 
-        self.exec_fn_call(
-            state, lib, name, hash, args, is_ref, false, pub_only, capture, def_val, level,
-        )
-        .map(|(v, _)| v)
+        if native {
+            return self
+                .exec_operator_call(state, lib, name, args, is_ref, def_val, level)
+                .map(|(v, _)| v);
+        }
+
+        match self.exec_fn_call(
+            state,
+            lib,
+            name,
+            hash_script,
+            args,
+            is_ref,
+            false,
+            pub_only,
+            capture,
+            def_val,
+            level,
+        ) {
+            Err(err) => self.call_missing_fn(name, args, err),
+            result => result.map(|(v, _)| v),
+        }
+    }
+
+    /// Call an operator function.
+    ///
+    /// If enabled via `Engine::set_operator_method_fallback`, the method named after the operator
+    /// (e.g. `add` for `+`) is called when no function is found for the operator.
+    /// Position in `EvalAltResult` is `None` and must be set afterwards.
+    ///
+    /// ## WARNING
+    ///
+    /// Function call arguments may be _consumed_ when the function requires them to be passed by value.
+    pub(crate) fn exec_operator_call(
+        &self,
+        state: &mut State,
+        lib: &Module,
+        op: &str,
+        args: &mut FnCallArgs,
+        is_ref: bool,
+        def_val: Option<bool>,
+        level: usize,
+    ) -> Result<(Dynamic, bool), Box<EvalAltResult>> {
+        let method = match operator_method_name(op, args.len()) {
+            Some(method) if self.operator_method_fallback => method,
+            _ => {
+                return self.exec_fn_call(
+                    state, lib, op, 0, args, is_ref, false, false, None, def_val, level,
+                )
+            }
+        };
+
+        // The default value only applies when there is no method either
+        let err = match self.exec_fn_call(
+            state, lib, op, 0, args, is_ref, false, false, None, None, level,
+        ) {
+            Err(err) if is_fn_not_found(&err, op) => err,
+            result => return result,
+        };
+
+        let hash_script = calc_fn_hash(empty(), method, args.len(), empty());
+
+        match self.exec_fn_call(
+            state,
+            lib,
+            method,
+            hash_script,
+            args,
+            is_ref,
+            false,
+            false,
+            None,
+            None,
+            level,
+        ) {
+            Err(method_err) if is_fn_not_found(&method_err, method) => match def_val {
+                Some(val) => Ok((val.into(), false)),
+                None => Err(err),
+            },
+            result => result,
+        }
+    }
+
+    /// Pass a function call that cannot be resolved to the callback registered via
+    /// `Engine::on_missing_fn`.
+    ///
+    /// Only errors reporting that the function itself is not found are passed on;
+    /// all other errors are returned unchanged.
+    fn call_missing_fn(
+        &self,
+        fn_name: &str,
+        args: &mut FnCallArgs,
+        err: Box<EvalAltResult>,
+    ) -> Result<Dynamic, Box<EvalAltResult>> {
+        let callback = match self.missing_fn {
+            Some(ref callback) => callback,
+            None => return Err(err),
+        };
+
+        if is_fn_not_found(&err, fn_name) {
+            callback(fn_name, args)?.ok_or(err)
+        } else {
+            Err(err)
+        }
     }
 
     /// Call a module-qualified function in normal function-call style.
@@ -993,7 +1555,7 @@ impl Engine {
         name: &str,
         args_expr: &[Expr],
         def_val: Option<bool>,
-        hash_script: u64,
+        mut hash_script: u64,
         _capture: bool,
         level: usize,
     ) -> Result<Dynamic, Box<EvalAltResult>> {
@@ -1005,6 +1567,13 @@ impl Engine {
         if args_expr.is_empty() {
             // No arguments
             args = Default::default();
+        } else if args_expr.iter().any(|expr| matches!(expr, Expr::Spread(_))) {
+            // Spread arguments - the actual number of arguments is only known now
+            arg_values =
+                self.eval_arg_values(scope, mods, state, lib, this_ptr, args_expr, level)?;
+            let qualifiers = modules.iter().map(|(m, _)| m.as_str());
+            hash_script = calc_fn_hash(qualifiers, name, arg_values.len(), empty());
+            args = arg_values.iter_mut().collect();
         } else {
             // See if the first argument is a variable (not module-qualified).
             // If so, convert to method-call style in order to leverage potential
@@ -1026,16 +1595,24 @@ impl Engine {
                         .collect::<Result<_, _>>()?;
 
                     // Get target reference to first argument
-                    let (target, _, _, pos) =
-                        search_scope_only(scope, state, this_ptr, args_expr.get(0).unwrap())?;
+                    let lhs = args_expr.get(0).unwrap();
 
-                    self.inc_operations(state)
-                        .map_err(|err| err.new_position(pos))?;
+                    match search_scope_only(scope, state, this_ptr, lhs) {
+                        Ok((target, _, _, pos)) => {
+                            self.inc_operations(state)
+                                .map_err(|err| err.new_position(pos))?;
 
-                    let (first, rest) = arg_values.split_first_mut().unwrap();
-                    first_arg_value = Some(first);
+                            let (first, rest) = arg_values.split_first_mut().unwrap();
+                            first_arg_value = Some(first);
 
-                    args = once(target).chain(rest.iter_mut()).collect();
+                            args = once(target).chain(rest.iter_mut()).collect();
+                        }
+                        // Pass an undefined variable as ()
+                        Err(err) if self.is_undefined_var_read_as_unit(&err, lhs) => {
+                            args = arg_values.iter_mut().collect();
+                        }
+                        Err(err) => return Err(err),
+                    }
                 }
                 // func(..., ...) or func(mod::x, ...)
                 _ => {
@@ -1066,7 +1643,24 @@ impl Engine {
                 // 3) The final hash is the XOR of the two hashes.
                 let hash_qualified_fn = hash_script ^ hash_fn_args;
 
-                module.get_qualified_fn(hash_qualified_fn)
+                module.get_qualified_fn(hash_qualified_fn).or_else(|| {
+                    // Then search for a variadic plugin function taking the leading arguments,
+                    // preferring the one with the most fixed parameters
+                    (0..args.len()).rev().find_map(|num_fixed| {
+                        let qualifiers = modules.iter().map(|(m, _)| m.as_str());
+                        let hash_varadic = calc_fn_hash(qualifiers, name, num_fixed, empty())
+                            ^ calc_fn_hash(
+                                empty(),
+                                "",
+                                0,
+                                args[..num_fixed].iter().map(|a| a.type_id()),
+                            );
+
+                        module
+                            .get_qualified_fn(hash_varadic)
+                            .filter(|f| f.is_varadic())
+                    })
+                })
             }
             r => r,
         };
@@ -1099,7 +1693,10 @@ impl Engine {
 
                 self.call_script_fn(scope, mods, state, lib, &mut None, name, func, args, level)
             }
-            Some(f) if f.is_plugin_fn() => f.get_plugin_fn().call(args.as_mut()),
+            Some(f) if f.is_plugin_fn() => {
+                self.report_deprecated(state, name, f);
+                f.get_plugin_fn().call(args.as_mut())
+            }
             Some(f) if f.is_native() => {
                 if !f.is_method() {
                     // Clone first argument
@@ -1110,7 +1707,13 @@ impl Engine {
                     }
                 }
 
-                f.get_native_fn()(self, lib, args.as_mut())
+                let context = &mut NativeCallContext {
+                    engine: self,
+                    lib,
+                    state,
+                    level,
+                };
+                f.get_native_fn()(context, args.as_mut())
             }
             Some(_) => unreachable!(),
             None if def_val.is_some() => Ok(def_val.unwrap().into()),
@@ -1145,6 +1748,25 @@ pub fn run_builtin_binary_op(
 
     let args_type = x.type_id();
 
+    // Compare integers with floating-point numbers
+    #[cfg(not(feature = "no_float"))]
+    if args_type == TypeId::of::<INT>() && y.type_id() == TypeId::of::<FLOAT>() {
+        let x = x.clone().cast::<INT>();
+        let y = y.clone().cast::<FLOAT>();
+        return Ok(compare_int_float_op(op, x, y).map(Into::into));
+    } else if args_type == TypeId::of::<FLOAT>() && y.type_id() == TypeId::of::<INT>() {
+        let x = x.clone().cast::<FLOAT>();
+        let y = y.clone().cast::<INT>();
+        let op = match op {
+            ">" => "<",
+            ">=" => "<=",
+            "<" => ">",
+            "<=" => ">=",
+            op => op,
+        };
+        return Ok(compare_int_float_op(op, y, x).map(Into::into));
+    }
+
     if y.type_id() != args_type {
         return Ok(None);
     }
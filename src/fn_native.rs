@@ -1,12 +1,13 @@
 //! Module defining interfaces to native-Rust functions.
 
-use crate::any::Dynamic;
-use crate::engine::Engine;
+use crate::any::{Dynamic, Variant};
+use crate::engine::{Engine, State};
 use crate::module::Module;
 use crate::parser::{FnAccess, ScriptFnDef};
 use crate::plugin::PluginFunction;
 use crate::result::EvalAltResult;
-use crate::token::{is_valid_identifier, Position};
+use crate::scope::Scope;
+use crate::token::{is_valid_identifier, Position, SourceMap};
 use crate::utils::ImmutableString;
 
 #[cfg(not(feature = "no_function"))]
@@ -17,10 +18,10 @@ use crate::stdlib::{boxed::Box, convert::TryFrom, fmt, string::String, vec::Vec}
 #[cfg(not(feature = "no_function"))]
 use crate::stdlib::{iter::empty, mem};
 
-#[cfg(not(feature = "sync"))]
-use crate::stdlib::rc::Rc;
 #[cfg(feature = "sync")]
-use crate::stdlib::sync::Arc;
+use crate::stdlib::sync::{Arc, RwLock};
+#[cfg(not(feature = "sync"))]
+use crate::stdlib::{cell::RefCell, rc::Rc};
 
 /// Trait that maps to `Send + Sync` only under the `sync` feature.
 #[cfg(feature = "sync")]
@@ -71,6 +72,60 @@ pub fn shared_take<T>(value: Shared<T>) -> T {
 
 pub type FnCallArgs<'a> = [&'a mut Dynamic];
 
+/// Context of a native Rust function call.
+///
+/// Functions calling back into script functions or closures should go through the context,
+/// so that the calls share the current states of the evaluation (e.g. the number of operations
+/// performed and the depth of the call stack) and the `Engine`'s safety limits keep applying.
+#[derive(Debug)]
+pub struct NativeCallContext<'e, 'm, 's> {
+    pub(crate) engine: &'e Engine,
+    pub(crate) lib: &'m Module,
+    #[allow(dead_code)]
+    pub(crate) state: &'s mut State,
+    #[allow(dead_code)]
+    pub(crate) level: usize,
+}
+
+impl NativeCallContext<'_, '_, '_> {
+    /// The current `Engine`.
+    pub fn engine(&self) -> &Engine {
+        self.engine
+    }
+    /// The current library of script-defined functions.
+    pub fn lib(&self) -> &Module {
+        self.lib
+    }
+    /// Call a function pointer (which may be a closure) with curried arguments (if any).
+    ///
+    /// The call counts towards the maximum number of operations and the maximum call stack depth
+    /// of the current evaluation.  Any error raised by the function is returned unchanged.
+    ///
+    /// If the function is a script-defined function, it must not be marked private.
+    ///
+    /// ## WARNING
+    ///
+    /// All the arguments are _consumed_, meaning that they're replaced by `()`.
+    /// This is to avoid unnecessarily cloning the arguments.
+    /// Do not use the arguments after this call. If they are needed afterwards,
+    /// clone them _before_ calling this function.
+    #[cfg(not(feature = "no_function"))]
+    pub fn call_fn_dynamic(
+        &mut self,
+        fn_ptr: &FnPtr,
+        arg_values: impl AsMut<[Dynamic]>,
+    ) -> FuncReturn<Dynamic> {
+        fn_ptr.call_with_state(
+            self.engine,
+            self.state,
+            self.lib,
+            None,
+            arg_values,
+            self.level + 1,
+        )
+    }
+}
+
 /// A general function pointer, which may carry additional (i.e. curried) argument values
 /// to be passed onto a function during a call.
 #[derive(Debug, Clone, Default)]
@@ -102,6 +157,9 @@ impl FnPtr {
     ///
     /// If this function is a script-defined function, it must not be marked private.
     ///
+    /// The call starts a new evaluation with fresh states.  Within a native Rust function,
+    /// use `NativeCallContext::call_fn_dynamic` instead.
+    ///
     /// ## WARNING
     ///
     /// All the arguments are _consumed_, meaning that they're replaced by `()`.
@@ -114,7 +172,27 @@ impl FnPtr {
         engine: &Engine,
         lib: impl AsRef<Module>,
         this_ptr: Option<&mut Dynamic>,
+        arg_values: impl AsMut<[Dynamic]>,
+    ) -> FuncReturn<Dynamic> {
+        self.call_with_state(
+            engine,
+            &mut Default::default(),
+            lib.as_ref(),
+            this_ptr,
+            arg_values,
+            0,
+        )
+    }
+    /// Call the function pointer with curried arguments (if any), using the current states.
+    #[cfg(not(feature = "no_function"))]
+    pub(crate) fn call_with_state(
+        &self,
+        engine: &Engine,
+        state: &mut State,
+        lib: &Module,
+        this_ptr: Option<&mut Dynamic>,
         mut arg_values: impl AsMut<[Dynamic]>,
+        level: usize,
     ) -> FuncReturn<Dynamic> {
         let mut args_data = self
             .1
@@ -136,8 +214,8 @@ impl FnPtr {
 
         engine
             .exec_fn_call(
-                &mut Default::default(),
-                lib.as_ref(),
+                state,
+                lib,
                 fn_name,
                 hash_script,
                 args.as_mut(),
@@ -146,7 +224,7 @@ impl FnPtr {
                 true,
                 None,
                 None,
-                0,
+                level,
             )
             .map(|(v, _)| v)
     }
@@ -190,15 +268,90 @@ impl TryFrom<&str> for FnPtr {
 
 /// A general function trail object.
 #[cfg(not(feature = "sync"))]
-pub type FnAny = dyn Fn(&Engine, &Module, &mut FnCallArgs) -> Result<Dynamic, Box<EvalAltResult>>;
+pub type FnAny =
+    dyn Fn(&mut NativeCallContext, &mut FnCallArgs) -> Result<Dynamic, Box<EvalAltResult>>;
 /// A general function trail object.
 #[cfg(feature = "sync")]
-pub type FnAny =
-    dyn Fn(&Engine, &Module, &mut FnCallArgs) -> Result<Dynamic, Box<EvalAltResult>> + Send + Sync;
+pub type FnAny = dyn Fn(&mut NativeCallContext, &mut FnCallArgs) -> Result<Dynamic, Box<EvalAltResult>>
+    + Send
+    + Sync;
+
+/// A guard deciding whether a function registered via `register_fn_guarded` handles a call.
+#[cfg(not(feature = "sync"))]
+pub type FnGuard = dyn Fn(&mut FnCallArgs) -> bool;
+/// A guard deciding whether a function registered via `register_fn_guarded` handles a call.
+#[cfg(feature = "sync")]
+pub type FnGuard = dyn Fn(&mut FnCallArgs) -> bool + Send + Sync;
 
 /// A standard function that gets an iterator from a type.
 pub type IteratorFn = fn(Dynamic) -> Box<dyn Iterator<Item = Dynamic>>;
 
+/// A host iterator pulled by an `ExternalIterator`.
+#[cfg(not(feature = "sync"))]
+type HostIterator = RefCell<Box<dyn Iterator<Item = Dynamic>>>;
+/// A host iterator pulled by an `ExternalIterator`.
+#[cfg(feature = "sync")]
+type HostIterator = RwLock<Box<dyn Iterator<Item = Dynamic> + Send + Sync>>;
+
+/// An iterator that lazily pulls values from a Rust iterator, one at a time, only when the
+/// script asks for the next value.
+///
+/// A `for` loop over an `ExternalIterator` streams through the values in constant memory
+/// and ends cleanly when the Rust iterator is exhausted.  The `next` function returns the
+/// next value, or `()` when there are no more values, so it can drive a `while let` loop.
+///
+/// Cloning an `ExternalIterator` does not copy the Rust iterator - all clones pull from the
+/// same stream of values.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+/// use rhai::{Engine, ExternalIterator, RegisterFn, INT};
+///
+/// let mut engine = Engine::new();
+///
+/// // Values are produced only when the script pulls them
+/// engine.register_fn("numbers", |n: INT| ExternalIterator::new(1..=n));
+///
+/// assert_eq!(engine.eval::<INT>("let sum = 0; for x in numbers(100) { sum += x; } sum")?, 5050);
+///
+/// assert_eq!(engine.eval::<INT>("let src = numbers(3); next(src) * 10 + next(src)")?, 12);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ExternalIterator(Shared<HostIterator>);
+
+impl ExternalIterator {
+    /// Create a new `ExternalIterator` pulling values from a Rust iterator.
+    pub fn new<T: Variant + Clone>(iter: impl Iterator<Item = T> + SendSync + 'static) -> Self {
+        let iter = Box::new(iter.map(Dynamic::from));
+
+        #[cfg(not(feature = "sync"))]
+        return Self(Rc::new(RefCell::new(iter)));
+        #[cfg(feature = "sync")]
+        return Self(Arc::new(RwLock::new(iter)));
+    }
+}
+
+impl Iterator for ExternalIterator {
+    type Item = Dynamic;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        #[cfg(not(feature = "sync"))]
+        return self.0.borrow_mut().next();
+        #[cfg(feature = "sync")]
+        return self.0.write().unwrap().next();
+    }
+}
+
+impl fmt::Debug for ExternalIterator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ExternalIterator")
+    }
+}
+
 #[cfg(feature = "sync")]
 pub type SharedPluginFunction = Arc<dyn PluginFunction + Send + Sync>;
 #[cfg(not(feature = "sync"))]
@@ -211,6 +364,57 @@ pub type Callback<T, R> = Box<dyn Fn(&T) -> R + 'static>;
 #[cfg(feature = "sync")]
 pub type Callback<T, R> = Box<dyn Fn(&T) -> R + Send + Sync + 'static>;
 
+/// A callback function for function calls that cannot be resolved.
+#[cfg(not(feature = "sync"))]
+pub type OnMissingFnCallback =
+    Box<dyn Fn(&str, &mut FnCallArgs) -> Result<Option<Dynamic>, Box<EvalAltResult>> + 'static>;
+/// A callback function for function calls that cannot be resolved.
+#[cfg(feature = "sync")]
+pub type OnMissingFnCallback = Box<
+    dyn Fn(&str, &mut FnCallArgs) -> Result<Option<Dynamic>, Box<EvalAltResult>>
+        + Send
+        + Sync
+        + 'static,
+>;
+
+/// A callback function called at the end of each top-level evaluation.
+#[cfg(not(feature = "sync"))]
+pub type OnEvalEndCallback = Box<dyn Fn(&mut Scope) + 'static>;
+/// A callback function called at the end of each top-level evaluation.
+#[cfg(feature = "sync")]
+pub type OnEvalEndCallback = Box<dyn Fn(&mut Scope) + Send + Sync + 'static>;
+
+/// A callback function called when a script calls a deprecated plugin function.
+#[cfg(not(feature = "sync"))]
+pub type OnDeprecatedCallback = Box<dyn Fn(&str, &str) + 'static>;
+/// A callback function called when a script calls a deprecated plugin function.
+#[cfg(feature = "sync")]
+pub type OnDeprecatedCallback = Box<dyn Fn(&str, &str) + Send + Sync + 'static>;
+
+/// A factory function that builds a module.
+#[cfg(not(feature = "no_module"))]
+#[cfg(not(feature = "sync"))]
+pub type ModuleFactory = Box<dyn Fn() -> Module + 'static>;
+/// A factory function that builds a module.
+#[cfg(not(feature = "no_module"))]
+#[cfg(feature = "sync")]
+pub type ModuleFactory = Box<dyn Fn() -> Module + Send + Sync + 'static>;
+
+/// A callback function that pre-processes the source text of a script before it is parsed.
+#[cfg(not(feature = "sync"))]
+pub type OnPreprocessCallback = Box<dyn Fn(&str) -> (String, Option<SourceMap>) + 'static>;
+/// A callback function that pre-processes the source text of a script before it is parsed.
+#[cfg(feature = "sync")]
+pub type OnPreprocessCallback =
+    Box<dyn Fn(&str) -> (String, Option<SourceMap>) + Send + Sync + 'static>;
+
+/// A callback function that converts the text of a number literal into a custom number type.
+#[cfg(not(feature = "sync"))]
+pub type OnNumberLiteralCallback = Box<dyn Fn(&str) -> Option<Dynamic> + 'static>;
+/// A callback function that converts the text of a number literal into a custom number type.
+#[cfg(feature = "sync")]
+pub type OnNumberLiteralCallback = Box<dyn Fn(&str) -> Option<Dynamic> + Send + Sync + 'static>;
+
 /// A type encapsulating a function callable by Rhai.
 #[derive(Clone)]
 pub enum CallableFunction {
@@ -310,6 +514,16 @@ impl CallableFunction {
             Self::Script(_) => false,
         }
     }
+    /// Is this a plugin function taking any number of trailing arguments?
+    pub fn is_varadic(&self) -> bool {
+        match self {
+            Self::Plugin(p) => p.is_varadic(),
+            Self::Pure(_) | Self::Method(_) | Self::Iterator(_) => false,
+
+            #[cfg(not(feature = "no_function"))]
+            Self::Script(_) => false,
+        }
+    }
     /// Is this a native Rust function?
     pub fn is_native(&self) -> bool {
         match self {
@@ -2,17 +2,29 @@
 
 #![allow(non_snake_case)]
 
-use crate::any::{Dynamic, DynamicWriteLock, Variant};
+use crate::any::{Dynamic, DynamicReadLock, DynamicWriteLock, Variant};
+use crate::calc_fn_hash;
 use crate::engine::Engine;
-use crate::fn_native::{CallableFunction, FnAny, FnCallArgs, SendSync};
-use crate::module::Module;
+use crate::fn_call::ArgBackup;
+use crate::fn_native::{CallableFunction, FnAny, FnCallArgs, FnGuard, NativeCallContext, SendSync};
 use crate::parser::FnAccess;
 use crate::plugin::Plugin;
 use crate::r#unsafe::unsafe_cast_box;
 use crate::result::EvalAltResult;
+use crate::token::Position;
 use crate::utils::ImmutableString;
 
-use crate::stdlib::{any::TypeId, boxed::Box, mem, string::String};
+#[cfg(not(feature = "unchecked"))]
+use crate::stdlib::format;
+
+use crate::stdlib::{
+    any::TypeId,
+    boxed::Box,
+    iter::empty,
+    mem,
+    ops::Deref,
+    string::{String, ToString},
+};
 
 /// A trait to register custom plugins with the `Engine`.
 ///
@@ -145,9 +157,23 @@ pub trait RegisterFn<FN, ARGS, RET> {
     /// engine.register_fn("sub", |x: i64, y: i64| x - y );
     ///
     /// assert_eq!(engine.eval::<i64>("sub(44, 2)")?, 42);
+    ///
+    /// // Fallible functions returning 'Result<T, Box<EvalAltResult>>' are detected automatically.
+    /// // Errors are propagated to the script instead of being returned as values.
+    /// engine.register_fn("div", |x: i64, y: i64| -> Result<i64, Box<rhai::EvalAltResult>> {
+    ///     if y == 0 { Err("division by zero!".into()) } else { Ok(x / y) }
+    /// });
+    ///
+    /// assert_eq!(engine.eval::<i64>("div(84, 2)")?, 42);
+    /// assert!(engine.eval::<i64>("div(42, 0)").is_err());
     /// # Ok(())
     /// # }
     /// ```
+    ///
+    /// # Fallible Functions
+    ///
+    /// The error type must be `Box<EvalAltResult>`.  Map any other error type into it
+    /// (e.g. via `.map_err(|err| err.to_string().into())`) before returning.
     fn register_fn(&mut self, name: &str, f: FN) -> &mut Self;
 }
 
@@ -181,6 +207,53 @@ pub trait RegisterResultFn<FN, ARGS> {
     fn register_result_fn(&mut self, name: &str, f: FN) -> &mut Self;
 }
 
+/// Trait to register custom functions that only handle calls accepted by a guard with the `Engine`.
+pub trait RegisterGuardedFn<GUARD, FN, ARGS, RET> {
+    /// Register a custom function with the `Engine`, to be called only when `guard` returns
+    /// `true` for the arguments.
+    ///
+    /// The same function name and parameter types can be registered many times with different
+    /// guards.  A call runs the first registration (in order of registration) whose guard accepts
+    /// the arguments.  If no guard accepts them, the call fails with
+    /// `EvalAltResult::ErrorFunctionNotFound`.
+    ///
+    /// The guard takes each argument by reference, even for a function taking its first argument
+    /// by `&mut` reference.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// # #[cfg(not(feature = "no_object"))]
+    /// # {
+    /// use rhai::{Engine, Map, RegisterGuardedFn};
+    ///
+    /// fn is_kind(shape: &Map, kind: &str) -> bool {
+    ///     shape.get("kind").and_then(|v| v.as_str().ok()) == Some(kind)
+    /// }
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// // You must use the trait rhai::RegisterGuardedFn to get this method.
+    /// engine
+    ///     .register_fn_guarded("area", |s: &Map| is_kind(s, "square"), |s: Map| {
+    ///         let side = s["side"].as_int().unwrap();
+    ///         side * side
+    ///     })
+    ///     .register_fn_guarded("area", |s: &Map| is_kind(s, "rect"), |s: Map| {
+    ///         s["width"].as_int().unwrap() * s["height"].as_int().unwrap()
+    ///     });
+    ///
+    /// assert_eq!(engine.eval::<i64>(r#"area(#{ kind: "square", side: 3 })"#)?, 9);
+    /// assert_eq!(engine.eval::<i64>(r#"area(#{ kind: "rect", width: 2, height: 5 })"#)?, 10);
+    /// assert!(engine.eval::<i64>(r#"area(#{ kind: "circle" })"#).is_err());
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn register_fn_guarded(&mut self, name: &str, guard: GUARD, f: FN) -> &mut Self;
+}
+
 // These types are used to build a unique _marker_ tuple type for each combination
 // of function parameter types in order to make each trait implementation unique.
 // That is because stable Rust currently does not allow distinguishing implementations
@@ -198,6 +271,13 @@ pub trait RegisterResultFn<FN, ARGS> {
 pub struct Mut<T>(T);
 //pub struct Ref<T>(T);
 
+// Marker type for functions returning `Result<T, Box<EvalAltResult>>`.
+//
+// `RegisterFn<FN, ARGS, Fallible<T>>` requires `FN: (...) -> Result<T, Box<EvalAltResult>>`.
+// The return type itself cannot be used as the marker because it would conflict with the
+// blanket implementation for all `RET: Variant + Clone`.
+pub struct Fallible<T>(T);
+
 /// Dereference into DynamicWriteLock
 #[inline(always)]
 pub fn by_ref<T: Variant + Clone>(data: &mut Dynamic) -> DynamicWriteLock<T> {
@@ -223,6 +303,35 @@ pub fn by_value<T: Variant + Clone>(data: &mut Dynamic) -> T {
     }
 }
 
+/// A reference to a function argument that is inspected without being consumed.
+enum ArgRef<'a, T: Variant + Clone> {
+    Locked(DynamicReadLock<'a, T>),
+    Owned(T),
+}
+
+impl<T: Variant + Clone> Deref for ArgRef<'_, T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Locked(value) => value.deref(),
+            Self::Owned(value) => value,
+        }
+    }
+}
+
+/// Dereference into a reference, without consuming the argument.
+#[inline(always)]
+fn by_peek<T: Variant + Clone>(data: &mut Dynamic) -> ArgRef<T> {
+    if TypeId::of::<T>() == TypeId::of::<&str>() || TypeId::of::<T>() == TypeId::of::<String>() {
+        // Strings are not consumed by `by_value`
+        ArgRef::Owned(by_value(data))
+    } else {
+        ArgRef::Locked(data.read_lock::<T>().unwrap())
+    }
+}
+
 impl<PL: Plugin> RegisterPlugin<PL> for Engine {
     fn register_plugin(&mut self, plugin: PL) {
         plugin.register_contents(self);
@@ -239,7 +348,7 @@ macro_rules! make_func {
 //                                                       ^ dereferencing function
 //                                                                         ^ argument reference expression(like A, *B, &mut C etc)
 
-		Box::new(move |_: &Engine, _: &Module, args: &mut FnCallArgs| {
+		Box::new(move |_: &mut NativeCallContext, args: &mut FnCallArgs| {
             // The arguments are assumed to be of the correct number and types!
 
 			let mut _drain = args.iter_mut();
@@ -255,12 +364,35 @@ macro_rules! make_func {
 	};
 }
 
+/// This macro creates a closure wrapping the guard of a registered function.
+macro_rules! make_guard {
+    ($guard:ident ; $($par:ident),*) => {
+        Box::new(move |args: &mut FnCallArgs| {
+            // The arguments are assumed to be of the correct number and types!
+
+            let mut _drain = args.iter_mut();
+            $(let $par = by_peek::<$par>(_drain.next().unwrap());)*
+
+            // Call the guard with a reference to each parameter value
+            $guard($(&*$par),*)
+        }) as Box<FnGuard>
+    };
+}
+
 /// To Dynamic mapping function.
 #[inline(always)]
 pub fn map_dynamic<T: Variant + Clone>(data: T) -> Result<Dynamic, Box<EvalAltResult>> {
     Ok(data.into_dynamic())
 }
 
+/// To Dynamic mapping function for fallible functions.
+#[inline(always)]
+pub fn map_result_dynamic<T: Variant + Clone>(
+    data: Result<T, Box<EvalAltResult>>,
+) -> Result<Dynamic, Box<EvalAltResult>> {
+    data.map(|v| v.into_dynamic())
+}
+
 /// To Dynamic mapping function.
 #[inline(always)]
 pub fn map_result(
@@ -283,6 +415,106 @@ fn map_type_id<T: 'static>() -> TypeId {
     }
 }
 
+impl Engine {
+    /// Make sure that a native function being registered does not take more parameters than
+    /// allowed by `Engine::set_max_fn_params`.
+    ///
+    /// A function with too many parameters is replaced by one that always fails with
+    /// `EvalAltResult::ErrorDataTooLarge` when called.
+    #[cfg(not(feature = "unchecked"))]
+    pub(crate) fn check_num_params(
+        &self,
+        name: &str,
+        num_params: usize,
+        func: CallableFunction,
+    ) -> CallableFunction {
+        let max = self.limits.max_fn_params;
+
+        if max == 0 || num_params <= max {
+            return func;
+        }
+
+        let typ = format!("Number of parameters of function '{}'", name);
+
+        CallableFunction::from_pure(Box::new(move |_, _| {
+            EvalAltResult::ErrorDataTooLarge(typ.clone(), max, num_params, Position::none()).into()
+        }))
+    }
+
+    /// Make sure that a native function being registered does not take more parameters than
+    /// allowed by `Engine::set_max_fn_params`.
+    #[cfg(feature = "unchecked")]
+    #[inline(always)]
+    pub(crate) fn check_num_params(
+        &self,
+        _name: &str,
+        _num_params: usize,
+        func: CallableFunction,
+    ) -> CallableFunction {
+        func
+    }
+
+    /// Add a guarded implementation of a function, then register a function that dispatches
+    /// each call to the first implementation whose guard accepts the arguments.
+    fn set_guarded_fn(
+        &mut self,
+        name: &str,
+        arg_types: &[TypeId],
+        guard: Box<FnGuard>,
+        func: CallableFunction,
+    ) {
+        let args_len = if arg_types.is_empty() {
+            // Distinguish between a script function and a function with no parameters
+            usize::MAX
+        } else {
+            arg_types.len()
+        };
+        let hash_fn = calc_fn_hash(empty(), name, args_len, arg_types.iter().cloned());
+
+        let fns = self
+            .guarded_fns
+            .entry(hash_fn)
+            .or_insert_with(Default::default);
+        fns.push((guard.into(), func));
+
+        let fns = fns.clone();
+        let is_method = fns.iter().any(|(_, func)| func.is_method());
+        let fn_name = name.to_string();
+
+        let dispatch = Box::new(
+            move |context: &mut NativeCallContext, args: &mut FnCallArgs| {
+                let func = match fns.iter().find(|(guard, _)| (**guard)(args)) {
+                    Some((_, func)) => func,
+                    None => {
+                        return EvalAltResult::ErrorFunctionNotFound(
+                            context.engine().make_fn_signature(&fn_name, args),
+                            Position::none(),
+                        )
+                        .into()
+                    }
+                };
+
+                // A pure function must not consume the first argument of a method call
+                let mut backup: ArgBackup = Default::default();
+                backup.change_first_arg_to_copy(is_method && func.is_pure(), args);
+                let result = func.get_native_fn()(context, args);
+                backup.restore_first_arg(args);
+
+                result
+            },
+        ) as Box<FnAny>;
+
+        let func = if is_method {
+            CallableFunction::from_method(dispatch)
+        } else {
+            CallableFunction::from_pure(dispatch)
+        };
+
+        self.global_module
+            .set_fn(name, FnAccess::Public, arg_types, func);
+    }
+}
+
 macro_rules! def_register {
     () => {
         def_register!(imp from_pure :);
@@ -301,10 +533,27 @@ macro_rules! def_register {
         > RegisterFn<FN, ($($mark,)*), RET> for Engine
         {
             fn register_fn(&mut self, name: &str, f: FN) -> &mut Self {
-                self.global_module.set_fn(name, FnAccess::Public,
-                    &[$(map_type_id::<$par>()),*],
+                let arg_types = [$(map_type_id::<$par>()),*];
+                let func = self.check_num_params(name, arg_types.len(),
                     CallableFunction::$abi(make_func!(f : map_dynamic ; $($par => $let => $clone => $arg),*))
                 );
+                self.global_module.set_fn(name, FnAccess::Public, &arg_types, func);
+                self
+            }
+        }
+
+        impl<
+            $($par: Variant + Clone,)*
+            FN: Fn($($param),*) -> Result<RET, Box<EvalAltResult>> + SendSync + 'static,
+            RET: Variant + Clone
+        > RegisterFn<FN, ($($mark,)*), Fallible<RET>> for Engine
+        {
+            fn register_fn(&mut self, name: &str, f: FN) -> &mut Self {
+                let arg_types = [$(map_type_id::<$par>()),*];
+                let func = self.check_num_params(name, arg_types.len(),
+                    CallableFunction::$abi(make_func!(f : map_result_dynamic ; $($par => $let => $clone => $arg),*))
+                );
+                self.global_module.set_fn(name, FnAccess::Public, &arg_types, func);
                 self
             }
         }
@@ -315,10 +564,45 @@ macro_rules! def_register {
         > RegisterResultFn<FN, ($($mark,)*)> for Engine
         {
             fn register_result_fn(&mut self, name: &str, f: FN) -> &mut Self {
-                self.global_module.set_fn(name, FnAccess::Public,
-                    &[$(map_type_id::<$par>()),*],
+                let arg_types = [$(map_type_id::<$par>()),*];
+                let func = self.check_num_params(name, arg_types.len(),
                     CallableFunction::$abi(make_func!(f : map_result ; $($par => $let => $clone => $arg),*))
                 );
+                self.global_module.set_fn(name, FnAccess::Public, &arg_types, func);
+                self
+            }
+        }
+
+        impl<
+            $($par: Variant + Clone,)*
+            GUARD: Fn($(&$par),*) -> bool + SendSync + 'static,
+            FN: Fn($($param),*) -> RET + SendSync + 'static,
+            RET: Variant + Clone
+        > RegisterGuardedFn<GUARD, FN, ($($mark,)*), RET> for Engine
+        {
+            fn register_fn_guarded(&mut self, name: &str, guard: GUARD, f: FN) -> &mut Self {
+                let arg_types = [$(map_type_id::<$par>()),*];
+                let func = self.check_num_params(name, arg_types.len(),
+                    CallableFunction::$abi(make_func!(f : map_dynamic ; $($par => $let => $clone => $arg),*))
+                );
+                self.set_guarded_fn(name, &arg_types, make_guard!(guard ; $($par),*), func);
+                self
+            }
+        }
+
+        impl<
+            $($par: Variant + Clone,)*
+            GUARD: Fn($(&$par),*) -> bool + SendSync + 'static,
+            FN: Fn($($param),*) -> Result<RET, Box<EvalAltResult>> + SendSync + 'static,
+            RET: Variant + Clone
+        > RegisterGuardedFn<GUARD, FN, ($($mark,)*), Fallible<RET>> for Engine
+        {
+            fn register_fn_guarded(&mut self, name: &str, guard: GUARD, f: FN) -> &mut Self {
+                let arg_types = [$(map_type_id::<$par>()),*];
+                let func = self.check_num_params(name, arg_types.len(),
+                    CallableFunction::$abi(make_func!(f : map_result_dynamic ; $($par => $let => $clone => $arg),*))
+                );
+                self.set_guarded_fn(name, &arg_types, make_guard!(guard ; $($par),*), func);
                 self
             }
         }
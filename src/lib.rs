@@ -59,6 +59,7 @@ extern crate alloc;
 
 mod any;
 mod api;
+mod debugger;
 mod engine;
 mod error;
 mod fn_args;
@@ -79,20 +80,23 @@ mod settings;
 mod stdlib;
 mod syntax;
 mod token;
+mod type_builder;
 mod r#unsafe;
 mod utils;
 
 pub use any::Dynamic;
-pub use engine::Engine;
-pub use error::{ParseError, ParseErrorType};
-pub use fn_native::{FnPtr, IteratorFn};
-pub use fn_register::{RegisterFn, RegisterPlugin, RegisterResultFn};
+pub use debugger::DebuggerCommand;
+pub use engine::{Engine, ReusableState};
+pub use error::{LexError, ParseError, ParseErrorKind, ParseErrorType};
+pub use fn_native::{ExternalIterator, FnPtr, IteratorFn, NativeCallContext, Shared};
+pub use fn_register::{RegisterFn, RegisterGuardedFn, RegisterPlugin, RegisterResultFn};
 pub use module::Module;
+pub use packages::arithmetic::DivByZero;
 pub use parser::{ImmutableString, AST, INT};
-pub use result::EvalAltResult;
+pub use result::{CaughtError, EvalAltResult};
 pub use scope::Scope;
 pub use syntax::{EvalContext, Expression};
-pub use token::Position;
+pub use token::{Position, SourceMap};
 pub use utils::calc_fn_hash;
 
 pub use rhai_codegen::*;
@@ -111,9 +115,15 @@ pub use engine::Array;
 #[cfg(not(feature = "no_object"))]
 pub use engine::Map;
 
+#[cfg(not(feature = "no_object"))]
+pub use type_builder::TypeBuilder;
+
 #[cfg(not(feature = "no_float"))]
 pub use parser::FLOAT;
 
+#[cfg(not(feature = "no_float"))]
+pub use packages::arithmetic::FloatDivByZero;
+
 #[cfg(not(feature = "no_module"))]
 pub use module::ModuleResolver;
 
@@ -147,15 +157,25 @@ pub use optimize::OptimizationLevel;
 
 #[cfg(feature = "internals")]
 #[deprecated(note = "this type is volatile and may change")]
-pub use error::LexError;
+pub use token::{
+    get_next_token, parse_raw_string_literal, parse_string_literal, InputStream, Token,
+    TokenizeState,
+};
+
+#[cfg(feature = "internals")]
+#[deprecated(note = "this type is volatile and may change")]
+pub use parser::{
+    CustomExpr, CustomNumberWrapper, Expr, FloatWrapper, ReturnType, ScriptFnDef, Stmt,
+};
 
 #[cfg(feature = "internals")]
+#[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
 #[deprecated(note = "this type is volatile and may change")]
-pub use token::{get_next_token, parse_string_literal, InputStream, Token, TokenizeState};
+pub use parser::Pattern;
 
 #[cfg(feature = "internals")]
 #[deprecated(note = "this type is volatile and may change")]
-pub use parser::{CustomExpr, Expr, FloatWrapper, ReturnType, ScriptFnDef, Stmt};
+pub use parser::SwitchPattern;
 
 #[cfg(feature = "internals")]
 #[deprecated(note = "this type is volatile and may change")]
@@ -3,7 +3,9 @@
 use crate::any::{Dynamic, Variant};
 use crate::calc_fn_hash;
 use crate::engine::Engine;
-use crate::fn_native::{CallableFunction as Func, FnCallArgs, IteratorFn, SendSync};
+use crate::fn_native::{
+    CallableFunction as Func, FnCallArgs, IteratorFn, NativeCallContext, SendSync,
+};
 use crate::fn_register::by_value as cast_arg;
 use crate::parser::{FnAccess, FnAccess::Public};
 use crate::result::EvalAltResult;
@@ -11,11 +13,15 @@ use crate::token::{Position, Token};
 use crate::utils::{ImmutableString, StaticVec, StraightHasherBuilder};
 
 #[cfg(not(feature = "no_function"))]
-use crate::{fn_native::Shared, parser::ScriptFnDef};
+use crate::{fn_native::FnPtr, parser::ScriptFnDef};
+
+#[cfg(any(not(feature = "no_function"), not(feature = "no_module")))]
+use crate::fn_native::Shared;
 
 #[cfg(not(feature = "no_module"))]
 use crate::{
     engine::Imports,
+    fn_native::shared_try_take,
     parser::AST,
     scope::{Entry as ScopeEntry, Scope},
 };
@@ -29,7 +35,7 @@ use crate::engine::{make_getter, make_setter, Map};
 use crate::stdlib::{
     any::TypeId,
     boxed::Box,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt, format,
     iter::empty,
     num::NonZeroUsize,
@@ -38,16 +44,17 @@ use crate::stdlib::{
     vec::Vec,
 };
 
-#[cfg(not(feature = "no_std"))]
 #[cfg(not(feature = "no_module"))]
 #[cfg(not(feature = "sync"))]
 use crate::stdlib::cell::RefCell;
 
-#[cfg(not(feature = "no_std"))]
 #[cfg(not(feature = "no_module"))]
 #[cfg(feature = "sync")]
 use crate::stdlib::sync::RwLock;
 
+#[cfg(not(feature = "no_module"))]
+use crate::fn_native::ModuleFactory;
+
 /// Return type of module-level Rust function.
 pub type FuncReturn<T> = Result<T, Box<EvalAltResult>>;
 
@@ -73,6 +80,17 @@ pub struct Module {
         StraightHasherBuilder,
     >,
 
+    /// Hashes of Rust functions that are also callable without qualification when the module
+    /// is imported.
+    global_functions: HashSet<u64, StraightHasherBuilder>,
+
+    /// Hashes of Rust functions taking only `Dynamic` parameters, which accept arguments of any type.
+    dynamic_functions: HashSet<u64, StraightHasherBuilder>,
+
+    /// Largest number of parameters among all functions of the same name, keyed by the hash of
+    /// the function name only (i.e. no qualifiers, zero number of arguments and no argument types).
+    fn_max_params: HashMap<u64, usize, StraightHasherBuilder>,
+
     /// Iterator functions, keyed by the type producing the iterator.
     type_iterators: HashMap<TypeId, IteratorFn>,
 
@@ -173,6 +191,9 @@ impl Module {
             },
             variables: self.variables.clone(),
             functions: self.functions.clone(),
+            global_functions: self.global_functions.clone(),
+            dynamic_functions: self.dynamic_functions.clone(),
+            fn_max_params: self.fn_max_params.clone(),
             type_iterators: self.type_iterators.clone(),
             ..Default::default()
         }
@@ -267,6 +288,7 @@ impl Module {
         // None + function name + number of arguments.
         let num_params = fn_def.params.len();
         let hash_script = calc_fn_hash(empty(), &fn_def.name, num_params, empty());
+        record_fn_params(&mut self.fn_max_params, &fn_def.name, num_params);
         self.functions.insert(
             hash_script,
             (
@@ -418,6 +440,14 @@ impl Module {
 
         let hash_fn = calc_fn_hash(empty(), &name, args_len, arg_types.iter().cloned());
 
+        if !arg_types.is_empty() && arg_types.iter().all(|&t| t == TypeId::of::<Dynamic>()) {
+            self.dynamic_functions.insert(hash_fn);
+        } else {
+            self.dynamic_functions.remove(&hash_fn);
+        }
+
+        record_fn_params(&mut self.fn_max_params, &name, arg_types.len());
+
         self.functions
             .insert(hash_fn, (name, access, args_len, Some(params), func.into()));
 
@@ -461,7 +491,7 @@ impl Module {
     ///                 // Pass parameter types via a slice with TypeId's
     ///                 &[std::any::TypeId::of::<i64>(), std::any::TypeId::of::<bool>()],
     ///                 // Fixed closure signature
-    ///                 |engine, lib, args| {
+    ///                 |context, args| {
     ///                     // 'args' is guaranteed to be the right length and of the correct types
     ///
     ///                     // Get the second parameter by 'consuming' it
@@ -486,10 +516,10 @@ impl Module {
         &mut self,
         name: impl Into<String>,
         arg_types: &[TypeId],
-        func: impl Fn(&Engine, &Module, &mut [&mut Dynamic]) -> FuncReturn<T> + SendSync + 'static,
+        func: impl Fn(&mut NativeCallContext, &mut [&mut Dynamic]) -> FuncReturn<T> + SendSync + 'static,
     ) -> u64 {
-        let f = move |engine: &Engine, lib: &Module, args: &mut FnCallArgs| {
-            func(engine, lib, args).map(Dynamic::from)
+        let f = move |context: &mut NativeCallContext, args: &mut FnCallArgs| {
+            func(context, args).map(Dynamic::from)
         };
         self.set_fn(name, Public, arg_types, Func::from_method(Box::new(f)))
     }
@@ -500,12 +530,15 @@ impl Module {
         &mut self,
         name: impl Into<String>,
         num_args: usize,
-        func: impl Fn(&Engine, &Module, &mut [&mut Dynamic]) -> FuncReturn<Dynamic> + SendSync + 'static,
+        func: impl Fn(&mut NativeCallContext, &mut [&mut Dynamic]) -> FuncReturn<Dynamic>
+            + SendSync
+            + 'static,
     ) -> u64 {
         // None + function name + number of arguments.
         let name = name.into();
         let hash_script = calc_fn_hash(empty(), &name, num_args, empty());
-        let f = move |engine: &Engine, lib: &Module, args: &mut FnCallArgs| func(engine, lib, args);
+        record_fn_params(&mut self.fn_max_params, &name, num_args);
+        let f = move |context: &mut NativeCallContext, args: &mut FnCallArgs| func(context, args);
         self.functions.insert(
             hash_script,
             (
@@ -538,7 +571,7 @@ impl Module {
         name: impl Into<String>,
         func: impl Fn() -> FuncReturn<T> + SendSync + 'static,
     ) -> u64 {
-        let f = move |_: &Engine, _: &Module, _: &mut FnCallArgs| func().map(Dynamic::from);
+        let f = move |_: &mut NativeCallContext, _: &mut FnCallArgs| func().map(Dynamic::from);
         let arg_types = [];
         self.set_fn(name, Public, &arg_types, Func::from_pure(Box::new(f)))
     }
@@ -561,7 +594,7 @@ impl Module {
         name: impl Into<String>,
         func: impl Fn(A) -> FuncReturn<T> + SendSync + 'static,
     ) -> u64 {
-        let f = move |_: &Engine, _: &Module, args: &mut FnCallArgs| {
+        let f = move |_: &mut NativeCallContext, args: &mut FnCallArgs| {
             func(cast_arg::<A>(&mut args[0])).map(Dynamic::from)
         };
         let arg_types = [TypeId::of::<A>()];
@@ -586,7 +619,7 @@ impl Module {
         name: impl Into<String>,
         func: impl Fn(&mut A) -> FuncReturn<T> + SendSync + 'static,
     ) -> u64 {
-        let f = move |_: &Engine, _: &Module, args: &mut FnCallArgs| {
+        let f = move |_: &mut NativeCallContext, args: &mut FnCallArgs| {
             func(&mut args[0].write_lock::<A>().unwrap()).map(Dynamic::from)
         };
         let arg_types = [TypeId::of::<A>()];
@@ -635,7 +668,7 @@ impl Module {
         name: impl Into<String>,
         func: impl Fn(A, B) -> FuncReturn<T> + SendSync + 'static,
     ) -> u64 {
-        let f = move |_: &Engine, _: &Module, args: &mut FnCallArgs| {
+        let f = move |_: &mut NativeCallContext, args: &mut FnCallArgs| {
             let a = cast_arg::<A>(&mut args[0]);
             let b = cast_arg::<B>(&mut args[1]);
 
@@ -666,7 +699,7 @@ impl Module {
         name: impl Into<String>,
         func: impl Fn(&mut A, B) -> FuncReturn<T> + SendSync + 'static,
     ) -> u64 {
-        let f = move |_: &Engine, _: &Module, args: &mut FnCallArgs| {
+        let f = move |_: &mut NativeCallContext, args: &mut FnCallArgs| {
             let b = cast_arg::<B>(&mut args[1]);
             let a = &mut args[0].write_lock::<A>().unwrap();
 
@@ -770,7 +803,7 @@ impl Module {
         name: impl Into<String>,
         func: impl Fn(A, B, C) -> FuncReturn<T> + SendSync + 'static,
     ) -> u64 {
-        let f = move |_: &Engine, _: &Module, args: &mut FnCallArgs| {
+        let f = move |_: &mut NativeCallContext, args: &mut FnCallArgs| {
             let a = cast_arg::<A>(&mut args[0]);
             let b = cast_arg::<B>(&mut args[1]);
             let c = cast_arg::<C>(&mut args[2]);
@@ -807,7 +840,7 @@ impl Module {
         name: impl Into<String>,
         func: impl Fn(&mut A, B, C) -> FuncReturn<T> + SendSync + 'static,
     ) -> u64 {
-        let f = move |_: &Engine, _: &Module, args: &mut FnCallArgs| {
+        let f = move |_: &mut NativeCallContext, args: &mut FnCallArgs| {
             let b = cast_arg::<B>(&mut args[2]);
             let c = cast_arg::<C>(&mut args[3]);
             let a = &mut args[0].write_lock::<A>().unwrap();
@@ -859,7 +892,7 @@ impl Module {
             panic!("Cannot register indexer for strings.");
         }
 
-        let f = move |_: &Engine, _: &Module, args: &mut FnCallArgs| {
+        let f = move |_: &mut NativeCallContext, args: &mut FnCallArgs| {
             let b = cast_arg::<B>(&mut args[1]);
             let c = cast_arg::<C>(&mut args[2]);
             let a = &mut args[0].write_lock::<A>().unwrap();
@@ -941,7 +974,7 @@ impl Module {
         name: impl Into<String>,
         func: impl Fn(A, B, C, D) -> FuncReturn<T> + SendSync + 'static,
     ) -> u64 {
-        let f = move |_: &Engine, _: &Module, args: &mut FnCallArgs| {
+        let f = move |_: &mut NativeCallContext, args: &mut FnCallArgs| {
             let a = cast_arg::<A>(&mut args[0]);
             let b = cast_arg::<B>(&mut args[1]);
             let c = cast_arg::<C>(&mut args[2]);
@@ -985,7 +1018,7 @@ impl Module {
         name: impl Into<String>,
         func: impl Fn(&mut A, B, C, D) -> FuncReturn<T> + SendSync + 'static,
     ) -> u64 {
-        let f = move |_: &Engine, _: &Module, args: &mut FnCallArgs| {
+        let f = move |_: &mut NativeCallContext, args: &mut FnCallArgs| {
             let b = cast_arg::<B>(&mut args[1]);
             let c = cast_arg::<C>(&mut args[2]);
             let d = cast_arg::<D>(&mut args[3]);
@@ -1006,7 +1039,7 @@ impl Module {
     ///
     /// The `u64` hash is calculated by the function `crate::calc_fn_hash`.
     /// It is also returned by the `set_fn_XXX` calls.
-    pub(crate) fn get_fn(&self, hash_fn: u64, public_only: bool) -> Option<&Func> {
+    pub(crate) fn get_fn_by_hash(&self, hash_fn: u64, public_only: bool) -> Option<&Func> {
         if hash_fn == 0 {
             None
         } else {
@@ -1020,6 +1053,120 @@ impl Module {
         }
     }
 
+    /// Make a Rust function also callable without qualification when the module is imported,
+    /// as if it had been registered into the `Engine` itself.
+    ///
+    /// The `u64` hash is the one returned by `set_fn` and the `set_fn_XXX` calls.
+    ///
+    /// This is useful for operators, property getters/setters and indexers, which cannot be
+    /// called with a module qualifier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// # #[cfg(not(feature = "no_module"))]
+    /// # {
+    /// use rhai::{Engine, Module};
+    /// use rhai::module_resolvers::StaticModuleResolver;
+    ///
+    /// let mut module = Module::new();
+    /// let hash = module.set_fn_1("double", |x: i64| Ok(x * 2));
+    /// module.set_fn_global(hash);
+    ///
+    /// let mut resolver = StaticModuleResolver::new();
+    /// resolver.insert("math", module);
+    ///
+    /// let mut engine = Engine::new();
+    /// engine.set_module_resolver(Some(resolver));
+    ///
+    /// assert_eq!(engine.eval::<i64>(r#"import "math" as m; m::double(21)"#)?, 42);
+    /// assert_eq!(engine.eval::<i64>(r#"import "math"; double(21)"#)?, 42);
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_fn_global(&mut self, hash_fn: u64) -> &mut Self {
+        self.global_functions.insert(hash_fn);
+        self
+    }
+
+    /// Does the module, or any of its sub-modules, contain functions callable without
+    /// qualification?
+    pub(crate) fn has_global_fns(&self) -> bool {
+        !self.global_functions.is_empty() || self.modules.values().any(Self::has_global_fns)
+    }
+
+    /// Get the largest number of parameters among all functions of the same name.
+    ///
+    /// The `u64` hash is calculated by the function `crate::calc_fn_hash`,
+    /// with no qualifiers, zero number of arguments and no argument types.
+    pub(crate) fn get_fn_max_params(&self, hash_name: u64) -> Option<usize> {
+        self.fn_max_params.get(&hash_name).cloned()
+    }
+
+    /// Does the module contain Rust functions taking only `Dynamic` parameters?
+    pub(crate) fn has_dynamic_fns(&self) -> bool {
+        !self.dynamic_functions.is_empty()
+    }
+
+    /// Get a Rust function taking only `Dynamic` parameters via its hash key.
+    pub(crate) fn get_dynamic_fn(&self, hash_fn: u64, public_only: bool) -> Option<&Func> {
+        if self.dynamic_functions.contains(&hash_fn) {
+            self.get_fn_by_hash(hash_fn, public_only)
+        } else {
+            None
+        }
+    }
+
+    /// Get a Rust function callable without qualification, searching sub-modules as well.
+    pub(crate) fn get_global_fn(&self, hash_fn: u64, public_only: bool) -> Option<&Func> {
+        if self.global_functions.contains(&hash_fn) {
+            if let Some(f) = self.get_fn_by_hash(hash_fn, public_only) {
+                return Some(f);
+            }
+        }
+
+        self.modules
+            .values()
+            .find_map(|m| m.get_global_fn(hash_fn, public_only))
+    }
+
+    /// Get a function pointer to a public script-defined function in the module,
+    /// by name and number of parameters.
+    ///
+    /// The function pointer can be called via `Engine::call_fn_ptr` with this module as the library.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// # #[cfg(not(feature = "no_module"))]
+    /// # {
+    /// use rhai::{Engine, Module, Scope};
+    ///
+    /// let engine = Engine::new();
+    /// let ast = engine.compile("fn add(x, y) { x + y }")?;
+    /// let module = Module::eval_ast_as_new(Scope::new(), &ast, &engine)?;
+    ///
+    /// let add = module.get_fn("add", 2).unwrap();
+    /// assert_eq!(add.fn_name(), "add");
+    /// assert!(module.get_fn("add", 1).is_none());
+    ///
+    /// assert_eq!(engine.call_fn_ptr::<_, i64>(&module, &add, (40_i64, 2_i64))?, 42);
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "no_function"))]
+    pub fn get_fn(&self, name: &str, arity: usize) -> Option<FnPtr> {
+        let hash_script = calc_fn_hash(empty(), name, arity, empty());
+
+        self.get_fn_by_hash(hash_script, true)
+            .filter(|f| f.is_script())
+            .map(|_| FnPtr::new_unchecked(name, Default::default()))
+    }
+
     /// Get a modules-qualified function.
     /// Name and Position in `EvalAltResult` are None and must be set afterwards.
     ///
@@ -1035,6 +1182,13 @@ impl Module {
         self.modules.extend(other.modules.into_iter());
         self.variables.extend(other.variables.into_iter());
         self.functions.extend(other.functions.into_iter());
+        self.global_functions
+            .extend(other.global_functions.into_iter());
+        self.dynamic_functions
+            .extend(other.dynamic_functions.into_iter());
+        other.fn_max_params.into_iter().for_each(|(hash, num)| {
+            merge_fn_params(&mut self.fn_max_params, hash, num);
+        });
         self.type_iterators.extend(other.type_iterators.into_iter());
         self.all_functions.clear();
         self.all_variables.clear();
@@ -1052,6 +1206,13 @@ impl Module {
 
         self.variables.extend(other.variables.into_iter());
         self.functions.extend(other.functions.into_iter());
+        self.global_functions
+            .extend(other.global_functions.into_iter());
+        self.dynamic_functions
+            .extend(other.dynamic_functions.into_iter());
+        other.fn_max_params.into_iter().for_each(|(hash, num)| {
+            merge_fn_params(&mut self.fn_max_params, hash, num);
+        });
         self.type_iterators.extend(other.type_iterators.into_iter());
         self.all_functions.clear();
         self.all_variables.clear();
@@ -1084,6 +1245,8 @@ impl Module {
         self.variables
             .extend(other.variables.iter().map(|(k, v)| (k.clone(), v.clone())));
 
+        let fn_max_params = &mut self.fn_max_params;
+
         self.functions.extend(
             other
                 .functions
@@ -1093,9 +1256,18 @@ impl Module {
                     Func::Script(ref f) => _filter(f.access, f.name.as_str(), f.params.len()),
                     _ => true,
                 })
+                .inspect(|(_, (name, _, num_args, params, _))| {
+                    let num_params = params.as_ref().map_or(*num_args, |p| p.len());
+                    record_fn_params(fn_max_params, name, num_params);
+                })
                 .map(|(&k, v)| (k, v.clone())),
         );
 
+        self.global_functions
+            .extend(other.global_functions.iter().cloned());
+        self.dynamic_functions
+            .extend(other.dynamic_functions.iter().cloned());
+
         self.type_iterators
             .extend(other.type_iterators.iter().map(|(&k, v)| (k, v.clone())));
 
@@ -1116,6 +1288,16 @@ impl Module {
             _ => true,
         });
 
+        // Functions may have been removed, so rebuild the largest numbers of parameters
+        let fn_max_params = &mut self.fn_max_params;
+        fn_max_params.clear();
+        self.functions
+            .values()
+            .for_each(|(name, _, num_args, params, _)| {
+                let num_params = params.as_ref().map_or(*num_args, |p| p.len());
+                record_fn_params(fn_max_params, name, num_params);
+            });
+
         self.all_functions.clear();
         self.all_variables.clear();
         self.indexed = false;
@@ -1157,12 +1339,17 @@ impl Module {
             .map(|f| f.get_shared_fn_def())
     }
 
+    /// Iterate through all script-defined functions in the module.
+    ///
+    /// The action receives the access mode, name, number of parameters and doc-comments of each function.
     #[cfg(not(feature = "no_function"))]
-    pub fn iter_script_fn_info(&self, mut action: impl FnMut(FnAccess, &str, usize)) {
+    pub fn iter_script_fn_info(&self, mut action: impl FnMut(FnAccess, &str, usize, &[String])) {
         self.functions
             .iter()
             .for_each(|(_, (_, _, _, _, v))| match v {
-                Func::Script(ref f) => action(f.access, f.name.as_str(), f.params.len()),
+                Func::Script(ref f) => {
+                    action(f.access, f.name.as_str(), f.params.len(), &f.comments)
+                }
                 _ => (),
             });
     }
@@ -1204,6 +1391,7 @@ impl Module {
 
         // Modules left in the scope become sub-modules
         mods.into_iter().for_each(|(alias, m)| {
+            let m = shared_try_take(m).unwrap_or_else(|m| (*m).clone());
             module.modules.insert(alias.to_string(), m);
         });
 
@@ -1308,6 +1496,26 @@ impl Module {
     }
 }
 
+/// Record the number of parameters of a function, keeping the largest one for each function name.
+fn record_fn_params(
+    fn_max_params: &mut HashMap<u64, usize, StraightHasherBuilder>,
+    name: &str,
+    num_params: usize,
+) {
+    let hash_name = calc_fn_hash(empty(), name, 0, empty());
+    merge_fn_params(fn_max_params, hash_name, num_params);
+}
+
+/// Merge the number of parameters of a function into the largest one for its name.
+fn merge_fn_params(
+    fn_max_params: &mut HashMap<u64, usize, StraightHasherBuilder>,
+    hash_name: u64,
+    num_params: usize,
+) {
+    let max = fn_max_params.entry(hash_name).or_insert(0);
+    *max = (*max).max(num_params);
+}
+
 /// [INTERNALS] A chain of module names to qualify a variable or function call.
 /// Exported under the `internals` feature only.
 ///
@@ -1378,6 +1586,76 @@ pub trait ModuleResolver: SendSync {
     fn resolve(&self, _: &Engine, path: &str, pos: Position) -> Result<Module, Box<EvalAltResult>>;
 }
 
+/// State of a `LazyModule`.
+#[cfg(not(feature = "no_module"))]
+enum LazyModuleState {
+    /// The module is not yet built.
+    Factory(ModuleFactory),
+    /// The module is built.
+    Module(Shared<Module>),
+}
+
+/// A module that is built by a factory function on first use.
+#[cfg(not(feature = "no_module"))]
+pub(crate) struct LazyModule {
+    #[cfg(not(feature = "sync"))]
+    state: RefCell<LazyModuleState>,
+    #[cfg(feature = "sync")]
+    state: RwLock<LazyModuleState>,
+}
+
+#[cfg(not(feature = "no_module"))]
+impl LazyModule {
+    /// Create a new `LazyModule` from a factory function.
+    pub fn new(factory: ModuleFactory) -> Self {
+        let state = LazyModuleState::Factory(factory);
+
+        Self {
+            #[cfg(not(feature = "sync"))]
+            state: RefCell::new(state),
+            #[cfg(feature = "sync")]
+            state: RwLock::new(state),
+        }
+    }
+
+    /// Get the module, building it if this is the first use.
+    ///
+    /// The factory function is called at most once, after which it is dropped.
+    /// Once built, the module is shared and not cloned.
+    /// Under the `sync` feature, concurrent callers wait for the module to be built.
+    pub fn get(&self) -> Shared<Module> {
+        // Fast path - the module is already built
+        {
+            #[cfg(not(feature = "sync"))]
+            let state = self.state.borrow();
+            #[cfg(feature = "sync")]
+            let state = self.state.read().unwrap();
+
+            if let LazyModuleState::Module(module) = &*state {
+                return module.clone();
+            }
+        }
+
+        #[cfg(not(feature = "sync"))]
+        let mut state = self.state.borrow_mut();
+        #[cfg(feature = "sync")]
+        let mut state = self.state.write().unwrap();
+
+        // Another thread may have built the module while waiting for the lock
+        let module = match &*state {
+            LazyModuleState::Module(module) => return module.clone(),
+            LazyModuleState::Factory(factory) => {
+                let mut module = factory();
+                module.index_all_sub_modules();
+                Shared::new(module)
+            }
+        };
+
+        *state = LazyModuleState::Module(module.clone());
+        module
+    }
+}
+
 /// Re-export module resolvers.
 #[cfg(not(feature = "no_module"))]
 pub mod resolvers {
@@ -1735,7 +2013,7 @@ mod file {
             let mut _module = Module::eval_ast_as_new(Scope::new(), ast, engine)?;
 
             #[cfg(not(feature = "no_function"))]
-            ast.iter_functions(|access, name, num_args| match access {
+            ast.iter_functions(|access, name, num_args, _| match access {
                 FnAccess::Private => (),
                 FnAccess::Public => {
                     let fn_name = name.to_string();
@@ -1744,8 +2022,8 @@ mod file {
                     _module.set_raw_fn_as_scripted(
                         name,
                         num_args,
-                        move |engine: &Engine, _, args: &mut [&mut Dynamic]| {
-                            engine.call_fn_dynamic_raw(
+                        move |context: &mut NativeCallContext, args: &mut [&mut Dynamic]| {
+                            context.engine().call_fn_dynamic_raw(
                                 &mut Scope::new(),
                                 &ast_lib,
                                 &fn_name,
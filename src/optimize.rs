@@ -5,9 +5,11 @@ use crate::calc_fn_hash;
 use crate::engine::{
     Engine, KEYWORD_DEBUG, KEYWORD_EVAL, KEYWORD_FN_PTR, KEYWORD_PRINT, KEYWORD_TYPE_OF,
 };
+use crate::fn_call::run_builtin_binary_op;
 use crate::fn_native::FnPtr;
 use crate::module::Module;
-use crate::parser::{map_dynamic_to_expr, Expr, ScriptFnDef, Stmt, AST};
+use crate::packages::arithmetic::{div_by_zero, is_zero};
+use crate::parser::{map_dynamic_to_expr, Expr, ScriptFnDef, Stmt, SwitchPattern, AST};
 use crate::scope::{Entry as ScopeEntry, EntryType as ScopeEntryType, Scope};
 use crate::utils::StaticVec;
 
@@ -62,7 +64,8 @@ struct State<'a> {
     /// Has the AST been changed during this pass?
     changed: bool,
     /// Collection of constants to use for eager function evaluations.
-    constants: Vec<(String, Expr)>,
+    /// A constant shadowed by a variable has no value.
+    constants: Vec<(String, Option<Expr>)>,
     /// An `Engine` instance for eager function evaluation.
     engine: &'a Engine,
     /// Library of script-defined functions.
@@ -96,7 +99,7 @@ impl<'a> State<'a> {
     }
     /// Does a constant exist?
     pub fn contains_constant(&self, name: &str) -> bool {
-        self.constants.iter().any(|(n, _)| n == name)
+        self.find_constant(name).is_some()
     }
     /// Prune the list of constants back to a specified size.
     pub fn restore_constants(&mut self, len: usize) {
@@ -104,13 +107,19 @@ impl<'a> State<'a> {
     }
     /// Add a new constant to the list.
     pub fn push_constant(&mut self, name: &str, value: Expr) {
-        self.constants.push((name.into(), value))
+        self.constants.push((name.into(), Some(value)))
+    }
+    /// Hide any constant with the same name as a new variable.
+    pub fn shadow_constant(&mut self, name: &str) {
+        if self.contains_constant(name) {
+            self.constants.push((name.into(), None))
+        }
     }
     /// Look up a constant from the list.
     pub fn find_constant(&self, name: &str) -> Option<&Expr> {
         for (n, expr) in self.constants.iter().rev() {
             if n == name {
-                return Some(expr);
+                return expr.as_ref();
             }
         }
 
@@ -143,11 +152,42 @@ fn call_fn_with_constant_arguments(
             false,
             true,
             None,
+            0,
         )
         .ok()
         .map(|(v, _)| v)
 }
 
+/// Evaluate a built-in binary operator on constant operands.
+///
+/// Returns `None` if there is no built-in implementation for the operand types,
+/// if the operator is overridden by a registered, package or script-defined function,
+/// or if it fails (e.g. division by zero), so the error is raised at run-time.
+fn call_builtin_op_with_constant_arguments(
+    state: &State,
+    op: &str,
+    args: &[Expr],
+) -> Option<Dynamic> {
+    let x = args[0].get_constant_value();
+    let y = args[1].get_constant_value();
+
+    let hash_fn = calc_fn_hash(empty(), op, 2, [x.type_id(), y.type_id()].iter().cloned());
+
+    if state.engine.global_module.contains_fn(hash_fn, false)
+        || state.engine.packages.contains_fn(hash_fn, false)
+        || state.lib.contains_fn(hash_fn, false)
+    {
+        return None;
+    }
+
+    // Division by zero depends on the settings of the `Engine`
+    if (op == "/" || op == "%") && is_zero(&y) {
+        return div_by_zero(state.engine, op, &x, &y).ok().flatten();
+    }
+
+    run_builtin_binary_op(op, &x, &y).ok().flatten()
+}
+
 /// Optimize a statement.
 fn optimize_stmt(stmt: Stmt, state: &mut State, preserve_result: bool) -> Stmt {
     match stmt {
@@ -204,6 +244,46 @@ fn optimize_stmt(stmt: Stmt, state: &mut State, preserve_result: bool) -> Stmt {
                 x.3,
             ))),
         },
+        // switch expr { pattern if guard => stmt, ... }
+        Stmt::Switch(x) => {
+            let (expr, arms, pos) = *x;
+
+            let arms = arms
+                .into_iter()
+                .map(|(pattern, guard, body)| {
+                    let pattern = match pattern {
+                        SwitchPattern::Value(expr) => {
+                            SwitchPattern::Value(optimize_expr(expr, state))
+                        }
+                        SwitchPattern::Range(expr) => {
+                            SwitchPattern::Range(optimize_expr(expr, state))
+                        }
+                        pattern => pattern,
+                    };
+
+                    // A bound variable hides any constant of the same name
+                    let orig_constants = match &pattern {
+                        SwitchPattern::Bind(name, _) => {
+                            let orig_constants = state.constants.clone();
+                            state.constants.retain(|(n, _)| n != name);
+                            Some(orig_constants)
+                        }
+                        _ => None,
+                    };
+
+                    let guard = guard.map(|expr| optimize_expr(expr, state));
+                    let body = optimize_stmt(body, state, true);
+
+                    if let Some(constants) = orig_constants {
+                        state.constants = constants;
+                    }
+
+                    (pattern, guard, body)
+                })
+                .collect();
+
+            Stmt::Switch(Box::new((optimize_expr(expr, state), arms, pos)))
+        }
         // while expr { block }
         Stmt::While(x) => match x.0 {
             // while false { block } -> Noop
@@ -230,6 +310,13 @@ fn optimize_stmt(stmt: Stmt, state: &mut State, preserve_result: bool) -> Stmt {
                 stmt => Stmt::While(Box::new((optimize_expr(expr, state), stmt, x.2))),
             },
         },
+        // while let id = expr { block }
+        Stmt::WhileLet(x) => Stmt::WhileLet(Box::new((
+            x.0,
+            optimize_expr(x.1, state),
+            optimize_stmt(x.2, state, false),
+            x.3,
+        ))),
         // loop { block }
         Stmt::Loop(x) => match optimize_stmt(x.0, state, false) {
             // loop { break; } -> Noop
@@ -248,6 +335,16 @@ fn optimize_stmt(stmt: Stmt, state: &mut State, preserve_result: bool) -> Stmt {
             optimize_stmt(x.2, state, false),
             x.3,
         ))),
+        // try { block } catch ( id ) { block }
+        Stmt::TryCatch(x) => {
+            let (body, var_def, catch_body, pos) = *x;
+            Stmt::TryCatch(Box::new((
+                optimize_stmt(body, state, true),
+                var_def,
+                optimize_stmt(catch_body, state, true),
+                pos,
+            )))
+        }
         // let id = expr;
         Stmt::Let(x) if x.1.is_some() => Stmt::Let(Box::new((
             x.0,
@@ -256,6 +353,9 @@ fn optimize_stmt(stmt: Stmt, state: &mut State, preserve_result: bool) -> Stmt {
         ))),
         // let id;
         stmt @ Stmt::Let(_) => stmt,
+        // let pattern = expr;
+        #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+        Stmt::Destructure(x) => Stmt::Destructure(Box::new((x.0, optimize_expr(x.1, state), x.2))),
         // import expr as id;
         #[cfg(not(feature = "no_module"))]
         Stmt::Import(x) => Stmt::Import(Box::new((optimize_expr(x.0, state), x.1, x.2))),
@@ -276,6 +376,14 @@ fn optimize_stmt(stmt: Stmt, state: &mut State, preserve_result: bool) -> Stmt {
                             state.set_dirty();
                             Stmt::Noop(pos) // No need to keep constants
                         }
+                        // Variables hide constants with the same name
+                        Stmt::Let(_) => {
+                            let stmt = optimize_stmt(stmt, state, preserve_result);
+                            if let Stmt::Let(x) = &stmt {
+                                state.shadow_constant(&(x.0).0);
+                            }
+                            stmt
+                        }
                         // Optimize the statement
                         _ => optimize_stmt(stmt, state, preserve_result),
                     })
@@ -357,6 +465,9 @@ fn optimize_stmt(stmt: Stmt, state: &mut State, preserve_result: bool) -> Stmt {
                 }
                 // Only one let statement - leave it alone
                 [Stmt::Let(_)] => Stmt::Block(Box::new((result.into(), pos))),
+                // Only one destructuring let statement - leave it alone
+                #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+                [Stmt::Destructure(_)] => Stmt::Block(Box::new((result.into(), pos))),
                 // Only one import statement - leave it alone
                 #[cfg(not(feature = "no_module"))]
                 [Stmt::Import(_)] => Stmt::Block(Box::new((result.into(), pos))),
@@ -381,6 +492,21 @@ fn optimize_stmt(stmt: Stmt, state: &mut State, preserve_result: bool) -> Stmt {
     }
 }
 
+/// Optimize an expression that may not be evaluated at run-time, such as the right operand of `&&`.
+///
+/// Functions within it are never eagerly called, even under `OptimizationLevel::Full`.
+fn optimize_conditional_expr(expr: Expr, state: &mut State) -> Expr {
+    let level = state.optimization_level;
+
+    if level == OptimizationLevel::Full {
+        state.optimization_level = OptimizationLevel::Simple;
+    }
+    let expr = optimize_expr(expr, state);
+    state.optimization_level = level;
+
+    expr
+}
+
 /// Optimize an expression.
 fn optimize_expr(expr: Expr, state: &mut State) -> Expr {
     // These keywords are handled specially
@@ -430,7 +556,8 @@ fn optimize_expr(expr: Expr, state: &mut State) -> Expr {
         Expr::Index(x) => match (x.0, x.1) {
             // array[int]
             (Expr::Array(mut a), Expr::IntegerConstant(i))
-                if i.0 >= 0 && (i.0 as usize) < a.0.len() && a.0.iter().all(Expr::is_pure) =>
+                if i.0 >= 0 && (i.0 as usize) < a.0.len() && a.0.iter().all(Expr::is_pure)
+                    && !a.0.iter().any(|x| matches!(x, Expr::Spread(_))) =>
             {
                 // Array literal where everything is pure - promote the indexed item.
                 // All other items can be thrown away.
@@ -463,6 +590,9 @@ fn optimize_expr(expr: Expr, state: &mut State) -> Expr {
         Expr::Array(a) => Expr::Array(Box::new((a.0
                                 .into_iter().map(|expr| optimize_expr(expr, state))
                                 .collect(), a.1))),
+        // ...expr
+        #[cfg(not(feature = "no_index"))]
+        Expr::Spread(x) => Expr::Spread(Box::new((optimize_expr(x.0, state), x.1))),
         // [ items .. ]
         #[cfg(not(feature = "no_object"))]
         Expr::Map(m) => Expr::Map(Box::new((m.0
@@ -504,45 +634,61 @@ fn optimize_expr(expr: Expr, state: &mut State) -> Expr {
             (lhs, rhs) => Expr::In(Box::new((optimize_expr(lhs, state), optimize_expr(rhs, state), x.2))),
         },
         // lhs && rhs
-        Expr::And(x) => match (x.0, x.1) {
-            // true && rhs -> rhs
-            (Expr::True(_), rhs) => {
-                state.set_dirty();
-                rhs
-            }
-            // false && rhs -> false
-            (Expr::False(pos), _) => {
-                state.set_dirty();
-                Expr::False(pos)
-            }
-            // lhs && true -> lhs
-            (lhs, Expr::True(_)) => {
-                state.set_dirty();
-                optimize_expr(lhs, state)
+        //
+        // The left operand is optimized first.  If it short-circuits, the right operand is discarded
+        // without being optimized.  Otherwise, functions in the right operand are never eagerly called
+        // because they may not be evaluated at all.
+        Expr::And(x) => {
+            let (lhs, rhs, pos) = *x;
+
+            match optimize_expr(lhs, state) {
+                // true && rhs -> rhs
+                Expr::True(_) => {
+                    state.set_dirty();
+                    optimize_expr(rhs, state)
+                }
+                // false && rhs -> false
+                Expr::False(pos) => {
+                    state.set_dirty();
+                    Expr::False(pos)
+                }
+                lhs => match optimize_conditional_expr(rhs, state) {
+                    // lhs && true -> lhs
+                    Expr::True(_) => {
+                        state.set_dirty();
+                        lhs
+                    }
+                    // lhs && rhs
+                    rhs => Expr::And(Box::new((lhs, rhs, pos))),
+                },
             }
-            // lhs && rhs
-            (lhs, rhs) => Expr::And(Box::new((optimize_expr(lhs, state), optimize_expr(rhs, state), x.2))),
-        },
+        }
         // lhs || rhs
-        Expr::Or(x) => match (x.0, x.1) {
-            // false || rhs -> rhs
-            (Expr::False(_), rhs) => {
-                state.set_dirty();
-                rhs
-            }
-            // true || rhs -> true
-            (Expr::True(pos), _) => {
-                state.set_dirty();
-                Expr::True(pos)
-            }
-            // lhs || false
-            (lhs, Expr::False(_)) => {
-                state.set_dirty();
-                optimize_expr(lhs, state)
+        Expr::Or(x) => {
+            let (lhs, rhs, pos) = *x;
+
+            match optimize_expr(lhs, state) {
+                // false || rhs -> rhs
+                Expr::False(_) => {
+                    state.set_dirty();
+                    optimize_expr(rhs, state)
+                }
+                // true || rhs -> true
+                Expr::True(pos) => {
+                    state.set_dirty();
+                    Expr::True(pos)
+                }
+                lhs => match optimize_conditional_expr(rhs, state) {
+                    // lhs || false -> lhs
+                    Expr::False(_) => {
+                        state.set_dirty();
+                        lhs
+                    }
+                    // lhs || rhs
+                    rhs => Expr::Or(Box::new((lhs, rhs, pos))),
+                },
             }
-            // lhs || rhs
-            (lhs, rhs) => Expr::Or(Box::new((optimize_expr(lhs, state), optimize_expr(rhs, state), x.2))),
-        },
+        }
 
         // Do not call some special keywords
         Expr::FnCall(mut x) if DONT_EVAL_KEYWORDS.contains(&(x.0).0.as_ref())=> {
@@ -568,6 +714,25 @@ fn optimize_expr(expr: Expr, state: &mut State) -> Expr {
             }
         }
 
+        // Fold built-in operators on constants, which cannot have side effects
+        Expr::FnCall(mut x)
+                if x.1.is_none() // Non-qualified
+                && (x.0).1 // Built-in operator
+                && state.optimization_level == OptimizationLevel::Simple // full optimizations call the function below
+                && x.3.len() == 2 // binary operator
+                && x.3.iter().all(|expr| expr.is_constant()) // all arguments are constants
+        => {
+            let ((op, _, _, pos), _, _, args, _) = x.as_mut();
+
+            call_builtin_op_with_constant_arguments(&state, op, args)
+                .and_then(|result| map_dynamic_to_expr(result, *pos))
+                .map(|expr| {
+                    state.set_dirty();
+                    expr
+                })
+                .unwrap_or_else(|| Expr::FnCall(x))
+        }
+
         // Eagerly call functions
         Expr::FnCall(mut x)
                 if x.1.is_none() // Non-qualified
@@ -707,11 +872,20 @@ fn optimize(
                         // and always keep the last return value
                         let keep = match stmt {
                             Stmt::Let(_) => true,
+                            #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+                            Stmt::Destructure(_) => true,
                             #[cfg(not(feature = "no_module"))]
                             Stmt::Import(_) => true,
                             _ => i == num_statements - 1,
                         };
-                        optimize_stmt(stmt, &mut state, keep)
+                        let stmt = optimize_stmt(stmt, &mut state, keep);
+
+                        // Variables hide constants with the same name
+                        if let Stmt::Let(x) = &stmt {
+                            state.shadow_constant(&(x.0).0);
+                        }
+
+                        stmt
                     }
                 }
             })
@@ -771,6 +945,8 @@ pub fn optimize_into_ast(
                         #[cfg(not(feature = "no_closure"))]
                         externals: fn_def.externals.clone(),
                         pos: fn_def.pos,
+                        comments: Default::default(),
+                        lib: None,
                     }
                     .into()
                 })
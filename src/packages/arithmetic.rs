@@ -1,6 +1,8 @@
 #![allow(non_snake_case)]
 
+use crate::any::Dynamic;
 use crate::def_package;
+use crate::engine::Engine;
 use crate::parser::INT;
 use crate::plugin::*;
 
@@ -13,13 +15,129 @@ use crate::parser::FLOAT;
 #[cfg(not(feature = "no_float"))]
 use num_traits::float::Float;
 
-use crate::stdlib::{format, string::String};
+use crate::stdlib::{any::TypeId, format, string::String};
 
 #[inline(always)]
 pub fn make_err(msg: String) -> Box<EvalAltResult> {
     EvalAltResult::ErrorArithmetic(msg, Position::none()).into()
 }
 
+/// Behavior of integer division (`/`) and modulo (`%`) by zero.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum DivByZero {
+    /// Raise an arithmetic error.  This is the default.
+    Error,
+    /// The result is zero.
+    Zero,
+    /// The result is the maximum value of the integer type (e.g. `i64::MAX`).
+    MaxValue,
+}
+
+impl Default for DivByZero {
+    fn default() -> Self {
+        Self::Error
+    }
+}
+
+/// Behavior of floating-point division (`/`) and modulo (`%`) by zero.
+///
+/// Not available under the `no_float` feature.
+#[cfg(not(feature = "no_float"))]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum FloatDivByZero {
+    /// Raise an arithmetic error.
+    Error,
+    /// Follow IEEE 754, i.e. division results in positive or negative infinity
+    /// (or NaN when the dividend is also zero), and modulo results in NaN.  This is the default.
+    Infinity,
+}
+
+#[cfg(not(feature = "no_float"))]
+impl Default for FloatDivByZero {
+    fn default() -> Self {
+        Self::Infinity
+    }
+}
+
+/// Is the value a zero of one of the standard number types?
+pub fn is_zero(value: &Dynamic) -> bool {
+    macro_rules! is_zero {
+        ($($type:ty => $zero:expr),+) => {
+            $(
+                if value.type_id() == TypeId::of::<$type>() {
+                    return *value.read_lock::<$type>().unwrap() == $zero;
+                }
+            )+
+        };
+    }
+
+    is_zero!(INT => 0);
+
+    #[cfg(not(feature = "only_i32"))]
+    #[cfg(not(feature = "only_i64"))]
+    is_zero!(i8 => 0, u8 => 0, i16 => 0, u16 => 0, i32 => 0, u32 => 0, u64 => 0);
+
+    #[cfg(not(feature = "only_i32"))]
+    #[cfg(not(feature = "only_i64"))]
+    #[cfg(not(target_arch = "wasm32"))]
+    is_zero!(i128 => 0, u128 => 0);
+
+    #[cfg(not(feature = "no_float"))]
+    is_zero!(f32 => 0.0, f64 => 0.0);
+
+    false
+}
+
+/// Divide (`/`) or modulo (`%`) a number by zero according to the settings of the `Engine`.
+///
+/// Returns `None` if the divisor is not a zero of the same number type as the dividend,
+/// or if the standard behavior of the operator applies.
+pub fn div_by_zero(
+    engine: &Engine,
+    _op: &str,
+    x: &Dynamic,
+    y: &Dynamic,
+) -> Result<Option<Dynamic>, Box<EvalAltResult>> {
+    if x.type_id() != y.type_id() || !is_zero(y) {
+        return Ok(None);
+    }
+
+    macro_rules! div_by_zero_int {
+        ($($type:ty),+) => {
+            $(
+                if x.type_id() == TypeId::of::<$type>() {
+                    return Ok(match engine.div_by_zero {
+                        DivByZero::Error => None,
+                        DivByZero::Zero => Some(Dynamic::from(0 as $type)),
+                        DivByZero::MaxValue => Some(Dynamic::from(<$type>::MAX)),
+                    });
+                }
+            )+
+        };
+    }
+
+    div_by_zero_int!(INT);
+
+    #[cfg(not(feature = "only_i32"))]
+    #[cfg(not(feature = "only_i64"))]
+    div_by_zero_int!(i8, u8, i16, u16, i32, u32, u64);
+
+    #[cfg(not(feature = "only_i32"))]
+    #[cfg(not(feature = "only_i64"))]
+    #[cfg(not(target_arch = "wasm32"))]
+    div_by_zero_int!(i128, u128);
+
+    #[cfg(not(feature = "no_float"))]
+    if engine.float_div_by_zero == FloatDivByZero::Error {
+        return Err(make_err(match _op {
+            "%" => format!("Modulo division by zero: {} % {}", x, y),
+            _ => format!("Division by zero: {} / {}", x, y),
+        }));
+    }
+
+    Ok(None)
+}
+
 macro_rules! gen_arithmetic_functions {
     ($root:ident => $($arg_type:ident),+) => {
         pub mod $root { $(pub mod $arg_type {
@@ -2,55 +2,33 @@
 #![allow(non_snake_case)]
 
 use crate::any::{Dynamic, Variant};
+use crate::calc_fn_hash;
 use crate::def_package;
-use crate::engine::{Array, Engine};
-use crate::fn_native::FnPtr;
+use crate::engine::Array;
+use crate::fn_native::{FnPtr, NativeCallContext};
 use crate::parser::{ImmutableString, INT};
 use crate::plugin::*;
-
-#[cfg(not(feature = "unchecked"))]
-use crate::{result::EvalAltResult, token::Position};
+use crate::result::EvalAltResult;
+use crate::token::Position;
 
 #[cfg(not(feature = "no_object"))]
-use crate::engine::Map;
+use crate::engine::{make_getter, Map};
 
-use crate::stdlib::{any::TypeId, boxed::Box};
+use crate::stdlib::{any::TypeId, boxed::Box, cmp::Ordering, format, iter::empty, mem, vec::Vec};
 
 #[cfg(not(feature = "unchecked"))]
 use crate::stdlib::string::ToString;
 
 pub type Unit = ();
 
-macro_rules! gen_array_functions {
-    ($root:ident => $($arg_type:ident),+ ) => {
-        pub mod $root { $( pub mod $arg_type {
-            use super::super::*;
-
-            #[export_module]
-            pub mod functions {
-                #[rhai_fn(name = "push", name = "+=")]
-                #[inline(always)]
-                pub fn push(list: &mut Array, item: $arg_type) {
-                    list.push(Dynamic::from(item));
-                }
-
-                pub fn insert(list: &mut Array, position: INT, item: $arg_type) {
-                    if position <= 0 {
-                        list.insert(0, Dynamic::from(item));
-                    } else if (position as usize) >= list.len() - 1 {
-                        push(list, item);
-                    } else {
-                        list.insert(position as usize, Dynamic::from(item));
-                    }
-                }
-            }
-        })* }
-    }
-}
-
 macro_rules! reg_functions {
-    ($mod_name:ident += $root:ident ; $($arg_type:ident),+) => { $(
-        combine_with_exported_module!($mod_name, "array_functions", $root::$arg_type::functions);
+    ($mod_name:ident ; $($arg_type:ident),+) => { $(
+        $mod_name.set_raw_fn("push", &[TypeId::of::<Array>(), TypeId::of::<$arg_type>()], push);
+        $mod_name.set_raw_fn("+=", &[TypeId::of::<Array>(), TypeId::of::<$arg_type>()], push);
+
+        $mod_name.set_raw_fn("insert",
+            &[TypeId::of::<Array>(), TypeId::of::<INT>(), TypeId::of::<$arg_type>()],
+            insert);
 
         $mod_name.set_raw_fn("pad",
             &[TypeId::of::<Array>(), TypeId::of::<INT>(), TypeId::of::<$arg_type>()],
@@ -59,26 +37,37 @@ macro_rules! reg_functions {
 }
 
 def_package!(crate:BasicArrayPackage:"Basic array utilities.", lib, {
-    reg_functions!(lib += basic; INT, bool, char, ImmutableString, FnPtr, Array, Unit);
+    reg_functions!(lib; INT, bool, char, ImmutableString, FnPtr, Array, Unit);
 
     #[cfg(not(feature = "only_i32"))]
     #[cfg(not(feature = "only_i64"))]
     {
-        reg_functions!(lib += numbers; i8, u8, i16, u16, i32, i64, u32, u64);
+        reg_functions!(lib; i8, u8, i16, u16, i32, i64, u32, u64);
 
         #[cfg(not(target_arch = "wasm32"))]
-        reg_functions!(lib += num_128; i128, u128);
+        reg_functions!(lib; i128, u128);
     }
 
     #[cfg(not(feature = "no_float"))]
-    reg_functions!(lib += float; f32, f64);
+    reg_functions!(lib; f32, f64);
 
     #[cfg(not(feature = "no_object"))]
-    reg_functions!(lib += map; Map);
+    reg_functions!(lib; Map);
 
-    // Merge in the module at the end to override `+=` for arrays
     combine_with_exported_module!(lib, "array", array_functions);
 
+    // Register at the end to override `+=` for arrays
+    lib.set_raw_fn("append", &[TypeId::of::<Array>(), TypeId::of::<Array>()], append);
+    lib.set_raw_fn("+=", &[TypeId::of::<Array>(), TypeId::of::<Array>()], append);
+
+    lib.set_raw_fn("elem_type", &[TypeId::of::<Array>()], elem_type);
+    #[cfg(not(feature = "no_object"))]
+    lib.set_raw_fn(make_getter("elem_type"), &[TypeId::of::<Array>()], elem_type);
+
+    lib.set_raw_fn("dedup", &[TypeId::of::<Array>()], dedup);
+    lib.set_raw_fn("sort", &[TypeId::of::<Array>()], sort);
+    lib.set_raw_fn("flatten", &[TypeId::of::<Array>()], flatten);
+
     // Register array iterator
     lib.set_iter(
         TypeId::of::<Array>(),
@@ -88,16 +77,19 @@ def_package!(crate:BasicArrayPackage:"Basic array utilities.", lib, {
 
 #[export_module]
 mod array_functions {
+    #[inline(always)]
+    pub fn typed_array(elem_type: ImmutableString) -> Dynamic {
+        Dynamic::typed_array(elem_type)
+    }
+    #[inline(always)]
+    pub fn to_array(list: &mut Array) -> Array {
+        list.clone()
+    }
     #[rhai_fn(name = "len", get = "len")]
     #[inline(always)]
     pub fn len(list: &mut Array) -> INT {
         list.len() as INT
     }
-    #[rhai_fn(name = "append", name = "+=")]
-    #[inline(always)]
-    pub fn append(x: &mut Array, y: Array) {
-        x.extend(y);
-    }
     #[rhai_fn(name = "+")]
     #[inline]
     pub fn concat(mut x: Array, y: Array) -> Array {
@@ -137,24 +129,205 @@ mod array_functions {
     pub fn reverse(list: &mut Array) {
         list.reverse();
     }
+    #[rhai_fn(return_raw)]
+    pub fn chunks(list: &mut Array, size: INT) -> Result<Dynamic, Box<EvalAltResult>> {
+        if size <= 0 {
+            return EvalAltResult::ErrorRuntime(
+                format!("Chunk size must be positive: {}", size),
+                Position::none(),
+            )
+            .into();
+        }
+
+        Ok(list
+            .chunks(size as usize)
+            .map(|chunk| Dynamic::from(chunk.to_vec()))
+            .collect::<Array>()
+            .into())
+    }
+    #[rhai_fn(return_raw)]
+    pub fn windows(list: &mut Array, size: INT) -> Result<Dynamic, Box<EvalAltResult>> {
+        if size <= 0 {
+            return EvalAltResult::ErrorRuntime(
+                format!("Window size must be positive: {}", size),
+                Position::none(),
+            )
+            .into();
+        }
+
+        Ok(list
+            .windows(size as usize)
+            .map(|window| Dynamic::from(window.to_vec()))
+            .collect::<Array>()
+            .into())
+    }
+}
+
+/// Remove consecutive duplicates, as determined by the `==` operator.
+fn dedup(
+    context: &mut NativeCallContext,
+    args: &mut [&mut Dynamic],
+) -> Result<(), Box<EvalAltResult>> {
+    let mut list = args[0].write_lock::<Array>().unwrap();
+
+    if list.len() < 2 {
+        return Ok(());
+    }
+
+    let op = "==";
+    let mut keep = Vec::with_capacity(list.len());
+    keep.push(true);
+
+    // Call the `==` operator to compare each value with the one before it
+    for pair in list.windows(2) {
+        let args = &mut [&mut pair[0].clone(), &mut pair[1].clone()];
+
+        // Qualifiers (none) + function name + number of arguments + argument `TypeId`'s.
+        let hash = calc_fn_hash(empty(), op, args.len(), args.iter().map(|a| a.type_id()));
+
+        let (result, _) = context.engine.call_native_fn(
+            context.state,
+            context.lib,
+            op,
+            hash,
+            args,
+            false,
+            false,
+            Some(false),
+            context.level,
+        )?;
+
+        keep.push(!result.as_bool().unwrap_or(false));
+    }
+
+    let mut keep = keep.into_iter();
+    list.retain(|_| keep.next().unwrap());
+
+    Ok(())
+}
+
+fn sort(
+    context: &mut NativeCallContext,
+    args: &mut [&mut Dynamic],
+) -> Result<(), Box<EvalAltResult>> {
+    let mut list = args[0].write_lock::<Array>().unwrap();
+    let mut error = None;
+
+    // Call the `<` operator to compare items, stopping at the first error
+    list.sort_by(|x, y| {
+        if error.is_some() {
+            return Ordering::Equal;
+        }
+
+        context
+            .engine
+            .compare_values(context.state, context.lib, x, y, context.level)
+            .unwrap_or_else(|err| {
+                error = Some(err);
+                Ordering::Equal
+            })
+    });
+
+    error.map_or(Ok(()), Err)
+}
+
+/// Flatten one level of nested arrays.
+fn flatten(
+    context: &mut NativeCallContext,
+    args: &mut [&mut Dynamic],
+) -> Result<Array, Box<EvalAltResult>> {
+    let list = args[0].read_lock::<Array>().unwrap();
+    let mut result = Array::with_capacity(list.len());
+
+    for (index, item) in list.iter().enumerate() {
+        match item.read_lock::<Array>() {
+            Some(inner) => result.extend(inner.iter().cloned()),
+            None => {
+                return EvalAltResult::ErrorRuntime(
+                    format!(
+                        "Only arrays can be flattened, but element {} is {}",
+                        index,
+                        context.engine.map_type_name(item.type_name())
+                    ),
+                    Position::none(),
+                )
+                .into()
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+fn push(
+    context: &mut NativeCallContext,
+    args: &mut [&mut Dynamic],
+) -> Result<(), Box<EvalAltResult>> {
+    context.engine().check_elem_type(args[0], args[1])?;
+
+    let item = mem::take(args[1]).flatten();
+    args[0].write_lock::<Array>().unwrap().push(item);
+    Ok(())
+}
+
+fn insert(
+    context: &mut NativeCallContext,
+    args: &mut [&mut Dynamic],
+) -> Result<(), Box<EvalAltResult>> {
+    context.engine().check_elem_type(args[0], args[2])?;
+
+    let position = args[1].as_int().unwrap();
+    let item = mem::take(args[2]).flatten();
+    let mut list = args[0].write_lock::<Array>().unwrap();
+
+    if position <= 0 {
+        list.insert(0, item);
+    } else if (position as usize) >= list.len() {
+        list.push(item);
+    } else {
+        list.insert(position as usize, item);
+    }
+    Ok(())
+}
+
+fn append(
+    context: &mut NativeCallContext,
+    args: &mut [&mut Dynamic],
+) -> Result<(), Box<EvalAltResult>> {
+    let items = mem::take(args[1]).cast::<Array>();
+
+    // Check all the items first so that the array is unchanged on error
+    for item in items.iter() {
+        context.engine().check_elem_type(args[0], item)?;
+    }
+
+    args[0].write_lock::<Array>().unwrap().extend(items);
+    Ok(())
+}
+
+/// Get the name of the type that the elements of an array must be of, or `()` if there is none.
+fn elem_type(
+    _context: &mut NativeCallContext,
+    args: &mut [&mut Dynamic],
+) -> Result<Dynamic, Box<EvalAltResult>> {
+    Ok(args[0].elem_type().map(Into::into).unwrap_or_default())
 }
 
 fn pad<T: Variant + Clone>(
-    _engine: &Engine,
-    _: &Module,
+    _context: &mut NativeCallContext,
     args: &mut [&mut Dynamic],
 ) -> Result<(), Box<EvalAltResult>> {
     let len = *args[1].read_lock::<INT>().unwrap();
 
     // Check if array will be over max size limit
     #[cfg(not(feature = "unchecked"))]
-    if _engine.limits.max_array_size > 0
+    if _context.engine().limits.max_array_size > 0
         && len > 0
-        && (len as usize) > _engine.limits.max_array_size
+        && (len as usize) > _context.engine().limits.max_array_size
     {
         return EvalAltResult::ErrorDataTooLarge(
             "Size of array".to_string(),
-            _engine.limits.max_array_size,
+            _context.engine().limits.max_array_size,
             len as usize,
             Position::none(),
         )
@@ -162,6 +335,8 @@ fn pad<T: Variant + Clone>(
     }
 
     if len > 0 {
+        _context.engine().check_elem_type(args[0], args[2])?;
+
         let item = args[2].clone();
         let mut list = args[0].write_lock::<Array>().unwrap();
 
@@ -171,20 +346,3 @@ fn pad<T: Variant + Clone>(
     }
     Ok(())
 }
-
-gen_array_functions!(basic => INT, bool, char, ImmutableString, FnPtr, Array, Unit);
-
-#[cfg(not(feature = "only_i32"))]
-#[cfg(not(feature = "only_i64"))]
-gen_array_functions!(numbers => i8, u8, i16, u16, i32, i64, u32, u64);
-
-#[cfg(not(feature = "only_i32"))]
-#[cfg(not(feature = "only_i64"))]
-#[cfg(not(target_arch = "wasm32"))]
-gen_array_functions!(num_128 => i128, u128);
-
-#[cfg(not(feature = "no_float"))]
-gen_array_functions!(float => f32, f64);
-
-#[cfg(not(feature = "no_object"))]
-gen_array_functions!(map => Map);
@@ -0,0 +1,38 @@
+use crate::def_package;
+use crate::parser::INT;
+use crate::plugin::*;
+use crate::result::CaughtError;
+
+use crate::stdlib::string::ToString;
+
+def_package!(crate:BasicErrorPackage:"Basic functions for errors caught in scripts.", lib, {
+    combine_with_exported_module!(lib, "CaughtError", caught_error_functions);
+});
+
+#[export_module]
+mod caught_error_functions {
+    #[rhai_fn(name = "message", get = "message")]
+    #[inline(always)]
+    pub fn message(err: &mut CaughtError) -> ImmutableString {
+        err.message().into()
+    }
+    #[rhai_fn(name = "line", get = "line")]
+    pub fn line(err: &mut CaughtError) -> Dynamic {
+        err.position()
+            .line()
+            .map(|n| (n as INT).into())
+            .unwrap_or_default()
+    }
+    #[rhai_fn(name = "position", get = "position")]
+    pub fn position(err: &mut CaughtError) -> Dynamic {
+        err.position()
+            .position()
+            .map(|n| (n as INT).into())
+            .unwrap_or_default()
+    }
+    #[rhai_fn(name = "to_string", name = "print", name = "debug")]
+    #[inline(always)]
+    pub fn to_string(err: &mut CaughtError) -> ImmutableString {
+        err.to_string().into()
+    }
+}
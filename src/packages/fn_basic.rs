@@ -1,9 +1,11 @@
 use crate::def_package;
-use crate::fn_native::FnPtr;
+use crate::fn_native::{FnPtr, NativeCallContext};
 use crate::plugin::*;
 
 def_package!(crate:BasicFnPackage:"Basic Fn functions.", lib, {
     combine_with_exported_module!(lib, "FnPtr", fn_ptr_functions);
+
+    lib.set_raw_fn("ops_remaining", &[], ops_remaining);
 });
 
 #[export_module]
@@ -14,3 +16,22 @@ mod fn_ptr_functions {
         f.get_fn_name().clone()
     }
 }
+
+/// Return the number of operations left before the maximum number of operations is exceeded,
+/// or -1 if there is no limit.
+fn ops_remaining(
+    _context: &mut NativeCallContext,
+    _args: &mut [&mut Dynamic],
+) -> Result<INT, Box<EvalAltResult>> {
+    #[cfg(not(feature = "unchecked"))]
+    match _context.engine.limits.max_operations {
+        0 => (),
+        max => {
+            return Ok(max
+                .saturating_sub(_context.state.operations)
+                .min(INT::MAX as u64) as INT)
+        }
+    }
+
+    Ok(-1)
+}
@@ -1,5 +1,6 @@
 use crate::any::{Dynamic, Variant};
 use crate::def_package;
+use crate::fn_native::ExternalIterator;
 use crate::module::{FuncReturn, Module};
 use crate::parser::INT;
 
@@ -10,7 +11,7 @@ use crate::stdlib::{
 };
 
 // Register range function
-fn reg_range<T: Variant + Clone>(lib: &mut Module)
+fn reg_range<T: Variant + Clone + PartialOrd>(lib: &mut Module)
 where
     Range<T>: Iterator<Item = T>,
 {
@@ -18,6 +19,9 @@ where
         Box::new(source.cast::<Range<T>>().map(|x| x.into_dynamic()))
             as Box<dyn Iterator<Item = Dynamic>>
     });
+    lib.set_fn_2_mut("contains", |range: &mut Range<T>, x: T| {
+        Ok(range.contains(&x))
+    });
 }
 
 fn get_range<T: Variant + Clone>(from: T, to: T) -> FuncReturn<Range<T>> {
@@ -26,7 +30,7 @@ fn get_range<T: Variant + Clone>(from: T, to: T) -> FuncReturn<Range<T>> {
 
 // Register range function with step
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
-struct StepRange<T>(T, T, T)
+pub(crate) struct StepRange<T>(T, T, T)
 where
     for<'a> &'a T: Add<&'a T, Output = T>,
     T: Variant + Clone + PartialOrd;
@@ -69,9 +73,10 @@ where
     Ok(StepRange::<T>(from, to, step))
 }
 
-def_package!(crate:BasicIteratorPackage:"Basic range iterators.", lib, {
+def_package!(crate:BasicIteratorPackage:"Basic range iterators and external iterators.", lib, {
     reg_range::<INT>(lib);
     lib.set_fn_2("range", get_range::<INT>);
+    lib.set_fn_2("..", get_range::<INT>);
 
     if cfg!(not(feature = "only_i32")) && cfg!(not(feature = "only_i64")) {
         macro_rules! reg_range {
@@ -79,6 +84,7 @@ def_package!(crate:BasicIteratorPackage:"Basic range iterators.", lib, {
                 $(
                     reg_range::<$y>($lib);
                     $lib.set_fn_2($x, get_range::<$y>);
+                    $lib.set_fn_2("..", get_range::<$y>);
                 )*
             )
         }
@@ -109,4 +115,11 @@ def_package!(crate:BasicIteratorPackage:"Basic range iterators.", lib, {
             reg_step!(lib, "range", i128, u128);
         }
     }
+
+    lib.set_iter(TypeId::of::<ExternalIterator>(), |source| {
+        Box::new(source.cast::<ExternalIterator>()) as Box<dyn Iterator<Item = Dynamic>>
+    });
+    lib.set_fn_1_mut("next", |iter: &mut ExternalIterator| {
+        Ok(iter.next().unwrap_or_default())
+    });
 });
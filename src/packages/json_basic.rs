@@ -0,0 +1,119 @@
+#![cfg(feature = "json")]
+#![allow(non_snake_case)]
+
+use crate::any::Dynamic;
+use crate::def_package;
+use crate::parser::{ImmutableString, INT};
+use crate::plugin::*;
+use crate::result::EvalAltResult;
+use crate::serde::de::from_dynamic;
+use crate::token::Position;
+
+#[cfg(not(feature = "no_float"))]
+use crate::parser::FLOAT;
+
+#[cfg(not(feature = "no_index"))]
+use crate::engine::Array;
+
+#[cfg(not(feature = "no_object"))]
+use crate::engine::Map;
+
+use crate::stdlib::{boxed::Box, format, string::ToString};
+
+use serde_json::Value;
+
+def_package!(crate:BasicJsonPackage:"Basic JSON utilities.", lib, {
+    set_exported_fn!(lib, "parse_json", parse_json);
+    set_exported_fn!(lib, "to_json", to_json);
+});
+
+#[export_fn(return_raw)]
+fn parse_json(json: &str) -> Result<Dynamic, Box<EvalAltResult>> {
+    let value: Value = serde_json::from_str(json).map_err(|err| {
+        Box::new(EvalAltResult::ErrorRuntime(
+            format!(
+                "Invalid JSON at byte offset {}: {}",
+                byte_offset(json, err.line(), err.column()),
+                err
+            ),
+            Position::none(),
+        ))
+    })?;
+
+    json_to_dynamic(value)
+}
+
+/// Serialize a value into JSON text.
+///
+/// Values that have no JSON representation (e.g. custom types), even when nested inside
+/// arrays or object maps, raise an error naming the type.
+#[export_fn(return_raw)]
+fn to_json(value: Dynamic) -> Result<Dynamic, Box<EvalAltResult>> {
+    let value: Value = from_dynamic(&value.flatten()).map_err(|err| match *err {
+        EvalAltResult::ErrorMismatchOutputType(_, typ, pos) => Box::new(
+            EvalAltResult::ErrorRuntime(format!("Cannot serialize type '{}' into JSON", typ), pos),
+        ),
+        _ => err,
+    })?;
+
+    Ok(value.to_string().into())
+}
+
+/// Convert a line/column position (both 1-based, column in bytes) into a 0-based byte offset.
+fn byte_offset(text: &str, line: usize, column: usize) -> usize {
+    let line_start: usize = text
+        .split('\n')
+        .take(line.saturating_sub(1))
+        .map(|s| s.len() + 1)
+        .sum();
+
+    (line_start + column.saturating_sub(1)).min(text.len())
+}
+
+fn json_to_dynamic(value: Value) -> Result<Dynamic, Box<EvalAltResult>> {
+    match value {
+        Value::Null => Ok(().into()),
+        Value::Bool(b) => Ok(b.into()),
+        Value::String(s) => Ok(s.into()),
+        Value::Number(n) => {
+            // Integers that do not fit into INT fall back to floating-point
+            if let Some(x) = n.as_i64() {
+                #[cfg(not(feature = "only_i32"))]
+                return Ok((x as INT).into());
+
+                #[cfg(feature = "only_i32")]
+                if x >= INT::MIN as i64 && x <= INT::MAX as i64 {
+                    return Ok((x as INT).into());
+                }
+            }
+
+            #[cfg(not(feature = "no_float"))]
+            return Ok((n.as_f64().unwrap() as FLOAT).into());
+
+            #[cfg(feature = "no_float")]
+            return EvalAltResult::ErrorArithmetic(
+                format!("Floating-point number not supported: {}", n),
+                Position::none(),
+            )
+            .into();
+        }
+        #[cfg(not(feature = "no_index"))]
+        Value::Array(a) => Ok(a
+            .into_iter()
+            .map(json_to_dynamic)
+            .collect::<Result<Array, _>>()?
+            .into()),
+        #[cfg(not(feature = "no_object"))]
+        Value::Object(m) => Ok(m
+            .into_iter()
+            .map(|(k, v)| json_to_dynamic(v).map(|v| (k.into(), v)))
+            .collect::<Result<Map, _>>()?
+            .into()),
+        #[allow(unreachable_patterns)]
+        v => EvalAltResult::ErrorRuntime(
+            format!("JSON value not supported: {}", v),
+            Position::none(),
+        )
+        .into(),
+    }
+}
@@ -1,6 +1,11 @@
 use crate::def_package;
 use crate::plugin::*;
 
+#[cfg(not(feature = "no_float"))]
+use crate::parser::{FLOAT, INT};
+
+use crate::stdlib::cmp::Ordering;
+
 macro_rules! gen_cmp_functions {
     ($root:ident => $($arg_type:ident),+) => {
         mod $root { $(pub mod $arg_type {
@@ -60,9 +65,16 @@ def_package!(crate:LogicPackage:"Logical operators.", lib, {
     }
 
     #[cfg(not(feature = "no_float"))]
-    reg_functions!(lib += float; f32);
+    {
+        reg_functions!(lib += float; f32);
+        combine_with_exported_module!(lib, "int_float", int_float_functions);
+    }
 
     set_exported_fn!(lib, "!", not);
+
+    let any_args = &[TypeId::of::<Dynamic>(), TypeId::of::<Dynamic>()];
+    lib.set_raw_fn("min", any_args, min);
+    lib.set_raw_fn("max", any_args, max);
 });
 
 // Logic operators
@@ -72,6 +84,40 @@ fn not(x: bool) -> bool {
     !x
 }
 
+/// Return the smaller of two values, comparing them with the `<` operator.
+/// Return the first value if both are equal.
+fn min(
+    context: &mut NativeCallContext,
+    args: &mut [&mut Dynamic],
+) -> Result<Dynamic, Box<EvalAltResult>> {
+    let (x, y) = (&*args[0], &*args[1]);
+
+    match context
+        .engine
+        .compare_values(context.state, context.lib, x, y, context.level)?
+    {
+        Ordering::Greater => Ok(y.clone()),
+        _ => Ok(x.clone()),
+    }
+}
+
+/// Return the larger of two values, comparing them with the `<` operator.
+/// Return the first value if both are equal.
+fn max(
+    context: &mut NativeCallContext,
+    args: &mut [&mut Dynamic],
+) -> Result<Dynamic, Box<EvalAltResult>> {
+    let (x, y) = (&*args[0], &*args[1]);
+
+    match context
+        .engine
+        .compare_values(context.state, context.lib, x, y, context.level)?
+    {
+        Ordering::Less => Ok(y.clone()),
+        _ => Ok(x.clone()),
+    }
+}
+
 #[cfg(not(feature = "only_i32"))]
 #[cfg(not(feature = "only_i64"))]
 gen_cmp_functions!(numbers => i8, u8, i16, u16, i32, u32, u64);
@@ -83,3 +129,112 @@ gen_cmp_functions!(num_128 => i128, u128);
 
 #[cfg(not(feature = "no_float"))]
 gen_cmp_functions!(float => f32);
+
+/// Compare an integer with a floating-point number.
+///
+/// The comparison is exact: when the float holds an integral value, both sides are compared
+/// as integers, so large integers that cannot be represented exactly as `FLOAT` still compare
+/// correctly.  Returns `None` if the float is `NaN`.
+#[cfg(not(feature = "no_float"))]
+pub(crate) fn compare_int_float(x: INT, y: FLOAT) -> Option<Ordering> {
+    // INT::MIN is a power of two, so both bounds are exact
+    let min = INT::MIN as FLOAT;
+
+    if y.is_nan() {
+        None
+    } else if y >= -min {
+        Some(Ordering::Less)
+    } else if y < min {
+        Some(Ordering::Greater)
+    } else {
+        let whole = y.trunc();
+
+        Some(x.cmp(&(whole as INT)).then_with(|| {
+            if y > whole {
+                Ordering::Less
+            } else if y < whole {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            }
+        }))
+    }
+}
+
+/// Run a comparison operator between an integer and a floating-point number.
+///
+/// Returns `None` if the operator is not a comparison operator.
+#[cfg(not(feature = "no_float"))]
+pub(crate) fn compare_int_float_op(op: &str, x: INT, y: FLOAT) -> Option<bool> {
+    let result = compare_int_float(x, y);
+
+    Some(match op {
+        "==" => result == Some(Ordering::Equal),
+        "!=" => result != Some(Ordering::Equal),
+        ">" => result == Some(Ordering::Greater),
+        ">=" => result == Some(Ordering::Greater) || result == Some(Ordering::Equal),
+        "<" => result == Some(Ordering::Less),
+        "<=" => result == Some(Ordering::Less) || result == Some(Ordering::Equal),
+        _ => return None,
+    })
+}
+
+#[cfg(not(feature = "no_float"))]
+#[export_module]
+mod int_float_functions {
+    #[rhai_fn(name = "<")]
+    pub fn lt_if(x: INT, y: FLOAT) -> bool {
+        compare_int_float(x, y) == Some(Ordering::Less)
+    }
+    #[rhai_fn(name = "<=")]
+    pub fn lte_if(x: INT, y: FLOAT) -> bool {
+        match compare_int_float(x, y) {
+            Some(Ordering::Less) | Some(Ordering::Equal) => true,
+            _ => false,
+        }
+    }
+    #[rhai_fn(name = ">")]
+    pub fn gt_if(x: INT, y: FLOAT) -> bool {
+        compare_int_float(x, y) == Some(Ordering::Greater)
+    }
+    #[rhai_fn(name = ">=")]
+    pub fn gte_if(x: INT, y: FLOAT) -> bool {
+        match compare_int_float(x, y) {
+            Some(Ordering::Greater) | Some(Ordering::Equal) => true,
+            _ => false,
+        }
+    }
+    #[rhai_fn(name = "==")]
+    pub fn eq_if(x: INT, y: FLOAT) -> bool {
+        compare_int_float(x, y) == Some(Ordering::Equal)
+    }
+    #[rhai_fn(name = "!=")]
+    pub fn ne_if(x: INT, y: FLOAT) -> bool {
+        compare_int_float(x, y) != Some(Ordering::Equal)
+    }
+
+    #[rhai_fn(name = "<")]
+    pub fn lt_fi(x: FLOAT, y: INT) -> bool {
+        gt_if(y, x)
+    }
+    #[rhai_fn(name = "<=")]
+    pub fn lte_fi(x: FLOAT, y: INT) -> bool {
+        gte_if(y, x)
+    }
+    #[rhai_fn(name = ">")]
+    pub fn gt_fi(x: FLOAT, y: INT) -> bool {
+        lt_if(y, x)
+    }
+    #[rhai_fn(name = ">=")]
+    pub fn gte_fi(x: FLOAT, y: INT) -> bool {
+        lte_if(y, x)
+    }
+    #[rhai_fn(name = "==")]
+    pub fn eq_fi(x: FLOAT, y: INT) -> bool {
+        eq_if(y, x)
+    }
+    #[rhai_fn(name = "!=")]
+    pub fn ne_fi(x: FLOAT, y: INT) -> bool {
+        ne_if(y, x)
+    }
+}
@@ -14,6 +14,7 @@ def_package!(crate:BasicMapPackage:"Basic object map utilities.", lib, {
 
 #[export_module]
 mod map_functions {
+    #[rhai_fn(name = "has", name = "contains")]
     #[inline(always)]
     pub fn has(map: &mut Map, prop: ImmutableString) -> bool {
         map.contains_key(&prop)
@@ -28,7 +29,7 @@ mod map_functions {
     }
     #[inline(always)]
     pub fn remove(x: &mut Map, name: ImmutableString) -> Dynamic {
-        x.remove(&name).unwrap_or_else(|| ().into())
+        x.shift_remove(&name).unwrap_or_else(|| ().into())
     }
     #[rhai_fn(name = "mixin", name = "+=")]
     pub fn mixin(map1: &mut Map, map2: Map) {
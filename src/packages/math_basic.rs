@@ -166,7 +166,7 @@ mod float_functions {
     pub fn floor(x: FLOAT) -> FLOAT {
         x.floor()
     }
-    #[rhai_fn(name = "ceiling", get = "ceiling")]
+    #[rhai_fn(name = "ceiling", name = "ceil", get = "ceiling")]
     #[inline(always)]
     pub fn ceiling(x: FLOAT) -> FLOAT {
         x.ceil()
@@ -174,7 +174,29 @@ mod float_functions {
     #[rhai_fn(name = "round", get = "round")]
     #[inline(always)]
     pub fn round(x: FLOAT) -> FLOAT {
-        x.ceil()
+        x.round()
+    }
+    #[inline]
+    pub fn round_to(x: FLOAT, digits: INT) -> FLOAT {
+        if digits >= 0 {
+            let scale = (10.0 as FLOAT).powi(digits.min(i32::MAX as INT) as i32);
+
+            if scale.is_finite() && (x * scale).is_finite() {
+                (x * scale).round() / scale
+            } else {
+                // Too many digits to make a difference
+                x
+            }
+        } else {
+            let scale = (10.0 as FLOAT).powi((-digits).min(i32::MAX as INT) as i32);
+
+            if scale.is_finite() {
+                (x / scale).round() * scale
+            } else {
+                // Rounded away all the significant digits
+                0.0 * x.signum()
+            }
+        }
     }
     #[rhai_fn(name = "int", get = "int")]
     #[inline(always)]
@@ -202,30 +224,24 @@ mod float_functions {
         x.is_infinite()
     }
     #[rhai_fn(name = "to_int", return_raw)]
-    #[inline]
+    #[inline(always)]
     pub fn f32_to_int(x: f32) -> Result<Dynamic, Box<EvalAltResult>> {
-        if cfg!(not(feature = "unchecked")) && x > (MAX_INT as f32) {
-            EvalAltResult::ErrorArithmetic(
-                format!("Integer overflow: to_int({})", x),
-                Position::none(),
-            )
-            .into()
-        } else {
-            Ok((x.trunc() as INT).into())
-        }
+        float_to_int(x as f64, "to_int", cfg!(not(feature = "unchecked")))
     }
     #[rhai_fn(name = "to_int", return_raw)]
-    #[inline]
+    #[inline(always)]
     pub fn f64_to_int(x: f64) -> Result<Dynamic, Box<EvalAltResult>> {
-        if cfg!(not(feature = "unchecked")) && x > (MAX_INT as f64) {
-            EvalAltResult::ErrorArithmetic(
-                format!("Integer overflow: to_int({})", x),
-                Position::none(),
-            )
-            .into()
-        } else {
-            Ok((x.trunc() as INT).into())
-        }
+        float_to_int(x, "to_int", cfg!(not(feature = "unchecked")))
+    }
+    #[rhai_fn(name = "to_int_checked", return_raw)]
+    #[inline(always)]
+    pub fn f32_to_int_checked(x: f32) -> Result<Dynamic, Box<EvalAltResult>> {
+        float_to_int(x as f64, "to_int_checked", true)
+    }
+    #[rhai_fn(name = "to_int_checked", return_raw)]
+    #[inline(always)]
+    pub fn f64_to_int_checked(x: f64) -> Result<Dynamic, Box<EvalAltResult>> {
+        float_to_int(x, "to_int_checked", true)
     }
 
     #[rhai_fn(return_raw)]
@@ -244,6 +260,35 @@ mod float_functions {
     }
 }
 
+/// Truncate a floating-point number into an `INT`.
+///
+/// `NaN` and infinity are always errors.  Finite numbers outside the range of `INT`
+/// are errors only if `check_range` is set, otherwise they saturate.
+#[cfg(not(feature = "no_float"))]
+fn float_to_int(x: f64, fn_name: &str, check_range: bool) -> Result<Dynamic, Box<EvalAltResult>> {
+    // Both bounds are powers of two and so are exact as floats
+    const MIN: f64 = INT::MIN as f64;
+    const MAX: f64 = -(INT::MIN as f64);
+
+    let x = x.trunc();
+
+    if !x.is_finite() {
+        EvalAltResult::ErrorArithmetic(
+            format!("Cannot convert {} to an integer: {}({})", x, fn_name, x),
+            Position::none(),
+        )
+        .into()
+    } else if check_range && !(MIN..MAX).contains(&x) {
+        EvalAltResult::ErrorArithmetic(
+            format!("Integer overflow: {}({})", fn_name, x),
+            Position::none(),
+        )
+        .into()
+    } else {
+        Ok((x as INT).into())
+    }
+}
+
 #[cfg(not(feature = "no_float"))]
 gen_conversion_functions!(basic_to_float => to_float (INT) -> FLOAT);
 
@@ -271,22 +316,34 @@ gen_conversion_functions!(num_128_to_int => to_int (i128, u128) -> INT);
 
 #[export_fn(return_raw)]
 fn parse_int_radix(s: &str, radix: INT) -> Result<Dynamic, Box<EvalAltResult>> {
-    if radix < 2 || radix > 36 {
+    if !(2..=36).contains(&radix) {
         return EvalAltResult::ErrorArithmetic(
-            format!("Invalid radix: '{}'", radix),
+            format!("Invalid radix: '{}' (must be between 2 and 36)", radix),
             Position::none(),
         )
         .into();
     }
 
-    INT::from_str_radix(s.trim(), radix as u32)
+    // Leading and trailing whitespace is ignored
+    let text = s.trim();
+
+    INT::from_str_radix(text, radix as u32)
         .map(Into::<Dynamic>::into)
         .map_err(|err| {
-            EvalAltResult::ErrorArithmetic(
-                format!("Error parsing integer number '{}': {}", s, err),
-                Position::none(),
-            )
-            .into()
+            // A well-formed number can only fail to parse by being out of range
+            let digits = if text.starts_with('+') || text.starts_with('-') {
+                &text[1..]
+            } else {
+                text
+            };
+
+            let msg = if !digits.is_empty() && digits.chars().all(|c| c.is_digit(radix as u32)) {
+                format!("Integer overflow: parse_int('{}')", s)
+            } else {
+                format!("Error parsing integer number '{}': {}", s, err)
+            };
+
+            EvalAltResult::ErrorArithmetic(msg, Position::none()).into()
         })
 }
 
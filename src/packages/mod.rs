@@ -8,32 +8,43 @@ use crate::stdlib::any::TypeId;
 
 pub(crate) mod arithmetic;
 mod array_basic;
+mod error_basic;
 mod eval;
 mod fn_basic;
-mod iter_basic;
-mod logic;
+pub(crate) mod iter_basic;
+mod json_basic;
+pub(crate) mod logic;
 mod map_basic;
 mod math_basic;
 mod pkg_core;
 mod pkg_std;
+mod rand_basic;
 mod string_basic;
 mod string_more;
+mod testing;
 mod time_basic;
 
 pub use arithmetic::ArithmeticPackage;
 #[cfg(not(feature = "no_index"))]
 pub use array_basic::BasicArrayPackage;
+pub use error_basic::BasicErrorPackage;
 pub use eval::EvalPackage;
 pub use fn_basic::BasicFnPackage;
 pub use iter_basic::BasicIteratorPackage;
+#[cfg(feature = "json")]
+pub use json_basic::BasicJsonPackage;
 pub use logic::LogicPackage;
 #[cfg(not(feature = "no_object"))]
 pub use map_basic::BasicMapPackage;
 pub use math_basic::BasicMathPackage;
 pub use pkg_core::CorePackage;
 pub use pkg_std::StandardPackage;
+#[cfg(feature = "rand")]
+pub use rand_basic::RandomPackage;
 pub use string_basic::BasicStringPackage;
 pub use string_more::MoreStringPackage;
+#[cfg(feature = "testing")]
+pub use testing::TestingPackage;
 #[cfg(not(feature = "no_std"))]
 pub use time_basic::BasicTimePackage;
 
@@ -69,10 +80,20 @@ impl PackagesCollection {
     pub fn get_fn(&self, hash: u64, public_only: bool) -> Option<&CallableFunction> {
         self.0
             .iter()
-            .map(|p| p.get_fn(hash, public_only))
+            .map(|p| p.get_fn_by_hash(hash, public_only))
             .find(|f| f.is_some())
             .flatten()
     }
+    /// Does the `PackagesCollection` contain functions taking only `Dynamic` parameters?
+    pub fn has_dynamic_fns(&self) -> bool {
+        self.0.iter().any(|p| p.has_dynamic_fns())
+    }
+    /// Get a function taking only `Dynamic` parameters via its hash key.
+    pub fn get_dynamic_fn(&self, hash: u64, public_only: bool) -> Option<&CallableFunction> {
+        self.0
+            .iter()
+            .find_map(|p| p.get_dynamic_fn(hash, public_only))
+    }
     /// Does the specified TypeId iterator exist in the `PackagesCollection`?
     #[allow(dead_code)]
     pub fn contains_iter(&self, id: TypeId) -> bool {
@@ -86,6 +107,10 @@ impl PackagesCollection {
             .find(|f| f.is_some())
             .flatten()
     }
+    /// Get an iterator over the `Module`s in the `PackagesCollection`.
+    pub fn iter(&self) -> impl Iterator<Item = &Module> {
+        self.0.iter().map(|p| &**p)
+    }
 }
 
 /// Macro that makes it easy to define a _package_ (which is basically a shared module)
@@ -1,4 +1,5 @@
 use super::arithmetic::ArithmeticPackage;
+use super::error_basic::BasicErrorPackage;
 use super::fn_basic::BasicFnPackage;
 use super::iter_basic::BasicIteratorPackage;
 use super::logic::LogicPackage;
@@ -12,4 +13,5 @@ def_package!(crate:CorePackage:"_Core_ package containing basic facilities.", li
     BasicStringPackage::init(lib);
     BasicIteratorPackage::init(lib);
     BasicFnPackage::init(lib);
+    BasicErrorPackage::init(lib);
 });
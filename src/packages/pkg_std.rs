@@ -1,10 +1,16 @@
 #[cfg(not(feature = "no_index"))]
 use super::array_basic::BasicArrayPackage;
+#[cfg(feature = "json")]
+use super::json_basic::BasicJsonPackage;
 #[cfg(not(feature = "no_object"))]
 use super::map_basic::BasicMapPackage;
 use super::math_basic::BasicMathPackage;
 use super::pkg_core::CorePackage;
+#[cfg(feature = "rand")]
+use super::rand_basic::RandomPackage;
 use super::string_more::MoreStringPackage;
+#[cfg(feature = "testing")]
+use super::testing::TestingPackage;
 #[cfg(not(feature = "no_std"))]
 use super::time_basic::BasicTimePackage;
 
@@ -20,4 +26,10 @@ def_package!(crate:StandardPackage:"_Standard_ package containing all built-in f
     #[cfg(not(feature = "no_std"))]
     BasicTimePackage::init(lib);
     MoreStringPackage::init(lib);
+    #[cfg(feature = "json")]
+    BasicJsonPackage::init(lib);
+    #[cfg(feature = "testing")]
+    TestingPackage::init(lib);
+    #[cfg(feature = "rand")]
+    RandomPackage::init(lib);
 });
@@ -0,0 +1,141 @@
+#![cfg(feature = "rand")]
+
+use crate::any::Dynamic;
+use crate::def_package;
+use crate::engine::State;
+use crate::fn_native::NativeCallContext;
+use crate::parser::INT;
+use crate::result::EvalAltResult;
+use crate::token::Position;
+
+#[cfg(not(feature = "no_index"))]
+use crate::engine::Array;
+
+#[cfg(not(feature = "no_float"))]
+use crate::parser::FLOAT;
+
+use crate::stdlib::{any::TypeId, boxed::Box, format};
+
+#[cfg(not(feature = "no_std"))]
+use crate::stdlib::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+};
+
+def_package!(crate:RandomPackage:"Seedable random number generation.", lib, {
+    lib.set_raw_fn("seed_rng", &[TypeId::of::<INT>()], seed_rng);
+    lib.set_raw_fn("rand_int", &[TypeId::of::<INT>(), TypeId::of::<INT>()], rand_int);
+
+    #[cfg(not(feature = "no_float"))]
+    lib.set_raw_fn("rand", &[], rand);
+
+    #[cfg(not(feature = "no_index"))]
+    lib.set_raw_fn("shuffle", &[TypeId::of::<Array>()], shuffle);
+});
+
+/// Get the next number in the random sequence.
+///
+/// The generator is _SplitMix64_.  The sequence produced from a given seed is stable within
+/// the same minor version of Rhai.
+fn next_u64(state: &mut State) -> u64 {
+    let seed = state.rng.unwrap_or_else(initial_seed);
+    let seed = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    state.rng = Some(seed);
+
+    let mut z = seed;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Seed for a generator that is never explicitly seeded.
+#[cfg(not(feature = "no_std"))]
+fn initial_seed() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+/// Seed for a generator that is never explicitly seeded.
+///
+/// There is no source of entropy under `no_std`, so the sequence is always the same.
+#[cfg(feature = "no_std")]
+fn initial_seed() -> u64 {
+    0
+}
+
+/// Get a random number between zero and `max` (inclusive), without bias.
+fn next_up_to(state: &mut State, max: u64) -> u64 {
+    if max == u64::MAX {
+        return next_u64(state);
+    }
+
+    let range = max + 1;
+    // Reject values in the incomplete last bucket
+    let zone = u64::MAX - (u64::MAX - range + 1) % range;
+
+    loop {
+        let value = next_u64(state);
+
+        if value <= zone {
+            return value % range;
+        }
+    }
+}
+
+fn seed_rng(
+    context: &mut NativeCallContext,
+    args: &mut [&mut Dynamic],
+) -> Result<(), Box<EvalAltResult>> {
+    let seed = args[0].as_int().unwrap();
+    context.state.rng = Some(seed as i64 as u64);
+    Ok(())
+}
+
+fn rand_int(
+    context: &mut NativeCallContext,
+    args: &mut [&mut Dynamic],
+) -> Result<INT, Box<EvalAltResult>> {
+    let min = args[0].as_int().unwrap();
+    let max = args[1].as_int().unwrap();
+
+    if min > max {
+        return EvalAltResult::ErrorRuntime(
+            format!(
+                "Invalid range for random integer: minimum {} is larger than maximum {}",
+                min, max
+            ),
+            Position::none(),
+        )
+        .into();
+    }
+
+    let span = (max as i64).wrapping_sub(min as i64) as u64;
+    let offset = next_up_to(context.state, span);
+
+    Ok((min as i64).wrapping_add(offset as i64) as INT)
+}
+
+#[cfg(not(feature = "no_float"))]
+fn rand(
+    context: &mut NativeCallContext,
+    _args: &mut [&mut Dynamic],
+) -> Result<FLOAT, Box<EvalAltResult>> {
+    // Use the top 53 bits, which fit exactly into the mantissa of an f64
+    let value = (next_u64(context.state) >> 11) as f64 / (1u64 << 53) as f64;
+    Ok(value as FLOAT)
+}
+
+#[cfg(not(feature = "no_index"))]
+fn shuffle(
+    context: &mut NativeCallContext,
+    args: &mut [&mut Dynamic],
+) -> Result<(), Box<EvalAltResult>> {
+    let mut list = args[0].write_lock::<Array>().unwrap();
+
+    // Fisher-Yates shuffle
+    for i in (1..list.len()).rev() {
+        let j = next_up_to(context.state, i as u64) as usize;
+        list.swap(i, j);
+    }
+
+    Ok(())
+}
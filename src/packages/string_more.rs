@@ -1,20 +1,27 @@
 #![allow(non_snake_case)]
 
 use crate::any::Dynamic;
+use crate::calc_fn_hash;
 use crate::def_package;
-use crate::engine::Engine;
-use crate::fn_native::FnPtr;
+use crate::engine::FN_TO_STRING;
+use crate::fn_native::{FnPtr, NativeCallContext};
 use crate::parser::{ImmutableString, INT};
 use crate::plugin::*;
+use crate::result::EvalAltResult;
+use crate::token::Position;
 use crate::utils::StaticVec;
 
-#[cfg(not(feature = "unchecked"))]
-use crate::{result::EvalAltResult, token::Position};
+#[cfg(not(feature = "no_object"))]
+use crate::engine::Map;
 
 use crate::stdlib::{
-    any::TypeId, boxed::Box, format, mem, string::String, string::ToString, vec::Vec,
+    any::TypeId, boxed::Box, format, iter::empty, mem, string::String, string::ToString, vec,
+    vec::Vec,
 };
 
+/// Maximum number of arguments, not counting the template, that can be passed to `format`.
+const MAX_FORMAT_ARGS: usize = 16;
+
 macro_rules! gen_concat_functions {
     ($root:ident => $($arg_type:ident),+ ) => {
         pub mod $root { $( pub mod $arg_type {
@@ -64,15 +71,15 @@ def_package!(crate:MoreStringPackage:"Additional string utilities, including str
     lib.set_raw_fn(
         "pad",
         &[TypeId::of::<ImmutableString>(), TypeId::of::<INT>(), TypeId::of::<char>()],
-        |_engine: &Engine, _: &Module, args: &mut [&mut Dynamic]| {
+        |_context: &mut NativeCallContext, args: &mut [&mut Dynamic]| {
             let len = *args[1].read_lock::<INT>().unwrap();
 
             // Check if string will be over max size limit
             #[cfg(not(feature = "unchecked"))]
-            if _engine.limits.max_string_size > 0 && len > 0 && (len as usize) > _engine.limits.max_string_size {
+            if _context.engine().limits.max_string_size > 0 && len > 0 && (len as usize) > _context.engine().limits.max_string_size {
                 return EvalAltResult::ErrorDataTooLarge(
                     "Length of string".to_string(),
-                    _engine.limits.max_string_size,
+                    _context.engine().limits.max_string_size,
                     len as usize,
                     Position::none(),
                 ).into();
@@ -92,10 +99,10 @@ def_package!(crate:MoreStringPackage:"Additional string utilities, including str
                     }
 
                     #[cfg(not(feature = "unchecked"))]
-                    if _engine.limits.max_string_size > 0 && s.len() > _engine.limits.max_string_size {
+                    if _context.engine().limits.max_string_size > 0 && s.len() > _context.engine().limits.max_string_size {
                         return EvalAltResult::ErrorDataTooLarge(
                             "Length of string".to_string(),
-                            _engine.limits.max_string_size,
+                            _context.engine().limits.max_string_size,
                             s.len(),
                             Position::none(),
                         ).into();
@@ -107,6 +114,13 @@ def_package!(crate:MoreStringPackage:"Additional string utilities, including str
         },
     );
 
+    // Register format for a template followed by any number of arguments of any type
+    lib.set_raw_fn("format", &[TypeId::of::<ImmutableString>()], format);
+
+    for num_args in 1..=MAX_FORMAT_ARGS {
+        lib.set_raw_fn("format", &vec![TypeId::of::<Dynamic>(); num_args + 1], format);
+    }
+
     // Register string iterator
     lib.set_iter(
         TypeId::of::<ImmutableString>(),
@@ -356,3 +370,144 @@ mod string_functions {
         }
     }
 }
+
+/// Build a string from a template, replacing each placeholder by an argument rendered via `to_string`.
+///
+/// * `{}` is replaced by the next argument.
+/// * `{0}`, `{1}` etc. are replaced by the argument at that index.
+/// * `{name}` is replaced by the property `name` of an object map passed as the last argument.
+/// * `{{` and `}}` are replaced by literal braces.
+fn format(
+    context: &mut NativeCallContext,
+    args: &mut [&mut Dynamic],
+) -> Result<ImmutableString, Box<EvalAltResult>> {
+    let (template, args) = args.split_first_mut().unwrap();
+
+    let template = match template.read_lock::<ImmutableString>() {
+        Some(s) => s.clone(),
+        None => {
+            return Err(make_err(format!(
+                "The template for format must be a string, not '{}'",
+                context.engine.map_type_name(template.type_name())
+            )))
+        }
+    };
+
+    let mut result = String::with_capacity(template.len());
+    let mut chars = template.chars();
+    let mut next_index = 0;
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' if chars.as_str().starts_with('{') => {
+                chars.next();
+                result.push('{');
+            }
+            '}' if chars.as_str().starts_with('}') => {
+                chars.next();
+                result.push('}');
+            }
+            '}' => return Err(make_err("Unmatched '}' in template for format".into())),
+            '{' => {
+                let rest = chars.as_str();
+                let end = rest.find('}').ok_or_else(|| {
+                    make_err("Unterminated placeholder in template for format".into())
+                })?;
+                let placeholder = &rest[..end];
+                chars = rest[end + 1..].chars();
+
+                let index = if placeholder.is_empty() {
+                    next_index += 1;
+                    Some(next_index - 1)
+                } else {
+                    placeholder.parse::<usize>().ok()
+                };
+
+                let text = if let Some(index) = index {
+                    if index >= args.len() {
+                        return Err(make_err(format!(
+                            "Argument index {} is out of range for format: there are only {} argument(s)",
+                            index,
+                            args.len()
+                        )));
+                    }
+                    render(context, args[index])?
+                } else {
+                    let mut value = get_named_arg(args, placeholder)?;
+                    render(context, &mut value)?
+                };
+
+                result.push_str(&text);
+            }
+            ch => result.push(ch),
+        }
+    }
+
+    Ok(result.into())
+}
+
+/// Get the value of a named placeholder for `format` from the object map passed as the last argument.
+#[cfg(not(feature = "no_object"))]
+fn get_named_arg(args: &mut [&mut Dynamic], name: &str) -> Result<Dynamic, Box<EvalAltResult>> {
+    let map = args
+        .last()
+        .and_then(|arg| arg.read_lock::<Map>())
+        .ok_or_else(|| {
+            make_err(format!(
+                "Named placeholder '{{{}}}' for format requires an object map as the last argument",
+                name
+            ))
+        })?;
+
+    map.get(name).cloned().ok_or_else(|| {
+        make_err(format!(
+            "Property '{}' not found in the object map for format",
+            name
+        ))
+    })
+}
+
+/// Get the value of a named placeholder for `format`.
+///
+/// Named placeholders are not supported under `no_object`.
+#[cfg(feature = "no_object")]
+fn get_named_arg(_args: &mut [&mut Dynamic], name: &str) -> Result<Dynamic, Box<EvalAltResult>> {
+    Err(make_err(format!(
+        "Invalid placeholder '{{{}}}' in template for format",
+        name
+    )))
+}
+
+/// Render a value for `format` via the `to_string` function.
+///
+/// The value is passed by reference so that it is not consumed, as an argument can be used multiple times.
+fn render(
+    context: &mut NativeCallContext,
+    value: &mut Dynamic,
+) -> Result<String, Box<EvalAltResult>> {
+    let (result, _) = context.engine.exec_fn_call(
+        context.state,
+        context.lib,
+        FN_TO_STRING,
+        calc_fn_hash(empty(), FN_TO_STRING, 1, empty()),
+        &mut [value],
+        true,
+        false,
+        false,
+        None,
+        None,
+        context.level,
+    )?;
+
+    result.take_string().map_err(|typ| {
+        Box::new(EvalAltResult::ErrorMismatchOutputType(
+            "string".into(),
+            typ.into(),
+            Position::none(),
+        ))
+    })
+}
+
+fn make_err(msg: String) -> Box<EvalAltResult> {
+    EvalAltResult::ErrorRuntime(msg, Position::none()).into()
+}
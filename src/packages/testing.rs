@@ -0,0 +1,130 @@
+#![cfg(feature = "testing")]
+
+use crate::any::Dynamic;
+use crate::calc_fn_hash;
+use crate::def_package;
+use crate::engine::FN_TO_STRING;
+use crate::fn_call::run_builtin_binary_op;
+use crate::fn_native::NativeCallContext;
+use crate::parser::ImmutableString;
+use crate::result::EvalAltResult;
+use crate::token::Position;
+
+use crate::stdlib::{
+    any::TypeId,
+    boxed::Box,
+    format,
+    iter::empty,
+    string::{String, ToString},
+};
+
+def_package!(crate:TestingPackage:"Assertions for writing tests in scripts.", lib, {
+    lib.set_fn_1("assert", |condition: bool| assert(condition, ""));
+    lib.set_fn_2("assert", |condition: bool, message: ImmutableString| assert(condition, &message));
+
+    lib.set_raw_fn(
+        "assert_eq",
+        &[TypeId::of::<Dynamic>(), TypeId::of::<Dynamic>()],
+        assert_eq,
+    );
+});
+
+/// Raise a runtime error if the condition is `false`.
+fn assert(condition: bool, message: &str) -> Result<(), Box<EvalAltResult>> {
+    if condition {
+        Ok(())
+    } else if message.is_empty() {
+        EvalAltResult::ErrorRuntime("Assertion failed".into(), Position::none()).into()
+    } else {
+        EvalAltResult::ErrorRuntime(format!("Assertion failed: {}", message), Position::none())
+            .into()
+    }
+}
+
+/// Raise a runtime error, showing both values, if the two values are not equal
+/// according to the `==` operator.
+fn assert_eq(
+    context: &mut NativeCallContext,
+    args: &mut [&mut Dynamic],
+) -> Result<(), Box<EvalAltResult>> {
+    let op = "==";
+    let hash_fn = calc_fn_hash(empty(), op, 2, args.iter().map(|a| a.type_id()));
+    let hash_script = calc_fn_hash(empty(), op, 2, empty());
+
+    // Compare built-in types by reference, unless the operator is overridden
+    let builtin = if context
+        .engine
+        .has_override(context.lib, hash_fn, hash_script, false)
+    {
+        None
+    } else {
+        run_builtin_binary_op(op, args[0], args[1])?
+    };
+
+    let result = match builtin {
+        Some(result) => result,
+        None => {
+            // The second argument is consumed by the `==` operator, so pass a copy
+            // in order to keep the original for the error message
+            let mut right = args[1].clone();
+
+            // Treat the first argument as a reference so it is not consumed
+            context
+                .engine
+                .exec_fn_call(
+                    context.state,
+                    context.lib,
+                    op,
+                    hash_script,
+                    &mut [args[0], &mut right],
+                    true,
+                    false,
+                    false,
+                    None,
+                    Some(false),
+                    context.level,
+                )?
+                .0
+        }
+    };
+
+    if result.as_bool().unwrap_or(false) {
+        return Ok(());
+    }
+
+    let (left, right) = args.split_at_mut(1);
+
+    EvalAltResult::ErrorRuntime(
+        format!(
+            "Assertion failed: left == right (left: {}, right: {})",
+            render(context, left[0]),
+            render(context, right[0])
+        ),
+        Position::none(),
+    )
+    .into()
+}
+
+/// Render a value via the `to_string` function, falling back to its type name.
+fn render(context: &mut NativeCallContext, value: &mut Dynamic) -> String {
+    let type_name = context.engine.map_type_name(value.type_name()).to_string();
+
+    context
+        .engine
+        .exec_fn_call(
+            context.state,
+            context.lib,
+            FN_TO_STRING,
+            calc_fn_hash(empty(), FN_TO_STRING, 1, empty()),
+            &mut [value],
+            true,
+            false,
+            false,
+            None,
+            None,
+            context.level,
+        )
+        .ok()
+        .and_then(|(s, _)| s.take_string().ok())
+        .unwrap_or_else(|| format!("<{}>", type_name))
+}
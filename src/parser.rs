@@ -2,30 +2,42 @@
 
 use crate::any::{Dynamic, Union};
 use crate::calc_fn_hash;
-use crate::engine::{Engine, KEYWORD_THIS, MARKER_BLOCK, MARKER_EXPR, MARKER_IDENT};
+use crate::engine::{
+    Engine, State, KEYWORD_EVAL, KEYWORD_THIS, MARKER_BLOCK, MARKER_EXPR, MARKER_IDENT,
+};
 use crate::error::{LexError, ParseError, ParseErrorType};
 use crate::fn_native::{FnPtr, Shared};
 use crate::module::{Module, ModuleRef};
 use crate::optimize::{optimize_into_ast, OptimizationLevel};
 use crate::scope::{EntryType as ScopeEntryType, Scope};
 use crate::syntax::FnCustomSyntaxEval;
-use crate::token::{is_keyword_function, is_valid_identifier, Position, Token, TokenStream};
+use crate::token::{
+    is_keyword_function, is_valid_identifier, parse_number_literal, Position, Token, TokenStream,
+};
 use crate::utils::{StaticVec, StraightHasherBuilder};
 
 #[cfg(not(feature = "no_function"))]
 use crate::engine::{FN_ANONYMOUS, KEYWORD_FN_PTR_CURRY};
 
+#[cfg(not(feature = "no_index"))]
+use crate::engine::{KEYWORD_FN_PTR, KEYWORD_FN_PTR_CALL, KEYWORD_IS_SHARED};
+
 #[cfg(not(feature = "no_object"))]
-use crate::engine::{make_getter, make_setter};
+use crate::engine::{make_getter, make_setter, Map};
+
+#[cfg(not(feature = "no_module"))]
+use crate::module::FuncReturn;
 
 use crate::stdlib::{
+    any::TypeId,
     borrow::Cow,
     boxed::Box,
     char,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt, format,
     hash::{Hash, Hasher},
-    iter::empty,
+    iter::{empty, once},
+    mem,
     num::NonZeroUsize,
     ops::Add,
     string::{String, ToString},
@@ -37,9 +49,6 @@ use crate::stdlib::{
 #[cfg(not(feature = "no_function"))]
 use crate::stdlib::collections::hash_map::DefaultHasher;
 
-#[cfg(not(feature = "no_closure"))]
-use crate::stdlib::collections::HashSet;
-
 #[cfg(feature = "no_std")]
 #[cfg(not(feature = "no_function"))]
 use ahash::AHasher;
@@ -143,6 +152,45 @@ impl AST {
         Self(self.0.clone(), Default::default())
     }
 
+    /// Convert the `AST` into a `Module` containing all its script-defined functions.
+    ///
+    /// Top-level statements are run exactly once, with a fresh `Scope`, to initialize the module.
+    /// Exported variables become module variables, in the same manner as `Module::eval_ast_as_new`.
+    /// Any error raised by the statements is returned and no `Module` is created.
+    ///
+    /// Use `Module::get_fn` to get a function pointer to a function in the `Module`,
+    /// which can be called repeatedly with `Engine::call_fn_ptr`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// # #[cfg(not(feature = "no_function"))]
+    /// # {
+    /// use rhai::Engine;
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let ast = engine.compile(r"
+    ///     fn inc(x) { x + 1 }
+    ///     const answer = 42;
+    ///     export answer;
+    /// ")?;
+    ///
+    /// let module = ast.into_module(&engine)?;
+    /// assert_eq!(module.get_var_value::<i64>("answer").unwrap(), 42);
+    ///
+    /// let inc = module.get_fn("inc", 1).unwrap();
+    /// assert_eq!(engine.call_fn_ptr::<_, i64>(&module, &inc, (41_i64,))?, 42);
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "no_module"))]
+    pub fn into_module(self, engine: &Engine) -> FuncReturn<Module> {
+        Module::eval_ast_as_new(Default::default(), &self, engine)
+    }
+
     /// Merge two `AST` into one.  Both `AST`'s are untouched and a new, merged, version
     /// is returned.
     ///
@@ -299,9 +347,11 @@ impl AST {
         self.1.retain_functions(filter);
     }
 
-    /// Iterate through all functions
+    /// Iterate through all functions.
+    ///
+    /// The action receives the access mode, name, number of parameters and doc-comments of each function.
     #[cfg(not(feature = "no_function"))]
-    pub fn iter_functions(&self, action: impl FnMut(FnAccess, &str, usize)) {
+    pub fn iter_functions(&self, action: impl FnMut(FnAccess, &str, usize, &[String])) {
         self.1.iter_script_fn_info(action);
     }
 
@@ -315,6 +365,47 @@ impl AST {
     pub fn clear_statements(&mut self) {
         self.0 = vec![];
     }
+
+    /// Get the `Position`'s of all statements in the `AST` that can be reported by
+    /// `Engine::on_statement_executed`, including those in script-defined functions.
+    ///
+    /// Statements removed by script optimization do not appear.
+    /// Use this together with `Engine::on_statement_executed` to calculate coverage.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::Engine;
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let ast = engine.compile("let x = 42;\nif x > 0 {\n    x = 0;\n}")?;
+    ///
+    /// let lines: Vec<_> = ast.statement_positions()
+    ///                         .iter()
+    ///                         .map(|pos| pos.line().unwrap())
+    ///                         .collect();
+    ///
+    /// assert_eq!(lines, vec![1, 2, 3]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn statement_positions(&self) -> Vec<Position> {
+        let mut positions = Vec::new();
+        let mut action = |pos| positions.push(pos);
+
+        self.0
+            .iter()
+            .for_each(|stmt| stmt.walk_positions(&mut action));
+
+        #[cfg(not(feature = "no_function"))]
+        self.1
+            .iter_script_fn()
+            .for_each(|f| f.body.walk_positions(&mut action));
+
+        positions
+    }
 }
 
 impl Add<Self> for &AST {
@@ -376,6 +467,13 @@ pub struct ScriptFnDef {
     pub body: Stmt,
     /// Position of the function definition.
     pub pos: Position,
+    /// Doc-comments (i.e. lines starting with `///`) preceding the function definition.
+    pub comments: StaticVec<String>,
+    /// Library of functions that the function body calls, if different from the caller's.
+    ///
+    /// This is set for functions in a module compiled via `Engine::compile_into_module`,
+    /// so that they can call other (including private) functions defined in the same script.
+    pub lib: Option<Shared<Module>>,
 }
 
 impl fmt::Display for ScriptFnDef {
@@ -417,6 +515,8 @@ struct ParseState<'e> {
     engine: &'e Engine,
     /// Encapsulates a local stack with variable names to simulate an actual runtime scope.
     stack: Vec<(String, ScopeEntryType)>,
+    /// Values of constants evaluated at compile time, keyed by their index into `stack`.
+    constants: Vec<(usize, Expr)>,
     /// Tracks a list of external variables (variables that are not explicitly declared in the scope).
     #[cfg(not(feature = "no_closure"))]
     externals: HashMap<String, Position>,
@@ -428,6 +528,14 @@ struct ParseState<'e> {
     allow_capture: bool,
     /// Encapsulates a local stack with variable names to simulate an actual runtime scope.
     modules: Vec<String>,
+    /// Positions of calls to `eval` while it is disabled.
+    eval_calls: Vec<Position>,
+    /// Function calls in constant initializers - (constant name, function name, number of arguments, position).
+    const_calls: Vec<(String, String, usize, Position)>,
+    /// Interned string literals, shared by all functions in the `AST`.
+    strings: HashSet<ImmutableString>,
+    /// Level of optimization, which decides whether functions can be called at compile time.
+    optimization_level: OptimizationLevel,
     /// Maximum levels of expression nesting.
     #[cfg(not(feature = "unchecked"))]
     max_expr_depth: usize,
@@ -440,6 +548,7 @@ impl<'e> ParseState<'e> {
     /// Create a new `ParseState`.
     pub fn new(
         engine: &'e Engine,
+        optimization_level: OptimizationLevel,
         #[cfg(not(feature = "unchecked"))] max_expr_depth: usize,
         #[cfg(not(feature = "unchecked"))] max_function_expr_depth: usize,
     ) -> Self {
@@ -454,7 +563,49 @@ impl<'e> ParseState<'e> {
             #[cfg(not(feature = "no_closure"))]
             allow_capture: true,
             stack: Default::default(),
+            constants: Default::default(),
             modules: Default::default(),
+            eval_calls: Default::default(),
+            const_calls: Default::default(),
+            strings: Default::default(),
+            optimization_level,
+        }
+    }
+
+    /// Get an interned copy of a string literal, so that identical literals share the same
+    /// `ImmutableString`.
+    fn get_interned_string(&mut self, text: String) -> ImmutableString {
+        if let Some(s) = self.strings.get(text.as_str()) {
+            return s.clone();
+        }
+
+        let s: ImmutableString = text.into();
+        self.strings.insert(s.clone());
+        s
+    }
+
+    /// Declare a constant with its compile-time value.
+    fn push_constant(&mut self, name: String, value: Expr) {
+        let index = self.stack.len();
+        // Values of constants that have gone out of scope are stale
+        self.constants.retain(|(i, _)| *i < index);
+        self.constants.push((index, value));
+        self.stack.push((name, ScopeEntryType::Constant));
+    }
+
+    /// Find the compile-time value of a constant by name, searching in reverse order.
+    /// Return `None` if the name does not refer to a constant.
+    fn find_constant(&self, name: &str) -> Option<&Expr> {
+        let index = self.stack.iter().rposition(|(n, _)| n == name)?;
+
+        match self.stack[index].1 {
+            ScopeEntryType::Constant => self
+                .constants
+                .iter()
+                .rev()
+                .find(|(i, _)| *i == index)
+                .map(|(_, expr)| expr),
+            ScopeEntryType::Normal => None,
         }
     }
 
@@ -542,6 +693,59 @@ impl ParseSettings {
     }
 }
 
+/// [INTERNALS] A destructuring pattern on the left-hand side of a `let` statement.
+/// Exported under the `internals` feature only.
+///
+/// ## WARNING
+///
+/// This type is volatile and may change.
+#[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+#[derive(Debug, Clone, Hash)]
+pub enum Pattern {
+    /// [ id, ... ] or [ id, ..., ..rest ] - variables and optional variable for the remaining items
+    #[cfg(not(feature = "no_index"))]
+    Array(StaticVec<(String, Position)>, Option<(String, Position)>),
+    /// #{ id, ... } - variables named after the object map properties
+    #[cfg(not(feature = "no_object"))]
+    Map(StaticVec<(String, Position)>),
+}
+
+/// [INTERNALS] The pattern of an arm in a `switch` statement.
+/// Exported under the `internals` feature only.
+///
+/// ## WARNING
+///
+/// This type is volatile and may change.
+#[derive(Debug, Clone, Hash)]
+pub enum SwitchPattern {
+    /// `_` - matches any value
+    Any(Position),
+    /// `id` - matches any value, binding it to a variable within the guard and the body
+    Bind(String, Position),
+    /// `expr` - matches a value equal to the expression
+    Value(Expr),
+    /// `expr..expr` - matches a value within the range
+    Range(Expr),
+}
+
+impl SwitchPattern {
+    /// Get the `Position` of this pattern.
+    pub fn position(&self) -> Position {
+        match self {
+            Self::Any(pos) | Self::Bind(_, pos) => *pos,
+            Self::Value(expr) | Self::Range(expr) => expr.position(),
+        }
+    }
+
+    /// Is this pattern _pure_?
+    pub fn is_pure(&self) -> bool {
+        match self {
+            Self::Any(_) | Self::Bind(_, _) => true,
+            Self::Value(expr) | Self::Range(expr) => expr.is_pure(),
+        }
+    }
+}
+
 /// [INTERNALS] A Rhai statement.
 /// Exported under the `internals` feature only.
 ///
@@ -555,14 +759,29 @@ pub enum Stmt {
     IfThenElse(Box<(Expr, Stmt, Option<Stmt>, Position)>),
     /// while expr { stmt }
     While(Box<(Expr, Stmt, Position)>),
+    /// while let id = expr { stmt }
+    WhileLet(Box<(String, Expr, Stmt, Position)>),
     /// loop { stmt }
     Loop(Box<(Stmt, Position)>),
     /// for id in expr { stmt }
     For(Box<(String, Expr, Stmt, Position)>),
+    /// try { stmt } catch ( id ) { stmt }
+    TryCatch(Box<(Stmt, Option<(String, Position)>, Stmt, Position)>),
     /// let id = expr
     Let(Box<((String, Position), Option<Expr>, Position)>),
     /// const id = expr
     Const(Box<((String, Position), Expr, Position)>),
+    /// let [ id, ... ] = expr or let #{ id, ... } = expr
+    #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+    Destructure(Box<(Pattern, Expr, Position)>),
+    /// switch expr { pattern if guard => stmt, ... }
+    Switch(
+        Box<(
+            Expr,
+            StaticVec<(SwitchPattern, Option<Expr>, Stmt)>,
+            Position,
+        )>,
+    ),
     /// { stmt; ... }
     Block(Box<(StaticVec<Stmt>, Position)>),
     /// expr
@@ -602,13 +821,18 @@ impl Stmt {
             Stmt::Noop(pos) | Stmt::Continue(pos) | Stmt::Break(pos) => *pos,
             Stmt::Let(x) => (x.0).1,
             Stmt::Const(x) => (x.0).1,
+            #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+            Stmt::Destructure(x) => x.2,
             Stmt::ReturnWithVal(x) => (x.0).1,
             Stmt::Block(x) => x.1,
             Stmt::IfThenElse(x) => x.3,
+            Stmt::Switch(x) => x.2,
             Stmt::Expr(x) => x.position(),
             Stmt::While(x) => x.2,
             Stmt::Loop(x) => x.1,
+            Stmt::WhileLet(x) => x.3,
             Stmt::For(x) => x.3,
+            Stmt::TryCatch(x) => x.3,
 
             #[cfg(not(feature = "no_module"))]
             Stmt::Import(x) => x.2,
@@ -626,15 +850,20 @@ impl Stmt {
             Stmt::Noop(pos) | Stmt::Continue(pos) | Stmt::Break(pos) => *pos = new_pos,
             Stmt::Let(x) => (x.0).1 = new_pos,
             Stmt::Const(x) => (x.0).1 = new_pos,
+            #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+            Stmt::Destructure(x) => x.2 = new_pos,
             Stmt::ReturnWithVal(x) => (x.0).1 = new_pos,
             Stmt::Block(x) => x.1 = new_pos,
             Stmt::IfThenElse(x) => x.3 = new_pos,
+            Stmt::Switch(x) => x.2 = new_pos,
             Stmt::Expr(x) => {
                 x.set_position(new_pos);
             }
             Stmt::While(x) => x.2 = new_pos,
             Stmt::Loop(x) => x.1 = new_pos,
+            Stmt::WhileLet(x) => x.3 = new_pos,
             Stmt::For(x) => x.3 = new_pos,
+            Stmt::TryCatch(x) => x.3 = new_pos,
 
             #[cfg(not(feature = "no_module"))]
             Stmt::Import(x) => x.2 = new_pos,
@@ -652,9 +881,12 @@ impl Stmt {
     pub fn is_self_terminated(&self) -> bool {
         match self {
             Stmt::IfThenElse(_)
+            | Stmt::Switch(_)
             | Stmt::While(_)
             | Stmt::Loop(_)
+            | Stmt::WhileLet(_)
             | Stmt::For(_)
+            | Stmt::TryCatch(_)
             | Stmt::Block(_) => true,
 
             // A No-op requires a semicolon in order to know it is an empty statement!
@@ -667,6 +899,9 @@ impl Stmt {
             | Stmt::Break(_)
             | Stmt::ReturnWithVal(_) => false,
 
+            #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+            Stmt::Destructure(_) => false,
+
             #[cfg(not(feature = "no_module"))]
             Stmt::Import(_) | Stmt::Export(_) => false,
 
@@ -684,10 +919,22 @@ impl Stmt {
                 x.0.is_pure() && x.1.is_pure() && x.2.as_ref().unwrap().is_pure()
             }
             Stmt::IfThenElse(x) => x.1.is_pure(),
+            Stmt::Switch(x) => {
+                x.0.is_pure()
+                    && x.1.iter().all(|(pattern, guard, body)| {
+                        pattern.is_pure()
+                            && guard.as_ref().map(Expr::is_pure).unwrap_or(true)
+                            && body.is_pure()
+                    })
+            }
             Stmt::While(x) => x.0.is_pure() && x.1.is_pure(),
             Stmt::Loop(x) => x.0.is_pure(),
+            Stmt::WhileLet(x) => x.1.is_pure() && x.2.is_pure(),
             Stmt::For(x) => x.1.is_pure() && x.2.is_pure(),
+            Stmt::TryCatch(x) => x.0.is_pure() && x.2.is_pure(),
             Stmt::Let(_) | Stmt::Const(_) => false,
+            #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+            Stmt::Destructure(_) => false,
             Stmt::Block(x) => x.0.iter().all(Stmt::is_pure),
             Stmt::Continue(_) | Stmt::Break(_) | Stmt::ReturnWithVal(_) => false,
 
@@ -700,6 +947,77 @@ impl Stmt {
             Stmt::Share(_) => false,
         }
     }
+
+    /// Call a function with the `Position` of this statement and every statement nested within it,
+    /// in evaluation order.
+    ///
+    /// Blocks and no-ops are skipped, so these are the statements reported to
+    /// `Engine::on_statement_executed` when evaluated.
+    pub(crate) fn walk_positions(&self, action: &mut dyn FnMut(Position)) {
+        match self {
+            Stmt::Noop(_) => return,
+            Stmt::Block(x) => {
+                x.0.iter().for_each(|stmt| stmt.walk_positions(action));
+                return;
+            }
+            _ => action(self.position()),
+        }
+
+        match self {
+            Stmt::IfThenElse(x) => {
+                x.0.walk_positions(action);
+                x.1.walk_positions(action);
+                if let Some(stmt) = &x.2 {
+                    stmt.walk_positions(action);
+                }
+            }
+            Stmt::Switch(x) => {
+                x.0.walk_positions(action);
+                x.1.iter().for_each(|(pattern, guard, body)| {
+                    match pattern {
+                        SwitchPattern::Value(expr) | SwitchPattern::Range(expr) => {
+                            expr.walk_positions(action)
+                        }
+                        SwitchPattern::Any(_) | SwitchPattern::Bind(_, _) => (),
+                    }
+                    if let Some(expr) = guard {
+                        expr.walk_positions(action);
+                    }
+                    body.walk_positions(action);
+                });
+            }
+            Stmt::While(x) => {
+                x.0.walk_positions(action);
+                x.1.walk_positions(action);
+            }
+            Stmt::Loop(x) => x.0.walk_positions(action),
+            Stmt::WhileLet(x) | Stmt::For(x) => {
+                x.1.walk_positions(action);
+                x.2.walk_positions(action);
+            }
+            Stmt::TryCatch(x) => {
+                x.0.walk_positions(action);
+                x.2.walk_positions(action);
+            }
+            Stmt::Let(x) => {
+                if let Some(expr) = &x.1 {
+                    expr.walk_positions(action);
+                }
+            }
+            Stmt::Const(x) => x.1.walk_positions(action),
+            #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+            Stmt::Destructure(x) => x.1.walk_positions(action),
+            Stmt::Expr(expr) => expr.walk_positions(action),
+            Stmt::ReturnWithVal(x) => {
+                if let Some(expr) = &x.1 {
+                    expr.walk_positions(action);
+                }
+            }
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Import(x) => x.0.walk_positions(action),
+            _ => (),
+        }
+    }
 }
 
 /// [INTERNALS] A type wrapping a custom syntax definition.
@@ -744,6 +1062,25 @@ impl Hash for FloatWrapper {
     }
 }
 
+/// [INTERNALS] A type wrapping a number literal that is converted into a custom number type.
+/// Exported under the `internals` feature only.
+///
+/// This type is mainly used to provide a standard `Hash` implementation (via the source text of the literal)
+/// to values of custom number types, allowing `Expr` to derive `Hash` automatically.
+///
+/// ## WARNING
+///
+/// This type is volatile and may change.
+#[derive(Debug, Clone)]
+pub struct CustomNumberWrapper(pub Dynamic, pub ImmutableString, pub Position);
+
+impl Hash for CustomNumberWrapper {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.1.hash(state);
+        self.2.hash(state);
+    }
+}
+
 /// [INTERNALS] An expression sub-tree.
 /// Exported under the `internals` feature only.
 ///
@@ -760,6 +1097,8 @@ pub enum Expr {
     /// Floating-point constant.
     #[cfg(not(feature = "no_float"))]
     FloatConstant(Box<FloatWrapper>),
+    /// Number constant of a custom number type.
+    CustomNumberConstant(Box<CustomNumberWrapper>),
     /// Character constant.
     CharConstant(Box<(char, Position)>),
     /// String constant.
@@ -801,6 +1140,8 @@ pub enum Expr {
     Index(Box<(Expr, Expr, Position)>),
     /// [ expr, ... ]
     Array(Box<(StaticVec<Expr>, Position)>),
+    /// ...expr - only valid inside an array literal or a function call's arguments list
+    Spread(Box<(Expr, Position)>),
     /// #{ name:expr, ... }
     Map(Box<(StaticVec<((ImmutableString, Position), Expr)>, Position)>),
     /// lhs in rhs
@@ -838,6 +1179,7 @@ impl Expr {
             Self::IntegerConstant(x) => x.0.into(),
             #[cfg(not(feature = "no_float"))]
             Self::FloatConstant(x) => x.0.into(),
+            Self::CustomNumberConstant(x) => x.0.clone(),
             Self::CharConstant(x) => x.0.into(),
             Self::StringConstant(x) => x.0.clone().into(),
             Self::FnPointer(x) => Dynamic(Union::FnPtr(Box::new(FnPtr::new_unchecked(
@@ -858,7 +1200,7 @@ impl Expr {
                 Dynamic(Union::Map(Box::new(
                     x.0.iter()
                         .map(|((k, _), v)| (k.clone(), v.get_constant_value()))
-                        .collect::<HashMap<_, _>>(),
+                        .collect::<Map>(),
                 )))
             }
 
@@ -877,6 +1219,7 @@ impl Expr {
 
             #[cfg(not(feature = "no_float"))]
             Self::FloatConstant(x) => x.0.to_string(),
+            Self::CustomNumberConstant(x) => x.1.to_string(),
 
             Self::IntegerConstant(x) => x.0.to_string(),
             Self::CharConstant(x) => x.0.to_string(),
@@ -898,12 +1241,14 @@ impl Expr {
 
             #[cfg(not(feature = "no_float"))]
             Self::FloatConstant(x) => x.1,
+            Self::CustomNumberConstant(x) => x.2,
 
             Self::IntegerConstant(x) => x.1,
             Self::CharConstant(x) => x.1,
             Self::StringConstant(x) => x.1,
             Self::FnPointer(x) => x.1,
             Self::Array(x) => x.1,
+            Self::Spread(x) => x.1,
             Self::Map(x) => x.1,
             Self::Property(x) => x.1,
             Self::Stmt(x) => x.1,
@@ -930,12 +1275,14 @@ impl Expr {
 
             #[cfg(not(feature = "no_float"))]
             Self::FloatConstant(x) => x.1 = new_pos,
+            Self::CustomNumberConstant(x) => x.2 = new_pos,
 
             Self::IntegerConstant(x) => x.1 = new_pos,
             Self::CharConstant(x) => x.1 = new_pos,
             Self::StringConstant(x) => x.1 = new_pos,
             Self::FnPointer(x) => x.1 = new_pos,
             Self::Array(x) => x.1 = new_pos,
+            Self::Spread(x) => x.1 = new_pos,
             Self::Map(x) => x.1 = new_pos,
             Self::Variable(x) => (x.0).1 = new_pos,
             Self::Property(x) => x.1 = new_pos,
@@ -956,6 +1303,29 @@ impl Expr {
         self
     }
 
+    /// Call a function with the `Position` of every statement nested within this expression,
+    /// in evaluation order.
+    pub(crate) fn walk_positions(&self, action: &mut dyn FnMut(Position)) {
+        match self {
+            Self::Stmt(x) => x.0.walk_positions(action),
+            Self::Expr(x) => x.walk_positions(action),
+            Self::FnCall(x) => x.3.iter().for_each(|expr| expr.walk_positions(action)),
+            Self::Assignment(x) => {
+                x.0.walk_positions(action);
+                x.2.walk_positions(action);
+            }
+            Self::Dot(x) | Self::Index(x) | Self::In(x) | Self::And(x) | Self::Or(x) => {
+                x.0.walk_positions(action);
+                x.1.walk_positions(action);
+            }
+            Self::Array(x) => x.0.iter().for_each(|expr| expr.walk_positions(action)),
+            Self::Spread(x) => x.0.walk_positions(action),
+            Self::Map(x) => x.0.iter().for_each(|(_, expr)| expr.walk_positions(action)),
+            Self::Custom(x) => (x.0).0.iter().for_each(|expr| expr.walk_positions(action)),
+            _ => (),
+        }
+    }
+
     /// Is the expression pure?
     ///
     /// A pure expression has no side effects.
@@ -965,6 +1335,8 @@ impl Expr {
 
             Self::Array(x) => x.0.iter().all(Self::is_pure),
 
+            Self::Spread(x) => x.0.is_pure(),
+
             Self::Index(x) | Self::And(x) | Self::Or(x) | Self::In(x) => {
                 let (lhs, rhs, _) = x.as_ref();
                 lhs.is_pure() && rhs.is_pure()
@@ -987,6 +1359,7 @@ impl Expr {
             Self::FloatConstant(_) => true,
 
             Self::IntegerConstant(_)
+            | Self::CustomNumberConstant(_)
             | Self::CharConstant(_)
             | Self::StringConstant(_)
             | Self::FnPointer(_)
@@ -1020,8 +1393,10 @@ impl Expr {
             Self::FloatConstant(_) => false,
 
             Self::IntegerConstant(_)
+            | Self::CustomNumberConstant(_)
             | Self::CharConstant(_)
             | Self::FnPointer(_)
+            | Self::Spread(_)
             | Self::In(_)
             | Self::And(_)
             | Self::Or(_)
@@ -1199,6 +1574,31 @@ fn parse_fn_call(
         match input.peek().unwrap() {
             // id(...args, ) - handle trailing comma
             (Token::RightParen, _) => (),
+            // id(...expr
+            #[cfg(not(feature = "no_index"))]
+            (Token::Ellipsis, pos) => {
+                let pos = *pos;
+
+                // Built-in functions that evaluate their arguments specially cannot take spreads
+                let forbidden = modules.is_none()
+                    && match id.as_str() {
+                        KEYWORD_EVAL | KEYWORD_FN_PTR | KEYWORD_IS_SHARED => true,
+                        crate::engine::KEYWORD_FN_PTR_CURRY | KEYWORD_FN_PTR_CALL => {
+                            args.is_empty()
+                        }
+                        _ => false,
+                    };
+
+                if forbidden {
+                    return Err(PERR::MalformedCallExpr(format!(
+                        "Arguments to '{}' cannot be spread",
+                        id
+                    ))
+                    .into_err(pos));
+                }
+
+                args.push(parse_spread(input, state, lib, settings)?)
+            }
             _ => args.push(parse_expr(input, state, lib, settings)?),
         }
 
@@ -1223,6 +1623,16 @@ fn parse_fn_call(
                     calc_fn_hash(empty(), &id, args.len(), empty())
                 };
 
+                // eval(...) is only checked after parsing, as it may be overridden by a function
+                // defined later in the script
+                if modules.is_none()
+                    && args.len() == 1
+                    && id == KEYWORD_EVAL
+                    && !state.engine.allow_eval
+                {
+                    state.eval_calls.push(settings.pos);
+                }
+
                 return Ok(Expr::FnCall(Box::new((
                     (id.into(), false, capture, settings.pos),
                     modules,
@@ -1469,6 +1879,10 @@ fn parse_array_literal(
                 eat_token(input, Token::RightBracket);
                 break;
             }
+            (Token::Ellipsis, _) => {
+                let expr = parse_spread(input, state, lib, settings.level_up())?;
+                arr.push(expr);
+            }
             _ => {
                 let expr = parse_expr(input, state, lib, settings.level_up())?;
                 arr.push(expr);
@@ -1501,6 +1915,19 @@ fn parse_array_literal(
     Ok(Expr::Array(Box::new((arr, settings.pos))))
 }
 
+/// Parse a spread expression `...expr`.
+#[cfg(not(feature = "no_index"))]
+fn parse_spread(
+    input: &mut TokenStream,
+    state: &mut ParseState,
+    lib: &mut FunctionsLib,
+    settings: ParseSettings,
+) -> Result<Expr, ParseError> {
+    let pos = eat_token(input, Token::Ellipsis);
+    let expr = parse_expr(input, state, lib, settings)?;
+    Ok(Expr::Spread(Box::new((expr, pos))))
+}
+
 /// Parse a map literal.
 #[cfg(not(feature = "no_object"))]
 fn parse_map_literal(
@@ -1528,8 +1955,8 @@ fn parse_map_literal(
         let (name, pos) = match input.next().unwrap() {
             (Token::Identifier(s), pos) => (s, pos),
             (Token::StringConstant(s), pos) => (s, pos),
-            (Token::Reserved(s), pos) if is_valid_identifier(s.chars()) => {
-                return Err(PERR::Reserved(s).into_err(pos));
+            (t, pos) if t.is_reserved_keyword() => {
+                return Err(PERR::Reserved(t.syntax().into()).into_err(pos));
             }
             (Token::LexError(err), pos) => return Err(err.into_err(pos)),
             (_, pos) if map.is_empty() => {
@@ -1572,7 +1999,7 @@ fn parse_map_literal(
         }
 
         let expr = parse_expr(input, state, lib, settings.level_up())?;
-        map.push(((Into::<ImmutableString>::into(name), pos), expr));
+        map.push(((state.get_interned_string(name), pos), expr));
 
         match input.peek().unwrap() {
             (Token::Comma, _) => {
@@ -1626,8 +2053,15 @@ fn parse_primary(
     let (token, _) = match token {
         // { - block statement as expression
         Token::LeftBrace if settings.allow_stmt_expr => {
-            return parse_block(input, state, lib, settings.level_up())
-                .map(|block| Expr::Stmt(Box::new((block, settings.pos))))
+            return match parse_block(input, state, lib, settings.level_up())? {
+                // {} - most likely meant to be an empty object map
+                #[cfg(not(feature = "no_object"))]
+                Stmt::Block(x) if x.0.is_empty() => Err(PERR::BadInput(
+                    "An empty object map is written as '#{}'".to_string(),
+                )
+                .into_err(settings.pos)),
+                block => Ok(Expr::Stmt(Box::new((block, settings.pos)))),
+            };
         }
         Token::EOF => return Err(PERR::UnexpectedEOF.into_err(settings.pos)),
         _ => input.next().unwrap(),
@@ -1639,8 +2073,33 @@ fn parse_primary(
         Token::IntegerConstant(x) => Expr::IntegerConstant(Box::new((x, settings.pos))),
         #[cfg(not(feature = "no_float"))]
         Token::FloatConstant(x) => Expr::FloatConstant(Box::new(FloatWrapper(x, settings.pos))),
+        // Number literal to be converted by the `Engine`'s number literal handler
+        Token::NumberLiteral(text) => match state
+            .engine
+            .number_literal
+            .as_ref()
+            .and_then(|handler| handler(&text))
+        {
+            Some(value) => Expr::CustomNumberConstant(Box::new(CustomNumberWrapper(
+                value,
+                text.into(),
+                settings.pos,
+            ))),
+            None => match parse_number_literal(&text) {
+                Some(Token::IntegerConstant(x)) => {
+                    Expr::IntegerConstant(Box::new((x, settings.pos)))
+                }
+                #[cfg(not(feature = "no_float"))]
+                Some(Token::FloatConstant(x)) => {
+                    Expr::FloatConstant(Box::new(FloatWrapper(x, settings.pos)))
+                }
+                _ => return Err(LexError::MalformedNumber(text).into_err(settings.pos)),
+            },
+        },
         Token::CharConstant(c) => Expr::CharConstant(Box::new((c, settings.pos))),
-        Token::StringConstant(s) => Expr::StringConstant(Box::new((s.into(), settings.pos))),
+        Token::StringConstant(s) => {
+            Expr::StringConstant(Box::new((state.get_interned_string(s), settings.pos)))
+        }
 
         // Function call
         Token::Identifier(s) if *next_token == Token::LeftParen || *next_token == Token::Bang => {
@@ -1702,9 +2161,7 @@ fn parse_primary(
         Token::LexError(err) => return Err(err.into_err(settings.pos)),
 
         _ => {
-            return Err(
-                PERR::BadInput(format!("Unexpected '{}'", token.syntax())).into_err(settings.pos)
-            );
+            return Err(LexError::UnexpectedInput(token.syntax().into()).into_err(settings.pos));
         }
     };
 
@@ -1757,8 +2214,8 @@ fn parse_primary(
 
                     Expr::Variable(Box::new(((id2, pos2), modules, 0, index)))
                 }
-                (Token::Reserved(id2), pos2) if is_valid_identifier(id2.chars()) => {
-                    return Err(PERR::Reserved(id2).into_err(pos2));
+                (t, pos2) if t.is_reserved_keyword() => {
+                    return Err(PERR::Reserved(t.syntax().into()).into_err(pos2));
                 }
                 (_, pos2) => return Err(PERR::VariableExpected.into_err(pos2)),
             },
@@ -1812,6 +2269,11 @@ fn parse_unary(
             parse_if(input, state, lib, settings.level_up())?,
             settings.pos,
         )))),
+        // Switch statement is allowed to act as expressions
+        Token::Switch if settings.allow_if_expr => Ok(Expr::Stmt(Box::new((
+            parse_switch(input, state, lib, settings.level_up())?,
+            settings.pos,
+        )))),
         // -expr
         Token::UnaryMinus => {
             let pos = eat_token(input, Token::UnaryMinus);
@@ -1886,11 +2348,13 @@ fn parse_unary(
         Token::Pipe | Token::Or if settings.allow_anonymous_fn => {
             let mut new_state = ParseState::new(
                 state.engine,
+                state.optimization_level,
                 #[cfg(not(feature = "unchecked"))]
                 state.max_function_expr_depth,
                 #[cfg(not(feature = "unchecked"))]
                 state.max_function_expr_depth,
             );
+            new_state.strings = mem::take(&mut state.strings);
 
             let settings = ParseSettings {
                 allow_if_expr: true,
@@ -1910,6 +2374,10 @@ fn parse_unary(
                 state.access_var(closure, *pos);
             });
 
+            state.eval_calls.extend(new_state.eval_calls);
+            state.const_calls.extend(new_state.const_calls);
+            state.strings = new_state.strings;
+
             // Qualifiers (none) + function name + number of arguments.
             let hash = calc_fn_hash(empty(), &func.name, func.params.len(), empty());
 
@@ -2312,7 +2780,8 @@ fn parse_binary_op(
             | Token::PowerOf
             | Token::Ampersand
             | Token::Pipe
-            | Token::XOr => Expr::FnCall(Box::new((op, None, hash, args, None))),
+            | Token::XOr
+            | Token::ExclusiveRange => Expr::FnCall(Box::new((op, None, hash, args, None))),
 
             // '!=' defaults to true when passed invalid operands
             Token::NotEqualsTo => Expr::FnCall(Box::new((op, None, hash, args, Some(true)))),
@@ -2414,8 +2883,8 @@ fn parse_expr(
                             (Token::Identifier(s), pos) => {
                                 exprs.push(Expr::Variable(Box::new(((s, pos), None, 0, None))));
                             }
-                            (Token::Reserved(s), pos) if is_valid_identifier(s.chars()) => {
-                                return Err(PERR::Reserved(s).into_err(pos));
+                            (t, pos) if t.is_reserved_keyword() => {
+                                return Err(PERR::Reserved(t.syntax().into()).into_err(pos));
                             }
                             (_, pos) => return Err(PERR::VariableExpected.into_err(pos)),
                         },
@@ -2529,6 +2998,136 @@ fn parse_if(
     ))))
 }
 
+/// Parse a switch statement.
+fn parse_switch(
+    input: &mut TokenStream,
+    state: &mut ParseState,
+    lib: &mut FunctionsLib,
+    mut settings: ParseSettings,
+) -> Result<Stmt, ParseError> {
+    // switch ...
+    let token_pos = eat_token(input, Token::Switch);
+    settings.pos = token_pos;
+
+    #[cfg(not(feature = "unchecked"))]
+    settings.ensure_level_within_max_limit(state.max_expr_depth)?;
+
+    // switch expr { ...
+    ensure_not_statement_expr(input, "a value")?;
+    let expr = parse_expr(input, state, lib, settings.level_up())?;
+
+    match input.next().unwrap() {
+        (Token::LeftBrace, _) => (),
+        (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+        (_, pos) => {
+            return Err(PERR::MissingToken(
+                Token::LeftBrace.into(),
+                "to start the arms of this switch".into(),
+            )
+            .into_err(pos))
+        }
+    }
+
+    let mut arms = StaticVec::new();
+
+    while !match_token(input, Token::RightBrace)? {
+        let prev_stack_len = state.stack.len();
+
+        // _ | id | expr | expr..expr
+        let pattern = match input.peek().unwrap() {
+            (Token::Underscore, _) => SwitchPattern::Any(eat_token(input, Token::Underscore)),
+            (Token::EOF, pos) => {
+                return Err(PERR::MissingToken(
+                    Token::RightBrace.into(),
+                    "to end the arms of this switch".into(),
+                )
+                .into_err(*pos))
+            }
+            _ => match parse_expr(input, state, lib, settings.level_up())? {
+                // id - bind the value to a variable
+                Expr::Variable(x) if x.1.is_none() => {
+                    let (name, pos) = x.0;
+                    state.stack.push((name.clone(), ScopeEntryType::Normal));
+                    SwitchPattern::Bind(name, pos)
+                }
+                // expr..expr
+                Expr::FnCall(x) if x.1.is_none() && (x.0).0 == ".." => {
+                    SwitchPattern::Range(Expr::FnCall(x))
+                }
+                // expr
+                expr => SwitchPattern::Value(expr),
+            },
+        };
+
+        // pattern if guard
+        let guard = if match_token(input, Token::If)? {
+            let guard = parse_expr(input, state, lib, settings.level_up())?;
+            ensure_not_assignment(input)?;
+            Some(guard)
+        } else {
+            None
+        };
+
+        // pattern => ...
+        match input.next().unwrap() {
+            (Token::DoubleArrow, _) => (),
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (_, pos) => {
+                return Err(PERR::MissingToken(
+                    Token::DoubleArrow.into(),
+                    "after the pattern of this switch arm".into(),
+                )
+                .into_err(pos))
+            }
+        }
+
+        // pattern => { body } | pattern => stmt
+        let body = match input.peek().unwrap() {
+            (Token::LeftBrace, _) => parse_block(input, state, lib, settings.level_up())?,
+            (Token::Return, _) | (Token::Throw, _) | (Token::Break, _) | (Token::Continue, _) => {
+                parse_stmt(input, state, lib, settings.level_up())?.unwrap()
+            }
+            _ => Stmt::Expr(Box::new(parse_expr(
+                input,
+                state,
+                lib,
+                settings.level_up(),
+            )?)),
+        };
+
+        state.stack.truncate(prev_stack_len);
+
+        let need_comma = !body.is_self_terminated();
+
+        arms.push((pattern, guard, body));
+
+        match input.peek().unwrap() {
+            (Token::Comma, _) => {
+                eat_token(input, Token::Comma);
+            }
+            (Token::RightBrace, _) => (),
+            (Token::EOF, pos) => {
+                return Err(PERR::MissingToken(
+                    Token::RightBrace.into(),
+                    "to end the arms of this switch".into(),
+                )
+                .into_err(*pos))
+            }
+            _ if !need_comma => (),
+            (Token::LexError(err), pos) => return Err(err.into_err(*pos)),
+            (_, pos) => {
+                return Err(PERR::MissingToken(
+                    Token::Comma.into(),
+                    "to separate the arms of this switch".into(),
+                )
+                .into_err(*pos))
+            }
+        }
+    }
+
+    Ok(Stmt::Switch(Box::new((expr, arms, token_pos))))
+}
+
 /// Parse a while loop.
 fn parse_while(
     input: &mut TokenStream,
@@ -2543,6 +3142,11 @@ fn parse_while(
     #[cfg(not(feature = "unchecked"))]
     settings.ensure_level_within_max_limit(state.max_expr_depth)?;
 
+    // while let ...
+    if match_token(input, Token::Let)? {
+        return parse_while_let(input, state, lib, token_pos, settings);
+    }
+
     // while guard { body }
     ensure_not_statement_expr(input, "a boolean")?;
     let guard = parse_expr(input, state, lib, settings.level_up())?;
@@ -2554,6 +3158,56 @@ fn parse_while(
     Ok(Stmt::While(Box::new((guard, body, token_pos))))
 }
 
+/// Parse a while let loop.
+fn parse_while_let(
+    input: &mut TokenStream,
+    state: &mut ParseState,
+    lib: &mut FunctionsLib,
+    token_pos: Position,
+    mut settings: ParseSettings,
+) -> Result<Stmt, ParseError> {
+    // while let name ...
+    let name = match input.next().unwrap() {
+        // Variable name
+        (Token::Identifier(s), _) => s,
+        // Reserved keyword
+        (t, pos) if t.is_reserved_keyword() => {
+            return Err(PERR::Reserved(t.syntax().into()).into_err(pos));
+        }
+        // Bad identifier
+        (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+        // Not a variable name
+        (_, pos) => return Err(PERR::VariableExpected.into_err(pos)),
+    };
+
+    // while let name = ...
+    match input.next().unwrap() {
+        (Token::Equals, _) => (),
+        (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+        (_, pos) => {
+            return Err(
+                PERR::MissingToken(Token::Equals.into(), "after the loop variable".into())
+                    .into_err(pos),
+            )
+        }
+    }
+
+    // while let name = expr { body }
+    ensure_not_statement_expr(input, "a value")?;
+    let expr = parse_expr(input, state, lib, settings.level_up())?;
+    ensure_not_assignment(input)?;
+
+    let prev_stack_len = state.stack.len();
+    state.stack.push((name.clone(), ScopeEntryType::Normal));
+
+    settings.is_breakable = true;
+    let body = parse_block(input, state, lib, settings.level_up())?;
+
+    state.stack.truncate(prev_stack_len);
+
+    Ok(Stmt::WhileLet(Box::new((name, expr, body, token_pos))))
+}
+
 /// Parse a loop statement.
 fn parse_loop(
     input: &mut TokenStream,
@@ -2594,8 +3248,8 @@ fn parse_for(
         // Variable name
         (Token::Identifier(s), _) => s,
         // Reserved keyword
-        (Token::Reserved(s), pos) if is_valid_identifier(s.chars()) => {
-            return Err(PERR::Reserved(s).into_err(pos));
+        (t, pos) if t.is_reserved_keyword() => {
+            return Err(PERR::Reserved(t.syntax().into()).into_err(pos));
         }
         // Bad identifier
         (Token::LexError(err), pos) => return Err(err.into_err(pos)),
@@ -2630,6 +3284,85 @@ fn parse_for(
     Ok(Stmt::For(Box::new((name, expr, body, token_pos))))
 }
 
+/// Parse a try/catch statement.
+fn parse_try_catch(
+    input: &mut TokenStream,
+    state: &mut ParseState,
+    lib: &mut FunctionsLib,
+    mut settings: ParseSettings,
+) -> Result<Stmt, ParseError> {
+    // try ...
+    let token_pos = eat_token(input, Token::Try);
+    settings.pos = token_pos;
+
+    #[cfg(not(feature = "unchecked"))]
+    settings.ensure_level_within_max_limit(state.max_expr_depth)?;
+
+    // try { body }
+    let body = parse_block(input, state, lib, settings.level_up())?;
+
+    // try { body } catch
+    match input.next().unwrap() {
+        (Token::Catch, _) => (),
+        (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+        (_, pos) => {
+            return Err(
+                PERR::MissingToken(Token::Catch.into(), "for the 'try' statement".into())
+                    .into_err(pos),
+            )
+        }
+    }
+
+    // try { body } catch (
+    let var_def = if match_token(input, Token::LeftParen)? {
+        // try { body } catch ( name
+        let var_def = match input.next().unwrap() {
+            // Variable name
+            (Token::Identifier(s), pos) => (s, pos),
+            // Reserved keyword
+            (t, pos) if t.is_reserved_keyword() => {
+                return Err(PERR::Reserved(t.syntax().into()).into_err(pos));
+            }
+            // Bad identifier
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            // Not a variable name
+            (_, pos) => return Err(PERR::VariableExpected.into_err(pos)),
+        };
+
+        // try { body } catch ( name )
+        match input.next().unwrap() {
+            (Token::RightParen, _) => (),
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (_, pos) => {
+                return Err(PERR::MissingToken(
+                    Token::RightParen.into(),
+                    "to enclose the catch variable".into(),
+                )
+                .into_err(pos))
+            }
+        }
+
+        Some(var_def)
+    } else {
+        None
+    };
+
+    let prev_stack_len = state.stack.len();
+
+    if let Some((name, _)) = &var_def {
+        state.stack.push((name.clone(), ScopeEntryType::Normal));
+    }
+
+    // try { body } catch ( name ) { catch_body }
+    let catch_body = parse_block(input, state, lib, settings.level_up())?;
+
+    state.stack.truncate(prev_stack_len);
+
+    Ok(Stmt::TryCatch(Box::new((
+        body, var_def, catch_body, token_pos,
+    ))))
+}
+
 /// Parse a variable definition statement.
 fn parse_let(
     input: &mut TokenStream,
@@ -2645,11 +3378,23 @@ fn parse_let(
     #[cfg(not(feature = "unchecked"))]
     settings.ensure_level_within_max_limit(state.max_expr_depth)?;
 
+    // let [ ... ] = ... | let #{ ... } = ...
+    #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+    if var_type == ScopeEntryType::Normal {
+        match input.peek().unwrap() {
+            #[cfg(not(feature = "no_index"))]
+            (Token::LeftBracket, _) => return parse_let_pattern(input, state, lib, settings),
+            #[cfg(not(feature = "no_object"))]
+            (Token::MapStart, _) => return parse_let_pattern(input, state, lib, settings),
+            _ => (),
+        }
+    }
+
     // let name ...
     let (name, pos) = match input.next().unwrap() {
         (Token::Identifier(s), pos) => (s, pos),
-        (Token::Reserved(s), pos) if is_valid_identifier(s.chars()) => {
-            return Err(PERR::Reserved(s).into_err(pos));
+        (t, pos) if t.is_reserved_keyword() => {
+            return Err(PERR::Reserved(t.syntax().into()).into_err(pos));
         }
         (Token::LexError(err), pos) => return Err(err.into_err(pos)),
         (_, pos) => return Err(PERR::VariableExpected.into_err(pos)),
@@ -2670,14 +3415,11 @@ fn parse_let(
                     token_pos,
                 ))))
             }
-            // const name = { expr:constant }
-            ScopeEntryType::Constant if init_value.is_constant() => {
-                state.stack.push((name.clone(), ScopeEntryType::Constant));
-                Ok(Stmt::Const(Box::new(((name, pos), init_value, token_pos))))
-            }
-            // const name = expr: error
+            // const name = expr
             ScopeEntryType::Constant => {
-                Err(PERR::ForbiddenConstantExpr(name).into_err(init_value.position()))
+                let value = eval_constant_expr(state, &name, init_value)?;
+                state.push_constant(name.clone(), value.clone());
+                Ok(Stmt::Const(Box::new(((name, pos), value, token_pos))))
             }
         }
     } else {
@@ -2688,7 +3430,7 @@ fn parse_let(
                 Ok(Stmt::Let(Box::new(((name, pos), None, token_pos))))
             }
             ScopeEntryType::Constant => {
-                state.stack.push((name.clone(), ScopeEntryType::Constant));
+                state.push_constant(name.clone(), Expr::Unit(pos));
                 Ok(Stmt::Const(Box::new((
                     (name, pos),
                     Expr::Unit(pos),
@@ -2699,6 +3441,276 @@ fn parse_let(
     }
 }
 
+/// Evaluate the initializer of a constant at compile time, returning the resulting literal.
+///
+/// The initializer may only refer to other constants.  It may only call functions (other than
+/// operators) under `OptimizationLevel::Full`, which assumes that all functions are pure.
+/// An initializer that cannot be evaluated into a literal is a compile error.
+fn eval_constant_expr(state: &mut ParseState, name: &str, expr: Expr) -> Result<Expr, ParseError> {
+    let pos = expr.position();
+    let mut calls = Vec::new();
+    let expr = substitute_constants(state, name, expr, false, &mut calls)?;
+
+    if expr.is_constant() {
+        return Ok(expr);
+    }
+
+    if state.optimization_level != OptimizationLevel::Full {
+        if let Some((fn_name, _, pos)) = calls.first() {
+            return Err(PERR::NonConstantExpr(
+                name.to_string(),
+                format!(
+                    "'{}' can only be called at compile time under full optimization",
+                    fn_name
+                ),
+            )
+            .into_err(*pos));
+        }
+    }
+
+    state.const_calls.extend(
+        calls
+            .into_iter()
+            .map(|(fn_name, num_args, pos)| (name.to_string(), fn_name, num_args, pos)),
+    );
+
+    let mut eval_state = State {
+        debugger_step: None,
+        ..State::new()
+    };
+
+    let value = state
+        .engine
+        .eval_expr(
+            &mut Scope::new(),
+            &mut Default::default(),
+            &mut eval_state,
+            &Module::new(),
+            &mut None,
+            &expr,
+            0,
+        )
+        .map_err(|mut err| {
+            err.set_position(Position::none());
+            PERR::NonConstantExpr(name.to_string(), err.to_string()).into_err(pos)
+        })?
+        .flatten();
+
+    let type_name = state.engine.map_type_name(value.type_name()).to_string();
+
+    map_dynamic_to_expr(value, pos).ok_or_else(|| {
+        PERR::NonConstantExpr(
+            name.to_string(),
+            format!("a value of type '{}' cannot be a constant", type_name),
+        )
+        .into_err(pos)
+    })
+}
+
+/// Replace all references to constants in an expression with their compile-time values.
+///
+/// Returns an error if the expression refers to anything that is not constant.
+/// `chain` is `true` when the expression is the right-hand side of a dot chain.
+/// The names, numbers of arguments and positions of all function calls are added to `calls`.
+fn substitute_constants(
+    state: &ParseState,
+    name: &str,
+    expr: Expr,
+    chain: bool,
+    calls: &mut Vec<(String, usize, Position)>,
+) -> Result<Expr, ParseError> {
+    match expr {
+        Expr::Expr(x) => Ok(Expr::Expr(Box::new(substitute_constants(
+            state, name, *x, chain, calls,
+        )?))),
+        Expr::Property(_) if chain => Ok(expr),
+        Expr::Variable(x) if x.1.is_none() => match state.find_constant(&(x.0).0) {
+            Some(value) => Ok(value.clone()),
+            None => Err(PERR::ForbiddenConstantExpr(name.to_string()).into_err((x.0).1)),
+        },
+        Expr::FnCall(mut x) if x.1.is_none() && !(x.0).2 => {
+            x.3 = mem::take(&mut x.3)
+                .into_iter()
+                .map(|expr| substitute_constants(state, name, expr, false, calls))
+                .collect::<Result<_, _>>()?;
+
+            if !(x.0).1 {
+                // A method call has the object as its first argument
+                let num_args = x.3.len() + if chain { 1 } else { 0 };
+                calls.push(((x.0).0.to_string(), num_args, (x.0).3));
+            }
+
+            Ok(Expr::FnCall(x))
+        }
+        Expr::Dot(x) => {
+            let (lhs, rhs, pos) = *x;
+            Ok(Expr::Dot(Box::new((
+                substitute_constants(state, name, lhs, chain, calls)?,
+                substitute_constants(state, name, rhs, true, calls)?,
+                pos,
+            ))))
+        }
+        Expr::Index(x) => {
+            let (lhs, rhs, pos) = *x;
+            Ok(Expr::Index(Box::new((
+                substitute_constants(state, name, lhs, chain, calls)?,
+                substitute_constants(state, name, rhs, false, calls)?,
+                pos,
+            ))))
+        }
+        Expr::In(x) => {
+            let (lhs, rhs, pos) = *x;
+            Ok(Expr::In(Box::new((
+                substitute_constants(state, name, lhs, false, calls)?,
+                substitute_constants(state, name, rhs, false, calls)?,
+                pos,
+            ))))
+        }
+        Expr::And(x) => {
+            let (lhs, rhs, pos) = *x;
+            Ok(Expr::And(Box::new((
+                substitute_constants(state, name, lhs, false, calls)?,
+                substitute_constants(state, name, rhs, false, calls)?,
+                pos,
+            ))))
+        }
+        Expr::Or(x) => {
+            let (lhs, rhs, pos) = *x;
+            Ok(Expr::Or(Box::new((
+                substitute_constants(state, name, lhs, false, calls)?,
+                substitute_constants(state, name, rhs, false, calls)?,
+                pos,
+            ))))
+        }
+        Expr::Array(x) => {
+            let (items, pos) = *x;
+            let items = items
+                .into_iter()
+                .map(|expr| substitute_constants(state, name, expr, false, calls))
+                .collect::<Result<_, _>>()?;
+            Ok(Expr::Array(Box::new((items, pos))))
+        }
+        Expr::Spread(x) => {
+            let (expr, pos) = *x;
+            Ok(Expr::Spread(Box::new((
+                substitute_constants(state, name, expr, false, calls)?,
+                pos,
+            ))))
+        }
+        Expr::Map(x) => {
+            let (items, pos) = *x;
+            let items = items
+                .into_iter()
+                .map(|(key, expr)| {
+                    substitute_constants(state, name, expr, false, calls).map(|expr| (key, expr))
+                })
+                .collect::<Result<_, _>>()?;
+            Ok(Expr::Map(Box::new((items, pos))))
+        }
+        expr if expr.is_constant() => Ok(expr),
+        expr => Err(PERR::ForbiddenConstantExpr(name.to_string()).into_err(expr.position())),
+    }
+}
+
+/// Parse a destructuring pattern in a `let` statement.
+#[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+fn parse_let_pattern(
+    input: &mut TokenStream,
+    state: &mut ParseState,
+    lib: &mut FunctionsLib,
+    settings: ParseSettings,
+) -> Result<Stmt, ParseError> {
+    let (open, _) = input.next().unwrap();
+
+    let (close, desc) = match open {
+        #[cfg(not(feature = "no_index"))]
+        Token::LeftBracket => (Token::RightBracket, "array"),
+        #[cfg(not(feature = "no_object"))]
+        Token::MapStart => (Token::RightBrace, "object map"),
+        _ => unreachable!(),
+    };
+
+    let mut names: StaticVec<(String, Position)> = Default::default();
+    let mut rest = None;
+
+    loop {
+        match input.next().unwrap() {
+            // [ ... ] - handle trailing comma
+            (t, _) if t == close => break,
+            // [ ..., .. ] or [ ..., ..rest ] - must be the last
+            (Token::ExclusiveRange, pos) if close == Token::RightBracket => {
+                if let (Token::Identifier(_), _) = input.peek().unwrap() {
+                    if let (Token::Identifier(s), pos) = input.next().unwrap() {
+                        rest = Some((s, pos));
+                    }
+                }
+
+                match input.next().unwrap() {
+                    (t, _) if t == close => break,
+                    (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                    (_, _) => {
+                        return Err(PERR::MissingToken(
+                            close.into(),
+                            format!("to end this {} pattern after '..'", desc),
+                        )
+                        .into_err(pos))
+                    }
+                }
+            }
+            (Token::Identifier(s), pos) => names.push((s, pos)),
+            (t, pos) if t.is_reserved_keyword() => {
+                return Err(PERR::Reserved(t.syntax().into()).into_err(pos));
+            }
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (_, pos) => return Err(PERR::VariableExpected.into_err(pos)),
+        }
+
+        match input.next().unwrap() {
+            (Token::Comma, _) => (),
+            (t, _) if t == close => break,
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (_, pos) => {
+                return Err(PERR::MissingToken(
+                    Token::Comma.into(),
+                    format!("to separate the variables in this {} pattern", desc),
+                )
+                .into_err(pos))
+            }
+        }
+    }
+
+    // let pattern = ...
+    if !match_token(input, Token::Equals)? {
+        return Err(PERR::MissingToken(
+            Token::Equals.into(),
+            format!("to assign a value to this {} pattern", desc),
+        )
+        .into_err(input.peek().unwrap().1));
+    }
+
+    // let pattern = expr
+    let init_value = parse_expr(input, state, lib, settings.level_up())?;
+
+    names
+        .iter()
+        .chain(rest.iter())
+        .for_each(|(name, _)| state.stack.push((name.clone(), ScopeEntryType::Normal)));
+
+    let pattern = match close {
+        #[cfg(not(feature = "no_index"))]
+        Token::RightBracket => Pattern::Array(names, rest),
+        #[cfg(not(feature = "no_object"))]
+        Token::RightBrace => Pattern::Map(names),
+        _ => unreachable!(),
+    };
+
+    Ok(Stmt::Destructure(Box::new((
+        pattern,
+        init_value,
+        settings.pos,
+    ))))
+}
+
 /// Parse an import statement.
 #[cfg(not(feature = "no_module"))]
 fn parse_import(
@@ -2725,8 +3737,8 @@ fn parse_import(
     // import expr as name ...
     let (name, _) = match input.next().unwrap() {
         (Token::Identifier(s), pos) => (s, pos),
-        (Token::Reserved(s), pos) if is_valid_identifier(s.chars()) => {
-            return Err(PERR::Reserved(s).into_err(pos));
+        (t, pos) if t.is_reserved_keyword() => {
+            return Err(PERR::Reserved(t.syntax().into()).into_err(pos));
         }
         (Token::LexError(err), pos) => return Err(err.into_err(pos)),
         (_, pos) => return Err(PERR::VariableExpected.into_err(pos)),
@@ -2760,8 +3772,8 @@ fn parse_export(
     loop {
         let (id, id_pos) = match input.next().unwrap() {
             (Token::Identifier(s), pos) => (s.clone(), pos),
-            (Token::Reserved(s), pos) if is_valid_identifier(s.chars()) => {
-                return Err(PERR::Reserved(s).into_err(pos));
+            (t, pos) if t.is_reserved_keyword() => {
+                return Err(PERR::Reserved(t.syntax().into()).into_err(pos));
             }
             (Token::LexError(err), pos) => return Err(err.into_err(pos)),
             (_, pos) => return Err(PERR::VariableExpected.into_err(pos)),
@@ -2770,8 +3782,8 @@ fn parse_export(
         let rename = if match_token(input, Token::As)? {
             match input.next().unwrap() {
                 (Token::Identifier(s), pos) => Some((s.clone(), pos)),
-                (Token::Reserved(s), pos) if is_valid_identifier(s.chars()) => {
-                    return Err(PERR::Reserved(s).into_err(pos));
+                (t, pos) if t.is_reserved_keyword() => {
+                    return Err(PERR::Reserved(t.syntax().into()).into_err(pos));
                 }
                 (Token::LexError(err), pos) => return Err(err.into_err(pos)),
                 (_, pos) => return Err(PERR::VariableExpected.into_err(pos)),
@@ -2870,6 +3882,23 @@ fn parse_block(
             (_, _) if !need_semicolon => (),
             // { ... stmt <error>
             (Token::LexError(err), pos) => return Err(err.into_err(*pos)),
+            // { name: ... - most likely meant to be an object map literal
+            #[cfg(not(feature = "no_object"))]
+            (Token::Colon, pos)
+                if statements.len() == 1
+                    && match &statements[0] {
+                        Stmt::Expr(x) => match x.as_ref() {
+                            Expr::Variable(_) | Expr::StringConstant(_) => true,
+                            _ => false,
+                        },
+                        _ => false,
+                    } =>
+            {
+                return Err(PERR::BadInput(
+                    "Object map literals start with '#{', not '{'".to_string(),
+                )
+                .into_err(*pos));
+            }
             // { ... stmt ???
             (_, pos) => {
                 // Semicolons are not optional between statements
@@ -2934,23 +3963,38 @@ fn parse_stmt(
         Token::Fn if !settings.is_global => Err(PERR::WrongFnDefinition.into_err(settings.pos)),
 
         #[cfg(not(feature = "no_function"))]
-        Token::Fn | Token::Private => {
-            let access = if matches!(token, Token::Private) {
-                eat_token(input, Token::Private);
-                FnAccess::Private
-            } else {
-                FnAccess::Public
+        Token::DocComment(_) | Token::Fn | Token::Private => {
+            // Doc-comments only appear right before a function definition
+            let mut comments = StaticVec::new();
+
+            while let (Token::DocComment(_), _) = input.peek().unwrap() {
+                if let (Token::DocComment(comment), _) = input.next().unwrap() {
+                    comments.push(comment);
+                }
+            }
+
+            let access = match input.peek().unwrap() {
+                (Token::Fn, pos) if !settings.is_global => {
+                    return Err(PERR::WrongFnDefinition.into_err(*pos))
+                }
+                (Token::Private, _) => {
+                    eat_token(input, Token::Private);
+                    FnAccess::Private
+                }
+                _ => FnAccess::Public,
             };
 
             match input.next().unwrap() {
                 (Token::Fn, pos) => {
                     let mut new_state = ParseState::new(
                         state.engine,
+                        state.optimization_level,
                         #[cfg(not(feature = "unchecked"))]
                         state.max_function_expr_depth,
                         #[cfg(not(feature = "unchecked"))]
                         state.max_function_expr_depth,
                     );
+                    new_state.strings = mem::take(&mut state.strings);
 
                     let settings = ParseSettings {
                         allow_if_expr: true,
@@ -2963,7 +4007,12 @@ fn parse_stmt(
                         pos: pos,
                     };
 
-                    let func = parse_fn(input, &mut new_state, lib, access, settings)?;
+                    let mut func = parse_fn(input, &mut new_state, lib, access, settings)?;
+                    func.comments = comments;
+
+                    state.eval_calls.extend(new_state.eval_calls);
+                    state.const_calls.extend(new_state.const_calls);
+                    state.strings = new_state.strings;
 
                     // Qualifiers (none) + function name + number of arguments.
                     let hash = calc_fn_hash(empty(), &func.name, func.params.len(), empty());
@@ -2982,9 +4031,11 @@ fn parse_stmt(
         }
 
         Token::If => parse_if(input, state, lib, settings.level_up()).map(Some),
+        Token::Switch => parse_switch(input, state, lib, settings.level_up()).map(Some),
         Token::While => parse_while(input, state, lib, settings.level_up()).map(Some),
         Token::Loop => parse_loop(input, state, lib, settings.level_up()).map(Some),
         Token::For => parse_for(input, state, lib, settings.level_up()).map(Some),
+        Token::Try => parse_try_catch(input, state, lib, settings.level_up()).map(Some),
 
         Token::Continue if settings.is_breakable => {
             let pos = eat_token(input, Token::Continue);
@@ -3072,6 +4123,7 @@ fn parse_fn(
         .into_function_name_for_override()
         .map_err(|t| match t {
             Token::Reserved(s) => PERR::Reserved(s).into_err(pos),
+            t if t.is_keyword() => PERR::Reserved(t.syntax().into()).into_err(pos),
             _ => PERR::FnMissingName.into_err(pos),
         })?;
 
@@ -3092,6 +4144,9 @@ fn parse_fn(
                     state.stack.push((s.clone(), ScopeEntryType::Normal));
                     params.push((s, pos))
                 }
+                (t, pos) if t.is_reserved_keyword() => {
+                    return Err(PERR::Reserved(t.syntax().into()).into_err(pos));
+                }
                 (Token::LexError(err), pos) => return Err(err.into_err(pos)),
                 (_, pos) => {
                     return Err(PERR::MissingToken(
@@ -3128,6 +4183,9 @@ fn parse_fn(
             PERR::FnDuplicatedParam(name.to_string(), p.to_string()).into_err(pos)
         })?;
 
+    #[cfg(not(feature = "unchecked"))]
+    ensure_max_params(state, &name, &params)?;
+
     // Parse function body
     let body = match input.peek().unwrap() {
         (Token::LeftBrace, _) => {
@@ -3156,6 +4214,8 @@ fn parse_fn(
         externals,
         body,
         pos: settings.pos,
+        comments: Default::default(),
+        lib: None,
     })
 }
 
@@ -3217,6 +4277,25 @@ fn make_curry_from_externals(
     return expr;
 }
 
+/// Make sure that a function definition does not have more parameters than allowed.
+/// An empty name stands for an anonymous function.
+#[cfg(not(feature = "unchecked"))]
+#[cfg(not(feature = "no_function"))]
+fn ensure_max_params(
+    state: &ParseState,
+    name: &str,
+    params: &[(String, Position)],
+) -> Result<(), ParseError> {
+    let max = state.engine.limits.max_fn_params;
+
+    if max > 0 && params.len() > max {
+        // Point at the first parameter over the limit
+        Err(PERR::FnTooManyParams(name.to_string(), max).into_err(params[max].1))
+    } else {
+        Ok(())
+    }
+}
+
 /// Parse an anonymous function definition.
 #[cfg(not(feature = "no_function"))]
 fn parse_anon_fn(
@@ -3239,6 +4318,9 @@ fn parse_anon_fn(
                         state.stack.push((s.clone(), ScopeEntryType::Normal));
                         params.push((s, pos))
                     }
+                    (t, pos) if t.is_reserved_keyword() => {
+                        return Err(PERR::Reserved(t.syntax().into()).into_err(pos));
+                    }
                     (Token::LexError(err), pos) => return Err(err.into_err(pos)),
                     (_, pos) => {
                         return Err(PERR::MissingToken(
@@ -3278,6 +4360,9 @@ fn parse_anon_fn(
         })
         .map_err(|(p, pos)| PERR::FnDuplicatedParam("".to_string(), p.to_string()).into_err(pos))?;
 
+    #[cfg(not(feature = "unchecked"))]
+    ensure_max_params(state, "", &params)?;
+
     // Parse function body
     settings.is_breakable = false;
     let pos = input.peek().unwrap().1;
@@ -3333,6 +4418,8 @@ fn parse_anon_fn(
         externals: Default::default(),
         body,
         pos: settings.pos,
+        comments: Default::default(),
+        lib: None,
     };
 
     let expr = Expr::FnPointer(Box::new((fn_name, settings.pos)));
@@ -3356,6 +4443,7 @@ impl Engine {
         let mut functions = Default::default();
         let mut state = ParseState::new(
             self,
+            optimization_level,
             #[cfg(not(feature = "unchecked"))]
             self.limits.max_expr_depth,
             #[cfg(not(feature = "unchecked"))]
@@ -3376,13 +4464,13 @@ impl Engine {
 
         assert!(functions.is_empty());
 
+        self.check_disabled_eval(&state, &functions)?;
+
         match input.peek().unwrap() {
             (Token::EOF, _) => (),
             // Return error if the expression doesn't end
             (token, pos) => {
-                return Err(
-                    PERR::BadInput(format!("Unexpected '{}'", token.syntax())).into_err(*pos)
-                )
+                return Err(LexError::UnexpectedInput(token.syntax().into()).into_err(*pos))
             }
         }
 
@@ -3398,11 +4486,13 @@ impl Engine {
     fn parse_global_level(
         &self,
         input: &mut TokenStream,
+        optimization_level: OptimizationLevel,
     ) -> Result<(Vec<Stmt>, Vec<ScriptFnDef>), ParseError> {
         let mut statements: Vec<Stmt> = Default::default();
         let mut functions = Default::default();
         let mut state = ParseState::new(
             self,
+            optimization_level,
             #[cfg(not(feature = "unchecked"))]
             self.limits.max_expr_depth,
             #[cfg(not(feature = "unchecked"))]
@@ -3455,9 +4545,64 @@ impl Engine {
             }
         }
 
+        self.check_disabled_eval(&state, &functions)?;
+        self.check_constant_calls(&state, &functions)?;
+
         Ok((statements, functions.into_iter().map(|(_, v)| v).collect()))
     }
 
+    /// Reject calls to `eval` when it is disabled, unless `eval` is overridden by a function
+    /// defined in the script or registered with the `Engine`.
+    fn check_disabled_eval(
+        &self,
+        state: &ParseState,
+        functions: &FunctionsLib,
+    ) -> Result<(), ParseError> {
+        let pos = match state.eval_calls.first() {
+            Some(pos) => *pos,
+            None => return Ok(()),
+        };
+
+        let hash_script = calc_fn_hash(empty(), KEYWORD_EVAL, 1, empty());
+        let hash_fn = calc_fn_hash(
+            empty(),
+            KEYWORD_EVAL,
+            1,
+            once(TypeId::of::<ImmutableString>()),
+        );
+
+        if functions.contains_key(&hash_script)
+            || self.global_module.contains_fn(hash_fn, false)
+            || self.packages.contains_fn(hash_script, false)
+            || self.packages.contains_fn(hash_fn, false)
+        {
+            Ok(())
+        } else {
+            Err(PERR::DisabledFunction(KEYWORD_EVAL.into()).into_err(pos))
+        }
+    }
+
+    /// Reject constant initializers that call script-defined functions.
+    fn check_constant_calls(
+        &self,
+        state: &ParseState,
+        functions: &FunctionsLib,
+    ) -> Result<(), ParseError> {
+        state
+            .const_calls
+            .iter()
+            .find(|(_, fn_name, num_args, _)| {
+                functions.contains_key(&calc_fn_hash(empty(), fn_name, *num_args, empty()))
+            })
+            .map_or(Ok(()), |(name, fn_name, _, pos)| {
+                Err(PERR::NonConstantExpr(
+                    name.clone(),
+                    format!("'{}' is a script-defined function", fn_name),
+                )
+                .into_err(*pos))
+            })
+    }
+
     /// Run the parser on an input stream, returning an AST.
     pub(crate) fn parse(
         &self,
@@ -3465,7 +4610,7 @@ impl Engine {
         scope: &Scope,
         optimization_level: OptimizationLevel,
     ) -> Result<AST, ParseError> {
-        let (statements, lib) = self.parse_global_level(input)?;
+        let (statements, lib) = self.parse_global_level(input, optimization_level)?;
 
         Ok(
             // Optimize AST
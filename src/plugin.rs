@@ -1,11 +1,25 @@
 //! Module defining plugins in Rhai for use by plugin authors.
 
 pub use crate::{
-    fn_native::CallableFunction, stdlib::any::TypeId, stdlib::boxed::Box, stdlib::format,
-    stdlib::mem, stdlib::string::ToString, stdlib::vec as new_vec, stdlib::vec::Vec, Dynamic,
-    Engine, EvalAltResult, FnAccess, ImmutableString, Module, RegisterResultFn,
+    calc_fn_hash,
+    fn_native::{CallableFunction, NativeCallContext},
+    stdlib::any::TypeId,
+    stdlib::boxed::Box,
+    stdlib::format,
+    stdlib::mem,
+    stdlib::string::ToString,
+    stdlib::vec as new_vec,
+    stdlib::vec::Vec,
+    Dynamic, Engine, EvalAltResult, FnAccess, ImmutableString, Module, Position, RegisterResultFn,
+    INT,
 };
 
+#[cfg(not(feature = "no_index"))]
+pub use crate::Array;
+
+#[cfg(not(feature = "no_object"))]
+pub use crate::Map;
+
 #[cfg(not(features = "no_module"))]
 pub use rhai_codegen::*;
 #[cfg(features = "no_module")]
@@ -38,5 +52,31 @@ pub trait PluginFunction {
 
     fn clone_boxed(&self) -> Box<dyn PluginFunction>;
 
+    /// The `TypeId`s of the parameters, used when the function is registered.
+    ///
+    /// Function calls are resolved via hashes, so this is not called during evaluation.
+    /// The list is built on each call because `TypeId::of` is not a `const fn` in the
+    /// minimum supported Rust version.
     fn input_types(&self) -> Box<[TypeId]>;
+
+    /// The message of a function marked as deprecated, if any.
+    fn deprecated(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+/// The signature of a plugin function, as written in its source, for introspection.
+///
+/// Generated for each plugin function as `token_metadata()` (or `<fn>_token_metadata()` in a
+/// plugin module).  `rhai_module_metadata()` in a plugin module lists those of all its functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FnMetadata {
+    /// The names the function is registered under, e.g. `get$prop` for a property getter.
+    pub names: &'static [&'static str],
+    /// The number of parameters of the function.
+    pub arg_count: usize,
+    /// The types of the parameters.
+    pub param_types: &'static [&'static str],
+    /// The return type, `()` if there is none.
+    pub return_type: &'static str,
 }
@@ -39,6 +39,13 @@ pub enum EvalAltResult {
     /// An error has occurred inside a called function.
     /// Wrapped values are the name of the function and the interior error.
     ErrorInFunctionCall(String, Box<EvalAltResult>, Position),
+    /// An error has occurred inside a script run by `eval`.
+    /// Wrapped value is the interior error, with a `Position` relative to the evaluated script text.
+    /// The `Position` of this error is the location of the call to `eval`.
+    ErrorInEval(Box<EvalAltResult>, Position),
+    /// An error has occurred inside a script combined into a module.
+    /// Wrapped values are the name of the module, the name of the script and the interior error.
+    ErrorInModule(String, String, Box<EvalAltResult>, Position),
     /// Access to `this` that is not bound.
     ErrorUnboundThis(Position),
     /// Non-boolean operand encountered for boolean operator. Wrapped value is the operator.
@@ -59,8 +66,8 @@ pub enum EvalAltResult {
     ErrorStringIndexExpr(Position),
     /// Trying to import with an expression that is not `String`.
     ErrorImportExpr(Position),
-    /// Invalid arguments for `in` operator.
-    ErrorInExpr(Position),
+    /// Invalid arguments for `in` operator. Wrapped value is the error message (if any).
+    ErrorInExpr(String, Position),
     /// The guard expression in an `if` or `while` statement does not return a boolean value.
     ErrorLogicGuard(Position),
     /// The `for` statement encounters a type that is not an iterator.
@@ -94,6 +101,9 @@ pub enum EvalAltResult {
     ErrorTerminated(Position),
     /// Run-time error encountered. Wrapped value is the error message.
     ErrorRuntime(String, Position),
+    /// A value that is not a string is thrown via the `throw` keyword.
+    /// Wrapped value is the thrown value.
+    ErrorThrown(Dynamic, Position),
 
     /// Breaking out of loops - not an error if within a loop.
     /// The wrapped value, if true, means breaking clean out of the loop (i.e. a `break` statement).
@@ -113,6 +123,8 @@ impl EvalAltResult {
 
             Self::ErrorParsing(p, _) => p.desc(),
             Self::ErrorInFunctionCall(_, _, _) => "Error in called function",
+            Self::ErrorInEval(_, _) => "Error in evaluated script",
+            Self::ErrorInModule(_, _, _, _) => "Error in module",
             Self::ErrorFunctionNotFound(_, _) => "Function not found",
             Self::ErrorUnboundThis(_) => "'this' is not bound",
             Self::ErrorBooleanArgMismatch(_, _) => "Boolean operator expects boolean operands",
@@ -145,7 +157,7 @@ impl EvalAltResult {
             }
             Self::ErrorAssignmentToConstant(_, _) => "Assignment to a constant variable",
             Self::ErrorMismatchOutputType(_, _, _) => "Output type is incorrect",
-            Self::ErrorInExpr(_) => "Malformed 'in' expression",
+            Self::ErrorInExpr(_, _) => "Malformed 'in' expression",
             Self::ErrorDotExpr(_, _) => "Malformed dot expression",
             Self::ErrorArithmetic(_, _) => "Arithmetic error",
             Self::ErrorTooManyOperations(_) => "Too many operations",
@@ -153,7 +165,7 @@ impl EvalAltResult {
             Self::ErrorStackOverflow(_) => "Stack overflow",
             Self::ErrorDataTooLarge(_, _, _, _) => "Data size exceeds maximum limit",
             Self::ErrorTerminated(_) => "Script terminated.",
-            Self::ErrorRuntime(_, _) => "Runtime error",
+            Self::ErrorRuntime(_, _) | Self::ErrorThrown(_, _) => "Runtime error",
             Self::ErrorLoopBreak(true, _) => "Break statement not inside a loop",
             Self::ErrorLoopBreak(false, _) => "Continue statement not inside a loop",
             Self::Return(_, _) => "[Not Error] Function returns value",
@@ -180,13 +192,19 @@ impl fmt::Display for EvalAltResult {
             Self::ErrorInFunctionCall(s, err, _) => {
                 write!(f, "Error in call to function '{}' : {}", s, err)?
             }
+            Self::ErrorInEval(err, _) => write!(f, "{} : {}", desc, err)?,
+            Self::ErrorInModule(m, s, err, _) => {
+                write!(f, "{} '{}', script '{}' : {}", desc, m, s, err)?
+            }
 
             Self::ErrorFunctionNotFound(s, _)
             | Self::ErrorVariableNotFound(s, _)
             | Self::ErrorDataRace(s, _)
             | Self::ErrorModuleNotFound(s, _) => write!(f, "{}: '{}'", desc, s)?,
 
-            Self::ErrorDotExpr(s, _) if !s.is_empty() => write!(f, "{}", s)?,
+            Self::ErrorDotExpr(s, _) | Self::ErrorInExpr(s, _) if !s.is_empty() => {
+                write!(f, "{}", s)?
+            }
 
             Self::ErrorIndexingType(_, _)
             | Self::ErrorNumericIndexExpr(_)
@@ -196,7 +214,7 @@ impl fmt::Display for EvalAltResult {
             | Self::ErrorLogicGuard(_)
             | Self::ErrorFor(_)
             | Self::ErrorAssignmentToUnknownLHS(_)
-            | Self::ErrorInExpr(_)
+            | Self::ErrorInExpr(_, _)
             | Self::ErrorDotExpr(_, _)
             | Self::ErrorTooManyOperations(_)
             | Self::ErrorTooManyModules(_)
@@ -204,6 +222,7 @@ impl fmt::Display for EvalAltResult {
             | Self::ErrorTerminated(_) => f.write_str(desc)?,
 
             Self::ErrorRuntime(s, _) => f.write_str(if s.is_empty() { desc } else { s })?,
+            Self::ErrorThrown(value, _) => write!(f, "{}: {}", desc, value)?,
 
             Self::ErrorAssignmentToConstant(s, _) => write!(f, "{}: '{}'", desc, s)?,
             Self::ErrorMismatchOutputType(r, s, _) => {
@@ -270,6 +289,122 @@ impl<T: AsRef<str>> From<T> for Box<EvalAltResult> {
 }
 
 impl EvalAltResult {
+    /// Can this error be handled by script code (e.g. retried by a function that calls back into a script)?
+    ///
+    /// Errors that enforce the safety limits of the `Engine` (e.g. `ErrorTooManyOperations`) and
+    /// errors that abort a script entirely (e.g. `ErrorTerminated`) are not catchable.
+    /// An error inside a called function (or a script run by `eval`) is catchable only if the
+    /// interior error is catchable.
+    pub fn is_catchable(&self) -> bool {
+        match self {
+            #[cfg(not(feature = "no_std"))]
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::ErrorReadingScriptFile(_, _, _) => false,
+
+            Self::ErrorInFunctionCall(_, err, _)
+            | Self::ErrorInEval(err, _)
+            | Self::ErrorInModule(_, _, err, _) => err.is_catchable(),
+
+            Self::ErrorFunctionNotFound(_, _)
+            | Self::ErrorUnboundThis(_)
+            | Self::ErrorBooleanArgMismatch(_, _)
+            | Self::ErrorCharMismatch(_)
+            | Self::ErrorArrayBounds(_, _, _)
+            | Self::ErrorStringBounds(_, _, _)
+            | Self::ErrorIndexingType(_, _)
+            | Self::ErrorNumericIndexExpr(_)
+            | Self::ErrorStringIndexExpr(_)
+            | Self::ErrorImportExpr(_)
+            | Self::ErrorInExpr(_, _)
+            | Self::ErrorLogicGuard(_)
+            | Self::ErrorFor(_)
+            | Self::ErrorVariableNotFound(_, _)
+            | Self::ErrorModuleNotFound(_, _)
+            | Self::ErrorDataRace(_, _)
+            | Self::ErrorAssignmentToUnknownLHS(_)
+            | Self::ErrorAssignmentToConstant(_, _)
+            | Self::ErrorMismatchOutputType(_, _, _)
+            | Self::ErrorDotExpr(_, _)
+            | Self::ErrorArithmetic(_, _)
+            | Self::ErrorRuntime(_, _)
+            | Self::ErrorThrown(_, _) => true,
+
+            Self::ErrorParsing(_, _)
+            | Self::ErrorTooManyOperations(_)
+            | Self::ErrorTooManyModules(_)
+            | Self::ErrorStackOverflow(_)
+            | Self::ErrorDataTooLarge(_, _, _, _)
+            | Self::ErrorTerminated(_)
+            | Self::ErrorLoopBreak(_, _)
+            | Self::Return(_, _) => false,
+        }
+    }
+
+    /// Get the category of this error, as returned by `type_of` on an error caught in a
+    /// `try` ... `catch` statement.
+    ///
+    /// An error inside a called function (or a script run by `eval`) has the category of the
+    /// interior error.
+    pub fn category(&self) -> &'static str {
+        match self {
+            #[cfg(not(feature = "no_std"))]
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::ErrorReadingScriptFile(_, _, _) => "io",
+
+            Self::ErrorInFunctionCall(_, err, _)
+            | Self::ErrorInEval(err, _)
+            | Self::ErrorInModule(_, _, err, _) => err.category(),
+
+            Self::ErrorParsing(_, _) => "syntax",
+            Self::ErrorFunctionNotFound(_, _) => "function",
+            Self::ErrorVariableNotFound(_, _) => "variable",
+            Self::ErrorModuleNotFound(_, _) => "module",
+            Self::ErrorArrayBounds(_, _, _) | Self::ErrorStringBounds(_, _, _) => "bounds",
+
+            Self::ErrorUnboundThis(_)
+            | Self::ErrorBooleanArgMismatch(_, _)
+            | Self::ErrorCharMismatch(_)
+            | Self::ErrorIndexingType(_, _)
+            | Self::ErrorNumericIndexExpr(_)
+            | Self::ErrorStringIndexExpr(_)
+            | Self::ErrorImportExpr(_)
+            | Self::ErrorInExpr(_, _)
+            | Self::ErrorLogicGuard(_)
+            | Self::ErrorFor(_)
+            | Self::ErrorMismatchOutputType(_, _, _)
+            | Self::ErrorDotExpr(_, _) => "type",
+
+            Self::ErrorAssignmentToUnknownLHS(_) | Self::ErrorAssignmentToConstant(_, _) => {
+                "assignment"
+            }
+            Self::ErrorDataRace(_, _) => "data_race",
+            Self::ErrorArithmetic(_, _) => "arithmetic",
+            Self::ErrorRuntime(_, _) | Self::ErrorThrown(_, _) => "runtime",
+
+            Self::ErrorTooManyOperations(_)
+            | Self::ErrorTooManyModules(_)
+            | Self::ErrorStackOverflow(_)
+            | Self::ErrorDataTooLarge(_, _, _, _) => "limit",
+            Self::ErrorTerminated(_) => "terminated",
+
+            Self::ErrorLoopBreak(_, _) | Self::Return(_, _) => "control_flow",
+        }
+    }
+
+    /// Convert this error into the value bound to the variable of a `catch` block.
+    ///
+    /// A value thrown via `throw` is returned as is, even from inside a called function.
+    /// Other errors are returned as `CaughtError`.
+    pub(crate) fn into_caught_value(self) -> Dynamic {
+        match self {
+            Self::ErrorInFunctionCall(_, err, _)
+            | Self::ErrorInEval(err, _)
+            | Self::ErrorInModule(_, _, err, _) => (*err).into_caught_value(),
+            Self::ErrorThrown(value, _) => value,
+            err => Dynamic::from(CaughtError::new(err)),
+        }
+    }
+
     /// Get the `Position` of this error.
     pub fn position(&self) -> Position {
         match self {
@@ -280,6 +415,8 @@ impl EvalAltResult {
             Self::ErrorParsing(_, pos)
             | Self::ErrorFunctionNotFound(_, pos)
             | Self::ErrorInFunctionCall(_, _, pos)
+            | Self::ErrorInEval(_, pos)
+            | Self::ErrorInModule(_, _, _, pos)
             | Self::ErrorUnboundThis(pos)
             | Self::ErrorBooleanArgMismatch(_, pos)
             | Self::ErrorCharMismatch(pos)
@@ -297,7 +434,7 @@ impl EvalAltResult {
             | Self::ErrorAssignmentToUnknownLHS(pos)
             | Self::ErrorAssignmentToConstant(_, pos)
             | Self::ErrorMismatchOutputType(_, _, pos)
-            | Self::ErrorInExpr(pos)
+            | Self::ErrorInExpr(_, pos)
             | Self::ErrorDotExpr(_, pos)
             | Self::ErrorArithmetic(_, pos)
             | Self::ErrorTooManyOperations(pos)
@@ -306,6 +443,7 @@ impl EvalAltResult {
             | Self::ErrorDataTooLarge(_, _, _, pos)
             | Self::ErrorTerminated(pos)
             | Self::ErrorRuntime(_, pos)
+            | Self::ErrorThrown(_, pos)
             | Self::ErrorLoopBreak(_, pos)
             | Self::Return(_, pos) => *pos,
         }
@@ -321,6 +459,8 @@ impl EvalAltResult {
             Self::ErrorParsing(_, pos)
             | Self::ErrorFunctionNotFound(_, pos)
             | Self::ErrorInFunctionCall(_, _, pos)
+            | Self::ErrorInEval(_, pos)
+            | Self::ErrorInModule(_, _, _, pos)
             | Self::ErrorUnboundThis(pos)
             | Self::ErrorBooleanArgMismatch(_, pos)
             | Self::ErrorCharMismatch(pos)
@@ -338,7 +478,7 @@ impl EvalAltResult {
             | Self::ErrorAssignmentToUnknownLHS(pos)
             | Self::ErrorAssignmentToConstant(_, pos)
             | Self::ErrorMismatchOutputType(_, _, pos)
-            | Self::ErrorInExpr(pos)
+            | Self::ErrorInExpr(_, pos)
             | Self::ErrorDotExpr(_, pos)
             | Self::ErrorArithmetic(_, pos)
             | Self::ErrorTooManyOperations(pos)
@@ -347,6 +487,7 @@ impl EvalAltResult {
             | Self::ErrorDataTooLarge(_, _, _, pos)
             | Self::ErrorTerminated(pos)
             | Self::ErrorRuntime(_, pos)
+            | Self::ErrorThrown(_, pos)
             | Self::ErrorLoopBreak(_, pos)
             | Self::Return(_, pos) => *pos = new_position,
         }
@@ -367,3 +508,65 @@ impl<T> From<EvalAltResult> for Result<T, Box<EvalAltResult>> {
         Err(err.into())
     }
 }
+
+/// An error caught by a `try` ... `catch` statement in a script.
+///
+/// Calling `type_of` on a caught error returns its category (e.g. `"runtime"` or `"arithmetic"`)
+/// instead of a type name.  See `EvalAltResult::category`.
+#[derive(Debug, Clone)]
+pub struct CaughtError {
+    category: &'static str,
+    message: String,
+    position: Position,
+}
+
+impl CaughtError {
+    /// Create a `CaughtError` from an `EvalAltResult`.
+    ///
+    /// An error inside a called function (or a script run by `eval`) is represented by
+    /// the interior error.
+    pub fn new(mut err: EvalAltResult) -> Self {
+        loop {
+            match err {
+                EvalAltResult::ErrorInFunctionCall(_, inner, _)
+                | EvalAltResult::ErrorInEval(inner, _)
+                | EvalAltResult::ErrorInModule(_, _, inner, _) => err = *inner,
+                _ => break,
+            }
+        }
+
+        let category = err.category();
+        let position = err.position();
+        err.set_position(Position::none());
+
+        Self {
+            category,
+            message: err.to_string(),
+            position,
+        }
+    }
+    /// Get the category of the error.
+    pub fn category(&self) -> &'static str {
+        self.category
+    }
+    /// Get the error message, without the position.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+    /// Get the `Position` where the error occurs.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+}
+
+impl fmt::Display for CaughtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)?;
+
+        if !self.position.is_none() {
+            write!(f, " ({})", self.position)?;
+        }
+
+        Ok(())
+    }
+}
@@ -417,7 +417,13 @@ impl<'a> Scope<'a> {
         self.0.iter().rev() // Always search a Scope in reverse order
     }
 
-    /// Get an iterator to entries in the Scope.
+    /// Get an iterator to entries in the Scope, in the order they were added.
+    ///
+    /// Each entry is a tuple of the variable's name, whether it is a constant, and its value.
+    /// Shared values are not expanded.
+    ///
+    /// Shadowed variables are all included.  When more than one entry has the same name,
+    /// only the _last_ one (i.e. the innermost) is active and visible to scripts.
     ///
     /// # Examples
     ///
@@ -426,22 +432,36 @@ impl<'a> Scope<'a> {
     ///
     /// let mut my_scope = Scope::new();
     ///
-    /// my_scope.push("x", 42_i64);
+    /// my_scope.push_constant("x", 42_i64);
     /// my_scope.push("foo", "hello".to_string());
+    /// my_scope.push("x", 123_i64);
     ///
     /// let mut iter = my_scope.iter();
     ///
-    /// let (name, value) = iter.next().unwrap();
+    /// let (name, constant, value) = iter.next().unwrap();
     /// assert_eq!(name, "x");
-    /// assert_eq!(value.cast::<i64>(), 42);
+    /// assert!(constant);
+    /// assert_eq!(value.clone().cast::<i64>(), 42);
     ///
-    /// let (name, value) = iter.next().unwrap();
+    /// let (name, constant, value) = iter.next().unwrap();
     /// assert_eq!(name, "foo");
-    /// assert_eq!(value.cast::<String>(), "hello");
+    /// assert!(!constant);
+    /// assert_eq!(value.clone().cast::<String>(), "hello");
+    ///
+    /// // The last entry shadows the first 'x'
+    /// let (name, constant, value) = iter.next().unwrap();
+    /// assert_eq!(name, "x");
+    /// assert!(!constant);
+    /// assert_eq!(value.clone().cast::<i64>(), 123);
+    ///
+    /// assert!(iter.next().is_none());
     /// ```
-    pub fn iter(&self) -> impl Iterator<Item = (&str, Dynamic)> {
-        self.iter_raw()
-            .map(|(name, value)| (name, value.flatten_clone()))
+    pub fn iter(&self) -> impl Iterator<Item = (&str, bool, &Dynamic)> {
+        self.0.iter().map(
+            |Entry {
+                 name, typ, value, ..
+             }| (name.as_ref(), *typ == EntryType::Constant, value),
+        )
     }
 
     /// Get an iterator to entries in the Scope.
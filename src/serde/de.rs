@@ -156,7 +156,7 @@ impl<'de> Deserializer<'de> for &mut DynamicDeserializer<'de> {
             #[cfg(not(feature = "no_float"))]
             Union::Float(_) => self.deserialize_f64(visitor),
             #[cfg(not(feature = "no_index"))]
-            Union::Array(_) => self.deserialize_seq(visitor),
+            Union::Array(_) | Union::TypedArray(_) => self.deserialize_seq(visitor),
             #[cfg(not(feature = "no_object"))]
             Union::Map(_) => self.deserialize_map(visitor),
             Union::FnPtr(_) => self.type_error(),
@@ -177,6 +177,10 @@ impl<'de> Deserializer<'de> for &mut DynamicDeserializer<'de> {
 
             Union::Variant(_) => self.type_error(),
 
+            Union::Frozen(value) => {
+                DynamicDeserializer::from_dynamic(value).deserialize_any(visitor)
+            }
+
             #[cfg(not(feature = "no_closure"))]
             Union::Shared(_) => self.type_error(),
         }
@@ -1,8 +1,8 @@
 //! Configuration settings for `Engine`.
 
 use crate::engine::Engine;
-use crate::packages::PackageLibrary;
-use crate::token::{is_valid_identifier, Token};
+use crate::packages::{arithmetic::DivByZero, PackageLibrary};
+use crate::token::{is_reserved_word, is_valid_identifier, Token, BINARY_OPERATORS};
 
 #[cfg(not(feature = "no_module"))]
 use crate::module::ModuleResolver;
@@ -10,6 +10,9 @@ use crate::module::ModuleResolver;
 #[cfg(not(feature = "no_optimize"))]
 use crate::optimize::OptimizationLevel;
 
+#[cfg(not(feature = "no_float"))]
+use crate::packages::arithmetic::FloatDivByZero;
+
 use crate::stdlib::{format, string::String};
 
 #[cfg(not(feature = "no_module"))]
@@ -45,6 +48,181 @@ impl Engine {
         self.optimization_level
     }
 
+    /// Control whether a destructuring `let` statement raises an error when there is no value
+    /// to bind to a variable (i.e. an array that is too short or a missing object map property).
+    ///
+    /// If not enabled (the default), such variables are bound to `()`.
+    pub fn set_strict_destructuring(&mut self, enable: bool) -> &mut Self {
+        self.strict_destructuring = enable;
+        self
+    }
+
+    /// Does a destructuring `let` statement raise an error when there is no value to bind?
+    pub fn strict_destructuring(&self) -> bool {
+        self.strict_destructuring
+    }
+
+    /// Control whether reading a variable that is not defined raises an error.
+    ///
+    /// If not enabled, reading an undefined variable returns `()` instead.
+    /// Reading an undefined variable qualified by a module path is always an error.
+    /// Assigning to an undefined variable is controlled separately by
+    /// `Engine::set_fail_on_undefined_var_assign`.  The default is to raise an error.
+    pub fn set_fail_on_undefined_var(&mut self, enable: bool) -> &mut Self {
+        self.fail_on_undefined_var = enable;
+        self
+    }
+
+    /// Does reading a variable that is not defined raise an error?
+    pub fn fail_on_undefined_var(&self) -> bool {
+        self.fail_on_undefined_var
+    }
+
+    /// Control whether assigning to a variable that is not defined raises an error.
+    ///
+    /// If not enabled, assigning to an undefined variable defines it, just like a `let` statement.  Compound assignments (e.g. `x += 1`) to an undefined variable
+    /// are always an error.  The default is to raise an error.
+    pub fn set_fail_on_undefined_var_assign(&mut self, enable: bool) -> &mut Self {
+        self.fail_on_undefined_var_assign = enable;
+        self
+    }
+
+    /// Does assigning to a variable that is not defined raise an error?
+    pub fn fail_on_undefined_var_assign(&self) -> bool {
+        self.fail_on_undefined_var_assign
+    }
+
+    /// Control whether scripts may call `eval` to run script text built at runtime.
+    ///
+    /// If not allowed, calling `eval` raises a runtime error. The default is to allow `eval`.
+    pub fn set_allow_eval(&mut self, enable: bool) -> &mut Self {
+        self.allow_eval = enable;
+        self
+    }
+
+    /// Can scripts call `eval`?
+    pub fn allow_eval(&self) -> bool {
+        self.allow_eval
+    }
+
+    /// Control whether words that are reserved for future use, but not yet used by Rhai
+    /// (e.g. `var`, `match`, `yield`), may be used as identifiers.
+    ///
+    /// Active keywords (e.g. `let`, `switch`) can never be used as identifiers.
+    /// The default is to reject all reserved words.
+    pub fn set_allow_reserved_identifiers(&mut self, enable: bool) -> &mut Self {
+        self.allow_reserved_identifiers = enable;
+        self
+    }
+
+    /// Can words that are reserved for future use be used as identifiers?
+    pub fn allow_reserved_identifiers(&self) -> bool {
+        self.allow_reserved_identifiers
+    }
+
+    /// Control whether an operator falls back to calling the method named after it
+    /// (e.g. `add` for `+`) when no function is found for the operator and its operand types.
+    ///
+    /// Operators that work on their operands, including all built-in operators, are never
+    /// affected.  The default is not to fall back.
+    pub fn set_operator_method_fallback(&mut self, enable: bool) -> &mut Self {
+        self.operator_method_fallback = enable;
+        self
+    }
+
+    /// Do operators fall back to calling the method named after them?
+    pub fn operator_method_fallback(&self) -> bool {
+        self.operator_method_fallback
+    }
+
+    /// Is a word a keyword, active or reserved, that cannot be used as an identifier?
+    ///
+    /// Custom keywords registered via `register_custom_operator` are also included.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::Engine;
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// assert!(engine.is_reserved_keyword("switch"));
+    /// assert!(engine.is_reserved_keyword("var"));
+    /// assert!(!engine.is_reserved_keyword("foo"));
+    ///
+    /// engine.set_allow_reserved_identifiers(true);
+    ///
+    /// assert!(engine.is_reserved_keyword("switch"));
+    /// assert!(!engine.is_reserved_keyword("var"));
+    /// ```
+    pub fn is_reserved_keyword(&self, keyword: &str) -> bool {
+        if self
+            .custom_keywords
+            .as_ref()
+            .map(|k| k.contains_key(keyword))
+            .unwrap_or(false)
+        {
+            return is_valid_identifier(keyword.chars());
+        }
+
+        if self.allow_reserved_identifiers && is_reserved_word(keyword) {
+            return false;
+        }
+
+        Token::lookup_from_syntax(keyword)
+            .map(|token| token.is_reserved_keyword())
+            .unwrap_or(false)
+    }
+
+    /// Set the behavior of integer division (`/`) and modulo (`%`) by zero.
+    ///
+    /// The default is `DivByZero::Error`, which raises an arithmetic error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{DivByZero, Engine};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// assert!(engine.eval::<i64>("42 / 0").is_err());
+    ///
+    /// engine.set_div_by_zero(DivByZero::Zero);
+    ///
+    /// assert_eq!(engine.eval::<i64>("42 / 0")?, 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_div_by_zero(&mut self, behavior: DivByZero) -> &mut Self {
+        self.div_by_zero = behavior;
+        self
+    }
+
+    /// The behavior of integer division (`/`) and modulo (`%`) by zero.
+    pub fn div_by_zero(&self) -> DivByZero {
+        self.div_by_zero
+    }
+
+    /// Set the behavior of floating-point division (`/`) and modulo (`%`) by zero.
+    ///
+    /// The default is `FloatDivByZero::Infinity`, which follows IEEE 754.
+    ///
+    /// Not available under the `no_float` feature.
+    #[cfg(not(feature = "no_float"))]
+    pub fn set_float_div_by_zero(&mut self, behavior: FloatDivByZero) -> &mut Self {
+        self.float_div_by_zero = behavior;
+        self
+    }
+
+    /// The behavior of floating-point division (`/`) and modulo (`%`) by zero.
+    ///
+    /// Not available under the `no_float` feature.
+    #[cfg(not(feature = "no_float"))]
+    pub fn float_div_by_zero(&self) -> FloatDivByZero {
+        self.float_div_by_zero
+    }
+
     /// Set the maximum levels of function calls allowed for a script in order to avoid
     /// infinite recursion and stack overflows.
     #[cfg(not(feature = "unchecked"))]
@@ -90,6 +268,22 @@ impl Engine {
         self.limits.max_modules
     }
 
+    /// Set the maximum number of parameters of a function (0 for unlimited).
+    ///
+    /// Script functions with more parameters fail to compile, and registering a native Rust function
+    /// with more parameters panics.  Defaults to 20.
+    #[cfg(not(feature = "unchecked"))]
+    pub fn set_max_fn_params(&mut self, max_params: usize) -> &mut Self {
+        self.limits.max_fn_params = max_params;
+        self
+    }
+
+    /// The maximum number of parameters of a function (0 for unlimited).
+    #[cfg(not(feature = "unchecked"))]
+    pub fn max_fn_params(&self) -> usize {
+        self.limits.max_fn_params
+    }
+
     /// Set the depth limits for expressions (0 for unlimited).
     #[cfg(not(feature = "unchecked"))]
     pub fn set_max_expr_depths(
@@ -165,6 +359,50 @@ impl Engine {
         self.limits.max_map_size
     }
 
+    /// Set the maximum nesting depth of arrays and object maps (0 for unlimited).
+    ///
+    /// Deeply-nested data is processed recursively (e.g. when it is cloned, printed or dropped),
+    /// so an unlimited depth allows a malicious script to overflow the native stack.
+    #[cfg(not(feature = "unchecked"))]
+    #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+    pub fn set_max_data_depth(&mut self, max_depth: usize) -> &mut Self {
+        self.limits.max_data_depth = if max_depth == usize::MAX {
+            0
+        } else {
+            max_depth
+        };
+        self
+    }
+
+    /// The maximum nesting depth of arrays and object maps (0 for unlimited).
+    #[cfg(not(feature = "unchecked"))]
+    #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+    pub fn max_data_depth(&self) -> usize {
+        self.limits.max_data_depth
+    }
+
+    /// Set the maximum estimated memory usage (in bytes) of all variables, in all function calls
+    /// (0 for unlimited).
+    ///
+    /// Memory usage is only an approximation, updated whenever a variable is created, assigned,
+    /// grows or goes out of scope.  It does not track actual allocations.
+    #[cfg(not(feature = "unchecked"))]
+    pub fn set_max_memory(&mut self, max_memory: usize) -> &mut Self {
+        self.limits.max_memory = if max_memory == usize::MAX {
+            0
+        } else {
+            max_memory
+        };
+        self
+    }
+
+    /// The maximum estimated memory usage (in bytes) of all variables, in all function calls
+    /// (0 for unlimited).
+    #[cfg(not(feature = "unchecked"))]
+    pub fn max_memory(&self) -> usize {
+        self.limits.max_memory
+    }
+
     /// Set the module resolution service used by the `Engine`.
     ///
     /// Not available under the `no_module` feature.
@@ -290,4 +528,119 @@ impl Engine {
 
         Ok(self)
     }
+
+    /// Get the precedence of a binary operator, including custom operators.
+    ///
+    /// A higher precedence binds tighter.  Assignment operators (e.g. `=`, `+=`) have a precedence of zero.
+    ///
+    /// Returns `None` if the symbol is not a binary operator, or if it is disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rhai::Engine;
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.register_custom_operator("foo", 160).unwrap();
+    ///
+    /// assert_eq!(engine.operator_precedence("+"), Some(150));
+    /// assert_eq!(engine.operator_precedence("*"), Some(180));
+    /// assert_eq!(engine.operator_precedence("foo"), Some(160));
+    /// assert_eq!(engine.operator_precedence("="), Some(0));
+    /// assert_eq!(engine.operator_precedence("!"), None);
+    /// ```
+    pub fn operator_precedence(&self, symbol: &str) -> Option<u8> {
+        self.lookup_operator(symbol)
+            .map(|token| token.precedence(self.custom_keywords.as_ref()))
+    }
+
+    /// Does a binary operator bind to the right (i.e. is it right-associative)?
+    ///
+    /// Assignment operators (e.g. `=`, `+=`) and property access (`.`) bind to the right.
+    /// All other operators, including custom operators, bind to the left.
+    ///
+    /// Returns `None` if the symbol is not a binary operator, or if it is disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rhai::Engine;
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// assert_eq!(engine.is_operator_bind_right("="), Some(true));
+    /// assert_eq!(engine.is_operator_bind_right("+"), Some(false));
+    /// assert_eq!(engine.is_operator_bind_right("hello"), None);
+    /// ```
+    pub fn is_operator_bind_right(&self, symbol: &str) -> Option<bool> {
+        self.lookup_operator(symbol)
+            .map(|token| token.is_bind_right())
+    }
+
+    /// Get an iterator over all the binary operators, including custom operators, in no particular order.
+    ///
+    /// Each item is a tuple of the operator symbol, its precedence, and whether it binds to the right.
+    /// Disabled operators are not included.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rhai::Engine;
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.register_custom_operator("foo", 160).unwrap();
+    ///
+    /// let mut operators: Vec<_> = engine.iter_operators().collect();
+    /// operators.sort_by_key(|&(_, precedence, _)| precedence);
+    ///
+    /// assert!(operators.contains(&("foo", 160, false)));
+    /// assert!(operators.contains(&("=", 0, true)));
+    /// ```
+    pub fn iter_operators(&self) -> impl Iterator<Item = (&str, u8, bool)> {
+        let custom = self
+            .custom_keywords
+            .iter()
+            .flat_map(|keywords| keywords.keys())
+            .map(String::as_str);
+
+        BINARY_OPERATORS
+            .iter()
+            .cloned()
+            .chain(custom)
+            .filter_map(move |symbol| {
+                self.lookup_operator(symbol).map(|token| {
+                    (
+                        symbol,
+                        token.precedence(self.custom_keywords.as_ref()),
+                        token.is_bind_right(),
+                    )
+                })
+            })
+    }
+
+    /// Map a symbol into the token of an active binary operator.
+    fn lookup_operator(&self, symbol: &str) -> Option<Token> {
+        // Custom operators (but not custom syntax keywords, which have zero precedence)
+        if let Some(&precedence) = self.custom_keywords.as_ref().and_then(|c| c.get(symbol)) {
+            return if precedence > 0 {
+                Some(Token::Custom(symbol.into()))
+            } else {
+                None
+            };
+        }
+
+        if self
+            .disabled_symbols
+            .as_ref()
+            .map(|d| d.contains(symbol))
+            .unwrap_or(false)
+            || !BINARY_OPERATORS.contains(&symbol)
+        {
+            return None;
+        }
+
+        Token::lookup_from_syntax(symbol)
+    }
 }
@@ -18,7 +18,7 @@ use crate::stdlib::{
     char,
     collections::HashMap,
     fmt, format,
-    iter::Peekable,
+    iter::{once, Peekable},
     str::{Chars, FromStr},
     string::{String, ToString},
 };
@@ -29,12 +29,17 @@ pub type TokenStream<'a, 't> = Peekable<TokenIterator<'a, 't>>;
 
 /// A location (line number + character position) in the input script.
 ///
+/// The character position counts _characters_, not bytes or display columns - e.g. a tab is
+/// one character regardless of how wide it is displayed.  The byte offset of a position, which is
+/// unambiguous, can be found from the script text via `Position::offset_in`.
+///
 /// # Limitations
 ///
 /// In order to keep footprint small, both line number and character position have 16-bit resolution,
 /// meaning they go up to a maximum of 65,535 lines and 65,535 characters per line.
 ///
 /// Advancing beyond the maximum line length or maximum number of lines is not an error but has no effect.
+/// The byte offset is not available for positions beyond these limits.
 #[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy)]
 pub struct Position {
     /// Line number - 0 = none
@@ -79,6 +84,37 @@ impl Position {
         }
     }
 
+    /// Get the byte offset (0-based) of this position in the script text it comes from,
+    /// or `None` if there is no such position in the text.
+    ///
+    /// The byte offset is not kept in the `Position`, so the script text must be provided.
+    /// For a script compiled from multiple segments, provide the segments concatenated.
+    /// When a source pre-processor is used, positions (and offsets) refer to the original script.
+    pub fn offset_in(&self, script: &str) -> Option<usize> {
+        if self.is_none() || self.line == u16::MAX || self.pos == u16::MAX {
+            return None;
+        }
+
+        let mut line_start = 0;
+
+        for _ in 1..self.line {
+            line_start += script[line_start..].find('\n')? + 1;
+        }
+
+        let line = &script[line_start..];
+        let line = &line[..line.find('\n').unwrap_or(line.len())];
+
+        match self.pos {
+            0 => Some(line_start),
+            pos => line
+                .char_indices()
+                .map(|(i, _)| i)
+                .chain(once(line.len()))
+                .nth(pos as usize - 1)
+                .map(|i| line_start + i),
+        }
+    }
+
     /// Advance by one character position.
     pub(crate) fn advance(&mut self) {
         assert!(!self.is_none(), "cannot advance Position::none");
@@ -144,6 +180,119 @@ impl fmt::Debug for Position {
     }
 }
 
+/// A mapping from positions in a pre-processed script back to positions in the original script.
+///
+/// Returned by the source pre-processor registered via `Engine::set_source_preprocessor`.
+///
+/// The map is a list of anchors, each mapping a position in the pre-processed script to a position
+/// in the original script.  A position is translated using the nearest anchor at or before it:
+///
+/// * on the same line as the anchor, the position keeps the same distance from the anchor;
+/// * on a later line, the line keeps the same distance from the anchor and the character position
+///   is unchanged.
+///
+/// Positions before the first anchor are not translated.
+///
+/// # Example
+///
+/// ```
+/// use rhai::{Position, SourceMap};
+///
+/// let mut map = SourceMap::new();
+///
+/// // Line 1 of the pre-processed script was line 3 of the original script
+/// map.add(Position::new(1, 1), Position::new(3, 1));
+///
+/// assert_eq!(map.translate(Position::new(1, 5)), Position::new(3, 5));
+/// assert_eq!(map.translate(Position::new(2, 8)), Position::new(4, 8));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap(Vec<(Position, Position)>);
+
+impl SourceMap {
+    /// Create a new, empty `SourceMap`.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add an anchor mapping a position in the pre-processed script to a position in the original script.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either position is `Position::none()`.
+    pub fn add(&mut self, transformed: Position, original: Position) -> &mut Self {
+        assert!(!transformed.is_none(), "cannot map Position::none");
+        assert!(!original.is_none(), "cannot map to Position::none");
+
+        let index = self.0.iter().take_while(|(t, _)| *t <= transformed).count();
+        self.0.insert(index, (transformed, original));
+        self
+    }
+
+    /// Translate a position in the pre-processed script into a position in the original script.
+    pub fn translate(&self, pos: Position) -> Position {
+        if pos.is_none() {
+            return pos;
+        }
+
+        let (transformed, original) = match self.0.iter().rev().find(|(t, _)| *t <= pos) {
+            Some(anchor) => anchor,
+            None => return pos,
+        };
+
+        if pos.line == transformed.line {
+            Position::new(
+                original.line,
+                original.pos.saturating_add(pos.pos - transformed.pos),
+            )
+        } else {
+            Position::new(
+                original.line.saturating_add(pos.line - transformed.line),
+                pos.pos,
+            )
+        }
+    }
+}
+
+/// Symbols of all the built-in binary operators, including assignments.
+pub(crate) const BINARY_OPERATORS: &[&str] = &[
+    "=",
+    "+=",
+    "-=",
+    "*=",
+    "/=",
+    "%=",
+    "~=",
+    "<<=",
+    ">>=",
+    "&=",
+    "|=",
+    "^=",
+    "||",
+    "|",
+    "^",
+    "&&",
+    "&",
+    "==",
+    "!=",
+    "<",
+    "<=",
+    ">",
+    ">=",
+    "in",
+    "..",
+    "+",
+    "-",
+    "*",
+    "/",
+    "%",
+    "~",
+    "<<",
+    ">>",
+    #[cfg(not(feature = "no_object"))]
+    ".",
+];
+
 /// [INTERNALS] A Rhai language token.
 /// Exported under the `internals` feature only.
 ///
@@ -159,6 +308,9 @@ pub enum Token {
     /// Reserved under the `no_float` feature.
     #[cfg(not(feature = "no_float"))]
     FloatConstant(FLOAT),
+    /// The original text of a number literal, produced instead of `IntegerConstant` and
+    /// `FloatConstant` when `TokenizeState::raw_numbers` is set.
+    NumberLiteral(String),
     /// An identifier.
     Identifier(String),
     /// A character constant.
@@ -207,6 +359,10 @@ pub enum Token {
     Comma,
     /// `.`
     Period,
+    /// `..`
+    ExclusiveRange,
+    /// `...`
+    Ellipsis,
     /// `#{`
     MapStart,
     /// `=`
@@ -231,6 +387,12 @@ pub enum Token {
     For,
     /// `in`
     In,
+    /// `switch`
+    Switch,
+    /// `=>`
+    DoubleArrow,
+    /// `_`
+    Underscore,
     /// `<`
     LessThan,
     /// `>`
@@ -268,6 +430,10 @@ pub enum Token {
     Return,
     /// `throw`
     Throw,
+    /// `try`
+    Try,
+    /// `catch`
+    Catch,
     /// `+=`
     PlusAssign,
     /// `-=`
@@ -314,6 +480,8 @@ pub enum Token {
     LexError(Box<LexError>),
     /// A comment block.
     Comment(String),
+    /// A doc-comment line (i.e. starting with `///`), including the leading `///`.
+    DocComment(String),
     /// A reserved symbol.
     Reserved(String),
     /// A custom keyword.
@@ -331,11 +499,13 @@ impl Token {
             IntegerConstant(i) => i.to_string().into(),
             #[cfg(not(feature = "no_float"))]
             FloatConstant(f) => f.to_string().into(),
+            NumberLiteral(s) => s.clone().into(),
             StringConstant(_) => "string".into(),
             CharConstant(c) => c.to_string().into(),
             Identifier(s) => s.clone().into(),
             Reserved(s) => s.clone().into(),
             Custom(s) => s.clone().into(),
+            Comment(s) | DocComment(s) => s.clone().into(),
             LexError(err) => err.to_string().into(),
 
             token => match token {
@@ -356,6 +526,8 @@ impl Token {
                 DoubleColon => "::",
                 Comma => ",",
                 Period => ".",
+                ExclusiveRange => "..",
+                Ellipsis => "...",
                 MapStart => "#{",
                 Equals => "=",
                 True => "true",
@@ -368,6 +540,9 @@ impl Token {
                 Loop => "loop",
                 For => "for",
                 In => "in",
+                Switch => "switch",
+                DoubleArrow => "=>",
+                Underscore => "_",
                 LessThan => "<",
                 GreaterThan => ">",
                 Bang => "!",
@@ -383,6 +558,8 @@ impl Token {
                 Break => "break",
                 Return => "return",
                 Throw => "throw",
+                Try => "try",
+                Catch => "catch",
                 PlusAssign => "+=",
                 MinusAssign => "-=",
                 MultiplyAssign => "*=",
@@ -438,6 +615,8 @@ impl Token {
             "::" => DoubleColon,
             "," => Comma,
             "." => Period,
+            ".." => ExclusiveRange,
+            "..." => Ellipsis,
             "#{" => MapStart,
             "=" => Equals,
             "true" => True,
@@ -448,6 +627,9 @@ impl Token {
             "else" => Else,
             "while" => While,
             "loop" => Loop,
+            "switch" => Switch,
+            "=>" => DoubleArrow,
+            "_" => Underscore,
             "for" => For,
             "in" => In,
             "<" => LessThan,
@@ -465,6 +647,8 @@ impl Token {
             "break" => Break,
             "return" => Return,
             "throw" => Throw,
+            "try" => Try,
+            "catch" => Catch,
             "+=" => PlusAssign,
             "-=" => MinusAssign,
             "*=" => MultiplyAssign,
@@ -500,11 +684,11 @@ impl Token {
             #[cfg(feature = "no_module")]
             "import" | "export" | "as" => Reserved(syntax.into()),
 
-            "===" | "!==" | "->" | "<-" | "=>" | ":=" | "::<" | "(*" | "*)" | "#" | "public"
-            | "new" | "use" | "module" | "package" | "var" | "static" | "shared" | "with"
-            | "do" | "each" | "then" | "goto" | "exit" | "switch" | "match" | "case" | "try"
-            | "catch" | "default" | "void" | "null" | "nil" | "spawn" | "go" | "sync" | "async"
-            | "await" | "yield" => Reserved(syntax.into()),
+            "===" | "!==" | "->" | "<-" | ":=" | "::<" | "(*" | "*)" | "#" => {
+                Reserved(syntax.into())
+            }
+
+            s if is_reserved_word(s) => Reserved(syntax.into()),
 
             KEYWORD_PRINT | KEYWORD_DEBUG | KEYWORD_TYPE_OF | KEYWORD_EVAL | KEYWORD_FN_PTR
             | KEYWORD_FN_PTR_CALL | KEYWORD_FN_PTR_CURRY | KEYWORD_IS_SHARED | KEYWORD_THIS => {
@@ -546,6 +730,8 @@ impl Token {
             Divide           |
             Comma            |
             Period           |
+            ExclusiveRange   | // ..-expr - is unary
+            Ellipsis         | // ...-expr - is unary
             Equals           |
             LessThan         |
             GreaterThan      |
@@ -560,6 +746,8 @@ impl Token {
             And              |
             If               |
             While            |
+            Switch           |
+            DoubleArrow      | // => -expr - is unary
             PlusAssign       |
             MinusAssign      |
             MultiplyAssign   |
@@ -604,6 +792,8 @@ impl Token {
 
             In => 130,
 
+            ExclusiveRange => 140,
+
             Plus | Minus => 150,
 
             Divide | Multiply | PowerOf | Modulo => 180,
@@ -643,11 +833,12 @@ impl Token {
         match self {
             LeftBrace | RightBrace | LeftParen | RightParen | LeftBracket | RightBracket | Plus
             | UnaryPlus | Minus | UnaryMinus | Multiply | Divide | Modulo | PowerOf | LeftShift
-            | RightShift | SemiColon | Colon | DoubleColon | Comma | Period | MapStart | Equals
-            | LessThan | GreaterThan | LessThanEqualsTo | GreaterThanEqualsTo | EqualsTo
-            | NotEqualsTo | Bang | Pipe | Or | XOr | Ampersand | And | PlusAssign | MinusAssign
-            | MultiplyAssign | DivideAssign | LeftShiftAssign | RightShiftAssign | AndAssign
-            | OrAssign | XOrAssign | ModuloAssign | PowerOfAssign => true,
+            | RightShift | SemiColon | Colon | DoubleColon | Comma | Period | ExclusiveRange
+            | Ellipsis | DoubleArrow | MapStart | Equals | LessThan | GreaterThan
+            | LessThanEqualsTo | GreaterThanEqualsTo | EqualsTo | NotEqualsTo | Bang | Pipe
+            | Or | XOr | Ampersand | And | PlusAssign | MinusAssign | MultiplyAssign
+            | DivideAssign | LeftShiftAssign | RightShiftAssign | AndAssign | OrAssign
+            | XOrAssign | ModuloAssign | PowerOfAssign => true,
 
             _ => false,
         }
@@ -664,13 +855,21 @@ impl Token {
             #[cfg(not(feature = "no_module"))]
             Import | Export | As => true,
 
-            True | False | Let | Const | If | Else | While | Loop | For | In | Continue | Break
-            | Return | Throw => true,
+            True | False | Let | Const | If | Else | While | Loop | For | In | Switch
+            | Continue | Break | Return | Throw | Try | Catch => true,
 
             _ => false,
         }
     }
 
+    /// Is this token a keyword, active or reserved, that cannot be used as an identifier?
+    pub fn is_reserved_keyword(&self) -> bool {
+        match self {
+            Self::Reserved(s) => is_valid_identifier(s.chars()),
+            token => token.is_keyword(),
+        }
+    }
+
     /// Is this token a reserved symbol?
     pub fn is_reserved(&self) -> bool {
         match self {
@@ -722,6 +921,45 @@ pub struct TokenizeState {
     pub end_with_none: bool,
     /// Include comments?
     pub include_comments: bool,
+    /// Keep the original text of number literals in `Token::NumberLiteral`?
+    pub raw_numbers: bool,
+}
+
+/// Parse the text of a number literal into an `IntegerConstant` or `FloatConstant` token.
+/// Returns `None` if the number is malformed or out of range.
+pub(crate) fn parse_number_literal(text: &str) -> Option<Token> {
+    let (sign, digits) = if text.starts_with('-') {
+        ("-", &text[1..])
+    } else {
+        ("", text)
+    };
+
+    let radix = match digits.get(..2) {
+        Some("0x") | Some("0X") => Some(16),
+        Some("0o") | Some("0O") => Some(8),
+        Some("0b") | Some("0B") => Some(2),
+        _ => None,
+    };
+
+    if let Some(radix) = radix {
+        let out: String = sign
+            .chars()
+            .chain(digits[2..].chars().filter(|&c| c != '_'))
+            .collect();
+
+        INT::from_str_radix(&out, radix)
+            .ok()
+            .map(Token::IntegerConstant)
+    } else {
+        let out: String = text.chars().filter(|&c| c != '_').collect();
+        let num = INT::from_str(&out).map(Token::IntegerConstant);
+
+        // If integer parsing is unnecessary, try float instead
+        #[cfg(not(feature = "no_float"))]
+        let num = num.or_else(|_| FLOAT::from_str(&out).map(Token::FloatConstant));
+
+        num.ok()
+    }
 }
 
 /// [INTERNALS] Trait that encapsulates a peekable character input stream.
@@ -873,6 +1111,91 @@ pub fn parse_string_literal(
     Ok(s)
 }
 
+/// [INTERNALS] Parse a raw string literal in the form `r"..."` or `r#"..."#`.
+/// The leading `r` must already have been consumed.
+/// Exported under the `internals` feature only.
+///
+/// Escape sequences are not processed and the literal may span multiple lines.
+/// The literal is only terminated by a `"` followed by the same number of `#`'s
+/// as in the opening delimiter.
+///
+/// ## WARNING
+///
+/// This type is volatile and may change.
+pub fn parse_raw_string_literal(
+    stream: &mut impl InputStream,
+    state: &mut TokenizeState,
+    pos: &mut Position,
+) -> Result<String, (LexError, Position)> {
+    let start = *pos;
+
+    // Count the number of opening #'s
+    let mut hashes = 0;
+
+    loop {
+        match stream.get_next() {
+            Some('#') => {
+                pos.advance();
+                hashes += 1;
+            }
+            Some('"') => {
+                pos.advance();
+                break;
+            }
+            Some(ch) => {
+                pos.advance();
+                let mut seq: String = "r".into();
+                (0..hashes).for_each(|_| seq.push('#'));
+                seq.push(ch);
+                return Err((LERR::UnexpectedInput(seq), start));
+            }
+            None => return Err((LERR::UnterminatedString, start)),
+        }
+    }
+
+    let mut result = String::new();
+
+    loop {
+        let next_char = stream.get_next().ok_or((LERR::UnterminatedString, start))?;
+
+        match next_char {
+            '\n' => pos.new_line(),
+            _ => pos.advance(),
+        }
+
+        if state.max_string_size > 0 && result.len() > state.max_string_size {
+            return Err((LexError::StringTooLong(state.max_string_size), *pos));
+        }
+
+        if next_char != '"' {
+            result.push(next_char);
+            continue;
+        }
+
+        // Try to match the closing #'s
+        let mut matched = 0;
+
+        while matched < hashes && stream.peek_next() == Some('#') {
+            eat_next(stream, pos);
+            matched += 1;
+        }
+
+        if matched == hashes {
+            break;
+        }
+
+        // Not enough #'s - they are part of the string
+        result.push('"');
+        (0..matched).for_each(|_| result.push('#'));
+    }
+
+    if state.max_string_size > 0 && result.len() > state.max_string_size {
+        return Err((LexError::StringTooLong(state.max_string_size), *pos));
+    }
+
+    Ok(result)
+}
+
 /// Consume the next character.
 fn eat_next(stream: &mut impl InputStream, pos: &mut Position) -> Option<char> {
     pos.advance();
@@ -893,30 +1216,23 @@ fn scan_comment(
             comment.push(c);
         }
 
-        match c {
-            '/' => {
-                if let Some(c2) = stream.get_next() {
-                    if state.include_comments {
-                        comment.push(c2);
-                    }
-                    if c2 == '*' {
-                        state.comment_level += 1;
-                    }
+        match (c, stream.peek_next().unwrap_or('\0')) {
+            // Nested block comment
+            ('/', '*') => {
+                eat_next(stream, pos);
+                if state.include_comments {
+                    comment.push('*');
                 }
-                pos.advance();
+                state.comment_level += 1;
             }
-            '*' => {
-                if let Some(c2) = stream.get_next() {
-                    if state.include_comments {
-                        comment.push(c2);
-                    }
-                    if c2 == '/' {
-                        state.comment_level -= 1;
-                    }
+            ('*', '/') => {
+                eat_next(stream, pos);
+                if state.include_comments {
+                    comment.push('/');
                 }
-                pos.advance();
+                state.comment_level -= 1;
             }
-            '\n' => pos.new_line(),
+            ('\n', _) => pos.new_line(),
             _ => (),
         }
 
@@ -940,8 +1256,10 @@ pub fn get_next_token(
     let result = get_next_token_inner(stream, state, pos);
 
     // Save the last token's state
-    if let Some((ref token, _)) = result {
-        state.non_unary = !token.is_next_unary();
+    match result {
+        Some((Token::DocComment(_), _)) => (),
+        Some((ref token, _)) => state.non_unary = !token.is_next_unary(),
+        None => (),
     }
 
     result
@@ -1013,8 +1331,9 @@ fn get_next_token_inner(
                             result.push(next_char);
                             eat_next(stream, pos);
                         }
-                        #[cfg(not(feature = "no_float"))]
-                        '.' => {
+                        // Number literals with decimal points are kept for the number literal
+                        // handler even without floating-point support
+                        '.' if cfg!(not(feature = "no_float")) || state.raw_numbers => {
                             stream.get_next().unwrap();
 
                             // Check if followed by digits (or _)
@@ -1062,7 +1381,8 @@ fn get_next_token_inner(
                             });
 
                             while let Some(next_char_in_escape_seq) = stream.peek_next() {
-                                if !valid(next_char_in_escape_seq) {
+                                if next_char_in_escape_seq != '_' && !valid(next_char_in_escape_seq)
+                                {
                                     break;
                                 }
 
@@ -1075,41 +1395,51 @@ fn get_next_token_inner(
                     }
                 }
 
+                // Underscores are only allowed between digits
+                let digits = if radix_base.is_some() {
+                    &result[2..]
+                } else {
+                    &result[..]
+                };
+
+                let misplaced_underscore = digits.first() == Some(&'_')
+                    || digits.last() == Some(&'_')
+                    || digits
+                        .windows(2)
+                        .any(|w| (w[0] == '_' && w[1] == '.') || (w[0] == '.' && w[1] == '_'));
+
                 if negated {
                     result.insert(0, '-');
                 }
 
-                // Parse number
-                if let Some(radix) = radix_base {
-                    let out: String = result.iter().skip(2).filter(|&&c| c != '_').collect();
-
+                if misplaced_underscore {
                     return Some((
-                        INT::from_str_radix(&out, radix)
-                            .map(Token::IntegerConstant)
-                            .unwrap_or_else(|_| {
-                                Token::LexError(Box::new(LERR::MalformedNumber(
-                                    result.into_iter().collect(),
-                                )))
-                            }),
+                        Token::LexError(Box::new(LERR::MalformedNumber(
+                            result.into_iter().collect(),
+                        ))),
                         start_pos,
                     ));
-                } else {
-                    let out: String = result.iter().filter(|&&c| c != '_').collect();
-                    let num = INT::from_str(&out).map(Token::IntegerConstant);
+                }
 
-                    // If integer parsing is unnecessary, try float instead
-                    #[cfg(not(feature = "no_float"))]
-                    let num = num.or_else(|_| FLOAT::from_str(&out).map(Token::FloatConstant));
+                let text: String = result.into_iter().collect();
 
-                    return Some((
-                        num.unwrap_or_else(|_| {
-                            Token::LexError(Box::new(LERR::MalformedNumber(
-                                result.into_iter().collect(),
-                            )))
-                        }),
-                        start_pos,
-                    ));
+                if state.raw_numbers {
+                    return Some((Token::NumberLiteral(text), start_pos));
                 }
+
+                return Some((
+                    parse_number_literal(&text)
+                        .unwrap_or_else(|| Token::LexError(Box::new(LERR::MalformedNumber(text)))),
+                    start_pos,
+                ));
+            }
+
+            // r" or r#" - raw string literal
+            ('r', '"') | ('r', '#') => {
+                return parse_raw_string_literal(stream, state, pos).map_or_else(
+                    |err| Some((Token::LexError(Box::new(err.0)), err.1)),
+                    |out| Some((Token::StringConstant(out), start_pos)),
+                )
             }
 
             // letter or underscore ...
@@ -1210,7 +1540,10 @@ fn get_next_token_inner(
             ('/', '/') => {
                 eat_next(stream, pos);
 
-                let mut comment = if state.include_comments {
+                // Doc-comments (i.e. starting with exactly `///`) are always kept
+                let is_doc_comment = stream.peek_next() == Some('/');
+
+                let mut comment = if state.include_comments || is_doc_comment {
                     "//".to_string()
                 } else {
                     String::new()
@@ -1222,7 +1555,7 @@ fn get_next_token_inner(
                         break;
                     }
 
-                    if state.include_comments {
+                    if state.include_comments || is_doc_comment {
                         comment.push(c);
                     }
                     pos.advance();
@@ -1230,6 +1563,8 @@ fn get_next_token_inner(
 
                 if state.include_comments {
                     return Some((Token::Comment(comment), start_pos));
+                } else if is_doc_comment && !comment.starts_with("////") {
+                    return Some((Token::DocComment(comment), start_pos));
                 }
             }
             ('/', '*') => {
@@ -1244,6 +1579,15 @@ fn get_next_token_inner(
                 };
                 scan_comment(stream, state, pos, &mut comment);
 
+                // Unterminated block comment at the end of the script
+                if state.comment_level > 0 && !state.end_with_none {
+                    state.comment_level = 0;
+                    return Some((
+                        Token::LexError(Box::new(LERR::UnterminatedComment)),
+                        start_pos,
+                    ));
+                }
+
                 if state.include_comments {
                     return Some((Token::Comment(comment), start_pos));
                 }
@@ -1257,6 +1601,16 @@ fn get_next_token_inner(
 
             (';', _) => return Some((Token::SemiColon, start_pos)),
             (',', _) => return Some((Token::Comma, start_pos)),
+            ('.', '.') => {
+                eat_next(stream, pos);
+
+                if stream.peek_next() == Some('.') {
+                    eat_next(stream, pos);
+                    return Some((Token::Ellipsis, start_pos));
+                }
+
+                return Some((Token::ExclusiveRange, start_pos));
+            }
             ('.', _) => return Some((Token::Period, start_pos)),
 
             ('=', '=') => {
@@ -1272,7 +1626,7 @@ fn get_next_token_inner(
             }
             ('=', '>') => {
                 eat_next(stream, pos);
-                return Some((Token::Reserved("=>".into()), start_pos));
+                return Some((Token::DoubleArrow, start_pos));
             }
             ('=', _) => return Some((Token::Equals, start_pos)),
 
@@ -1433,7 +1787,11 @@ fn get_identifier(
 
     let is_valid_identifier = is_valid_identifier(result.iter().cloned());
 
-    let identifier = result.into_iter().collect();
+    let identifier: String = result.into_iter().collect();
+
+    if let Some(token) = Token::lookup_from_syntax(&identifier) {
+        return Some((token, start_pos));
+    }
 
     if !is_valid_identifier {
         return Some((
@@ -1442,10 +1800,18 @@ fn get_identifier(
         ));
     }
 
-    return Some((
-        Token::lookup_from_syntax(&identifier).unwrap_or_else(|| Token::Identifier(identifier)),
-        start_pos,
-    ));
+    return Some((Token::Identifier(identifier), start_pos));
+}
+
+/// Is this a word reserved as a keyword for future use, but not yet used by Rhai?
+#[inline(always)]
+pub fn is_reserved_word(name: &str) -> bool {
+    match name {
+        "public" | "new" | "use" | "module" | "package" | "var" | "static" | "shared" | "with"
+        | "do" | "each" | "then" | "goto" | "exit" | "match" | "case" | "default" | "void"
+        | "null" | "nil" | "spawn" | "go" | "sync" | "async" | "await" | "yield" => true,
+        _ => false,
+    }
 }
 
 /// Is this keyword allowed as a function?
@@ -1576,14 +1942,48 @@ pub struct TokenIterator<'a, 'e> {
     pos: Position,
     /// Input character stream.
     stream: MultiInputsStream<'a>,
+    /// Map positions back into the original script (if any).
+    source_map: Option<SourceMap>,
     /// A processor function (if any) that maps a token to another.
     map: Option<Box<dyn Fn(Token) -> Token>>,
+    /// Doc-comments waiting for the next token.
+    doc_comments: Vec<(Token, Position)>,
+    /// Tokens to return before reading the input stream, in reverse order.
+    queued: Vec<(Token, Position)>,
 }
 
 impl<'a> Iterator for TokenIterator<'a, '_> {
     type Item = (Token, Position);
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some(token) = self.queued.pop() {
+            return Some(token);
+        }
+
+        // Doc-comments are only returned right before a function definition, otherwise they are skipped
+        loop {
+            match self.next_token() {
+                Some(token @ (Token::DocComment(_), _)) => self.doc_comments.push(token),
+                #[cfg(not(feature = "no_function"))]
+                Some(token @ (Token::Fn, _)) | Some(token @ (Token::Private, _))
+                    if !self.doc_comments.is_empty() =>
+                {
+                    self.queued.push(token);
+                    self.queued.extend(self.doc_comments.drain(..).rev());
+                    return self.queued.pop();
+                }
+                token => {
+                    self.doc_comments.clear();
+                    return token;
+                }
+            }
+        }
+    }
+}
+
+impl<'a> TokenIterator<'a, '_> {
+    /// Get the next token from the input stream.
+    fn next_token(&mut self) -> Option<(Token, Position)> {
         let token = match (
             get_next_token(&mut self.stream, &mut self.state, &mut self.pos),
             self.engine.disabled_symbols.as_ref(),
@@ -1606,9 +2006,6 @@ impl<'a> Iterator for TokenIterator<'a, '_> {
                 ("<-", false) => Token::LexError(Box::new(LERR::ImproperSymbol(
                     "'<-' is not a valid symbol. This is not Go! Should it be '<='?".to_string(),
                 ))),
-                ("=>", false) => Token::LexError(Box::new(LERR::ImproperSymbol(
-                    "'=>' is not a valid symbol. This is not Rust! Should it be '>='?".to_string(),
-                ))),
                 (":=", false) => Token::LexError(Box::new(LERR::ImproperSymbol(
                     "':=' is not a valid assignment operator. This is not Go! Should it be simply '='?".to_string(),
                 ))),
@@ -1631,6 +2028,8 @@ impl<'a> Iterator for TokenIterator<'a, '_> {
                 (token, false) if disabled.map(|d| d.contains(token)).unwrap_or(false) => Token::LexError(Box::new(LERR::ImproperSymbol(
                     format!("reserved symbol '{}' is disabled", token)
                 ))),
+                // Reserved word that is allowed as an identifier.
+                (token, false) if self.engine.allow_reserved_identifiers && is_reserved_word(token) => Token::Identifier(s),
                 // Reserved keyword/operator that is not custom.
                 (_, false) => Token::Reserved(s),
             }, pos)),
@@ -1670,7 +2069,11 @@ impl<'a> Iterator for TokenIterator<'a, '_> {
 
         match token {
             None => None,
-            Some((token, pos)) => {
+            Some((token, mut pos)) => {
+                if let Some(ref source_map) = self.source_map {
+                    pos = source_map.translate(pos);
+                }
+
                 if let Some(ref map) = self.map {
                     Some((map(token), pos))
                 } else {
@@ -1686,6 +2089,18 @@ pub fn lex<'a, 'e>(
     input: &'a [&'a str],
     map: Option<Box<dyn Fn(Token) -> Token>>,
     engine: &'e Engine,
+) -> TokenIterator<'a, 'e> {
+    lex_with_source_map(input, None, map, engine)
+}
+
+/// Tokenize a pre-processed input text stream.
+///
+/// If a `SourceMap` is provided, all positions are mapped back into the original text.
+pub(crate) fn lex_with_source_map<'a, 'e>(
+    input: &'a [&'a str],
+    source_map: Option<SourceMap>,
+    map: Option<Box<dyn Fn(Token) -> Token>>,
+    engine: &'e Engine,
 ) -> TokenIterator<'a, 'e> {
     TokenIterator {
         engine,
@@ -1698,6 +2113,7 @@ pub fn lex<'a, 'e>(
             comment_level: 0,
             end_with_none: false,
             include_comments: false,
+            raw_numbers: engine.number_literal.is_some(),
         },
         pos: Position::new(1, 0),
         stream: MultiInputsStream {
@@ -1705,6 +2121,9 @@ pub fn lex<'a, 'e>(
             streams: input.iter().map(|s| s.chars().peekable()).collect(),
             index: 0,
         },
+        source_map,
         map,
+        doc_comments: Vec::new(),
+        queued: Vec::new(),
     }
 }
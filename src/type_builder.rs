@@ -0,0 +1,305 @@
+//! Module that defines the fluent builder API for registering a custom type and its methods.
+#![cfg(not(feature = "no_object"))]
+
+use crate::any::{Dynamic, Variant};
+use crate::engine::Engine;
+use crate::fn_native::{SendSync, Shared};
+use crate::fn_register::RegisterFn;
+use crate::result::EvalAltResult;
+
+use crate::stdlib::{
+    boxed::Box,
+    cmp::Ordering,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+/// Builder for registering functions, property getters/setters and indexers
+/// for a custom type `T`.
+///
+/// Returned by `Engine::register_type` and `Engine::register_type_with_name`.
+///
+/// All functions are registered with the `Engine` immediately.  Because the signatures of getters,
+/// setters and indexers are tied to `T`, registering an accessor with the wrong number
+/// of parameters, or on the wrong type, is a compile-time error.
+///
+/// The builder also dereferences to the `Engine`, so existing code that chains `Engine` methods
+/// after `register_type` still works.
+///
+/// # Example
+///
+/// ```
+/// #[derive(Debug, Clone, Eq, PartialEq)]
+/// struct TestStruct {
+///     field: i64
+/// }
+///
+/// impl TestStruct {
+///     fn new() -> Self                    { TestStruct { field: 1 } }
+///     fn update(&mut self, offset: i64)   { self.field += offset; }
+///     fn get_field(&mut self) -> i64      { self.field }
+///     fn set_field(&mut self, x: i64)     { self.field = x; }
+/// }
+///
+/// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+/// use rhai::Engine;
+///
+/// let mut engine = Engine::new();
+///
+/// engine
+///     .register_type::<TestStruct>()
+///     .with_fn("new_ts", TestStruct::new)
+///     .with_fn("update", TestStruct::update)
+///     .with_get_set("xyz", TestStruct::get_field, TestStruct::set_field);
+///
+/// assert_eq!(engine.eval::<i64>("let x = new_ts(); x.update(41); x.xyz")?, 42);
+/// # Ok(())
+/// # }
+/// ```
+pub struct TypeBuilder<'e, T: Variant + Clone> {
+    engine: &'e mut Engine,
+    _marker: PhantomData<T>,
+}
+
+impl<'e, T: Variant + Clone> TypeBuilder<'e, T> {
+    pub(crate) fn new(engine: &'e mut Engine) -> Self {
+        Self {
+            engine,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Register a function or method with the `Engine`.
+    ///
+    /// This is the same as `Engine::register_fn`.
+    pub fn with_fn<FN, ARGS, RET>(&mut self, name: &str, method: FN) -> &mut Self
+    where
+        Engine: RegisterFn<FN, ARGS, RET>,
+    {
+        self.engine.register_fn(name, method);
+        self
+    }
+
+    /// Register a getter function for a member of the type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the property name is empty.
+    pub fn with_get<U: Variant + Clone>(
+        &mut self,
+        name: &str,
+        callback: impl Fn(&mut T) -> U + SendSync + 'static,
+    ) -> &mut Self {
+        check_property_name(name);
+        self.engine.register_get(name, callback);
+        self
+    }
+
+    /// Register a getter function for a member of the type.
+    /// Returns `Result<Dynamic, Box<EvalAltResult>>`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the property name is empty.
+    pub fn with_get_result(
+        &mut self,
+        name: &str,
+        callback: impl Fn(&mut T) -> Result<Dynamic, Box<EvalAltResult>> + SendSync + 'static,
+    ) -> &mut Self {
+        check_property_name(name);
+        self.engine.register_get_result(name, callback);
+        self
+    }
+
+    /// Register a setter function for a member of the type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the property name is empty.
+    pub fn with_set<U: Variant + Clone>(
+        &mut self,
+        name: &str,
+        callback: impl Fn(&mut T, U) + SendSync + 'static,
+    ) -> &mut Self {
+        check_property_name(name);
+        self.engine.register_set(name, callback);
+        self
+    }
+
+    /// Register a setter function for a member of the type.
+    /// Returns `Result<(), Box<EvalAltResult>>`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the property name is empty.
+    pub fn with_set_result<U: Variant + Clone>(
+        &mut self,
+        name: &str,
+        callback: impl Fn(&mut T, U) -> Result<(), Box<EvalAltResult>> + SendSync + 'static,
+    ) -> &mut Self {
+        check_property_name(name);
+        self.engine.register_set_result(name, callback);
+        self
+    }
+
+    /// Short-hand for registering both getter and setter functions for a member of the type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the property name is empty.
+    pub fn with_get_set<U: Variant + Clone>(
+        &mut self,
+        name: &str,
+        get_fn: impl Fn(&mut T) -> U + SendSync + 'static,
+        set_fn: impl Fn(&mut T, U) + SendSync + 'static,
+    ) -> &mut Self {
+        self.with_get(name, get_fn).with_set(name, set_fn)
+    }
+
+    /// Register the comparison operators `==`, `!=`, `<`, `<=`, `>` and `>=` for the type,
+    /// based on its `PartialOrd` implementation.
+    ///
+    /// This makes the type usable with `sort`, `min`, `max` and in `switch` statements.
+    pub fn with_comparison(&mut self) -> &mut Self
+    where
+        T: PartialOrd,
+    {
+        self.with_fn("==", |x: T, y: T| x == y)
+            .with_fn("!=", |x: T, y: T| x != y)
+            .with_fn("<", |x: T, y: T| x < y)
+            .with_fn("<=", |x: T, y: T| x <= y)
+            .with_fn(">", |x: T, y: T| x > y)
+            .with_fn(">=", |x: T, y: T| x >= y)
+    }
+
+    /// Register the comparison operators `==`, `!=`, `<`, `<=`, `>` and `>=` for the type,
+    /// based on a comparator function.
+    ///
+    /// This makes the type usable with `sort`, `min`, `max` and in `switch` statements.
+    pub fn with_comparator(
+        &mut self,
+        comparator: impl Fn(&T, &T) -> Ordering + SendSync + 'static,
+    ) -> &mut Self {
+        let comparator = Shared::new(comparator);
+
+        let ops: [(&str, fn(Ordering) -> bool); 6] = [
+            ("==", |o| o == Ordering::Equal),
+            ("!=", |o| o != Ordering::Equal),
+            ("<", |o| o == Ordering::Less),
+            ("<=", |o| o != Ordering::Greater),
+            (">", |o| o == Ordering::Greater),
+            (">=", |o| o != Ordering::Less),
+        ];
+
+        for &(op, test) in ops.iter() {
+            let comparator = comparator.clone();
+            self.engine
+                .register_fn(op, move |x: T, y: T| test(comparator(&x, &y)));
+        }
+
+        self
+    }
+
+    /// Register an index getter for the type.
+    ///
+    /// Not available under the `no_index` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the type is `Array`, `Map` or `String`.
+    #[cfg(not(feature = "no_index"))]
+    pub fn with_indexer_get<X: Variant + Clone, U: Variant + Clone>(
+        &mut self,
+        callback: impl Fn(&mut T, X) -> U + SendSync + 'static,
+    ) -> &mut Self {
+        self.engine.register_indexer_get(callback);
+        self
+    }
+
+    /// Register an index getter for the type.
+    /// Returns `Result<Dynamic, Box<EvalAltResult>>`.
+    ///
+    /// Not available under the `no_index` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the type is `Array`, `Map` or `String`.
+    #[cfg(not(feature = "no_index"))]
+    pub fn with_indexer_get_result<X: Variant + Clone>(
+        &mut self,
+        callback: impl Fn(&mut T, X) -> Result<Dynamic, Box<EvalAltResult>> + SendSync + 'static,
+    ) -> &mut Self {
+        self.engine.register_indexer_get_result(callback);
+        self
+    }
+
+    /// Register an index setter for the type.
+    ///
+    /// Not available under the `no_index` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the type is `Array`, `Map` or `String`.
+    #[cfg(not(feature = "no_index"))]
+    pub fn with_indexer_set<X: Variant + Clone, U: Variant + Clone>(
+        &mut self,
+        callback: impl Fn(&mut T, X, U) + SendSync + 'static,
+    ) -> &mut Self {
+        self.engine.register_indexer_set(callback);
+        self
+    }
+
+    /// Register an index setter for the type.
+    /// Returns `Result<(), Box<EvalAltResult>>`.
+    ///
+    /// Not available under the `no_index` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the type is `Array`, `Map` or `String`.
+    #[cfg(not(feature = "no_index"))]
+    pub fn with_indexer_set_result<X: Variant + Clone, U: Variant + Clone>(
+        &mut self,
+        callback: impl Fn(&mut T, X, U) -> Result<(), Box<EvalAltResult>> + SendSync + 'static,
+    ) -> &mut Self {
+        self.engine.register_indexer_set_result(callback);
+        self
+    }
+
+    /// Short-hand for registering both index getter and setter functions for the type.
+    ///
+    /// Not available under the `no_index` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the type is `Array`, `Map` or `String`.
+    #[cfg(not(feature = "no_index"))]
+    pub fn with_indexer_get_set<X: Variant + Clone, U: Variant + Clone>(
+        &mut self,
+        getter: impl Fn(&mut T, X) -> U + SendSync + 'static,
+        setter: impl Fn(&mut T, X, U) + SendSync + 'static,
+    ) -> &mut Self {
+        self.with_indexer_get(getter).with_indexer_set(setter)
+    }
+}
+
+impl<T: Variant + Clone> Deref for TypeBuilder<'_, T> {
+    type Target = Engine;
+
+    fn deref(&self) -> &Self::Target {
+        self.engine
+    }
+}
+
+impl<T: Variant + Clone> DerefMut for TypeBuilder<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.engine
+    }
+}
+
+/// Property names must not be empty, otherwise the accessor can never be called.
+fn check_property_name(name: &str) {
+    if name.is_empty() {
+        panic!("Property name for a getter or setter cannot be empty.");
+    }
+}
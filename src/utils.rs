@@ -415,4 +415,10 @@ impl ImmutableString {
     pub fn make_mut(&mut self) -> &mut String {
         shared_make_mut(&mut self.0)
     }
+    /// Do two `ImmutableString`s share the same underlying string?
+    ///
+    /// Identical string literals within the same `AST` always share the same string.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Shared::ptr_eq(&self.0, &other.0)
+    }
 }
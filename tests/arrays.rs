@@ -1,5 +1,5 @@
 #![cfg(not(feature = "no_index"))]
-use rhai::{Array, Engine, EvalAltResult, RegisterFn, INT};
+use rhai::{Array, Dynamic, Engine, EvalAltResult, ParseErrorType, RegisterFn, INT};
 
 #[test]
 fn test_arrays() -> Result<(), Box<EvalAltResult>> {
@@ -62,6 +62,119 @@ fn test_arrays() -> Result<(), Box<EvalAltResult>> {
     Ok(())
 }
 
+#[test]
+fn test_arrays_slice() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let to_ints = |a: Array| a.into_iter().map(|v| v.cast::<INT>()).collect::<Vec<_>>();
+
+    assert_eq!(
+        to_ints(engine.eval::<Array>("let x = [1, 2, 3, 4, 5]; x[1..3]")?),
+        vec![2, 3]
+    );
+    assert_eq!(
+        to_ints(engine.eval::<Array>("let i = 1; [1, 2, 3][i..i + 1]")?),
+        vec![2]
+    );
+    assert_eq!(
+        to_ints(engine.eval::<Array>("let x = [[1, 2], [3, 4]]; x[1][0..1]")?),
+        vec![3]
+    );
+
+    // The upper bound is clamped to the length of the array
+    assert_eq!(
+        to_ints(engine.eval::<Array>("[1, 2, 3][1..99]")?),
+        vec![2, 3]
+    );
+    assert!(engine.eval::<Array>("[1, 2, 3][5..9]")?.is_empty());
+
+    // A reversed range is empty
+    assert!(engine.eval::<Array>("[1, 2, 3][2..1]")?.is_empty());
+
+    // A slice can be indexed further
+    assert_eq!(engine.eval::<INT>("let x = [1, 2, 3]; x[0..2][0]")?, 1);
+    assert_eq!(engine.eval::<INT>("let x = [1, 2, 3]; x[1..3][1]")?, 3);
+    assert_eq!(engine.eval::<INT>("let x = [1, 2, 3]; len(x[0..2])")?, 2);
+    assert_eq!(
+        to_ints(engine.eval::<Array>("let x = [1, 2, 3]; x[0..3][1..3]")?),
+        vec![2, 3]
+    );
+
+    // The slice is a copy
+    assert_eq!(
+        engine.eval::<INT>("let x = [1, 2, 3]; let y = x[0..2]; y[0] = 42; x[0]")?,
+        1
+    );
+
+    assert!(matches!(
+        *engine
+            .eval::<Array>("[1, 2, 3][-1..2]")
+            .expect_err("should error"),
+        EvalAltResult::ErrorArrayBounds(3, -1, _)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<()>("let x = [1, 2, 3]; x[0..2] = [42];")
+            .expect_err("should error"),
+        EvalAltResult::ErrorAssignmentToUnknownLHS(_)
+    ));
+
+    // Writing through a slice is an error, as the slice is a copy
+    assert!(matches!(
+        *engine
+            .eval::<()>("let x = [1, 2, 3]; x[0..2][0] = 42;")
+            .expect_err("should error"),
+        EvalAltResult::ErrorAssignmentToUnknownLHS(_)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<()>("let x = [[1], [2]]; x[0..2][0] += [42];")
+            .expect_err("should error"),
+        EvalAltResult::ErrorAssignmentToUnknownLHS(_)
+    ));
+    #[cfg(not(feature = "no_object"))]
+    assert!(matches!(
+        *engine
+            .eval::<()>("let x = [1, 2, 3]; x[0..2].push(42);")
+            .expect_err("should error"),
+        EvalAltResult::ErrorAssignmentToUnknownLHS(_)
+    ));
+    #[cfg(not(feature = "no_object"))]
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let x = [1, 2, 3]; x[0..2].pop()")
+            .expect_err("should error"),
+        EvalAltResult::ErrorAssignmentToUnknownLHS(_)
+    ));
+    #[cfg(not(feature = "no_object"))]
+    assert!(matches!(
+        *engine
+            .eval::<()>("let x = [[1], [2]]; x[0..2][0].push(42);")
+            .expect_err("should error"),
+        EvalAltResult::ErrorAssignmentToUnknownLHS(_)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_array_trailing_comma() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("let x = [1, 2, 3,]; len(x)")?, 3);
+    assert_eq!(engine.eval::<INT>("let x = [42,]; x[0]")?, 42);
+    assert!(engine.eval::<Array>("[]")?.is_empty());
+    assert!(engine.eval::<INT>("[,]").is_err());
+
+    #[cfg(not(feature = "no_function"))]
+    assert_eq!(
+        engine.eval::<INT>("fn add(x, y,) { x + y } add(40, 2,)")?,
+        42
+    );
+
+    Ok(())
+}
+
 #[test]
 #[cfg(not(feature = "no_object"))]
 fn test_array_with_structs() -> Result<(), Box<EvalAltResult>> {
@@ -112,3 +225,291 @@ fn test_array_with_structs() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_array_spread() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_fn("sum3", |a: INT, b: INT, c: INT| a + b + c);
+
+    assert_eq!(
+        engine.eval::<INT>("let rest = [2, 3, 4]; let x = [1, ...rest, 5]; len(x) * 10 + x[3]")?,
+        54
+    );
+    assert_eq!(engine.eval::<INT>("let x = [...[], ...[1]]; x[0]")?, 1);
+    assert_eq!(
+        engine.eval::<INT>("let args = [1, 2, 3]; sum3(...args)")?,
+        6
+    );
+    assert_eq!(
+        engine.eval::<INT>("let args = [2, 3]; sum3(1, ...args)")?,
+        6
+    );
+    assert_eq!(engine.eval::<INT>("[1, 2, 3][1]")?, 2);
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        assert_eq!(
+            engine.eval::<INT>(
+                r"
+                    fn add(a, b) { a + b }
+                    let args = [40, 2];
+                    add(...args)
+                "
+            )?,
+            42
+        );
+
+        #[cfg(not(feature = "no_object"))]
+        assert_eq!(
+            engine.eval::<INT>(
+                r"
+                    fn add(b) { this + b }
+                    let x = 40;
+                    let args = [2];
+                    x.add(...args)
+                "
+            )?,
+            42
+        );
+    }
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let x = 42; sum3(...x)")
+            .expect_err("should error"),
+        EvalAltResult::ErrorMismatchOutputType(_, _, _)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let args = [1, 2]; sum3(...args)")
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"let a = ["1+1"]; eval(...a)"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorParsing(ParseErrorType::MalformedCallExpr(_), _)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<Dynamic>(r#"let a = ["foo"]; Fn(...a)"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorParsing(ParseErrorType::MalformedCallExpr(_), _)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_array_from_iter() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_fn("squares", |n: INT| -> Dynamic {
+        (1..=n).map(|x| x * x).collect()
+    });
+
+    assert_eq!(engine.eval::<INT>("let x = squares(4); len(x) + x[3]")?, 20);
+    assert_eq!(
+        engine.eval::<INT>("let x = squares(3); push(x, 42); x[3]")?,
+        42
+    );
+    assert_eq!(engine.eval::<String>("type_of(squares(0))")?, "array");
+    assert_eq!(engine.eval::<INT>("len(squares(0))")?, 0);
+
+    let array: Dynamic = vec!["hello", "world"].into_iter().collect();
+    assert!(array.is::<Array>());
+
+    let items: Vec<String> = engine
+        .eval::<Dynamic>(r#"["a", "b", "c"]"#)?
+        .into_array_iter()
+        .unwrap()
+        .map(|v| v.take_string().unwrap())
+        .collect();
+    assert_eq!(items, ["a", "b", "c"]);
+
+    assert!(Dynamic::from(42 as INT).into_array_iter().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_array_slicing_helpers() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    fn ints(array: Array) -> Vec<INT> {
+        array.into_iter().map(|v| v.as_int().unwrap()).collect()
+    }
+    fn nested(array: Array) -> Vec<Vec<INT>> {
+        array.into_iter().map(|v| ints(v.cast::<Array>())).collect()
+    }
+
+    assert_eq!(
+        ints(engine.eval::<Array>("let a = [1, 1, 2, 2, 2, 3, 1, 1]; dedup(a); a")?),
+        [1, 2, 3, 1]
+    );
+    assert_eq!(
+        engine.eval::<INT>(r#"let a = [1, "1", "1", true, 'x', 'x', ()]; dedup(a); len(a)"#)?,
+        5
+    );
+    assert_eq!(engine.eval::<INT>("let a = []; dedup(a); len(a)")?, 0);
+
+    assert_eq!(
+        ints(engine.eval::<Array>("let a = [3, 1, 4, 1, 5, 9, 2, 6]; sort(a); a")?),
+        [1, 1, 2, 3, 4, 5, 6, 9]
+    );
+    assert!(matches!(
+        *engine.eval::<()>("let a = [3, [1], [2]]; sort(a);").expect_err("should error"),
+        EvalAltResult::ErrorRuntime(ref s, _) if s.starts_with("Type ")
+    ));
+
+    #[cfg(not(feature = "no_float"))]
+    assert_eq!(
+        engine.eval::<INT>("let a = [1, 1.0, 2]; dedup(a); len(a)")?,
+        2
+    );
+
+    assert_eq!(
+        nested(engine.eval::<Array>("chunks([1, 2, 3, 4, 5], 2)")?),
+        [vec![1, 2], vec![3, 4], vec![5]]
+    );
+    assert_eq!(
+        nested(engine.eval::<Array>("chunks([1, 2, 3], 5)")?),
+        [vec![1, 2, 3]]
+    );
+    assert_eq!(
+        nested(engine.eval::<Array>("windows([1, 2, 3, 4], 3)")?),
+        [vec![1, 2, 3], vec![2, 3, 4]]
+    );
+    assert_eq!(engine.eval::<INT>("len(windows([1, 2], 3))")?, 0);
+
+    assert!(matches!(
+        *engine.eval::<Array>("chunks([1, 2, 3], 0)").expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg == "Chunk size must be positive: 0"
+    ));
+    assert!(matches!(
+        *engine.eval::<Array>("windows([1, 2, 3], 0)").expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg == "Window size must be positive: 0"
+    ));
+
+    assert_eq!(
+        ints(engine.eval::<Array>("flatten([[1, 2], [], [3]])")?),
+        [1, 2, 3]
+    );
+    assert_eq!(
+        nested(engine.eval::<Array>("flatten([[[1, 2]], [[3]]])")?),
+        [vec![1, 2], vec![3]]
+    );
+    assert!(matches!(
+        *engine.eval::<Array>(r#"flatten([[1], "x"])"#).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _)
+            if msg == "Only arrays can be flattened, but element 1 is string"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_typed_arrays() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>(
+            r#"
+                let a = typed_array("string");
+                push(a, "hello");
+                a += "world";
+                insert(a, 0, "well,");
+                a[0] = "oh,";
+                let s = "";
+                for x in a { s += x + " "; }
+                s + a[2] + " " + len(a) + " " + type_of(a) + " " + elem_type(a)
+            "#
+        )?,
+        "oh, hello world world 3 array string"
+    );
+
+    assert!(engine.eval::<bool>(
+        r#"
+            let a = typed_array(type_of(0));
+            a += [1, 2, 3];
+            2 in a && !(4 in a)
+        "#
+    )?);
+
+    // All array functions work on typed arrays
+    assert_eq!(
+        engine.eval::<String>(
+            r#"
+                let a = typed_array(type_of(0));
+                a += [3, 1, 2];
+                sort(a);
+                let b = a[1..3];
+                reverse(b);
+                to_string(a) + " " + b + " " + elem_type(b)
+            "#
+        )?,
+        format!("[1, 2, 3] [3, 2] {}", std::any::type_name::<INT>())
+    );
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                let a = typed_array("array");
+                a.push([1]);
+                a[0].push(2);
+                a[0][1]
+            "#
+        )?,
+        2
+    );
+    assert!(engine.eval::<bool>(r#"let a = [1, 2]; elem_type(a) == ()"#)?);
+    #[cfg(not(feature = "no_object"))]
+    assert!(engine.eval::<bool>(r#"let a = typed_array("string"); a.elem_type == "string""#)?);
+
+    assert!(matches!(
+        *engine.eval::<()>(r#"let a = typed_array("string"); push(a, 'x');"#).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg == "Cannot add char to a typed array of string"
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<()>(r#"let a = typed_array("string"); push(a, "x"); a[0] = true;"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg == "Cannot add bool to a typed array of string"
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<()>(r#"let a = typed_array(type_of(0)); a += [1, "x"];"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg.starts_with("Cannot add string to a typed array of ")
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"let a = typed_array(type_of(0)); a[0]"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorArrayBounds(0, 0, _)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<()>(r#"let a = typed_array("string"); let b = a; insert(b, 0, 42);"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg.starts_with("Cannot add i")
+    ));
+
+    // The array is unchanged after an error
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                let a = typed_array(type_of(0));
+                push(a, 1);
+                try { push(a, "x"); } catch {}
+                try { a += [2, "y"]; } catch {}
+                len(a)
+            "#
+        )?,
+        1
+    );
+
+    Ok(())
+}
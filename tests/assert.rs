@@ -0,0 +1,87 @@
+#![cfg(feature = "testing")]
+use rhai::{Engine, EvalAltResult, RegisterFn, INT};
+
+#[test]
+fn test_assert() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    engine.eval::<()>("assert(true)")?;
+    engine.eval::<()>(r#"let x = 42; assert(x > 0, "x should be positive")"#)?;
+
+    assert!(matches!(
+        *engine.eval::<()>("assert(1 > 2)").expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg == "Assertion failed"
+    ));
+
+    let err = engine
+        .eval::<()>(r#"let x = -1;  assert(x > 0, "x should be positive")"#)
+        .expect_err("should error");
+    assert!(matches!(
+        *err,
+        EvalAltResult::ErrorRuntime(ref msg, _) if msg == "Assertion failed: x should be positive"
+    ));
+    assert_eq!(err.position().position(), Some(14));
+
+    Ok(())
+}
+
+#[test]
+fn test_assert_eq() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    engine.eval::<()>("assert_eq(40 + 2, 42)")?;
+    engine.eval::<()>(r#"assert_eq("hello", "hel" + "lo")"#)?;
+
+    #[cfg(not(feature = "no_float"))]
+    engine.eval::<()>("assert_eq(1, 1.0)")?;
+
+    assert!(matches!(
+        *engine.eval::<()>("assert_eq(1, 2)").expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg == "Assertion failed: left == right (left: 1, right: 2)"
+    ));
+    assert!(matches!(
+        *engine.eval::<()>(r#"assert_eq(42, "42")"#).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg == "Assertion failed: left == right (left: 42, right: 42)"
+    ));
+
+    // Values are not consumed
+    assert_eq!(
+        engine.eval::<INT>("let x = 42; let y = 42; assert_eq(x, y); x + y")?,
+        84
+    );
+    assert_eq!(
+        engine.eval::<INT>("let x = 1; let y = 2; try { assert_eq(x, y); } catch {} x + y")?,
+        3
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_assert_eq_custom_type() -> Result<(), Box<EvalAltResult>> {
+    #[derive(Debug, Clone)]
+    struct Point(INT);
+
+    let mut engine = Engine::new();
+
+    engine
+        .register_type_with_name::<Point>("Point")
+        .register_fn("point", Point)
+        .register_fn("==", |a: Point, b: Point| a.0 == b.0);
+
+    engine.eval::<()>("assert_eq(point(42), point(42))")?;
+
+    assert!(matches!(
+        *engine.eval::<()>("assert_eq(point(1), point(2))").expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg == "Assertion failed: left == right (left: <Point>, right: <Point>)"
+    ));
+
+    engine.register_fn("to_string", |p: &mut Point| format!("Point({})", p.0));
+
+    assert!(matches!(
+        *engine.eval::<()>("assert_eq(point(1), point(2))").expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg == "Assertion failed: left == right (left: Point(1), right: Point(2))"
+    ));
+
+    Ok(())
+}
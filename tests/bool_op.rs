@@ -1,4 +1,5 @@
-use rhai::{Engine, EvalAltResult};
+use rhai::{Engine, EvalAltResult, RegisterFn, INT};
+use std::sync::{Arc, RwLock};
 
 #[test]
 fn test_bool_op1() -> Result<(), Box<EvalAltResult>> {
@@ -61,6 +62,54 @@ fn test_bool_op_short_circuit() -> Result<(), Box<EvalAltResult>> {
     Ok(())
 }
 
+#[test]
+fn test_bool_op_short_circuit_side_effects() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    let counter = Arc::new(RwLock::new(0 as INT));
+    let count = counter.clone();
+
+    engine.register_fn("side_effect", move |x: bool| {
+        *count.write().unwrap() += 1;
+        x
+    });
+
+    let scripts = [
+        "true || side_effect(true)",
+        "false && side_effect(true)",
+        "let x = true; x || side_effect(false)",
+        "let x = false; x && side_effect(true)",
+        "(1 < 2) || side_effect(false)",
+        "(1 > 2) && side_effect(true)",
+        "true || (false || side_effect(true))",
+    ];
+
+    #[cfg(not(feature = "no_optimize"))]
+    let levels = vec![
+        rhai::OptimizationLevel::None,
+        rhai::OptimizationLevel::Simple,
+        rhai::OptimizationLevel::Full,
+    ];
+    #[cfg(feature = "no_optimize")]
+    let levels = vec![()];
+
+    for _level in levels {
+        #[cfg(not(feature = "no_optimize"))]
+        engine.set_optimization_level(_level);
+
+        for script in scripts.iter() {
+            engine.eval::<bool>(script)?;
+            assert_eq!(*counter.read().unwrap(), 0, "{}", script);
+        }
+
+        assert_eq!(engine.eval::<bool>("false || side_effect(true)")?, true);
+        assert_eq!(*counter.read().unwrap(), 1);
+        *counter.write().unwrap() = 0;
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_bool_op_no_short_circuit1() {
     let engine = Engine::new();
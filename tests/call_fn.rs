@@ -1,6 +1,7 @@
 #![cfg(not(feature = "no_function"))]
 use rhai::{
-    Dynamic, Engine, EvalAltResult, FnPtr, Func, Module, ParseErrorType, RegisterFn, Scope, INT,
+    Dynamic, Engine, EvalAltResult, FnPtr, Func, NativeCallContext, ParseErrorType, RegisterFn,
+    Scope, INT,
 };
 use std::any::TypeId;
 
@@ -98,12 +99,12 @@ fn test_fn_ptr_raw() -> Result<(), Box<EvalAltResult>> {
                 TypeId::of::<FnPtr>(),
                 TypeId::of::<INT>(),
             ],
-            move |engine: &Engine, lib: &Module, args: &mut [&mut Dynamic]| {
+            move |context: &mut NativeCallContext, args: &mut [&mut Dynamic]| {
                 let fp = std::mem::take(args[1]).cast::<FnPtr>();
                 let value = args[2].clone();
                 let this_ptr = args.get_mut(0).unwrap();
 
-                fp.call_dynamic(engine, lib, Some(this_ptr), [value])
+                fp.call_dynamic(context.engine(), context.lib(), Some(this_ptr), [value])
             },
         );
 
@@ -161,6 +162,29 @@ fn test_fn_ptr_raw() -> Result<(), Box<EvalAltResult>> {
     Ok(())
 }
 
+#[test]
+fn test_fn_raw_dynamic_params() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    #[allow(deprecated)]
+    engine
+        .register_fn("describe", |x: INT| format!("int {}", x))
+        .register_raw_fn(
+            "describe",
+            &[TypeId::of::<Dynamic>()],
+            |_: &mut NativeCallContext, args: &mut [&mut Dynamic]| {
+                Ok(args[0].type_name().to_string())
+            },
+        );
+
+    // Exact argument types take precedence
+    assert_eq!(engine.eval::<String>("describe(42)")?, "int 42");
+    assert_eq!(engine.eval::<String>("describe(true)")?, "bool");
+    assert_eq!(engine.eval::<String>("describe(())")?, "()");
+
+    Ok(())
+}
+
 #[test]
 fn test_anonymous_fn() -> Result<(), Box<EvalAltResult>> {
     let calc_func = Func::<(INT, INT, INT), INT>::create_from_script(
@@ -192,3 +216,93 @@ fn test_anonymous_fn() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_fn_ptr_native_call_context() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    #[allow(deprecated)]
+    engine.register_raw_fn(
+        "retry",
+        &[TypeId::of::<INT>(), TypeId::of::<FnPtr>()],
+        |context: &mut NativeCallContext, args: &mut [&mut Dynamic]| {
+            let times = args[0].as_int().unwrap();
+            let fp = std::mem::take(args[1]).cast::<FnPtr>();
+
+            let mut attempt = 1;
+
+            loop {
+                match context.call_fn_dynamic(&fp, [Dynamic::from(attempt)]) {
+                    Err(err) if err.is_catchable() && attempt < times => attempt += 1,
+                    result => return result,
+                }
+            }
+        },
+    );
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                fn flaky(n) {
+                    if n < 3 { throw "not yet"; }
+                    n * 14
+                }
+                retry(5, Fn("flaky"))
+            "#
+        )?,
+        42
+    );
+
+    // Errors from the last attempt are returned unchanged
+    match *engine
+        .eval::<INT>(r#"retry(2, |n| { throw "attempt " + n; })"#)
+        .expect_err("should error")
+    {
+        EvalAltResult::ErrorInFunctionCall(_, err, _) => {
+            assert!(matches!(*err, EvalAltResult::ErrorRuntime(ref msg, _) if msg == "attempt 2"))
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+
+    #[cfg(not(feature = "unchecked"))]
+    {
+        // Script functions keep track of the recursion depth
+        assert!(matches!(
+            *engine
+                .eval::<INT>(
+                    r#"
+                        fn recurse(n) { retry(1, Fn("recurse")) }
+                        recurse(0)
+                    "#
+                )
+                .expect_err("should error"),
+            EvalAltResult::ErrorInFunctionCall(_, err, _) if !err.is_catchable()
+        ));
+
+        engine.set_max_operations(500);
+
+        fn too_many_operations(err: &EvalAltResult) -> bool {
+            match err {
+                EvalAltResult::ErrorInFunctionCall(_, err, _) => too_many_operations(err),
+                EvalAltResult::ErrorTooManyOperations(_) => true,
+                _ => false,
+            }
+        }
+
+        // The operations performed by the callee count towards the limit and are not retried
+        assert!(too_many_operations(
+            &engine
+                .eval::<INT>("retry(100, |n| { let x = 0; loop { x += 1; } })")
+                .expect_err("should error")
+        ));
+
+        // Many cheap attempts add up
+        assert!(too_many_operations(
+            &engine
+                .eval::<INT>("retry(1000, |n| { throw n; })")
+                .expect_err("should error")
+        ));
+    }
+
+    Ok(())
+}
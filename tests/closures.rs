@@ -1,5 +1,8 @@
 #![cfg(not(feature = "no_function"))]
-use rhai::{Dynamic, Engine, EvalAltResult, FnPtr, Module, ParseErrorType, RegisterFn, Scope, INT};
+use rhai::{
+    Dynamic, Engine, EvalAltResult, FnPtr, LexError, NativeCallContext, ParseErrorType, RegisterFn,
+    Scope, INT,
+};
 use std::any::TypeId;
 use std::cell::RefCell;
 use std::mem::take;
@@ -16,9 +19,14 @@ fn test_fn_ptr_curry_call() -> Result<(), Box<EvalAltResult>> {
     engine.register_raw_fn(
         "call_with_arg",
         &[TypeId::of::<FnPtr>(), TypeId::of::<INT>()],
-        |engine: &Engine, lib: &Module, args: &mut [&mut Dynamic]| {
+        |context: &mut NativeCallContext, args: &mut [&mut Dynamic]| {
             let fn_ptr = std::mem::take(args[0]).cast::<FnPtr>();
-            fn_ptr.call_dynamic(engine, lib, None, [std::mem::take(args[1])])
+            fn_ptr.call_dynamic(
+                context.engine(),
+                context.lib(),
+                None,
+                [std::mem::take(args[1])],
+            )
         },
     );
 
@@ -49,7 +57,7 @@ fn test_closures() -> Result<(), Box<EvalAltResult>> {
             .compile_expression("let f = |x| {};")
             .expect_err("should error")
             .0,
-        ParseErrorType::BadInput(_)
+        ParseErrorType::LexError(LexError::UnexpectedInput(ref s)) if s == "let"
     ));
 
     assert_eq!(
@@ -69,6 +77,23 @@ fn test_closures() -> Result<(), Box<EvalAltResult>> {
         42
     );
 
+    // A curried closure keeps its captured variables
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                let x = 30;
+                let f = |y, z| x + y + z;
+                let g = f.curry(10);
+                x = 40;
+                g.call(2)
+            "#
+        )?,
+        52
+    );
+    assert!(engine
+        .eval::<INT>("let x = 1; let f = |y| x + y; f.curry(1, 2)")
+        .is_err());
+
     assert_eq!(
         engine.eval::<INT>(
             r#"
@@ -135,10 +160,10 @@ fn test_closures() -> Result<(), Box<EvalAltResult>> {
     engine.register_raw_fn(
         "custom_call",
         &[TypeId::of::<INT>(), TypeId::of::<FnPtr>()],
-        |engine: &Engine, module: &Module, args: &mut [&mut Dynamic]| {
+        |context: &mut NativeCallContext, args: &mut [&mut Dynamic]| {
             let func = take(args[1]).cast::<FnPtr>();
 
-            func.call_dynamic(engine, module, None, [])
+            context.call_fn_dynamic(&func, [])
         },
     );
 
@@ -1,4 +1,4 @@
-use rhai::{Engine, INT};
+use rhai::{Engine, EvalAltResult, LexError, ParseErrorType, Position, INT};
 
 #[test]
 fn test_comments() {
@@ -12,3 +12,105 @@ fn test_comments() {
         .eval::<INT>("let /* I am a multi-line comment, yay! */ x = 5; x")
         .is_ok());
 }
+
+#[test]
+fn test_comments_nested() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                let x = 40;
+                /* commented out:
+                    x = 0;  /* nested */
+                    x = 1;  // line comment
+                */
+                x + /** inline **/ 2
+            "
+        )?,
+        42
+    );
+
+    assert_eq!(engine.eval::<INT>("/* a /* b /* c */ d */ e */ 42")?, 42);
+    assert_eq!(engine.eval::<INT>("/* /*/*/*/*/**/*/*/*/*/ */ 42")?, 42);
+
+    Ok(())
+}
+
+#[test]
+fn test_comments_unterminated() {
+    let engine = Engine::new();
+
+    let err = engine
+        .compile("let x = 42;\n/* outer\n  /* inner */\nx")
+        .expect_err("should error");
+    assert_eq!(
+        *err.0,
+        ParseErrorType::LexError(LexError::UnterminatedComment)
+    );
+    assert_eq!(err.1, Position::new(2, 1));
+}
+
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_comments_doc() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let ast = engine.compile(
+        r"
+            /// This is a doc-comment
+            /// on two lines
+            fn foo(x) { x + 1 }
+
+            //// Not a doc-comment
+            /// Private function
+            private fn bar() { 42 }
+
+            /// Not attached to a function
+            let x = 1;
+
+            fn baz() { /// inside the body
+                0 }
+
+            // Regular comment
+            fn qux() { 0 }
+        ",
+    )?;
+
+    let mut functions = Vec::new();
+
+    ast.iter_functions(|_, name, _, comments| {
+        functions.push((name.to_string(), comments.to_vec()))
+    });
+    functions.sort();
+
+    assert_eq!(
+        functions,
+        vec![
+            ("bar".to_string(), vec!["/// Private function".to_string()]),
+            ("baz".to_string(), vec![]),
+            (
+                "foo".to_string(),
+                vec![
+                    "/// This is a doc-comment".to_string(),
+                    "/// on two lines".to_string()
+                ]
+            ),
+            ("qux".to_string(), vec![]),
+        ]
+    );
+
+    // Doc-comments are comments everywhere else
+    assert_eq!(engine.eval::<INT>("let x = 40 + /// doc\n 2; x")?, 42);
+    assert_eq!(engine.eval::<INT>("let x = 40 - /// doc\n -2; x")?, 42);
+
+    assert!(matches!(
+        *engine
+            .compile("{ /// doc\n fn foo() {} }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::WrongFnDefinition
+    ));
+
+    Ok(())
+}
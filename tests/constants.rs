@@ -21,3 +21,197 @@ fn test_constant() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_constant_compile_time() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("const x = 40 + 2; x")?, 42);
+    assert_eq!(
+        engine.eval::<INT>("const x = 6 * 6; const y = x + 6; y")?,
+        42
+    );
+    assert_eq!(
+        engine.eval::<INT>("const x = 1; { const x = 40; } const y = x + 41; y")?,
+        42
+    );
+    assert_eq!(
+        engine.eval::<INT>("const x = 1; { const x = 6 * 6; x + 6 }")?,
+        42
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"const s = "hello" + ", world"; s"#)?,
+        "hello, world"
+    );
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        engine.eval::<INT>("const a = [1, 2 * 2, 3]; const n = a[1] + 3; n")?,
+        7
+    );
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_optimize"))]
+#[test]
+fn test_constant_compile_time_functions() -> Result<(), Box<EvalAltResult>> {
+    use rhai::{OptimizationLevel, RegisterFn};
+    use std::sync::{Arc, RwLock};
+
+    let mut engine = Engine::new();
+
+    engine.register_fn("square", |x: INT| x * x);
+
+    let logbook = Arc::new(RwLock::new(Vec::<String>::new()));
+    let log = logbook.clone();
+    engine.on_print(move |s| log.write().unwrap().push(s.to_string()));
+
+    // Functions are not called at compile time unless under full optimization
+    assert!(matches!(
+        *engine
+            .compile("const x = square(6);")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::NonConstantExpr(x, _) if x == "x"
+    ));
+    assert!(matches!(
+        *engine
+            .compile(r#"const x = print("side effect");"#)
+            .expect_err("should error")
+            .0,
+        ParseErrorType::NonConstantExpr(x, _) if x == "x"
+    ));
+    assert!(logbook.read().unwrap().is_empty());
+
+    engine.set_optimization_level(OptimizationLevel::Full);
+
+    assert_eq!(
+        engine.eval::<INT>("const x = square(6); const y = x + 6; y")?,
+        42
+    );
+    assert_eq!(
+        engine.eval::<INT>("const x = 1; { const x = square(6); x + 6 }")?,
+        42
+    );
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        engine.eval::<INT>("const a = [1, square(2), 3]; const n = len(a) + a[1]; n")?,
+        7
+    );
+
+    // Functions are called only once, when the script is compiled
+    let ast = engine.compile(r#"const x = print("side effect"); 42"#)?;
+    assert_eq!(logbook.read().unwrap().as_slice(), ["side effect"]);
+    assert_eq!(engine.eval_ast::<INT>(&ast)?, 42);
+    assert_eq!(logbook.read().unwrap().as_slice(), ["side effect"]);
+
+    assert!(matches!(
+        *engine
+            .compile("const x = unknown(42);")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::NonConstantExpr(x, _) if x == "x"
+    ));
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_float"))]
+#[test]
+fn test_constant_div_by_zero() -> Result<(), Box<EvalAltResult>> {
+    use rhai::{FloatDivByZero, FLOAT};
+
+    let mut engine = Engine::new();
+
+    assert!(engine
+        .eval::<FLOAT>("const x = 1.0 / 0.0; x")?
+        .is_infinite());
+
+    engine.set_float_div_by_zero(FloatDivByZero::Error);
+
+    assert!(matches!(
+        *engine
+            .compile("const x = 1.0 / 0.0;")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::NonConstantExpr(x, _) if x == "x"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_constant_not_constant() {
+    let engine = Engine::new();
+
+    assert!(matches!(
+        *engine
+            .compile("let x = 40; const y = x + 2;")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::ForbiddenConstantExpr(x) if x == "y"
+    ));
+    assert!(matches!(
+        *engine
+            .compile("const x = 40; x += 2;")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::AssignmentToConstant(x) if x == "x"
+    ));
+    assert!(matches!(
+        *engine
+            .compile("const x = { 42 };")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::ForbiddenConstantExpr(x) if x == "x"
+    ));
+    #[cfg(not(feature = "unchecked"))]
+    assert!(matches!(
+        *engine
+            .compile("const x = 1 / 0;")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::NonConstantExpr(x, _) if x == "x"
+    ));
+}
+
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_optimize"))]
+#[test]
+fn test_constant_script_function() {
+    use rhai::RegisterFn;
+
+    #[derive(Debug, Clone)]
+    struct Point;
+
+    let mut engine = Engine::new();
+
+    engine.register_fn("new_point", || Point);
+    engine.register_fn("square", |x: INT| x * x);
+
+    engine.set_optimization_level(rhai::OptimizationLevel::Full);
+
+    assert!(matches!(
+        *engine
+            .compile("const x = foo(40); fn foo(x) { x + 2 }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::NonConstantExpr(x, r) if x == "x" && r.contains("foo")
+    ));
+    assert!(matches!(
+        *engine
+            .compile("const y = square(6); fn square(x) { x }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::NonConstantExpr(x, r) if x == "y" && r.contains("script-defined")
+    ));
+    assert!(matches!(
+        *engine
+            .compile("const p = new_point();")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::NonConstantExpr(x, _) if x == "p"
+    ));
+}
@@ -0,0 +1,97 @@
+use rhai::{Engine, EvalAltResult, INT};
+
+#[test]
+fn test_contains_range() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert!(engine.eval::<bool>("5 in 0..10")?);
+    assert!(engine.eval::<bool>("0 in 0..10")?);
+    assert!(!engine.eval::<bool>("10 in 0..10")?);
+    assert!(engine.eval::<bool>("let n = 3; n + 1 in n..n * 2")?);
+    assert!(engine.eval::<bool>("let r = 1..4; contains(r, 3)")?);
+
+    // 'in' inside a 'for' header is the loop separator
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                let sum = 0;
+                for x in 0..5 { sum += x; }
+                sum
+            "
+        )?,
+        10
+    );
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                let count = 0;
+                for x in 0..10 { if x in 3..6 { count += 1; } }
+                count
+            "
+        )?,
+        3
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_contains_fn() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert!(engine.eval::<bool>(r#"contains("hello", "ell")"#)?);
+
+    #[cfg(not(feature = "no_object"))]
+    assert!(engine.eval::<bool>(r#"let m = #{a: 1}; m.contains("a")"#)?);
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_contains_custom_type() -> Result<(), Box<EvalAltResult>> {
+    use rhai::RegisterFn;
+
+    #[derive(Debug, Clone)]
+    struct Evens;
+
+    let mut engine = Engine::new();
+
+    engine
+        .register_type_with_name::<Evens>("Evens")
+        .register_fn("evens", || Evens)
+        .register_fn("contains", |_: &mut Evens, x: INT| x % 2 == 0);
+
+    assert!(engine.eval::<bool>("42 in evens()")?);
+    assert!(!engine.eval::<bool>("let e = evens(); 41 in e")?);
+
+    Ok(())
+}
+
+#[test]
+fn test_contains_errors() {
+    let engine = Engine::new();
+
+    let err = engine
+        .eval::<bool>("let x = 42; 1 in x")
+        .expect_err("should error");
+    assert!(matches!(*err, EvalAltResult::ErrorInExpr(_, _)));
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Cannot check whether {0} is in {0} (line 1, position 18)",
+            std::any::type_name::<INT>()
+        )
+    );
+
+    assert!(matches!(
+        *engine.eval::<bool>(r#"let s = "hello"; 42 in s"#).expect_err("should error"),
+        EvalAltResult::ErrorInExpr(msg, _) if msg.starts_with("Cannot check whether") && msg.ends_with("is in string")
+    ));
+
+    #[cfg(not(feature = "no_float"))]
+    assert!(matches!(
+        *engine.eval::<bool>("let x = 1.5; x in 0..10").expect_err("should error"),
+        EvalAltResult::ErrorInExpr(msg, _) if msg.ends_with("is in range")
+    ));
+}
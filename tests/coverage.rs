@@ -0,0 +1,90 @@
+use rhai::{Engine, EvalAltResult};
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+
+#[test]
+fn test_coverage() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    let covered = Arc::new(RwLock::new(Vec::new()));
+    let logger = covered.clone();
+
+    engine.on_statement_executed(move |pos| logger.write().unwrap().push(pos.line().unwrap()));
+
+    let ast = engine.compile(
+        r"
+            let x = 1;
+            if false {
+                x = 2;
+            }
+            while x < 3 {
+                x += 1;
+            }
+            if x > 10 {
+                x = 0;
+            }
+        ",
+    )?;
+
+    let lines: Vec<_> = ast
+        .statement_positions()
+        .iter()
+        .map(|pos| pos.line().unwrap())
+        .collect();
+
+    engine.consume_ast(&ast)?;
+
+    let executed = covered.read().unwrap().clone();
+    let covered: HashSet<_> = executed.iter().cloned().collect();
+    let uncovered: Vec<_> = lines.iter().filter(|n| !covered.contains(n)).collect();
+
+    // 'if false { ... }' is optimized away
+    #[cfg(not(feature = "no_optimize"))]
+    {
+        assert_eq!(lines, vec![2, 6, 7, 9, 10]);
+        assert_eq!(executed, vec![2, 6, 7, 7, 9]);
+        assert_eq!(uncovered, vec![&10]);
+    }
+    #[cfg(feature = "no_optimize")]
+    {
+        assert_eq!(lines, vec![2, 3, 4, 6, 7, 9, 10]);
+        assert_eq!(executed, vec![2, 3, 6, 7, 7, 9]);
+        assert_eq!(uncovered, vec![&4, &10]);
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_coverage_functions() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    let covered = Arc::new(RwLock::new(Vec::new()));
+    let logger = covered.clone();
+
+    engine.on_statement_executed(move |pos| logger.write().unwrap().push(pos.line().unwrap()));
+
+    let ast = engine.compile(
+        r"
+            fn foo(x) {
+                let y = x * 2;
+                y + 1
+            }
+            foo(20);
+        ",
+    )?;
+
+    let mut lines: Vec<_> = ast
+        .statement_positions()
+        .iter()
+        .map(|pos| pos.line().unwrap())
+        .collect();
+    lines.sort();
+    assert_eq!(lines, vec![3, 4, 6]);
+
+    engine.consume_ast(&ast)?;
+    assert_eq!(*covered.read().unwrap(), vec![6, 3, 4]);
+
+    Ok(())
+}
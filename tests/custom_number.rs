@@ -0,0 +1,142 @@
+use rhai::{Dynamic, Engine, EvalAltResult, RegisterFn, INT};
+use std::sync::{Arc, RwLock};
+
+/// Fixed-point number with 16 fractional bits.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+struct Q16(i32);
+
+impl Q16 {
+    fn parse(text: &str) -> Option<Self> {
+        let (int, frac) = match text.find('.') {
+            Some(p) => (&text[..p], &text[p + 1..]),
+            None => (text, ""),
+        };
+
+        let int: i64 = int.parse().ok()?;
+        let mut value = int << 16;
+
+        if !frac.is_empty() {
+            let digits: i64 = frac.parse().ok()?;
+            let scale = 10_i64.pow(frac.len() as u32);
+            value += ((digits << 16) + scale / 2) / scale;
+        }
+
+        if value > i32::MAX as i64 {
+            None
+        } else {
+            Some(Q16(value as i32))
+        }
+    }
+}
+
+fn fixed_point_engine(convert_integers: bool) -> Engine {
+    let mut engine = Engine::new();
+
+    #[cfg(not(feature = "no_object"))]
+    engine.register_type_with_name::<Q16>("Q16");
+    engine.register_fn("+", |a: Q16, b: Q16| Q16(a.0 + b.0));
+    engine.register_fn("-", |a: Q16, b: Q16| Q16(a.0 - b.0));
+    engine.register_fn("-", |a: Q16| Q16(-a.0));
+    engine.register_fn("*", |a: Q16, b: Q16| {
+        Q16(((a.0 as i64 * b.0 as i64) >> 16) as i32)
+    });
+    engine.register_fn("<", |a: Q16, b: Q16| a < b);
+    engine.register_fn("==", |a: Q16, b: Q16| a == b);
+
+    engine.on_number_literal(move |text| {
+        if convert_integers || text.contains('.') {
+            Q16::parse(text).map(Dynamic::from)
+        } else {
+            None
+        }
+    });
+
+    engine
+}
+
+#[test]
+fn test_custom_number() -> Result<(), Box<EvalAltResult>> {
+    let engine = fixed_point_engine(true);
+
+    assert_eq!(engine.eval::<Q16>("42")?, Q16(42 << 16));
+    assert_eq!(engine.eval::<Q16>("1 + 2")?, Q16(3 << 16));
+    assert_eq!(engine.eval::<Q16>("-3 + 1")?, Q16(-2 << 16));
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(engine.eval::<String>("type_of(42)")?, "Q16");
+    assert!(engine.eval::<bool>("let x = 1; x < 2")?);
+    assert!(engine.eval::<bool>("const x = 7; x == 7")?);
+
+    // Literals with decimal points are converted even without floating-point support
+    assert_eq!(engine.eval::<Q16>("1.5")?, Q16(0x18000));
+    assert_eq!(engine.eval::<Q16>("2.5 * 2")?, Q16(5 << 16));
+    assert_eq!(engine.eval::<Q16>("let x = 0.25; x + x - 1")?, Q16(-0x8000));
+
+    // Literals that cannot be converted keep their standard type
+    assert_eq!(engine.eval::<INT>("65536")?, 65536);
+
+    // No operator for mixed types
+    assert!(matches!(
+        *engine.eval::<INT>("40 + 65536").expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_custom_number_keep_integers() -> Result<(), Box<EvalAltResult>> {
+    let engine = fixed_point_engine(false);
+
+    assert_eq!(engine.eval::<INT>("40 + 2")?, 42);
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(engine.eval::<INT>("let a = [1, 2, 3]; a[1]")?, 2);
+
+    assert_eq!(engine.eval::<Q16>("0.5 + 0.5")?, Q16(1 << 16));
+    assert_eq!(engine.eval::<Q16>("2.00")?, Q16(2 << 16));
+
+    Ok(())
+}
+
+#[test]
+fn test_custom_number_text() -> Result<(), Box<EvalAltResult>> {
+    let literals = Arc::new(RwLock::new(Vec::<String>::new()));
+    let log = literals.clone();
+
+    let mut engine = Engine::new();
+
+    engine.on_number_literal(move |text| {
+        log.write().unwrap().push(text.to_string());
+        Some(Dynamic::from(text.to_string()))
+    });
+
+    // The callback receives the literal exactly as written
+    assert_eq!(engine.eval::<String>("0xff")?, "0xff");
+    assert_eq!(engine.eval::<String>("2_55")?, "2_55");
+    assert_eq!(engine.eval::<String>("2.00")?, "2.00");
+    assert_eq!(engine.eval::<String>("-1.50")?, "-1.50");
+    assert_eq!(
+        engine.eval::<String>("123456789012345678901234567890")?,
+        "123456789012345678901234567890"
+    );
+    assert_eq!(
+        engine.eval::<String>("0.1000000000000000000000001")?,
+        "0.1000000000000000000000001"
+    );
+
+    assert_eq!(literals.read().unwrap().len(), 6);
+
+    // Literals not converted by the callback keep their standard type
+    let mut engine = Engine::new();
+    engine.on_number_literal(|_| None);
+
+    assert_eq!(engine.eval::<INT>("0xff + 2_55")?, 510);
+    assert!(matches!(
+        *engine
+            .eval::<INT>("0x123456789abcdef0123456789")
+            .expect_err("should error"),
+        EvalAltResult::ErrorParsing(_, _)
+    ));
+
+    Ok(())
+}
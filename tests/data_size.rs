@@ -273,3 +273,176 @@ fn test_max_map_size() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_max_memory() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    engine.set_max_memory(10_000);
+
+    // Many small allocations that stay within the array and string limits
+    assert!(matches!(
+        *engine
+            .eval::<()>(
+                r#"
+                    let x = [];
+                    loop { push(x, "hello"); }
+                "#
+            )
+            .expect_err("should error"),
+        EvalAltResult::ErrorDataTooLarge(_, 10_000, _, _)
+    ));
+
+    // Memory is released when variables go out of scope
+    engine.eval::<()>(
+        r#"
+            for i in range(0, 100) {
+                let x = [];
+                for j in range(0, 50) { push(x, "hello"); }
+            }
+        "#,
+    )?;
+
+    #[cfg(not(feature = "no_object"))]
+    assert!(matches!(
+        *engine
+            .eval::<()>(
+                r#"
+                    let x = #{};
+                    let i = 0;
+                    loop { x[i.to_string()] = [i]; i += 1; }
+                "#
+            )
+            .expect_err("should error"),
+        EvalAltResult::ErrorDataTooLarge(_, 10_000, _, _)
+    ));
+
+    // Values that are added and removed again do not count
+    engine.eval::<()>(
+        r#"
+            let x = [];
+            for i in range(0, 1000) { push(x, "hello"); pop(x); }
+        "#,
+    )?;
+
+    // Strings growing in place
+    assert!(matches!(
+        *engine
+            .eval::<()>(r#"let s = ""; loop { s += "hello"; }"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorDataTooLarge(_, 10_000, _, _)
+    ));
+
+    // Variables in all function call frames are counted
+    #[cfg(not(feature = "no_function"))]
+    engine.eval::<()>(
+        r#"
+            fn f(n) {
+                let x = [];
+                for i in range(0, 100) { push(x, i); }
+                if n > 0 { f(n - 1) }
+            }
+            for i in range(0, 10) { f(2); }
+        "#,
+    )?;
+    #[cfg(not(feature = "no_function"))]
+    assert!(matches!(
+        *engine
+            .eval::<()>(
+                r#"
+                    fn f(n) {
+                        let x = [];
+                        for i in range(0, 100) { push(x, i); }
+                        if n > 0 { f(n - 1) }
+                    }
+                    f(10);
+                "#
+            )
+            .expect_err("should error"),
+        EvalAltResult::ErrorInFunctionCall(_, err, _)
+            if matches!(*err, EvalAltResult::ErrorDataTooLarge(_, 10_000, _, _))
+    ));
+
+    engine.set_max_memory(0);
+
+    engine.eval::<()>(
+        r#"
+            let x = [];
+            for i in range(0, 1000) { push(x, "hello"); }
+        "#,
+    )?;
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_max_data_depth() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    // Deeply-nested literals are stopped by the parser
+    let script = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+    assert_eq!(
+        *engine.compile(&script).expect_err("should error").0,
+        ParseErrorType::ExprTooDeep
+    );
+
+    // Deeply-nested data built at run-time is stopped by default, before it can overflow the stack
+    assert!(matches!(
+        *engine
+            .eval::<()>("let a = []; for i in range(0, 100000) { a = [a]; }")
+            .expect_err("should error"),
+        EvalAltResult::ErrorDataTooLarge(_, max, _, _) if max == engine.max_data_depth()
+    ));
+
+    engine.set_max_data_depth(10);
+
+    assert_eq!(
+        engine.eval::<rhai::INT>("let a = []; for i in range(0, 9) { a = [a]; } len(a)")?,
+        1
+    );
+    assert!(matches!(
+        *engine
+            .eval::<()>("let a = []; for i in range(0, 10) { a = [a]; }")
+            .expect_err("should error"),
+        EvalAltResult::ErrorDataTooLarge(_, 10, 11, _)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<()>("let a = []; for i in range(0, 20) { let b = a; a = []; push(a, b); }")
+            .expect_err("should error"),
+        EvalAltResult::ErrorDataTooLarge(_, 10, 11, _)
+    ));
+
+    #[cfg(not(feature = "no_object"))]
+    assert!(matches!(
+        *engine
+            .eval::<()>("let a = #{}; for i in range(0, 20) { a = #{ x: [a] }; }")
+            .expect_err("should error"),
+        EvalAltResult::ErrorDataTooLarge(_, 10, 11, _)
+    ));
+
+    // Data returned from a function is checked without being stored
+    #[cfg(not(feature = "no_function"))]
+    assert!(matches!(
+        *engine
+            .eval::<()>("fn f(n) { if n == 0 { return []; } [f(n - 1)] } f(12); ()")
+            .expect_err("should error"),
+        EvalAltResult::ErrorInFunctionCall(_, err, _)
+            if matches!(*err, EvalAltResult::ErrorDataTooLarge(_, 10, 11, _))
+    ));
+
+    engine.set_max_data_depth(0);
+
+    engine.eval::<()>("let a = []; for i in range(0, 100) { a = [a]; }")?;
+
+    // Only the limits that are set are checked
+    #[cfg(not(feature = "no_object"))]
+    {
+        let mut engine = Engine::new();
+        engine.set_max_map_size(10);
+        engine.eval::<rhai::Map>("#{ a: [1, 2, 3] }")?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use rhai::{DebuggerCommand, Engine, EvalAltResult, INT};
+use std::sync::{Arc, RwLock};
+
+#[test]
+fn test_debugger_step() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    let lines = Arc::new(RwLock::new(Vec::new()));
+    let logger = lines.clone();
+
+    engine.register_debugger(move |_, pos| {
+        logger.write().unwrap().push(pos.line().unwrap());
+        DebuggerCommand::StepInto
+    });
+
+    engine.consume(
+        r"
+            let x = 1;
+            if x > 0 {
+                x = 2;
+            }
+            x += 1;
+        ",
+    )?;
+
+    assert_eq!(*lines.read().unwrap(), vec![2, 3, 4, 6]);
+
+    Ok(())
+}
+
+#[test]
+fn test_debugger_breakpoints() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    let values = Arc::new(RwLock::new(Vec::new()));
+    let logger = values.clone();
+
+    engine.register_debugger(move |scope, pos| {
+        if pos.line() == Some(4) {
+            logger
+                .write()
+                .unwrap()
+                .push(scope.get_value::<INT>("x").unwrap());
+        }
+        DebuggerCommand::Continue
+    });
+    engine.set_breakpoint(4);
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                let x = 0;
+                for i in range(0, 3) {
+                    x += i;
+                }
+                x
+            "
+        )?,
+        3
+    );
+
+    assert_eq!(*values.read().unwrap(), vec![0, 0, 1]);
+
+    engine.remove_breakpoint(4);
+    values.write().unwrap().clear();
+
+    engine.consume("let x = 0;\nlet y = 1;\nlet z = 2;\nx = 3;")?;
+    assert!(values.read().unwrap().is_empty());
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_debugger_step_over() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    let lines = Arc::new(RwLock::new(Vec::new()));
+    let logger = lines.clone();
+
+    engine.register_debugger(move |_, pos| {
+        logger.write().unwrap().push(pos.line().unwrap());
+        DebuggerCommand::Break
+    });
+
+    engine.consume(
+        r"
+            fn foo(x) {
+                x + 1
+            }
+            let x = foo(1);
+            x += 1;
+        ",
+    )?;
+
+    assert_eq!(*lines.read().unwrap(), vec![5, 6]);
+
+    Ok(())
+}
+
+#[test]
+fn test_debugger_terminate() {
+    let mut engine = Engine::new();
+
+    engine.register_debugger(|scope, _| {
+        if scope.contains("y") {
+            DebuggerCommand::Terminate
+        } else {
+            DebuggerCommand::StepInto
+        }
+    });
+
+    assert!(matches!(
+        *engine
+            .consume("let x = 1; let y = 2; let z = 3;")
+            .expect_err("should error"),
+        EvalAltResult::ErrorTerminated(pos) if pos.position() == Some(27)
+    ));
+}
@@ -0,0 +1,112 @@
+#![cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+use rhai::{Engine, EvalAltResult, ParseErrorType, Scope, INT};
+
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_destructure_array() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("let [a, b] = [40, 2]; a + b")?, 42);
+    assert_eq!(engine.eval::<INT>("let [a, b,] = [40, 2, 99]; a + b")?, 42);
+    assert_eq!(engine.eval::<INT>("let [a, ..] = [42, 2, 99]; a")?, 42);
+    assert_eq!(
+        engine.eval::<INT>("let [a, ..rest] = [1, 2, 3, 4]; a * 10 + len(rest)")?,
+        13
+    );
+    assert_eq!(engine.eval::<()>("let [a, b, c] = [1, 2]; c")?, ());
+    assert_eq!(
+        engine.eval::<INT>("let x = 1; let [x, y] = [x + 40, x]; x + y")?,
+        42
+    );
+
+    let mut scope = Scope::new();
+    engine.eval_with_scope::<()>(&mut scope, "let [x, y] = [1, 2];")?;
+    assert_eq!(scope.get_value::<INT>("y"), Some(2));
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let [a] = 42; a")
+            .expect_err("should error"),
+        EvalAltResult::ErrorMismatchOutputType(_, _, _)
+    ));
+    assert!(matches!(
+        *engine
+            .compile("let [a, 1] = x;")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::VariableExpected
+    ));
+    assert!(matches!(
+        *engine
+            .compile("let [a, ..rest, b] = x;")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::MissingToken(_, _)
+    ));
+    assert!(matches!(
+        *engine.compile("let [a, b];").expect_err("should error").0,
+        ParseErrorType::MissingToken(_, _)
+    ));
+
+    engine.set_strict_destructuring(true);
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let [a, b, c] = [1, 2]; a")
+            .expect_err("should error"),
+        EvalAltResult::ErrorArrayBounds(2, 2, _)
+    ));
+    assert_eq!(engine.eval::<INT>("let [a] = [42, 0]; a")?, 42);
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_destructure_map() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>("let #{x, y} = #{x: 40, y: 2, z: 99}; x + y")?,
+        42
+    );
+    assert_eq!(engine.eval::<()>("let #{x, w} = #{x: 40}; w")?, ());
+
+    #[cfg(not(feature = "no_function"))]
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                fn point() { #{ x: 1, y: 2 } }
+
+                let #{ x, y } = point();
+                x * 10 + y
+            "
+        )?,
+        12
+    );
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let #{x} = 42; x")
+            .expect_err("should error"),
+        EvalAltResult::ErrorMismatchOutputType(_, _, _)
+    ));
+    assert!(matches!(
+        *engine
+            .compile("let #{x, ..} = y;")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::VariableExpected
+    ));
+
+    engine.set_strict_destructuring(true);
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let #{x, w} = #{x: 40}; x")
+            .expect_err("should error"),
+        EvalAltResult::ErrorDotExpr(_, _)
+    ));
+
+    Ok(())
+}
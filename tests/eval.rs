@@ -1,4 +1,4 @@
-use rhai::{Engine, EvalAltResult, Scope, INT};
+use rhai::{Engine, EvalAltResult, ParseErrorType, RegisterFn, Scope, INT};
 
 #[test]
 fn test_eval() -> Result<(), Box<EvalAltResult>> {
@@ -78,3 +78,121 @@ fn test_eval_override() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_eval_error_position() {
+    let engine = Engine::new();
+
+    let err = engine
+        .eval::<INT>(
+            r#"
+                let x = 40;
+                eval("x + y")
+            "#,
+        )
+        .expect_err("should error");
+
+    match *err {
+        EvalAltResult::ErrorInEval(ref inner, pos) => {
+            // Position of the call to 'eval'
+            assert_eq!(pos.line(), Some(3));
+            assert_eq!(pos.position(), Some(17));
+
+            // Position within the evaluated script text
+            assert!(matches!(
+                **inner,
+                EvalAltResult::ErrorVariableNotFound(ref name, pos)
+                    if name == "y" && pos.line() == Some(1) && pos.position() == Some(5)
+            ));
+        }
+        ref err => panic!("wrong error: {}", err),
+    }
+    assert_eq!(
+        err.to_string(),
+        "Error in evaluated script : Variable not found: 'y' (line 1, position 5) (line 3, position 17)"
+    );
+
+    // Syntax errors in the evaluated script
+    assert!(matches!(
+        *engine.eval::<INT>(r#"eval("40 +")"#).expect_err("should error"),
+        EvalAltResult::ErrorInEval(ref inner, _)
+            if matches!(**inner, EvalAltResult::ErrorParsing(_, _))
+    ));
+}
+
+#[test]
+fn test_eval_nested_error_position() {
+    let engine = Engine::new();
+
+    let err = engine
+        .eval::<INT>(r#"eval("1 + eval(\"x\")")"#)
+        .expect_err("should error");
+
+    assert_eq!(
+        err.to_string(),
+        "Error in evaluated script : Error in evaluated script : Variable not found: 'x' (line 1, position 1) (line 1, position 5) (line 1, position 1)"
+    );
+}
+
+#[test]
+fn test_eval_disabled() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    // Scripts compiled before eval is disabled fail when run
+    let ast = engine.compile(r#"eval("40 + 2")"#)?;
+
+    engine.set_allow_eval(false);
+    assert!(!engine.allow_eval());
+
+    assert!(matches!(
+        *engine.eval_ast::<INT>(&ast).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, pos) if msg == "'eval' is disabled" && pos.position() == Some(1)
+    ));
+
+    // Calls to eval are rejected at compile time
+    let err = engine
+        .compile("let x = 1;\nx + eval(\"40 + 2\")")
+        .expect_err("should error");
+    assert_eq!(*err.0, ParseErrorType::DisabledFunction("eval".into()));
+    assert_eq!(err.1.line(), Some(2));
+    assert_eq!(err.1.position(), Some(5));
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"eval("40 + 2")"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorParsing(ParseErrorType::DisabledFunction(_), _)
+    ));
+    assert!(matches!(
+        *engine
+            .eval_expression::<INT>(r#"eval("40 + 2")"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorParsing(ParseErrorType::DisabledFunction(_), _)
+    ));
+
+    #[cfg(not(feature = "no_function"))]
+    assert!(matches!(
+        *engine
+            .compile(r#"fn foo(x) { eval(x) }"#)
+            .expect_err("should error")
+            .0,
+        ParseErrorType::DisabledFunction(_)
+    ));
+
+    // Functions named eval are still allowed
+    #[cfg(not(feature = "no_function"))]
+    assert_eq!(
+        engine.eval::<INT>(r#"let x = eval("hello"); fn eval(s) { len(s) } x"#)?,
+        5
+    );
+
+    let mut engine2 = Engine::new();
+    engine2.set_allow_eval(false);
+    engine2.register_fn("eval", |s: &str| s.len() as INT);
+    assert_eq!(engine2.eval::<INT>(r#"eval("hello")"#)?, 5);
+
+    engine.set_allow_eval(true);
+    assert_eq!(engine.eval::<INT>(r#"eval("40 + 2")"#)?, 42);
+
+    Ok(())
+}
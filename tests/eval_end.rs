@@ -0,0 +1,122 @@
+use rhai::{Engine, EvalAltResult, Scope, INT};
+use std::sync::{Arc, RwLock};
+
+#[test]
+fn test_eval_end() -> Result<(), Box<EvalAltResult>> {
+    let log = Arc::new(RwLock::new(Vec::<String>::new()));
+    let logger = log.clone();
+
+    let mut engine = Engine::new();
+
+    engine.on_eval_end(move |scope| {
+        let names: Vec<_> = scope.iter().map(|(name, _, _)| name.to_string()).collect();
+        logger.write().unwrap().push(names.join(","));
+    });
+
+    assert_eq!(engine.eval::<INT>("let x = 40; x + 2")?, 42);
+    assert_eq!(*log.read().unwrap(), ["x"]);
+
+    engine.consume("let y = 1; let z = 2;")?;
+    assert_eq!(log.read().unwrap().last().unwrap(), "y,z");
+
+    // Called even when the script fails
+    assert!(engine.eval::<INT>("let a = 1; throw 42;").is_err());
+    assert_eq!(log.read().unwrap().last().unwrap(), "a");
+
+    assert!(engine.consume("let b = 1; foo(b);").is_err());
+    assert_eq!(log.read().unwrap().last().unwrap(), "b");
+
+    // Not called when the script does not compile
+    assert!(engine.eval::<INT>("let c = ;").is_err());
+    assert_eq!(log.read().unwrap().len(), 4);
+
+    // The scope can be modified
+    engine.on_eval_end(|scope| {
+        scope.set_value("x", 0 as INT);
+    });
+
+    let mut scope = Scope::new();
+    scope.push("x", 40 as INT);
+
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "x += 2; x")?, 42);
+    assert_eq!(scope.get_value::<INT>("x").unwrap(), 0);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "unchecked"))]
+fn test_eval_end_terminated() -> Result<(), Box<EvalAltResult>> {
+    let count = Arc::new(RwLock::new(0));
+    let counter = count.clone();
+
+    let mut engine = Engine::new();
+    engine.set_max_operations(100);
+    engine.on_eval_end(move |_| *counter.write().unwrap() += 1);
+
+    assert!(matches!(
+        *engine.eval::<()>("loop {}").expect_err("should error"),
+        EvalAltResult::ErrorTooManyOperations(_)
+    ));
+    assert_eq!(*count.read().unwrap(), 1);
+
+    engine.on_progress(|_| false);
+
+    assert!(matches!(
+        *engine.consume("let x = 1;").expect_err("should error"),
+        EvalAltResult::ErrorTerminated(_)
+    ));
+    assert_eq!(*count.read().unwrap(), 2);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_eval_end_call_fn() -> Result<(), Box<EvalAltResult>> {
+    let count = Arc::new(RwLock::new(0));
+    let counter = count.clone();
+
+    let mut engine = Engine::new();
+    engine.on_eval_end(move |_| *counter.write().unwrap() += 1);
+
+    let ast = engine.compile("fn add(x, y) { x + y } fn fail() { throw 42; }")?;
+    let mut scope = Scope::new();
+
+    assert_eq!(
+        engine.call_fn::<_, INT>(&mut scope, &ast, "add", (40 as INT, 2 as INT))?,
+        42
+    );
+    assert!(engine
+        .call_fn::<_, INT>(&mut scope, &ast, "fail", ())
+        .is_err());
+    assert_eq!(*count.read().unwrap(), 2);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_module"))]
+fn test_eval_end_imported_fn() -> Result<(), Box<EvalAltResult>> {
+    use rhai::module_resolvers::FileModuleResolver;
+
+    let dir = std::env::temp_dir().join("rhai_test_eval_end_imported_fn");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("m.rhai"), "fn f(x) { x + 1 }").unwrap();
+
+    let count = Arc::new(RwLock::new(0));
+    let counter = count.clone();
+
+    let mut engine = Engine::new();
+    engine.set_module_resolver(Some(FileModuleResolver::new_with_path(&dir)));
+    engine.on_eval_end(move |_| *counter.write().unwrap() += 1);
+
+    assert_eq!(
+        engine.eval::<INT>(r#"import "m" as m; m::f(1) + m::f(39)"#)?,
+        42
+    );
+    assert_eq!(*count.read().unwrap(), 1);
+
+    Ok(())
+}
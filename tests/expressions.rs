@@ -1,4 +1,4 @@
-use rhai::{Engine, EvalAltResult, Scope, INT};
+use rhai::{Engine, EvalAltResult, LexError, ParseErrorType, Scope, INT};
 
 #[test]
 fn test_expressions() -> Result<(), Box<EvalAltResult>> {
@@ -26,6 +26,46 @@ fn test_expressions() -> Result<(), Box<EvalAltResult>> {
     Ok(())
 }
 
+#[test]
+fn test_expressions_only() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+    let mut scope = Scope::new();
+
+    scope.push("x", 10 as INT);
+
+    // Function calls are expressions
+    assert_eq!(
+        engine.eval_expression_with_scope::<INT>(&mut scope, "abs(-x) + 32")?,
+        42
+    );
+
+    // Assignments are statements
+    for script in &["x += 1", "x = x + 1"] {
+        assert!(matches!(
+            *engine
+                .compile_expression(script)
+                .expect_err("should error")
+                .0,
+            ParseErrorType::LexError(LexError::UnexpectedInput(_))
+        ));
+    }
+    #[cfg(not(feature = "no_index"))]
+    assert!(engine.compile_expression("x[0] = 1").is_err());
+    #[cfg(not(feature = "no_object"))]
+    assert!(engine.compile_expression("x.a = 1").is_err());
+
+    #[cfg(not(feature = "no_module"))]
+    assert!(engine.compile_expression(r#"import "foo" as foo"#).is_err());
+    #[cfg(not(feature = "no_function"))]
+    assert!(engine.compile_expression("fn foo() { 42 }").is_err());
+
+    assert!(engine.compile_expression("while x > 0 { }").is_err());
+    assert!(engine.compile_expression("40 + 2; 1").is_err());
+    assert_eq!(scope.get_value::<INT>("x").unwrap(), 10);
+
+    Ok(())
+}
+
 /// This example taken from https://github.com/jonathandturner/rhai/issues/115
 #[test]
 #[cfg(not(feature = "no_object"))]
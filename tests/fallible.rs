@@ -0,0 +1,55 @@
+use rhai::{Engine, EvalAltResult, RegisterFn, INT};
+
+fn checked_div(x: INT, y: INT) -> Result<INT, Box<EvalAltResult>> {
+    if y == 0 {
+        Err("division by zero".into())
+    } else {
+        Ok(x / y)
+    }
+}
+
+#[test]
+fn test_fallible_register_fn() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_fn("div", checked_div);
+    engine.register_fn(
+        "greet",
+        |name: &str| -> Result<String, Box<EvalAltResult>> {
+            if name.is_empty() {
+                Err("no name".into())
+            } else {
+                Ok(format!("hello, {}!", name))
+            }
+        },
+    );
+    engine.register_fn("bump", |x: &mut INT| -> Result<(), Box<EvalAltResult>> {
+        *x += 1;
+        if *x > 2 {
+            Err("too large".into())
+        } else {
+            Ok(())
+        }
+    });
+
+    assert_eq!(engine.eval::<INT>("div(84, 2)")?, 42);
+    assert_eq!(engine.eval::<String>(r#"greet("world")"#)?, "hello, world!");
+    assert_eq!(engine.eval::<INT>("let x = 0; bump(x); bump(x); x")?, 2);
+
+    assert!(matches!(
+        *engine.eval::<INT>("div(42, 0)").expect_err("should error"),
+        EvalAltResult::ErrorRuntime(s, _) if s == "division by zero"
+    ));
+    assert!(matches!(
+        *engine.eval::<String>(r#"greet("")"#).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(s, _) if s == "no name"
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let x = 2; bump(x); x")
+            .expect_err("should error"),
+        EvalAltResult::ErrorRuntime(s, _) if s == "too large"
+    ));
+
+    Ok(())
+}
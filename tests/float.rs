@@ -1,5 +1,5 @@
 #![cfg(not(feature = "no_float"))]
-use rhai::{Engine, EvalAltResult, RegisterFn, FLOAT};
+use rhai::{Engine, EvalAltResult, RegisterFn, FLOAT, INT};
 
 const EPSILON: FLOAT = 0.000_000_000_1;
 
@@ -20,11 +20,127 @@ fn test_float() -> Result<(), Box<EvalAltResult>> {
     Ok(())
 }
 
+#[test]
+fn test_float_int_compare() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<bool>("1 == 1.0")?, true);
+    assert_eq!(engine.eval::<bool>("1.0 == 1")?, true);
+    assert_eq!(engine.eval::<bool>("1 != 1.5")?, true);
+    assert_eq!(engine.eval::<bool>("1 < 1.5")?, true);
+    assert_eq!(engine.eval::<bool>("1.5 < 1")?, false);
+    assert_eq!(engine.eval::<bool>("-2 <= -1.5")?, true);
+    assert_eq!(engine.eval::<bool>("-1.5 >= -1")?, false);
+    assert_eq!(engine.eval::<bool>("2 > 1.5")?, true);
+    assert_eq!(engine.eval::<bool>("let x = 0.0; x == 0")?, true);
+
+    // NaN compares false with everything
+    assert_eq!(engine.eval::<bool>("let x = 0.0 / 0.0; x == 0")?, false);
+    assert_eq!(
+        engine.eval::<bool>("let x = 0.0 / 0.0; x < 0 || x >= 0")?,
+        false
+    );
+    assert_eq!(engine.eval::<bool>("let x = 0.0 / 0.0; x != 0")?, true);
+
+    // Large integers are compared exactly
+    #[cfg(not(feature = "only_i32"))]
+    {
+        assert_eq!(
+            engine.eval::<bool>("9007199254740993 == 9007199254740992.0")?,
+            false
+        );
+        assert_eq!(
+            engine.eval::<bool>("9007199254740993 > 9007199254740992.0")?,
+            true
+        );
+        assert_eq!(
+            engine.eval::<bool>("9223372036854775807 < 9223372036854775807.0")?,
+            true
+        );
+        assert_eq!(
+            engine.eval::<bool>("-18446744073709551616.0 < -9223372036854775807")?,
+            true
+        );
+    }
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(engine.eval::<bool>("2 in [1.0, 2.0, 3.0]")?, true);
+
+    // Also works without the standard packages
+    let engine = Engine::new_raw();
+    assert_eq!(engine.eval::<bool>("1 == 1.0")?, true);
+    assert_eq!(engine.eval::<bool>("0.5 < 1")?, true);
+
+    Ok(())
+}
+
 #[test]
 fn test_float_parse() -> Result<(), Box<EvalAltResult>> {
     let engine = Engine::new();
 
     assert!((engine.eval::<FLOAT>(r#"parse_float("9.9999")"#)? - 9.9999 as FLOAT).abs() < EPSILON);
+    assert!((engine.eval::<FLOAT>(r#"parse_float(" -1.5 ")"#)? + 1.5).abs() < EPSILON);
+
+    assert!(matches!(
+        *engine.eval::<FLOAT>(r#"parse_float("1.5.5")"#).expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(msg, _) if msg.starts_with("Error parsing floating-point number")
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_float_rounding() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<FLOAT>("round(2.5)")?, 3.0);
+    assert_eq!(engine.eval::<FLOAT>("round(-2.5)")?, -3.0);
+    assert_eq!(engine.eval::<FLOAT>("round(2.4)")?, 2.0);
+    assert_eq!(engine.eval::<FLOAT>("floor(-2.5)")?, -3.0);
+    assert_eq!(engine.eval::<FLOAT>("ceil(2.1)")?, 3.0);
+    assert_eq!(engine.eval::<FLOAT>("ceiling(-2.1)")?, -2.0);
+
+    assert!((engine.eval::<FLOAT>("round_to(2.71828, 2)")? - 2.72).abs() < EPSILON);
+    assert!((engine.eval::<FLOAT>("round_to(2.71828, 0)")? - 3.0).abs() < EPSILON);
+    assert_eq!(engine.eval::<FLOAT>("round_to(1234.5, -2)")?, 1200.0);
+    assert_eq!(engine.eval::<FLOAT>("round_to(1250.0, -2)")?, 1300.0);
+    assert_eq!(engine.eval::<FLOAT>("round_to(1234.5, -9999)")?, 0.0);
+    assert_eq!(engine.eval::<FLOAT>("round_to(1234.5, 9999)")?, 1234.5);
+
+    Ok(())
+}
+
+#[test]
+fn test_float_to_int() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("to_int(2.9)")?, 2);
+    assert_eq!(engine.eval::<INT>("to_int(-2.9)")?, -2);
+    assert_eq!(engine.eval::<INT>("to_int(round(2.5))")?, 3);
+    assert_eq!(engine.eval::<INT>("to_int_checked(-42.5)")?, -42);
+
+    assert!(matches!(
+        *engine.eval::<INT>("to_int(parse_float(\"NaN\"))").expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(msg, _) if msg.starts_with("Cannot convert NaN")
+    ));
+    assert!(matches!(
+        *engine.eval::<INT>("to_int(-1.0 / 0.0)").expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(msg, _) if msg.starts_with("Cannot convert -inf")
+    ));
+    assert!(matches!(
+        *engine.eval::<INT>("to_int_checked(parse_float(\"1e100\"))").expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(msg, _) if msg.starts_with("Integer overflow")
+    ));
+    assert!(matches!(
+        *engine.eval::<INT>("to_int_checked(parse_float(\"-1e100\"))").expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(msg, _) if msg.starts_with("Integer overflow")
+    ));
+
+    #[cfg(not(feature = "unchecked"))]
+    assert!(matches!(
+        *engine.eval::<INT>("to_int(parse_float(\"1e100\"))").expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(msg, _) if msg.starts_with("Integer overflow")
+    ));
 
     Ok(())
 }
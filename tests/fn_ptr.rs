@@ -108,5 +108,49 @@ fn test_fn_ptr_curry() -> Result<(), Box<EvalAltResult>> {
         42
     );
 
+    // Currying all the arguments
+    assert_eq!(
+        engine.eval::<INT>(r#"let f = curry(Fn("foo"), 40, 2); call(f)"#)?,
+        42
+    );
+
+    // Currying more arguments than the function takes
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"let f = curry(Fn("foo"), 40, 2, 1); call(f)"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _)
+            if msg == "Cannot curry 3 argument(s) into function 'foo', which takes at most 2 parameter(s)"
+    ));
+
+    #[cfg(not(feature = "no_object"))]
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"let f = Fn("foo").curry(40); f.curry(2).curry(1)"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg.starts_with("Cannot curry 3 argument(s)")
+    ));
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        assert_eq!(
+            engine.eval::<INT>(
+                r#"
+                    fn add(x, y, z) { x + y + z }
+                    let f = curry(Fn("add"), 1);
+                    let g = curry(f, 2);
+                    call(g, 39)
+                "#
+            )?,
+            42
+        );
+        assert!(engine
+            .eval::<INT>(r#"fn add(x, y) { x + y } curry(Fn("add"), 1, 2, 3)"#)
+            .is_err());
+
+        // Functions not (yet) defined are not checked
+        engine.eval::<()>(r#"let f = curry(Fn("unknown"), 1, 2, 3);"#)?;
+    }
+
     Ok(())
 }
@@ -1,4 +1,8 @@
-use rhai::{Engine, EvalAltResult, INT};
+use rhai::{Engine, EvalAltResult, ExternalIterator, RegisterFn, INT};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
 
 #[cfg(not(feature = "no_index"))]
 #[test]
@@ -75,3 +79,57 @@ fn test_for_object() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_for_external_iterator() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    let pulled = Arc::new(AtomicUsize::new(0));
+    let counter = pulled.clone();
+
+    engine.register_fn("numbers", move |n: INT| {
+        let counter = counter.clone();
+        ExternalIterator::new((1..=n).inspect(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }))
+    });
+
+    // Values are streamed, not collected
+    assert_eq!(
+        engine.eval::<INT>("let n = 0; for x in numbers(1000000) { n += 1; } n")?,
+        1000000
+    );
+    assert_eq!(pulled.swap(0, Ordering::SeqCst), 1000000);
+
+    // Values are pulled only when needed
+    assert_eq!(
+        engine.eval::<INT>(
+            "let n = 0; for x in numbers(1000000) { n = x; if x == 3 { break; } } n"
+        )?,
+        3
+    );
+    assert_eq!(pulled.swap(0, Ordering::SeqCst), 3);
+
+    // An exhausted iterator ends the loop without errors
+    assert_eq!(
+        engine.eval::<INT>("let n = 0; for x in numbers(0) { n += 1; } n")?,
+        0
+    );
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                let source = numbers(5);
+                let sum = 0;
+                while let x = next(source) { sum += x; }
+                sum
+            "
+        )?,
+        15
+    );
+    engine.eval::<()>("let source = numbers(1); next(source); next(source)")?;
+
+    assert_eq!(engine.eval::<String>("type_of(numbers(5))")?, "iterator");
+
+    Ok(())
+}
@@ -0,0 +1,207 @@
+#![cfg(not(feature = "no_object"))]
+
+use rhai::{Dynamic, Engine, EvalAltResult, Map, Scope, INT};
+
+#[cfg(not(feature = "no_index"))]
+use rhai::Array;
+
+fn make_table() -> Dynamic {
+    let mut table = Map::new();
+    table.insert("a".into(), (1 as INT).into());
+    table.insert("name".into(), "hello".into());
+
+    let mut inner = Map::new();
+    inner.insert("x".into(), (42 as INT).into());
+    table.insert("inner".into(), inner.into());
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        let list: Array = vec![(1 as INT).into(), (2 as INT).into(), (3 as INT).into()];
+        table.insert("list".into(), list.into());
+    }
+
+    Dynamic::from(table).into_frozen()
+}
+
+#[test]
+fn test_frozen_read() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+    let mut scope = Scope::new();
+
+    let table = make_table();
+    assert!(table.is_frozen());
+    assert_eq!(table.type_name(), "map");
+
+    scope.push("table", table.clone());
+
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "table.a")?, 1);
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "table.inner.x")?,
+        42
+    );
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        engine.eval_with_scope::<String>(&mut scope, r#"table["name"]"#)?,
+        "hello"
+    );
+    assert_eq!(
+        engine.eval_with_scope::<String>(&mut scope, "type_of(table)")?,
+        "map"
+    );
+    assert!(engine.eval_with_scope::<bool>(&mut scope, r#""inner" in table"#)?);
+    assert!(!engine.eval_with_scope::<bool>(&mut scope, r#""foo" in table"#)?);
+    assert!(engine
+        .eval_with_scope::<()>(&mut scope, "table.foo")
+        .is_ok());
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "table.list[1] + table.list[2]")?,
+        5
+    );
+
+    // Reading does not copy the frozen value
+    assert!(scope.get_value::<Dynamic>("table").unwrap().is_frozen());
+
+    Ok(())
+}
+
+#[test]
+fn test_frozen_copy_on_write() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+    let mut scope = Scope::new();
+
+    scope.push("table", make_table());
+
+    assert_eq!(
+        engine
+            .eval_with_scope::<INT>(&mut scope, "let t = table; t.a = 42; t.a * 100 + table.a")?,
+        4201
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(
+            &mut scope,
+            "let t = table; t.inner.x += 1; t.inner.x * 100 + table.inner.x"
+        )?,
+        4342
+    );
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        assert_eq!(
+            engine.eval_with_scope::<INT>(
+                &mut scope,
+                "let t = table; t.list.push(4); t.list.len() * 10 + len(table.list)"
+            )?,
+            43
+        );
+        assert_eq!(
+            engine.eval_with_scope::<INT>(
+                &mut scope,
+                "let t = table; t.list[0] = 9; t.list[0] * 10 + table.list[0]"
+            )?,
+            91
+        );
+    }
+
+    #[cfg(not(feature = "no_function"))]
+    assert_eq!(
+        engine.eval_with_scope::<INT>(
+            &mut scope,
+            "fn change(t) { t.a = 0; t.a } change(table) * 10 + table.a"
+        )?,
+        1
+    );
+
+    // The original value is still frozen and unchanged
+    let table = scope.get_value::<Dynamic>("table").unwrap();
+    assert!(table.is_frozen());
+    assert_eq!(table.cast::<Map>()["a"].as_int().unwrap(), 1);
+
+    // Modifying the variable itself copies it
+    engine.eval_with_scope::<()>(&mut scope, "table.a = 2")?;
+    assert!(!scope.get_value::<Dynamic>("table").unwrap().is_frozen());
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "table.a")?, 2);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+fn test_frozen_array() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+    let mut scope = Scope::new();
+
+    let array: Array = (1..=5).map(|x| (x as INT).into()).collect();
+    scope.push_constant("DATA", Dynamic::from(array).into_frozen());
+
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "let s = 0; for x in DATA { s += x; } s")?,
+        15
+    );
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "DATA[4]")?, 5);
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "DATA.len()")?, 5);
+    assert!(matches!(
+        *engine
+            .eval_with_scope::<INT>(&mut scope, "DATA[5]")
+            .expect_err("should error"),
+        EvalAltResult::ErrorArrayBounds(5, 5, _)
+    ));
+
+    let array = scope.get_value::<Array>("DATA").unwrap();
+    assert_eq!(array.len(), 5);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "unchecked"))]
+#[cfg(not(feature = "no_index"))]
+fn test_frozen_data_size() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let mut scope = Scope::new();
+
+    let array: Array = (1..=5).map(|x| (x as INT).into()).collect();
+    scope.push_constant("DATA", Dynamic::from(array).into_frozen());
+
+    // The items inside a frozen array count towards the limits
+    engine.set_max_array_size(8);
+
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "let x = [DATA, 1]; x.len()")?,
+        2
+    );
+    assert!(matches!(
+        *engine
+            .eval_with_scope::<()>(&mut scope, "let x = [DATA, DATA];")
+            .expect_err("should error"),
+        EvalAltResult::ErrorDataTooLarge(_, 8, 10, _)
+    ));
+
+    engine.set_max_array_size(0);
+    engine.set_max_data_depth(2);
+
+    engine.eval_with_scope::<()>(&mut scope, "let x = [DATA];")?;
+    assert!(matches!(
+        *engine
+            .eval_with_scope::<()>(&mut scope, "let x = [[DATA]];")
+            .expect_err("should error"),
+        EvalAltResult::ErrorDataTooLarge(_, 2, 3, _)
+    ));
+
+    // The memory used by a frozen array is counted as well
+    let array: Array = (0..100).map(|_| "hello, world!".into()).collect();
+    scope.push_constant("BIG", Dynamic::from(array).into_frozen());
+
+    engine.set_max_data_depth(0);
+    engine.set_max_memory(1_000);
+
+    assert!(matches!(
+        *engine
+            .eval_with_scope::<()>(&mut scope, "let x = [BIG];")
+            .expect_err("should error"),
+        EvalAltResult::ErrorDataTooLarge(_, 1_000, _, _)
+    ));
+
+    Ok(())
+}
@@ -1,5 +1,5 @@
 #![cfg(not(feature = "no_function"))]
-use rhai::{Engine, EvalAltResult, ParseErrorType, INT};
+use rhai::{Engine, EvalAltResult, ParseErrorType, RegisterFn, INT};
 
 #[test]
 fn test_functions() -> Result<(), Box<EvalAltResult>> {
@@ -173,3 +173,86 @@ fn test_function_captures() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+#[cfg(not(feature = "unchecked"))]
+fn test_function_max_params() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    assert_eq!(engine.max_fn_params(), 20);
+
+    fn make_script(num_params: usize) -> String {
+        let params: Vec<_> = (0..num_params).map(|n| format!("p{}", n)).collect();
+        let args: Vec<_> = (0..num_params).map(|n| n.to_string()).collect();
+
+        format!(
+            "fn foo({}) {{ p0 + p{} }} foo({})",
+            params.join(", "),
+            num_params - 1,
+            args.join(", ")
+        )
+    }
+
+    assert_eq!(engine.eval::<INT>(&make_script(20))?, 19);
+
+    // One parameter over the limit
+    let err = engine.compile(&make_script(21)).expect_err("should error");
+    assert_eq!(
+        *err.0,
+        ParseErrorType::FnTooManyParams("foo".to_string(), 20)
+    );
+    assert_eq!(err.1.position(), Some(98));
+
+    engine.set_max_fn_params(2);
+
+    assert_eq!(engine.eval::<INT>("fn foo(x, y) { x + y } foo(40, 2)")?, 42);
+    assert!(matches!(
+        *engine
+            .compile("fn foo(x, y, z) { x + y + z }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::FnTooManyParams(ref name, 2) if name == "foo"
+    ));
+    assert!(matches!(
+        *engine
+            .compile("let f = |x, y, z| x + y + z;")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::FnTooManyParams(ref name, 2) if name.is_empty()
+    ));
+
+    engine.set_max_fn_params(0);
+
+    assert_eq!(engine.eval::<INT>(&make_script(30))?, 29);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "unchecked"))]
+fn test_function_max_params_native() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.set_max_fn_params(2);
+    engine
+        .register_fn("add2", |x: INT, y: INT| x + y)
+        .register_fn("add3", |x: INT, y: INT, z: INT| x + y + z);
+
+    assert_eq!(engine.eval::<INT>("add2(40, 2)")?, 42);
+
+    // Registering a function over the limit does not fail, but calling it does
+    let err = engine
+        .eval::<INT>("add3(1, 2, 3)")
+        .expect_err("should error");
+    assert!(matches!(
+        *err,
+        EvalAltResult::ErrorDataTooLarge(ref typ, 2, 3, _)
+            if typ == "Number of parameters of function 'add3'"
+    ));
+    assert_eq!(
+        err.to_string(),
+        "Number of parameters of function 'add3' (3) exceeds the maximum limit (2) (line 1, position 1)"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+#![cfg(feature = "json")]
+use rhai::{Engine, EvalAltResult, RegisterFn, INT};
+
+#[test]
+fn test_json_parse() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>(r#"parse_json("42")"#)?, 42);
+    assert_eq!(engine.eval::<bool>(r#"parse_json(" true ")"#)?, true);
+    assert_eq!(engine.eval::<()>(r#"parse_json("null")"#)?, ());
+    assert_eq!(
+        engine.eval::<String>(r#"parse_json("\"hello\"")"#)?,
+        "hello"
+    );
+
+    #[cfg(not(feature = "no_index"))]
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                let config = parse_json("{\"a\": [1, 2, {\"b\": 39}], \"c\": \"x\"}");
+                config.a[0] + config.a[1] + config.a[2].b
+            "#
+        )?,
+        42
+    );
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        use rhai::FLOAT;
+
+        // Too large for INT - falls back to floating-point
+        assert_eq!(
+            engine.eval::<FLOAT>(r#"parse_json("18446744073709551615")"#)?,
+            18446744073709551615_u64 as FLOAT
+        );
+        assert_eq!(engine.eval::<FLOAT>(r#"parse_json("1.5")"#)?, 1.5);
+    }
+
+    assert!(matches!(
+        *engine.eval::<INT>(r#"parse_json("[1, x]")"#).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg.starts_with("Invalid JSON at byte offset 4:")
+    ));
+    assert!(matches!(
+        *engine.eval::<INT>(r#"parse_json("[1,\n  2,\n  }")"#).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg.starts_with("Invalid JSON at byte offset 11:")
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_json_stringify() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>("to_json(42)")?, "42");
+    assert_eq!(engine.eval::<String>("to_json(())")?, "null");
+    assert_eq!(engine.eval::<String>(r#"to_json("hi")"#)?, r#""hi""#);
+
+    #[cfg(not(feature = "no_index"))]
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert_eq!(
+            engine.eval::<String>(r#"to_json(#{ a: [1, true, "x"], b: #{} })"#)?,
+            r#"{"a":[1,true,"x"],"b":{}}"#
+        );
+        assert_eq!(
+            engine.eval::<String>(r#"to_json(parse_json("{\"a\": [1, 2, {\"b\": null}]}"))"#)?,
+            r#"{"a":[1,2,{"b":null}]}"#
+        );
+
+        // Properties keep their order
+        assert_eq!(
+            engine.eval::<String>(r#"let m = #{ z: 1, a: 2 }; m.m = 3; to_json(m)"#)?,
+            r#"{"z":1,"a":2,"m":3}"#
+        );
+        assert_eq!(
+            engine.eval::<String>(r#"to_json(parse_json("{\"y\": 1, \"x\": 2}"))"#)?,
+            r#"{"y":1,"x":2}"#
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_json_custom_type() {
+    #[derive(Debug, Clone)]
+    struct Point;
+
+    let mut engine = Engine::new();
+    engine.register_fn("new_point", || Point);
+
+    let err = engine
+        .eval::<String>("to_json(new_point())")
+        .expect_err("should error");
+
+    assert!(matches!(
+        *err,
+        EvalAltResult::ErrorRuntime(ref msg, _)
+            if msg.starts_with("Cannot serialize type '") && msg.ends_with("Point' into JSON")
+    ));
+    assert_eq!(err.position().line(), Some(1));
+
+    #[cfg(not(feature = "no_index"))]
+    assert!(matches!(
+        *engine.eval::<String>("to_json([1, new_point()])").expect_err("should error"),
+        EvalAltResult::ErrorRuntime(ref msg, _) if msg.ends_with("Point' into JSON")
+    ));
+
+    #[cfg(not(feature = "no_object"))]
+    assert!(matches!(
+        *engine
+            .eval::<String>("to_json(#{ a: 1, b: #{ c: new_point() } })")
+            .expect_err("should error"),
+        EvalAltResult::ErrorRuntime(ref msg, _) if msg.ends_with("Point' into JSON")
+    ));
+}
@@ -140,6 +140,70 @@ fn test_map_for() -> Result<(), Box<EvalAltResult>> {
     Ok(())
 }
 
+#[test]
+#[cfg(not(feature = "no_index"))]
+fn test_map_order() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>(
+            r#"
+                let map = #{z: 1, a: 2, m: 3, b: 4};
+                map.x = 5;
+                map.remove("m");
+                map.mixin(#{a: 0, c: 6});
+
+                let s = "";
+                for key in keys(map) { s += key; }
+                for value in values(map) { s += value; }
+                s
+            "#
+        )?,
+        "zabxc10456"
+    );
+
+    let map = engine.eval::<Map>("#{c: 1, b: 2, a: 3}")?;
+    assert_eq!(
+        map.keys().map(|k| k.as_str()).collect::<Vec<_>>(),
+        ["c", "b", "a"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_map_syntax() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("let x = #{a: 1,}; x.a")?, 1);
+    assert_eq!(engine.eval::<INT>("let x = #{a: 1, b: 2,}; x.b")?, 2);
+    assert!(engine.eval::<Map>("#{}")?.is_empty());
+    assert_eq!(engine.eval::<String>("type_of(#{})")?, "map");
+
+    assert!(matches!(
+        *engine.compile("let x = {};").expect_err("should error").0,
+        ParseErrorType::BadInput(msg) if msg.contains("#{}")
+    ));
+    assert!(matches!(
+        *engine.compile("let x = {a: 1};").expect_err("should error").0,
+        ParseErrorType::BadInput(msg) if msg.contains("#{")
+    ));
+    assert!(matches!(
+        *engine.compile(r#"let x = {"a": 1};"#).expect_err("should error").0,
+        ParseErrorType::BadInput(msg) if msg.contains("#{")
+    ));
+    assert!(matches!(
+        *engine.compile("let x = #{,};").expect_err("should error").0,
+        ParseErrorType::MissingToken(_, _)
+    ));
+
+    // Empty statement blocks are still fine
+    assert_eq!(engine.eval::<INT>("{} if true {} else {} 42")?, 42);
+    assert_eq!(engine.eval::<INT>("let x = { 42 }; x")?, 42);
+
+    Ok(())
+}
+
 #[test]
 /// Because a Rhai object map literal is almost the same as JSON,
 /// it is possible to convert from JSON into a Rhai object map.
@@ -1,4 +1,4 @@
-use rhai::{Engine, EvalAltResult, INT};
+use rhai::{DivByZero, Engine, EvalAltResult, INT};
 
 #[cfg(not(feature = "no_float"))]
 use rhai::FLOAT;
@@ -109,6 +109,122 @@ fn test_math() -> Result<(), Box<EvalAltResult>> {
     Ok(())
 }
 
+#[test]
+#[cfg(not(feature = "unchecked"))]
+#[cfg(not(feature = "only_i32"))]
+fn test_math_error_position() {
+    let engine = Engine::new();
+
+    let position = |script: &str| match *engine.eval::<()>(script).expect_err("should error") {
+        EvalAltResult::ErrorArithmetic(_, pos) => (pos.line().unwrap(), pos.position().unwrap()),
+        err => panic!("wrong error: {}", err),
+    };
+
+    // The error points at the operator
+    assert_eq!(position("let x = 9223372036854775807 + 1;"), (1, 29));
+    assert_eq!(position("let x = 1;\nlet y = x * 9223372036854775807 * 2;"), (2, 33));
+    assert_eq!(position("let x = 0; let y = 42 / x;"), (1, 23));
+    assert_eq!(position("let x = -9223372036854775807 - 1; let y = -x;"), (1, 43));
+    assert_eq!(position("let x = 9223372036854775807; x += 1;"), (1, 32));
+    assert_eq!(position("let x = 42; x %= 0;"), (1, 15));
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(position("let a = [9223372036854775807]; a[0] += 1;"), (1, 37));
+}
+
+#[test]
+fn test_math_div_by_zero() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    assert_eq!(engine.div_by_zero(), DivByZero::Error);
+
+    #[cfg(not(feature = "unchecked"))]
+    {
+        assert!(matches!(
+            *engine
+                .eval::<INT>("let x = 0; 42 / x")
+                .expect_err("should error"),
+            EvalAltResult::ErrorArithmetic(_, _)
+        ));
+        assert!(matches!(
+            *engine
+                .eval::<INT>("let x = 0; 42 % x")
+                .expect_err("should error"),
+            EvalAltResult::ErrorArithmetic(_, _)
+        ));
+        assert!(matches!(
+            *engine
+                .eval::<INT>("let x = 42; x /= 0; x")
+                .expect_err("should error"),
+            EvalAltResult::ErrorArithmetic(_, _)
+        ));
+    }
+
+    engine.set_div_by_zero(DivByZero::Zero);
+
+    assert_eq!(engine.eval::<INT>("let x = 0; 42 / x")?, 0);
+    assert_eq!(engine.eval::<INT>("let x = 0; 42 % x")?, 0);
+    assert_eq!(engine.eval::<INT>("-42 / 0")?, 0);
+    assert_eq!(engine.eval::<INT>("let x = 42; x /= 0; x")?, 0);
+    assert_eq!(engine.eval::<INT>("let x = 42; x %= 0; x")?, 0);
+    assert_eq!(engine.eval::<INT>("42 / 5")?, 8);
+    assert_eq!(engine.eval::<INT>("42 % 5")?, 2);
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(engine.eval::<INT>("let a = [42]; a[0] /= 0; a[0]")?, 0);
+
+    engine.set_div_by_zero(DivByZero::MaxValue);
+
+    assert_eq!(engine.eval::<INT>("let x = 0; 42 / x")?, INT::MAX);
+    assert_eq!(engine.eval::<INT>("let x = 0; 42 % x")?, INT::MAX);
+    assert_eq!(engine.eval::<INT>("let x = 42; x /= 0; x")?, INT::MAX);
+    assert_eq!(engine.eval::<INT>("let x = 42; x %= 0; x")?, INT::MAX);
+    assert_eq!(engine.eval::<INT>("42 / 5")?, 8);
+
+    #[cfg(not(feature = "only_i32"))]
+    #[cfg(not(feature = "only_i64"))]
+    {
+        use rhai::RegisterFn;
+
+        engine.register_fn("byte", |x: INT| x as u8);
+        assert_eq!(engine.eval::<u8>("byte(42) / byte(0)")?, u8::MAX);
+        assert_eq!(engine.eval::<u8>("byte(42) % byte(0)")?, u8::MAX);
+    }
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        use rhai::FloatDivByZero;
+
+        assert_eq!(engine.float_div_by_zero(), FloatDivByZero::Infinity);
+        assert_eq!(engine.eval::<FLOAT>("1.0 / 0.0")?, FLOAT::INFINITY);
+        assert_eq!(engine.eval::<FLOAT>("-1.0 / 0.0")?, FLOAT::NEG_INFINITY);
+        assert!(engine.eval::<FLOAT>("1.0 % 0.0")?.is_nan());
+
+        engine.set_float_div_by_zero(FloatDivByZero::Error);
+
+        assert!(matches!(
+            *engine.eval::<FLOAT>("let x = 0.0; 1.0 / x").expect_err("should error"),
+            EvalAltResult::ErrorArithmetic(msg, _) if msg.starts_with("Division by zero")
+        ));
+        assert!(matches!(
+            *engine.eval::<FLOAT>("let x = 0.0; 1.0 % x").expect_err("should error"),
+            EvalAltResult::ErrorArithmetic(msg, _) if msg.starts_with("Modulo division by zero")
+        ));
+        assert!(matches!(
+            *engine
+                .eval::<FLOAT>("let x = 1.0; x /= 0.0; x")
+                .expect_err("should error"),
+            EvalAltResult::ErrorArithmetic(_, _)
+        ));
+        assert_eq!(engine.eval::<FLOAT>("1.0 / 4.0")?, 0.25);
+
+        // Integer division is not affected
+        assert_eq!(engine.eval::<INT>("42 / 0")?, INT::MAX);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_math_parse() -> Result<(), Box<EvalAltResult>> {
     let engine = Engine::new();
@@ -116,6 +232,25 @@ fn test_math_parse() -> Result<(), Box<EvalAltResult>> {
     assert_eq!(engine.eval::<INT>(r#"parse_int("42")"#)?, 42);
     assert_eq!(engine.eval::<INT>(r#"parse_int("42", 16)"#)?, 0x42);
     assert_eq!(engine.eval::<INT>(r#"parse_int("abcdef", 16)"#)?, 0xabcdef);
+    assert_eq!(engine.eval::<INT>(r#"parse_int("  -101 ", 2)"#)?, -5);
+    assert_eq!(engine.eval::<INT>(r#"parse_int("zz", 36)"#)?, 35 * 36 + 35);
+
+    assert!(matches!(
+        *engine.eval::<INT>(r#"parse_int("12x")"#).expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(msg, _) if msg.starts_with("Error parsing integer number")
+    ));
+    assert!(matches!(
+        *engine.eval::<INT>(r#"parse_int("")"#).expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(msg, _) if msg.starts_with("Error parsing integer number")
+    ));
+    assert!(matches!(
+        *engine.eval::<INT>(r#"parse_int("42", 37)"#).expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(msg, _) if msg.starts_with("Invalid radix")
+    ));
+    assert!(matches!(
+        *engine.eval::<INT>(r#"parse_int("99999999999999999999999")"#).expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(msg, _) if msg.starts_with("Integer overflow")
+    ));
 
     Ok(())
 }
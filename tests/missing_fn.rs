@@ -0,0 +1,60 @@
+use rhai::{Dynamic, Engine, EvalAltResult, INT};
+
+#[test]
+fn test_missing_fn() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.on_missing_fn(|name, args| match name {
+        "remote_add" => Ok(Some(
+            args.iter().map(|a| a.as_int().unwrap()).sum::<INT>().into(),
+        )),
+        "bump" => {
+            *args[0] = Dynamic::from(args[0].as_int().unwrap() + 1);
+            Ok(Some(().into()))
+        }
+        "fail" => Err("remote failure".into()),
+        _ => Ok(None),
+    });
+
+    assert_eq!(engine.eval::<INT>("remote_add(1, 2, 39)")?, 42);
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(engine.eval::<INT>("let x = 40; x.remote_add(2)")?, 42);
+    assert_eq!(engine.eval::<INT>("let x = 41; bump(x); x")?, 42);
+
+    #[cfg(not(feature = "no_function"))]
+    assert_eq!(
+        engine.eval::<INT>("fn foo(x) { remote_add(x, 1) } foo(41)")?,
+        42
+    );
+
+    assert!(matches!(
+        *engine.eval::<INT>("fail()").expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg == "remote failure"
+    ));
+    assert!(matches!(
+        *engine.eval::<INT>("unknown(42)").expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(sig, _) if sig.starts_with("unknown (")
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_missing_fn_not_operators() {
+    let mut engine = Engine::new();
+
+    engine.on_missing_fn(|_, _| Ok(Some(Dynamic::from(42 as INT))));
+
+    assert!(matches!(
+        *engine.eval::<INT>("1 + true").expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(sig, _) if sig.starts_with("+ (")
+    ));
+
+    #[cfg(not(feature = "no_object"))]
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let x = 1; x.prop")
+            .expect_err("should error"),
+        EvalAltResult::ErrorDotExpr(_, _)
+    ));
+}
@@ -1,7 +1,7 @@
 #![cfg(not(feature = "no_module"))]
 use rhai::{
     module_resolvers::StaticModuleResolver, Dynamic, Engine, EvalAltResult, ImmutableString,
-    Module, ParseError, ParseErrorType, Scope, INT,
+    Module, ParseError, ParseErrorType, Scope, Shared, INT,
 };
 
 #[test]
@@ -299,6 +299,59 @@ fn test_module_from_ast() -> Result<(), Box<EvalAltResult>> {
     Ok(())
 }
 
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_module_into_fn_ptr() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let ast = engine.compile(
+        r#"
+            fn scale(x) { x * factor() }
+            fn factor() { 10 }
+            private fn hidden() { 0 }
+
+            // Top-level statements run once to initialize the module
+            let count = 0;
+            count += 1;
+            export count;
+        "#,
+    )?;
+
+    let module = ast.into_module(&engine)?;
+    assert_eq!(module.get_var_value::<INT>("count").unwrap(), 1);
+
+    let scale = module.get_fn("scale", 1).expect("scale should exist");
+    assert_eq!(scale.fn_name(), "scale");
+
+    for x in 1..=3 {
+        assert_eq!(
+            engine.call_fn_ptr::<_, INT>(&module, &scale, (x as INT,))?,
+            x * 10
+        );
+    }
+
+    assert!(module.get_fn("scale", 2).is_none());
+    assert!(module.get_fn("hidden", 0).is_none());
+    assert!(module.get_fn("missing", 0).is_none());
+
+    assert!(matches!(
+        *engine
+            .call_fn_ptr::<_, String>(&module, &scale, (1 as INT,))
+            .expect_err("should error"),
+        EvalAltResult::ErrorMismatchOutputType(_, _, _)
+    ));
+
+    // Errors in the top-level statements are returned
+    let ast = engine.compile(r#"fn foo() { 42 } throw "init failed";"#)?;
+
+    assert!(matches!(
+        *ast.into_module(&engine).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(err, _) if err.to_string() == "init failed"
+    ));
+
+    Ok(())
+}
+
 #[test]
 fn test_module_export() -> Result<(), Box<EvalAltResult>> {
     let engine = Engine::new();
@@ -317,6 +370,171 @@ fn test_module_export() -> Result<(), Box<EvalAltResult>> {
     Ok(())
 }
 
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_module_compile_scripts() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    let module = engine.compile_into_module(
+        &[
+            (
+                "math.rhai",
+                r"
+                const SCALE = 10;
+                let temp = 1;
+                fn scale(x) { x * 10 }
+                private fn helper(x) { x + 1 }
+                fn add_one(x) { helper(x) }
+                fn double(x) { twice(x) }
+                private fn twice(x) { x * 2 }
+            ",
+            ),
+            (
+                "text.rhai",
+                r#"
+                const GREETING = "Hello";
+                let count = 2;
+                export count;
+                fn greet(name) { "Hello, " + name }
+                fn scale(x, y) { x * y }
+            "#,
+            ),
+        ],
+        "lib",
+    )?;
+
+    assert_eq!(module.get_var_value::<INT>("SCALE"), Some(10));
+    assert_eq!(module.get_var_value::<INT>("count"), Some(2));
+    assert!(!module.contains_var("temp"));
+
+    let mut resolver = StaticModuleResolver::new();
+    resolver.insert("lib", Shared::try_unwrap(module).unwrap());
+    engine.set_module_resolver(Some(resolver));
+
+    assert_eq!(
+        engine.eval::<INT>(r#"import "lib" as lib; lib::scale(lib::SCALE) + lib::scale(2, 3)"#)?,
+        106
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"import "lib" as lib; lib::greet(lib::GREETING)"#)?,
+        "Hello, Hello"
+    );
+
+    // Public functions can call private functions
+    assert_eq!(
+        engine.eval::<INT>(r#"import "lib" as lib; lib::add_one(41)"#)?,
+        42
+    );
+    assert_eq!(
+        engine.eval::<INT>(r#"import "lib" as lib; lib::double(4)"#)?,
+        8
+    );
+
+    // Private functions are not exported
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"import "lib" as lib; lib::helper(1)"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_module_compile_scripts_errors() {
+    let mut engine = Engine::new();
+
+    // Functions defined in more than one script
+    let err = engine
+        .compile_into_module(
+            &[
+                ("a.rhai", "fn foo(x) { x }"),
+                ("b.rhai", "fn bar() { 0 }"),
+                ("c.rhai", "fn foo(y) { y }"),
+            ],
+            "lib",
+        )
+        .expect_err("should error");
+
+    assert!(matches!(
+        *err,
+        EvalAltResult::ErrorInModule(ref module, ref source, ref inner, _)
+            if module == "lib" && source == "c.rhai" && matches!(
+                **inner,
+                EvalAltResult::ErrorParsing(ParseErrorType::DuplicatedDefinition(ref f, ref first), _)
+                    if f == "foo(y)" && first == "a.rhai"
+            )
+    ));
+    assert_eq!(
+        err.to_string(),
+        "Error in module 'lib', script 'c.rhai' : Syntax error: 'foo(y)' is already defined in 'a.rhai' (line 1, position 1)"
+    );
+
+    // Private functions do not clash, and each script calls its own functions
+    let module = engine
+        .compile_into_module(
+            &[
+                ("a.rhai", "private fn foo() { 1 } fn a() { foo() }"),
+                ("c.rhai", "fn foo() { 3 }"),
+                ("b.rhai", "private fn foo() { 2 } fn b() { foo() }"),
+            ],
+            "lib",
+        )
+        .unwrap();
+
+    let mut resolver = StaticModuleResolver::new();
+    resolver.insert("lib", Shared::try_unwrap(module).unwrap());
+    engine.set_module_resolver(Some(resolver));
+
+    assert_eq!(
+        engine
+            .eval::<INT>(r#"import "lib" as lib; lib::a() * 100 + lib::b() * 10 + lib::foo()"#)
+            .unwrap(),
+        123
+    );
+
+    // Functions with different numbers of parameters do not clash
+    assert!(engine
+        .compile_into_module(
+            &[("a.rhai", "fn foo() { 1 }"), ("b.rhai", "fn foo(x) { x }")],
+            "lib"
+        )
+        .is_ok());
+
+    // Constants defined in more than one script
+    assert!(matches!(
+        *engine
+            .compile_into_module(&[("a.rhai", "const X = 1;"), ("b.rhai", "const X = 2;")], "lib")
+            .expect_err("should error"),
+        EvalAltResult::ErrorInModule(ref module, ref source, ref inner, _)
+            if module == "lib" && source == "b.rhai" && matches!(
+                **inner,
+                EvalAltResult::ErrorParsing(ParseErrorType::DuplicatedDefinition(ref name, ref first), _)
+                    if name == "X" && first == "a.rhai"
+            )
+    ));
+
+    // A script cannot see the functions of another script
+    assert!(matches!(
+        *engine
+            .compile_into_module(&[("a.rhai", "fn foo() { 1 }"), ("b.rhai", "foo();")], "lib")
+            .expect_err("should error"),
+        EvalAltResult::ErrorInModule(ref module, ref source, ref inner, _)
+            if module == "lib" && source == "b.rhai" && matches!(**inner, EvalAltResult::ErrorFunctionNotFound(_, _))
+    ));
+
+    // Syntax errors name the script
+    assert!(matches!(
+        *engine
+            .compile_into_module(&[("a.rhai", "let x = ;")], "lib")
+            .expect_err("should error"),
+        EvalAltResult::ErrorInModule(ref module, ref source, ref inner, _)
+            if module == "lib" && source == "a.rhai" && matches!(**inner, EvalAltResult::ErrorParsing(_, _))
+    ));
+}
+
 #[test]
 fn test_module_str() -> Result<(), Box<EvalAltResult>> {
     fn test_fn(_input: ImmutableString) -> Result<INT, Box<EvalAltResult>> {
@@ -354,3 +572,101 @@ fn test_module_str() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_module_lazy() -> Result<(), Box<EvalAltResult>> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let built = Arc::new(AtomicUsize::new(0));
+    let counter = built.clone();
+
+    let mut engine = Engine::new();
+
+    engine.register_static_module_lazy("hello", move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+
+        let mut module = Module::new();
+        module.set_var("answer", 41 as INT);
+        module.set_var("greeting", ImmutableString::from("hello"));
+        module.set_fn_1("inc", |x: INT| Ok(x + 1));
+        module
+    });
+
+    let ast = engine.compile(r#"import "hello" as h; h::inc(h::answer)"#)?;
+
+    // Compiling does not build the module
+    assert_eq!(built.load(Ordering::SeqCst), 0);
+
+    assert_eq!(engine.eval_ast::<INT>(&ast)?, 42);
+    assert_eq!(engine.eval_ast::<INT>(&ast)?, 42);
+    assert_eq!(
+        engine.eval::<INT>(r#"import "hello" as a; import "hello" as b; a::answer + b::inc(0)"#)?,
+        42
+    );
+    assert_eq!(built.load(Ordering::SeqCst), 1);
+
+    // The built module is shared, so changes made by one import are not seen by the next
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine.eval::<String>(r#"import "hello" as h; h::greeting.pad(8, '!'); h::greeting"#)?,
+        "hello!!!"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"import "hello" as h; h::greeting"#)?,
+        "hello"
+    );
+
+    // Lazy modules take precedence over the module resolver
+    let mut resolver = StaticModuleResolver::new();
+    resolver.insert("hello", Module::new());
+    engine.set_module_resolver(Some(resolver));
+
+    assert_eq!(engine.eval_ast::<INT>(&ast)?, 42);
+    assert_eq!(built.load(Ordering::SeqCst), 1);
+
+    Ok(())
+}
+
+#[cfg(feature = "sync")]
+#[test]
+fn test_module_lazy_threads() -> Result<(), Box<EvalAltResult>> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    let built = Arc::new(AtomicUsize::new(0));
+    let counter = built.clone();
+
+    let mut engine = Engine::new();
+
+    engine.register_static_module_lazy("slow", move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+        thread::sleep(std::time::Duration::from_millis(20));
+
+        let mut module = Module::new();
+        module.set_var("answer", 42 as INT);
+        module
+    });
+
+    let engine = Arc::new(engine);
+
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let engine = engine.clone();
+            thread::spawn(move || {
+                engine
+                    .eval::<INT>(r#"import "slow" as s; s::answer"#)
+                    .unwrap()
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), 42);
+    }
+
+    assert_eq!(built.load(Ordering::SeqCst), 1);
+
+    Ok(())
+}
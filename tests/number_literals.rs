@@ -1,4 +1,4 @@
-use rhai::{Engine, EvalAltResult, INT};
+use rhai::{Engine, EvalAltResult, LexError, ParseErrorType, INT};
 
 #[test]
 fn test_number_literal() -> Result<(), Box<EvalAltResult>> {
@@ -54,3 +54,41 @@ fn test_binary_literal() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_number_literal_separators() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("1_000_000")?, 1_000_000);
+    assert_eq!(engine.eval::<INT>("0xff_ff")?, 0xffff);
+    assert_eq!(engine.eval::<INT>("-0x10")?, -16);
+    assert_eq!(engine.eval::<INT>("-0b1_0")?, -2);
+
+    #[cfg(not(feature = "no_float"))]
+    assert_eq!(engine.eval::<rhai::FLOAT>("1_000.000_5")?, 1_000.000_5);
+
+    for script in &[
+        "1_", "0x_ff", "0xff_", "0b_1", "0o7_", "1_.5", "1._5", "0x1.5",
+    ] {
+        if script.contains('.') && cfg!(feature = "no_float") {
+            continue;
+        }
+
+        assert!(
+            matches!(
+                *engine.compile(script).expect_err("should error").0,
+                ParseErrorType::LexError(LexError::MalformedNumber(ref s)) if s.contains(script)
+            ),
+            "{} should not parse",
+            script
+        );
+    }
+
+    #[cfg(not(feature = "only_i32"))]
+    assert!(matches!(
+        *engine.compile("0x1_0000_0000_0000_0000").expect_err("should error").0,
+        ParseErrorType::LexError(LexError::MalformedNumber(ref s)) if s.contains("0x1_0000_0000_0000_0000")
+    ));
+
+    Ok(())
+}
@@ -1,5 +1,5 @@
 #![cfg(not(feature = "unchecked"))]
-use rhai::{Engine, EvalAltResult};
+use rhai::{Engine, EvalAltResult, INT};
 
 #[test]
 fn test_max_operations() -> Result<(), Box<EvalAltResult>> {
@@ -128,3 +128,37 @@ fn test_max_operations_progress() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_max_operations_remaining() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    // No limit
+    assert_eq!(engine.eval::<INT>("ops_remaining()")?, -1);
+
+    engine.set_max_operations(500);
+
+    let remaining = engine.eval::<INT>("ops_remaining()")?;
+    assert!(remaining > 490 && remaining < 500);
+
+    assert!(engine.eval::<bool>(
+        r"
+            let before = ops_remaining();
+            let x = 0;
+            while x < 10 { x += 1; }
+            ops_remaining() < before - 10
+        "
+    )?);
+
+    // Stop before running out of operations
+    let count = engine.eval::<INT>(
+        r"
+            let x = 0;
+            while ops_remaining() > 20 { x += 1; }
+            x
+        ",
+    )?;
+    assert!(count > 0);
+
+    Ok(())
+}
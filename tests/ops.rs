@@ -1,4 +1,4 @@
-use rhai::{Engine, EvalAltResult, INT};
+use rhai::{Engine, EvalAltResult, RegisterFn, INT};
 
 #[test]
 fn test_ops() -> Result<(), Box<EvalAltResult>> {
@@ -21,3 +21,57 @@ fn test_op_precedence() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_op_method_fallback() -> Result<(), Box<EvalAltResult>> {
+    #[derive(Debug, Clone, PartialEq)]
+    struct Vec2(INT, INT);
+
+    let mut engine = Engine::new();
+
+    engine.register_fn("vec2", |x: INT, y: INT| Vec2(x, y));
+    engine.register_fn("sum", |v: Vec2| v.0 + v.1);
+    engine.register_fn("add", |a: Vec2, b: Vec2| Vec2(a.0 + b.0, a.1 + b.1));
+    engine.register_fn("neg", |a: Vec2| Vec2(-a.0, -a.1));
+    engine.register_fn("add", |_: INT, _: INT| 0 as INT);
+
+    // Not enabled by default
+    assert!(!engine.operator_method_fallback());
+    assert!(matches!(
+        *engine.eval::<Vec2>("vec2(1, 2) + vec2(3, 4)").expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(sig, _) if sig.starts_with("+ (")
+    ));
+
+    engine.set_operator_method_fallback(true);
+
+    assert_eq!(engine.eval::<Vec2>("vec2(1, 2) + vec2(3, 4)")?, Vec2(4, 6));
+    assert_eq!(engine.eval::<Vec2>("-vec2(1, 2)")?, Vec2(-1, -2));
+    assert_eq!(
+        engine.eval::<INT>("let v = vec2(1, 2); v += vec2(10, 20); sum(v)")?,
+        33
+    );
+
+    // Existing operators are not shadowed
+    assert_eq!(engine.eval::<INT>("1 + 2")?, 3);
+
+    // Comparisons keep their defaults when there is no method
+    assert!(!engine.eval::<bool>("vec2(1, 2) == vec2(1, 2)")?);
+    assert!(engine.eval::<bool>("vec2(1, 2) != vec2(1, 2)")?);
+
+    engine.register_fn("eq", |a: Vec2, b: Vec2| a == b);
+    assert!(engine.eval::<bool>("vec2(1, 2) == vec2(1, 2)")?);
+
+    // The error is reported for the operator when there is no method either
+    assert!(matches!(
+        *engine.eval::<Vec2>("vec2(1, 2) / vec2(3, 4)").expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(sig, _) if sig.starts_with("/ (")
+    ));
+
+    #[cfg(not(feature = "no_function"))]
+    assert_eq!(
+        engine.eval::<INT>("fn mul(a, b) { sum(a) * sum(b) } vec2(1, 2) * vec2(3, 4)")?,
+        21
+    );
+
+    Ok(())
+}
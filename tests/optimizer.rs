@@ -1,6 +1,6 @@
 #![cfg(not(feature = "no_optimize"))]
 
-use rhai::{Engine, EvalAltResult, OptimizationLevel, INT};
+use rhai::{Engine, EvalAltResult, OptimizationLevel, RegisterFn, INT};
 
 #[test]
 fn test_optimizer_run() -> Result<(), Box<EvalAltResult>> {
@@ -48,3 +48,53 @@ fn test_optimizer_parse() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_optimizer_constant_folding() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    engine.set_optimization_level(OptimizationLevel::Simple);
+    engine.register_fn("buffer", |size: INT| size);
+
+    // A chain of constants folds into a single literal
+    let ast = engine.compile(
+        r"
+            const A = 4;
+            const B = A * 256;
+            const C = B + A;
+            C * 2 + A
+        ",
+    )?;
+    let debug = format!("{:?}", ast);
+    assert!(debug.contains("Expr(IntegerConstant((2060, "));
+    assert!(!debug.contains("FnCall"));
+    assert_eq!(engine.eval_ast::<INT>(&ast)?, 2060);
+
+    // Operators implemented by package functions are not folded
+    let ast = engine.compile(r#"const X = 2; "x" + X"#)?;
+    assert!(format!("{:?}", ast).contains(r#"FnCall((("+", "#));
+    assert_eq!(engine.eval_ast::<String>(&ast)?, "x2");
+
+    // Constants are propagated into function calls, which are not evaluated
+    let ast = engine.compile("const SIZE = 64; buffer(SIZE * 2)")?;
+    let debug = format!("{:?}", ast);
+    assert!(debug.contains(r#"FnCall((("buffer", "#));
+    assert!(debug.contains("[IntegerConstant((128, "));
+    assert!(!debug.contains("Variable"));
+
+    // Errors are left to run-time
+    let ast = engine.compile("const X = 0; 1 / X")?;
+    assert!(format!("{:?}", ast).contains(r#"FnCall((("/", "#));
+    #[cfg(not(feature = "unchecked"))]
+    assert!(matches!(
+        *engine.eval_ast::<INT>(&ast).expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(_, _)
+    ));
+
+    // Operators overridden by registered functions are not folded
+    engine.register_fn("+", |x: INT, y: INT| x * y);
+    let ast = engine.compile("const X = 6; X + 7")?;
+    assert!(format!("{:?}", ast).contains(r#"FnCall((("+", "#));
+    assert_eq!(engine.eval_ast::<INT>(&ast)?, 42);
+
+    Ok(())
+}
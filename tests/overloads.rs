@@ -0,0 +1,208 @@
+use rhai::{Engine, EvalAltResult, ImmutableString, RegisterFn, RegisterGuardedFn, INT};
+
+#[cfg(not(feature = "no_object"))]
+use rhai::Map;
+
+#[test]
+fn test_overloads_listed() {
+    #[derive(Debug, Clone)]
+    struct Point;
+
+    let mut engine = Engine::new();
+
+    engine
+        .register_fn("combine", |x: INT, y: INT| x + y)
+        .register_fn("combine", |x: INT, y: INT, z: INT| x + y + z)
+        .register_fn("combine", |s: ImmutableString, c: char| {
+            format!("{}{}", s, c)
+        })
+        .register_fn("new_point", || Point)
+        .register_fn("combine", |_: &mut Point, _: bool| ());
+
+    let err = engine.eval::<INT>("combine(1)").expect_err("should error");
+
+    assert!(matches!(
+        *err,
+        EvalAltResult::ErrorFunctionNotFound(ref sig, _) if sig.starts_with("combine (")
+    ));
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Function not found: 'combine ({0}) - available: combine ({0}, {0}); combine ({0}, {0}, {0}); combine (?, bool); combine (string, char)' (line 1, position 1)",
+            std::any::type_name::<INT>()
+        )
+    );
+
+    // Custom types are named when they are among the arguments
+    #[cfg(not(feature = "no_object"))]
+    let err = engine
+        .eval::<()>("let p = new_point(); p.combine(1)")
+        .expect_err("should error");
+
+    #[cfg(not(feature = "no_object"))]
+    assert!(err
+        .to_string()
+        .contains("combine (overloads::test_overloads_listed::Point, bool)"));
+
+    // Only the most relevant overloads are listed
+    engine
+        .register_fn("combine", |x: bool| x)
+        .register_fn("combine", |x: char| x)
+        .register_fn("combine", |x: INT, _: bool| x);
+
+    let err = engine.eval::<INT>("combine(1)").expect_err("should error");
+
+    assert!(err.to_string().ends_with(&format!(
+        "available: combine ({0}, bool); combine ({0}, {0}); combine ({0}, {0}, {0}); combine (bool); combine (char); and 2 more' (line 1, position 1)",
+        std::any::type_name::<INT>()
+    )));
+
+    // Overloads in packages taking internal types are not listed
+    assert!(!engine
+        .eval::<()>("to_string(1, 2)")
+        .expect_err("should error")
+        .to_string()
+        .contains('?'));
+
+    // Functions that do not exist at all are reported as before
+    assert!(matches!(
+        *engine.eval::<INT>("unknown(1)").expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(ref sig, _)
+            if sig == &format!("unknown ({})", std::any::type_name::<INT>())
+    ));
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_overloads_listed_script() {
+    let engine = Engine::new();
+
+    assert!(engine
+        .eval::<INT>("fn foo(x, y) { x + y } foo(1)")
+        .expect_err("should error")
+        .to_string()
+        .contains("- available: foo (x, y)"));
+}
+
+#[test]
+fn test_overloads_guarded() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine
+        .register_fn_guarded(
+            "classify",
+            |x: &INT| *x < 0,
+            |_: INT| "negative".to_string(),
+        )
+        .register_fn_guarded(
+            "classify",
+            |x: &INT| *x % 2 == 0,
+            |_: INT| "even".to_string(),
+        )
+        .register_fn_guarded("classify", |x: &INT| *x > 100, |_: INT| "large".to_string())
+        .register_fn_guarded(
+            "classify",
+            |x: &INT| *x % 2 != 0,
+            |_: INT| "odd".to_string(),
+        );
+
+    assert_eq!(engine.eval::<String>("classify(-4)")?, "negative");
+    assert_eq!(engine.eval::<String>("classify(42)")?, "even");
+    assert_eq!(engine.eval::<String>("classify(43)")?, "odd");
+
+    // The first matching registration wins
+    assert_eq!(engine.eval::<String>("classify(1000)")?, "even");
+    assert_eq!(engine.eval::<String>("classify(1001)")?, "large");
+
+    engine
+        .register_fn_guarded(
+            "greet",
+            |s: &&str| s.is_empty(),
+            |_: &str| "nobody".to_string(),
+        )
+        .register_fn_guarded("greet", |s: &String| s.len() < 10, |s: String| s);
+
+    assert_eq!(engine.eval::<String>(r#"greet("")"#)?, "nobody");
+    assert_eq!(engine.eval::<String>(r#"greet("world")"#)?, "world");
+
+    // No guard accepts the arguments
+    assert!(matches!(
+        *engine.eval::<String>(r#"greet("a long, long name")"#).expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(ref sig, _)
+            if sig == "greet (&str | ImmutableString | String)"
+    ));
+
+    // Fallible implementations
+    engine.register_fn_guarded(
+        "checked_div",
+        |_: &INT, _: &INT| true,
+        |x: INT, y: INT| -> Result<INT, Box<EvalAltResult>> {
+            if y == 0 {
+                Err("division by zero!".into())
+            } else {
+                Ok(x / y)
+            }
+        },
+    );
+
+    assert_eq!(engine.eval::<INT>("checked_div(84, 2)")?, 42);
+    assert!(engine.eval::<INT>("checked_div(1, 0)").is_err());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_overloads_guarded_tagged_union() -> Result<(), Box<EvalAltResult>> {
+    fn is_kind(value: &Map, kind: &str) -> bool {
+        value.get("kind").and_then(|v| v.as_str().ok()) == Some(kind)
+    }
+
+    let mut engine = Engine::new();
+
+    engine
+        .register_fn_guarded(
+            "process",
+            |m: &Map| is_kind(m, "deposit"),
+            |m: &mut Map| {
+                let amount = m["amount"].as_int().unwrap();
+                m.insert("done".into(), true.into());
+                amount
+            },
+        )
+        .register_fn_guarded(
+            "process",
+            |m: &Map| is_kind(m, "withdrawal"),
+            |m: Map| -m["amount"].as_int().unwrap(),
+        );
+
+    assert_eq!(
+        engine.eval::<INT>(r#"process(#{ kind: "deposit", amount: 42 })"#)?,
+        42
+    );
+    assert_eq!(
+        engine.eval::<INT>(r#"process(#{ kind: "withdrawal", amount: 42 })"#)?,
+        -42
+    );
+
+    // The first argument is updated in place by a method...
+    assert_eq!(
+        engine.eval::<bool>(r#"let x = #{ kind: "deposit", amount: 1 }; x.process(); x.done"#)?,
+        true
+    );
+    // ... but is not consumed by a pure function
+    assert_eq!(
+        engine
+            .eval::<INT>(r#"let x = #{ kind: "withdrawal", amount: 1 }; x.process() + x.amount"#)?,
+        0
+    );
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"process(#{ kind: "refund" })"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(ref sig, _) if sig == "process (map)"
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use rhai::{Engine, LexError, ParseErrorKind, ParseErrorType, Position};
+
+#[test]
+fn test_parse_errors_kind() {
+    let engine = Engine::new();
+
+    for (script, kind, pos) in &[
+        (
+            r#"let x = "hello"#,
+            ParseErrorKind::UnterminatedString,
+            (1, 9),
+        ),
+        ("/* abc", ParseErrorKind::UnterminatedComment, (1, 1)),
+        ("let x = 1 +* 2;", ParseErrorKind::UnexpectedToken, (1, 12)),
+        ("x $ 1", ParseErrorKind::UnexpectedToken, (1, 3)),
+        ("let x = 1_;", ParseErrorKind::MalformedNumber, (1, 9)),
+        (
+            r#"let x = "\x4g";"#,
+            ParseErrorKind::MalformedEscapeSequence,
+            (1, 13),
+        ),
+        ("let x = 'ab';", ParseErrorKind::MalformedChar, (1, 9)),
+        ("let x = 1 === 2;", ParseErrorKind::ImproperSymbol, (1, 11)),
+        ("let x = ", ParseErrorKind::UnexpectedEOF, (1, 9)),
+        ("let = 1;", ParseErrorKind::VariableExpected, (1, 5)),
+        ("let switch = 1;", ParseErrorKind::Reserved, (1, 5)),
+        ("break;", ParseErrorKind::LoopBreak, (1, 1)),
+    ] {
+        let err = engine.compile(script).expect_err("should error");
+
+        assert_eq!(err.error_kind(), *kind, "{}", script);
+        assert_eq!(err.position(), Position::new(pos.0, pos.1), "{}", script);
+        assert_eq!(err.error_type().kind(), *kind);
+    }
+}
+
+#[test]
+fn test_parse_errors_expected_token() {
+    let engine = Engine::new();
+
+    let err = engine.compile("foo(1, 2").expect_err("should error");
+    assert_eq!(err.error_kind(), ParseErrorKind::MissingToken);
+    assert_eq!(err.expected_token(), Some(")"));
+    assert_eq!(err.position(), Position::new(1, 9));
+
+    let err = engine.compile("let x = 1 +* 2;").expect_err("should error");
+    assert_eq!(err.expected_token(), None);
+
+    // Errors from tokenizing the script keep the details
+    assert_eq!(
+        *engine.compile("let x = 'ab';").expect_err("should error").0,
+        ParseErrorType::LexError(LexError::MalformedChar("ab".to_string()))
+    );
+
+    // The message is unchanged
+    assert_eq!(
+        engine
+            .compile("let x = 1 +* 2;")
+            .expect_err("should error")
+            .to_string(),
+        "Unexpected '*' (line 1, position 12)"
+    );
+}
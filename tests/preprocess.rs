@@ -0,0 +1,69 @@
+use rhai::{Engine, EvalAltResult, LexError, ParseErrorType, Position, SourceMap, INT};
+
+/// Strip all lines starting with '%', mapping the remaining lines back into the original script.
+fn strip_directives(text: &str) -> (String, Option<SourceMap>) {
+    let mut output = Vec::new();
+    let mut map = SourceMap::new();
+
+    for (line, text) in text.lines().enumerate() {
+        if !text.starts_with('%') {
+            output.push(text);
+            map.add(
+                Position::new(output.len() as u16, 1),
+                Position::new(line as u16 + 1, 1),
+            );
+        }
+    }
+
+    (output.join("\n"), Some(map))
+}
+
+#[test]
+fn test_preprocess() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.set_source_preprocessor(strip_directives);
+
+    assert_eq!(
+        engine.eval::<INT>("% directive\nlet x = 40;\n% another\nx + 2")?,
+        42
+    );
+    assert_eq!(engine.eval_expression::<INT>("% directive\n40 + 2")?, 42);
+
+    let script = "% directive\n% another\nlet x = 40;\nx +* 2";
+
+    let err = engine.compile(script).expect_err("should error");
+    assert_eq!(err.1, Position::new(4, 4));
+    assert_eq!(err.1.offset_in(script), Some(script.find('*').unwrap()));
+
+    let script = "% directive\nlet x = 40;\n% another\nx + undefined";
+
+    let err = engine.eval::<INT>(script).expect_err("should error");
+    assert!(
+        matches!(*err, EvalAltResult::ErrorVariableNotFound(ref name, _) if name == "undefined")
+    );
+    assert_eq!(err.position(), Position::new(4, 5));
+    assert_eq!(
+        err.position().offset_in(script),
+        Some(script.find("undefined").unwrap())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_preprocess_no_source_map() {
+    let mut engine = Engine::new();
+
+    // Without a source map, positions refer to the pre-processed script
+    engine.set_source_preprocessor(|text| (strip_directives(text).0, None));
+
+    let err = engine
+        .compile("% directive\n% another\nlet x = 40;\nx +* 2")
+        .expect_err("should error");
+    assert!(matches!(
+        *err.0,
+        ParseErrorType::LexError(LexError::UnexpectedInput(_))
+    ));
+    assert_eq!(err.1, Position::new(2, 4));
+}
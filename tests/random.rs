@@ -0,0 +1,99 @@
+#![cfg(feature = "rand")]
+use rhai::{Engine, EvalAltResult, INT};
+
+#[cfg(not(feature = "no_index"))]
+use rhai::Array;
+
+#[test]
+fn test_random_seed() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let script = r"
+        seed_rng(42);
+        let x = rand_int(1, 999);
+        let y = rand_int(1, 999);
+        x * 1000 + y
+    ";
+
+    // The sequence for a given seed is stable
+    assert_eq!(engine.eval::<INT>(script)?, 128182);
+    assert_eq!(engine.eval::<INT>(script)?, engine.eval::<INT>(script)?);
+
+    assert_ne!(
+        engine.eval::<INT>("seed_rng(1); rand_int(1, 1000000)")?,
+        engine.eval::<INT>("seed_rng(2); rand_int(1, 1000000)")?
+    );
+
+    // Re-seeding restarts the sequence
+    assert!(engine.eval::<bool>(
+        "seed_rng(7); let x = rand_int(0, 100); seed_rng(7); x == rand_int(0, 100)"
+    )?);
+
+    #[cfg(not(feature = "no_float"))]
+    assert!(engine.eval::<bool>(
+        "seed_rng(7); let x = rand(); seed_rng(7); x == rand() && x >= 0.0 && x < 1.0"
+    )?);
+
+    Ok(())
+}
+
+#[test]
+fn test_random_int() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert!(engine.eval::<bool>(
+        r"
+            let ok = true;
+            for i in range(0, 100) {
+                let x = rand_int(-3, 3);
+                if x < -3 || x > 3 { ok = false; }
+            }
+            ok
+        "
+    )?);
+    assert_eq!(engine.eval::<INT>("rand_int(5, 5)")?, 5);
+
+    // Full range of integers
+    engine.eval::<INT>(&format!("rand_int({}, {})", INT::MIN + 1, INT::MAX))?;
+
+    assert!(matches!(
+        *engine.eval::<INT>("rand_int(10, 1)").expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _)
+            if msg == "Invalid range for random integer: minimum 10 is larger than maximum 1"
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+fn test_random_shuffle() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let script = "seed_rng(123); let a = [1, 2, 3, 4, 5, 6, 7, 8]; shuffle(a); a";
+
+    let shuffled = engine.eval::<Array>(script)?;
+    let mut values: Vec<INT> = shuffled.iter().map(|v| v.as_int().unwrap()).collect();
+    assert_eq!(values, vec![7, 1, 8, 3, 2, 5, 6, 4]);
+    values.sort();
+    assert_eq!(values, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+
+    // The same seed shuffles in the same way
+    let again = engine.eval::<Array>(script)?;
+    assert_eq!(
+        again
+            .iter()
+            .map(|v| v.as_int().unwrap())
+            .collect::<Vec<_>>(),
+        shuffled
+            .iter()
+            .map(|v| v.as_int().unwrap())
+            .collect::<Vec<_>>()
+    );
+
+    assert!(engine
+        .eval::<Array>("let a = []; a.shuffle(); a")?
+        .is_empty());
+
+    Ok(())
+}
@@ -79,3 +79,42 @@ fn test_side_effects_print() -> Result<(), Box<EvalAltResult>> {
     assert_eq!(*result.read().unwrap(), "42");
     Ok(())
 }
+
+#[test]
+fn test_side_effects_run() -> Result<(), Box<EvalAltResult>> {
+    let result = Arc::new(RwLock::new(String::from("")));
+
+    let mut engine = Engine::new();
+
+    let logger = result.clone();
+    engine.on_print(move |s| logger.write().unwrap().push_str(s));
+
+    // The final value is not '()'
+    let ast = engine.compile(r#"print("hello"); 42"#)?;
+
+    assert!(matches!(
+        *engine.eval_ast::<()>(&ast).expect_err("should error"),
+        EvalAltResult::ErrorMismatchOutputType(_, _, _)
+    ));
+
+    result.write().unwrap().clear();
+    engine.run(&ast)?;
+    assert_eq!(*result.read().unwrap(), "hello");
+
+    let mut scope = Scope::new();
+    scope.push("x", 0 as INT);
+
+    engine.run_with_scope(&mut scope, &engine.compile("x = 42; let y = x; y")?)?;
+    assert_eq!(scope.get_value::<INT>("x").unwrap(), 42);
+    assert_eq!(scope.get_value::<INT>("y").unwrap(), 42);
+
+    // Errors are still reported
+    assert!(matches!(
+        *engine
+            .run(&engine.compile(r#"throw "oops"; 42"#)?)
+            .expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg == "oops"
+    ));
+
+    Ok(())
+}
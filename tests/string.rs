@@ -1,4 +1,7 @@
-use rhai::{Engine, EvalAltResult, ImmutableString, RegisterFn, INT};
+use rhai::{Engine, EvalAltResult, ImmutableString, LexError, ParseErrorType, RegisterFn, INT};
+
+#[cfg(not(feature = "no_index"))]
+use rhai::Array;
 
 #[test]
 fn test_string() -> Result<(), Box<EvalAltResult>> {
@@ -49,6 +52,47 @@ fn test_string() -> Result<(), Box<EvalAltResult>> {
     Ok(())
 }
 
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_string_slice() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>(r#"let s = "hello"; s[1..3]"#)?, "el");
+    assert_eq!(engine.eval::<String>(r#""hello"[2..99]"#)?, "llo");
+    assert_eq!(engine.eval::<String>(r#""hello"[3..1]"#)?, "");
+    assert_eq!(engine.eval::<char>(r#"let s = "hello"; s[1..3][1]"#)?, 'l');
+
+    // Slices are by character position, so multi-byte characters are never split
+    assert_eq!(
+        engine.eval::<String>(r#""\u2764x\u2764y"[1..3]"#)?,
+        "x\u{2764}"
+    );
+
+    assert!(matches!(
+        *engine
+            .eval::<String>(r#""hello"[-1..3]"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorStringBounds(5, -1, _)
+    ));
+
+    // Writing through a slice is an error, as the slice is a copy
+    assert!(matches!(
+        *engine
+            .eval::<()>(r#"let s = "hello"; s[1..3][0] = 'x';"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorAssignmentToUnknownLHS(_)
+    ));
+    #[cfg(not(feature = "no_object"))]
+    assert!(matches!(
+        *engine
+            .eval::<()>(r#"let s = "hello"; s[1..3].clear();"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorAssignmentToUnknownLHS(_)
+    ));
+
+    Ok(())
+}
+
 #[cfg(not(feature = "no_object"))]
 #[test]
 fn test_string_substring() -> Result<(), Box<EvalAltResult>> {
@@ -186,3 +230,155 @@ fn test_string_fn() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_string_raw() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>(r#"r"hello\nworld""#)?,
+        r"hello\nworld"
+    );
+    assert_eq!(engine.eval::<String>(r##"r#"say "hi""#"##)?, r#"say "hi""#);
+    assert_eq!(
+        engine.eval::<String>(r###"r##"a "# b"##"###)?,
+        r##"a "# b"##
+    );
+    assert_eq!(engine.eval::<String>("r\"line1\nline2\"")?, "line1\nline2");
+    assert_eq!(engine.eval::<String>(r#"r"C:\path" + "!""#)?, r"C:\path!");
+    assert_eq!(engine.eval::<INT>(r#"let r = 42; r"#)?, 42);
+
+    assert!(matches!(
+        *engine.compile(r##"r#"hello"##).expect_err("should error").0,
+        ParseErrorType::LexError(LexError::UnterminatedString)
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+fn test_string_interned() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let strings = |script: &str| -> Result<Vec<ImmutableString>, Box<EvalAltResult>> {
+        Ok(engine
+            .eval::<Array>(script)?
+            .into_iter()
+            .map(|v| v.cast::<ImmutableString>())
+            .collect())
+    };
+
+    // Identical literals share the same string
+    let s = strings(r#"let x = "hello"; ["hello", x, "world", "hel" + "lo"]"#)?;
+    assert!(s[0].ptr_eq(&s[1]));
+    assert!(!s[0].ptr_eq(&s[2]));
+    assert_eq!(s[0], s[3]);
+
+    // ... even across functions
+    #[cfg(not(feature = "no_function"))]
+    {
+        let s = strings(r#"fn foo() { "hello" } ["hello", foo(), call(|| "hello")]"#)?;
+        assert!(s[0].ptr_eq(&s[1]));
+        assert!(s[0].ptr_eq(&s[2]));
+    }
+
+    // Property names in object map literals share the same string
+    #[cfg(not(feature = "no_object"))]
+    {
+        let mut script = String::from("[");
+        for i in 0..1000 {
+            script.push_str(&format!(r#"#{{ name: "item", value: {} }},"#, i));
+        }
+        script.push(']');
+
+        let keys: Vec<ImmutableString> = engine
+            .eval::<Array>(&script)?
+            .into_iter()
+            .flat_map(|v| v.cast::<rhai::Map>().into_iter().map(|(k, _)| k))
+            .collect();
+
+        assert_eq!(keys.len(), 2000);
+        assert!(keys.iter().step_by(2).all(|k| k.ptr_eq(&keys[0])));
+        assert!(keys.iter().skip(1).step_by(2).all(|k| k.ptr_eq(&keys[1])));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_string_format() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>(r#"format("hello")"#)?, "hello");
+    assert_eq!(
+        engine.eval::<String>(r#"format("{} + {} = {}", 1, 2, 1 + 2)"#)?,
+        "1 + 2 = 3"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"format("{1}{0}{1} {}{}", "a", 'b', true)"#)?,
+        "bab ab"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"format("{{{}}} {{}} }}{{", "x")"#)?,
+        "{x} {} }{"
+    );
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine.eval::<String>(r#"let s = "{0}-{0}"; s.format(42)"#)?,
+        "42-42"
+    );
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(engine.eval::<String>(r#"format("{}", [1, 2])"#)?, "[1, 2]");
+
+    assert!(matches!(
+        *engine.eval::<String>(r#"format("{} {}", 1)"#).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _)
+            if msg == "Argument index 1 is out of range for format: there are only 1 argument(s)"
+    ));
+    assert!(matches!(
+        *engine.eval::<String>(r#"format("{5}", 1, 2)"#).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg.starts_with("Argument index 5 is out of range")
+    ));
+    assert!(matches!(
+        *engine.eval::<String>(r#"format("{", 1)"#).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg == "Unterminated placeholder in template for format"
+    ));
+    assert!(matches!(
+        *engine.eval::<String>(r#"format("}", 1)"#).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg == "Unmatched '}' in template for format"
+    ));
+    assert!(matches!(
+        *engine.eval::<String>("format(42, 1)").expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg.starts_with("The template for format must be a string")
+    ));
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert_eq!(
+            engine.eval::<String>(r#"format("{name} is {age}", #{ name: "Bob", age: 42 })"#)?,
+            "Bob is 42"
+        );
+        assert_eq!(
+            engine.eval::<String>(r#"format("{} {x}", 1, #{ x: 2 })"#)?,
+            "1 2"
+        );
+
+        assert!(matches!(
+            *engine
+                .eval::<String>(r#"format("{name} {missing}", #{ name: "Bob" })"#)
+                .expect_err("should error"),
+            EvalAltResult::ErrorRuntime(msg, _)
+                if msg == "Property 'missing' not found in the object map for format"
+        ));
+        assert!(matches!(
+            *engine
+                .eval::<String>(r#"format("{name}", 42)"#)
+                .expect_err("should error"),
+            EvalAltResult::ErrorRuntime(msg, _) if msg.contains("requires an object map")
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,165 @@
+use rhai::{Engine, EvalAltResult, ParseErrorType, Scope, INT};
+
+#[test]
+fn test_switch() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+    let mut scope = Scope::new();
+    scope.push("x", 42 as INT);
+
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "switch x { 1 => 123, 42 => 1, _ => 9 }")?,
+        1
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "switch x { 1 => 123, _ => 9 }")?,
+        9
+    );
+    assert_eq!(
+        engine.eval_with_scope::<()>(&mut scope, "switch x { 1 => 123, 2 => 'a' }")?,
+        ()
+    );
+    assert_eq!(
+        engine.eval_with_scope::<String>(
+            &mut scope,
+            r#"switch "hello" { "world" => 1, "hello" => "found", _ => 0 }"#
+        )?,
+        "found"
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, r#"switch x { "42" => 1, 40 + 2 => 2 }"#)?,
+        2
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(
+            &mut scope,
+            r"
+                let y = 0;
+                switch x {
+                    0 => { y = 1; }
+                    42 => { y = 2; }
+                    _ => { y = 3; }
+                }
+                y
+            "
+        )?,
+        2
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(
+            &mut scope,
+            "let y = switch x { 42 => { let z = 40; z + 2 } }; y"
+        )?,
+        42
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_switch_range() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        let ast = engine.compile(
+            r#"
+                fn classify(x) {
+                    switch x {
+                        0..10 => "small",
+                        5..100 => "medium",
+                        n if n >= 100 => "large",
+                        _ => "negative"
+                    }
+                }
+            "#,
+        )?;
+
+        assert_eq!(
+            engine.call_fn::<_, String>(&mut Scope::new(), &ast, "classify", (0 as INT,))?,
+            "small"
+        );
+        // Overlapping ranges - the first arm wins
+        assert_eq!(
+            engine.call_fn::<_, String>(&mut Scope::new(), &ast, "classify", (7 as INT,))?,
+            "small"
+        );
+        assert_eq!(
+            engine.call_fn::<_, String>(&mut Scope::new(), &ast, "classify", (10 as INT,))?,
+            "medium"
+        );
+        assert_eq!(
+            engine.call_fn::<_, String>(&mut Scope::new(), &ast, "classify", (1000 as INT,))?,
+            "large"
+        );
+        assert_eq!(
+            engine.call_fn::<_, String>(&mut Scope::new(), &ast, "classify", (-1 as INT,))?,
+            "negative"
+        );
+    }
+
+    assert_eq!(
+        engine.eval::<INT>("let lo = 40; switch 42 { lo..lo + 5 => 1, _ => 0 }")?,
+        1
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_switch_guard() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>("switch 42 { 42 if false => 1, 42 if true => 2, _ => 3 }")?,
+        2
+    );
+    assert_eq!(
+        engine.eval::<INT>("switch 42 { x if x < 10 => x, x if x > 10 => x + 1 }")?,
+        43
+    );
+    assert_eq!(
+        engine.eval::<INT>("let x = 1; switch 42 { x if x > 10 => x }; x")?,
+        1
+    );
+    assert_eq!(engine.eval::<INT>("const x = 1; switch 42 { x => x }")?, 42);
+
+    // A guard that is not boolean is an error
+    assert!(matches!(
+        *engine
+            .eval::<INT>("switch 42 { x if 123 => 1, _ => 2 }")
+            .expect_err("should error"),
+        EvalAltResult::ErrorLogicGuard(_)
+    ));
+
+    // A guard that errors aborts the switch instead of moving on to the next arm
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"switch 42 { x if no_such_fn(x) => 1, _ => 2 }"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+    assert!(matches!(
+        *engine.eval::<INT>(r#"switch 42 { x if { throw "oops"; } => 1, _ => 2 }"#).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg == "oops"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_switch_errors() {
+    let engine = Engine::new();
+
+    assert!(matches!(
+        *engine.compile("switch 42 { 1 => 2 3 => 4 }").expect_err("should error").0,
+        ParseErrorType::MissingToken(ref token, _) if token == ","
+    ));
+    assert!(matches!(
+        *engine.compile("switch 42 { 1 2 }").expect_err("should error").0,
+        ParseErrorType::MissingToken(ref token, _) if token == "=>"
+    ));
+    assert!(matches!(
+        *engine.compile("switch 42 { 1 => 2").expect_err("should error").0,
+        ParseErrorType::MissingToken(ref token, _) if token == "}"
+    ));
+}
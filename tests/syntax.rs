@@ -1,4 +1,4 @@
-use rhai::{Engine, EvalAltResult, EvalContext, Expression, ParseErrorType, Scope, INT};
+use rhai::{Engine, EvalAltResult, EvalContext, Expression, LexError, ParseErrorType, Scope, INT};
 
 #[test]
 fn test_custom_syntax() -> Result<(), Box<EvalAltResult>> {
@@ -66,7 +66,9 @@ fn test_custom_syntax() -> Result<(), Box<EvalAltResult>> {
             .register_custom_syntax(&["!"], 0, |_, _, _, _| Ok(().into()))
             .expect_err("should error")
             .0,
-        ParseErrorType::BadInput("Improper symbol for custom syntax: '!'".to_string())
+        ParseErrorType::LexError(LexError::ImproperSymbol(
+            "Improper symbol for custom syntax: '!'".to_string()
+        ))
     );
 
     Ok(())
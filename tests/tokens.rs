@@ -1,4 +1,4 @@
-use rhai::{Engine, EvalAltResult, ParseErrorType, RegisterFn, INT};
+use rhai::{Engine, EvalAltResult, LexError, ParseErrorType, RegisterFn, INT};
 
 #[test]
 fn test_tokens_disabled() {
@@ -18,7 +18,7 @@ fn test_tokens_disabled() {
 
     assert!(matches!(
         *engine.compile("let x = 40 + 2; x += 1;").expect_err("should error").0,
-        ParseErrorType::BadInput(ref s) if s == "Unexpected '+='"
+        ParseErrorType::LexError(LexError::UnexpectedInput(ref s)) if s == "+="
     ));
 }
 
@@ -52,6 +52,43 @@ fn test_tokens_custom_operator() -> Result<(), Box<EvalAltResult>> {
     Ok(())
 }
 
+#[test]
+fn test_tokens_operator_precedence() {
+    let mut engine = Engine::new();
+
+    assert_eq!(engine.operator_precedence("+"), Some(150));
+    assert_eq!(engine.operator_precedence("*"), Some(180));
+    assert_eq!(engine.operator_precedence("=="), Some(90));
+    assert_eq!(engine.operator_precedence("+="), Some(0));
+    assert_eq!(engine.operator_precedence("!"), None);
+    assert_eq!(engine.operator_precedence("("), None);
+    assert_eq!(engine.operator_precedence("foo"), None);
+
+    assert_eq!(engine.is_operator_bind_right("="), Some(true));
+    assert_eq!(engine.is_operator_bind_right("*="), Some(true));
+    assert_eq!(engine.is_operator_bind_right("+"), Some(false));
+    assert_eq!(engine.is_operator_bind_right("~"), Some(false));
+    assert_eq!(engine.is_operator_bind_right("foo"), None);
+
+    engine.register_custom_operator("foo", 160).unwrap();
+    engine.disable_symbol("&&");
+
+    assert_eq!(engine.operator_precedence("foo"), Some(160));
+    assert_eq!(engine.is_operator_bind_right("foo"), Some(false));
+    assert_eq!(engine.operator_precedence("&&"), None);
+
+    let operators: Vec<_> = engine.iter_operators().collect();
+
+    assert!(operators.contains(&("foo", 160, false)));
+    assert!(operators.contains(&("=", 0, true)));
+    assert!(operators.contains(&("-", 150, false)));
+    assert!(!operators.iter().any(|&(op, _, _)| op == "&&"));
+    assert!(operators.iter().all(
+        |&(op, precedence, bind_right)| engine.operator_precedence(op) == Some(precedence)
+            && engine.is_operator_bind_right(op) == Some(bind_right)
+    ));
+}
+
 #[test]
 fn test_tokens_unicode_xid_ident() -> Result<(), Box<EvalAltResult>> {
     let engine = Engine::new();
@@ -77,3 +114,105 @@ fn test_tokens_unicode_xid_ident() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_tokens_position_offset() {
+    let engine = Engine::new();
+
+    // Tabs and multi-byte characters count as one character position each
+    let script = "let s = \"日本\";\n\t\tlet x = s +* 1;";
+
+    let err = engine.compile(script).expect_err("should error");
+    let pos = err.1;
+
+    assert_eq!(pos.line(), Some(2));
+    assert_eq!(pos.position(), Some(14));
+    assert_eq!(pos.offset_in(script), script.find('*'));
+
+    // Positions raised at run-time map to byte offsets as well
+    let script = "let s = \"日本\";\n\tlet x = unknown(s);";
+
+    let err = engine.eval::<()>(script).expect_err("should error");
+    let pos = err.position();
+
+    assert_eq!(pos.line(), Some(2));
+    assert_eq!(pos.position(), Some(10));
+    assert_eq!(pos.offset_in(script), script.find("unknown"));
+
+    // Byte offsets are not kept in positions
+    assert_eq!(pos, rhai::Position::new(2, 10));
+    assert_eq!(std::mem::size_of::<rhai::Position>(), 4);
+
+    // Start and end of a line, and positions not in the script
+    assert_eq!(
+        rhai::Position::new(2, 0).offset_in(script),
+        script.find('\t')
+    );
+    assert_eq!(
+        rhai::Position::new(2, 21).offset_in(script),
+        Some(script.len())
+    );
+    assert_eq!(rhai::Position::new(2, 22).offset_in(script), None);
+    assert_eq!(rhai::Position::new(3, 1).offset_in(script), None);
+    assert_eq!(rhai::Position::none().offset_in(script), None);
+}
+
+#[test]
+fn test_tokens_reserved_keywords() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    for (script, keyword) in &[
+        ("let switch = 1;", "switch"),
+        ("const var = 1;", "var"),
+        ("for in in [] {}", "in"),
+        ("try {} catch (yield) {}", "yield"),
+        ("let print = 1;", "print"),
+    ] {
+        assert!(matches!(
+            *engine.compile(script).expect_err("should error").0,
+            ParseErrorType::Reserved(ref s) if s == keyword
+        ));
+    }
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        assert!(matches!(
+            *engine.compile("fn foo(x, while) {}").expect_err("should error").0,
+            ParseErrorType::Reserved(ref s) if s == "while"
+        ));
+        assert!(matches!(
+            *engine.compile("fn match() {}").expect_err("should error").0,
+            ParseErrorType::Reserved(ref s) if s == "match"
+        ));
+        assert!(matches!(
+            *engine.compile("|x, let| x").expect_err("should error").0,
+            ParseErrorType::Reserved(ref s) if s == "let"
+        ));
+    }
+
+    assert!(engine.is_reserved_keyword("switch"));
+    assert!(engine.is_reserved_keyword("var"));
+    assert!(engine.is_reserved_keyword("print"));
+    assert!(!engine.is_reserved_keyword("foo"));
+    assert!(!engine.is_reserved_keyword("+"));
+
+    engine.register_custom_operator("foo", 160).unwrap();
+    assert!(engine.is_reserved_keyword("foo"));
+
+    // Words reserved for future use can be allowed as identifiers
+    engine.set_allow_reserved_identifiers(true);
+
+    assert!(!engine.is_reserved_keyword("var"));
+    assert!(engine.is_reserved_keyword("switch"));
+    assert_eq!(engine.eval::<INT>("let var = 40; var + 2")?, 42);
+
+    #[cfg(not(feature = "no_function"))]
+    assert_eq!(engine.eval::<INT>("fn match(x) { x } match(42)")?, 42);
+
+    assert!(matches!(
+        *engine.compile("let switch = 1;").expect_err("should error").0,
+        ParseErrorType::Reserved(ref s) if s == "switch"
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use rhai::{Engine, EvalAltResult, ParseErrorType, INT};
+
+#[test]
+fn test_try_catch() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>("let x = 1; try { x = 2; throw; x = 3; } catch { x += 40; } x")?,
+        42
+    );
+    assert_eq!(engine.eval::<INT>("try { 42 } catch { 0 }")?, 42);
+    assert_eq!(
+        engine.eval::<String>(r#"try { throw "hello"; } catch (e) { message(e) }"#)?,
+        "hello"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"try { throw "hello"; } catch (e) { type_of(e) }"#)?,
+        "runtime"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"try { x + 1 } catch (e) { to_string(e) }"#)?,
+        "Variable not found: 'x' (line 1, position 7)"
+    );
+
+    // Variables inside the try block are gone inside the catch block
+    assert!(matches!(
+        *engine
+            .eval::<INT>("try { let x = 42; throw; } catch { x }")
+            .expect_err("should error"),
+        EvalAltResult::ErrorVariableNotFound(_, _)
+    ));
+
+    // Errors inside the catch block are not caught
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"try { throw "a"; } catch { throw "b"; }"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorRuntime(s, _) if s == "b"
+    ));
+
+    // Re-throwing keeps the caught error
+    assert_eq!(
+        engine.eval::<String>(
+            r#"
+                try {
+                    try { throw "inner"; } catch (e) { throw e; }
+                } catch (e) {
+                    type_of(e) + ": " + message(e)
+                }
+            "#
+        )?,
+        "runtime: inner"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_try_catch_error_type() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        let script = r#"
+            fn classify(f) {
+                try {
+                    call(f);
+                    "ok"
+                } catch (e) {
+                    switch type_of(e) {
+                        "runtime" => "runtime: " + message(e),
+                        "arithmetic" => "arithmetic",
+                        "function" => "function",
+                        _ => "other"
+                    }
+                }
+            }
+        "#;
+
+        let ast = engine.compile(script)?;
+
+        let run = |f: &str| engine.eval_ast::<String>(&ast.merge(&engine.compile(f).unwrap()));
+
+        assert_eq!(run(r#"classify(|| 42)"#)?, "ok");
+        assert_eq!(run(r#"classify(|| { throw "oops"; })"#)?, "runtime: oops");
+        assert_eq!(run(r#"classify(|| no_such_fn())"#)?, "function");
+
+        #[cfg(not(feature = "unchecked"))]
+        assert_eq!(run(r#"classify(|| 1 / 0)"#)?, "arithmetic");
+    }
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        engine.eval::<String>("try { [1, 2][5] } catch (e) { type_of(e) }")?,
+        "bounds"
+    );
+
+    assert_eq!(
+        engine.eval::<INT>("try { throw; } catch (e) { line(e) * 100 + position(e) }")?,
+        107
+    );
+
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine.eval::<String>(
+            r#"try { throw "oops"; } catch (e) { e.message + " at line " + e.line }"#
+        )?,
+        "oops at line 1"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_try_catch_thrown_value() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("try { throw 42; } catch (e) { e }")?, 42);
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert_eq!(
+            engine.eval::<INT>("try { throw #{ code: 42 }; } catch (e) { e.code }")?,
+            42
+        );
+        assert_eq!(
+            engine.eval::<String>("try { throw #{ code: 42 }; } catch (e) { type_of(e) }")?,
+            "map"
+        );
+
+        #[cfg(not(feature = "no_function"))]
+        assert_eq!(
+            engine.eval::<String>(
+                r#"
+                    fn fail(msg) { throw #{ message: msg, code: 1 }; }
+                    try { fail("oops"); } catch (e) { e.message + e.code }
+                "#
+            )?,
+            "oops1"
+        );
+    }
+
+    // A thrown value that is not a string is kept in the error
+    assert!(matches!(
+        *engine.eval::<()>("throw 42").expect_err("should error"),
+        EvalAltResult::ErrorThrown(value, _) if value.as_int().unwrap() == 42
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "unchecked"))]
+fn test_try_catch_not_catchable() {
+    let mut engine = Engine::new();
+    engine.set_max_operations(100);
+
+    assert!(matches!(
+        *engine
+            .eval::<()>("try { loop {} } catch { }")
+            .expect_err("should error"),
+        EvalAltResult::ErrorTooManyOperations(_)
+    ));
+}
+
+#[test]
+fn test_try_catch_errors() {
+    let engine = Engine::new();
+
+    assert!(matches!(
+        *engine.compile("try { 42 }").expect_err("should error").0,
+        ParseErrorType::MissingToken(ref token, _) if token == "catch"
+    ));
+    assert!(matches!(
+        *engine
+            .compile("try { 42 } catch (42) { 0 }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::VariableExpected
+    ));
+    assert!(matches!(
+        *engine
+            .compile("try { 42 } catch (e { 0 }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::MissingToken(ref token, _) if token == ")"
+    ));
+}
@@ -0,0 +1,147 @@
+#![cfg(not(feature = "no_object"))]
+use rhai::{Engine, EvalAltResult, INT};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct TestStruct {
+    x: INT,
+    items: Vec<INT>,
+}
+
+impl TestStruct {
+    fn new() -> Self {
+        TestStruct {
+            x: 1,
+            items: vec![10, 20, 30],
+        }
+    }
+    fn update(&mut self, offset: INT) {
+        self.x += offset;
+    }
+    fn len(&mut self) -> INT {
+        self.items.len() as INT
+    }
+    fn get_x(&mut self) -> INT {
+        self.x
+    }
+    fn set_x(&mut self, value: INT) {
+        self.x = value;
+    }
+    fn get_item(&mut self, index: INT) -> INT {
+        self.items[index as usize]
+    }
+    fn set_item(&mut self, index: INT, value: INT) {
+        self.items[index as usize] = value;
+    }
+}
+
+#[test]
+fn test_type_builder() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    let mut builder = engine.register_type_with_name::<TestStruct>("TestStruct");
+
+    builder
+        .with_fn("new_ts", TestStruct::new)
+        .with_fn("update", TestStruct::update)
+        .with_fn("len", TestStruct::len)
+        .with_get_set("x", TestStruct::get_x, TestStruct::set_x)
+        .with_get("double_x", |t: &mut TestStruct| t.x * 2);
+
+    #[cfg(not(feature = "no_index"))]
+    builder.with_indexer_get_set(TestStruct::get_item, TestStruct::set_item);
+
+    assert_eq!(
+        engine.eval::<INT>("let t = new_ts(); t.update(41); t.x")?,
+        42
+    );
+    assert_eq!(
+        engine.eval::<INT>("let t = new_ts(); t.x = 21; t.double_x")?,
+        42
+    );
+    assert_eq!(engine.eval::<INT>("let t = new_ts(); t.len()")?, 3);
+    assert_eq!(
+        engine.eval::<String>("let t = new_ts(); type_of(t)")?,
+        "TestStruct"
+    );
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(engine.eval::<INT>("let t = new_ts(); t[1] = 42; t[1]")?, 42);
+
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "Property name for a getter or setter cannot be empty.")]
+fn test_type_builder_empty_property() {
+    let mut engine = Engine::new();
+
+    engine
+        .register_type::<TestStruct>()
+        .with_get("", TestStruct::get_x);
+}
+
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+struct Version(INT, INT);
+
+#[test]
+fn test_type_builder_comparison() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine
+        .register_type_with_name::<Version>("Version")
+        .with_comparison()
+        .with_fn("version", |major: INT, minor: INT| Version(major, minor))
+        .with_get("minor", |v: &mut Version| v.1);
+
+    engine
+        .register_type_with_name::<TestStruct>("TestStruct")
+        .with_comparator(|a: &TestStruct, b: &TestStruct| a.x.cmp(&b.x))
+        .with_fn("new_ts", |x: INT| TestStruct {
+            x,
+            items: Vec::new(),
+        })
+        .with_get("x", TestStruct::get_x);
+
+    assert!(engine.eval::<bool>("version(1, 2) < version(1, 10)")?);
+    assert!(engine.eval::<bool>("new_ts(3) >= new_ts(3)")?);
+    assert_eq!(
+        engine.eval::<INT>("max(version(1, 2), version(1, 10)).minor")?,
+        10
+    );
+    assert_eq!(engine.eval::<INT>("min(new_ts(5), new_ts(3)).x")?, 3);
+    assert_eq!(engine.eval::<INT>("max(1, 42)")?, 42);
+    assert_eq!(
+        engine.eval::<INT>(
+            "switch version(1, 2) { version(1, 0) => 0, version(1, 2) => 2, _ => 9 }"
+        )?,
+        2
+    );
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        engine.eval::<INT>(
+            "let a = [new_ts(3), new_ts(1), new_ts(2)]; a.sort(); a[0].x * 100 + a[1].x * 10 + a[2].x"
+        )?,
+        123
+    );
+
+    engine
+        .register_type_with_name::<Vec<INT>>("List")
+        .with_fn("list", || vec![1 as INT]);
+
+    assert!(matches!(
+        *engine.eval::<()>("max(list(), list())").expect_err("should error"),
+        EvalAltResult::ErrorRuntime(ref s, _) if s == "Type List is not comparable"
+    ));
+    assert!(matches!(
+        *engine.eval::<()>(r#"min(list(), "x")"#).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(ref s, _) if s == "Type List is not comparable with string"
+    ));
+    assert!(matches!(
+        *engine.eval::<INT>("switch list() { list() => 1, _ => 0 }").expect_err("should error"),
+        EvalAltResult::ErrorRuntime(ref s, _) if s == "Type List is not comparable"
+    ));
+    assert_eq!(engine.eval::<INT>("switch list() { 42 => 1, _ => 0 }")?, 0);
+
+    Ok(())
+}
@@ -1,4 +1,5 @@
-use rhai::{Engine, EvalAltResult, RegisterFn, INT};
+use rhai::{Dynamic, Engine, EvalAltResult, RegisterFn, INT};
+use std::any::type_name;
 
 #[test]
 fn test_type_of() -> Result<(), Box<EvalAltResult>> {
@@ -48,3 +49,103 @@ fn test_type_of() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_type_of_all_values() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let type_of = |expr: &str| engine.eval::<String>(&format!("type_of({})", expr));
+
+    assert_eq!(type_of("()")?, "()");
+    assert_eq!(type_of("true")?, "bool");
+    assert_eq!(type_of("42")?, type_name::<INT>());
+    assert_eq!(type_of("'x'")?, "char");
+    assert_eq!(type_of(r#""hello""#)?, "string");
+    assert_eq!(type_of(r#"Fn("foo")"#)?, "Fn");
+    assert_eq!(type_of("range(0, 10)")?, "range");
+    assert_eq!(type_of("range(0, 10, 2)")?, "range");
+
+    #[cfg(not(feature = "no_float"))]
+    assert_eq!(type_of("4.2")?, "f64");
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(type_of("[1, 2, 3]")?, "array");
+
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(type_of("#{a: 1}")?, "map");
+
+    #[cfg(not(feature = "no_function"))]
+    assert_eq!(type_of("|x| x + 1")?, "Fn");
+
+    #[cfg(not(feature = "no_std"))]
+    assert_eq!(type_of("timestamp()")?, "timestamp");
+
+    // Caught errors are named after their category
+    let type_of_error = |script: &str| {
+        engine.eval::<String>(&format!("try {{ {} }} catch (e) {{ type_of(e) }}", script))
+    };
+
+    assert_eq!(type_of_error("throw;")?, "runtime");
+    assert_eq!(type_of_error("x")?, "variable");
+    assert_eq!(type_of_error("no_such_fn()")?, "function");
+    assert_eq!(type_of_error("let x = 42; if x { 1 } else { 2 }")?, "type");
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(type_of_error("[1, 2][5]")?, "bounds");
+
+    #[cfg(not(feature = "unchecked"))]
+    assert_eq!(type_of_error("42 / 0")?, "arithmetic");
+
+    // A thrown value keeps its own type
+    assert_eq!(type_of_error("throw 42;")?, type_name::<INT>());
+
+    Ok(())
+}
+
+#[test]
+fn test_type_predicates() -> Result<(), Box<EvalAltResult>> {
+    #[derive(Clone)]
+    struct TestStruct {
+        x: INT,
+    }
+
+    let mut engine = Engine::new();
+
+    assert!(engine.eval::<Dynamic>("()")?.is_unit());
+    assert!(engine.eval::<Dynamic>("true")?.is_bool());
+    assert!(engine.eval::<Dynamic>("42")?.is_int());
+    assert!(engine.eval::<Dynamic>("'x'")?.is_char());
+    assert!(engine.eval::<Dynamic>(r#""hello""#)?.is_string());
+    assert!(!engine.eval::<Dynamic>("42")?.is_string());
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        assert!(engine.eval::<Dynamic>("42.0")?.is_float());
+        assert!(!engine.eval::<Dynamic>("42")?.is_float());
+    }
+
+    #[cfg(not(feature = "no_index"))]
+    assert!(engine.eval::<Dynamic>("[1, 2, 3]")?.is_array());
+
+    #[cfg(not(feature = "no_object"))]
+    assert!(engine.eval::<Dynamic>("#{a: 1}")?.is_map());
+
+    // Shared values are checked on their inner value
+    #[cfg(not(feature = "no_closure"))]
+    assert!(Dynamic::from(42 as INT).into_shared().is_int());
+
+    let value = Dynamic::from(TestStruct { x: 42 });
+    assert_eq!(value.clone().cast::<TestStruct>().x, 42);
+
+    assert_eq!(engine.type_name_of(&Dynamic::from("hello")), "string");
+    assert_eq!(engine.type_name_of(&value), type_name::<TestStruct>());
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        engine.register_type_with_name::<TestStruct>("Hello");
+        assert_eq!(engine.type_name_of(&value), "Hello");
+        assert_eq!(value.type_name(), type_name::<TestStruct>());
+    }
+
+    Ok(())
+}
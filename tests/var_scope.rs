@@ -1,4 +1,4 @@
-use rhai::{Engine, EvalAltResult, Scope, INT};
+use rhai::{Engine, EvalAltResult, ReusableState, Scope, INT};
 
 #[test]
 fn test_var_scope() -> Result<(), Box<EvalAltResult>> {
@@ -52,3 +52,141 @@ fn test_scope_eval() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_scope_iter() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+    let mut scope = Scope::new();
+
+    scope.push_constant("x", 1 as INT);
+    engine.eval_with_scope::<()>(&mut scope, "let y = 2; let x = 3;")?;
+
+    let entries: Vec<_> = scope
+        .iter()
+        .map(|(name, constant, value)| (name, constant, value.clone().cast::<INT>()))
+        .collect();
+
+    assert_eq!(
+        entries,
+        vec![("x", true, 1), ("y", false, 2), ("x", false, 3)]
+    );
+    assert_eq!(scope.get_value::<INT>("x"), Some(3));
+
+    Ok(())
+}
+
+#[test]
+fn test_var_undefined() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    assert!(engine.fail_on_undefined_var());
+    assert!(matches!(
+        *engine.eval::<()>("x").expect_err("should error"),
+        EvalAltResult::ErrorVariableNotFound(name, _) if name == "x"
+    ));
+
+    engine.set_fail_on_undefined_var(false);
+
+    engine.eval::<()>("x")?;
+    assert_eq!(engine.eval::<String>("type_of(x)")?, "()");
+    assert_eq!(engine.eval::<String>(r#""hello" + x"#)?, "hello");
+    assert!(engine.eval::<bool>("x == ()")?);
+
+    // Variables that are defined are not affected
+    let mut scope = Scope::new();
+    scope.push("x", 42 as INT);
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "x")?, 42);
+
+    #[cfg(not(feature = "no_object"))]
+    assert!(matches!(
+        *engine.eval::<INT>("x.len").expect_err("should error"),
+        EvalAltResult::ErrorDotExpr(_, _)
+    ));
+
+    // Assignment to an undefined variable is controlled separately
+    assert!(engine.fail_on_undefined_var_assign());
+    assert!(matches!(
+        *engine.eval::<()>("x = 1").expect_err("should error"),
+        EvalAltResult::ErrorVariableNotFound(name, _) if name == "x"
+    ));
+    assert!(matches!(
+        *engine.eval::<()>("x += 1").expect_err("should error"),
+        EvalAltResult::ErrorVariableNotFound(name, _) if name == "x"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_var_undefined_assign() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.set_fail_on_undefined_var_assign(false);
+
+    // Assigning defines the variable
+    assert_eq!(engine.eval::<INT>("x = 40; x + 2")?, 42);
+    assert_eq!(engine.eval::<INT>("let y = 1; x = 40; y + x + 1")?, 42);
+
+    // ... in the current block only
+    assert!(matches!(
+        *engine.eval::<INT>("{ x = 41; x += 1; } x").expect_err("should error"),
+        EvalAltResult::ErrorVariableNotFound(name, _) if name == "x"
+    ));
+
+    // Compound assignment is still an error
+    assert!(matches!(
+        *engine.eval::<()>("x += 1").expect_err("should error"),
+        EvalAltResult::ErrorVariableNotFound(name, _) if name == "x"
+    ));
+
+    // Reading an undefined variable is still an error
+    assert!(engine.fail_on_undefined_var());
+    assert!(matches!(
+        *engine.eval::<()>("z").expect_err("should error"),
+        EvalAltResult::ErrorVariableNotFound(name, _) if name == "z"
+    ));
+
+    // Both switches together
+    engine.set_fail_on_undefined_var(false);
+    assert_eq!(engine.eval::<String>("type_of(z)")?, "()");
+    assert_eq!(engine.eval::<INT>("z = 42; z")?, 42);
+
+    Ok(())
+}
+
+#[test]
+fn test_scope_eval_reuse() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+    let ast = engine.compile("let total = x * 10; if x > 2 { total += y } total")?;
+
+    let mut state = ReusableState::new();
+
+    for x in 0..5 as INT {
+        let mut scope = Scope::new();
+        scope.push("x", x);
+        scope.push("y", x + 1);
+
+        let expected = engine.eval_ast_with_scope::<INT>(&mut scope, &ast)?;
+        let result = engine.eval_reuse::<INT>(
+            &ast,
+            &mut state,
+            vec![("x", x.into()), ("y", (x + 1).into())],
+        )?;
+
+        assert_eq!(result, expected);
+        assert_eq!(state.scope().len(), scope.len());
+        assert_eq!(state.scope().get_value::<INT>("total"), Some(expected));
+    }
+
+    // Variables of the previous run are cleared
+    let ast = engine.compile("total")?;
+    assert!(matches!(
+        *engine
+            .eval_reuse::<INT>(&ast, &mut state, vec![])
+            .expect_err("should error"),
+        EvalAltResult::ErrorVariableNotFound(ref name, _) if name == "total"
+    ));
+    assert!(state.scope().is_empty());
+
+    Ok(())
+}
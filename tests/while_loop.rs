@@ -1,4 +1,4 @@
-use rhai::{Engine, EvalAltResult, INT};
+use rhai::{Engine, EvalAltResult, ParseErrorType, INT};
 
 #[test]
 fn test_while() -> Result<(), Box<EvalAltResult>> {
@@ -24,3 +24,85 @@ fn test_while() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_while_let() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    // The expression is re-evaluated on every iteration until it returns ()
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                let x = 5;
+                let sum = 0;
+
+                while let v = if x > 0 { x -= 1; x } {
+                    sum += v;
+                }
+
+                sum
+            "
+        )?,
+        10
+    );
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                let x = 0;
+                let sum = 0;
+
+                while let v = if x < 10 { x += 1; x } {
+                    if v > 6 { break; }
+                    if v % 2 == 0 { continue; }
+                    sum += v;
+                }
+
+                sum * 100 + x
+            "
+        )?,
+        907
+    );
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                let queue = [1, 2, 3, 4];
+                let result = 0;
+
+                while let item = pop(queue) {
+                    result = result * 10 + item;
+                }
+
+                result
+            "
+        )?,
+        4321
+    );
+
+    // The loop variable is only visible inside the body
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let x = 1; while let v = if x > 0 { x -= 1; 42 } { } v")
+            .expect_err("should error"),
+        EvalAltResult::ErrorVariableNotFound(name, _) if name == "v"
+    ));
+
+    assert!(matches!(
+        *engine
+            .compile("while let v 42 { }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::MissingToken(ref token, _) if token == "="
+    ));
+    assert!(matches!(
+        *engine
+            .compile("while let 42 = 1 { }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::VariableExpected
+    ));
+
+    Ok(())
+}